@@ -31,6 +31,8 @@ pub enum FeatureFlag {
     CryptographyAlgebraNatives,
     Bls12381Structures,
     Ed25519PubkeyValidateReturnFalseWrongLength,
+    Secp256k1EcdsaAuthenticator,
+    WebAuthnAuthenticator,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -138,6 +140,10 @@ impl From<FeatureFlag> for AptosFeatureFlag {
             FeatureFlag::Ed25519PubkeyValidateReturnFalseWrongLength => {
                 AptosFeatureFlag::ED25519_PUBKEY_VALIDATE_RETURN_FALSE_WRONG_LENGTH
             },
+            FeatureFlag::Secp256k1EcdsaAuthenticator => {
+                AptosFeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR
+            },
+            FeatureFlag::WebAuthnAuthenticator => AptosFeatureFlag::WEBAUTHN_AUTHENTICATOR,
         }
     }
 }
@@ -170,6 +176,10 @@ impl From<AptosFeatureFlag> for FeatureFlag {
             AptosFeatureFlag::ED25519_PUBKEY_VALIDATE_RETURN_FALSE_WRONG_LENGTH => {
                 FeatureFlag::Ed25519PubkeyValidateReturnFalseWrongLength
             },
+            AptosFeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR => {
+                FeatureFlag::Secp256k1EcdsaAuthenticator
+            },
+            AptosFeatureFlag::WEBAUTHN_AUTHENTICATOR => FeatureFlag::WebAuthnAuthenticator,
         }
     }
 }
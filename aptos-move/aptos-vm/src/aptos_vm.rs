@@ -33,10 +33,10 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     on_chain_config::{new_epoch_event_key, FeatureFlag, TimedFeatureOverride},
     transaction::{
-        ChangeSet, EntryFunction, ExecutionError, ExecutionStatus, ModuleBundle, Multisig,
-        MultisigTransactionPayload, SignatureCheckedTransaction, SignedTransaction, Transaction,
-        TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
-        WriteSetPayload,
+        authenticator::Scheme, ChangeSet, EntryFunction, ExecutionError, ExecutionStatus,
+        ModuleBundle, Multisig, MultisigTransactionPayload, SignatureCheckedTransaction,
+        SignedTransaction, Transaction, TransactionOutput, TransactionPayload, TransactionStatus,
+        VMValidatorResult, WriteSetPayload,
     },
     vm_status::{AbortLocation, DiscardedVMStatus, StatusCode, VMStatus},
     write_set::WriteSet,
@@ -69,6 +69,7 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
 static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
@@ -1359,6 +1360,43 @@ impl AptosVM {
             },
         }
     }
+
+    /// Validates a transaction whose signature has already been checked.
+    fn validate_signature_checked(
+        &self,
+        txn: SignatureCheckedTransaction,
+        state_view: &impl StateView,
+    ) -> VMValidatorResult {
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let inner_resolver = &state_view.as_move_resolver();
+        let resolver = self.0.new_move_resolver(inner_resolver);
+        let mut session = self.new_session(&resolver, SessionId::txn(&txn));
+        let validation_result = self.validate_signature_checked_transaction(
+            &mut session,
+            &resolver,
+            &txn,
+            true,
+            &log_context,
+        );
+
+        // Increment the counter for transactions verified.
+        let (counter_label, result) = match validation_result {
+            Ok(_) => (
+                "success",
+                VMValidatorResult::new(None, txn.gas_unit_price()),
+            ),
+            Err(err) => (
+                "failure",
+                VMValidatorResult::new(Some(err.status_code()), 0),
+            ),
+        };
+
+        TRANSACTIONS_VALIDATED
+            .with_label_values(&[counter_label])
+            .inc();
+
+        result
+    }
 }
 
 // Executor external API
@@ -1416,42 +1454,35 @@ impl VMValidator for AptosVM {
         state_view: &impl StateView,
     ) -> VMValidatorResult {
         let _timer = TXN_VALIDATION_SECONDS.start_timer();
-        let log_context = AdapterLogSchema::new(state_view.id(), 0);
         let txn = match Self::check_signature(transaction) {
             Ok(t) => t,
             _ => {
                 return VMValidatorResult::error(StatusCode::INVALID_SIGNATURE);
             },
         };
+        self.validate_signature_checked(txn, state_view)
+    }
 
-        let inner_resolver = &state_view.as_move_resolver();
-        let resolver = self.0.new_move_resolver(inner_resolver);
-        let mut session = self.new_session(&resolver, SessionId::txn(&txn));
-        let validation_result = self.validate_signature_checked_transaction(
-            &mut session,
-            &resolver,
-            &txn,
-            true,
-            &log_context,
-        );
-
-        // Increment the counter for transactions verified.
-        let (counter_label, result) = match validation_result {
-            Ok(_) => (
-                "success",
-                VMValidatorResult::new(None, txn.gas_unit_price()),
-            ),
-            Err(err) => (
-                "failure",
-                VMValidatorResult::new(Some(err.status_code()), 0),
-            ),
-        };
-
-        TRANSACTIONS_VALIDATED
-            .with_label_values(&[counter_label])
-            .inc();
-
-        result
+    /// Same as `validate_transaction`, except that the signatures of the transactions are
+    /// verified as a batch.
+    fn validate_transactions(
+        &self,
+        transactions: Vec<SignedTransaction>,
+        state_view: &impl StateView,
+    ) -> Vec<VMValidatorResult> {
+        let start = Instant::now();
+        let num_transactions = transactions.len();
+        let results: Vec<_> = SignedTransaction::batch_check_signature(transactions)
+            .into_iter()
+            .map(|txn| match txn {
+                Ok(txn) => self.validate_signature_checked(txn, state_view),
+                Err(_) => VMValidatorResult::error(StatusCode::INVALID_SIGNATURE),
+            })
+            .collect();
+        if num_transactions > 0 {
+            TXN_VALIDATION_SECONDS.observe(start.elapsed().as_secs_f64() / num_transactions as f64);
+        }
+        results
     }
 }
 
@@ -1476,6 +1507,20 @@ impl VMAdapter for AptosVM {
             ));
         }
 
+        let authenticator = txn.authenticator();
+        for signer in
+            std::iter::once(authenticator.sender()).chain(authenticator.secondary_signers())
+        {
+            let feature = match signer.scheme() {
+                Scheme::Secp256k1Ecdsa => FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR,
+                Scheme::WebAuthn => FeatureFlag::WEBAUTHN_AUTHENTICATOR,
+                _ => continue,
+            };
+            if !self.0.get_features().is_enabled(feature) {
+                return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING, None));
+            }
+        }
+
         Ok(())
     }
 
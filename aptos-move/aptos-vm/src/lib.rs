@@ -138,6 +138,19 @@ pub trait VMValidator {
         transaction: SignedTransaction,
         state_view: &impl StateView,
     ) -> VMValidatorResult;
+
+    /// Validates a batch of transactions, returning the results in the same order. This lets
+    /// implementations share work across the batch, e.g., signature verification.
+    fn validate_transactions(
+        &self,
+        transactions: Vec<SignedTransaction>,
+        state_view: &impl StateView,
+    ) -> Vec<VMValidatorResult> {
+        transactions
+            .into_iter()
+            .map(|transaction| self.validate_transaction(transaction, state_view))
+            .collect()
+    }
 }
 
 /// This trait describes the VM's execution interface.
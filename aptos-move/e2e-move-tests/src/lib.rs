@@ -9,7 +9,10 @@ pub mod transaction_fee;
 use anyhow::bail;
 use aptos_framework::UPGRADE_POLICY_CUSTOM_FIELD;
 pub use harness::*;
-use move_package::{package_hooks::PackageHooks, source_package::parsed_manifest::CustomDepInfo};
+use move_package::{
+    package_hooks::PackageHooks,
+    source_package::parsed_manifest::{CustomDepInfo, CustomDepPin},
+};
 use move_symbol_pool::Symbol;
 pub use stake::*;
 
@@ -31,7 +34,8 @@ impl PackageHooks for AptosPackageHooks {
         &self,
         _dep_name: Symbol,
         _info: &CustomDepInfo,
-    ) -> anyhow::Result<()> {
+        _pin: Option<&CustomDepPin>,
+    ) -> anyhow::Result<CustomDepPin> {
         bail!("not used")
     }
 }
@@ -27,6 +27,7 @@ mod per_category_gas_limits;
 mod resource_groups;
 mod rotate_auth_key;
 mod scripts;
+mod secp256k1_ecdsa_authenticator;
 mod simple_defi;
 mod smart_data_structures;
 mod stake;
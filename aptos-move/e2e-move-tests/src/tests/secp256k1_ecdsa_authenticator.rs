@@ -0,0 +1,96 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_success, MoveHarness};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{
+    secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey},
+    PrivateKey, Uniform,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    on_chain_config::FeatureFlag,
+    transaction::{
+        authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionStatus,
+    },
+};
+use move_core_types::vm_status::StatusCode;
+
+/// Creates an account whose authentication key is derived from a new secp256k1 ECDSA key
+fn create_secp256k1_ecdsa_account(
+    harness: &mut MoveHarness,
+) -> (AccountAddress, Secp256k1EcdsaPrivateKey) {
+    let private_key = Secp256k1EcdsaPrivateKey::generate_for_testing();
+    let address = AuthenticationKey::secp256k1_ecdsa(&private_key.public_key()).derived_address();
+    let funder = harness.new_account_at(AccountAddress::from_hex_literal("0xf1").unwrap());
+    assert_success!(harness.run_transaction_payload(
+        &funder,
+        aptos_stdlib::aptos_account_transfer(address, 100_000_000),
+    ));
+    (address, private_key)
+}
+
+fn transfer_from(
+    address: AccountAddress,
+    sequence_number: u64,
+    private_key: &Secp256k1EcdsaPrivateKey,
+    public_key: Secp256k1EcdsaPublicKey,
+) -> SignedTransaction {
+    RawTransaction::new(
+        address,
+        sequence_number,
+        aptos_stdlib::aptos_account_transfer(AccountAddress::from_hex_literal("0xf2").unwrap(), 1),
+        2_000_000,
+        100,
+        u64::MAX,
+        ChainId::test(),
+    )
+    .sign_secp256k1_ecdsa(private_key, public_key)
+    .unwrap()
+    .into_inner()
+}
+
+#[test]
+fn secp256k1_ecdsa_authenticator() {
+    let mut harness =
+        MoveHarness::new_with_features(vec![FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR], vec![]);
+    let (address, private_key) = create_secp256k1_ecdsa_account(&mut harness);
+
+    let txn = transfer_from(address, 0, &private_key, private_key.public_key());
+    assert_success!(harness.run(txn));
+    assert_eq!(harness.sequence_number(&address), 1);
+
+    // The authentication key of the account must match the public key
+    let other_private_key = Secp256k1EcdsaPrivateKey::generate(&mut rand::rngs::OsRng);
+    let txn = transfer_from(
+        address,
+        1,
+        &other_private_key,
+        other_private_key.public_key(),
+    );
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::INVALID_AUTH_KEY)
+    );
+
+    // And the signature must match the transaction
+    let txn = transfer_from(address, 1, &other_private_key, private_key.public_key());
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::INVALID_SIGNATURE)
+    );
+}
+
+#[test]
+fn secp256k1_ecdsa_authenticator_disabled() {
+    let mut harness =
+        MoveHarness::new_with_features(vec![], vec![FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR]);
+    let (address, private_key) = create_secp256k1_ecdsa_account(&mut harness);
+
+    let txn = transfer_from(address, 0, &private_key, private_key.public_key());
+    assert_eq!(
+        harness.run(txn),
+        TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    );
+}
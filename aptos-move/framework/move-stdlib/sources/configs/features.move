@@ -162,6 +162,24 @@ module std::features {
     /// Lifetime: ephemeral
     const ED25519_PUBKEY_VALIDATE_RETURN_FALSE_WRONG_LENGTH: u64 = 14;
 
+    /// Whether transactions can be authenticated with secp256k1 ECDSA signatures.
+    ///
+    /// Lifetime: transient
+    const SECP256K1_ECDSA_AUTHENTICATOR: u64 = 15;
+    public fun get_secp256k1_ecdsa_authenticator_feature(): u64 { SECP256K1_ECDSA_AUTHENTICATOR }
+    public fun secp256k1_ecdsa_authenticator_enabled(): bool acquires Features {
+        is_enabled(SECP256K1_ECDSA_AUTHENTICATOR)
+    }
+
+    /// Whether transactions can be authenticated with WebAuthn assertions of secp256r1 passkeys.
+    ///
+    /// Lifetime: transient
+    const WEBAUTHN_AUTHENTICATOR: u64 = 16;
+    public fun get_webauthn_authenticator_feature(): u64 { WEBAUTHN_AUTHENTICATOR }
+    public fun webauthn_authenticator_enabled(): bool acquires Features {
+        is_enabled(WEBAUTHN_AUTHENTICATOR)
+    }
+
     // ============================================================================================
     // Feature Flag Implementation
 
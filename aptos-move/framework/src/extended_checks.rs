@@ -24,7 +24,7 @@ use thiserror::Error;
 const INIT_MODULE_FUN: &str = "init_module";
 const LEGAC_ENTRY_FUN_ATTRIBUTE: &str = "legacy_entry_fun";
 const ERROR_PREFIX: &str = "E";
-const RESOURCE_GROUP: &str = "resource_group";
+pub(crate) const RESOURCE_GROUP: &str = "resource_group";
 const RESOURCE_GROUP_MEMBER: &str = "resource_group_member";
 const RESOURCE_GROUP_NAME: &str = "group";
 const RESOURCE_GROUP_SCOPE: &str = "scope";
@@ -137,21 +137,12 @@ impl<'a> ExtendedChecker<'a> {
     }
 
     fn check_transaction_input_type(&self, loc: &Loc, ty: &Type) {
-        use Type::*;
         match ty {
-            Primitive(_) | TypeParameter(_) => {
-                // Any primitive type allowed, any parameter expected to instantiate with primitive
-            },
-            Reference(false, bt) if matches!(bt.as_ref(), Primitive(PrimitiveType::Signer)) => {
-                // Reference to signer allowed
-            },
-            Vector(ety) => {
+            Type::Vector(ety) => {
                 // Vectors are allowed if element type is allowed
                 self.check_transaction_input_type(loc, ety)
             },
-            Struct(mid, sid, _) if self.is_allowed_input_struct(mid.qualified(*sid)) => {
-                // Specific struct types are allowed
-            },
+            _ if is_transaction_input_type(self.env, ty) => {},
             _ => {
                 // Everything else is disallowed.
                 self.env.error(
@@ -164,13 +155,29 @@ impl<'a> ExtendedChecker<'a> {
             },
         }
     }
+}
 
-    fn is_allowed_input_struct(&self, qid: QualifiedId<StructId>) -> bool {
-        let name = self.env.get_struct(qid).get_full_name_with_address();
-        matches!(name.as_str(), "0x1::string::String")
+/// Returns whether values of the type can be passed as arguments of a transaction.
+pub(crate) fn is_transaction_input_type(env: &GlobalEnv, ty: &Type) -> bool {
+    use Type::*;
+    match ty {
+        // Any primitive type allowed, any parameter expected to instantiate with primitive
+        Primitive(_) | TypeParameter(_) => true,
+        // Reference to signer allowed
+        Reference(false, bt) => matches!(bt.as_ref(), Primitive(PrimitiveType::Signer)),
+        // Vectors are allowed if element type is allowed
+        Vector(ety) => is_transaction_input_type(env, ety),
+        // Specific struct types are allowed
+        Struct(mid, sid, _) => is_allowed_input_struct(env, mid.qualified(*sid)),
+        _ => false,
     }
 }
 
+fn is_allowed_input_struct(env: &GlobalEnv, qid: QualifiedId<StructId>) -> bool {
+    let name = env.get_struct(qid).get_full_name_with_address();
+    matches!(name.as_str(), "0x1::string::String")
+}
+
 // ----------------------------------------------------------------------------------
 // Resource Group Functions
 
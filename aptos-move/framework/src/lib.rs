@@ -20,6 +20,7 @@ mod release_builder;
 pub use release_builder::*;
 pub mod docgen;
 pub mod extended_checks;
+pub mod lint;
 pub use extended_checks::ResourceGroupScope;
 pub mod prover;
 mod release_bundle;
@@ -0,0 +1,65 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports public and entry functions which move coins between `Coin` values without emitting an
+//! event, which makes the transfer invisible to indexers and wallets.
+
+use crate::lint::{Lint, LintContext};
+use move_model::model::{FunId, FunctionEnv, GlobalEnv, QualifiedId};
+use move_stackless_bytecode::function_target::FunctionData;
+
+const COIN_MODULE: &str = "0x1::coin";
+
+/// The functions which move coins between `Coin` values.
+const COIN_MOVING_FUNCTIONS: &[&str] = &[
+    "0x1::coin::merge",
+    "0x1::coin::extract",
+    "0x1::coin::extract_all",
+];
+
+/// The functions which emit events.
+const EVENT_FUNCTIONS: &[&str] = &["0x1::event::emit_event"];
+
+pub(crate) struct CoinWithoutEvent;
+
+impl Lint for CoinWithoutEvent {
+    fn name(&self) -> &'static str {
+        "coin_without_event"
+    }
+
+    fn description(&self) -> &'static str {
+        "a function moves coins without emitting an event"
+    }
+
+    fn check_function(&self, ctx: &mut LintContext, fun: &FunctionEnv, _data: &FunctionData) {
+        if !fun.is_exposed() || fun.module_env.get_full_name_str() == COIN_MODULE {
+            return;
+        }
+        let env = ctx.env();
+        let callees = fun.get_transitive_closure_of_called_functions();
+        let calls_any = |names: &[&str]| {
+            callees
+                .iter()
+                .any(|id| names.contains(&full_name(env, *id).as_str()))
+        };
+        if calls_any(COIN_MOVING_FUNCTIONS) && !calls_any(EVENT_FUNCTIONS) {
+            ctx.report(
+                &fun.get_loc(),
+                format!(
+                    "function `{}` moves coins but does not emit an event",
+                    fun.get_name_str()
+                ),
+            );
+        }
+    }
+}
+
+/// Returns the name of a function including the address of its module, like `0x1::coin::merge`.
+fn full_name(env: &GlobalEnv, id: QualifiedId<FunId>) -> String {
+    let fun = env.get_function(id);
+    format!(
+        "{}::{}",
+        fun.module_env.get_full_name_str(),
+        fun.get_name_str()
+    )
+}
@@ -0,0 +1,67 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports structs with the `key` ability which their module never moves to global storage.
+//! Only the declaring module can publish a resource, so `key` on such a struct is either unneeded
+//! or the publishing code is missing.
+
+use crate::{
+    extended_checks::RESOURCE_GROUP,
+    lint::{Lint, LintContext},
+};
+use move_binary_format::file_format::{Ability, Bytecode};
+use move_model::{ast::Attribute, model::StructEnv};
+
+pub(crate) struct KeyNeverStored;
+
+impl Lint for KeyNeverStored {
+    fn name(&self) -> &'static str {
+        "key_never_stored"
+    }
+
+    fn description(&self) -> &'static str {
+        "a struct has the `key` ability but is never moved to global storage"
+    }
+
+    fn check_struct(&self, ctx: &mut LintContext, struct_env: &StructEnv) {
+        if !struct_env.get_abilities().has_ability(Ability::Key)
+            || struct_env.is_native()
+            || is_resource_group(struct_env)
+        {
+            return;
+        }
+        let module = &struct_env.module_env;
+        let stored = module.get_functions().any(|fun| {
+            fun.get_bytecode().iter().any(|bytecode| {
+                let idx = match bytecode {
+                    Bytecode::MoveTo(idx) => *idx,
+                    Bytecode::MoveToGeneric(idx) => {
+                        module
+                            .get_verified_module()
+                            .struct_instantiation_at(*idx)
+                            .def
+                    },
+                    _ => return false,
+                };
+                module.get_struct_id(idx) == struct_env.get_id()
+            })
+        });
+        if !stored {
+            ctx.report(
+                &struct_env.get_loc(),
+                format!(
+                    "struct `{}` has the `key` ability but is never moved to global storage",
+                    struct_env.get_full_name_str()
+                ),
+            );
+        }
+    }
+}
+
+/// Returns whether the struct is a resource group container, which is never stored itself.
+fn is_resource_group(struct_env: &StructEnv) -> bool {
+    let pool = struct_env.symbol_pool();
+    struct_env.get_attributes().iter().any(|attr| {
+        matches!(attr, Attribute::Apply(_, name, _) if pool.string(*name).as_str() == RESOURCE_GROUP)
+    })
+}
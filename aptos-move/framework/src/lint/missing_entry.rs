@@ -0,0 +1,58 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports public functions which look like transaction entry points but are not `entry`: they
+//! take a signer and transaction arguments, return nothing and are not called from the package.
+
+use crate::{
+    extended_checks::is_transaction_input_type,
+    lint::{Lint, LintContext},
+};
+use move_model::{
+    model::{FunctionEnv, FunctionVisibility},
+    ty::{PrimitiveType, Type},
+};
+use move_stackless_bytecode::function_target::FunctionData;
+
+pub(crate) struct MissingEntry;
+
+impl Lint for MissingEntry {
+    fn name(&self) -> &'static str {
+        "missing_entry"
+    }
+
+    fn description(&self) -> &'static str {
+        "a public function which is only usable as a transaction is not `entry`"
+    }
+
+    fn check_function(&self, ctx: &mut LintContext, fun: &FunctionEnv, _data: &FunctionData) {
+        if fun.visibility() != FunctionVisibility::Public
+            || fun.is_entry()
+            || fun.is_native()
+            || fun.module_env.is_script_module()
+            || fun.get_return_count() > 0
+            || !fun.get_calling_functions().is_empty()
+        {
+            return;
+        }
+        let param_types = fun.get_parameter_types();
+        let takes_signer = matches!(
+            param_types.first(),
+            Some(Type::Reference(false, ty)) if **ty == Type::Primitive(PrimitiveType::Signer)
+        );
+        if takes_signer
+            && param_types
+                .iter()
+                .all(|ty| is_transaction_input_type(ctx.env(), ty))
+        {
+            ctx.report(
+                &fun.get_loc(),
+                format!(
+                    "public function `{}` takes a signer and is never called in the package, \
+                     consider making it `entry`",
+                    fun.get_name_str()
+                ),
+            );
+        }
+    }
+}
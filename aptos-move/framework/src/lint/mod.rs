@@ -0,0 +1,268 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lints for Move packages.
+//!
+//! Lints report code which compiles but is likely a mistake, like a function which takes a `&mut`
+//! parameter it never mutates. They run on the Move model of a package, and each lint reports its
+//! findings at a level which defaults to the lint's own but can be configured per rule. Findings
+//! of a rule in a module, struct or function are suppressed by annotating it with
+//! `#[lint_allow(<rule>, ..)]`.
+
+mod coin_without_event;
+mod key_never_stored;
+mod missing_entry;
+mod narrowing_cast;
+mod needless_mut_ref;
+mod unused_acquires;
+
+use crate::build_model;
+use anyhow::bail;
+use codespan_reporting::{
+    diagnostic::{Diagnostic, Label, Severity},
+    term::termcolor::{ColorChoice, StandardStream},
+};
+use move_core_types::account_address::AccountAddress;
+use move_model::{
+    ast::Attribute,
+    model::{FunctionEnv, GlobalEnv, Loc, ModuleEnv, StructEnv},
+};
+use move_stackless_bytecode::{
+    function_target::FunctionData, stackless_bytecode_generator::StacklessBytecodeGenerator,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+const LINT_ALLOW_ATTRIBUTE: &str = "lint_allow";
+
+/// The level at which the findings of a lint are reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LintLevel {
+    Allow,
+    Warning,
+    Error,
+}
+
+/// A lint rule. Each rule implements the checks for the kinds of items it looks at, and reports
+/// findings through the given context.
+pub trait Lint {
+    /// The name of the rule, as used on the command line and in `#[lint_allow]`.
+    fn name(&self) -> &'static str;
+
+    /// A short description of what the rule reports.
+    fn description(&self) -> &'static str;
+
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warning
+    }
+
+    fn check_module(&self, _ctx: &mut LintContext, _module: &ModuleEnv) {}
+
+    fn check_struct(&self, _ctx: &mut LintContext, _struct_env: &StructEnv) {}
+
+    /// Checks a function, given its stackless bytecode.
+    fn check_function(&self, _ctx: &mut LintContext, _fun: &FunctionEnv, _data: &FunctionData) {}
+}
+
+/// Returns all available lints.
+pub fn all_lints() -> Vec<Box<dyn Lint>> {
+    vec![
+        Box::new(unused_acquires::UnusedAcquires),
+        Box::new(needless_mut_ref::NeedlessMutRef),
+        Box::new(missing_entry::MissingEntry),
+        Box::new(narrowing_cast::NarrowingCast),
+        Box::new(key_never_stored::KeyNeverStored),
+        Box::new(coin_without_event::CoinWithoutEvent),
+    ]
+}
+
+/// A finding of a lint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintDiagnostic {
+    pub rule: String,
+    pub level: LintLevel,
+    pub message: String,
+    pub file: String,
+    /// The line of the finding, starting at 1.
+    pub line: u32,
+    /// The column of the finding, starting at 1.
+    pub column: u32,
+}
+
+/// The context in which a lint checks an item.
+pub struct LintContext<'a> {
+    env: &'a GlobalEnv,
+    rule: &'static str,
+    level: LintLevel,
+    /// Whether the rule is allowed for the item being checked.
+    allowed: bool,
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn env(&self) -> &'a GlobalEnv {
+        self.env
+    }
+
+    /// Reports a finding of the current rule at `loc`, unless the rule is allowed there.
+    pub fn report(&mut self, loc: &Loc, message: impl Into<String>) {
+        if self.allowed {
+            return;
+        }
+        let message = message.into();
+        let severity = if self.level == LintLevel::Error {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        self.env.add_diag(
+            Diagnostic::new(severity)
+                .with_code(self.rule)
+                .with_message(&message)
+                .with_labels(vec![Label::primary(loc.file_id(), loc.span())]),
+        );
+        let (file, line, column) = match self.env.get_file_and_location(loc) {
+            Some((file, location)) => (file, location.line.0 + 1, location.column.0 + 1),
+            None => (String::new(), 0, 0),
+        };
+        self.diagnostics.push(LintDiagnostic {
+            rule: self.rule.to_string(),
+            level: self.level,
+            message,
+            file,
+            line,
+            column,
+        });
+    }
+}
+
+/// Runs all lints on the target modules of `env`, at the levels in `levels` or, for rules which
+/// are not in there, their default levels. Findings are also added to the diagnostics of `env`.
+pub fn run_lints(env: &GlobalEnv, levels: &BTreeMap<String, LintLevel>) -> Vec<LintDiagnostic> {
+    let lints: Vec<_> = all_lints()
+        .into_iter()
+        .map(|lint| {
+            let level = levels
+                .get(lint.name())
+                .copied()
+                .unwrap_or_else(|| lint.default_level());
+            (lint, level)
+        })
+        .filter(|(_, level)| *level != LintLevel::Allow)
+        .collect();
+    let mut ctx = LintContext {
+        env,
+        rule: "",
+        level: LintLevel::Allow,
+        allowed: false,
+        diagnostics: vec![],
+    };
+    for module in env.get_modules().filter(|module| module.is_target()) {
+        let module_allowed = allowed_rules(env, module.get_attributes());
+        let functions: Vec<_> = module
+            .get_functions()
+            .map(|fun| {
+                let data = StacklessBytecodeGenerator::new(&fun).generate_function();
+                (fun, data)
+            })
+            .collect();
+        for (lint, level) in &lints {
+            let rule = lint.name();
+            ctx.rule = rule;
+            ctx.level = *level;
+            ctx.allowed = module_allowed.contains(rule);
+            lint.check_module(&mut ctx, &module);
+            for struct_env in module.get_structs() {
+                ctx.allowed = module_allowed.contains(rule)
+                    || allowed_rules(env, struct_env.get_attributes()).contains(rule);
+                lint.check_struct(&mut ctx, &struct_env);
+            }
+            for (fun, data) in &functions {
+                ctx.allowed = module_allowed.contains(rule)
+                    || allowed_rules(env, fun.get_attributes()).contains(rule);
+                lint.check_function(&mut ctx, fun, data);
+            }
+        }
+    }
+    ctx.diagnostics
+}
+
+/// Returns the rules allowed by the `#[lint_allow(..)]` attributes among `attributes`.
+fn allowed_rules(env: &GlobalEnv, attributes: &[Attribute]) -> BTreeSet<String> {
+    let pool = env.symbol_pool();
+    let mut rules = BTreeSet::new();
+    for attr in attributes {
+        if let Attribute::Apply(_, name, args) = attr {
+            if pool.string(*name).as_str() == LINT_ALLOW_ATTRIBUTE {
+                rules.extend(args.iter().map(|arg| match arg {
+                    Attribute::Apply(_, rule, _) | Attribute::Assign(_, rule, _) => {
+                        pool.string(*rule).to_string()
+                    },
+                }));
+            }
+        }
+    }
+    rules
+}
+
+#[derive(Debug, Clone, Default, clap::Parser, Serialize, Deserialize)]
+pub struct LintOptions {
+    /// Lints to report as errors, which make linting fail
+    #[clap(long, multiple_values = true)]
+    pub deny: Vec<String>,
+
+    /// Lints to report as warnings
+    #[clap(long, multiple_values = true)]
+    pub warn: Vec<String>,
+
+    /// Lints to skip
+    #[clap(long, multiple_values = true)]
+    pub allow: Vec<String>,
+}
+
+impl LintOptions {
+    /// Builds the package and lints it, printing the findings to stderr. Returns the findings,
+    /// and fails if the package does not compile.
+    pub fn lint(
+        &self,
+        package_path: &Path,
+        named_addresses: BTreeMap<String, AccountAddress>,
+        bytecode_version: Option<u32>,
+    ) -> anyhow::Result<Vec<LintDiagnostic>> {
+        let levels = self.levels()?;
+        let model = build_model(package_path, named_addresses, None, bytecode_version)?;
+        let mut error_writer = StandardStream::stderr(ColorChoice::Auto);
+        if model.has_errors() {
+            model.report_diag(&mut error_writer, Severity::Warning);
+            bail!("compilation failed")
+        }
+        let diagnostics = run_lints(&model, &levels);
+        model.report_diag(&mut error_writer, Severity::Warning);
+        Ok(diagnostics)
+    }
+
+    fn levels(&self) -> anyhow::Result<BTreeMap<String, LintLevel>> {
+        let known: BTreeSet<_> = all_lints().iter().map(|lint| lint.name()).collect();
+        let mut levels = BTreeMap::new();
+        for (rules, level) in [
+            (&self.allow, LintLevel::Allow),
+            (&self.warn, LintLevel::Warning),
+            (&self.deny, LintLevel::Error),
+        ] {
+            for rule in rules {
+                if !known.contains(rule.as_str()) {
+                    bail!(
+                        "unknown lint `{}`, expected one of: {}",
+                        rule,
+                        known.iter().cloned().collect::<Vec<_>>().join(", ")
+                    )
+                }
+                levels.insert(rule.clone(), level);
+            }
+        }
+        Ok(levels)
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports casts to a narrower integer type, like `(x as u64)` for an `x: u128`, whose operand is
+//! never compared in the function. Such casts abort when the value does not fit, which is usually
+//! better checked with a dedicated error code.
+
+use crate::lint::{Lint, LintContext};
+use move_model::{
+    ast::TempIndex,
+    model::FunctionEnv,
+    ty::{PrimitiveType, Type},
+};
+use move_stackless_bytecode::{
+    function_target::FunctionData,
+    stackless_bytecode::{AssignKind, Bytecode, Operation},
+};
+use std::collections::BTreeSet;
+
+pub(crate) struct NarrowingCast;
+
+impl Lint for NarrowingCast {
+    fn name(&self) -> &'static str {
+        "narrowing_cast"
+    }
+
+    fn description(&self) -> &'static str {
+        "an integer is cast to a narrower type without checking that it fits"
+    }
+
+    fn check_function(&self, ctx: &mut LintContext, _fun: &FunctionEnv, data: &FunctionData) {
+        // The locals compared with `<`, `>`, `<=` or `>=`, or the temporaries if not read from one.
+        let compared: BTreeSet<_> = data
+            .code
+            .iter()
+            .filter_map(|bytecode| match bytecode {
+                Bytecode::Call(
+                    _,
+                    _,
+                    Operation::Lt | Operation::Gt | Operation::Le | Operation::Ge,
+                    srcs,
+                    _,
+                ) => Some(srcs),
+                _ => None,
+            })
+            .flatten()
+            .map(|temp| origin(data, *temp))
+            .collect();
+        for bytecode in &data.code {
+            let (attr_id, op, src) = match bytecode {
+                Bytecode::Call(attr_id, _, op, srcs, _) if srcs.len() == 1 => {
+                    (attr_id, op, srcs[0])
+                },
+                _ => continue,
+            };
+            let target = match op {
+                Operation::CastU8 => PrimitiveType::U8,
+                Operation::CastU16 => PrimitiveType::U16,
+                Operation::CastU32 => PrimitiveType::U32,
+                Operation::CastU64 => PrimitiveType::U64,
+                Operation::CastU128 => PrimitiveType::U128,
+                _ => continue,
+            };
+            let source = match &data.local_types[src] {
+                Type::Primitive(ty) => *ty,
+                _ => continue,
+            };
+            if width(source) > width(target) && !compared.contains(&origin(data, src)) {
+                if let Some(loc) = data.locations.get(attr_id) {
+                    ctx.report(
+                        loc,
+                        format!(
+                            "cast from `u{}` to `u{}` aborts if the value does not fit",
+                            width(source),
+                            width(target)
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns the local from which the temporary `temp` was copied or moved, or `temp` itself.
+fn origin(data: &FunctionData, temp: TempIndex) -> TempIndex {
+    data.code
+        .iter()
+        .find_map(|bytecode| match bytecode {
+            Bytecode::Assign(_, dest, src, AssignKind::Copy | AssignKind::Move)
+                if *dest == temp =>
+            {
+                Some(*src)
+            },
+            _ => None,
+        })
+        .unwrap_or(temp)
+}
+
+/// Returns the number of bits of an integer type, or 0 for other types.
+fn width(ty: PrimitiveType) -> usize {
+    match ty {
+        PrimitiveType::U8 => 8,
+        PrimitiveType::U16 => 16,
+        PrimitiveType::U32 => 32,
+        PrimitiveType::U64 => 64,
+        PrimitiveType::U128 => 128,
+        PrimitiveType::U256 => 256,
+        _ => 0,
+    }
+}
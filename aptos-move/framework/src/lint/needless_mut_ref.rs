@@ -0,0 +1,84 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports `&mut` parameters through which a function never mutates, and which could therefore
+//! be immutable references.
+
+use crate::lint::{Lint, LintContext};
+use move_model::{
+    ast::TempIndex,
+    model::{FunctionEnv, Parameter},
+    ty::Type,
+};
+use move_stackless_bytecode::{
+    function_target::FunctionData,
+    stackless_bytecode::{Bytecode, Operation},
+};
+use std::collections::BTreeSet;
+
+pub(crate) struct NeedlessMutRef;
+
+impl Lint for NeedlessMutRef {
+    fn name(&self) -> &'static str {
+        "needless_mut_ref"
+    }
+
+    fn description(&self) -> &'static str {
+        "a `&mut` parameter is never used to mutate"
+    }
+
+    fn check_function(&self, ctx: &mut LintContext, fun: &FunctionEnv, data: &FunctionData) {
+        if fun.is_native() {
+            return;
+        }
+        for (idx, Parameter(name, ty)) in fun.get_parameters().into_iter().enumerate() {
+            if matches!(ty, Type::Reference(true, _)) && !is_used_mutably(data, idx) {
+                ctx.report(
+                    &fun.get_loc(),
+                    format!(
+                        "parameter `{}` of `{}` is a mutable reference but is never used to mutate",
+                        name.display(fun.symbol_pool()),
+                        fun.get_name_str()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Returns whether the reference in `param` is used for anything but reading, conservatively
+/// assuming that passing it to a function or returning it mutates.
+fn is_used_mutably(data: &FunctionData, param: TempIndex) -> bool {
+    // The temporaries holding the reference, which are copied from each other.
+    let mut aliases = BTreeSet::from([param]);
+    loop {
+        let count = aliases.len();
+        for bytecode in &data.code {
+            if let Bytecode::Assign(_, dest, src, _) = bytecode {
+                if aliases.contains(src) {
+                    aliases.insert(*dest);
+                }
+            }
+        }
+        if aliases.len() == count {
+            break;
+        }
+    }
+    data.code.iter().any(|bytecode| match bytecode {
+        Bytecode::Call(_, dests, op, srcs, _) if srcs.iter().any(|src| aliases.contains(src)) => {
+            match op {
+                Operation::ReadRef
+                | Operation::FreezeRef
+                | Operation::Destroy
+                | Operation::Eq
+                | Operation::Neq => false,
+                Operation::BorrowField(..) => {
+                    matches!(data.local_types[dests[0]], Type::Reference(true, _))
+                },
+                _ => true,
+            }
+        },
+        Bytecode::Ret(_, srcs) => srcs.iter().any(|src| aliases.contains(src)),
+        _ => false,
+    })
+}
@@ -0,0 +1,61 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports resources in the `acquires` list of a function which neither the function nor the
+//! functions it calls in its module access.
+
+use crate::lint::{Lint, LintContext};
+use move_model::model::FunctionEnv;
+use move_stackless_bytecode::{
+    function_target::FunctionData,
+    stackless_bytecode::{Bytecode, Operation},
+};
+use std::collections::BTreeSet;
+
+pub(crate) struct UnusedAcquires;
+
+impl Lint for UnusedAcquires {
+    fn name(&self) -> &'static str {
+        "unused_acquires"
+    }
+
+    fn description(&self) -> &'static str {
+        "a resource in the `acquires` list of a function is never accessed"
+    }
+
+    fn check_function(&self, ctx: &mut LintContext, fun: &FunctionEnv, data: &FunctionData) {
+        let module_id = fun.module_env.get_id();
+        let mut accessed = BTreeSet::new();
+        for bytecode in &data.code {
+            if let Bytecode::Call(_, _, op, _, _) = bytecode {
+                match op {
+                    Operation::MoveFrom(mid, sid, _) | Operation::BorrowGlobal(mid, sid, _)
+                        if *mid == module_id =>
+                    {
+                        accessed.insert(*sid);
+                    },
+                    Operation::Function(mid, fid, _) if *mid == module_id => {
+                        accessed.extend(
+                            fun.module_env
+                                .get_function(*fid)
+                                .get_acquires_global_resources(),
+                        );
+                    },
+                    _ => {},
+                }
+            }
+        }
+        for sid in fun.get_acquires_global_resources() {
+            if !accessed.contains(&sid) {
+                ctx.report(
+                    &fun.get_loc(),
+                    format!(
+                        "function `{}` acquires `{}` but never accesses it",
+                        fun.get_name_str(),
+                        fun.module_env.get_struct(sid).get_full_name_str()
+                    ),
+                );
+            }
+        }
+    }
+}
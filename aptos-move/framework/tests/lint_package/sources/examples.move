@@ -0,0 +1,72 @@
+/// Code with findings of the lints, checked by `lint_tests.rs`.
+module lint_examples::examples {
+    use aptos_framework::coin::{Self, Coin};
+    use aptos_framework::event::{Self, EventHandle};
+
+    const MAX_U64: u128 = 18446744073709551615;
+
+    struct Counter has key {
+        value: u64,
+    }
+
+    struct Unpublished has key {
+        value: u64,
+    }
+
+    #[lint_allow(key_never_stored)]
+    struct AllowedUnpublished has key {
+        value: u64,
+    }
+
+    public fun publish(account: &signer) {
+        move_to(account, Counter { value: 0 });
+    }
+
+    #[lint_allow(missing_entry)]
+    public fun publish_allowed(account: &signer) {
+        move_to(account, Counter { value: 0 });
+    }
+
+    public entry fun publish_entry(account: &signer) {
+        publish_with_value(account, 0);
+    }
+
+    public fun publish_with_value(account: &signer, value: u64) {
+        move_to(account, Counter { value });
+    }
+
+    public fun increment(counter: &mut Counter) {
+        counter.value = counter.value + 1;
+    }
+
+    public fun value(counter: &mut Counter): u64 {
+        counter.value
+    }
+
+    public fun truncate(x: u128): u64 {
+        (x as u64)
+    }
+
+    public fun checked_truncate(x: u128): u64 {
+        assert!(x <= MAX_U64, 1);
+        (x as u64)
+    }
+
+    public fun widen(x: u64): u128 {
+        (x as u128)
+    }
+
+    public fun join<CoinType>(coins: &mut Coin<CoinType>, other: Coin<CoinType>) {
+        coin::merge(coins, other);
+    }
+
+    public fun join_with_event<CoinType>(
+        coins: &mut Coin<CoinType>,
+        other: Coin<CoinType>,
+        handle: &mut EventHandle<u64>,
+    ) {
+        let amount = coin::value(&other);
+        coin::merge(coins, other);
+        event::emit_event(handle, amount);
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_framework::{
+    build_model,
+    lint::{run_lints, LintLevel},
+    path_in_crate,
+};
+use std::collections::BTreeMap;
+
+/// Lints the package in `tests/lint_package` and returns the level, rule and message of each
+/// finding, sorted.
+fn lint_findings(levels: &[(&str, LintLevel)]) -> Vec<(LintLevel, String, String)> {
+    let env = build_model(
+        &path_in_crate("tests/lint_package"),
+        BTreeMap::new(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(!env.has_errors(), "lint package does not compile");
+    let levels = levels
+        .iter()
+        .map(|(rule, level)| (rule.to_string(), *level))
+        .collect();
+    let mut findings: Vec<_> = run_lints(&env, &levels)
+        .into_iter()
+        .map(|diag| {
+            assert!(diag.file.ends_with("examples.move"));
+            (diag.level, diag.rule, diag.message)
+        })
+        .collect();
+    findings.sort();
+    findings
+}
+
+fn warning(rule: &str, message: &str) -> (LintLevel, String, String) {
+    (LintLevel::Warning, rule.to_string(), message.to_string())
+}
+
+#[test]
+fn lint_default_levels() {
+    assert_eq!(lint_findings(&[]), vec![
+        warning(
+            "coin_without_event",
+            "function `join` moves coins but does not emit an event"
+        ),
+        warning(
+            "key_never_stored",
+            "struct `examples::Unpublished` has the `key` ability but is never moved to global \
+             storage"
+        ),
+        warning(
+            "missing_entry",
+            "public function `publish` takes a signer and is never called in the package, \
+             consider making it `entry`"
+        ),
+        warning(
+            "narrowing_cast",
+            "cast from `u128` to `u64` aborts if the value does not fit"
+        ),
+        warning(
+            "needless_mut_ref",
+            "parameter `counter` of `value` is a mutable reference but is never used to mutate"
+        ),
+    ]);
+}
+
+#[test]
+fn lint_configured_levels() {
+    let findings = lint_findings(&[
+        ("narrowing_cast", LintLevel::Error),
+        ("coin_without_event", LintLevel::Allow),
+        ("key_never_stored", LintLevel::Allow),
+        ("missing_entry", LintLevel::Allow),
+    ]);
+    assert_eq!(findings, vec![
+        warning(
+            "needless_mut_ref",
+            "parameter `counter` of `value` is a mutable reference but is never used to mutate"
+        ),
+        (
+            LintLevel::Error,
+            "narrowing_cast".to_string(),
+            "cast from `u128` to `u64` aborts if the value does not fit".to_string()
+        ),
+    ]);
+}
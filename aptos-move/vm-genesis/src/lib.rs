@@ -420,6 +420,8 @@ pub fn default_features() -> Vec<FeatureFlag> {
         FeatureFlag::MULTISIG_ACCOUNTS,
         FeatureFlag::DELEGATION_POOLS,
         FeatureFlag::ED25519_PUBKEY_VALIDATE_RETURN_FALSE_WRONG_LENGTH,
+        FeatureFlag::SECP256K1_ECDSA_AUTHENTICATOR,
+        FeatureFlag::WEBAUTHN_AUTHENTICATOR,
     ]
 }
 
@@ -19,6 +19,7 @@ use anyhow::anyhow;
 use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
 use aptos_config::config::{NodeConfig, PersistableConfig};
+use aptos_consensus::{quorum_store_inspection::QuorumStoreInspector, ProposalSimulationService};
 use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
@@ -374,8 +375,15 @@ pub fn setup_environment_and_start_node(
     // Log the node config at node startup
     info!("Using node config {:?}", &node_config);
 
-    // Start the node inspection service
-    services::start_node_inspection_service(&node_config);
+    // Start the node inspection service. Proposals can be simulated, and the quorum store
+    // inspected, once consensus has started.
+    let proposal_simulation_service = ProposalSimulationService::default();
+    let quorum_store_inspector = QuorumStoreInspector::default();
+    services::start_node_inspection_service(
+        &node_config,
+        proposal_simulation_service.clone(),
+        quorum_store_inspector.clone(),
+    );
 
     // Set up the storage database and any RocksDB checkpoints
     let (aptos_db, db_rw, backup_service, genesis_waypoint) =
@@ -409,6 +417,7 @@ pub fn setup_environment_and_start_node(
     let (
         network_runtimes,
         consensus_network_interfaces,
+        consensus_observer_network_interfaces,
         mempool_network_interfaces,
         peer_monitoring_service_network_interfaces,
         storage_service_network_interfaces,
@@ -416,7 +425,7 @@ pub fn setup_environment_and_start_node(
         &node_config,
         chain_id,
         &mut event_subscription_service,
-    );
+    )?;
 
     // Start the peer monitoring service
     let peer_monitoring_service_runtime = services::start_peer_monitoring_service(
@@ -449,23 +458,50 @@ pub fn setup_environment_and_start_node(
             mempool_client_receiver,
         );
 
-    // Create the consensus runtime (this blocks on state sync first)
-    let consensus_runtime = consensus_network_interfaces.map(|consensus_network_interfaces| {
-        // Wait until state sync has been initialized
-        debug!("Waiting until state sync is initialized!");
-        state_sync_runtimes.block_until_initialized();
-        debug!("State sync initialization complete.");
-
-        // Initialize and start consensus
-        services::start_consensus_runtime(
-            &mut node_config,
-            db_rw,
-            consensus_reconfig_subscription,
-            consensus_network_interfaces,
-            consensus_notifier,
-            consensus_to_mempool_sender,
-        )
-    });
+    // Create the consensus (or consensus observer) runtime (this blocks on state sync first)
+    let consensus_runtime = match (
+        consensus_network_interfaces,
+        consensus_observer_network_interfaces,
+    ) {
+        (Some(consensus_network_interfaces), consensus_observer_network_interfaces) => {
+            // Wait until state sync has been initialized
+            debug!("Waiting until state sync is initialized!");
+            state_sync_runtimes.block_until_initialized();
+            debug!("State sync initialization complete.");
+
+            // Initialize and start consensus
+            Some(services::start_consensus_runtime(
+                &mut node_config,
+                db_rw,
+                consensus_reconfig_subscription,
+                consensus_network_interfaces,
+                consensus_observer_network_interfaces,
+                consensus_notifier,
+                consensus_to_mempool_sender,
+                proposal_simulation_service,
+                quorum_store_inspector,
+            ))
+        },
+        (None, Some(consensus_observer_network_interfaces))
+            if node_config.consensus_observer.observer_enabled =>
+        {
+            // Wait until state sync has been initialized
+            debug!("Waiting until state sync is initialized!");
+            state_sync_runtimes.block_until_initialized();
+            debug!("State sync initialization complete.");
+
+            // Initialize and start the consensus observer
+            Some(services::start_consensus_observer_runtime(
+                &node_config,
+                db_rw,
+                consensus_reconfig_subscription,
+                consensus_observer_network_interfaces,
+                consensus_notifier,
+                consensus_to_mempool_sender,
+            ))
+        },
+        _ => None,
+    };
 
     Ok(AptosHandle {
         _api_runtime: api_runtime,
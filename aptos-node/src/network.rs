@@ -6,7 +6,10 @@ use aptos_config::{
     config::{NetworkConfig, NodeConfig},
     network_id::NetworkId,
 };
-use aptos_consensus::network_interface::{ConsensusMsg, DIRECT_SEND, RPC};
+use aptos_consensus::{
+    network_interface::{ConsensusMsg, DIRECT_SEND, RPC},
+    ConsensusObserverMessage,
+};
 use aptos_event_notifications::EventSubscriptionService;
 use aptos_logger::debug;
 use aptos_mempool::network::MempoolSyncMsg;
@@ -68,6 +71,24 @@ pub fn consensus_network_configuration() -> NetworkApplicationConfig {
     NetworkApplicationConfig::new(network_client_config, network_service_config)
 }
 
+/// Returns the network application config for the consensus observer client and service
+pub fn consensus_observer_network_configuration(
+    node_config: &NodeConfig,
+) -> NetworkApplicationConfig {
+    let direct_send_protocols = vec![ProtocolId::ConsensusObserver];
+    let rpc_protocols = vec![ProtocolId::ConsensusObserverRpc];
+    let max_network_channel_size = node_config.consensus_observer.max_network_channel_size as usize;
+
+    let network_client_config =
+        NetworkClientConfig::new(direct_send_protocols.clone(), rpc_protocols.clone());
+    let network_service_config = NetworkServiceConfig::new(
+        direct_send_protocols,
+        rpc_protocols,
+        aptos_channel::Config::new(max_network_channel_size).queue_style(QueueStyle::FIFO),
+    );
+    NetworkApplicationConfig::new(network_client_config, network_service_config)
+}
+
 /// Returns the network application config for the mempool client and service
 pub fn mempool_network_configuration() -> NetworkApplicationConfig {
     let direct_send_protocols = vec![ProtocolId::MempoolDirectSend];
@@ -166,13 +187,14 @@ pub fn setup_networks_and_get_interfaces(
     node_config: &NodeConfig,
     chain_id: ChainId,
     event_subscription_service: &mut EventSubscriptionService,
-) -> (
+) -> anyhow::Result<(
     Vec<Runtime>,
     Option<ApplicationNetworkInterfaces<ConsensusMsg>>,
+    Option<ApplicationNetworkInterfaces<ConsensusObserverMessage>>,
     ApplicationNetworkInterfaces<MempoolSyncMsg>,
     ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     ApplicationNetworkInterfaces<StorageServiceMessage>,
-) {
+)> {
     // Gather all network configs and network ids
     let (network_configs, network_ids) = extract_network_configs_and_ids(node_config);
 
@@ -182,6 +204,7 @@ pub fn setup_networks_and_get_interfaces(
     // Create each network and register the application handles
     let mut network_runtimes = vec![];
     let mut consensus_network_handle = None;
+    let mut consensus_observer_network_handles = vec![];
     let mut mempool_network_handles = vec![];
     let mut peer_monitoring_service_network_handles = vec![];
     let mut storage_service_network_handles = vec![];
@@ -217,6 +240,16 @@ pub fn setup_networks_and_get_interfaces(
             }
         }
 
+        // Register the consensus observer (both client and server) with the network
+        if node_config.consensus_observer.is_enabled() {
+            let consensus_observer_network_handle = register_client_and_service_with_network(
+                &mut network_builder,
+                network_id,
+                consensus_observer_network_configuration(node_config),
+            );
+            consensus_observer_network_handles.push(consensus_observer_network_handle);
+        }
+
         // Register mempool (both client and server) with the network
         let mempool_network_handle = register_client_and_service_with_network(
             &mut network_builder,
@@ -242,7 +275,7 @@ pub fn setup_networks_and_get_interfaces(
         storage_service_network_handles.push(storage_service_network_handle);
 
         // Build and start the network on the runtime
-        network_builder.build(runtime.handle().clone());
+        network_builder.build(runtime.handle().clone())?;
         network_builder.start();
         network_runtimes.push(runtime);
         debug!(
@@ -254,25 +287,28 @@ pub fn setup_networks_and_get_interfaces(
     // Transform all network handles into application interfaces
     let (
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
     ) = transform_network_handles_into_interfaces(
         node_config,
         consensus_network_handle,
+        consensus_observer_network_handles,
         mempool_network_handles,
         peer_monitoring_service_network_handles,
         storage_service_network_handles,
         peers_and_metadata,
     );
 
-    (
+    Ok((
         network_runtimes,
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
-    )
+    ))
 }
 
 /// Creates a network runtime for the given network config
@@ -308,6 +344,7 @@ fn register_client_and_service_with_network<T: Serialize + for<'de> Deserialize<
 fn transform_network_handles_into_interfaces(
     node_config: &NodeConfig,
     consensus_network_handle: Option<ApplicationNetworkHandle<ConsensusMsg>>,
+    consensus_observer_network_handles: Vec<ApplicationNetworkHandle<ConsensusObserverMessage>>,
     mempool_network_handles: Vec<ApplicationNetworkHandle<MempoolSyncMsg>>,
     peer_monitoring_service_network_handles: Vec<
         ApplicationNetworkHandle<PeerMonitoringServiceMessage>,
@@ -316,6 +353,7 @@ fn transform_network_handles_into_interfaces(
     peers_and_metadata: Arc<PeersAndMetadata>,
 ) -> (
     Option<ApplicationNetworkInterfaces<ConsensusMsg>>,
+    Option<ApplicationNetworkInterfaces<ConsensusObserverMessage>>,
     ApplicationNetworkInterfaces<MempoolSyncMsg>,
     ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     ApplicationNetworkInterfaces<StorageServiceMessage>,
@@ -327,6 +365,15 @@ fn transform_network_handles_into_interfaces(
             peers_and_metadata.clone(),
         )
     });
+    let consensus_observer_interfaces = if consensus_observer_network_handles.is_empty() {
+        None
+    } else {
+        Some(create_network_interfaces(
+            consensus_observer_network_handles,
+            consensus_observer_network_configuration(node_config),
+            peers_and_metadata.clone(),
+        ))
+    };
     let mempool_interfaces = create_network_interfaces(
         mempool_network_handles,
         mempool_network_configuration(),
@@ -345,6 +392,7 @@ fn transform_network_handles_into_interfaces(
 
     (
         consensus_interfaces,
+        consensus_observer_interfaces,
         mempool_interfaces,
         peer_monitoring_service_interfaces,
         storage_service_interfaces,
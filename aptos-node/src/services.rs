@@ -4,7 +4,10 @@
 use crate::{bootstrap_api, indexer, mpsc::Receiver, network::ApplicationNetworkInterfaces};
 use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
-use aptos_consensus::network_interface::ConsensusMsg;
+use aptos_consensus::{
+    network_interface::ConsensusMsg, quorum_store_inspection::QuorumStoreInspector,
+    ConsensusObserverMessage, ProposalSimulationService,
+};
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_indexer_grpc_fullnode::runtime::bootstrap as bootstrap_indexer_grpc;
@@ -79,8 +82,13 @@ pub fn start_consensus_runtime(
     db_rw: DbReaderWriter,
     consensus_reconfig_subscription: Option<ReconfigNotificationListener>,
     consensus_network_interfaces: ApplicationNetworkInterfaces<ConsensusMsg>,
+    consensus_observer_network_interfaces: Option<
+        ApplicationNetworkInterfaces<ConsensusObserverMessage>,
+    >,
     consensus_notifier: ConsensusNotifier,
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
+    proposal_simulation_service: ProposalSimulationService,
+    quorum_store_inspector: QuorumStoreInspector,
 ) -> Runtime {
     let instant = Instant::now();
     let consensus_runtime = aptos_consensus::consensus_provider::start_consensus(
@@ -92,11 +100,42 @@ pub fn start_consensus_runtime(
         db_rw,
         consensus_reconfig_subscription
             .expect("Consensus requires a reconfiguration subscription!"),
+        consensus_observer_network_interfaces
+            .map(|interfaces| (interfaces.network_client, interfaces.network_service_events)),
+        proposal_simulation_service,
+        quorum_store_inspector,
     );
     debug!("Consensus started in {} ms", instant.elapsed().as_millis());
     consensus_runtime
 }
 
+/// Starts the consensus observer (on fullnodes) and returns the runtime
+pub fn start_consensus_observer_runtime(
+    node_config: &NodeConfig,
+    db_rw: DbReaderWriter,
+    consensus_reconfig_subscription: Option<ReconfigNotificationListener>,
+    consensus_observer_network_interfaces: ApplicationNetworkInterfaces<ConsensusObserverMessage>,
+    consensus_notifier: ConsensusNotifier,
+    consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
+) -> Runtime {
+    let instant = Instant::now();
+    let consensus_observer_runtime = aptos_consensus::consensus_provider::start_consensus_observer(
+        node_config,
+        consensus_observer_network_interfaces.network_client,
+        consensus_observer_network_interfaces.network_service_events,
+        Arc::new(consensus_notifier),
+        consensus_to_mempool_sender,
+        db_rw,
+        consensus_reconfig_subscription
+            .expect("The consensus observer requires a reconfiguration subscription!"),
+    );
+    debug!(
+        "Consensus observer started in {} ms",
+        instant.elapsed().as_millis()
+    );
+    consensus_observer_runtime
+}
+
 /// Create the mempool runtime and start mempool
 pub fn start_mempool_runtime_and_get_consensus_sender(
     node_config: &mut NodeConfig,
@@ -128,10 +167,18 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
 }
 
 /// Spawns a new thread for the node inspection service
-pub fn start_node_inspection_service(node_config: &NodeConfig) {
+pub fn start_node_inspection_service(
+    node_config: &NodeConfig,
+    proposal_simulation_service: ProposalSimulationService,
+    quorum_store_inspector: QuorumStoreInspector,
+) {
     let node_config = node_config.clone();
     thread::spawn(move || {
-        aptos_inspection_service::inspection_service::start_inspection_service(node_config)
+        aptos_inspection_service::inspection_service::start_inspection_service(
+            node_config,
+            proposal_simulation_service,
+            quorum_store_inspector,
+        )
     });
 }
 
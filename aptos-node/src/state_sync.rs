@@ -51,8 +51,11 @@ pub fn create_event_subscription_service(
         .subscribe_to_reconfigurations()
         .expect("Mempool must subscribe to reconfigurations");
 
-    // Create a reconfiguration subscription for consensus (if this is a validator)
-    let consensus_reconfig_subscription = if node_config.base.role.is_validator() {
+    // Create a reconfiguration subscription for consensus (if this is a validator,
+    // or a fullnode running the consensus observer)
+    let consensus_reconfig_subscription = if node_config.base.role.is_validator()
+        || node_config.consensus_observer.observer_enabled
+    {
         Some(
             event_subscription_service
                 .subscribe_to_reconfigurations()
@@ -178,6 +181,7 @@ fn setup_aptos_data_client(
     // Create the data client and spawn the data poller
     let (aptos_data_client, data_summary_poller) = AptosNetDataClient::new(
         node_config.state_sync.aptos_data_client,
+        node_config.state_sync.peer_selection.clone(),
         node_config.base.clone(),
         TimeService::real(),
         storage_service_client,
@@ -0,0 +1,48 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::network_id::PeerNetworkId;
+use serde::{Deserialize, Serialize};
+
+/// The consensus observer lets fullnodes follow consensus directly: an observer
+/// subscribes to a trusted publisher (a validator or VFN), and executes the ordered
+/// blocks it receives, instead of waiting for state sync to fetch them after commit.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusObserverConfig {
+    pub observer_enabled: bool, // Whether the node follows consensus through a publisher (fullnodes only)
+    pub publisher_enabled: bool, // Whether the node publishes its blocks to subscribed observers
+    pub publisher: Option<PeerNetworkId>, // The trusted publisher the observer subscribes to
+    pub max_network_channel_size: u64, // Max num of pending network messages
+    pub network_request_timeout_ms: u64, // The timeout (ms) of the subscription requests
+    pub progress_check_interval_ms: u64, // The interval (ms) between observer progress checks
+    pub progress_timeout_ms: u64, // The time (ms) without a commit after which the observer stops executing and re-subscribes
+}
+
+impl Default for ConsensusObserverConfig {
+    fn default() -> Self {
+        Self {
+            observer_enabled: false,
+            publisher_enabled: false,
+            publisher: None,
+            max_network_channel_size: 1000,
+            network_request_timeout_ms: 5_000,
+            progress_check_interval_ms: 1_000,
+            progress_timeout_ms: 10_000,
+        }
+    }
+}
+
+impl ConsensusObserverConfig {
+    /// Returns true iff the consensus observer network protocols are required
+    pub fn is_enabled(&self) -> bool {
+        self.observer_enabled || self.publisher_enabled
+    }
+
+    /// The time (ms) without consensus notifications after which state sync stops
+    /// waiting for the observer and resumes syncing on its own. This is longer than the
+    /// progress timeout, so that the observer always stops executing blocks first.
+    pub fn state_sync_fallback_timeout_ms(&self) -> u64 {
+        self.progress_timeout_ms.saturating_mul(2)
+    }
+}
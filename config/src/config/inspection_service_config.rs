@@ -10,6 +10,8 @@ pub struct InspectionServiceConfig {
     pub address: String,
     pub port: u16,
     pub expose_configuration: bool,
+    pub expose_proposal_simulation: bool,
+    pub expose_quorum_store_batches: bool,
     pub expose_system_information: bool,
 }
 
@@ -19,6 +21,8 @@ impl Default for InspectionServiceConfig {
             address: "0.0.0.0".to_string(),
             port: 9101,
             expose_configuration: false,
+            expose_proposal_simulation: false,
+            expose_quorum_store_batches: false,
             expose_system_information: true,
         }
     }
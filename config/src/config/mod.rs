@@ -19,6 +19,8 @@ use thiserror::Error;
 
 mod consensus_config;
 pub use consensus_config::*;
+mod consensus_observer_config;
+pub use consensus_observer_config::*;
 mod quorum_store_config;
 pub use quorum_store_config::*;
 mod error;
@@ -69,6 +71,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub consensus: ConsensusConfig,
     #[serde(default)]
+    pub consensus_observer: ConsensusObserverConfig,
+    #[serde(default)]
     pub execution: ExecutionConfig,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub full_node_networks: Vec<NetworkConfig>,
@@ -296,7 +300,9 @@ impl NodeConfig {
         let mut config = config
             .validate_indexer_configs()?
             .validate_indexer_grpc_configs()?
-            .validate_network_configs()?;
+            .validate_network_configs()?
+            .validate_state_sync_configs()?
+            .validate_safety_rules_configs()?;
         config.set_data_dir(config.data_dir().to_path_buf());
         Ok(config)
     }
@@ -435,6 +441,29 @@ impl NodeConfig {
         Ok(self)
     }
 
+    /// Checks that the `StateSyncConfig` has everything its bootstrapping mode requires
+    fn validate_state_sync_configs(self) -> Result<NodeConfig, Error> {
+        if self.state_sync.state_sync_driver.bootstrapping_mode
+            == BootstrappingMode::RestoreFromBackup
+        {
+            invariant(
+                self.state_sync.backup_storage.is_some(),
+                "RestoreFromBackup bootstrapping requires a state sync backup storage".into(),
+            )?;
+        }
+        Ok(self)
+    }
+
+    /// Checks that consensus pins the key of an authenticated safety rules process
+    fn validate_safety_rules_configs(self) -> Result<NodeConfig, Error> {
+        if let SafetyRulesService::Process(remote_service) = &self.consensus.safety_rules.service {
+            if let Some(authentication) = &remote_service.authentication {
+                authentication.server_public_key()?;
+            }
+        }
+        Ok(self)
+    }
+
     pub fn save<P: AsRef<Path>>(&mut self, output_path: P) -> Result<(), Error> {
         let output_dir = RootPath::new(&output_path);
         self.execution.save(&output_dir)?;
@@ -510,6 +539,10 @@ impl NodeConfig {
             .unwrap_or_else(|e| panic!("Error in {}: {}", path, e))
             .validate_network_configs()
             .unwrap_or_else(|e| panic!("Error in {}: {}", path, e))
+            .validate_state_sync_configs()
+            .unwrap_or_else(|e| panic!("Error in {}: {}", path, e))
+            .validate_safety_rules_configs()
+            .unwrap_or_else(|e| panic!("Error in {}: {}", path, e))
     }
 
     pub fn default_for_public_full_node() -> Self {
@@ -602,6 +635,8 @@ impl RootPath {
 #[cfg(test)]
 mod test {
     use super::*;
+    use aptos_crypto::{PrivateKey, Uniform};
+    use aptos_types::network_address::NetworkAddress;
 
     #[test]
     fn verify_role_type_conversion() {
@@ -634,6 +669,20 @@ mod test {
             .unwrap_or_else(|e| panic!("Error in safety_rules.yaml: {}", e));
     }
 
+    #[test]
+    fn validate_zero_dns_discovery_interval() {
+        let mut config = NodeConfig::default_for_public_full_node();
+        let network = config.full_node_networks.iter_mut().next().unwrap();
+        network.discovery_methods = vec![DiscoveryMethod::Dns(DnsDiscovery {
+            peers: vec![],
+            interval_secs: 0,
+        })];
+        assert!(matches!(
+            config.validate_network_configs(),
+            Err(Error::InvariantViolation(_))
+        ));
+    }
+
     #[test]
     fn validate_invalid_network_id() {
         let mut config = NodeConfig::default_for_public_full_node();
@@ -644,4 +693,47 @@ mod test {
             Err(Error::InvariantViolation(_))
         ));
     }
+
+    #[test]
+    fn validate_restore_from_backup_without_backup_storage() {
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.state_sync.state_sync_driver.bootstrapping_mode =
+            BootstrappingMode::RestoreFromBackup;
+        assert!(matches!(
+            config.clone().validate_state_sync_configs(),
+            Err(Error::InvariantViolation(_))
+        ));
+
+        config.state_sync.backup_storage = Some(BackupStorageConfig::LocalFs(PathBuf::from(
+            "/opt/aptos/backup",
+        )));
+        assert!(config.validate_state_sync_configs().is_ok());
+    }
+
+    #[test]
+    fn validate_safety_rules_server_public_keys() {
+        let mut config = NodeConfig::default_for_validator();
+        let mut rng = StdRng::from_seed([0u8; 32]);
+
+        // Only pinning exactly one safety rules key is accepted
+        for num_keys in 0..3 {
+            let identity_key = x25519::PrivateKey::generate(&mut rng);
+            let peer_public_keys = (0..num_keys)
+                .map(|_| x25519::PrivateKey::generate(&mut rng).public_key())
+                .collect();
+            config.consensus.safety_rules.service = SafetyRulesService::Process(RemoteService {
+                server_address: NetworkAddress::from_str("/ip4/127.0.0.1/tcp/5555").unwrap(),
+                authentication: Some(RemoteServiceAuthentication::new(
+                    identity_key,
+                    peer_public_keys,
+                )),
+            });
+            let result = config.clone().validate_safety_rules_configs();
+            if num_keys == 1 {
+                assert!(result.is_ok());
+            } else {
+                assert!(matches!(result, Err(Error::InvariantViolation(_))));
+            }
+        }
+    }
 }
@@ -55,6 +55,10 @@ pub const INBOUND_TCP_RX_BUFFER_SIZE: u32 = 3 * 1024 * 1024; // 3MB ~6MB/s with
 pub const INBOUND_TCP_TX_BUFFER_SIZE: u32 = 512 * 1024; // 1MB use a bigger spoon
 pub const OUTBOUND_TCP_RX_BUFFER_SIZE: u32 = 3 * 1024 * 1024; // 3MB ~6MB/s with 500ms latency
 pub const OUTBOUND_TCP_TX_BUFFER_SIZE: u32 = 1024 * 1024; // 1MB use a bigger spoon
+pub const MAX_OUTBOUND_QUEUED_MESSAGES: usize = 1024;
+pub const MAX_CONCURRENT_STREAMS: usize = 4; // Streams reassembled at once per peer
+pub const MAX_TRAFFIC_CAPTURE_FILE_SIZE: u64 = 64 * 1024 * 1024; /* 64 MiB */
+pub const MAX_TRAFFIC_CAPTURE_FILES: usize = 8;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -113,6 +117,20 @@ pub struct NetworkConfig {
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
     // The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
+    // Priority classes used to schedule outbound messages to each peer. Classes are served
+    // with weighted fair queuing; protocols not listed in any class fall into the last class.
+    pub outbound_priority_classes: Vec<PriorityClassConfig>,
+    // Maximum number of outbound frames (i.e., messages and stream fragments) queued for
+    // scheduling per priority class of each peer. Messages of a class that is full are dropped.
+    pub max_outbound_queued_messages: usize,
+    // Whether the outbound streams of different priority classes may be interleaved, so that
+    // a large bulk stream doesn't block a streamed consensus message. Peers running an older
+    // version can only reassemble one stream at a time, so this is disabled by default and
+    // should only be enabled once the whole network has been upgraded.
+    pub interleave_outbound_streams: bool,
+    // Records the messages exchanged with peers to disk, for debugging. If not
+    // specified, no traffic is captured.
+    pub traffic_capture: Option<TrafficCaptureConfig>,
 }
 
 impl Default for NetworkConfig {
@@ -149,6 +167,10 @@ impl NetworkConfig {
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
             max_message_size: MAX_MESSAGE_SIZE,
+            outbound_priority_classes: PriorityClassConfig::default_classes(),
+            max_outbound_queued_messages: MAX_OUTBOUND_QUEUED_MESSAGES,
+            interleave_outbound_streams: false,
+            traffic_capture: None,
             inbound_rx_buffer_size_bytes: Some(INBOUND_TCP_RX_BUFFER_SIZE),
             inbound_tx_buffer_size_bytes: Some(INBOUND_TCP_TX_BUFFER_SIZE),
             outbound_rx_buffer_size_bytes: Some(OUTBOUND_TCP_RX_BUFFER_SIZE),
@@ -226,6 +248,27 @@ impl NetworkConfig {
         }
 
         self.prepare_identity();
+        self.verify_discovery_methods()
+    }
+
+    /// Verifies that the periodic discovery methods have a non-zero interval
+    fn verify_discovery_methods(&self) -> Result<(), Error> {
+        for method in std::iter::once(&self.discovery_method).chain(&self.discovery_methods) {
+            let interval_secs = match method {
+                DiscoveryMethod::Dns(dns_discovery) => dns_discovery.interval_secs,
+                DiscoveryMethod::Kubernetes(kubernetes_discovery) => {
+                    kubernetes_discovery.interval_secs
+                },
+                _ => continue,
+            };
+            crate::config::invariant(
+                interval_secs > 0,
+                format!(
+                    "The interval_secs of the {:?} discovery method must be greater than 0",
+                    method
+                ),
+            )?;
+        }
         Ok(())
     }
 
@@ -393,6 +436,8 @@ pub enum DiscoveryMethod {
     Onchain,
     File(FileDiscovery),
     Rest(RestDiscovery),
+    Dns(DnsDiscovery),
+    Kubernetes(KubernetesDiscovery),
     None,
 }
 
@@ -410,6 +455,76 @@ pub struct RestDiscovery {
     pub interval_secs: u64,
 }
 
+/// Discovers peers by periodically resolving DNS records. The records only provide
+/// the addresses of the peers, so their identities must be configured.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DnsDiscovery {
+    pub peers: Vec<DnsPeer>,
+    pub interval_secs: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DnsPeer {
+    pub peer_id: PeerId,
+    pub public_key: x25519::PublicKey,
+    #[serde(default)]
+    pub role: PeerRole,
+    /// The DNS record that resolves to the addresses of the peer
+    pub record: DnsRecord,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsRecord {
+    /// The A/AAAA records of a host, dialed on the given port
+    Host { name: String, port: u16 },
+    /// SRV records (e.g., `_aptos._tcp.fullnode.example.com`), which provide both
+    /// the target hosts and their ports
+    Srv { name: String },
+}
+
+/// Discovers the pods of a Kubernetes StatefulSet through the stable DNS names
+/// given to them by its headless service, i.e.,
+/// `<stateful_set>-<ordinal>.<service>.<namespace>.svc.<cluster_domain>`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct KubernetesDiscovery {
+    pub stateful_set: String,
+    pub service: String,
+    pub namespace: String,
+    #[serde(default = "KubernetesDiscovery::default_cluster_domain")]
+    pub cluster_domain: String,
+    pub port: u16,
+    #[serde(default)]
+    pub role: PeerRole,
+    /// The identities of the pods, by ordinal (i.e., pod `<stateful_set>-<i>` is `peers[i]`)
+    pub peers: Vec<KubernetesPeer>,
+    pub interval_secs: u64,
+}
+
+impl KubernetesDiscovery {
+    fn default_cluster_domain() -> String {
+        "cluster.local".into()
+    }
+
+    /// Returns the DNS name of the pod with the given ordinal
+    pub fn pod_dns_name(&self, ordinal: usize) -> String {
+        format!(
+            "{}-{}.{}.{}.svc.{}",
+            self.stateful_set, ordinal, self.service, self.namespace, self.cluster_domain
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct KubernetesPeer {
+    pub peer_id: PeerId,
+    pub public_key: x25519::PublicKey,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Identity {
@@ -486,6 +601,86 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// A set of application protocols that share an outbound queue and a scheduling weight.
+/// Protocols are referenced by their `ProtocolId` name, e.g., "ConsensusRpcBcs".
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PriorityClassConfig {
+    /// Name of the class, used to label metrics
+    pub name: String,
+    /// Relative share of the outbound bandwidth when all classes are backlogged
+    pub weight: u32,
+    /// The protocols whose messages belong to this class
+    pub protocols: Vec<String>,
+}
+
+impl PriorityClassConfig {
+    pub fn new(name: &str, weight: u32, protocols: &[&str]) -> Self {
+        Self {
+            name: name.into(),
+            weight,
+            protocols: protocols
+                .iter()
+                .map(|protocol| protocol.to_string())
+                .collect(),
+        }
+    }
+
+    /// The default classes ensure that consensus messages are not starved by
+    /// bulk state sync traffic (e.g., large storage service responses).
+    pub fn default_classes() -> Vec<PriorityClassConfig> {
+        vec![
+            PriorityClassConfig::new("consensus", 16, &[
+                "ConsensusRpcBcs",
+                "ConsensusDirectSendBcs",
+                "ConsensusDirectSendJson",
+                "ConsensusRpcJson",
+                "ConsensusRpcCompressed",
+                "ConsensusDirectSendCompressed",
+                "ConsensusObserver",
+                "ConsensusObserverRpc",
+            ]),
+            PriorityClassConfig::new("control", 8, &[
+                "HealthCheckerRpc",
+                "PeerMonitoringServiceRpc",
+                "DiscoveryDirectSend",
+            ]),
+            PriorityClassConfig::new("mempool", 4, &["MempoolDirectSend", "MempoolRpc"]),
+            PriorityClassConfig::new("bulk", 1, &["StorageServiceRpc", "StateSyncDirectSend"]),
+        ]
+    }
+}
+
+/// Captures the (decrypted) messages exchanged with peers into a set of rotating
+/// files, which can be decoded and replayed with the network capture tool.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrafficCaptureConfig {
+    /// The directory in which the capture files are written
+    pub path: PathBuf,
+    /// A new capture file is started once the current one reaches this size
+    pub max_file_size_bytes: u64,
+    /// The maximum number of capture files kept, the oldest files are deleted first
+    pub max_files: usize,
+    /// Only capture the traffic of these peers. If empty, all peers are captured.
+    pub peers: HashSet<PeerId>,
+    /// Only capture the messages of these protocols (by `ProtocolId` name, e.g.,
+    /// "ConsensusRpcBcs"). If empty, all protocols are captured.
+    pub protocols: Vec<String>,
+}
+
+impl Default for TrafficCaptureConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("network_capture"),
+            max_file_size_bytes: MAX_TRAFFIC_CAPTURE_FILE_SIZE,
+            max_files: MAX_TRAFFIC_CAPTURE_FILES,
+            peers: HashSet::new(),
+            protocols: Vec::new(),
+        }
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
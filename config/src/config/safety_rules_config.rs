@@ -3,10 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::{IdentityBlob, LoggerConfig, SecureBackend, WaypointConfig},
+    config::{Error, IdentityBlob, LoggerConfig, SecureBackend, WaypointConfig},
     keys::ConfigKey,
 };
-use aptos_crypto::{bls12381, Uniform};
+use aptos_crypto::{bls12381, x25519, Uniform};
 use aptos_types::{network_address::NetworkAddress, waypoint::Waypoint, PeerId};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
@@ -24,6 +24,9 @@ pub struct SafetyRulesConfig {
     pub test: Option<SafetyRulesTestConfig>,
     // Read/Write/Connect networking operation timeout in milliseconds.
     pub network_timeout_ms: u64,
+    // Timeout in milliseconds of a request to a safety rules process, including all the
+    // reconnection attempts.
+    pub request_timeout_ms: u64,
     pub enable_cached_safety_data: bool,
     pub initial_safety_rules_config: InitialSafetyRulesConfig,
 }
@@ -37,6 +40,8 @@ impl Default for SafetyRulesConfig {
             test: None,
             // Default value of 30 seconds for a timeout
             network_timeout_ms: 30_000,
+            // Default value of 60 seconds for a request timeout
+            request_timeout_ms: 60_000,
             enable_cached_safety_data: true,
             initial_safety_rules_config: InitialSafetyRulesConfig::None,
         }
@@ -108,6 +113,10 @@ pub enum SafetyRulesService {
 #[serde(deny_unknown_fields)]
 pub struct RemoteService {
     pub server_address: NetworkAddress,
+    /// Authenticates and encrypts the connection to the service (the service is unauthenticated
+    /// if this is not set)
+    #[serde(default)]
+    pub authentication: Option<RemoteServiceAuthentication>,
 }
 
 impl RemoteService {
@@ -120,6 +129,39 @@ impl RemoteService {
     }
 }
 
+/// The pinned x25519 keys authenticating the connection between consensus and a safety rules
+/// process
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteServiceAuthentication {
+    /// The static key of this end of the connection
+    pub identity_key: ConfigKey<x25519::PrivateKey>,
+    /// The public keys of the other end of the connection: for consensus, the key of the safety
+    /// rules process, and for the safety rules process, the keys of the clients it accepts
+    pub peer_public_keys: Vec<x25519::PublicKey>,
+}
+
+impl RemoteServiceAuthentication {
+    pub fn new(identity_key: x25519::PrivateKey, peer_public_keys: Vec<x25519::PublicKey>) -> Self {
+        Self {
+            identity_key: ConfigKey::new(identity_key),
+            peer_public_keys,
+        }
+    }
+
+    /// Returns the pinned key of the safety rules process (as seen by consensus). This is
+    /// checked when the node config is loaded.
+    pub fn server_public_key(&self) -> Result<x25519::PublicKey, Error> {
+        match self.peer_public_keys.as_slice() {
+            [server_public_key] => Ok(*server_public_key),
+            peer_public_keys => Err(Error::InvariantViolation(format!(
+                "Consensus must pin exactly one safety rules public key, but found {}",
+                peer_public_keys.len()
+            ))),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SafetyRulesTestConfig {
     pub author: PeerId,
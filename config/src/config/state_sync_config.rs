@@ -2,7 +2,9 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_types::PeerId;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, path::PathBuf};
 
 // The maximum message size per state sync message
 const MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024; /* 4 MiB */
@@ -20,6 +22,8 @@ pub struct StateSyncConfig {
     pub aptos_data_client: AptosDataClientConfig,
     pub state_sync_driver: StateSyncDriverConfig,
     pub storage_service: StorageServiceConfig,
+    pub backup_storage: Option<BackupStorageConfig>, // The backup storage to bootstrap from (if any)
+    pub peer_selection: PeerSelectionConfig, // The peers to prefer (or avoid) for data requests
 }
 
 /// The bootstrapping mode determines how the node will bootstrap to the latest
@@ -30,6 +34,7 @@ pub enum BootstrappingMode {
     DownloadLatestStates, // Downloads the state keys and values (at the latest version)
     ExecuteTransactionsFromGenesis, // Executes transactions (starting at genesis)
     ExecuteOrApplyFromGenesis, // Executes transactions or applies outputs from genesis (whichever is faster)
+    RestoreFromBackup, // Restores the latest state snapshot in the backup storage (at an epoch ending version)
 }
 
 impl BootstrappingMode {
@@ -43,8 +48,28 @@ impl BootstrappingMode {
                 "execute_transactions_from_genesis"
             },
             BootstrappingMode::ExecuteOrApplyFromGenesis => "execute_or_apply_from_genesis",
+            BootstrappingMode::RestoreFromBackup => "restore_from_backup",
         }
     }
+
+    /// Returns true iff the node bootstraps to a state snapshot (instead of
+    /// syncing all transactions from genesis).
+    pub fn is_fast_sync(&self) -> bool {
+        matches!(
+            self,
+            BootstrappingMode::DownloadLatestStates | BootstrappingMode::RestoreFromBackup
+        )
+    }
+}
+
+/// The backup storage from which to bootstrap the node (i.e., when the
+/// bootstrapping mode is `RestoreFromBackup`). Once bootstrapped, the node
+/// continues to sync from its peers.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupStorageConfig {
+    CommandAdapter(PathBuf), // The path to a command adapter config (see the backup-cli)
+    LocalFs(PathBuf),        // The directory of a backup on the local file system
 }
 
 /// The continuous syncing mode determines how the node will stay up-to-date
@@ -81,7 +106,9 @@ pub struct StateSyncDriverConfig {
     pub max_connection_deadline_secs: u64, // The max time (secs) to wait for connections from peers before auto-bootstrapping
     pub max_consecutive_stream_notifications: u64, // The max number of notifications to process per driver loop
     pub max_num_stream_timeouts: u64, // The max number of stream timeouts allowed before termination
-    pub max_pending_data_chunks: u64, // The max number of data chunks pending execution or commit
+    pub max_pending_chunks_to_commit: u64, // The max number of ledger updated chunks pending commit
+    pub max_pending_chunks_to_update_ledger: u64, // The max number of executed chunks pending ledger update
+    pub max_pending_data_chunks: u64,             // The max number of data chunks pending execution
     pub max_stream_wait_time_ms: u64, // The max time (ms) to wait for a data stream notification
     pub num_versions_to_skip_snapshot_sync: u64, // The version lag we'll tolerate before snapshot syncing
 }
@@ -100,6 +127,8 @@ impl Default for StateSyncDriverConfig {
             max_connection_deadline_secs: 10,
             max_consecutive_stream_notifications: 10,
             max_num_stream_timeouts: 12,
+            max_pending_chunks_to_commit: 100,
+            max_pending_chunks_to_update_ledger: 100,
             max_pending_data_chunks: 100,
             max_stream_wait_time_ms: 5000,
             num_versions_to_skip_snapshot_sync: 100_000_000, // At 5k TPS, this allows a node to fail for about 6 hours.
@@ -199,6 +228,15 @@ pub struct AptosDataClientConfig {
     pub use_compression: bool,        // Whether or not to request compression for incoming data
 }
 
+/// The peers that the data client should prefer (or avoid) when selecting
+/// peers for data requests. Peers are identified across all networks.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerSelectionConfig {
+    pub denied_peers: HashSet<PeerId>, // Peers that are never polled or sent data requests
+    pub preferred_peers: HashSet<PeerId>, // Peers that are sent data requests before all others
+}
+
 impl Default for AptosDataClientConfig {
     fn default() -> Self {
         Self {
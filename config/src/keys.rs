@@ -67,3 +67,5 @@ impl<T: PrivateKey + Serialize> PartialEq for ConfigKey<T> {
         bcs::to_bytes(&self).unwrap() == bcs::to_bytes(&other).unwrap()
     }
 }
+
+impl<T: PrivateKey + Serialize> Eq for ConfigKey<T> {}
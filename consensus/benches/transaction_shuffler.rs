@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_consensus::{
+    gas_and_dependency_aware_shuffler::{GasAndDependencyAwareShuffler, PayloadAccessSetEstimator},
+    sender_aware_shuffler::SenderAwareShuffler,
+    transaction_shuffler::TransactionShuffler,
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+use aptos_types::{
+    chain_id::ChainId,
+    transaction::{
+        RawTransaction, Script, SignedTransaction, TransactionArgument, TransactionPayload,
+    },
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use move_core_types::account_address::AccountAddress;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const BLOCK_SIZE: usize = 10_000;
+const NUM_SENDERS: usize = 1_000;
+const NUM_RECIPIENTS: usize = 100;
+const CONFLICT_WINDOW_SIZE: usize = 32;
+
+/// Creates a block of transfers from `NUM_SENDERS` senders with random gas unit prices, each to
+/// one of `NUM_RECIPIENTS` recipients, so that many of the transactions conflict
+fn create_block() -> Vec<SignedTransaction> {
+    let mut rng = StdRng::from_seed([0u8; 32]);
+    let private_key = Ed25519PrivateKey::generate(&mut rng);
+    let public_key = private_key.public_key();
+    let senders: Vec<_> = (0..NUM_SENDERS).map(|_| AccountAddress::random()).collect();
+    let recipients: Vec<_> = (0..NUM_RECIPIENTS)
+        .map(|_| AccountAddress::random())
+        .collect();
+
+    (0..BLOCK_SIZE)
+        .map(|i| {
+            let recipient = recipients[rng.gen_range(0, NUM_RECIPIENTS)];
+            let raw_transaction = RawTransaction::new(
+                senders[i % NUM_SENDERS],
+                (i / NUM_SENDERS) as u64,
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![
+                    TransactionArgument::Address(recipient),
+                ])),
+                0,
+                rng.gen_range(100, 200),
+                0,
+                ChainId::new(10),
+            );
+            SignedTransaction::new(
+                raw_transaction.clone(),
+                public_key.clone(),
+                private_key.sign(&raw_transaction).unwrap(),
+            )
+        })
+        .collect()
+}
+
+/// Consensus shuffles every block twice (when it's executed and when it's committed), so the
+/// cost of the shuffler is on the critical path of the block pipeline
+fn shuffle_block(c: &mut Criterion) {
+    let block = create_block();
+    let shufflers: Vec<(&str, Box<dyn TransactionShuffler>)> = vec![
+        (
+            "sender_aware",
+            Box::new(SenderAwareShuffler::new(CONFLICT_WINDOW_SIZE)),
+        ),
+        (
+            "gas_and_dependency_aware",
+            Box::new(GasAndDependencyAwareShuffler::new(
+                CONFLICT_WINDOW_SIZE,
+                PayloadAccessSetEstimator,
+            )),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("shuffle_block");
+    for (name, shuffler) in shufflers {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || block.clone(),
+                |txns| black_box(shuffler.shuffle(txns)),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    name = transaction_shuffler_benches;
+    config = Criterion::default().sample_size(10);
+    targets = shuffle_block
+);
+criterion_main!(transaction_shuffler_benches);
@@ -244,6 +244,16 @@ impl Block {
         }
     }
 
+    /// Creates a proposal without signing it, for blocks that are executed locally but never
+    /// sent to other validators, e.g. to simulate a proposal. It doesn't pass signature checks.
+    pub fn new_unsigned_proposal_from_block_data(block_data: BlockData) -> Self {
+        Block {
+            id: block_data.hash(),
+            block_data,
+            signature: None,
+        }
+    }
+
     /// Verifies that the proposal and the QC are correctly signed.
     /// If this is the genesis block, we skip these checks.
     pub fn validate_signature(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
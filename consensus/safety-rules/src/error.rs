@@ -56,6 +56,12 @@ pub enum Error {
     WaypointOutOfDate(u64, u64, u64, u64),
     #[error("Invalid Timeout: {0}")]
     InvalidTimeout(String),
+    #[error("Incompatible remote protocol version: received {0}, expected {1}")]
+    IncompatibleProtocolVersion(u32, u32),
+    #[error("Request to the SafetyRules service timed out after {0} ms")]
+    RequestTimeout(u64),
+    #[error("Invalid slashing protection data: {0}")]
+    InvalidSlashingProtectionData(String),
 }
 
 impl From<serde_json::Error> for Error {
@@ -16,13 +16,18 @@ mod safety_rules;
 mod safety_rules_2chain;
 mod safety_rules_manager;
 mod serializer;
+mod slashing_protection;
 mod t_safety_rules;
 mod thread;
 
 pub use crate::{
-    consensus_state::ConsensusState, error::Error,
-    persistent_safety_storage::PersistentSafetyStorage, process::Process,
-    safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
+    consensus_state::ConsensusState,
+    error::Error,
+    persistent_safety_storage::PersistentSafetyStorage,
+    process::Process,
+    safety_rules::SafetyRules,
+    safety_rules_manager::{existing_storage, SafetyRulesManager},
+    slashing_protection::{SlashingProtectionData, SLASHING_PROTECTION_DATA_VERSION},
     t_safety_rules::TSafetyRules,
 };
 
@@ -5,6 +5,7 @@
 use crate::{
     counters,
     logging::{self, LogEntry, LogEvent},
+    slashing_protection::SlashingProtectionData,
     Error,
 };
 use aptos_consensus_types::{common::Author, safety_data::SafetyData};
@@ -160,6 +161,32 @@ impl PersistentSafetyStorage {
         Ok(())
     }
 
+    /// Exports the safety data and waypoint, e.g., to migrate SafetyRules to another host
+    pub fn export_slashing_protection_data(&mut self) -> Result<SlashingProtectionData, Error> {
+        Ok(SlashingProtectionData::new(
+            self.author()?,
+            self.safety_data()?,
+            self.waypoint()?,
+        ))
+    }
+
+    /// Imports the slashing protection data exported by another host of the same validator. The
+    /// data is merged with the current safety data, so that nothing signed by either host can be
+    /// signed again, and the waypoint is only updated if the imported one is newer.
+    pub fn import_slashing_protection_data(
+        &mut self,
+        data: &SlashingProtectionData,
+    ) -> Result<(), Error> {
+        data.verify(self.author()?)?;
+
+        let safety_data = data.merge_safety_data(self.safety_data()?);
+        self.set_safety_data(safety_data)?;
+        if data.waypoint.version() > self.waypoint()?.version() {
+            self.set_waypoint(&data.waypoint)?;
+        }
+        Ok(())
+    }
+
     #[cfg(any(test, feature = "testing"))]
     pub fn internal_store(&mut self) -> &mut Storage {
         &mut self.internal_store
@@ -7,7 +7,7 @@ use crate::{
     remote_service::{self, RemoteService},
     safety_rules_manager,
 };
-use aptos_config::config::{SafetyRulesConfig, SafetyRulesService};
+use aptos_config::config::{RemoteServiceAuthentication, SafetyRulesConfig, SafetyRulesService};
 use std::net::SocketAddr;
 
 pub struct Process {
@@ -29,13 +29,19 @@ impl Process {
                 server_addr,
                 storage,
                 network_timeout: config.network_timeout_ms,
+                authentication: service.authentication.clone(),
             }),
         }
     }
 
     pub fn start(&mut self) {
         let data = self.data.take().expect("Unable to retrieve ProcessData");
-        remote_service::execute(data.storage, data.server_addr, data.network_timeout);
+        remote_service::execute(
+            data.storage,
+            data.server_addr,
+            data.network_timeout,
+            data.authentication,
+        );
     }
 }
 
@@ -44,18 +50,28 @@ struct ProcessData {
     storage: PersistentSafetyStorage,
     // Timeout in Seconds for network operations
     network_timeout: u64,
+    authentication: Option<RemoteServiceAuthentication>,
 }
 
 pub struct ProcessService {
     server_addr: SocketAddr,
     network_timeout_ms: u64,
+    request_timeout_ms: u64,
+    authentication: Option<RemoteServiceAuthentication>,
 }
 
 impl ProcessService {
-    pub fn new(server_addr: SocketAddr, network_timeout: u64) -> Self {
+    pub fn new(
+        server_addr: SocketAddr,
+        network_timeout: u64,
+        request_timeout_ms: u64,
+        authentication: Option<RemoteServiceAuthentication>,
+    ) -> Self {
         Self {
             server_addr,
             network_timeout_ms: network_timeout,
+            request_timeout_ms,
+            authentication,
         }
     }
 }
@@ -68,4 +84,12 @@ impl RemoteService for ProcessService {
     fn network_timeout_ms(&self) -> u64 {
         self.network_timeout_ms
     }
+
+    fn request_timeout_ms(&self) -> Option<u64> {
+        Some(self.request_timeout_ms)
+    }
+
+    fn authentication(&self) -> Option<&RemoteServiceAuthentication> {
+        self.authentication.as_ref()
+    }
 }
@@ -7,18 +7,53 @@ use crate::{
     serializer::{SafetyRulesInput, SerializerClient, SerializerService, TSerializerClient},
     Error, SafetyRules, TSafetyRules,
 };
+use aptos_config::config::RemoteServiceAuthentication;
 use aptos_logger::warn;
 use aptos_secure_net::{NetworkClient, NetworkServer};
-use std::net::SocketAddr;
+use serde::{Deserialize, Serialize};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// The version of the protocol between consensus and a remote SafetyRules service. Services
+/// reject requests of any other version with `Error::IncompatibleProtocolVersion`.
+pub const REMOTE_PROTOCOL_VERSION: u32 = 1;
+
+/// A request to a remote SafetyRules service
+#[derive(Deserialize, Serialize)]
+struct RemoteRequest {
+    version: u32,
+    input: SafetyRulesInput,
+}
+
+/// The version of a request, which can be parsed independently of its input. Requests that
+/// predate the versioning don't have a version, and are treated as version 0.
+#[derive(Deserialize)]
+struct RemoteRequestHeader {
+    #[serde(default)]
+    version: u32,
+}
 
 pub trait RemoteService {
     fn client(&self) -> SerializerClient {
-        let network_client = NetworkClient::new(
-            "safety-rules",
-            self.server_address(),
-            self.network_timeout_ms(),
-        );
-        let service = Box::new(RemoteClient::new(network_client));
+        let network_client = match self.authentication() {
+            Some(authentication) => NetworkClient::new_authenticated(
+                "safety-rules",
+                self.server_address(),
+                self.network_timeout_ms(),
+                authentication.identity_key.private_key(),
+                authentication
+                    .server_public_key()
+                    .expect("The safety rules config must be validated before use"),
+            ),
+            None => NetworkClient::new(
+                "safety-rules",
+                self.server_address(),
+                self.network_timeout_ms(),
+            ),
+        };
+        let service = Box::new(RemoteClient::new(network_client, self.request_timeout_ms()));
         SerializerClient::new_client(service)
     }
 
@@ -26,16 +61,41 @@ pub trait RemoteService {
 
     /// Network Timeout in milliseconds.
     fn network_timeout_ms(&self) -> u64;
+
+    /// Request Timeout in milliseconds, including reconnections. Requests are retried until
+    /// they succeed if there is no timeout.
+    fn request_timeout_ms(&self) -> Option<u64> {
+        None
+    }
+
+    /// The keys authenticating the connection to the service, if any.
+    fn authentication(&self) -> Option<&RemoteServiceAuthentication> {
+        None
+    }
 }
 
-pub fn execute(storage: PersistentSafetyStorage, listen_addr: SocketAddr, network_timeout_ms: u64) {
+pub fn execute(
+    storage: PersistentSafetyStorage,
+    listen_addr: SocketAddr,
+    network_timeout_ms: u64,
+    authentication: Option<RemoteServiceAuthentication>,
+) {
     let mut safety_rules = SafetyRules::new(storage);
     if let Err(e) = safety_rules.consensus_state() {
         warn!("Unable to print consensus state: {}", e);
     }
 
     let mut serializer_service = SerializerService::new(safety_rules);
-    let mut network_server = NetworkServer::new("safety-rules", listen_addr, network_timeout_ms);
+    let mut network_server = match authentication {
+        Some(authentication) => NetworkServer::new_authenticated(
+            "safety-rules",
+            listen_addr,
+            network_timeout_ms,
+            authentication.identity_key.private_key(),
+            authentication.peer_public_keys.into_iter().collect(),
+        ),
+        None => NetworkServer::new("safety-rules", listen_addr, network_timeout_ms),
+    };
 
     loop {
         if let Err(e) = process_one_message(&mut network_server, &mut serializer_service) {
@@ -49,18 +109,34 @@ fn process_one_message(
     serializer_service: &mut SerializerService,
 ) -> Result<(), Error> {
     let request = network_server.read()?;
-    let response = serializer_service.handle_message(request)?;
+    let version = serde_json::from_slice::<RemoteRequestHeader>(&request)
+        .map(|header| header.version)
+        .unwrap_or_default();
+    let response = if version == REMOTE_PROTOCOL_VERSION {
+        let request: RemoteRequest = serde_json::from_slice(&request)?;
+        serializer_service.handle_input(request.input)?
+    } else {
+        // The error deserializes as the result of any request
+        serde_json::to_vec(&Err::<(), _>(Error::IncompatibleProtocolVersion(
+            version,
+            REMOTE_PROTOCOL_VERSION,
+        )))?
+    };
     network_server.write(&response)?;
     Ok(())
 }
 
 struct RemoteClient {
     network_client: NetworkClient,
+    request_timeout_ms: Option<u64>,
 }
 
 impl RemoteClient {
-    pub fn new(network_client: NetworkClient) -> Self {
-        Self { network_client }
+    pub fn new(network_client: NetworkClient, request_timeout_ms: Option<u64>) -> Self {
+        Self {
+            network_client,
+            request_timeout_ms,
+        }
     }
 
     fn process_one_message(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
@@ -71,12 +147,27 @@ impl RemoteClient {
 
 impl TSerializerClient for RemoteClient {
     fn request(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
-        let input_message = serde_json::to_vec(&input)?;
+        let input_message = serde_json::to_vec(&RemoteRequest {
+            version: REMOTE_PROTOCOL_VERSION,
+            input,
+        })?;
+        let deadline = self.request_timeout_ms.map(|timeout_ms| {
+            (
+                Instant::now() + Duration::from_millis(timeout_ms),
+                timeout_ms,
+            )
+        });
         loop {
+            // The network client reconnects on the next attempt after a failure
             match self.process_one_message(&input_message) {
                 Err(err) => warn!("Failed to communicate with SafetyRules service: {}", err),
                 Ok(value) => return Ok(value),
             }
+            if let Some((deadline, timeout_ms)) = deadline {
+                if Instant::now() >= deadline {
+                    return Err(Error::RequestTimeout(timeout_ms));
+                }
+            }
         }
     }
 }
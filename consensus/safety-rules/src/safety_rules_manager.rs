@@ -9,9 +9,11 @@ use crate::{
     remote_service::RemoteService,
     serializer::{SerializerClient, SerializerService},
     thread::ThreadService,
-    SafetyRules, TSafetyRules,
+    Error, SafetyRules, TSafetyRules,
+};
+use aptos_config::config::{
+    InitialSafetyRulesConfig, RemoteServiceAuthentication, SafetyRulesConfig, SafetyRulesService,
 };
-use aptos_config::config::{InitialSafetyRulesConfig, SafetyRulesConfig, SafetyRulesService};
 use aptos_infallible::RwLock;
 use aptos_secure_storage::{KVStorage, Storage};
 use std::{convert::TryInto, net::SocketAddr, sync::Arc};
@@ -74,6 +76,17 @@ pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
     }
 }
 
+/// Opens the (already initialized) safety rules storage of the given config without starting
+/// SafetyRules, e.g., to export or import slashing protection data.
+pub fn existing_storage(config: &SafetyRulesConfig) -> Result<PersistentSafetyStorage, Error> {
+    let internal_storage: Storage = (&config.backend).into();
+    internal_storage.available()?;
+
+    let storage = PersistentSafetyStorage::new(internal_storage, config.enable_cached_safety_data);
+    storage.author()?;
+    Ok(storage)
+}
+
 enum SafetyRulesWrapper {
     Local(Arc<RwLock<SafetyRules>>),
     Process(ProcessService),
@@ -88,7 +101,12 @@ pub struct SafetyRulesManager {
 impl SafetyRulesManager {
     pub fn new(config: &SafetyRulesConfig) -> Self {
         if let SafetyRulesService::Process(conf) = &config.service {
-            return Self::new_process(conf.server_address(), config.network_timeout_ms);
+            return Self::new_process(
+                conf.server_address(),
+                config.network_timeout_ms,
+                config.request_timeout_ms,
+                conf.authentication.clone(),
+            );
         }
 
         let storage = storage(config);
@@ -107,8 +125,14 @@ impl SafetyRulesManager {
         }
     }
 
-    pub fn new_process(server_addr: SocketAddr, timeout_ms: u64) -> Self {
-        let process_service = ProcessService::new(server_addr, timeout_ms);
+    pub fn new_process(
+        server_addr: SocketAddr,
+        timeout_ms: u64,
+        request_timeout_ms: u64,
+        authentication: Option<RemoteServiceAuthentication>,
+    ) -> Self {
+        let process_service =
+            ProcessService::new(server_addr, timeout_ms, request_timeout_ms, authentication);
         Self {
             internal_safety_rules: SafetyRulesWrapper::Process(process_service),
         }
@@ -131,6 +155,23 @@ impl SafetyRulesManager {
         }
     }
 
+    pub fn new_authenticated_thread(
+        storage: PersistentSafetyStorage,
+        timeout_ms: u64,
+        service_authentication: RemoteServiceAuthentication,
+        client_authentication: RemoteServiceAuthentication,
+    ) -> Self {
+        let thread = ThreadService::new_authenticated(
+            storage,
+            timeout_ms,
+            service_authentication,
+            client_authentication,
+        );
+        Self {
+            internal_safety_rules: SafetyRulesWrapper::Thread(thread),
+        }
+    }
+
     pub fn client(&self) -> Box<dyn TSafetyRules + Send + Sync> {
         match &self.internal_safety_rules {
             SafetyRulesWrapper::Local(safety_rules) => {
@@ -42,7 +42,11 @@ impl SerializerService {
 
     pub fn handle_message(&mut self, input_message: Vec<u8>) -> Result<Vec<u8>, Error> {
         let input = serde_json::from_slice(&input_message)?;
+        self.handle_input(input)
+    }
 
+    /// Executes an already deserialized request and returns the serialized result
+    pub fn handle_input(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
         let output = match input {
             SafetyRulesInput::ConsensusState => {
                 serde_json::to_vec(&self.internal.consensus_state())
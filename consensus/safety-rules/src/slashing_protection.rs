@@ -0,0 +1,84 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Slashing protection data is the portable form of the safety data of a validator. Exporting it
+//! from one SafetyRules host and importing it into another allows migrating SafetyRules between
+//! hosts without ever signing a vote or timeout that conflicts with one signed on the old host.
+
+use crate::Error;
+use aptos_consensus_types::{common::Author, safety_data::SafetyData, vote::Vote};
+use aptos_types::waypoint::Waypoint;
+use serde::{Deserialize, Serialize};
+use std::cmp::{max, Ordering};
+
+/// The version of the slashing protection data format
+pub const SLASHING_PROTECTION_DATA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SlashingProtectionData {
+    pub version: u32,
+    pub author: Author,
+    pub safety_data: SafetyData,
+    pub waypoint: Waypoint,
+}
+
+impl SlashingProtectionData {
+    pub fn new(author: Author, safety_data: SafetyData, waypoint: Waypoint) -> Self {
+        Self {
+            version: SLASHING_PROTECTION_DATA_VERSION,
+            author,
+            safety_data,
+            waypoint,
+        }
+    }
+
+    /// Checks that the data can be imported by the validator `author`
+    pub fn verify(&self, author: Author) -> Result<(), Error> {
+        if self.version != SLASHING_PROTECTION_DATA_VERSION {
+            return Err(Error::InvalidSlashingProtectionData(format!(
+                "unsupported version {}, expected {}",
+                self.version, SLASHING_PROTECTION_DATA_VERSION
+            )));
+        }
+        if self.author != author {
+            return Err(Error::InvalidSlashingProtectionData(format!(
+                "exported by {}, but imported by {}",
+                self.author, author
+            )));
+        }
+        Ok(())
+    }
+
+    /// Merges the imported safety data into the current one. The result is at least as
+    /// restrictive as both: the later epoch wins, and within the same epoch every round is the
+    /// highest of the two.
+    pub fn merge_safety_data(&self, current: SafetyData) -> SafetyData {
+        let imported = &self.safety_data;
+        match imported.epoch.cmp(&current.epoch) {
+            Ordering::Greater => imported.clone(),
+            Ordering::Less => current,
+            Ordering::Equal => {
+                let last_vote = match (&current.last_vote, &imported.last_vote) {
+                    (Some(current_vote), Some(imported_vote))
+                        if vote_round(imported_vote) > vote_round(current_vote) =>
+                    {
+                        imported.last_vote.clone()
+                    },
+                    (None, Some(_)) => imported.last_vote.clone(),
+                    _ => current.last_vote,
+                };
+                SafetyData::new(
+                    current.epoch,
+                    max(current.last_voted_round, imported.last_voted_round),
+                    max(current.preferred_round, imported.preferred_round),
+                    max(current.one_chain_round, imported.one_chain_round),
+                    last_vote,
+                )
+            },
+        }
+    }
+}
+
+fn vote_round(vote: &Vote) -> u64 {
+    vote.vote_data().proposed().round()
+}
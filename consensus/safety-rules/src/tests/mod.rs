@@ -6,6 +6,7 @@ mod local;
 mod networking;
 mod safety_rules;
 mod serializer;
+mod slashing_protection;
 mod suite;
 mod thread;
 mod vault;
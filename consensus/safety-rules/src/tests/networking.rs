@@ -2,7 +2,14 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_utils, SafetyRulesManager};
+use crate::{
+    remote_service::{RemoteService, REMOTE_PROTOCOL_VERSION},
+    serializer::SafetyRulesInput,
+    test_utils,
+    thread::ThreadService,
+    ConsensusState, Error, SafetyRulesManager,
+};
+use aptos_secure_net::NetworkClient;
 use aptos_types::validator_signer::ValidatorSigner;
 
 #[test]
@@ -18,3 +25,24 @@ fn test_reconnect() {
     let state1 = safety_rules_manager.client().consensus_state().unwrap();
     assert_eq!(state0, state1);
 }
+
+#[test]
+fn test_incompatible_protocol_version() {
+    let signer = ValidatorSigner::from_int(0);
+    let storage = test_utils::test_storage(&signer);
+    // test value for network timeout, in milliseconds.
+    let network_timeout = 5_000;
+    let thread_service = ThreadService::new(storage, network_timeout);
+
+    // Requests without a version predate the versioned protocol
+    let mut network_client =
+        NetworkClient::new("test", thread_service.server_address(), network_timeout);
+    let request = serde_json::to_vec(&SafetyRulesInput::ConsensusState).unwrap();
+    network_client.write(&request).unwrap();
+    let response = network_client.read().unwrap();
+    let result: Result<ConsensusState, Error> = serde_json::from_slice(&response).unwrap();
+    assert_eq!(
+        result.unwrap_err(),
+        Error::IncompatibleProtocolVersion(0, REMOTE_PROTOCOL_VERSION)
+    );
+}
@@ -0,0 +1,99 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{test_utils, Error, SafetyRules, SlashingProtectionData, TSafetyRules};
+use aptos_consensus_types::{common::Payload, safety_data::SafetyData};
+use aptos_types::{validator_signer::ValidatorSigner, waypoint::Waypoint};
+
+#[test]
+fn test_migrate_between_hosts() {
+    let signer = ValidatorSigner::from_int(0);
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer);
+    let a2 =
+        test_utils::make_proposal_with_parent(Payload::empty(false), round + 2, &a1, None, &signer);
+
+    let mut old_host = SafetyRules::new(test_utils::test_storage(&signer));
+    old_host.initialize(&proof).unwrap();
+    old_host
+        .construct_and_sign_vote_two_chain(&a1, None)
+        .unwrap();
+    let a2_vote = old_host
+        .construct_and_sign_vote_two_chain(&a2, None)
+        .unwrap();
+    let data = old_host
+        .persistent_storage
+        .export_slashing_protection_data()
+        .unwrap();
+
+    let mut storage = test_utils::test_storage(&signer);
+    storage.import_slashing_protection_data(&data).unwrap();
+    let mut new_host = SafetyRules::new(storage);
+    new_host.initialize(&proof).unwrap();
+
+    // The new host resends the last vote of the old host, and refuses to vote for older rounds
+    assert_eq!(
+        new_host
+            .construct_and_sign_vote_two_chain(&a2, None)
+            .unwrap(),
+        a2_vote
+    );
+    assert!(matches!(
+        new_host.construct_and_sign_vote_two_chain(&a1, None),
+        Err(Error::IncorrectLastVotedRound(_, _))
+    ));
+}
+
+#[test]
+fn test_merge_safety_data() {
+    let signer = ValidatorSigner::from_int(0);
+    let data = SlashingProtectionData::new(
+        signer.author(),
+        SafetyData::new(2, 10, 5, 8, None),
+        Waypoint::default(),
+    );
+
+    // Within the same epoch, every round is the highest of the two
+    assert_eq!(
+        data.merge_safety_data(SafetyData::new(2, 12, 3, 9, None)),
+        SafetyData::new(2, 12, 5, 9, None)
+    );
+    // Otherwise, the later epoch wins
+    assert_eq!(
+        data.merge_safety_data(SafetyData::new(1, 20, 20, 20, None)),
+        SafetyData::new(2, 10, 5, 8, None)
+    );
+    assert_eq!(
+        data.merge_safety_data(SafetyData::new(3, 1, 1, 1, None)),
+        SafetyData::new(3, 1, 1, 1, None)
+    );
+}
+
+#[test]
+fn test_invalid_import() {
+    let signer = ValidatorSigner::from_int(0);
+    let mut storage = test_utils::test_storage(&signer);
+    let mut data = SlashingProtectionData::new(
+        ValidatorSigner::from_int(1).author(),
+        storage.safety_data().unwrap(),
+        Waypoint::default(),
+    );
+    assert!(matches!(
+        storage.import_slashing_protection_data(&data),
+        Err(Error::InvalidSlashingProtectionData(_))
+    ));
+
+    data.author = signer.author();
+    data.version += 1;
+    assert!(matches!(
+        storage.import_slashing_protection_data(&data),
+        Err(Error::InvalidSlashingProtectionData(_))
+    ));
+
+    // An older waypoint is ignored
+    let waypoint = storage.waypoint().unwrap();
+    data.version -= 1;
+    storage.import_slashing_protection_data(&data).unwrap();
+    assert_eq!(storage.waypoint().unwrap(), waypoint);
+}
@@ -3,13 +3,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{test_utils, tests::suite, SafetyRulesManager};
+use aptos_config::config::RemoteServiceAuthentication;
+use aptos_crypto::{x25519, Uniform};
 use aptos_types::validator_signer::ValidatorSigner;
+use rand::{rngs::StdRng, SeedableRng};
 
 #[test]
 fn test() {
     suite::run_test_suite(&safety_rules());
 }
 
+#[test]
+fn test_authenticated() {
+    suite::run_test_suite(&authenticated_safety_rules());
+}
+
 fn safety_rules() -> suite::Callback {
     Box::new(move || {
         let signer = ValidatorSigner::from_int(0);
@@ -21,3 +29,29 @@ fn safety_rules() -> suite::Callback {
         (safety_rules, signer)
     })
 }
+
+fn authenticated_safety_rules() -> suite::Callback {
+    Box::new(move || {
+        let signer = ValidatorSigner::from_int(0);
+        let storage = test_utils::test_storage(&signer);
+        let mut rng = StdRng::from_seed([0; 32]);
+        let service_key = x25519::PrivateKey::generate(&mut rng);
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let service_authentication =
+            RemoteServiceAuthentication::new(service_key, vec![client_key.public_key()]);
+        let client_authentication = RemoteServiceAuthentication::new(
+            client_key,
+            vec![service_authentication.identity_key.public_key()],
+        );
+        // Test value for network_timeout, in milliseconds.
+        let network_timeout = 5_000;
+        let safety_rules_manager = SafetyRulesManager::new_authenticated_thread(
+            storage,
+            network_timeout,
+            service_authentication,
+            client_authentication,
+        );
+        let safety_rules = safety_rules_manager.client();
+        (safety_rules, signer)
+    })
+}
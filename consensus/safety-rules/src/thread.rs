@@ -12,7 +12,7 @@ use crate::{
     persistent_safety_storage::PersistentSafetyStorage,
     remote_service::{self, RemoteService},
 };
-use aptos_config::utils;
+use aptos_config::{config::RemoteServiceAuthentication, utils};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     thread::{self, JoinHandle},
@@ -24,20 +24,49 @@ pub struct ThreadService {
     _child: JoinHandle<()>,
     server_addr: SocketAddr,
     network_timeout: u64,
+    client_authentication: Option<RemoteServiceAuthentication>,
 }
 
 impl ThreadService {
     pub fn new(storage: PersistentSafetyStorage, timeout: u64) -> Self {
+        Self::spawn(storage, timeout, None, None)
+    }
+
+    /// Authenticates the connections between the clients and the thread with the given keys of
+    /// the service and of its clients
+    pub fn new_authenticated(
+        storage: PersistentSafetyStorage,
+        timeout: u64,
+        service_authentication: RemoteServiceAuthentication,
+        client_authentication: RemoteServiceAuthentication,
+    ) -> Self {
+        Self::spawn(
+            storage,
+            timeout,
+            Some(service_authentication),
+            Some(client_authentication),
+        )
+    }
+
+    fn spawn(
+        storage: PersistentSafetyStorage,
+        timeout: u64,
+        service_authentication: Option<RemoteServiceAuthentication>,
+        client_authentication: Option<RemoteServiceAuthentication>,
+    ) -> Self {
         let listen_port = utils::get_available_port();
         let listen_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port);
         let server_addr = listen_addr;
 
-        let child = thread::spawn(move || remote_service::execute(storage, listen_addr, timeout));
+        let child = thread::spawn(move || {
+            remote_service::execute(storage, listen_addr, timeout, service_authentication)
+        });
 
         Self {
             _child: child,
             server_addr,
             network_timeout: timeout,
+            client_authentication,
         }
     }
 }
@@ -50,4 +79,8 @@ impl RemoteService for ThreadService {
     fn network_timeout_ms(&self) -> u64 {
         self.network_timeout
     }
+
+    fn authentication(&self) -> Option<&RemoteServiceAuthentication> {
+        self.client_authentication.as_ref()
+    }
 }
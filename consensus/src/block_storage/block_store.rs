@@ -205,6 +205,7 @@ impl BlockStore {
             vec![],                   /* compute_status */
             vec![],                   /* txn_infos */
             vec![],                   /* reconfig_events */
+            0,                        /* gas_used */
         );
 
         let executed_root_block = ExecutedBlock::new(
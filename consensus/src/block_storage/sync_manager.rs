@@ -399,6 +399,8 @@ pub struct BlockRetriever {
     network: NetworkSender,
     preferred_peer: Author,
     validator_addresses: Vec<AccountAddress>,
+    // Picks the peers to retry failed retrievals with
+    rng: StdRng,
 }
 
 impl BlockRetriever {
@@ -411,9 +413,17 @@ impl BlockRetriever {
             network,
             preferred_peer,
             validator_addresses,
+            rng: StdRng::from_entropy(),
         }
     }
 
+    /// Seeds the choice of the peers to retry failed retrievals with, so that it can be
+    /// reproduced (e.g., in simulations)
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
     /// Retrieve n blocks for given block_id from peers
     ///
     /// Returns Result with Vec that if succeeded. This method will
@@ -524,7 +534,7 @@ impl BlockRetriever {
         .await
     }
 
-    fn pick_peer(&self, attempt: u32, peers: &mut Vec<AccountAddress>) -> AccountAddress {
+    fn pick_peer(&mut self, attempt: u32, peers: &mut Vec<AccountAddress>) -> AccountAddress {
         assert!(!peers.is_empty(), "pick_peer on empty peer list");
 
         if attempt == 0 {
@@ -539,7 +549,7 @@ impl BlockRetriever {
             return self.preferred_peer;
         }
 
-        let peer_idx = thread_rng().gen_range(0, peers.len());
+        let peer_idx = self.rng.gen_range(0, peers.len());
         peers.remove(peer_idx)
     }
 }
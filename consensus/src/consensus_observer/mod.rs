@@ -0,0 +1,21 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+/*
+ * Consensus observers let fullnodes execute blocks as soon as they are ordered:
+ *
+ *  ┌───────────────────────┐  1. Subscribe (RPC)   ┌──────────────────────┐
+ *  │ Validator (or VFN)    ◄───────────────────────┤ Fullnode             │
+ *  │                       │                       │                      │
+ *  │ Buffer Manager        │  2. Ordered blocks    │ Consensus Observer   │
+ *  │   └ Consensus         ├───────────────────────►   └ Buffer Manager   │
+ *  │     Publisher         │  3. Commit decisions  │     (no signing)     │
+ *  └───────────────────────┘                       └──────────────────────┘
+ *
+ * Observers that fall behind (or stop making progress) sync to the next commit
+ * decision of their publisher with state sync instead.
+ */
+
+pub mod network;
+pub mod observer;
+pub mod publisher;
@@ -0,0 +1,413 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::types::BatchPayload;
+use anyhow::{bail, ensure};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_consensus_types::{
+    block::Block,
+    common::{DataStatus, Payload, Round},
+    executed_block::ExecutedBlock,
+};
+use aptos_crypto::hash::CryptoHash;
+use aptos_network::{
+    application::interface::NetworkServiceEvents,
+    protocols::network::{Event, RpcError},
+    ProtocolId,
+};
+use aptos_types::{
+    epoch_change::EpochChangeProof, epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
+    transaction::SignedTransaction,
+};
+use bytes::Bytes;
+use futures::{
+    channel::oneshot,
+    future,
+    stream::{select_all, BoxStream, StreamExt},
+};
+use serde::{Deserialize, Serialize};
+
+/// The messages exchanged between consensus publishers and observers
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ConsensusObserverMessage {
+    /// RPC from an observer, to receive the blocks of the publisher
+    Subscribe,
+    /// RPC from an observer, to stop receiving the blocks of the publisher
+    Unsubscribe,
+    /// The response of the publisher to the subscription RPCs
+    SubscriptionAck,
+    /// Blocks that were ordered (and are being executed) by the publisher
+    OrderedBlock(Box<OrderedBlock>),
+    /// The commit decision of the last block of an ordered block
+    CommitDecision(Box<LedgerInfoWithSignatures>),
+    /// RPC from an observer, to receive the epoch changes from `start_epoch` up to
+    /// `end_epoch` (e.g., to verify a commit decision of a later epoch)
+    EpochChangeRequest { start_epoch: u64, end_epoch: u64 },
+    /// The response of the publisher to the epoch change RPCs
+    EpochChangeProof(Box<EpochChangeProof>),
+}
+
+/// A batch of ordered blocks, along with the transactions of their payloads (as observers
+/// can't fetch the quorum store batches themselves) and the proof of their ordering
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OrderedBlock {
+    /// The ordered blocks, from the oldest to the newest
+    pub blocks: Vec<Block>,
+    /// The transactions of the quorum store batches of each block (empty for the
+    /// blocks without quorum store payloads)
+    pub transactions: Vec<Vec<SignedTransaction>>,
+    /// The ordering proof of the last block
+    pub ordered_proof: LedgerInfoWithSignatures,
+}
+
+impl OrderedBlock {
+    /// Creates the ordered block of the given blocks, whose quorum store transactions
+    /// must have been fetched already (i.e., the blocks are being executed)
+    pub fn from_executed_blocks(
+        executed_blocks: &[ExecutedBlock],
+        ordered_proof: LedgerInfoWithSignatures,
+    ) -> anyhow::Result<Self> {
+        let mut blocks = vec![];
+        let mut transactions = vec![];
+        for executed_block in executed_blocks {
+            let block = executed_block.block();
+            transactions.push(match block.payload() {
+                Some(Payload::InQuorumStore(proof_with_data)) => {
+                    match &*proof_with_data.status.lock() {
+                        Some(DataStatus::Cached(txns)) => txns.clone(),
+                        _ => bail!("The transactions of block {} aren't cached", block.id()),
+                    }
+                },
+                _ => vec![],
+            });
+            blocks.push(block.clone());
+        }
+        Ok(Self {
+            blocks,
+            transactions,
+            ordered_proof,
+        })
+    }
+
+    /// The epoch of the blocks
+    pub fn epoch(&self) -> u64 {
+        self.ordered_proof.ledger_info().epoch()
+    }
+
+    /// The round of the last block
+    pub fn round(&self) -> Round {
+        self.ordered_proof.commit_info().round()
+    }
+
+    /// Verifies that the blocks are a chain ending with the block certified by the ordered
+    /// proof, that the proof is signed by the validators of the epoch, and that the
+    /// transactions are those of the quorum store batches of the blocks
+    pub fn verify(&self, epoch_state: &EpochState) -> anyhow::Result<()> {
+        ensure!(!self.blocks.is_empty(), "Ordered block without blocks");
+        ensure!(
+            self.transactions.len() == self.blocks.len(),
+            "Expected the transactions of {} blocks, got {}",
+            self.blocks.len(),
+            self.transactions.len()
+        );
+        ensure!(
+            self.epoch() == epoch_state.epoch,
+            "Ordered block of epoch {}, expected epoch {}",
+            self.epoch(),
+            epoch_state.epoch
+        );
+        for block in &self.blocks {
+            ensure!(
+                block.epoch() == epoch_state.epoch,
+                "Block {} of epoch {}, expected epoch {}",
+                block.id(),
+                block.epoch(),
+                epoch_state.epoch
+            );
+            block.verify_well_formed()?;
+        }
+        for (parent, child) in self.blocks.iter().zip(self.blocks.iter().skip(1)) {
+            ensure!(
+                parent.is_parent_of(child),
+                "Block {} isn't the parent of block {}",
+                parent.id(),
+                child.id()
+            );
+        }
+        let last_block = self.blocks.last().expect("Blocks are not empty");
+        ensure!(
+            self.ordered_proof.commit_info().id() == last_block.id(),
+            "The ordered proof certifies block {}, not the last block {}",
+            self.ordered_proof.commit_info().id(),
+            last_block.id()
+        );
+        self.ordered_proof
+            .verify_signatures(&epoch_state.verifier)?;
+        for (block, transactions) in self.blocks.iter().zip(&self.transactions) {
+            verify_payload_transactions(block, transactions)?;
+        }
+        Ok(())
+    }
+
+    /// Caches the transactions in the quorum store payloads, so that the blocks can be
+    /// executed without fetching the batches
+    pub fn cache_payloads(&self) {
+        for (block, transactions) in self.blocks.iter().zip(&self.transactions) {
+            if let Some(Payload::InQuorumStore(proof_with_data)) = block.payload() {
+                proof_with_data
+                    .status
+                    .lock()
+                    .replace(DataStatus::Cached(transactions.clone()));
+            }
+        }
+    }
+}
+
+/// Verifies that the transactions are those of the (non expired) batches of the block
+fn verify_payload_transactions(
+    block: &Block,
+    transactions: &[SignedTransaction],
+) -> anyhow::Result<()> {
+    match block.payload() {
+        Some(Payload::InQuorumStore(proof_with_data)) => {
+            let mut remaining = transactions;
+            for proof in &proof_with_data.proofs {
+                // expired batches are skipped by execution
+                if block.timestamp_usecs() > proof.expiration() {
+                    continue;
+                }
+                let num_txns = proof.num_txns() as usize;
+                ensure!(
+                    remaining.len() >= num_txns,
+                    "Missing transactions of batch {} in block {}",
+                    proof.digest(),
+                    block.id()
+                );
+                let (batch, rest) = remaining.split_at(num_txns);
+                ensure!(
+                    BatchPayload::new(batch.to_vec()).hash() == *proof.digest(),
+                    "Unexpected transactions for batch {} in block {}",
+                    proof.digest(),
+                    block.id()
+                );
+                remaining = rest;
+            }
+            ensure!(
+                remaining.is_empty(),
+                "{} unexpected transactions in block {}",
+                remaining.len(),
+                block.id()
+            );
+        },
+        _ => ensure!(
+            transactions.is_empty(),
+            "Unexpected transactions for block {}, which has no quorum store payload",
+            block.id()
+        ),
+    }
+    Ok(())
+}
+
+/// A network event of the consensus observer protocols
+pub enum ConsensusObserverNetworkEvent {
+    /// A direct send message (blocks and commit decisions)
+    Message(PeerNetworkId, ConsensusObserverMessage),
+    /// An RPC request (subscriptions and epoch changes), with the channel for its response
+    Request(PeerNetworkId, ConsensusObserverMessage, ResponseSender),
+    /// The connection to a peer was lost
+    LostPeer(PeerNetworkId),
+}
+
+/// Merges the events of all networks into a single stream
+pub fn consensus_observer_network_events(
+    network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+) -> BoxStream<'static, ConsensusObserverNetworkEvent> {
+    let network_events: Vec<_> = network_service_events
+        .into_network_and_events()
+        .into_iter()
+        .map(|(network_id, events)| events.map(move |event| (network_id, event)))
+        .collect();
+    select_all(network_events)
+        .filter_map(|(network_id, event)| future::ready(to_network_event(network_id, event)))
+        .boxed()
+}
+
+fn to_network_event(
+    network_id: NetworkId,
+    event: Event<ConsensusObserverMessage>,
+) -> Option<ConsensusObserverNetworkEvent> {
+    match event {
+        Event::Message(peer_id, message) => Some(ConsensusObserverNetworkEvent::Message(
+            PeerNetworkId::new(network_id, peer_id),
+            message,
+        )),
+        Event::RpcRequest(peer_id, message, protocol_id, response_tx) => {
+            Some(ConsensusObserverNetworkEvent::Request(
+                PeerNetworkId::new(network_id, peer_id),
+                message,
+                ResponseSender::new(protocol_id, response_tx),
+            ))
+        },
+        Event::LostPeer(metadata) => Some(ConsensusObserverNetworkEvent::LostPeer(
+            PeerNetworkId::new(network_id, metadata.remote_peer_id),
+        )),
+        Event::NewPeer(_) => None,
+    }
+}
+
+/// A channel for fulfilling a pending consensus observer RPC request
+pub struct ResponseSender {
+    protocol_id: ProtocolId,
+    response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+}
+
+impl ResponseSender {
+    pub fn new(
+        protocol_id: ProtocolId,
+        response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> Self {
+        Self {
+            protocol_id,
+            response_tx,
+        }
+    }
+
+    pub fn send(self, response: ConsensusObserverMessage) {
+        let result = self
+            .protocol_id
+            .to_bytes(&response)
+            .map(Bytes::from)
+            .map_err(RpcError::Error);
+        let _ = self.response_tx.send(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quorum_store::types::Batch;
+    use aptos_consensus_types::{
+        common::ProofWithData,
+        proof_of_store::{BatchId, ProofOfStore},
+        quorum_cert::QuorumCert,
+    };
+    use aptos_crypto::{
+        ed25519::{Ed25519PrivateKey, Ed25519Signature},
+        HashValue, PrivateKey, Uniform,
+    };
+    use aptos_executor_types::StateComputeResult;
+    use aptos_types::{
+        account_address::AccountAddress,
+        aggregate_signature::AggregateSignature,
+        chain_id::ChainId,
+        ledger_info::LedgerInfo,
+        transaction::{RawTransaction, Script, TransactionPayload},
+        validator_signer::ValidatorSigner,
+    };
+
+    fn create_transactions(num_txns: u64) -> Vec<SignedTransaction> {
+        let public_key = Ed25519PrivateKey::generate_for_testing().public_key();
+        (0..num_txns)
+            .map(|sequence_number| {
+                let raw_transaction = RawTransaction::new(
+                    AccountAddress::random(),
+                    sequence_number,
+                    TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                    0,
+                    0,
+                    0,
+                    ChainId::new(10),
+                );
+                SignedTransaction::new(
+                    raw_transaction,
+                    public_key.clone(),
+                    Ed25519Signature::dummy_signature(),
+                )
+            })
+            .collect()
+    }
+
+    fn create_proof(transactions: &[SignedTransaction], expiration: u64) -> ProofOfStore {
+        let batch = Batch::new(
+            BatchId::new_for_test(0),
+            transactions.to_vec(),
+            1,
+            expiration,
+            AccountAddress::random(),
+        );
+        ProofOfStore::new(batch.batch_info().clone(), AggregateSignature::empty())
+    }
+
+    fn create_block(proofs: Vec<ProofOfStore>, timestamp_usecs: u64) -> Block {
+        Block::new_proposal(
+            Payload::InQuorumStore(ProofWithData::new(proofs)),
+            1,
+            timestamp_usecs,
+            QuorumCert::certificate_for_genesis(),
+            &ValidatorSigner::random(None),
+            vec![],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_payload_transactions() {
+        let first_batch = create_transactions(3);
+        let second_batch = create_transactions(2);
+        let block = create_block(
+            vec![
+                create_proof(&first_batch, 100),
+                create_proof(&second_batch, 100),
+            ],
+            10,
+        );
+        let transactions = [first_batch.clone(), second_batch.clone()].concat();
+        assert!(verify_payload_transactions(&block, &transactions).is_ok());
+
+        // Missing, reordered and additional transactions are rejected
+        assert!(verify_payload_transactions(&block, &transactions[1..]).is_err());
+        let reordered = [second_batch.clone(), first_batch.clone()].concat();
+        assert!(verify_payload_transactions(&block, &reordered).is_err());
+        let additional = [transactions.clone(), create_transactions(1)].concat();
+        assert!(verify_payload_transactions(&block, &additional).is_err());
+    }
+
+    #[test]
+    fn test_verify_payload_transactions_skips_expired_batches() {
+        let expired_batch = create_transactions(2);
+        let batch = create_transactions(2);
+        let block = create_block(
+            vec![create_proof(&expired_batch, 5), create_proof(&batch, 100)],
+            10,
+        );
+        assert!(verify_payload_transactions(&block, &batch).is_ok());
+        let transactions = [expired_batch, batch].concat();
+        assert!(verify_payload_transactions(&block, &transactions).is_err());
+    }
+
+    #[test]
+    fn test_cache_payloads() {
+        let batch = create_transactions(2);
+        let block = create_block(vec![create_proof(&batch, 100)], 10);
+        let ordered_block = OrderedBlock {
+            blocks: vec![block.clone()],
+            transactions: vec![batch.clone()],
+            ordered_proof: LedgerInfoWithSignatures::new(
+                LedgerInfo::new(
+                    block.gen_block_info(HashValue::zero(), 0, None),
+                    HashValue::zero(),
+                ),
+                AggregateSignature::empty(),
+            ),
+        };
+        ordered_block.cache_payloads();
+
+        let executed_blocks = vec![ExecutedBlock::new(block, StateComputeResult::new_dummy())];
+        let republished = OrderedBlock::from_executed_blocks(
+            &executed_blocks,
+            ordered_block.ordered_proof.clone(),
+        )
+        .unwrap();
+        assert_eq!(republished.transactions, vec![batch]);
+    }
+}
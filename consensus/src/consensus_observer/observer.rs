@@ -0,0 +1,736 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        network::{ConsensusObserverMessage, ConsensusObserverNetworkEvent, OrderedBlock},
+        publisher::ConsensusPublisher,
+    },
+    experimental::{
+        buffer_manager::{OrderedBlocks, ResetRequest},
+        decoupled_execution_utils::prepare_observer_phases_and_buffer_manager,
+    },
+    payload_manager::PayloadManager,
+    round_manager::VerifiedEvent,
+    state_replication::StateComputer,
+    transaction_shuffler::create_transaction_shuffler,
+};
+use anyhow::{anyhow, bail, ensure};
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_consensus_types::{
+    block::Block, common::Author, executed_block::ExecutedBlock,
+    experimental::commit_decision::CommitDecision,
+};
+use aptos_event_notifications::ReconfigNotificationListener;
+use aptos_executor_types::StateComputeResult;
+use aptos_logger::prelude::*;
+use aptos_network::application::interface::{NetworkClient, NetworkClientInterface};
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    account_address::AccountAddress,
+    block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{OnChainExecutionConfig, ValidatorSet},
+};
+use futures::{
+    channel::{
+        mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
+    stream::BoxStream,
+    StreamExt,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The channels to the execution pipeline of the current epoch
+struct ExecutionPipeline {
+    block_tx: UnboundedSender<OrderedBlocks>,
+    reset_tx: UnboundedSender<ResetRequest>,
+    commit_msg_tx: aptos_channel::Sender<AccountAddress, VerifiedEvent>,
+}
+
+impl ExecutionPipeline {
+    /// Drops all the blocks of the pipeline (and stops it if `stop` is set)
+    async fn reset(&self, stop: bool) {
+        let (tx, rx) = oneshot::channel();
+        if self
+            .reset_tx
+            .unbounded_send(ResetRequest { tx, stop })
+            .is_ok()
+        {
+            let _ = rx.await;
+        }
+    }
+}
+
+/// Follows consensus through the blocks and commit decisions of a publisher: ordered
+/// blocks are executed as soon as they are received, and committed once their commit
+/// decision arrives. If the observer misses blocks or stops making progress, it syncs
+/// to the next commit decision of the publisher with state sync instead.
+pub struct ConsensusObserver {
+    author: Author,
+    config: ConsensusObserverConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    // Handles the subscriptions of our own observers (e.g., on VFNs)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    execution_proxy: Arc<dyn StateComputer>,
+    db_reader: Arc<dyn DbReader>,
+    reconfig_events: ReconfigNotificationListener,
+
+    // The publisher we're subscribed to
+    subscription: Option<PeerNetworkId>,
+    epoch_state: Option<Arc<EpochState>>,
+    execution_pipeline: Option<ExecutionPipeline>,
+    // The latest committed ledger info
+    root: LedgerInfoWithSignatures,
+    // The last block sent to the execution pipeline (or the root block)
+    last_ordered_block: BlockInfo,
+    // Whether ordered blocks are ignored until we sync to a commit decision
+    sync_required: bool,
+    last_commit_time: Instant,
+    // The ledger infos committed by the execution pipeline
+    commit_tx: UnboundedSender<LedgerInfoWithSignatures>,
+    commit_rx: Option<UnboundedReceiver<LedgerInfoWithSignatures>>,
+}
+
+impl ConsensusObserver {
+    pub fn new(
+        author: Author,
+        config: ConsensusObserverConfig,
+        network_client: NetworkClient<ConsensusObserverMessage>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        execution_proxy: Arc<dyn StateComputer>,
+        db_reader: Arc<dyn DbReader>,
+        reconfig_events: ReconfigNotificationListener,
+    ) -> Self {
+        let root = db_reader
+            .get_latest_ledger_info()
+            .expect("Failed to read the latest ledger info");
+        let last_ordered_block = root_block_info(&root);
+        let (commit_tx, commit_rx) = unbounded();
+        Self {
+            author,
+            config,
+            network_client,
+            consensus_publisher,
+            execution_proxy,
+            db_reader,
+            reconfig_events,
+            subscription: None,
+            epoch_state: None,
+            execution_pipeline: None,
+            root,
+            last_ordered_block,
+            // We don't know whether the blocks of the publisher extend our ledger yet
+            sync_required: true,
+            last_commit_time: Instant::now(),
+            commit_tx,
+            commit_rx: Some(commit_rx),
+        }
+    }
+
+    pub async fn start(
+        mut self,
+        mut network_events: BoxStream<'static, ConsensusObserverNetworkEvent>,
+    ) {
+        let mut commit_rx = self.commit_rx.take().expect("The observer started twice");
+        let mut progress_check_interval = tokio::time::interval(Duration::from_millis(
+            self.config.progress_check_interval_ms,
+        ));
+
+        self.await_new_epoch().await;
+        self.subscribe().await;
+        info!("Consensus observer starts.");
+        loop {
+            tokio::select! {
+                Some(event) = network_events.next() => {
+                    self.process_network_event(event).await;
+                },
+                Some(ledger_info) = commit_rx.next() => {
+                    self.process_commit(ledger_info).await;
+                },
+                _ = progress_check_interval.tick() => {
+                    self.check_progress().await;
+                },
+                else => break,
+            }
+        }
+        info!("Consensus observer stops.");
+    }
+
+    /// Subscribes to the configured publisher
+    async fn subscribe(&mut self) {
+        let publisher = match self.config.publisher {
+            Some(publisher) => publisher,
+            None => {
+                warn!("The consensus observer has no publisher to subscribe to");
+                return;
+            },
+        };
+        let timeout = Duration::from_millis(self.config.network_request_timeout_ms);
+        match self
+            .network_client
+            .send_to_peer_rpc(ConsensusObserverMessage::Subscribe, timeout, publisher)
+            .await
+        {
+            Ok(ConsensusObserverMessage::SubscriptionAck) => {
+                info!("Subscribed to consensus publisher {}", publisher);
+                self.subscription = Some(publisher);
+            },
+            Ok(response) => warn!(
+                "Unexpected subscription response from {}: {:?}",
+                publisher, response
+            ),
+            Err(error) => warn!("Failed to subscribe to {}: {:?}", publisher, error),
+        }
+    }
+
+    async fn process_network_event(&mut self, event: ConsensusObserverNetworkEvent) {
+        match event {
+            ConsensusObserverNetworkEvent::Message(peer, message) => {
+                if self.subscription != Some(peer) {
+                    debug!("Ignoring consensus observer message from {}", peer);
+                    return;
+                }
+                match message {
+                    ConsensusObserverMessage::OrderedBlock(ordered_block) => {
+                        self.process_ordered_block(*ordered_block)
+                    },
+                    ConsensusObserverMessage::CommitDecision(commit_proof) => {
+                        self.process_commit_decision(*commit_proof).await
+                    },
+                    message => warn!(
+                        "Unexpected consensus observer message from {}: {:?}",
+                        peer, message
+                    ),
+                }
+            },
+            ConsensusObserverNetworkEvent::Request(peer, request, response_sender) => {
+                match &self.consensus_publisher {
+                    Some(consensus_publisher) => {
+                        consensus_publisher.handle_request(peer, request, response_sender)
+                    },
+                    None => debug!("Ignoring consensus observer request from {}", peer),
+                }
+            },
+            ConsensusObserverNetworkEvent::LostPeer(peer) => {
+                if let Some(consensus_publisher) = &self.consensus_publisher {
+                    consensus_publisher.remove_subscriber(&peer);
+                }
+                if self.subscription == Some(peer) {
+                    warn!("Lost the connection to consensus publisher {}", peer);
+                    self.subscription = None;
+                }
+            },
+        }
+    }
+
+    fn process_ordered_block(&mut self, ordered_block: OrderedBlock) {
+        if self.sync_required {
+            return;
+        }
+        let epoch_state = match &self.epoch_state {
+            Some(epoch_state) if epoch_state.epoch == ordered_block.epoch() => epoch_state.clone(),
+            _ => return,
+        };
+        if ordered_block.epoch() == self.last_ordered_block.epoch()
+            && ordered_block.round() <= self.last_ordered_block.round()
+        {
+            return;
+        }
+        if let Err(error) = ordered_block.verify(&epoch_state) {
+            warn!(
+                "Invalid ordered block {}: {:?}",
+                ordered_block.ordered_proof.commit_info(),
+                error
+            );
+            return;
+        }
+        if ordered_block.blocks[0].parent_id() != self.last_ordered_block.id() {
+            warn!(
+                "Ordered block {} doesn't extend block {}, waiting for a commit decision to sync to",
+                ordered_block.ordered_proof.commit_info(),
+                self.last_ordered_block
+            );
+            self.sync_required = true;
+            return;
+        }
+
+        ordered_block.cache_payloads();
+        self.last_ordered_block = ordered_block.ordered_proof.commit_info().clone();
+        let commit_tx = self.commit_tx.clone();
+        let ordered_blocks = OrderedBlocks {
+            ordered_blocks: ordered_block
+                .blocks
+                .into_iter()
+                .map(|block| ExecutedBlock::new(block, StateComputeResult::new_dummy()))
+                .collect(),
+            ordered_proof: ordered_block.ordered_proof,
+            callback: Box::new(move |_, ledger_info| {
+                let _ = commit_tx.unbounded_send(ledger_info);
+            }),
+        };
+        if let Some(execution_pipeline) = &self.execution_pipeline {
+            let _ = execution_pipeline.block_tx.unbounded_send(ordered_blocks);
+        }
+    }
+
+    async fn process_commit_decision(&mut self, commit_proof: LedgerInfoWithSignatures) {
+        let epoch_state = match &self.epoch_state {
+            Some(epoch_state) => epoch_state.clone(),
+            None => return,
+        };
+        let commit_info = commit_proof.commit_info();
+        // Commit decisions of later epochs can only be verified with the epoch changes
+        // leading to their epoch
+        let epoch_change_proof = if commit_info.epoch() > epoch_state.epoch
+            && !is_stale_commit_decision(&self.root, &commit_proof)
+        {
+            match self
+                .fetch_epoch_change_proof(epoch_state.epoch, commit_info.epoch())
+                .await
+            {
+                Ok(epoch_change_proof) => Some(epoch_change_proof),
+                Err(error) => {
+                    warn!(
+                        "Failed to fetch the epoch changes for commit decision {}: {:?}",
+                        commit_info, error
+                    );
+                    return;
+                },
+            }
+        } else {
+            None
+        };
+        match verify_commit_decision(
+            &self.root,
+            &epoch_state,
+            &commit_proof,
+            epoch_change_proof.as_ref(),
+        ) {
+            Ok(true) => (),
+            Ok(false) => return,
+            Err(error) => {
+                warn!("Invalid commit decision {}: {:?}", commit_info, error);
+                return;
+            },
+        }
+        if !self.sync_required
+            && commit_info.epoch() == self.last_ordered_block.epoch()
+            && commit_info.round() <= self.last_ordered_block.round()
+        {
+            if let Some(execution_pipeline) = &self.execution_pipeline {
+                let commit_decision = CommitDecision::new(commit_proof);
+                if let Err(error) = execution_pipeline.commit_msg_tx.push(
+                    self.author,
+                    VerifiedEvent::CommitDecision(Box::new(commit_decision)),
+                ) {
+                    warn!("Failed to forward commit decision: {:?}", error);
+                }
+            }
+            return;
+        }
+        // We're missing the blocks of the commit decision (or of its epoch), so we sync to it
+        self.sync_to_commit_decision(commit_proof).await;
+    }
+
+    /// Fetches the epoch changes from `start_epoch` up to `end_epoch` from the publisher
+    async fn fetch_epoch_change_proof(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> anyhow::Result<EpochChangeProof> {
+        let publisher = self
+            .subscription
+            .ok_or_else(|| anyhow!("Not subscribed to a consensus publisher"))?;
+        let timeout = Duration::from_millis(self.config.network_request_timeout_ms);
+        let request = ConsensusObserverMessage::EpochChangeRequest {
+            start_epoch,
+            end_epoch,
+        };
+        match self
+            .network_client
+            .send_to_peer_rpc(request, timeout, publisher)
+            .await?
+        {
+            ConsensusObserverMessage::EpochChangeProof(epoch_change_proof) => {
+                Ok(*epoch_change_proof)
+            },
+            response => bail!("Unexpected epoch change response: {:?}", response),
+        }
+    }
+
+    async fn sync_to_commit_decision(&mut self, commit_proof: LedgerInfoWithSignatures) {
+        info!(
+            "Consensus observer syncs to commit decision {}",
+            commit_proof.commit_info()
+        );
+        if let Some(execution_pipeline) = &self.execution_pipeline {
+            execution_pipeline.reset(false).await;
+        }
+        if let Err(error) = self.execution_proxy.sync_to(commit_proof.clone()).await {
+            warn!(
+                "Failed to sync to commit decision {}: {:?}",
+                commit_proof.commit_info(),
+                error
+            );
+            self.sync_required = true;
+            return;
+        }
+        self.sync_required = false;
+        self.last_commit_time = Instant::now();
+        self.root = self
+            .db_reader
+            .get_latest_ledger_info()
+            .expect("Failed to read the latest ledger info");
+        self.last_ordered_block = root_block_info(&self.root);
+        let epoch = self
+            .epoch_state
+            .as_ref()
+            .map(|epoch_state| epoch_state.epoch);
+        if epoch != Some(self.root.ledger_info().next_block_epoch()) {
+            self.end_epoch().await;
+            self.await_new_epoch().await;
+        }
+    }
+
+    /// Processes the ledger infos committed by the execution pipeline
+    async fn process_commit(&mut self, ledger_info: LedgerInfoWithSignatures) {
+        let commit_info = ledger_info.commit_info();
+        if (commit_info.epoch(), commit_info.round())
+            <= (
+                self.root.commit_info().epoch(),
+                self.root.commit_info().round(),
+            )
+        {
+            return;
+        }
+        self.root = ledger_info;
+        self.last_commit_time = Instant::now();
+        if self.root.ledger_info().ends_epoch() {
+            self.end_epoch().await;
+            self.await_new_epoch().await;
+        }
+    }
+
+    async fn check_progress(&mut self) {
+        if self.last_commit_time.elapsed() < Duration::from_millis(self.config.progress_timeout_ms)
+        {
+            if self.subscription.is_none() {
+                self.subscribe().await;
+            }
+            return;
+        }
+        if !self.sync_required {
+            warn!(
+                "The consensus observer made no progress for {}ms, waiting for a commit decision to sync to",
+                self.config.progress_timeout_ms
+            );
+            self.sync_required = true;
+            if let Some(execution_pipeline) = &self.execution_pipeline {
+                execution_pipeline.reset(false).await;
+            }
+        }
+        // The publisher may have dropped our subscription (e.g., after restarting)
+        self.subscribe().await;
+    }
+
+    async fn end_epoch(&mut self) {
+        if let Some(execution_pipeline) = self.execution_pipeline.take() {
+            execution_pipeline.reset(true).await;
+        }
+        self.execution_proxy.end_epoch();
+        self.epoch_state = None;
+    }
+
+    /// Waits for the reconfig notification of the epoch following the root, and starts
+    /// the execution pipeline of the epoch
+    async fn await_new_epoch(&mut self) {
+        let epoch = self.root.ledger_info().next_block_epoch();
+        let payload = loop {
+            let reconfig_notification = self
+                .reconfig_events
+                .next()
+                .await
+                .expect("Reconfig sender dropped, unable to start new epoch");
+            if reconfig_notification.on_chain_configs.epoch() >= epoch {
+                break reconfig_notification.on_chain_configs;
+            }
+        };
+        let validator_set: ValidatorSet = payload
+            .get()
+            .expect("failed to get ValidatorSet from payload");
+        let epoch_state = Arc::new(EpochState {
+            epoch: payload.epoch(),
+            verifier: (&validator_set).into(),
+        });
+        let onchain_execution_config: OnChainExecutionConfig =
+            payload.get().unwrap_or_else(|error| {
+                error!("Failed to read on-chain execution config {}", error);
+                OnChainExecutionConfig::default()
+            });
+        self.execution_proxy.new_epoch(
+            &epoch_state,
+            Arc::new(PayloadManager::ConsensusObserver),
+            create_transaction_shuffler(onchain_execution_config.transaction_shuffler_type()),
+        );
+
+        let (block_tx, block_rx) = unbounded();
+        let (reset_tx, reset_rx) = unbounded();
+        let (commit_msg_tx, commit_msg_rx) = aptos_channel::new(
+            QueueStyle::FIFO,
+            self.config.max_network_channel_size as usize,
+            None,
+        );
+        let (execution_phase, persisting_phase, buffer_manager) =
+            prepare_observer_phases_and_buffer_manager(
+                self.author,
+                self.execution_proxy.clone(),
+                commit_msg_rx,
+                self.execution_proxy.clone(),
+                block_rx,
+                reset_rx,
+                epoch_state.verifier.clone(),
+                self.consensus_publisher.clone(),
+            );
+        tokio::spawn(execution_phase.start());
+        tokio::spawn(persisting_phase.start());
+        tokio::spawn(buffer_manager.start());
+
+        info!("Consensus observer starts epoch {}", epoch_state.epoch);
+        self.last_ordered_block = root_block_info(&self.root);
+        self.execution_pipeline = Some(ExecutionPipeline {
+            block_tx,
+            reset_tx,
+            commit_msg_tx,
+        });
+        self.epoch_state = Some(epoch_state);
+    }
+}
+
+/// Returns true iff the commit decision doesn't go beyond the root
+fn is_stale_commit_decision(
+    root: &LedgerInfoWithSignatures,
+    commit_proof: &LedgerInfoWithSignatures,
+) -> bool {
+    let commit_info = commit_proof.commit_info();
+    (commit_info.epoch(), commit_info.round())
+        <= (root.commit_info().epoch(), root.commit_info().round())
+}
+
+/// Verifies the commit decision against the validators of its epoch, and returns whether
+/// the observer should process it (i.e., it's neither stale nor of a previous epoch). The
+/// validators of a later epoch are only trusted through the epoch change proof from the
+/// current epoch, so commit decisions of later epochs are rejected without one.
+fn verify_commit_decision(
+    root: &LedgerInfoWithSignatures,
+    epoch_state: &EpochState,
+    commit_proof: &LedgerInfoWithSignatures,
+    epoch_change_proof: Option<&EpochChangeProof>,
+) -> anyhow::Result<bool> {
+    let epoch = commit_proof.commit_info().epoch();
+    if is_stale_commit_decision(root, commit_proof) || epoch < epoch_state.epoch {
+        return Ok(false);
+    }
+    if epoch == epoch_state.epoch {
+        commit_proof.verify_signatures(&epoch_state.verifier)?;
+        return Ok(true);
+    }
+    let epoch_change_proof = epoch_change_proof
+        .ok_or_else(|| anyhow!("Missing the epoch changes to epoch {}", epoch))?;
+    let next_epoch_state = epoch_change_proof
+        .verify(epoch_state)?
+        .ledger_info()
+        .next_epoch_state()
+        .ok_or_else(|| anyhow!("The epoch change proof doesn't end an epoch"))?;
+    ensure!(
+        next_epoch_state.epoch == epoch,
+        "The epoch change proof leads to epoch {}, expected epoch {}",
+        next_epoch_state.epoch,
+        epoch
+    );
+    commit_proof.verify_signatures(&next_epoch_state.verifier)?;
+    Ok(true)
+}
+
+/// The block extended by the next ordered blocks: the committed block, or the genesis
+/// block of the next epoch if the committed block ends the epoch
+fn root_block_info(root: &LedgerInfoWithSignatures) -> BlockInfo {
+    let ledger_info = root.ledger_info();
+    if ledger_info.ends_epoch() {
+        Block::make_genesis_block_from_ledger_info(ledger_info).gen_block_info(
+            ledger_info.transaction_accumulator_hash(),
+            ledger_info.version(),
+            None,
+        )
+    } else {
+        ledger_info.commit_info().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::PartialSignatures,
+        ledger_info::LedgerInfo,
+        validator_signer::ValidatorSigner,
+        validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
+    };
+
+    /// Creates the validators of an epoch, whose keys are derived from the seed
+    fn create_epoch_state(epoch: u64, seed: u8) -> (Vec<ValidatorSigner>, EpochState) {
+        let signers: Vec<_> = (0..4)
+            .map(|index| ValidatorSigner::random([seed * 4 + index; 32]))
+            .collect();
+        let validator_infos = signers
+            .iter()
+            .map(|signer| ValidatorConsensusInfo::new(signer.author(), signer.public_key(), 1))
+            .collect();
+        (signers, EpochState {
+            epoch,
+            verifier: ValidatorVerifier::new(validator_infos),
+        })
+    }
+
+    fn create_ledger_info(
+        epoch: u64,
+        round: u64,
+        next_epoch_state: Option<EpochState>,
+    ) -> LedgerInfo {
+        LedgerInfo::new(
+            BlockInfo::new(
+                epoch,
+                round,
+                HashValue::zero(),
+                HashValue::zero(),
+                round,
+                0,
+                next_epoch_state,
+            ),
+            HashValue::zero(),
+        )
+    }
+
+    fn sign_ledger_info(
+        signers: &[ValidatorSigner],
+        epoch_state: &EpochState,
+        ledger_info: LedgerInfo,
+    ) -> LedgerInfoWithSignatures {
+        let partial_signatures = PartialSignatures::new(
+            signers
+                .iter()
+                .map(|signer| (signer.author(), signer.sign(&ledger_info).unwrap()))
+                .collect(),
+        );
+        let signatures = epoch_state
+            .verifier
+            .aggregate_signatures(&partial_signatures)
+            .unwrap();
+        LedgerInfoWithSignatures::new(ledger_info, signatures)
+    }
+
+    #[test]
+    fn test_verify_commit_decision() {
+        let (signers, epoch_state) = create_epoch_state(2, 0);
+        let root = sign_ledger_info(&signers, &epoch_state, create_ledger_info(2, 5, None));
+        let commit_proof =
+            sign_ledger_info(&signers, &epoch_state, create_ledger_info(2, 10, None));
+        assert!(verify_commit_decision(&root, &epoch_state, &commit_proof, None).unwrap());
+
+        // Commit decisions that aren't signed by the validators of the epoch are rejected
+        let (other_signers, other_epoch_state) = create_epoch_state(2, 1);
+        let unverified = sign_ledger_info(
+            &other_signers,
+            &other_epoch_state,
+            create_ledger_info(2, 10, None),
+        );
+        assert!(verify_commit_decision(&root, &epoch_state, &unverified, None).is_err());
+
+        // Stale commit decisions and those of previous epochs are ignored
+        for (epoch, round) in [(2, 5), (2, 3), (1, 20)] {
+            let stale = sign_ledger_info(
+                &signers,
+                &epoch_state,
+                create_ledger_info(epoch, round, None),
+            );
+            assert!(!verify_commit_decision(&root, &epoch_state, &stale, None).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_verify_future_epoch_commit_decision() {
+        let (signers, epoch_state) = create_epoch_state(2, 0);
+        let (next_signers, next_epoch_state) = create_epoch_state(3, 1);
+        let root = sign_ledger_info(&signers, &epoch_state, create_ledger_info(2, 5, None));
+        let commit_proof = sign_ledger_info(
+            &next_signers,
+            &next_epoch_state,
+            create_ledger_info(3, 1, None),
+        );
+
+        // Commit decisions of later epochs are rejected without the epoch changes
+        assert!(verify_commit_decision(&root, &epoch_state, &commit_proof, None).is_err());
+
+        // The epoch change must be signed by the validators of the current epoch
+        let epoch_change = sign_ledger_info(
+            &signers,
+            &epoch_state,
+            create_ledger_info(2, 8, Some(next_epoch_state.clone())),
+        );
+        let epoch_change_proof = EpochChangeProof::new(vec![epoch_change], false);
+        assert!(verify_commit_decision(
+            &root,
+            &epoch_state,
+            &commit_proof,
+            Some(&epoch_change_proof)
+        )
+        .unwrap());
+        let (other_signers, other_epoch_state) = create_epoch_state(2, 2);
+        let forged_epoch_change = sign_ledger_info(
+            &other_signers,
+            &other_epoch_state,
+            create_ledger_info(2, 8, Some(next_epoch_state.clone())),
+        );
+        let forged_epoch_change_proof = EpochChangeProof::new(vec![forged_epoch_change], false);
+        assert!(verify_commit_decision(
+            &root,
+            &epoch_state,
+            &commit_proof,
+            Some(&forged_epoch_change_proof)
+        )
+        .is_err());
+
+        // The commit decision must be signed by the validators of its epoch
+        let unverified = sign_ledger_info(&signers, &epoch_state, create_ledger_info(3, 1, None));
+        assert!(verify_commit_decision(
+            &root,
+            &epoch_state,
+            &unverified,
+            Some(&epoch_change_proof)
+        )
+        .is_err());
+
+        // The epoch changes must lead to the epoch of the commit decision
+        let (later_signers, later_epoch_state) = create_epoch_state(4, 3);
+        let later_commit_proof = sign_ledger_info(
+            &later_signers,
+            &later_epoch_state,
+            create_ledger_info(4, 1, None),
+        );
+        assert!(verify_commit_decision(
+            &root,
+            &epoch_state,
+            &later_commit_proof,
+            Some(&epoch_change_proof)
+        )
+        .is_err());
+    }
+}
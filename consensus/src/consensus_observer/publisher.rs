@@ -0,0 +1,151 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::network::{
+    ConsensusObserverMessage, ConsensusObserverNetworkEvent, OrderedBlock, ResponseSender,
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_consensus_types::executed_block::ExecutedBlock;
+use aptos_infallible::RwLock;
+use aptos_logger::prelude::*;
+use aptos_network::application::interface::{NetworkClient, NetworkClientInterface};
+use aptos_storage_interface::DbReader;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use futures::{stream::BoxStream, StreamExt};
+use std::{collections::HashSet, sync::Arc};
+
+/// Publishes the blocks ordered and committed by this node to the subscribed
+/// consensus observers
+pub struct ConsensusPublisher {
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    // Serves the epoch changes requested by the observers
+    db_reader: Arc<dyn DbReader>,
+    subscribers: RwLock<HashSet<PeerNetworkId>>,
+}
+
+impl ConsensusPublisher {
+    pub fn new(
+        network_client: NetworkClient<ConsensusObserverMessage>,
+        db_reader: Arc<dyn DbReader>,
+    ) -> Self {
+        Self {
+            network_client,
+            db_reader,
+            subscribers: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Handles the requests of the observers, until the network stops
+    pub async fn start(
+        self: std::sync::Arc<Self>,
+        mut network_events: BoxStream<'static, ConsensusObserverNetworkEvent>,
+    ) {
+        while let Some(event) = network_events.next().await {
+            match event {
+                ConsensusObserverNetworkEvent::Request(peer, request, response_sender) => {
+                    self.handle_request(peer, request, response_sender)
+                },
+                ConsensusObserverNetworkEvent::LostPeer(peer) => self.remove_subscriber(&peer),
+                ConsensusObserverNetworkEvent::Message(peer, message) => {
+                    warn!(
+                        "Unexpected consensus observer message from {}: {:?}",
+                        peer, message
+                    );
+                },
+            }
+        }
+        info!("Consensus publisher stops.");
+    }
+
+    /// Adds or removes the subscription of the peer, or sends it the epoch changes it
+    /// requested
+    pub fn handle_request(
+        &self,
+        peer: PeerNetworkId,
+        request: ConsensusObserverMessage,
+        response_sender: ResponseSender,
+    ) {
+        match request {
+            ConsensusObserverMessage::Subscribe => {
+                if self.subscribers.write().insert(peer) {
+                    info!("New consensus observer subscriber: {}", peer);
+                }
+            },
+            ConsensusObserverMessage::Unsubscribe => {
+                if self.subscribers.write().remove(&peer) {
+                    info!("Consensus observer {} unsubscribed", peer);
+                }
+            },
+            ConsensusObserverMessage::EpochChangeRequest {
+                start_epoch,
+                end_epoch,
+            } => {
+                match self
+                    .db_reader
+                    .get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+                {
+                    Ok(epoch_change_proof) => response_sender.send(
+                        ConsensusObserverMessage::EpochChangeProof(Box::new(epoch_change_proof)),
+                    ),
+                    Err(error) => warn!(
+                        "Failed to read the epoch changes from {} to {} requested by {}: {:?}",
+                        start_epoch, end_epoch, peer, error
+                    ),
+                }
+                return;
+            },
+            request => {
+                warn!(
+                    "Unexpected consensus observer request from {}: {:?}",
+                    peer, request
+                );
+                return;
+            },
+        }
+        response_sender.send(ConsensusObserverMessage::SubscriptionAck);
+    }
+
+    /// Drops the subscription of the peer (e.g., because it disconnected)
+    pub fn remove_subscriber(&self, peer: &PeerNetworkId) {
+        if self.subscribers.write().remove(peer) {
+            info!("Dropped the consensus observer subscription of {}", peer);
+        }
+    }
+
+    fn subscribers(&self) -> Vec<PeerNetworkId> {
+        self.subscribers.read().iter().copied().collect()
+    }
+
+    /// Sends the message to all subscribers. Delivery isn't guaranteed: observers that
+    /// miss messages fall back to syncing to the next commit decision.
+    pub fn publish(&self, message: ConsensusObserverMessage) {
+        let subscribers = self.subscribers();
+        if subscribers.is_empty() {
+            return;
+        }
+        if let Err(error) = self.network_client.send_to_peers(message, &subscribers) {
+            warn!("Failed to publish consensus observer message: {:?}", error);
+        }
+    }
+
+    /// Publishes blocks that are being executed, along with their transactions
+    pub fn publish_ordered_block(
+        &self,
+        executed_blocks: &[ExecutedBlock],
+        ordered_proof: &LedgerInfoWithSignatures,
+    ) {
+        if self.subscribers.read().is_empty() {
+            return;
+        }
+        match OrderedBlock::from_executed_blocks(executed_blocks, ordered_proof.clone()) {
+            Ok(ordered_block) => self.publish(ConsensusObserverMessage::OrderedBlock(Box::new(
+                ordered_block,
+            ))),
+            Err(error) => warn!(
+                "Failed to publish ordered block {}: {:?}",
+                ordered_proof.commit_info(),
+                error
+            ),
+        }
+    }
+}
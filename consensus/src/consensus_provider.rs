@@ -3,12 +3,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::{
+        network::{consensus_observer_network_events, ConsensusObserverMessage},
+        observer::ConsensusObserver,
+        publisher::ConsensusPublisher,
+    },
     counters,
     epoch_manager::EpochManager,
+    liveness::proposal_simulator::ProposalSimulationService,
     network::NetworkTask,
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     persistent_liveness_storage::StorageWriteProxy,
-    quorum_store::quorum_store_db::QuorumStoreDB,
+    quorum_store::{inspection::QuorumStoreInspector, quorum_store_db::QuorumStoreDB},
     state_computer::ExecutionProxy,
     txn_notifier::MempoolNotifier,
     util::time_service::ClockTimeService,
@@ -22,7 +28,7 @@ use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_network::application::interface::{NetworkClient, NetworkServiceEvents};
 use aptos_storage_interface::DbReaderWriter;
-use aptos_types::transaction::Transaction;
+use aptos_types::{account_address::AccountAddress, transaction::Transaction};
 use aptos_vm::AptosVM;
 use futures::channel::mpsc;
 use std::sync::Arc;
@@ -37,16 +43,28 @@ pub fn start_consensus(
     consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener,
+    consensus_observer_network: Option<(
+        NetworkClient<ConsensusObserverMessage>,
+        NetworkServiceEvents<ConsensusObserverMessage>,
+    )>,
+    proposal_simulation_service: ProposalSimulationService,
+    quorum_store_inspector: QuorumStoreInspector,
 ) -> Runtime {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
-    let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
+    let db_reader = aptos_db.reader.clone();
+    let storage = Arc::new(StorageWriteProxy::new(node_config, db_reader.clone()));
     let quorum_store_db = Arc::new(QuorumStoreDB::new(node_config.storage.dir()));
+    quorum_store_inspector.set_db(quorum_store_db.clone());
 
     let txn_notifier = Arc::new(MempoolNotifier::new(
         consensus_to_mempool_sender.clone(),
         node_config.consensus.mempool_executed_txn_timeout_ms,
     ));
 
+    // Proposals are simulated with their own executor, as they are never committed
+    proposal_simulation_service.set_executor(Arc::new(BlockExecutor::<AptosVM, Transaction>::new(
+        aptos_db.clone(),
+    )));
     let state_computer = Arc::new(ExecutionProxy::new(
         Arc::new(BlockExecutor::<AptosVM, Transaction>::new(aptos_db)),
         txn_notifier,
@@ -62,6 +80,20 @@ pub fn start_consensus(
 
     let consensus_network_client = ConsensusNetworkClient::new(network_client);
     let bounded_executor = BoundedExecutor::new(8, runtime.handle().clone());
+
+    // Publish the blocks to the consensus observers (if enabled)
+    let consensus_publisher = consensus_observer_network
+        .filter(|_| node_config.consensus_observer.publisher_enabled)
+        .map(|(network_client, network_service_events)| {
+            let consensus_publisher = Arc::new(ConsensusPublisher::new(network_client, db_reader));
+            runtime.spawn(
+                consensus_publisher
+                    .clone()
+                    .start(consensus_observer_network_events(network_service_events)),
+            );
+            consensus_publisher
+        });
+
     let epoch_mgr = EpochManager::new(
         node_config,
         time_service,
@@ -74,6 +106,9 @@ pub fn start_consensus(
         quorum_store_db,
         reconfig_events,
         bounded_executor,
+        consensus_publisher,
+        proposal_simulation_service,
+        quorum_store_inspector,
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_service_events, self_receiver);
@@ -84,3 +119,52 @@ pub fn start_consensus(
     debug!("Consensus started.");
     runtime
 }
+
+/// Helper function to start the consensus observer of a fullnode and return the runtime
+pub fn start_consensus_observer(
+    node_config: &NodeConfig,
+    network_client: NetworkClient<ConsensusObserverMessage>,
+    network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
+    aptos_db: DbReaderWriter,
+    reconfig_events: ReconfigNotificationListener,
+) -> Runtime {
+    let runtime = aptos_runtimes::spawn_named_runtime("observer".into(), None);
+
+    let txn_notifier = Arc::new(MempoolNotifier::new(
+        consensus_to_mempool_sender,
+        node_config.consensus.mempool_executed_txn_timeout_ms,
+    ));
+
+    let db_reader = aptos_db.reader.clone();
+    let execution_proxy = Arc::new(ExecutionProxy::new(
+        Arc::new(BlockExecutor::<AptosVM, Transaction>::new(aptos_db)),
+        txn_notifier,
+        state_sync_notifier,
+        runtime.handle(),
+    ));
+
+    // Fullnodes can publish the blocks they observe to their own observers
+    let consensus_publisher = node_config.consensus_observer.publisher_enabled.then(|| {
+        Arc::new(ConsensusPublisher::new(
+            network_client.clone(),
+            db_reader.clone(),
+        ))
+    });
+
+    let consensus_observer = ConsensusObserver::new(
+        node_config.peer_id().unwrap_or(AccountAddress::ZERO),
+        node_config.consensus_observer.clone(),
+        network_client,
+        consensus_publisher,
+        execution_proxy,
+        db_reader,
+        reconfig_events,
+    );
+    runtime
+        .spawn(consensus_observer.start(consensus_observer_network_events(network_service_events)));
+
+    debug!("Consensus observer started.");
+    runtime
+}
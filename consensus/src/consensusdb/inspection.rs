@@ -0,0 +1,235 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensusdb::ConsensusDB,
+    persistent_liveness_storage::{LedgerRecoveryData, RecoveryData},
+};
+use anyhow::Result;
+use aptos_consensus_types::{
+    block::Block, common::Author, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
+};
+use aptos_crypto::HashValue;
+use aptos_types::{block_info::Round, ledger_info::LedgerInfoWithSignatures};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+
+/// Summary of a block persisted in the consensus db.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockSummary {
+    /// Id of the block
+    pub id: HashValue,
+    /// Epoch of the block
+    pub epoch: u64,
+    /// Round of the block
+    pub round: Round,
+    /// Id of the parent block, certified by the quorum cert embedded in the block
+    pub parent_id: HashValue,
+    /// Proposer of the block, none for genesis and nil blocks
+    pub author: Option<Author>,
+    /// Timestamp of the block, in microseconds
+    pub timestamp_usecs: u64,
+    /// Number of transactions, or of proofs of store with the quorum store, in the block
+    pub payload_size: usize,
+}
+
+impl From<&Block> for BlockSummary {
+    fn from(block: &Block) -> Self {
+        Self {
+            id: block.id(),
+            epoch: block.epoch(),
+            round: block.round(),
+            parent_id: block.parent_id(),
+            author: block.author(),
+            timestamp_usecs: block.timestamp_usecs(),
+            payload_size: block.payload_size(),
+        }
+    }
+}
+
+/// Summary of a quorum cert persisted in the consensus db.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct QuorumCertSummary {
+    /// Id of the certified block
+    pub certified_block_id: HashValue,
+    /// Epoch of the certified block
+    pub epoch: u64,
+    /// Round of the certified block
+    pub round: Round,
+    /// Id of the block committed by the quorum cert, zero if none
+    pub commit_block_id: HashValue,
+    /// Round of the block committed by the quorum cert
+    pub commit_round: Round,
+}
+
+impl From<&QuorumCert> for QuorumCertSummary {
+    fn from(qc: &QuorumCert) -> Self {
+        Self {
+            certified_block_id: qc.certified_block().id(),
+            epoch: qc.certified_block().epoch(),
+            round: qc.certified_block().round(),
+            commit_block_id: qc.commit_info().id(),
+            commit_round: qc.commit_info().round(),
+        }
+    }
+}
+
+/// Content of the consensus db.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConsensusDbDump {
+    /// Persisted blocks, ordered by epoch and round
+    pub blocks: Vec<BlockSummary>,
+    /// Persisted quorum certs, ordered by epoch and round of the certified block
+    pub quorum_certs: Vec<QuorumCertSummary>,
+    /// Last vote sent by the validator
+    pub last_vote: Option<Vote>,
+    /// Highest timeout certificate known by the validator
+    pub highest_2chain_timeout_certificate: Option<TwoChainTimeoutCertificate>,
+}
+
+/// Result of validating the consensus db against the latest committed ledger info.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ValidationReport {
+    /// The committed root the node would recover from, none if it can't be found
+    pub root: Option<BlockSummary>,
+    /// Inconsistencies found in the persisted blocks and quorum certs
+    pub issues: Vec<String>,
+    /// Blocks and quorum certs the node prunes on recovery, as they don't descend from the root
+    pub blocks_to_prune: Vec<HashValue>,
+}
+
+type ConsensusData = (
+    Option<Vote>,
+    Option<TwoChainTimeoutCertificate>,
+    Vec<Block>,
+    Vec<QuorumCert>,
+);
+
+fn read_data(db: &ConsensusDB) -> Result<ConsensusData> {
+    let (last_vote, highest_2chain_timeout_certificate, mut blocks, mut quorum_certs) =
+        db.get_data()?;
+    let last_vote = last_vote.map(|bytes| bcs::from_bytes(&bytes)).transpose()?;
+    let highest_2chain_timeout_certificate = highest_2chain_timeout_certificate
+        .map(|bytes| bcs::from_bytes(&bytes))
+        .transpose()?;
+    blocks.sort_by_key(|block| (block.epoch(), block.round()));
+    quorum_certs.sort_by_key(|qc| (qc.certified_block().epoch(), qc.certified_block().round()));
+    Ok((
+        last_vote,
+        highest_2chain_timeout_certificate,
+        blocks,
+        quorum_certs,
+    ))
+}
+
+/// Reads the content of the consensus db in `db_root_path`
+pub fn dump(db_root_path: &Path) -> Result<ConsensusDbDump> {
+    let (last_vote, highest_2chain_timeout_certificate, blocks, quorum_certs) =
+        read_data(&ConsensusDB::open(db_root_path, true)?)?;
+    Ok(ConsensusDbDump {
+        blocks: blocks.iter().map(BlockSummary::from).collect(),
+        quorum_certs: quorum_certs.iter().map(QuorumCertSummary::from).collect(),
+        last_vote,
+        highest_2chain_timeout_certificate,
+    })
+}
+
+/// Finds the root and the blocks to prune the same way the node does on recovery
+fn find_blocks_to_prune(
+    committed_ledger_info: LedgerInfoWithSignatures,
+    mut blocks: Vec<Block>,
+    mut quorum_certs: Vec<QuorumCert>,
+) -> Result<(Block, Vec<HashValue>)> {
+    let root =
+        LedgerRecoveryData::new(committed_ledger_info).find_root(&mut blocks, &mut quorum_certs)?;
+    let blocks_to_prune =
+        RecoveryData::find_blocks_to_prune(root.0.id(), &mut blocks, &mut quorum_certs);
+    Ok((*root.0, blocks_to_prune))
+}
+
+/// Checks that the blocks and quorum certs in the consensus db in `db_root_path` chain correctly,
+/// and that the node can find its root given the latest committed ledger info of the ledger db.
+pub fn validate(
+    db_root_path: &Path,
+    committed_ledger_info: LedgerInfoWithSignatures,
+) -> Result<ValidationReport> {
+    let (_, _, blocks, quorum_certs) = read_data(&ConsensusDB::open(db_root_path, true)?)?;
+    let mut issues = vec![];
+    let (root, blocks_to_prune) =
+        match find_blocks_to_prune(committed_ledger_info, blocks.clone(), quorum_certs.clone()) {
+            Ok((root, blocks_to_prune)) => (Some(BlockSummary::from(&root)), blocks_to_prune),
+            Err(error) => {
+                issues.push(format!("Unable to find the root: {:#}", error));
+                (None, vec![])
+            },
+        };
+
+    let blocks_by_id: HashMap<_, _> = blocks.iter().map(|block| (block.id(), block)).collect();
+    for block in &blocks {
+        if block.is_genesis_block() {
+            continue;
+        }
+        if let Err(error) = block.verify_well_formed() {
+            issues.push(format!("Block {} is not well formed: {}", block, error));
+        }
+        if let Some(parent) = blocks_by_id.get(&block.parent_id()) {
+            let certified_block = block.quorum_cert().certified_block();
+            if (parent.epoch(), parent.round())
+                != (certified_block.epoch(), certified_block.round())
+            {
+                issues.push(format!(
+                    "Block {} certifies its parent at epoch {} round {}, but the parent is {}",
+                    block,
+                    certified_block.epoch(),
+                    certified_block.round(),
+                    parent
+                ));
+            }
+        }
+    }
+    for qc in &quorum_certs {
+        let certified_block = qc.certified_block();
+        match blocks_by_id.get(&certified_block.id()) {
+            Some(block) => {
+                if (block.epoch(), block.round())
+                    != (certified_block.epoch(), certified_block.round())
+                {
+                    issues.push(format!(
+                        "Quorum cert {} doesn't match the certified block {}",
+                        qc, block
+                    ));
+                }
+            },
+            // The genesis block of an epoch is not persisted, only its quorum cert
+            None if root.as_ref().map(|root| root.id) == Some(certified_block.id()) => (),
+            None => issues.push(format!("Quorum cert {} certifies a missing block", qc)),
+        }
+    }
+
+    Ok(ValidationReport {
+        root,
+        issues,
+        blocks_to_prune,
+    })
+}
+
+/// Deletes the blocks and quorum certs that don't descend from the root, i.e., that are below the
+/// committed root or on a fork of it, from the consensus db in `db_root_path`, and returns their
+/// ids. These are the ones the node prunes on recovery. The node has to be stopped, as the db can
+/// only be opened for writing once.
+pub fn prune_blocks_below_root(
+    db_root_path: &Path,
+    committed_ledger_info: LedgerInfoWithSignatures,
+    dry_run: bool,
+) -> Result<Vec<HashValue>> {
+    let db = ConsensusDB::open(db_root_path, dry_run)?;
+    let (_, _, blocks, quorum_certs) = read_data(&db)?;
+    let (_, mut blocks_to_prune) =
+        find_blocks_to_prune(committed_ledger_info, blocks, quorum_certs)?;
+    blocks_to_prune.sort();
+    if !dry_run && !blocks_to_prune.is_empty() {
+        db.delete_blocks_and_quorum_certificates(blocks_to_prune.clone())?;
+    }
+    Ok(blocks_to_prune)
+}
@@ -0,0 +1,159 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensusdb::{
+    inspection::{dump, prune_blocks_below_root, validate},
+    ConsensusDB,
+};
+use aptos_consensus_types::{
+    block::{
+        block_test_utils::{
+            certificate_for_genesis, gen_test_certificate, placeholder_certificate_for_block,
+        },
+        Block,
+    },
+    common::Payload,
+    quorum_cert::QuorumCert,
+};
+use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+};
+
+fn committed_ledger_info() -> LedgerInfoWithSignatures {
+    LedgerInfoWithSignatures::new(LedgerInfo::mock_genesis(None), AggregateSignature::empty())
+}
+
+/// Saves a chain of two blocks on top of the genesis root, and a block whose parent is unknown
+fn save_blocks(tmp_dir: &TempPath, signer: &ValidatorSigner) -> (Vec<Block>, Block) {
+    let db = ConsensusDB::new(tmp_dir);
+    let block_1 = Block::new_proposal(
+        Payload::empty(false),
+        1,
+        1,
+        certificate_for_genesis(),
+        signer,
+        vec![],
+    )
+    .unwrap();
+    let block_1_qc = gen_test_certificate(
+        &[signer.clone()],
+        block_1.gen_block_info(HashValue::zero(), 0, None),
+        block_1.quorum_cert().certified_block().clone(),
+        None,
+    );
+    let block_2 = Block::new_proposal(
+        Payload::empty(false),
+        2,
+        2,
+        block_1_qc.clone(),
+        signer,
+        vec![],
+    )
+    .unwrap();
+    let orphan_block = Block::new_proposal(
+        Payload::empty(false),
+        6,
+        10,
+        placeholder_certificate_for_block(
+            &[signer.clone()],
+            HashValue::random(),
+            5,
+            HashValue::random(),
+            4,
+        ),
+        signer,
+        vec![],
+    )
+    .unwrap();
+
+    db.save_blocks_and_quorum_certificates(
+        vec![block_1.clone(), block_2.clone(), orphan_block.clone()],
+        vec![certificate_for_genesis(), block_1_qc],
+    )
+    .unwrap();
+    (vec![block_1, block_2], orphan_block)
+}
+
+fn save_quorum_cert(tmp_dir: &TempPath, qc: QuorumCert) {
+    ConsensusDB::new(tmp_dir)
+        .save_blocks_and_quorum_certificates(vec![], vec![qc])
+        .unwrap();
+}
+
+#[test]
+fn test_dump() {
+    let tmp_dir = TempPath::new();
+    let signer = ValidatorSigner::random(None);
+    let (blocks, orphan_block) = save_blocks(&tmp_dir, &signer);
+
+    let dump = dump(tmp_dir.path()).unwrap();
+    assert_eq!(
+        dump.blocks.iter().map(|b| b.id).collect::<Vec<_>>(),
+        vec![blocks[0].id(), blocks[1].id(), orphan_block.id()]
+    );
+    assert_eq!(dump.blocks[1].parent_id, blocks[0].id());
+    assert_eq!(dump.blocks[1].author, Some(signer.author()));
+    assert_eq!(dump.quorum_certs.len(), 2);
+    assert_eq!(dump.quorum_certs[1].certified_block_id, blocks[0].id());
+    assert!(dump.last_vote.is_none());
+}
+
+#[test]
+fn test_validate() {
+    let tmp_dir = TempPath::new();
+    let signer = ValidatorSigner::random(None);
+    let (blocks, orphan_block) = save_blocks(&tmp_dir, &signer);
+
+    let report = validate(tmp_dir.path(), committed_ledger_info()).unwrap();
+    assert_eq!(
+        report.root.unwrap().id,
+        blocks[0].quorum_cert().certified_block().id()
+    );
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+    assert_eq!(report.blocks_to_prune, vec![orphan_block.id()]);
+
+    // A quorum cert for a block which isn't persisted
+    save_quorum_cert(
+        &tmp_dir,
+        gen_test_certificate(
+            &[signer],
+            BlockInfo::random(3),
+            blocks[1].gen_block_info(HashValue::zero(), 0, None),
+            None,
+        ),
+    );
+    let report = validate(tmp_dir.path(), committed_ledger_info()).unwrap();
+    assert_eq!(report.issues.len(), 1);
+}
+
+#[test]
+fn test_prune_blocks_below_root() {
+    let tmp_dir = TempPath::new();
+    let signer = ValidatorSigner::random(None);
+    let (blocks, orphan_block) = save_blocks(&tmp_dir, &signer);
+
+    let pruned = prune_blocks_below_root(tmp_dir.path(), committed_ledger_info(), true).unwrap();
+    assert_eq!(pruned, vec![orphan_block.id()]);
+    assert_eq!(dump(tmp_dir.path()).unwrap().blocks.len(), 3);
+
+    let pruned = prune_blocks_below_root(tmp_dir.path(), committed_ledger_info(), false).unwrap();
+    assert_eq!(pruned, vec![orphan_block.id()]);
+    assert_eq!(
+        dump(tmp_dir.path())
+            .unwrap()
+            .blocks
+            .iter()
+            .map(|b| b.id)
+            .collect::<Vec<_>>(),
+        vec![blocks[0].id(), blocks[1].id()]
+    );
+    assert!(validate(tmp_dir.path(), committed_ledger_info())
+        .unwrap()
+        .blocks_to_prune
+        .is_empty());
+}
@@ -4,6 +4,10 @@
 
 #[cfg(test)]
 mod consensusdb_test;
+/// Inspection and offline repair of the consensus db.
+pub mod inspection;
+#[cfg(test)]
+mod inspection_test;
 mod schema;
 
 use crate::{
@@ -71,6 +75,24 @@ impl ConsensusDB {
         Self { db }
     }
 
+    /// Opens an existing db, e.g., to inspect it offline. Opening it for writing fails
+    /// while the node holds it open.
+    pub fn open<P: AsRef<Path>>(db_root_path: P, readonly: bool) -> Result<Self> {
+        let column_families = vec![
+            /* UNUSED CF = */ DEFAULT_COLUMN_FAMILY_NAME,
+            BLOCK_CF_NAME,
+            QC_CF_NAME,
+            SINGLE_ENTRY_CF_NAME,
+        ];
+        let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
+        let db = if readonly {
+            DB::open_cf_readonly(&Options::default(), path, "consensus", column_families)?
+        } else {
+            DB::open(path, "consensus", column_families, &Options::default())?
+        };
+        Ok(Self { db })
+    }
+
     pub fn get_data(
         &self,
     ) -> Result<(
@@ -7,6 +7,7 @@ use crate::{
         tracing::{observe_block, BlockStage},
         BlockStore,
     },
+    consensus_observer::publisher::ConsensusPublisher,
     counters,
     error::{error_kind, DbError},
     experimental::{
@@ -21,6 +22,7 @@ use crate::{
             ProposerAndVoterHeuristic, ReputationHeuristic,
         },
         proposal_generator::{ChainHealthBackoffConfig, ProposalGenerator},
+        proposal_simulator::{ProposalSimulationService, ProposalSimulator},
         proposer_election::ProposerElection,
         rotating_proposer_election::{choose_leader, RotatingProposer},
         round_proposer_election::RoundProposer,
@@ -37,6 +39,7 @@ use crate::{
     payload_client::QuorumStoreClient,
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
     quorum_store::{
+        inspection::QuorumStoreInspector,
         quorum_store_builder::{DirectMempoolInnerBuilder, InnerBuilder, QuorumStoreBuilder},
         quorum_store_coordinator::CoordinatorCommand,
         quorum_store_db::QuorumStoreStorage,
@@ -135,6 +138,12 @@ pub struct EpochManager {
     batch_retrieval_tx:
         Option<aptos_channel::Sender<AccountAddress, IncomingBatchRetrievalRequest>>,
     bounded_executor: BoundedExecutor,
+    // publishes the blocks of this node to the consensus observers
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    // provided with the proposal simulator of each epoch
+    proposal_simulation_service: ProposalSimulationService,
+    // provided with the proof manager of each epoch
+    quorum_store_inspector: QuorumStoreInspector,
 }
 
 impl EpochManager {
@@ -150,6 +159,9 @@ impl EpochManager {
         quorum_store_storage: Arc<dyn QuorumStoreStorage>,
         reconfig_events: ReconfigNotificationListener,
         bounded_executor: BoundedExecutor,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        proposal_simulation_service: ProposalSimulationService,
+        quorum_store_inspector: QuorumStoreInspector,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -180,6 +192,9 @@ impl EpochManager {
             quorum_store_storage,
             batch_retrieval_tx: None,
             bounded_executor,
+            consensus_publisher,
+            proposal_simulation_service,
+            quorum_store_inspector,
         }
     }
 
@@ -510,6 +525,7 @@ impl EpochManager {
                 block_rx,
                 reset_rx,
                 verifier,
+                self.consensus_publisher.clone(),
             );
 
         tokio::spawn(execution_phase.start());
@@ -659,6 +675,7 @@ impl EpochManager {
                 epoch_state.verifier.clone(),
                 self.config.safety_rules.backend.clone(),
                 self.quorum_store_storage.clone(),
+                self.quorum_store_inspector.clone(),
             ))
         } else {
             info!("Building DirectMempool");
@@ -674,17 +691,17 @@ impl EpochManager {
             create_transaction_shuffler(onchain_execution_config.transaction_shuffler_type());
         self.quorum_store_msg_tx = quorum_store_msg_tx;
 
-        let payload_client = QuorumStoreClient::new(
+        let payload_client = Arc::new(QuorumStoreClient::new(
             consensus_to_quorum_store_tx,
             self.config.quorum_store_poll_count, // TODO: consider moving it to a quorum store config in later PRs.
             self.config.quorum_store_pull_timeout_ms,
             self.config.wait_for_full_blocks_above_recent_fill_threshold,
             self.config.wait_for_full_blocks_above_pending_blocks,
-        );
+        ));
         self.commit_state_computer.new_epoch(
             &epoch_state,
             payload_manager.clone(),
-            transaction_shuffler,
+            transaction_shuffler.clone(),
         );
         let state_computer = if onchain_consensus_config.decoupled_execution() {
             Arc::new(self.spawn_decoupled_execution(
@@ -713,13 +730,30 @@ impl EpochManager {
             self.batch_retrieval_tx = Some(batch_retrieval_rx);
         }
 
+        self.proposal_simulation_service
+            .set_simulator(Arc::new(ProposalSimulator::new(
+                epoch,
+                self.author,
+                block_store.clone(),
+                payload_client.clone(),
+                payload_manager.clone(),
+                transaction_shuffler,
+                self.create_proposer_election(&epoch_state, &onchain_consensus_config),
+                self.time_service.clone(),
+                self.config
+                    .max_sending_block_txns(self.quorum_store_enabled),
+                self.config
+                    .max_sending_block_bytes(self.quorum_store_enabled),
+                chain_health_backoff_config.clone(),
+            )));
+
         info!(epoch = epoch, "Create ProposalGenerator");
         // txn manager is required both by proposal generator (to pull the proposers)
         // and by event processor (to update their status).
         let proposal_generator = ProposalGenerator::new(
             self.author,
             block_store.clone(),
-            Arc::new(payload_client),
+            payload_client,
             self.time_service.clone(),
             self.config
                 .max_sending_block_txns(self.quorum_store_enabled),
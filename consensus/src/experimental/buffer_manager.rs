@@ -4,6 +4,7 @@
 
 use crate::{
     block_storage::tracing::{observe_block, BlockStage},
+    consensus_observer::{network::ConsensusObserverMessage, publisher::ConsensusPublisher},
     counters,
     experimental::{
         buffer::{Buffer, Cursor},
@@ -67,6 +68,8 @@ pub fn create_channel<T>() -> (Sender<T>, Receiver<T>) {
 /// BufferManager handles the states of ordered blocks and
 /// interacts with the execution phase, the signing phase, and
 /// the persisting phase.
+/// On consensus observers, there is no signing phase nor network sender: executed blocks
+/// wait for the commit decisions forwarded by the observer.
 pub struct BufferManager {
     author: Author,
 
@@ -79,12 +82,15 @@ pub struct BufferManager {
     execution_phase_rx: Receiver<ExecutionResponse>,
 
     signing_root: BufferItemRootType,
-    signing_phase_tx: Sender<CountedRequest<SigningRequest>>,
+    signing_phase_tx: Option<Sender<CountedRequest<SigningRequest>>>,
     signing_phase_rx: Receiver<SigningResponse>,
 
-    commit_msg_tx: NetworkSender,
+    commit_msg_tx: Option<NetworkSender>,
     commit_msg_rx: aptos_channels::aptos_channel::Receiver<AccountAddress, VerifiedEvent>,
 
+    // publishes the executed and committed blocks to the consensus observers
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+
     // we don't hear back from the persisting phase
     persisting_phase_tx: Sender<CountedRequest<PersistingRequest>>,
 
@@ -111,10 +117,11 @@ impl BufferManager {
         author: Author,
         execution_phase_tx: Sender<CountedRequest<ExecutionRequest>>,
         execution_phase_rx: Receiver<ExecutionResponse>,
-        signing_phase_tx: Sender<CountedRequest<SigningRequest>>,
+        signing_phase_tx: Option<Sender<CountedRequest<SigningRequest>>>,
         signing_phase_rx: Receiver<SigningResponse>,
-        commit_msg_tx: NetworkSender,
+        commit_msg_tx: Option<NetworkSender>,
         commit_msg_rx: aptos_channels::aptos_channel::Receiver<AccountAddress, VerifiedEvent>,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
         persisting_phase_tx: Sender<CountedRequest<PersistingRequest>>,
         block_rx: UnboundedReceiver<OrderedBlocks>,
         reset_rx: UnboundedReceiver<ResetRequest>,
@@ -139,6 +146,8 @@ impl BufferManager {
             commit_msg_tx,
             commit_msg_rx,
 
+            consensus_publisher,
+
             persisting_phase_tx,
 
             block_rx,
@@ -220,6 +229,10 @@ impl BufferManager {
     /// Set the signing root to the first not signed item (Executed) and send execution request
     /// Set to None if not exist
     async fn advance_signing_root(&mut self) {
+        let mut signing_phase_tx = match &self.signing_phase_tx {
+            Some(signing_phase_tx) => signing_phase_tx.clone(),
+            None => return,
+        };
         let cursor = self.signing_root;
         self.signing_root = self
             .buffer
@@ -238,10 +251,9 @@ impl BufferManager {
                 commit_ledger_info: executed_item.partial_commit_proof.ledger_info().clone(),
             });
             if cursor == self.signing_root {
-                let sender = self.signing_phase_tx.clone();
-                Self::spawn_retry_request(sender, request, Duration::from_millis(100));
+                Self::spawn_retry_request(signing_phase_tx, request, Duration::from_millis(100));
             } else {
-                self.signing_phase_tx
+                signing_phase_tx
                     .send(request)
                     .await
                     .expect("Failed to send signing request");
@@ -271,19 +283,28 @@ impl BufferManager {
                 let aggregated_item = item.unwrap_aggregated();
                 let block = aggregated_item.executed_blocks.last().unwrap().block();
                 observe_block(block.timestamp_usecs(), BlockStage::COMMIT_CERTIFIED);
-                // if we're the proposer for the block, we're responsible to broadcast the commit decision.
-                if block.author() == Some(self.author) {
-                    self.commit_msg_tx
-                        .broadcast_commit_proof(aggregated_item.commit_proof.clone())
-                        .await;
+                if let Some(consensus_publisher) = &self.consensus_publisher {
+                    consensus_publisher.publish(ConsensusObserverMessage::CommitDecision(
+                        Box::new(aggregated_item.commit_proof.clone()),
+                    ));
+                }
+                if let Some(commit_msg_tx) = &self.commit_msg_tx {
+                    // if we're the proposer for the block, we're responsible to broadcast the commit decision.
+                    if block.author() == Some(self.author) {
+                        commit_msg_tx
+                            .broadcast_commit_proof(aggregated_item.commit_proof.clone())
+                            .await;
+                    }
+                    if aggregated_item.commit_proof.ledger_info().ends_epoch() {
+                        commit_msg_tx
+                            .send_epoch_change(EpochChangeProof::new(
+                                vec![aggregated_item.commit_proof.clone()],
+                                false,
+                            ))
+                            .await;
+                    }
                 }
                 if aggregated_item.commit_proof.ledger_info().ends_epoch() {
-                    self.commit_msg_tx
-                        .send_epoch_change(EpochChangeProof::new(
-                            vec![aggregated_item.commit_proof.clone()],
-                            false,
-                        ))
-                        .await;
                     // the epoch ends, reset to avoid executing more blocks, execute after
                     // this persisting request will result in BlockNotFound
                     self.reset().await;
@@ -375,6 +396,14 @@ impl BufferManager {
         }
 
         let item = self.buffer.take(&current_cursor);
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            if let BufferItem::Ordered(ordered_item) = &item {
+                consensus_publisher.publish_ordered_block(
+                    &ordered_item.ordered_blocks,
+                    &ordered_item.ordered_proof,
+                );
+            }
+        }
         let new_item = item.advance_to_executed_or_aggregated(
             executed_blocks,
             &self.verifier,
@@ -424,12 +453,12 @@ impl BufferManager {
                 let commit_vote = signed_item.unwrap_signed_ref().commit_vote.clone();
 
                 self.buffer.set(&current_cursor, signed_item);
-                if let Some(proposer) = maybe_proposer {
-                    self.commit_msg_tx
-                        .send_commit_vote(commit_vote, proposer)
-                        .await;
-                } else {
-                    self.commit_msg_tx.broadcast_commit_vote(commit_vote).await;
+                if let Some(commit_msg_tx) = &self.commit_msg_tx {
+                    if let Some(proposer) = maybe_proposer {
+                        commit_msg_tx.send_commit_vote(commit_vote, proposer).await;
+                    } else {
+                        commit_msg_tx.broadcast_commit_vote(commit_vote).await;
+                    }
                 }
             } else {
                 self.buffer.set(&current_cursor, item);
@@ -502,6 +531,10 @@ impl BufferManager {
     /// this function retries all the items until the signing root
     /// note that there might be other signed items after the signing root
     async fn rebroadcast_commit_votes_if_needed(&mut self) {
+        let commit_msg_tx = match &self.commit_msg_tx {
+            Some(commit_msg_tx) => commit_msg_tx,
+            None => return,
+        };
         if self.previous_commit_time.elapsed()
             < Duration::from_millis(COMMIT_VOTE_REBROADCAST_INTERVAL_MS)
         {
@@ -516,7 +549,7 @@ impl BufferManager {
                     break;
                 }
                 let signed_item = item.unwrap_signed_ref();
-                self.commit_msg_tx
+                commit_msg_tx
                     .broadcast_commit_vote(signed_item.commit_vote.clone())
                     .await;
                 count += 1;
@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::publisher::ConsensusPublisher,
     experimental::{
-        buffer_manager::{create_channel, BufferManager, OrderedBlocks, ResetRequest},
+        buffer_manager::{create_channel, BufferManager, OrderedBlocks, ResetRequest, Sender},
         execution_phase::{ExecutionPhase, ExecutionRequest, ExecutionResponse},
         persisting_phase::{PersistingPhase, PersistingRequest},
         pipeline_phase::{CountedRequest, PipelinePhase},
@@ -33,6 +34,7 @@ pub fn prepare_phases_and_buffer_manager(
     block_rx: UnboundedReceiver<OrderedBlocks>,
     sync_rx: UnboundedReceiver<ResetRequest>,
     verifier: ValidatorVerifier,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
 ) -> (
     PipelinePhase<ExecutionPhase>,
     PipelinePhase<SigningPhase>,
@@ -40,20 +42,11 @@ pub fn prepare_phases_and_buffer_manager(
     BufferManager,
 ) {
     // Execution Phase
-    let (execution_phase_request_tx, execution_phase_request_rx) =
-        create_channel::<CountedRequest<ExecutionRequest>>();
-    let (execution_phase_response_tx, execution_phase_response_rx) =
-        create_channel::<ExecutionResponse>();
+    let (execution_phase, execution_phase_request_tx, execution_phase_response_rx) =
+        prepare_execution_phase(execution_proxy);
 
     let ongoing_tasks = Arc::new(AtomicU64::new(0));
 
-    let execution_phase_processor = ExecutionPhase::new(execution_proxy);
-    let execution_phase = PipelinePhase::new(
-        execution_phase_request_rx,
-        Some(execution_phase_response_tx),
-        Box::new(execution_phase_processor),
-    );
-
     // Signing Phase
     let (signing_phase_request_tx, signing_phase_request_rx) =
         create_channel::<CountedRequest<SigningRequest>>();
@@ -68,15 +61,8 @@ pub fn prepare_phases_and_buffer_manager(
     );
 
     // Persisting Phase
-    let (persisting_phase_request_tx, persisting_phase_request_rx) =
-        create_channel::<CountedRequest<PersistingRequest>>();
-
-    let persisting_phase_processor = PersistingPhase::new(persisting_proxy);
-    let persisting_phase = PipelinePhase::new(
-        persisting_phase_request_rx,
-        None,
-        Box::new(persisting_phase_processor),
-    );
+    let (persisting_phase, persisting_phase_request_tx) =
+        prepare_persisting_phase(persisting_proxy);
 
     (
         execution_phase,
@@ -86,10 +72,11 @@ pub fn prepare_phases_and_buffer_manager(
             author,
             execution_phase_request_tx,
             execution_phase_response_rx,
-            signing_phase_request_tx,
+            Some(signing_phase_request_tx),
             signing_phase_response_rx,
-            commit_msg_tx,
+            Some(commit_msg_tx),
             commit_msg_rx,
+            consensus_publisher,
             persisting_phase_request_tx,
             block_rx,
             sync_rx,
@@ -98,3 +85,92 @@ pub fn prepare_phases_and_buffer_manager(
         ),
     )
 }
+
+/// build channels and return phases and buffer manager of a consensus observer, which
+/// doesn't sign the executed blocks, but waits for the commit decisions of its publisher
+/// (received on commit_msg_rx)
+pub fn prepare_observer_phases_and_buffer_manager(
+    author: Author,
+    execution_proxy: Arc<dyn StateComputer>,
+    commit_msg_rx: Receiver<AccountAddress, VerifiedEvent>,
+    persisting_proxy: Arc<dyn StateComputer>,
+    block_rx: UnboundedReceiver<OrderedBlocks>,
+    sync_rx: UnboundedReceiver<ResetRequest>,
+    verifier: ValidatorVerifier,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+) -> (
+    PipelinePhase<ExecutionPhase>,
+    PipelinePhase<PersistingPhase>,
+    BufferManager,
+) {
+    let (execution_phase, execution_phase_request_tx, execution_phase_response_rx) =
+        prepare_execution_phase(execution_proxy);
+    let (persisting_phase, persisting_phase_request_tx) =
+        prepare_persisting_phase(persisting_proxy);
+
+    // there are no signing responses, the sender is dropped right away
+    let (_, signing_phase_response_rx) = create_channel::<SigningResponse>();
+
+    (
+        execution_phase,
+        persisting_phase,
+        BufferManager::new(
+            author,
+            execution_phase_request_tx,
+            execution_phase_response_rx,
+            None,
+            signing_phase_response_rx,
+            None,
+            commit_msg_rx,
+            consensus_publisher,
+            persisting_phase_request_tx,
+            block_rx,
+            sync_rx,
+            verifier,
+            Arc::new(AtomicU64::new(0)),
+        ),
+    )
+}
+
+fn prepare_execution_phase(
+    execution_proxy: Arc<dyn StateComputer>,
+) -> (
+    PipelinePhase<ExecutionPhase>,
+    Sender<CountedRequest<ExecutionRequest>>,
+    UnboundedReceiver<ExecutionResponse>,
+) {
+    let (execution_phase_request_tx, execution_phase_request_rx) =
+        create_channel::<CountedRequest<ExecutionRequest>>();
+    let (execution_phase_response_tx, execution_phase_response_rx) =
+        create_channel::<ExecutionResponse>();
+
+    let execution_phase_processor = ExecutionPhase::new(execution_proxy);
+    let execution_phase = PipelinePhase::new(
+        execution_phase_request_rx,
+        Some(execution_phase_response_tx),
+        Box::new(execution_phase_processor),
+    );
+    (
+        execution_phase,
+        execution_phase_request_tx,
+        execution_phase_response_rx,
+    )
+}
+
+fn prepare_persisting_phase(
+    persisting_proxy: Arc<dyn StateComputer>,
+) -> (
+    PipelinePhase<PersistingPhase>,
+    Sender<CountedRequest<PersistingRequest>>,
+) {
+    let (persisting_phase_request_tx, persisting_phase_request_rx) =
+        create_channel::<CountedRequest<PersistingRequest>>();
+
+    let persisting_phase_processor = PersistingPhase::new(persisting_proxy);
+    let persisting_phase = PipelinePhase::new(
+        persisting_phase_request_rx,
+        None,
+        Box::new(persisting_phase_processor),
+    );
+    (persisting_phase, persisting_phase_request_tx)
+}
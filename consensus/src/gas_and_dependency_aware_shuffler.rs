@@ -0,0 +1,444 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::{NUM_SENDERS_IN_BLOCK, TXN_SHUFFLE_SECONDS},
+    transaction_shuffler::TransactionShuffler,
+};
+use aptos_types::{
+    account_config::CORE_CODE_ADDRESS,
+    transaction::{SignedTransaction, TransactionArgument, TransactionPayload},
+};
+use aptos_vm::{move_vm_ext::MoveResolverExt, read_write_set_analysis::ReadWriteSetAnalysis};
+use move_core_types::account_address::AccountAddress;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+};
+
+/// Accounts a transaction is estimated to read from and write to. Accesses are tracked per
+/// account rather than per resource, as that is what can be estimated without the state.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AccessSet {
+    /// Accounts the transaction reads from
+    pub reads: HashSet<AccountAddress>,
+    /// Accounts the transaction writes to
+    pub writes: HashSet<AccountAddress>,
+}
+
+impl AccessSet {
+    fn insert_read(&mut self, address: AccountAddress) {
+        // Every transaction touches the framework account (gas fees, supply, timestamp), and the
+        // block executor handles those accesses as deltas, so they are not counted as conflicts.
+        if address != CORE_CODE_ADDRESS {
+            self.reads.insert(address);
+        }
+    }
+
+    fn insert_write(&mut self, address: AccountAddress) {
+        if address != CORE_CODE_ADDRESS {
+            self.writes.insert(address);
+        }
+    }
+}
+
+/// Estimates the access set of a transaction before it is executed.
+pub trait AccessSetEstimator {
+    /// Returns an estimate of the accounts accessed by the transaction
+    fn estimate(&self, txn: &SignedTransaction) -> AccessSet;
+}
+
+/// Estimates access sets from the transaction payload only: the sender and every address passed
+/// as an argument are assumed to be written. This is used by consensus, which shuffles a block
+/// before the state it executes on is available.
+pub struct PayloadAccessSetEstimator;
+
+impl AccessSetEstimator for PayloadAccessSetEstimator {
+    fn estimate(&self, txn: &SignedTransaction) -> AccessSet {
+        let mut access_set = AccessSet::default();
+        access_set.insert_write(txn.sender());
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                // Arguments are BCS encoded, so any 32 byte argument may be an address
+                for arg in entry_function.args() {
+                    if let Ok(address) = bcs::from_bytes::<AccountAddress>(arg) {
+                        access_set.insert_write(address);
+                    }
+                }
+            },
+            TransactionPayload::Script(script) => {
+                for arg in script.args() {
+                    if let TransactionArgument::Address(address) = arg {
+                        access_set.insert_write(*address);
+                    }
+                }
+            },
+            TransactionPayload::Multisig(multisig) => {
+                access_set.insert_write(multisig.multisig_address);
+            },
+            TransactionPayload::ModuleBundle(_) => (),
+        }
+        access_set
+    }
+}
+
+/// Estimates access sets with the static read/write set analysis of the called entry functions,
+/// for callers that have a view of the state, e.g., the executor benchmark. Falls back to the
+/// payload estimate for transactions the analysis doesn't support, such as scripts.
+pub struct ReadWriteSetAnalysisEstimator<'a, R: MoveResolverExt> {
+    analysis: ReadWriteSetAnalysis<'a, R>,
+}
+
+impl<'a, R: MoveResolverExt> ReadWriteSetAnalysisEstimator<'a, R> {
+    /// Creates an estimator from the analysis of the modules the transactions call
+    pub fn new(analysis: ReadWriteSetAnalysis<'a, R>) -> Self {
+        Self { analysis }
+    }
+}
+
+impl<'a, R: MoveResolverExt> AccessSetEstimator for ReadWriteSetAnalysisEstimator<'a, R> {
+    fn estimate(&self, txn: &SignedTransaction) -> AccessSet {
+        // Secondary indexes are not resolved, as it would require reading the state
+        match self.analysis.get_partial_keys_user_transaction(txn) {
+            Ok((reads, writes)) => {
+                let mut access_set = AccessSet::default();
+                for key in reads {
+                    access_set.insert_read(key.address());
+                }
+                for key in writes {
+                    access_set.insert_write(key.address());
+                }
+                access_set
+            },
+            Err(_) => PayloadAccessSetEstimator.estimate(txn),
+        }
+    }
+}
+
+/// Bounds the time spent on blocks in which most transactions conflict with each other
+const MAX_CANDIDATES_TO_CHECK: usize = 128;
+
+/// An implementation of transaction shuffler which orders a block by gas unit price while
+/// spreading out transactions with conflicting access sets, to reduce the number of Block-STM
+/// re-executions. It works as follows - transactions are grouped by sender, and only the first
+/// remaining transaction of each sender is a candidate to be added to the block next, so the
+/// relative ordering of the transactions of a sender is preserved. Among the candidates, the one
+/// with the highest gas unit price (the earliest in the original order on ties) which doesn't
+/// conflict with any of the last `conflict_window_size` transactions added to the block is added.
+/// Only the first `MAX_CANDIDATES_TO_CHECK` candidates are checked for conflicts, and if all of
+/// them conflict, the one with the highest gas unit price is added anyway. Two transactions
+/// conflict if one of them writes to an account the other accesses.
+pub struct GasAndDependencyAwareShuffler<E> {
+    conflict_window_size: usize,
+    estimator: E,
+}
+
+impl<E: AccessSetEstimator> GasAndDependencyAwareShuffler<E> {
+    /// Creates a shuffler that estimates access sets with `estimator`
+    pub fn new(conflict_window_size: usize, estimator: E) -> Self {
+        Self {
+            conflict_window_size,
+            estimator,
+        }
+    }
+}
+
+impl<E: AccessSetEstimator + Send + Sync> TransactionShuffler for GasAndDependencyAwareShuffler<E> {
+    fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        let _timer = TXN_SHUFFLE_SECONDS.start_timer();
+
+        let num_txns = txns.len();
+        let mut txns_by_sender: HashMap<AccountAddress, VecDeque<PendingTransaction>> =
+            HashMap::new();
+        for (index, txn) in txns.into_iter().enumerate() {
+            let access_set = self.estimator.estimate(&txn);
+            txns_by_sender
+                .entry(txn.sender())
+                .or_insert_with(VecDeque::new)
+                .push_back(PendingTransaction {
+                    index,
+                    txn,
+                    access_set,
+                });
+        }
+        NUM_SENDERS_IN_BLOCK.set(txns_by_sender.len() as f64);
+
+        let mut candidates: BTreeSet<Candidate> = txns_by_sender
+            .values()
+            .map(|sender_txns| Candidate::new(sender_txns.front().unwrap()))
+            .collect();
+        let mut window = ConflictWindow::new(self.conflict_window_size);
+        let mut shuffled_txns = Vec::with_capacity(num_txns);
+        while let Some(first) = candidates.iter().next() {
+            let selected = candidates
+                .iter()
+                .take(MAX_CANDIDATES_TO_CHECK)
+                .find(|candidate| {
+                    !window.has_conflict(
+                        &txns_by_sender
+                            .get(&candidate.sender)
+                            .unwrap()
+                            .front()
+                            .unwrap()
+                            .access_set,
+                    )
+                })
+                .unwrap_or(first)
+                .clone();
+            candidates.remove(&selected);
+
+            let sender_txns = txns_by_sender.get_mut(&selected.sender).unwrap();
+            let pending_txn = sender_txns.pop_front().unwrap();
+            if let Some(next) = sender_txns.front() {
+                candidates.insert(Candidate::new(next));
+            }
+            window.add(pending_txn.access_set);
+            shuffled_txns.push(pending_txn.txn);
+        }
+        shuffled_txns
+    }
+}
+
+struct PendingTransaction {
+    // Position in the original block, to keep the original order between equal gas unit prices
+    index: usize,
+    txn: SignedTransaction,
+    access_set: AccessSet,
+}
+
+/// The first remaining transaction of a sender, ordered by decreasing gas unit price and then by
+/// position in the original block.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct Candidate {
+    gas_unit_price: Reverse<u64>,
+    index: usize,
+    sender: AccountAddress,
+}
+
+impl Candidate {
+    fn new(pending_txn: &PendingTransaction) -> Self {
+        Self {
+            gas_unit_price: Reverse(pending_txn.txn.gas_unit_price()),
+            index: pending_txn.index,
+            sender: pending_txn.txn.sender(),
+        }
+    }
+}
+
+/// Access sets of the last `window_size` transactions added to the block, with the number of
+/// transactions in the window reading from and writing to each account.
+struct ConflictWindow {
+    window_size: usize,
+    access_sets: VecDeque<AccessSet>,
+    reads: HashMap<AccountAddress, usize>,
+    writes: HashMap<AccountAddress, usize>,
+}
+
+impl ConflictWindow {
+    fn new(window_size: usize) -> Self {
+        Self {
+            window_size,
+            access_sets: VecDeque::with_capacity(window_size),
+            reads: HashMap::new(),
+            writes: HashMap::new(),
+        }
+    }
+
+    fn has_conflict(&self, access_set: &AccessSet) -> bool {
+        let is_written = |address| self.writes.get(address).map_or(false, |count| *count > 0);
+        let is_read = |address| self.reads.get(address).map_or(false, |count| *count > 0);
+        access_set.reads.iter().any(is_written)
+            || access_set
+                .writes
+                .iter()
+                .any(|address| is_written(address) || is_read(address))
+    }
+
+    fn add(&mut self, access_set: AccessSet) {
+        if self.window_size == 0 {
+            return;
+        }
+        if self.access_sets.len() == self.window_size {
+            let dropped = self.access_sets.pop_front().unwrap();
+            for address in &dropped.reads {
+                *self.reads.get_mut(address).unwrap() -= 1;
+            }
+            for address in &dropped.writes {
+                *self.writes.get_mut(address).unwrap() -= 1;
+            }
+        }
+        for address in &access_set.reads {
+            *self.reads.entry(*address).or_insert(0) += 1;
+        }
+        for address in &access_set.writes {
+            *self.writes.entry(*address).or_insert(0) += 1;
+        }
+        self.access_sets.push_back(access_set);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        gas_and_dependency_aware_shuffler::{
+            AccessSetEstimator, GasAndDependencyAwareShuffler, PayloadAccessSetEstimator,
+        },
+        transaction_shuffler::TransactionShuffler,
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{
+            RawTransaction, Script, SignedTransaction, TransactionArgument, TransactionPayload,
+        },
+    };
+    use move_core_types::account_address::AccountAddress;
+    use rand::{rngs::OsRng, Rng};
+    use std::collections::HashMap;
+
+    fn create_signed_transactions(
+        sender: AccountAddress,
+        num_transactions: usize,
+        gas_unit_price: u64,
+        recipient: Option<AccountAddress>,
+    ) -> Vec<SignedTransaction> {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        let args = recipient
+            .map(|recipient| vec![TransactionArgument::Address(recipient)])
+            .unwrap_or_default();
+
+        (0..num_transactions)
+            .map(|i| {
+                let raw_transaction = RawTransaction::new(
+                    sender,
+                    i as u64,
+                    TransactionPayload::Script(Script::new(vec![], vec![], args.clone())),
+                    0,
+                    gas_unit_price,
+                    0,
+                    ChainId::new(10),
+                );
+                SignedTransaction::new(
+                    raw_transaction.clone(),
+                    public_key.clone(),
+                    private_key.sign(&raw_transaction).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn shuffler(conflict_window_size: usize) -> impl TransactionShuffler {
+        GasAndDependencyAwareShuffler::new(conflict_window_size, PayloadAccessSetEstimator)
+    }
+
+    #[test]
+    fn test_payload_access_set() {
+        let sender = AccountAddress::random();
+        let recipient = AccountAddress::random();
+        let txn = create_signed_transactions(sender, 1, 0, Some(recipient)).remove(0);
+        let access_set = PayloadAccessSetEstimator.estimate(&txn);
+        assert!(access_set.reads.is_empty());
+        assert_eq!(access_set.writes, [sender, recipient].into_iter().collect());
+    }
+
+    #[test]
+    fn test_unique_sender_txns_ordered_by_gas() {
+        let txns: Vec<_> = [1, 3, 2, 3]
+            .into_iter()
+            .flat_map(|gas_unit_price| {
+                create_signed_transactions(AccountAddress::random(), 1, gas_unit_price, None)
+            })
+            .collect();
+        let shuffled_txns = shuffler(10).shuffle(txns.clone());
+        // Ties keep the original order
+        assert_eq!(
+            shuffled_txns,
+            vec![
+                txns[1].clone(),
+                txns[3].clone(),
+                txns[2].clone(),
+                txns[0].clone()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_same_sender_relative_order() {
+        let mut rng = OsRng;
+        let mut orig_txns = Vec::new();
+        let mut orig_txns_by_sender = HashMap::new();
+        for _ in 0..100 {
+            let sender = AccountAddress::random();
+            // A sender can raise the gas unit price of its later transactions, which must still
+            // come after its earlier ones
+            let sender_txns: Vec<_> = (0..rng.gen_range(1, 20))
+                .flat_map(|_| create_signed_transactions(sender, 1, rng.gen_range(0, 10), None))
+                .collect();
+            orig_txns_by_sender.insert(sender, sender_txns.clone());
+            orig_txns.extend(sender_txns);
+        }
+
+        let shuffled_txns = shuffler(32).shuffle(orig_txns.clone());
+        assert_eq!(shuffled_txns.len(), orig_txns.len());
+        let mut shuffled_txns_by_sender = HashMap::new();
+        for txn in shuffled_txns {
+            shuffled_txns_by_sender
+                .entry(txn.sender())
+                .or_insert_with(Vec::new)
+                .push(txn);
+        }
+        assert_eq!(shuffled_txns_by_sender, orig_txns_by_sender);
+    }
+
+    #[test]
+    // S1 -> R, S2 -> R, S3, S4 with the same gas unit price and conflict_window_size=2, should
+    // return (the transfers to the same recipient are separated):
+    // S1 -> R, S3, S4, S2 -> R
+    fn test_conflicting_recipients_are_separated() {
+        let recipient = AccountAddress::random();
+        let txns: Vec<_> = [Some(recipient), Some(recipient), None, None]
+            .into_iter()
+            .flat_map(|recipient| {
+                create_signed_transactions(AccountAddress::random(), 1, 1, recipient)
+            })
+            .collect();
+        let shuffled_txns = shuffler(2).shuffle(txns.clone());
+        assert_eq!(
+            shuffled_txns,
+            vec![
+                txns[0].clone(),
+                txns[2].clone(),
+                txns[3].clone(),
+                txns[1].clone()
+            ]
+        );
+    }
+
+    #[test]
+    // A transfer to S1 conflicts with the transactions of S1, so with conflict_window_size=1:
+    // S1_1, S2 -> S1, S1_2, S3 should return S1_1, S3, S2 -> S1, S1_2
+    fn test_recipient_conflicts_with_sender() {
+        let sender1 = AccountAddress::random();
+        let sender1_txns = create_signed_transactions(sender1, 2, 1, None);
+        let sender2_txns =
+            create_signed_transactions(AccountAddress::random(), 1, 1, Some(sender1));
+        let sender3_txns = create_signed_transactions(AccountAddress::random(), 1, 1, None);
+        let txns = vec![
+            sender1_txns[0].clone(),
+            sender2_txns[0].clone(),
+            sender1_txns[1].clone(),
+            sender3_txns[0].clone(),
+        ];
+        let shuffled_txns = shuffler(1).shuffle(txns);
+        assert_eq!(
+            shuffled_txns,
+            vec![
+                sender1_txns[0].clone(),
+                sender3_txns[0].clone(),
+                sender2_txns[0].clone(),
+                sender1_txns[1].clone()
+            ]
+        );
+    }
+}
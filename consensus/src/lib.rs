@@ -16,6 +16,7 @@
 extern crate core;
 
 mod block_storage;
+mod consensus_observer;
 mod consensusdb;
 mod epoch_manager;
 mod error;
@@ -32,6 +33,8 @@ mod persistent_liveness_storage;
 mod quorum_store;
 mod recovery_manager;
 mod round_manager;
+#[cfg(test)]
+mod simulator;
 mod state_computer;
 mod state_replication;
 #[cfg(any(test, feature = "fuzzing"))]
@@ -47,15 +50,30 @@ pub mod consensus_provider;
 pub mod counters;
 /// AptosNet interface.
 pub mod network_interface;
+/// Required by the executor benchmark
+pub mod gas_and_dependency_aware_shuffler;
 mod payload_manager;
-mod sender_aware_shuffler;
-mod transaction_shuffler;
+/// Required by the executor benchmark
+pub mod sender_aware_shuffler;
+/// Required by the executor benchmark
+pub mod transaction_shuffler;
 
 use aptos_metrics_core::IntGauge;
+pub use consensus_observer::network::ConsensusObserverMessage;
 pub use consensusdb::create_checkpoint;
+/// Required by the db tool
+pub use consensusdb::inspection as consensus_db_inspection;
 /// Required by the smoke tests
 pub use consensusdb::CONSENSUS_DB_NAME;
-pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
+/// Required by the leader election analysis tool
+pub use liveness::leader_election_analysis;
+/// Required by the inspection service
+pub use liveness::proposal_simulator::{
+    ProposalSimulation, ProposalSimulationError, ProposalSimulationService,
+};
+pub use quorum_store::{
+    inspection as quorum_store_inspection, quorum_store_db::QUORUM_STORE_DB_NAME,
+};
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
 
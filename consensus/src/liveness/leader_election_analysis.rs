@@ -0,0 +1,476 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays committed block metadata against alternate leader election configs.
+//!
+//! Every config is replayed over the same rounds that were committed on chain. For each round
+//! the leader elected by the config is asked to propose, and whether it succeeds is decided by
+//! what the same validator did on chain closest to that round (if it never proposed, by whether
+//! it voted in that epoch). The outcomes are fed back as a synthetic NewBlockEvent history, so
+//! reputation based elections react to the failures of their own leaders, and not to the ones
+//! that happened on chain.
+//!
+//! The accumulator root hash used to seed V2 reputation elections is not available for the
+//! synthetic history, the id of the latest replayed block is used instead. Individual leaders
+//! therefore don't match the chain, only their distribution is meaningful.
+
+use crate::liveness::{
+    leader_reputation::{
+        LeaderReputation, MetadataBackend, NewBlockEventAggregation, ProposerAndVoterHeuristic,
+        ReputationHeuristic,
+    },
+    proposer_election::ProposerElection,
+    rotating_proposer_election::{choose_leader, RotatingProposer},
+    round_proposer_election::RoundProposer,
+};
+use anyhow::{ensure, Result};
+use aptos_bitvec::BitVec;
+use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    on_chain_config::{LeaderReputationType, OnChainConsensusConfig, ProposerElectionType},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+/// Validator set and committed blocks of a single epoch.
+#[derive(Clone, Debug)]
+pub struct EpochHistory {
+    /// Epoch number
+    pub epoch: u64,
+    /// Validators and their voting power, ordered by validator index
+    pub validators: Vec<(Author, u64)>,
+    /// Committed NewBlockEvents of the epoch, ordered by round
+    pub blocks: Vec<NewBlockEvent>,
+}
+
+impl EpochHistory {
+    fn proposers(&self) -> Vec<Author> {
+        self.validators.iter().map(|(author, _)| *author).collect()
+    }
+
+    fn total_voting_power(&self) -> u128 {
+        self.validators.iter().map(|(_, vp)| *vp as u128).sum()
+    }
+
+    /// Rounds covered by the committed blocks, failed ones included
+    fn num_rounds(&self) -> u64 {
+        match (self.blocks.first(), self.blocks.last()) {
+            (Some(first), Some(last)) => last.round() - first_round(first) + 1,
+            _ => 0,
+        }
+    }
+}
+
+/// First round covered by the block, i.e. the round of the earliest preceding failed proposer
+fn first_round(block: &NewBlockEvent) -> Round {
+    block
+        .round()
+        .saturating_sub(block.failed_proposer_indices().len() as u64)
+}
+
+fn default_exclude_round() -> u64 {
+    OnChainConsensusConfig::default().leader_reputation_exclude_round()
+}
+
+/// Named leader election config to replay the history with.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ElectionConfig {
+    /// Name the config is reported under
+    pub name: String,
+    /// Leader election to replay
+    pub proposer_election_type: ProposerElectionType,
+    /// Number of most recent rounds leader reputation ignores
+    #[serde(default = "default_exclude_round")]
+    pub exclude_round: u64,
+}
+
+impl ElectionConfig {
+    /// Config matching the leader election of the given on-chain consensus config
+    pub fn from_on_chain_config(name: String, config: &OnChainConsensusConfig) -> Self {
+        Self {
+            name,
+            proposer_election_type: config.proposer_election_type().clone(),
+            exclude_round: config.leader_reputation_exclude_round(),
+        }
+    }
+}
+
+/// Leader statistics of a single validator.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LeaderStats {
+    /// Rounds the validator was the leader of
+    pub elected_rounds: u64,
+    /// Rounds the validator was the leader of, and didn't produce a block
+    pub failed_rounds: u64,
+    /// Rounds the validator would lead if leaders were chosen proportionally to voting power
+    pub fair_rounds: f64,
+}
+
+/// Result of replaying the history with a single leader election config.
+#[derive(Clone, Debug)]
+pub struct ElectionReport {
+    /// Name of the replayed config
+    pub name: String,
+    /// Per validator leader statistics across all replayed epochs
+    pub validators: BTreeMap<Author, LeaderStats>,
+}
+
+impl ElectionReport {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            validators: BTreeMap::new(),
+        }
+    }
+
+    /// Report of the leaders that were elected on chain
+    pub fn from_history(name: String, history: &[EpochHistory]) -> Result<Self> {
+        let mut report = Self::new(name);
+        for epoch_history in history {
+            let proposers = epoch_history.proposers();
+            for block in &epoch_history.blocks {
+                for failed_proposer in NewBlockEventAggregation::indices_to_validators(
+                    &proposers,
+                    block.failed_proposer_indices(),
+                )
+                .map_err(|e| anyhow::anyhow!(e))?
+                {
+                    report.record_round(*failed_proposer, false);
+                }
+                report.record_round(block.proposer(), true);
+            }
+            report.record_fair_rounds(epoch_history);
+        }
+        Ok(report)
+    }
+
+    fn record_round(&mut self, leader: Author, succeeded: bool) {
+        let stats = self.validators.entry(leader).or_default();
+        stats.elected_rounds += 1;
+        if !succeeded {
+            stats.failed_rounds += 1;
+        }
+    }
+
+    fn record_fair_rounds(&mut self, epoch_history: &EpochHistory) {
+        let total_voting_power = epoch_history.total_voting_power();
+        if total_voting_power == 0 {
+            return;
+        }
+        let num_rounds = epoch_history.num_rounds() as f64;
+        for (author, voting_power) in &epoch_history.validators {
+            self.validators.entry(*author).or_default().fair_rounds +=
+                num_rounds * *voting_power as f64 / total_voting_power as f64;
+        }
+    }
+
+    /// Number of replayed rounds
+    pub fn total_rounds(&self) -> u64 {
+        self.validators.values().map(|s| s.elected_rounds).sum()
+    }
+
+    /// Number of replayed rounds without a block
+    pub fn failed_rounds(&self) -> u64 {
+        self.validators.values().map(|s| s.failed_rounds).sum()
+    }
+
+    /// Fraction of rounds without a block
+    pub fn failed_round_rate(&self) -> f64 {
+        ratio(self.failed_rounds(), self.total_rounds())
+    }
+
+    /// Fraction of the rounds the validator was the leader of
+    pub fn leader_share(&self, author: &Author) -> f64 {
+        ratio(
+            self.validators.get(author).map_or(0, |s| s.elected_rounds),
+            self.total_rounds(),
+        )
+    }
+
+    /// Elected rounds of each validator relative to its voting power based fair share,
+    /// 1.0 meaning the validator led exactly its share of rounds
+    fn fairness_ratios(&self) -> Vec<f64> {
+        self.validators
+            .values()
+            .filter(|s| s.fair_rounds > 0.0)
+            .map(|s| s.elected_rounds as f64 / s.fair_rounds)
+            .collect()
+    }
+
+    /// Jain's fairness index of the leader shares relative to voting power, 1.0 when every
+    /// validator leads its share of rounds, 1/n when a single validator leads all of them
+    pub fn fairness_index(&self) -> f64 {
+        let ratios = self.fairness_ratios();
+        let sum: f64 = ratios.iter().sum();
+        let sum_of_squares: f64 = ratios.iter().map(|r| r * r).sum();
+        if sum_of_squares == 0.0 {
+            return 0.0;
+        }
+        sum * sum / (ratios.len() as f64 * sum_of_squares)
+    }
+
+    /// Lowest and highest ratio of elected to fair share of rounds across validators
+    pub fn fairness_range(&self) -> (f64, f64) {
+        self.fairness_ratios()
+            .into_iter()
+            .fold(None, |range: Option<(f64, f64)>, r| {
+                Some(range.map_or((r, r), |(min, max)| (min.min(r), max.max(r))))
+            })
+            .unwrap_or((0.0, 0.0))
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// Proposal outcomes observed on chain, deciding whether a replayed leader succeeds.
+struct ObservedLiveness {
+    // (epoch, round) of every on chain proposal attempt, and whether it succeeded
+    attempts: HashMap<Author, Vec<((u64, Round), bool)>>,
+    voters: HashSet<(u64, Author)>,
+}
+
+impl ObservedLiveness {
+    fn new(history: &[EpochHistory]) -> Result<Self> {
+        let mut attempts: HashMap<Author, Vec<((u64, Round), bool)>> = HashMap::new();
+        let mut voters = HashSet::new();
+        for epoch_history in history {
+            let epoch = epoch_history.epoch;
+            let proposers = epoch_history.proposers();
+            for block in &epoch_history.blocks {
+                let failed_proposers = NewBlockEventAggregation::indices_to_validators(
+                    &proposers,
+                    block.failed_proposer_indices(),
+                )
+                .map_err(|e| anyhow::anyhow!(e))?;
+                for (round, failed_proposer) in (first_round(block)..).zip(failed_proposers) {
+                    attempts
+                        .entry(*failed_proposer)
+                        .or_default()
+                        .push(((epoch, round), false));
+                }
+                attempts
+                    .entry(block.proposer())
+                    .or_default()
+                    .push(((epoch, block.round()), true));
+
+                let bitvec: BitVec = block.previous_block_votes_bitvec().clone().into();
+                if let Ok(block_voters) =
+                    NewBlockEventAggregation::bitvec_to_voters(&proposers, &bitvec)
+                {
+                    voters.extend(block_voters.into_iter().map(|voter| (epoch, *voter)));
+                }
+            }
+        }
+        for validator_attempts in attempts.values_mut() {
+            validator_attempts.sort();
+        }
+        Ok(Self { attempts, voters })
+    }
+
+    fn would_succeed(&self, author: Author, epoch: u64, round: Round) -> bool {
+        match self.attempts.get(&author) {
+            Some(attempts) => {
+                let index = attempts.partition_point(|(position, _)| *position <= (epoch, round));
+                // Latest attempt at or before the round, or the first one after it
+                attempts[index.saturating_sub(1)].1
+            },
+            None => self.voters.contains(&(epoch, author)),
+        }
+    }
+}
+
+/// MetadataBackend over the NewBlockEvents produced by the replay itself.
+struct ReplayedMetadataBackend {
+    window_size: usize,
+    events: Arc<Mutex<Vec<NewBlockEvent>>>,
+}
+
+impl MetadataBackend for ReplayedMetadataBackend {
+    fn get_block_metadata(
+        &self,
+        target_epoch: u64,
+        target_round: Round,
+    ) -> (Vec<NewBlockEvent>, HashValue) {
+        let events: Vec<_> = self
+            .events
+            .lock()
+            .iter()
+            .rev()
+            .filter(|e| (e.epoch(), e.round()) <= (target_epoch, target_round))
+            .take(self.window_size)
+            .cloned()
+            .collect();
+        let root_hash = events
+            .first()
+            .and_then(|e| e.hash().ok())
+            .unwrap_or_else(HashValue::zero);
+        (events, root_hash)
+    }
+}
+
+fn create_leader_reputation(
+    leader_reputation_type: &LeaderReputationType,
+    exclude_round: u64,
+    history: &[EpochHistory],
+    events: Arc<Mutex<Vec<NewBlockEvent>>>,
+    window_for_chain_health: usize,
+) -> LeaderReputation {
+    let epoch_history = history.last().expect("history must not be empty");
+    let proposers = epoch_history.proposers();
+    let config = match leader_reputation_type {
+        LeaderReputationType::ProposerAndVoter(config)
+        | LeaderReputationType::ProposerAndVoterV2(config) => config,
+    };
+    let proposer_window_size = proposers.len() * config.proposer_window_num_validators_multiplier;
+    let voter_window_size = proposers.len() * config.voter_window_num_validators_multiplier;
+    let heuristic: Box<dyn ReputationHeuristic> = Box::new(ProposerAndVoterHeuristic::new(
+        AccountAddress::ZERO,
+        config.active_weight,
+        config.inactive_weight,
+        config.failed_weight,
+        config.failure_threshold_percent,
+        voter_window_size,
+        proposer_window_size,
+        leader_reputation_type.use_reputation_window_from_stale_end(),
+    ));
+    let backend = Box::new(ReplayedMetadataBackend {
+        window_size: std::cmp::max(proposer_window_size, voter_window_size),
+        events,
+    });
+    let voting_powers = if config.weight_by_voting_power {
+        epoch_history.validators.iter().map(|(_, vp)| *vp).collect()
+    } else {
+        vec![1; proposers.len()]
+    };
+
+    // Same as on chain, skip epoch 1, and only look back as far as configured
+    let epoch = epoch_history.epoch;
+    let first_epoch_to_consider = std::cmp::max(
+        if epoch == 1 { 1 } else { 2 },
+        epoch.saturating_sub(config.use_history_from_previous_epoch_max_count as u64),
+    );
+    let epoch_to_proposers = history
+        .iter()
+        .filter(|h| h.epoch >= first_epoch_to_consider || h.epoch == epoch)
+        .map(|h| (h.epoch, h.proposers()))
+        .collect();
+
+    LeaderReputation::new(
+        epoch,
+        epoch_to_proposers,
+        voting_powers,
+        backend,
+        heuristic,
+        exclude_round,
+        leader_reputation_type.use_root_hash_for_seed(),
+        window_for_chain_health,
+    )
+}
+
+fn create_proposer_election(
+    config: &ElectionConfig,
+    history: &[EpochHistory],
+    events: Arc<Mutex<Vec<NewBlockEvent>>>,
+    window_for_chain_health: usize,
+) -> Box<dyn ProposerElection> {
+    let proposers = history
+        .last()
+        .expect("history must not be empty")
+        .proposers();
+    match &config.proposer_election_type {
+        ProposerElectionType::RotatingProposer(contiguous_rounds) => {
+            Box::new(RotatingProposer::new(proposers, *contiguous_rounds))
+        },
+        ProposerElectionType::FixedProposer(contiguous_rounds) => Box::new(RotatingProposer::new(
+            vec![choose_leader(proposers)],
+            *contiguous_rounds,
+        )),
+        ProposerElectionType::LeaderReputation(leader_reputation_type) => {
+            Box::new(create_leader_reputation(
+                leader_reputation_type,
+                config.exclude_round,
+                history,
+                events,
+                window_for_chain_health,
+            ))
+        },
+        ProposerElectionType::RoundProposer(round_proposers) => {
+            Box::new(RoundProposer::new(round_proposers.clone(), proposers[0]))
+        },
+    }
+}
+
+/// Replays the history, ordered by epoch, with the given leader election config.
+///
+/// `window_for_chain_health` has to be one of the chain health windows, as in ConsensusConfig.
+pub fn replay(
+    config: &ElectionConfig,
+    history: &[EpochHistory],
+    window_for_chain_health: usize,
+) -> Result<ElectionReport> {
+    let observed = ObservedLiveness::new(history)?;
+    let events = Arc::new(Mutex::new(vec![]));
+    let mut report = ElectionReport::new(config.name.clone());
+    let mut height = 0;
+
+    for (index, epoch_history) in history.iter().enumerate() {
+        if epoch_history.validators.is_empty() || epoch_history.blocks.is_empty() {
+            continue;
+        }
+        ensure!(
+            index == 0 || history[index - 1].epoch < epoch_history.epoch,
+            "history is not ordered by epoch at epoch {}",
+            epoch_history.epoch
+        );
+        let epoch = epoch_history.epoch;
+        let proposer_election = create_proposer_election(
+            config,
+            &history[..=index],
+            events.clone(),
+            window_for_chain_health,
+        );
+        let proposer_indices: HashMap<Author, u64> =
+            epoch_history.proposers().into_iter().zip(0u64..).collect();
+
+        let mut failed_proposer_indices = vec![];
+        let mut next_round = first_round(&epoch_history.blocks[0]);
+        for block in &epoch_history.blocks {
+            for round in next_round..=block.round() {
+                let leader = proposer_election.get_valid_proposer(round);
+                let succeeded = observed.would_succeed(leader, epoch, round);
+                report.record_round(leader, succeeded);
+                if succeeded {
+                    height += 1;
+                    events.lock().push(NewBlockEvent::new(
+                        AccountAddress::new(*block.hash()?),
+                        epoch,
+                        round,
+                        height,
+                        block.previous_block_votes_bitvec().clone(),
+                        leader,
+                        std::mem::take(&mut failed_proposer_indices),
+                        block.proposed_time(),
+                    ));
+                } else {
+                    failed_proposer_indices.push(proposer_indices[&leader]);
+                }
+            }
+            next_round = block.round() + 1;
+        }
+        report.record_fair_rounds(epoch_history);
+    }
+    Ok(report)
+}
@@ -0,0 +1,139 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::liveness::leader_election_analysis::{
+    replay, ElectionConfig, ElectionReport, EpochHistory,
+};
+use aptos_bitvec::BitVec;
+use aptos_consensus_types::common::Author;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    on_chain_config::{OnChainConsensusConfig, ProposerElectionType},
+};
+
+const NUM_ROUNDS: u64 = 400;
+const WINDOW_FOR_CHAIN_HEALTH: usize = 100;
+
+fn validators() -> Vec<Author> {
+    (1..=4)
+        .map(|i| AccountAddress::from_hex_literal(&format!("0x{}", i)).unwrap())
+        .collect()
+}
+
+/// Epoch led by a rotating proposer, in which the last validator never produces a block
+fn history_with_offline_validator() -> Vec<EpochHistory> {
+    let validators = validators();
+    let offline_index = validators.len() - 1;
+    let mut votes = BitVec::with_num_bits(validators.len() as u16);
+    for index in 0..offline_index {
+        votes.set(index as u16);
+    }
+
+    let mut blocks = vec![];
+    let mut failed_proposer_indices = vec![];
+    for round in 1..=NUM_ROUNDS {
+        let leader_index = round as usize % validators.len();
+        if leader_index == offline_index {
+            failed_proposer_indices.push(leader_index as u64);
+            continue;
+        }
+        blocks.push(NewBlockEvent::new(
+            AccountAddress::random(),
+            2,
+            round,
+            blocks.len() as u64,
+            votes.clone().into(),
+            validators[leader_index],
+            std::mem::take(&mut failed_proposer_indices),
+            round * 1000,
+        ));
+    }
+    vec![EpochHistory {
+        epoch: 2,
+        validators: validators.into_iter().map(|v| (v, 1)).collect(),
+        blocks,
+    }]
+}
+
+fn config(proposer_election_type: ProposerElectionType) -> ElectionConfig {
+    ElectionConfig {
+        name: format!("{:?}", proposer_election_type),
+        proposer_election_type,
+        exclude_round: 40,
+    }
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 0.01,
+        "{} is not close to {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_report_from_history() {
+    let history = history_with_offline_validator();
+    let report = ElectionReport::from_history("chain".to_string(), &history).unwrap();
+
+    assert_eq!(report.total_rounds(), NUM_ROUNDS);
+    assert_eq!(report.failed_rounds(), NUM_ROUNDS / 4);
+    for validator in validators() {
+        assert_close(report.leader_share(&validator), 0.25);
+    }
+    assert_close(report.fairness_index(), 1.0);
+}
+
+#[test]
+fn test_replay_rotating_proposer_matches_history() {
+    let history = history_with_offline_validator();
+    let chain = ElectionReport::from_history("chain".to_string(), &history).unwrap();
+    let report = replay(
+        &config(ProposerElectionType::RotatingProposer(1)),
+        &history,
+        WINDOW_FOR_CHAIN_HEALTH,
+    )
+    .unwrap();
+
+    assert_eq!(report.validators, chain.validators);
+}
+
+#[test]
+fn test_replay_fixed_proposer_is_unfair() {
+    let history = history_with_offline_validator();
+    let report = replay(
+        &config(ProposerElectionType::FixedProposer(1)),
+        &history,
+        WINDOW_FOR_CHAIN_HEALTH,
+    )
+    .unwrap();
+
+    assert_eq!(report.failed_rounds(), 0);
+    assert_close(report.leader_share(&validators()[0]), 1.0);
+    assert_close(report.fairness_index(), 0.25);
+    let (min, max) = report.fairness_range();
+    assert_close(min, 0.0);
+    assert_close(max, 4.0);
+}
+
+#[test]
+fn test_replay_leader_reputation_avoids_offline_validator() {
+    let history = history_with_offline_validator();
+    let offline_validator = *validators().last().unwrap();
+    let report = replay(
+        &ElectionConfig::from_on_chain_config(
+            "default".to_string(),
+            &OnChainConsensusConfig::default(),
+        ),
+        &history,
+        WINDOW_FOR_CHAIN_HEALTH,
+    )
+    .unwrap();
+
+    assert_eq!(report.total_rounds(), NUM_ROUNDS);
+    assert!(report.failed_round_rate() < 0.1);
+    assert!(report.leader_share(&offline_validator) < 0.1);
+    assert!(report.fairness_index() < 1.0);
+}
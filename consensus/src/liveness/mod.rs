@@ -3,8 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) mod cached_proposer_election;
+pub mod leader_election_analysis;
 pub(crate) mod leader_reputation;
 pub(crate) mod proposal_generator;
+pub mod proposal_simulator;
 pub(crate) mod proposer_election;
 pub(crate) mod rotating_proposer_election;
 pub(crate) mod round_proposer_election;
@@ -14,6 +16,8 @@ pub(crate) mod unequivocal_proposer_election;
 #[cfg(test)]
 mod cached_proposer_election_test;
 #[cfg(test)]
+mod leader_election_analysis_test;
+#[cfg(test)]
 mod leader_reputation_test;
 #[cfg(test)]
 mod rotating_proposer_test;
@@ -22,6 +22,7 @@ use aptos_consensus_types::{
     common::{Author, Payload, PayloadFilter, Round},
     quorum_cert::QuorumCert,
 };
+use aptos_crypto::HashValue;
 use aptos_logger::{error, sample, sample::SampleRate, warn};
 use futures::future::BoxFuture;
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
@@ -89,6 +90,72 @@ impl ChainHealthBackoffConfig {
     }
 }
 
+/// The payloads of the blocks pending in the branch extended by a proposal, which the payload of
+/// the proposal must exclude.
+pub(crate) struct PendingPayloads {
+    pub filter: PayloadFilter,
+    // Whether some of the pending blocks with a payload aren't ordered yet
+    pub pending_ordering: bool,
+    pub num_pending_blocks: usize,
+    max_pending_block_len: usize,
+    max_pending_block_bytes: usize,
+}
+
+impl PendingPayloads {
+    pub fn new(
+        block_store: &(dyn BlockReader + Send + Sync),
+        certified_block_id: HashValue,
+    ) -> anyhow::Result<Self> {
+        // One needs to hold the blocks with the references to the payloads while get_block is
+        // being executed: pending blocks vector keeps all the pending ancestors of the extended branch.
+        let mut pending_blocks = block_store
+            .path_from_commit_root(certified_block_id)
+            .ok_or_else(|| format_err!("HQC {} already pruned", certified_block_id))?;
+        // Avoid txn manager long poll if the root block has txns, so that the leader can
+        // deliver the commit proof to others without delay.
+        pending_blocks.push(block_store.commit_root());
+
+        // Exclude all the pending transactions: these are all the ancestors of
+        // parent (including) up to the root (including).
+        let exclude_payload: Vec<_> = pending_blocks
+            .iter()
+            .flat_map(|block| block.payload())
+            .collect();
+        let filter = PayloadFilter::from(&exclude_payload);
+
+        let pending_ordering = block_store
+            .path_from_ordered_root(certified_block_id)
+            .ok_or_else(|| format_err!("HQC {} already pruned", certified_block_id))?
+            .iter()
+            .any(|block| !block.payload().map_or(true, |txns| txns.is_empty()));
+
+        let max_pending_block_len = pending_blocks
+            .iter()
+            .map(|block| block.payload().map_or(0, |p| p.len()))
+            .max()
+            .unwrap_or(0);
+        let max_pending_block_bytes = pending_blocks
+            .iter()
+            .map(|block| block.payload().map_or(0, |p| p.size()))
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self {
+            filter,
+            pending_ordering,
+            num_pending_blocks: pending_blocks.len(),
+            max_pending_block_len,
+            max_pending_block_bytes,
+        })
+    }
+
+    /// How full the fullest pending block is, relative to the given limits.
+    pub fn max_fill_fraction(&self, max_block_txns: u64, max_block_bytes: u64) -> f32 {
+        (self.max_pending_block_len as f32 / max_block_txns as f32)
+            .max(self.max_pending_block_bytes as f32 / max_block_bytes as f32)
+    }
+}
+
 /// ProposalGenerator is responsible for generating the proposed block on demand: it's typically
 /// used by a validator that believes it's a valid candidate for serving as a proposer at a given
 /// round.
@@ -206,30 +273,8 @@ impl ProposalGenerator {
                 hqc.certified_block().timestamp_usecs(),
             )
         } else {
-            // One needs to hold the blocks with the references to the payloads while get_block is
-            // being executed: pending blocks vector keeps all the pending ancestors of the extended branch.
-            let mut pending_blocks = self
-                .block_store
-                .path_from_commit_root(hqc.certified_block().id())
-                .ok_or_else(|| format_err!("HQC {} already pruned", hqc.certified_block().id()))?;
-            // Avoid txn manager long poll if the root block has txns, so that the leader can
-            // deliver the commit proof to others without delay.
-            pending_blocks.push(self.block_store.commit_root());
-
-            // Exclude all the pending transactions: these are all the ancestors of
-            // parent (including) up to the root (including).
-            let exclude_payload: Vec<_> = pending_blocks
-                .iter()
-                .flat_map(|block| block.payload())
-                .collect();
-            let payload_filter = PayloadFilter::from(&exclude_payload);
-
-            let pending_ordering = self
-                .block_store
-                .path_from_ordered_root(hqc.certified_block().id())
-                .ok_or_else(|| format_err!("HQC {} already pruned", hqc.certified_block().id()))?
-                .iter()
-                .any(|block| !block.payload().map_or(true, |txns| txns.is_empty()));
+            let pending_payloads =
+                PendingPayloads::new(self.block_store.as_ref(), hqc.certified_block().id())?;
 
             // All proposed blocks in a branch are guaranteed to have increasing timestamps
             // since their predecessor block will not be added to the BlockStore until
@@ -256,29 +301,19 @@ impl ProposalGenerator {
                 (self.max_block_txns, self.max_block_bytes)
             };
 
-            let max_pending_block_len = pending_blocks
-                .iter()
-                .map(|block| block.payload().map_or(0, |p| p.len()))
-                .max()
-                .unwrap_or(0);
-            let max_pending_block_bytes = pending_blocks
-                .iter()
-                .map(|block| block.payload().map_or(0, |p| p.size()))
-                .max()
-                .unwrap_or(0);
-            let max_fill_fraction = (max_pending_block_len as f32 / self.max_block_txns as f32)
-                .max(max_pending_block_bytes as f32 / self.max_block_bytes as f32);
-            PROPOSER_PENDING_BLOCKS_COUNT.set(pending_blocks.len() as i64);
+            let max_fill_fraction =
+                pending_payloads.max_fill_fraction(self.max_block_txns, self.max_block_bytes);
+            PROPOSER_PENDING_BLOCKS_COUNT.set(pending_payloads.num_pending_blocks as i64);
             PROPOSER_PENDING_BLOCKS_FILL_FRACTION.set(max_fill_fraction as f64);
             let payload = self
                 .payload_client
                 .pull_payload(
                     max_block_txns,
                     max_block_bytes,
-                    payload_filter,
+                    pending_payloads.filter,
                     wait_callback,
-                    pending_ordering,
-                    pending_blocks.len(),
+                    pending_payloads.pending_ordering,
+                    pending_payloads.num_pending_blocks,
                     max_fill_fraction,
                 )
                 .await
@@ -0,0 +1,287 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::BlockReader,
+    liveness::{
+        proposal_generator::{ChainHealthBackoffConfig, PendingPayloads},
+        proposer_election::ProposerElection,
+    },
+    payload_manager::PayloadManager,
+    state_replication::PayloadClient,
+    transaction_shuffler::TransactionShuffler,
+    util::time_service::TimeService,
+};
+use anyhow::{ensure, Context, Result};
+use aptos_consensus_types::{
+    block::Block,
+    block_data::BlockData,
+    common::{Author, Round},
+};
+use aptos_executor_types::BlockExecutorTrait;
+use aptos_infallible::RwLock;
+use aptos_types::transaction::{Transaction, TransactionStatus};
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Instant};
+use thiserror::Error;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+#[cfg(test)]
+#[path = "proposal_simulator_test.rs"]
+mod proposal_simulator_test;
+
+/// Report of a proposal simulated on top of the latest committed state.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProposalSimulation {
+    /// Epoch of the simulated proposal
+    pub epoch: u64,
+    /// Round of the simulated proposal, right after the highest quorum cert
+    pub round: Round,
+    /// Max number of transactions the payload was pulled with
+    pub max_block_txns: u64,
+    /// Max size of the payload in bytes
+    pub max_block_bytes: u64,
+    /// Whether the limits were reduced by the chain health backoff
+    pub chain_health_backoff: bool,
+    /// Number of transactions in the payload
+    pub num_txns: u64,
+    /// Size of the payload in bytes
+    pub num_bytes: u64,
+    /// Number of executed transactions that would be committed, including the block metadata and
+    /// state checkpoint transactions
+    pub num_txns_kept: u64,
+    /// Number of executed transactions that would be discarded
+    pub num_txns_discarded: u64,
+    /// Number of executed transactions that would be retried in a later block
+    pub num_txns_retried: u64,
+    /// Gas used by the executed transactions
+    pub gas_used: u64,
+    /// Time to pull the payload and fetch its transactions, in milliseconds
+    pub pull_time_ms: u64,
+    /// Time to execute the block, in milliseconds
+    pub execution_time_ms: u64,
+}
+
+/// Pulls payloads the way the proposal generator of the current epoch does, without proposing
+/// them, and executes them speculatively.
+pub(crate) struct ProposalSimulator {
+    epoch: u64,
+    author: Author,
+    block_store: Arc<dyn BlockReader + Send + Sync>,
+    payload_client: Arc<dyn PayloadClient>,
+    payload_manager: Arc<PayloadManager>,
+    transaction_shuffler: Arc<dyn TransactionShuffler>,
+    // Separate from the one of the round manager, so simulations don't affect its caches
+    proposer_election: Box<dyn ProposerElection + Send + Sync>,
+    time_service: Arc<dyn TimeService>,
+    max_block_txns: u64,
+    max_block_bytes: u64,
+    chain_health_backoff_config: ChainHealthBackoffConfig,
+}
+
+impl ProposalSimulator {
+    pub fn new(
+        epoch: u64,
+        author: Author,
+        block_store: Arc<dyn BlockReader + Send + Sync>,
+        payload_client: Arc<dyn PayloadClient>,
+        payload_manager: Arc<PayloadManager>,
+        transaction_shuffler: Arc<dyn TransactionShuffler>,
+        proposer_election: Box<dyn ProposerElection + Send + Sync>,
+        time_service: Arc<dyn TimeService>,
+        max_block_txns: u64,
+        max_block_bytes: u64,
+        chain_health_backoff_config: ChainHealthBackoffConfig,
+    ) -> Self {
+        Self {
+            epoch,
+            author,
+            block_store,
+            payload_client,
+            payload_manager,
+            transaction_shuffler,
+            proposer_election,
+            time_service,
+            max_block_txns,
+            max_block_bytes,
+            chain_health_backoff_config,
+        }
+    }
+
+    /// Simulates a proposal for the next round, with the given limits instead of the configured
+    /// ones if set. The block is executed on top of the latest committed state by `executor`,
+    /// which must not be used by consensus, as the block is never committed. The executor stays
+    /// locked until the execution is done, even if the simulation is dropped before.
+    pub async fn simulate(
+        &self,
+        executor: LockedExecutor,
+        max_block_txns: Option<u64>,
+        max_block_bytes: Option<u64>,
+    ) -> Result<ProposalSimulation> {
+        let hqc = self.block_store.highest_quorum_cert();
+        ensure!(
+            !hqc.ends_epoch(),
+            "The epoch has already ended, a proposal is not allowed to be generated"
+        );
+        ensure!(
+            !hqc.certified_block().has_reconfiguration(),
+            "Proposals are empty until the reconfiguration is committed"
+        );
+        let round = hqc.certified_block().round() + 1;
+
+        let max_block_txns = max_block_txns.unwrap_or(self.max_block_txns);
+        let max_block_bytes = max_block_bytes.unwrap_or(self.max_block_bytes);
+        let chain_health_backoff = self
+            .chain_health_backoff_config
+            .get_backoff(round, self.proposer_election.as_ref());
+        let (max_block_txns, max_block_bytes) = match chain_health_backoff {
+            Some(value) => (
+                max_block_txns.min(value.max_sending_block_txns_override),
+                max_block_bytes.min(value.max_sending_block_bytes_override),
+            ),
+            None => (max_block_txns, max_block_bytes),
+        };
+
+        let pull_timer = Instant::now();
+        let pending_payloads =
+            PendingPayloads::new(self.block_store.as_ref(), hqc.certified_block().id())?;
+        let max_fill_fraction = pending_payloads.max_fill_fraction(max_block_txns, max_block_bytes);
+        let payload = self
+            .payload_client
+            .pull_payload(
+                max_block_txns,
+                max_block_bytes,
+                pending_payloads.filter,
+                async {}.boxed(),
+                pending_payloads.pending_ordering,
+                pending_payloads.num_pending_blocks,
+                max_fill_fraction,
+            )
+            .await
+            .context("Fail to retrieve payload")?;
+        let num_txns = payload.len() as u64;
+        let num_bytes = payload.size() as u64;
+
+        let timestamp = self.time_service.get_current_timestamp();
+        let block = Block::new_unsigned_proposal_from_block_data(BlockData::new_proposal(
+            payload,
+            self.author,
+            vec![],
+            round,
+            timestamp.as_micros() as u64,
+            hqc.as_ref().clone(),
+        ));
+        self.payload_manager.prefetch_payload_data(&block).await;
+        let txns = self.payload_manager.get_transactions(&block).await?;
+        let txns = self.transaction_shuffler.shuffle(txns);
+        let pull_time_ms = pull_timer.elapsed().as_millis() as u64;
+
+        let block_id = block.id();
+        let transactions_to_execute = block.transactions_to_execute(&[], txns);
+        let (compute_result, execution_time_ms) = tokio::task::spawn_blocking(move || {
+            // Reload the committed state, and release the executed block afterwards
+            executor.reset()?;
+            let parent_block_id = executor.committed_block_id();
+            let execution_timer = Instant::now();
+            let compute_result =
+                executor.execute_block((block_id, transactions_to_execute), parent_block_id);
+            let execution_time_ms = execution_timer.elapsed().as_millis() as u64;
+            executor.finish();
+            Ok::<_, anyhow::Error>((compute_result?, execution_time_ms))
+        })
+        .await??;
+
+        let (mut num_txns_kept, mut num_txns_discarded, mut num_txns_retried) = (0, 0, 0);
+        for status in compute_result.compute_status() {
+            match status {
+                TransactionStatus::Keep(_) => num_txns_kept += 1,
+                TransactionStatus::Discard(_) => num_txns_discarded += 1,
+                TransactionStatus::Retry => num_txns_retried += 1,
+            }
+        }
+        Ok(ProposalSimulation {
+            epoch: self.epoch,
+            round,
+            max_block_txns,
+            max_block_bytes,
+            chain_health_backoff: chain_health_backoff.is_some(),
+            num_txns,
+            num_bytes,
+            num_txns_kept,
+            num_txns_discarded,
+            num_txns_retried,
+            gas_used: compute_result.gas_used(),
+            pull_time_ms,
+            execution_time_ms,
+        })
+    }
+}
+
+/// Error of a proposal simulation requested through the `ProposalSimulationService`
+#[derive(Debug, Error)]
+pub enum ProposalSimulationError {
+    #[error("Proposals can't be simulated: {0}")]
+    Unavailable(&'static str),
+    #[error("Another proposal simulation is in progress")]
+    InProgress,
+    #[error(transparent)]
+    Failed(#[from] anyhow::Error),
+}
+
+/// Executor to simulate proposals with, locked for the duration of a simulation
+pub(crate) type LockedExecutor = OwnedMutexGuard<Arc<dyn BlockExecutorTrait<Transaction>>>;
+
+/// Proposal simulation components of the running node
+#[derive(Default)]
+struct Simulators {
+    executor: Option<Arc<Mutex<Arc<dyn BlockExecutorTrait<Transaction>>>>>,
+    simulator: Option<Arc<ProposalSimulator>>,
+}
+
+/// Simulates proposals of the running validator on demand, e.g., for the inspection service.
+/// It is created before consensus starts, which then provides the executor to simulate proposals
+/// with and the simulator of each epoch. Simulations share the executor, so a simulation
+/// requested while another one is running is rejected rather than queued.
+#[derive(Clone, Default)]
+pub struct ProposalSimulationService {
+    simulators: Arc<RwLock<Simulators>>,
+}
+
+impl ProposalSimulationService {
+    /// Sets the executor to simulate proposals with, distinct from the one used by consensus
+    pub(crate) fn set_executor(&self, executor: Arc<dyn BlockExecutorTrait<Transaction>>) {
+        self.simulators.write().executor = Some(Arc::new(Mutex::new(executor)));
+    }
+
+    /// Sets the simulator of the current epoch, replacing the one of the previous epoch
+    pub(crate) fn set_simulator(&self, simulator: Arc<ProposalSimulator>) {
+        self.simulators.write().simulator = Some(simulator);
+    }
+
+    /// Pulls the payload the running validator would propose in the next round and executes it
+    /// on top of the latest committed state, without proposing it. The configured block limits
+    /// can be overridden, and are reduced by the chain health backoff like for actual proposals.
+    pub async fn simulate_proposal(
+        &self,
+        max_block_txns: Option<u64>,
+        max_block_bytes: Option<u64>,
+    ) -> Result<ProposalSimulation, ProposalSimulationError> {
+        let (executor, simulator) = {
+            let simulators = self.simulators.read();
+            (simulators.executor.clone(), simulators.simulator.clone())
+        };
+        let executor = executor.ok_or(ProposalSimulationError::Unavailable(
+            "consensus is not running",
+        ))?;
+        let simulator = simulator.ok_or(ProposalSimulationError::Unavailable(
+            "no epoch has started yet",
+        ))?;
+        let executor = executor
+            .try_lock_owned()
+            .map_err(|_| ProposalSimulationError::InProgress)?;
+        Ok(simulator
+            .simulate(executor, max_block_txns, max_block_bytes)
+            .await?)
+    }
+}
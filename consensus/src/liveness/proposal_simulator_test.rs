@@ -0,0 +1,220 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::BlockReader,
+    liveness::{
+        proposal_generator::ChainHealthBackoffConfig,
+        proposal_simulator::{
+            LockedExecutor, ProposalSimulationError, ProposalSimulationService, ProposalSimulator,
+        },
+        rotating_proposer_election::RotatingProposer,
+    },
+    payload_manager::PayloadManager,
+    test_utils::{MockPayloadManager, TreeInserter},
+    transaction_shuffler::NoOpShuffler,
+    util::mock_time_service::SimulatedTimeService,
+};
+use anyhow::Result;
+use aptos_config::config::ChainHealthBackoffValues;
+use aptos_consensus_types::block::block_test_utils::certificate_for_genesis;
+use aptos_crypto::HashValue;
+use aptos_executor_types::{BlockExecutorTrait, Error, StateComputeResult};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{ExecutionStatus, Transaction, TransactionStatus},
+};
+use std::sync::Arc;
+
+const GAS_USED_PER_TXN: u64 = 7;
+
+/// Records the executed blocks, and keeps all their transactions
+#[derive(Default)]
+struct RecordingExecutor {
+    executed_blocks: Mutex<Vec<(HashValue, Vec<Transaction>, HashValue)>>,
+}
+
+impl BlockExecutorTrait<Transaction> for RecordingExecutor {
+    fn committed_block_id(&self) -> HashValue {
+        HashValue::zero()
+    }
+
+    fn reset(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn execute_block(
+        &self,
+        block: (HashValue, Vec<Transaction>),
+        parent_block_id: HashValue,
+    ) -> Result<StateComputeResult, Error> {
+        let (block_id, txns) = block;
+        let compute_status = vec![TransactionStatus::Keep(ExecutionStatus::Success); txns.len()];
+        let gas_used = GAS_USED_PER_TXN * txns.len() as u64;
+        self.executed_blocks
+            .lock()
+            .push((block_id, txns, parent_block_id));
+        Ok(StateComputeResult::new(
+            HashValue::zero(),
+            vec![],
+            0,
+            vec![],
+            0,
+            None,
+            compute_status,
+            vec![],
+            vec![],
+            gas_used,
+        ))
+    }
+
+    fn commit_blocks_ext(
+        &self,
+        _block_ids: Vec<HashValue>,
+        _ledger_info_with_sigs: LedgerInfoWithSignatures,
+        _save_state_snapshots: bool,
+    ) -> Result<(), Error> {
+        unreachable!("Simulated proposals are never committed")
+    }
+
+    fn finish(&self) {}
+}
+
+fn lock_executor(executor: Arc<RecordingExecutor>) -> LockedExecutor {
+    Arc::new(tokio::sync::Mutex::new(
+        executor as Arc<dyn BlockExecutorTrait<Transaction>>,
+    ))
+    .try_lock_owned()
+    .unwrap()
+}
+
+async fn create_simulator(
+    chain_health_backoff_config: ChainHealthBackoffConfig,
+) -> (ProposalSimulator, HashValue) {
+    let mut inserter = TreeInserter::default();
+    let block_store = inserter.block_store();
+    let genesis = block_store.ordered_root();
+    let a1 = inserter
+        .insert_block_with_qc(certificate_for_genesis(), &genesis, 1)
+        .await;
+    inserter.insert_qc_for_block(a1.as_ref(), None);
+
+    let author = inserter.signer().author();
+    let simulator = ProposalSimulator::new(
+        1,
+        author,
+        block_store,
+        Arc::new(MockPayloadManager::new(None)),
+        Arc::new(PayloadManager::DirectMempool),
+        Arc::new(NoOpShuffler {}),
+        Box::new(RotatingProposer::new(vec![author], 1)),
+        Arc::new(SimulatedTimeService::new()),
+        100,
+        1000,
+        chain_health_backoff_config,
+    );
+    (simulator, a1.id())
+}
+
+#[tokio::test]
+async fn test_simulate_proposal() {
+    let (simulator, a1_id) = create_simulator(ChainHealthBackoffConfig::new_no_backoff()).await;
+    let executor = Arc::new(RecordingExecutor::default());
+
+    let simulation = simulator
+        .simulate(lock_executor(executor.clone()), None, None)
+        .await
+        .unwrap();
+    // The proposal extends the highest certified block
+    assert_eq!(simulation.round, 2);
+    assert_eq!(simulation.max_block_txns, 100);
+    assert_eq!(simulation.max_block_bytes, 1000);
+    assert!(!simulation.chain_health_backoff);
+    // The mock payload client always returns 10 transactions
+    assert_eq!(simulation.num_txns, 10);
+    assert!(simulation.num_bytes > 0);
+    // The block metadata and state checkpoint transactions are executed along with the payload
+    assert_eq!(simulation.num_txns_kept, 12);
+    assert_eq!(simulation.num_txns_discarded, 0);
+    assert_eq!(simulation.num_txns_retried, 0);
+    assert_eq!(simulation.gas_used, 12 * GAS_USED_PER_TXN);
+
+    // The block is executed on top of the committed block of the simulation executor
+    let executed_blocks = executor.executed_blocks.lock();
+    assert_eq!(executed_blocks.len(), 1);
+    let (block_id, txns, parent_block_id) = &executed_blocks[0];
+    assert_ne!(*block_id, a1_id);
+    assert_eq!(*parent_block_id, HashValue::zero());
+    assert!(matches!(txns.first(), Some(Transaction::BlockMetadata(_))));
+    assert!(matches!(txns.last(), Some(Transaction::StateCheckpoint(id)) if id == block_id));
+}
+
+#[tokio::test]
+async fn test_simulate_proposal_limits() {
+    let (simulator, _) = create_simulator(ChainHealthBackoffConfig::new(vec![
+        ChainHealthBackoffValues {
+            backoff_if_below_participating_voting_power_percentage: 100,
+            max_sending_block_txns_override: 50,
+            max_sending_block_bytes_override: 500,
+        },
+    ]))
+    .await;
+    let executor = Arc::new(RecordingExecutor::default());
+
+    // The chain health backoff reduces the configured limits
+    let simulation = simulator
+        .simulate(lock_executor(executor.clone()), None, None)
+        .await
+        .unwrap();
+    assert!(simulation.chain_health_backoff);
+    assert_eq!(simulation.max_block_txns, 50);
+    assert_eq!(simulation.max_block_bytes, 500);
+
+    // And the limits given instead of the configured ones
+    let simulation = simulator
+        .simulate(lock_executor(executor.clone()), Some(20), Some(5000))
+        .await
+        .unwrap();
+    assert!(simulation.chain_health_backoff);
+    assert_eq!(simulation.max_block_txns, 20);
+    assert_eq!(simulation.max_block_bytes, 500);
+}
+
+#[tokio::test]
+async fn test_proposal_simulation_service() {
+    let service = ProposalSimulationService::default();
+    assert!(matches!(
+        service.simulate_proposal(None, None).await,
+        Err(ProposalSimulationError::Unavailable(_))
+    ));
+
+    let executor = Arc::new(RecordingExecutor::default());
+    service.set_executor(executor.clone());
+    assert!(matches!(
+        service.simulate_proposal(None, None).await,
+        Err(ProposalSimulationError::Unavailable(_))
+    ));
+
+    let (simulator, _) = create_simulator(ChainHealthBackoffConfig::new_no_backoff()).await;
+    service.set_simulator(Arc::new(simulator));
+    service.simulate_proposal(None, None).await.unwrap();
+    assert_eq!(executor.executed_blocks.lock().len(), 1);
+
+    // Simulations requested while the executor is in use are rejected
+    let locked_executor = service
+        .simulators
+        .read()
+        .executor
+        .clone()
+        .unwrap()
+        .try_lock_owned()
+        .unwrap();
+    assert!(matches!(
+        service.simulate_proposal(None, None).await,
+        Err(ProposalSimulationError::InProgress)
+    ));
+    drop(locked_executor);
+    service.simulate_proposal(None, None).await.unwrap();
+    assert_eq!(executor.executed_blocks.lock().len(), 2);
+}
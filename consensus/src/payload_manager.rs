@@ -24,9 +24,11 @@ use tokio::sync::oneshot;
 
 /// Responsible to extract the transactions out of the payload and notify QuorumStore about commits.
 /// If QuorumStore is enabled, has to ask BatchReader for the transaction behind the proofs of availability in the payload.
+/// On consensus observers, the transactions of the batches are received (and cached) along with the blocks.
 pub enum PayloadManager {
     DirectMempool,
     InQuorumStore(Arc<BatchStore<NetworkSender>>, Sender<CoordinatorCommand>),
+    ConsensusObserver,
 }
 
 impl PayloadManager {
@@ -58,7 +60,7 @@ impl PayloadManager {
     ///Pass commit information to BatchReader and QuorumStore wrapper for their internal cleanups.
     pub async fn notify_commit(&self, block_timestamp: u64, payloads: Vec<Payload>) {
         match self {
-            PayloadManager::DirectMempool => {},
+            PayloadManager::DirectMempool | PayloadManager::ConsensusObserver => {},
             PayloadManager::InQuorumStore(batch_store, coordinator_tx) => {
                 // TODO: move this to somewhere in quorum store, so this can be a batch reader
                 batch_store
@@ -101,7 +103,7 @@ impl PayloadManager {
             None => return,
         };
         match self {
-            PayloadManager::DirectMempool => {},
+            PayloadManager::DirectMempool | PayloadManager::ConsensusObserver => {},
             PayloadManager::InQuorumStore(batch_store, _) => match payload {
                 Payload::InQuorumStore(proof_with_status) => {
                     if proof_with_status.status.lock().is_none() {
@@ -205,6 +207,18 @@ impl PayloadManager {
                     },
                 }
             },
+            (PayloadManager::ConsensusObserver, Payload::DirectMempool(txns)) => Ok(txns.clone()),
+            (PayloadManager::ConsensusObserver, Payload::InQuorumStore(proof_with_data)) => {
+                match &*proof_with_data.status.lock() {
+                    Some(DataStatus::Cached(data)) => Ok(data.clone()),
+                    _ => Err(Error::InternalError {
+                        error: format!(
+                            "The transactions of block {} weren't received from the publisher",
+                            block.id()
+                        ),
+                    }),
+                }
+            },
             (_, _) => unreachable!(
                 "Wrong payload {} epoch {}, round {}, id {}",
                 payload,
@@ -274,7 +274,7 @@ impl RecoveryData {
         self.highest_2chain_timeout_certificate.clone()
     }
 
-    fn find_blocks_to_prune(
+    pub(crate) fn find_blocks_to_prune(
         root_id: HashValue,
         blocks: &mut Vec<Block>,
         quorum_certs: &mut Vec<QuorumCert>,
@@ -0,0 +1,208 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::{
+    proof_manager::ProofManagerCommand,
+    quorum_store_db::{QuorumStoreDB, QuorumStoreStorage},
+    types::{PersistedValue, StorageMode},
+};
+use anyhow::{anyhow, Result};
+use aptos_consensus_types::proof_of_store::BatchId;
+use aptos_crypto::HashValue;
+use aptos_infallible::{duration_since_epoch, RwLock};
+use aptos_logger::prelude::*;
+use aptos_types::{transaction::SignedTransaction, PeerId};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path, sync::Arc};
+use tokio::sync::{mpsc, oneshot};
+
+/// Status of the proof of store of a batch, as known by the local proof manager.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofStatus {
+    /// Proofs only live in memory, so their status is unknown without a running quorum store
+    Unknown,
+    /// No proof of store was received for the batch
+    Missing,
+    /// The proof of store is waiting to be included in a block
+    Pending,
+    /// The batch was committed
+    Committed,
+}
+
+/// Summary of a persisted batch.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BatchSummary {
+    /// Digest of the batch
+    pub digest: HashValue,
+    /// Validator that created the batch
+    pub author: PeerId,
+    /// Id of the batch, unique per author
+    pub batch_id: BatchId,
+    /// Epoch the batch was created in
+    pub epoch: u64,
+    /// Expiration of the batch, in microseconds
+    pub expiration: u64,
+    /// Number of transactions in the batch
+    pub num_txns: u64,
+    /// Size of the batch in bytes
+    pub num_bytes: u64,
+    /// Whether the transactions of the batch are persisted, and not only its info
+    pub payload_persisted: bool,
+    /// Whether the batch was expired at the time of inspection
+    pub expired: bool,
+    /// Status of the proof of store of the batch
+    pub proof_status: ProofStatus,
+}
+
+impl BatchSummary {
+    fn new(
+        digest: HashValue,
+        value: &PersistedValue,
+        now_usecs: u64,
+        proof_status: ProofStatus,
+    ) -> Self {
+        Self {
+            digest,
+            author: value.author(),
+            batch_id: value.batch_id(),
+            epoch: value.epoch(),
+            expiration: value.expiration(),
+            num_txns: value.num_txns(),
+            num_bytes: value.num_bytes(),
+            payload_persisted: value.payload_storage_mode() == StorageMode::MemoryAndPersisted,
+            expired: value.expiration() <= now_usecs,
+            proof_status,
+        }
+    }
+}
+
+/// A persisted batch, with its transactions if they were persisted.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportedBatch {
+    /// Summary of the batch
+    pub summary: BatchSummary,
+    /// Transactions of the batch
+    pub transactions: Option<Vec<SignedTransaction>>,
+}
+
+/// Quorum store components of the running node
+#[derive(Default)]
+struct Components {
+    db: Option<Arc<dyn QuorumStoreStorage>>,
+    proof_manager_tx: Option<mpsc::Sender<ProofManagerCommand>>,
+}
+
+/// Inspects the quorum store of the running node on demand, e.g., for the inspection service.
+/// It is created before consensus starts, which then provides the quorum store db and the proof
+/// manager of each epoch.
+#[derive(Clone, Default)]
+pub struct QuorumStoreInspector {
+    components: Arc<RwLock<Components>>,
+}
+
+impl QuorumStoreInspector {
+    pub(crate) fn set_db(&self, db: Arc<dyn QuorumStoreStorage>) {
+        self.components.write().db = Some(db);
+    }
+
+    /// Sets the proof manager of the current epoch, replacing the one of the previous epoch
+    pub(crate) fn set_proof_manager(&self, proof_manager_tx: mpsc::Sender<ProofManagerCommand>) {
+        self.components.write().proof_manager_tx = Some(proof_manager_tx);
+    }
+
+    /// Lists the batches persisted by the running node, ordered by expiration
+    pub async fn list_batches(&self) -> Result<Vec<BatchSummary>> {
+        let db = self
+            .components
+            .read()
+            .db
+            .clone()
+            .ok_or_else(|| anyhow!("Quorum store db is not open"))?;
+        let batches = db.get_all_batches()?;
+        let proof_statuses = self
+            .get_proof_statuses(batches.keys().copied().collect())
+            .await;
+        Ok(summarize(batches, proof_statuses, now_usecs()))
+    }
+
+    async fn get_proof_statuses(&self, digests: Vec<HashValue>) -> Vec<ProofStatus> {
+        let num_digests = digests.len();
+        let proof_manager_tx = self.components.read().proof_manager_tx.clone();
+        let proof_manager_tx = match proof_manager_tx {
+            Some(proof_manager_tx) => proof_manager_tx,
+            None => return vec![ProofStatus::Unknown; num_digests],
+        };
+
+        // Never wait on a full channel, the proof manager is on the critical path of proposals
+        let (response_tx, response_rx) = oneshot::channel();
+        if let Err(error) =
+            proof_manager_tx.try_send(ProofManagerCommand::GetProofStatuses(digests, response_tx))
+        {
+            debug!("Failed to request proof statuses: {}", error);
+            return vec![ProofStatus::Unknown; num_digests];
+        }
+        response_rx
+            .await
+            .unwrap_or_else(|_| vec![ProofStatus::Unknown; num_digests])
+    }
+}
+
+// The proof statuses are in the iteration order of the batches
+fn summarize(
+    batches: HashMap<HashValue, PersistedValue>,
+    proof_statuses: Vec<ProofStatus>,
+    now_usecs: u64,
+) -> Vec<BatchSummary> {
+    let mut summaries: Vec<_> = batches
+        .iter()
+        .zip(proof_statuses)
+        .map(|((digest, value), proof_status)| {
+            BatchSummary::new(*digest, value, now_usecs, proof_status)
+        })
+        .collect();
+    summaries.sort_by_key(|s| (s.expiration, s.digest));
+    summaries
+}
+
+fn now_usecs() -> u64 {
+    duration_since_epoch().as_micros() as u64
+}
+
+/// Lists the batches persisted in the quorum store db in `db_root_path`, ordered by expiration
+pub fn list_batches(db_root_path: &Path) -> Result<Vec<BatchSummary>> {
+    let batches = QuorumStoreDB::open(db_root_path, true)?.get_all_batches()?;
+    let proof_statuses = vec![ProofStatus::Unknown; batches.len()];
+    Ok(summarize(batches, proof_statuses, now_usecs()))
+}
+
+/// Reads the batch with the given digest from the quorum store db in `db_root_path`
+pub fn export_batch(db_root_path: &Path, digest: HashValue) -> Result<Option<ExportedBatch>> {
+    let batch = QuorumStoreDB::open(db_root_path, true)?.get_batch(&digest)?;
+    Ok(batch.map(|mut value| ExportedBatch {
+        summary: BatchSummary::new(digest, &value, now_usecs(), ProofStatus::Unknown),
+        transactions: value.take_payload(),
+    }))
+}
+
+/// Deletes the batches that expired at or before `expiration_cutoff` (in microseconds) from the
+/// quorum store db in `db_root_path`, and returns them. The node has to be stopped, as the db can
+/// only be opened for writing once. Batches are deleted the same way the node does on restart, so
+/// the cutoff should not be later than the timestamp of the latest committed block.
+pub fn garbage_collect_expired_batches(
+    db_root_path: &Path,
+    expiration_cutoff: u64,
+    dry_run: bool,
+) -> Result<Vec<BatchSummary>> {
+    let db = QuorumStoreDB::open(db_root_path, dry_run)?;
+    let batches = db.get_all_batches()?;
+    let proof_statuses = vec![ProofStatus::Unknown; batches.len()];
+    let expired: Vec<_> = summarize(batches, proof_statuses, expiration_cutoff)
+        .into_iter()
+        .filter(|summary| summary.expired)
+        .collect();
+    if !dry_run {
+        db.delete_batches(expired.iter().map(|summary| summary.digest).collect())?;
+    }
+    Ok(expired)
+}
@@ -4,6 +4,8 @@
 pub mod counters;
 /// Equivalent to directly fetching blocks from mempool without a quorum store.
 pub mod direct_mempool_quorum_store;
+/// Inspection and offline maintenance of the persisted batches.
+pub mod inspection;
 
 pub(crate) mod batch_coordinator;
 pub(crate) mod batch_generator;
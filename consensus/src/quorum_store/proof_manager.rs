@@ -3,7 +3,9 @@
 
 use crate::{
     monitor,
-    quorum_store::{batch_generator::BackPressure, counters, utils::ProofQueue},
+    quorum_store::{
+        batch_generator::BackPressure, counters, inspection::ProofStatus, utils::ProofQueue,
+    },
 };
 use aptos_consensus_types::{
     common::{Payload, PayloadFilter, ProofWithData},
@@ -21,6 +23,10 @@ use std::collections::HashSet;
 pub enum ProofManagerCommand {
     ReceiveProof(ProofOfStore),
     CommitNotification(u64, Vec<HashValue>),
+    GetProofStatuses(
+        Vec<HashValue>,
+        tokio::sync::oneshot::Sender<Vec<ProofStatus>>,
+    ),
     Shutdown(tokio::sync::oneshot::Sender<()>),
 }
 
@@ -81,6 +87,10 @@ impl ProofManager {
         self.proofs_for_consensus.mark_committed(digests);
     }
 
+    pub(crate) fn proof_status(&self, digest: &HashValue) -> ProofStatus {
+        self.proofs_for_consensus.proof_status(digest)
+    }
+
     pub(crate) fn handle_proposal_request(&mut self, msg: GetPayloadCommand) {
         match msg {
             // TODO: check what max_txns consensus is using
@@ -178,6 +188,15 @@ impl ProofManager {
                             ProofManagerCommand::ReceiveProof(proof) => {
                                 self.receive_proof(proof);
                             },
+                            ProofManagerCommand::GetProofStatuses(digests, response_tx) => {
+                                let statuses = digests
+                                    .iter()
+                                    .map(|digest| self.proof_status(digest))
+                                    .collect();
+                                if response_tx.send(statuses).is_err() {
+                                    debug!("Proof status receiver not available");
+                                }
+                            },
                             ProofManagerCommand::CommitNotification(block_timestamp, digests) => {
                                 self.handle_commit_notification(block_timestamp, digests);
 
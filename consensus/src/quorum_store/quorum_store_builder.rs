@@ -14,6 +14,7 @@ use crate::{
         batch_store::BatchStore,
         counters,
         direct_mempool_quorum_store::DirectMempoolQuorumStore,
+        inspection::QuorumStoreInspector,
         network_listener::NetworkListener,
         proof_coordinator::{ProofCoordinator, ProofCoordinatorCommand},
         proof_manager::{ProofManager, ProofManagerCommand},
@@ -138,6 +139,7 @@ pub struct InnerBuilder {
     remote_batch_coordinator_cmd_tx: Vec<tokio::sync::mpsc::Sender<BatchCoordinatorCommand>>,
     remote_batch_coordinator_cmd_rx: Vec<tokio::sync::mpsc::Receiver<BatchCoordinatorCommand>>,
     batch_store: Option<Arc<BatchStore<NetworkSender>>>,
+    quorum_store_inspector: QuorumStoreInspector,
 }
 
 impl InnerBuilder {
@@ -154,6 +156,7 @@ impl InnerBuilder {
         verifier: ValidatorVerifier,
         backend: SecureBackend,
         quorum_store_storage: Arc<dyn QuorumStoreStorage>,
+        quorum_store_inspector: QuorumStoreInspector,
     ) -> Self {
         let (coordinator_tx, coordinator_rx) = futures_channel::mpsc::channel(config.channel_size);
         let (batch_generator_cmd_tx, batch_generator_cmd_rx) =
@@ -206,6 +209,7 @@ impl InnerBuilder {
             remote_batch_coordinator_cmd_tx,
             remote_batch_coordinator_cmd_rx,
             batch_store: None,
+            quorum_store_inspector,
         }
     }
 
@@ -330,6 +334,8 @@ impl InnerBuilder {
             )
         );
 
+        self.quorum_store_inspector
+            .set_proof_manager(self.proof_manager_cmd_tx.clone());
         let proof_manager_cmd_rx = self.proof_manager_cmd_rx.take().unwrap();
         let proof_manager = ProofManager::new(
             self.author,
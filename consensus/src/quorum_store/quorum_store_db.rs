@@ -59,6 +59,29 @@ impl QuorumStoreDB {
 
         Self { db }
     }
+
+    /// Opens an existing db, e.g., to inspect it offline. Opening it for writing fails
+    /// while the node holds it open.
+    pub(crate) fn open<P: AsRef<Path>>(db_root_path: P, readonly: bool) -> Result<Self> {
+        let column_families = vec![BATCH_CF_NAME, BATCH_ID_CF_NAME];
+        let path = db_root_path.as_ref().join(QUORUM_STORE_DB_NAME);
+        let db = if readonly {
+            DB::open_cf_readonly(
+                &Options::default(),
+                path,
+                QUORUM_STORE_DB_NAME,
+                column_families,
+            )?
+        } else {
+            DB::open(
+                path,
+                QUORUM_STORE_DB_NAME,
+                column_families,
+                &Options::default(),
+            )?
+        };
+        Ok(Self { db })
+    }
 }
 
 impl QuorumStoreStorage for QuorumStoreDB {
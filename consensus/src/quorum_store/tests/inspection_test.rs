@@ -0,0 +1,145 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::{
+    batch_store::PersistRequest,
+    inspection::{
+        export_batch, garbage_collect_expired_batches, list_batches, ProofStatus,
+        QuorumStoreInspector,
+    },
+    proof_manager::{ProofManager, ProofManagerCommand},
+    quorum_store_db::{QuorumStoreDB, QuorumStoreStorage},
+    tests::utils::create_vec_signed_transactions,
+    types::Batch,
+};
+use aptos_consensus_types::proof_of_store::{BatchId, BatchInfo, ProofOfStore};
+use aptos_crypto::HashValue;
+use aptos_temppath::TempPath;
+use aptos_types::{account_address::AccountAddress, aggregate_signature::AggregateSignature};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+fn save_batches(tmp_dir: &TempPath, expirations: &[u64]) -> Vec<HashValue> {
+    let db = QuorumStoreDB::new(tmp_dir);
+    let source = AccountAddress::random();
+    expirations
+        .iter()
+        .enumerate()
+        .map(|(i, expiration)| {
+            let persist_request: PersistRequest = Batch::new(
+                BatchId::new_for_test(i as u64),
+                create_vec_signed_transactions(10),
+                1,
+                *expiration,
+                source,
+            )
+            .into();
+            db.save_batch(persist_request.digest, persist_request.value)
+                .unwrap();
+            persist_request.digest
+        })
+        .collect()
+}
+
+#[test]
+fn test_list_and_export_batches() {
+    let tmp_dir = TempPath::new();
+    let digests = save_batches(&tmp_dir, &[30, 10, 20]);
+
+    let summaries = list_batches(tmp_dir.path()).unwrap();
+    assert_eq!(
+        summaries.iter().map(|s| s.digest).collect::<Vec<_>>(),
+        vec![digests[1], digests[2], digests[0]]
+    );
+    for summary in &summaries {
+        assert_eq!(summary.num_txns, 10);
+        assert!(summary.payload_persisted);
+        assert!(summary.expired);
+        assert_eq!(summary.proof_status, ProofStatus::Unknown);
+    }
+
+    let exported = export_batch(tmp_dir.path(), digests[0]).unwrap().unwrap();
+    assert_eq!(exported.summary.expiration, 30);
+    assert_eq!(exported.transactions.unwrap().len(), 10);
+    assert!(export_batch(tmp_dir.path(), HashValue::random())
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_list_running_node_batches() {
+    let inspector = QuorumStoreInspector::default();
+    assert!(inspector.list_batches().await.is_err());
+
+    let tmp_dir = TempPath::new();
+    let digests = save_batches(&tmp_dir, &[20, 10]);
+    inspector.set_db(Arc::new(QuorumStoreDB::new(&tmp_dir)));
+    let summaries = inspector.list_batches().await.unwrap();
+    assert_eq!(
+        summaries.iter().map(|s| s.digest).collect::<Vec<_>>(),
+        vec![digests[1], digests[0]]
+    );
+    assert!(summaries
+        .iter()
+        .all(|s| s.proof_status == ProofStatus::Unknown));
+
+    // Once an epoch started, its proof manager provides the proof statuses
+    let (proof_manager_tx, mut proof_manager_rx) = mpsc::channel(1);
+    inspector.set_proof_manager(proof_manager_tx);
+    tokio::spawn(async move {
+        if let Some(ProofManagerCommand::GetProofStatuses(digests, response_tx)) =
+            proof_manager_rx.recv().await
+        {
+            let _ = response_tx.send(vec![ProofStatus::Pending; digests.len()]);
+        }
+    });
+    let summaries = inspector.list_batches().await.unwrap();
+    assert_eq!(summaries.len(), 2);
+    assert!(summaries
+        .iter()
+        .all(|s| s.proof_status == ProofStatus::Pending));
+}
+
+#[test]
+fn test_garbage_collect_expired_batches() {
+    let tmp_dir = TempPath::new();
+    let digests = save_batches(&tmp_dir, &[10, 20, 30]);
+
+    let expired = garbage_collect_expired_batches(tmp_dir.path(), 20, true).unwrap();
+    assert_eq!(expired.iter().map(|s| s.digest).collect::<Vec<_>>(), vec![
+        digests[0], digests[1]
+    ]);
+    assert_eq!(list_batches(tmp_dir.path()).unwrap().len(), 3);
+
+    let expired = garbage_collect_expired_batches(tmp_dir.path(), 20, false).unwrap();
+    assert_eq!(expired.len(), 2);
+    let remaining = list_batches(tmp_dir.path()).unwrap();
+    assert_eq!(
+        remaining.iter().map(|s| s.digest).collect::<Vec<_>>(),
+        vec![digests[2]]
+    );
+}
+
+#[test]
+fn test_proof_status() {
+    let mut proof_manager = ProofManager::new(AccountAddress::random(), 10, 10);
+    let digest = HashValue::random();
+    assert_eq!(proof_manager.proof_status(&digest), ProofStatus::Missing);
+
+    proof_manager.receive_proof(ProofOfStore::new(
+        BatchInfo::new(
+            AccountAddress::random(),
+            BatchId::new_for_test(1),
+            0,
+            10,
+            digest,
+            1,
+            1,
+        ),
+        AggregateSignature::empty(),
+    ));
+    assert_eq!(proof_manager.proof_status(&digest), ProofStatus::Pending);
+
+    proof_manager.handle_commit_notification(5, vec![digest]);
+    assert_eq!(proof_manager.proof_status(&digest), ProofStatus::Committed);
+}
@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{monitor, quorum_store::counters};
+use crate::{
+    monitor,
+    quorum_store::{counters, inspection::ProofStatus},
+};
 use aptos_consensus_types::{common::TransactionSummary, proof_of_store::ProofOfStore};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
@@ -335,6 +338,14 @@ impl ProofQueue {
     }
 
     //mark in the hashmap committed PoS, but keep them until they expire
+    pub(crate) fn proof_status(&self, digest: &HashValue) -> ProofStatus {
+        match self.digest_proof.get(digest) {
+            Some(Some(_)) => ProofStatus::Pending,
+            Some(None) => ProofStatus::Committed,
+            None => ProofStatus::Missing,
+        }
+    }
+
     pub(crate) fn mark_committed(&mut self, digests: Vec<HashValue>) {
         for digest in digests {
             self.digest_proof.insert(digest, None);
@@ -50,6 +50,7 @@ use aptos_types::{
 };
 use fail::fail_point;
 use futures::{channel::oneshot, FutureExt, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::Serialize;
 use std::{
     mem::{discriminant, Discriminant},
@@ -206,6 +207,8 @@ pub struct RoundManager {
     round_manager_tx:
         aptos_channel::Sender<(Author, Discriminant<VerifiedEvent>), (Author, VerifiedEvent)>,
     local_config: ConsensusConfig,
+    // Seeds the block retrievers
+    block_retriever_rng: Mutex<StdRng>,
 }
 
 impl RoundManager {
@@ -245,9 +248,16 @@ impl RoundManager {
             onchain_config,
             round_manager_tx,
             local_config,
+            block_retriever_rng: Mutex::new(StdRng::from_entropy()),
         }
     }
 
+    /// Seeds the choice of the peers to retrieve blocks from, so that runs can be reproduced
+    #[cfg(test)]
+    pub fn set_block_retriever_seed(&mut self, seed: u64) {
+        self.block_retriever_rng = Mutex::new(StdRng::seed_from_u64(seed));
+    }
+
     fn decoupled_execution(&self) -> bool {
         self.onchain_config.decoupled_execution()
     }
@@ -262,6 +272,7 @@ impl RoundManager {
                 .get_ordered_account_addresses_iter()
                 .collect(),
         )
+        .with_seed(self.block_retriever_rng.lock().gen())
     }
 
     /// Leader:
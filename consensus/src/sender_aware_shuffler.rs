@@ -75,6 +75,7 @@ impl TransactionShuffler for SenderAwareShuffler {
 }
 
 impl SenderAwareShuffler {
+    /// Creates a shuffler that spreads out the transactions of a sender over `conflict_window_size`
     pub fn new(conflict_window_size: usize) -> Self {
         Self {
             conflict_window_size,
@@ -0,0 +1,645 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::BlockReader,
+    network::IncomingBlockRetrievalRequest,
+    network_interface::ConsensusMsg,
+    simulator::{
+        node::SimulatedNode,
+        scenario::{Fault, Scenario},
+    },
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use aptos_channels::aptos_channel;
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_consensus_types::{
+    block::Block,
+    common::{Author, Round},
+};
+use aptos_crypto::{bls12381, HashValue, Uniform};
+use aptos_logger::prelude::*;
+use aptos_network::{
+    application::storage::PeersAndMetadata,
+    peer_manager::PeerManagerRequest,
+    protocols::{
+        network::{Event, SerializedRequest},
+        rpc::OutboundRpcRequest,
+        wire::handshake::v1::ProtocolIdSet,
+    },
+    transport::ConnectionMetadata,
+    ProtocolId,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    ledger_info::LedgerInfo,
+    validator_signer::ValidatorSigner,
+    validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
+    waypoint::Waypoint,
+    PeerId,
+};
+use futures::{FutureExt, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    future::Future,
+    iter::FromIterator,
+    sync::Arc,
+    task::Poll,
+    time::Duration,
+};
+
+/// The simulated time at which simulations start (proposals need a later timestamp
+/// than the genesis block)
+const START_TIME: Duration = Duration::from_secs(1);
+
+/// The number of consecutive polls without any progress after which a node that is
+/// processing an event is considered stuck
+const MAX_IDLE_POLLS: usize = 10_000;
+
+/// The network conditions set up by the scenario steps applied so far
+#[derive(Default)]
+struct NetworkConditions {
+    partition: Option<Vec<Vec<usize>>>,
+    drop_probability: f64,
+    delays: Vec<(Option<usize>, Option<usize>, Duration)>,
+    jitter: Duration,
+}
+
+impl NetworkConditions {
+    fn is_connected(&self, from: usize, to: usize) -> bool {
+        from == to
+            || self.partition.as_ref().map_or(true, |groups| {
+                groups
+                    .iter()
+                    .any(|group| group.contains(&from) && group.contains(&to))
+            })
+    }
+
+    fn extra_delay(&self, from: usize, to: usize) -> Duration {
+        self.delays
+            .iter()
+            .filter(|(delay_from, delay_to, _)| {
+                delay_from.map_or(true, |node| node == from)
+                    && delay_to.map_or(true, |node| node == to)
+            })
+            .map(|(_, _, delay)| *delay)
+            .sum()
+    }
+}
+
+/// An input processed by the round manager of a node
+enum NodeInput {
+    Message(Author, ConsensusMsg),
+    Timeout(Round),
+}
+
+struct InFlightMessage {
+    from: usize,
+    to: usize,
+    message: ConsensusMsg,
+}
+
+/// A block committed by a node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit {
+    /// The simulated time (since the start of the simulation) of the commit
+    pub time: Duration,
+    pub node: usize,
+    pub round: Round,
+    pub block_id: HashValue,
+}
+
+/// The outcome of a successful simulation
+#[derive(Debug, PartialEq, Eq)]
+pub struct SimulationReport {
+    /// All commits, in the order they happened
+    pub commits: Vec<Commit>,
+    pub num_delivered_messages: usize,
+    pub num_dropped_messages: usize,
+}
+
+/// A violated safety or liveness invariant, with what is needed to replay the simulation
+#[derive(Debug)]
+pub struct SimulationFailure {
+    pub seed: u64,
+    pub scenario: Scenario,
+    /// The simulated time (since the start of the simulation) of the failure
+    pub time: Duration,
+    pub reason: String,
+}
+
+impl fmt::Display for SimulationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Simulation failed after {:?}: {}\nReplay it with seed {} and the scenario:\n{}",
+            self.time, self.reason, self.seed, self.scenario
+        )
+    }
+}
+
+/// Runs consensus nodes over a simulated network, following a scenario.
+///
+/// The simulation is single-threaded and driven by a simulated clock shared by all
+/// nodes: the simulator repeatedly picks the earliest event (a scenario step, a
+/// round timeout or a message delivery) and has the affected round manager process
+/// it. Message latencies, drops and the peers that nodes retrieve blocks from are
+/// drawn from an RNG seeded with the simulation seed, so a seed and a scenario
+/// reproduce the same run. Block retrieval RPCs are answered synchronously (unless
+/// the peer is unreachable).
+pub struct Simulator {
+    scenario: Scenario,
+    seed: u64,
+    rng: StdRng,
+    time_service: SimulatedTimeService,
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    nodes: Vec<SimulatedNode>,
+    node_indices: HashMap<Author, usize>,
+    conditions: NetworkConditions,
+    /// The messages in flight, by delivery time and send order
+    in_flight: BTreeMap<(Duration, u64), InFlightMessage>,
+    num_sent_messages: u64,
+    /// The round and id of the last block committed by each node
+    last_commits: Vec<Option<(Round, HashValue)>>,
+    /// The blocks committed by any node, by round
+    committed_blocks: BTreeMap<Round, HashValue>,
+    report: SimulationReport,
+}
+
+impl Simulator {
+    pub async fn new(scenario: Scenario, seed: u64) -> Self {
+        scenario
+            .validate()
+            .unwrap_or_else(|e| panic!("Invalid scenario: {}", e));
+        let num_nodes = scenario.num_nodes;
+
+        // Derive the validators from their index, so that the blocks (and their ids)
+        // only depend on the seed and the scenario
+        let signers: Vec<_> = (0..num_nodes)
+            .map(|index| {
+                let mut address = [0; AccountAddress::LENGTH];
+                address[AccountAddress::LENGTH - 1] = index as u8 + 1;
+                let mut rng = StdRng::from_seed([index as u8; 32]);
+                ValidatorSigner::new(
+                    AccountAddress::new(address),
+                    bls12381::PrivateKey::generate(&mut rng),
+                )
+            })
+            .collect();
+        let validators = ValidatorVerifier::new(
+            signers
+                .iter()
+                .map(|signer| ValidatorConsensusInfo::new(signer.author(), signer.public_key(), 1))
+                .collect(),
+        );
+        let waypoint =
+            Waypoint::new_epoch_boundary(&LedgerInfo::mock_genesis(Some((&validators).into())))
+                .unwrap();
+        let proposers: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+
+        let peers_and_metadata = PeersAndMetadata::new(&[NetworkId::Validator]);
+        for signer in &signers {
+            let mut connection_metadata = ConnectionMetadata::mock(signer.author());
+            connection_metadata.application_protocols = ProtocolIdSet::from_iter([
+                ProtocolId::ConsensusDirectSendJson,
+                ProtocolId::ConsensusDirectSendBcs,
+                ProtocolId::ConsensusRpcBcs,
+            ]);
+            peers_and_metadata
+                .insert_connection_metadata(
+                    PeerNetworkId::new(NetworkId::Validator, signer.author()),
+                    connection_metadata,
+                )
+                .unwrap();
+        }
+
+        let time_service = SimulatedTimeService::new();
+        time_service.advance_to(START_TIME);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut nodes = vec![];
+        for (id, signer) in signers.into_iter().enumerate() {
+            nodes.push(
+                SimulatedNode::start(
+                    id,
+                    signer,
+                    proposers.clone(),
+                    &validators,
+                    waypoint,
+                    time_service.clone(),
+                    peers_and_metadata.clone(),
+                    rng.gen(),
+                )
+                .await,
+            );
+        }
+        let node_indices = nodes.iter().map(|node| (node.author(), node.id)).collect();
+
+        Self {
+            scenario,
+            seed,
+            rng,
+            time_service,
+            peers_and_metadata,
+            nodes,
+            node_indices,
+            conditions: NetworkConditions::default(),
+            in_flight: BTreeMap::new(),
+            num_sent_messages: 0,
+            last_commits: vec![None; num_nodes],
+            committed_blocks: BTreeMap::new(),
+            report: SimulationReport {
+                commits: vec![],
+                num_delivered_messages: 0,
+                num_dropped_messages: 0,
+            },
+        }
+    }
+
+    /// Runs the scenario, and checks that no two nodes commit conflicting blocks, and
+    /// that every live node makes progress after the last step of the scenario
+    pub async fn run(mut self) -> Result<SimulationReport, SimulationFailure> {
+        let end_time = START_TIME + self.scenario.duration;
+        let mut next_step = 0;
+        let mut stable_rounds = if self.scenario.steps.is_empty() {
+            Some(self.committed_rounds())
+        } else {
+            None
+        };
+
+        self.settle().await?;
+        loop {
+            let step_time = self
+                .scenario
+                .steps
+                .get(next_step)
+                .map(|step| START_TIME + step.at);
+            let timer_time = self.time_service.next_deadline();
+            let message_time = self.in_flight.keys().next().map(|(time, _)| *time);
+            let time = match [step_time, timer_time, message_time]
+                .into_iter()
+                .flatten()
+                .min()
+            {
+                Some(time) if time <= end_time => time,
+                _ => break,
+            };
+
+            // Advancing the time fires the due round timeouts
+            self.time_service.advance_to(time);
+            if step_time == Some(time) {
+                let fault = self.scenario.steps[next_step].fault.clone();
+                self.apply(fault).await;
+                next_step += 1;
+                if next_step == self.scenario.steps.len() {
+                    stable_rounds = Some(self.committed_rounds());
+                }
+            } else if message_time == Some(time) {
+                let (_, message) = self.in_flight.pop_first().unwrap();
+                self.deliver(message).await;
+            }
+            self.settle().await?;
+        }
+
+        if let Some(stable_rounds) = stable_rounds {
+            let end_rounds = self.committed_rounds();
+            for (node, (stable_round, end_round)) in
+                stable_rounds.into_iter().zip(end_rounds).enumerate()
+            {
+                if let (Some(stable_round), Some(end_round)) = (stable_round, end_round) {
+                    if end_round < stable_round + self.scenario.expected_progress {
+                        return Err(self.failure(format!(
+                            "Node {} only advanced its committed round from {} to {} after the last step",
+                            node, stable_round, end_round
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(self.report)
+    }
+
+    fn now(&self) -> Duration {
+        self.time_service.get_current_timestamp()
+    }
+
+    fn failure(&self, reason: String) -> SimulationFailure {
+        SimulationFailure {
+            seed: self.seed,
+            scenario: self.scenario.clone(),
+            time: self.now().saturating_sub(START_TIME),
+            reason,
+        }
+    }
+
+    /// Returns the highest committed round of every live node
+    fn committed_rounds(&self) -> Vec<Option<Round>> {
+        self.nodes
+            .iter()
+            .map(|node| {
+                node.instance
+                    .as_ref()
+                    .map(|instance| instance.block_store.commit_root().round())
+            })
+            .collect()
+    }
+
+    async fn apply(&mut self, fault: Fault) {
+        info!("[simulator] Applying step: {}", fault);
+        match fault {
+            Fault::Partition(groups) => self.conditions.partition = Some(groups),
+            Fault::Drop(probability) => self.conditions.drop_probability = probability,
+            Fault::Delay { from, to, delay } => self.conditions.delays.push((from, to, delay)),
+            Fault::Reorder(jitter) => self.conditions.jitter = jitter,
+            Fault::Crash(node) => self.nodes[node].crash(),
+            Fault::Restart(node) => {
+                let block_retriever_seed = self.rng.gen();
+                self.nodes[node]
+                    .restart(
+                        self.time_service.clone(),
+                        self.peers_and_metadata.clone(),
+                        block_retriever_seed,
+                    )
+                    .await
+            },
+            Fault::Heal => self.conditions = NetworkConditions::default(),
+        }
+    }
+
+    /// Processes the local work of every node: fired round timeouts, ordered blocks
+    /// and sent messages
+    async fn settle(&mut self) -> Result<(), SimulationFailure> {
+        for index in 0..self.nodes.len() {
+            while let Some(round) = self.nodes[index]
+                .instance
+                .as_mut()
+                .and_then(|instance| instance.timeout_receiver.next().now_or_never().flatten())
+            {
+                self.process(index, NodeInput::Timeout(round)).await;
+            }
+
+            while let Some(ordered_blocks) =
+                self.nodes[index].instance.as_mut().and_then(|instance| {
+                    instance
+                        .ordered_blocks_receiver
+                        .next()
+                        .now_or_never()
+                        .flatten()
+                })
+            {
+                for block in &ordered_blocks.ordered_blocks {
+                    self.record_commit(index, block.block())
+                        .map_err(|reason| self.failure(reason))?;
+                }
+                // The ordered blocks are committed right away
+                let state_computer = self.nodes[index]
+                    .instance
+                    .as_ref()
+                    .unwrap()
+                    .state_computer
+                    .clone();
+                state_computer
+                    .commit_to_storage(ordered_blocks)
+                    .await
+                    .unwrap();
+            }
+
+            while let Some(event) = self.nodes[index]
+                .instance
+                .as_mut()
+                .and_then(|instance| instance.self_receiver.next().now_or_never().flatten())
+            {
+                if let Event::Message(_, message) = event {
+                    self.send(index, index, message);
+                }
+            }
+
+            if let Some(mut instance) = self.nodes[index].instance.take() {
+                self.flush_outbound(index, &mut instance.network_reqs_rx)
+                    .await;
+                self.nodes[index].instance = Some(instance);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the block doesn't conflict with the blocks committed so far
+    fn record_commit(&mut self, node: usize, block: &Block) -> Result<(), String> {
+        let (round, block_id) = (block.round(), block.id());
+        let parent_round = block.quorum_cert().certified_block().round();
+        if let Some(committed_id) = self.committed_blocks.get(&round) {
+            if *committed_id != block_id {
+                return Err(format!(
+                    "Node {} committed block {} at round {}, but block {} was committed at that round",
+                    node, block_id, round, committed_id
+                ));
+            }
+        }
+        if let Some(committed_parent_id) = self.committed_blocks.get(&parent_round) {
+            if *committed_parent_id != block.parent_id() {
+                return Err(format!(
+                    "Node {} committed block {} at round {}, which doesn't extend the committed block {} at round {}",
+                    node, block_id, round, committed_parent_id, parent_round
+                ));
+            }
+        }
+        if let Some((last_round, last_block_id)) = self.last_commits[node] {
+            // The node may have synced past its last committed block, but it must never
+            // commit a block that doesn't extend it
+            if parent_round < last_round
+                || (parent_round == last_round && block.parent_id() != last_block_id)
+            {
+                return Err(format!(
+                    "Node {} committed block {} at round {}, which doesn't extend its last committed block {} at round {}",
+                    node, block_id, round, last_block_id, last_round
+                ));
+            }
+        }
+
+        self.committed_blocks.insert(round, block_id);
+        self.last_commits[node] = Some((round, block_id));
+        self.report.commits.push(Commit {
+            time: self.now().saturating_sub(START_TIME),
+            node,
+            round,
+            block_id,
+        });
+        Ok(())
+    }
+
+    async fn deliver(&mut self, in_flight_message: InFlightMessage) {
+        let InFlightMessage { from, to, message } = in_flight_message;
+        // Messages are also lost if the link goes down while they're in flight
+        if !self.nodes[to].is_up() || !self.conditions.is_connected(from, to) {
+            self.report.num_dropped_messages += 1;
+            return;
+        }
+        self.report.num_delivered_messages += 1;
+        let author = self.nodes[from].author();
+        self.process(to, NodeInput::Message(author, message)).await;
+    }
+
+    async fn process(&mut self, index: usize, input: NodeInput) {
+        let mut instance = match self.nodes[index].instance.take() {
+            Some(instance) => instance,
+            None => return,
+        };
+        let round_manager = &mut instance.round_manager;
+        let network_reqs_rx = &mut instance.network_reqs_rx;
+        let result = match input {
+            NodeInput::Message(_, ConsensusMsg::ProposalMsg(proposal)) => {
+                self.drive(
+                    index,
+                    network_reqs_rx,
+                    round_manager.process_proposal_msg(*proposal),
+                )
+                .await
+            },
+            NodeInput::Message(_, ConsensusMsg::VoteMsg(vote)) => {
+                self.drive(
+                    index,
+                    network_reqs_rx,
+                    round_manager.process_vote_msg(*vote),
+                )
+                .await
+            },
+            NodeInput::Message(author, ConsensusMsg::SyncInfo(sync_info)) => {
+                self.drive(
+                    index,
+                    network_reqs_rx,
+                    round_manager.process_sync_info_msg(*sync_info, author),
+                )
+                .await
+            },
+            NodeInput::Timeout(round) => {
+                self.drive(
+                    index,
+                    network_reqs_rx,
+                    round_manager.process_local_timeout(round),
+                )
+                .await
+            },
+            // The buffer manager and the quorum store aren't simulated
+            NodeInput::Message(_, _) => Ok(()),
+        };
+        if let Err(error) = result {
+            debug!(
+                "[simulator] Node {} failed to process an event: {:?}",
+                index, error
+            );
+        }
+        self.nodes[index].instance = Some(instance);
+    }
+
+    /// Polls the future of a node until it completes, answering the RPCs the node
+    /// sends in the meantime
+    async fn drive(
+        &mut self,
+        index: usize,
+        network_reqs_rx: &mut aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+        future: impl Future<Output = anyhow::Result<()>>,
+    ) -> anyhow::Result<()> {
+        futures::pin_mut!(future);
+        let mut idle_polls = 0;
+        loop {
+            if let Poll::Ready(result) = futures::poll!(future.as_mut()) {
+                return result;
+            }
+            if self.flush_outbound(index, network_reqs_rx).await {
+                idle_polls = 0;
+            } else {
+                idle_polls += 1;
+                assert!(idle_polls < MAX_IDLE_POLLS, "Node {} is stuck", index);
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+
+    /// Sends the pending messages of a node, and answers its pending RPCs. Returns
+    /// whether there was anything to send.
+    async fn flush_outbound(
+        &mut self,
+        index: usize,
+        network_reqs_rx: &mut aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+    ) -> bool {
+        let mut flushed = false;
+        while let Some(Some(request)) = network_reqs_rx.next().now_or_never() {
+            flushed = true;
+            match request {
+                PeerManagerRequest::SendDirectSend(peer, message) => {
+                    let message = message
+                        .to_message()
+                        .expect("Nodes should only send consensus messages");
+                    self.send(index, self.node_indices[&peer], message);
+                },
+                PeerManagerRequest::SendRpc(peer, request) => {
+                    self.answer_rpc(index, self.node_indices[&peer], request)
+                        .await
+                },
+            }
+        }
+        flushed
+    }
+
+    fn send(&mut self, from: usize, to: usize, message: ConsensusMsg) {
+        let dropped = self.rng.gen_bool(self.conditions.drop_probability);
+        let latency = self.random_duration(self.scenario.min_latency, self.scenario.max_latency);
+        let jitter = self.random_duration(Duration::ZERO, self.conditions.jitter);
+
+        // Messages to self are delivered right away
+        let delay = if from == to {
+            Duration::ZERO
+        } else if dropped || !self.conditions.is_connected(from, to) {
+            self.report.num_dropped_messages += 1;
+            return;
+        } else {
+            latency + jitter + self.conditions.extra_delay(from, to)
+        };
+        self.in_flight.insert(
+            (self.now() + delay, self.num_sent_messages),
+            InFlightMessage { from, to, message },
+        );
+        self.num_sent_messages += 1;
+    }
+
+    async fn answer_rpc(&mut self, from: usize, to: usize, request: OutboundRpcRequest) {
+        // Dropping the response sender fails the RPC right away
+        let dropped = self.rng.gen_bool(self.conditions.drop_probability);
+        let block_store = match &self.nodes[to].instance {
+            Some(instance) if !dropped && self.conditions.is_connected(from, to) => {
+                instance.block_store.clone()
+            },
+            _ => {
+                self.report.num_dropped_messages += 1;
+                return;
+            },
+        };
+        match request.to_message() {
+            Ok(ConsensusMsg::BlockRetrievalRequest(retrieval_request)) => {
+                self.report.num_delivered_messages += 1;
+                let _ = block_store
+                    .process_block_retrieval(IncomingBlockRetrievalRequest {
+                        req: *retrieval_request,
+                        protocol: request.protocol_id,
+                        response_sender: request.res_tx,
+                    })
+                    .await;
+            },
+            message => warn!(
+                "[simulator] Unexpected RPC from node {}: {:?}",
+                from, message
+            ),
+        }
+    }
+
+    fn random_duration(&mut self, min: Duration, max: Duration) -> Duration {
+        if max <= min {
+            return min;
+        }
+        Duration::from_micros(
+            self.rng
+                .gen_range(min.as_micros() as u64, max.as_micros() as u64 + 1),
+        )
+    }
+}
@@ -0,0 +1,22 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic simulator of consensus nodes.
+//!
+//! Unlike the twins tests (which run every node on its own runtime), the simulator
+//! drives the round managers of all nodes from a single task, over a simulated
+//! network and clock. Partitions, drops, delays, reorderings and crashes are
+//! scripted with a [`Scenario`], and every run is checked for safety (no conflicting
+//! commits) and liveness (progress after the last step). A failing run reports its
+//! seed and scenario, which replay it.
+//!
+//! Run the random scenarios with a specific seed:
+//! CONSENSUS_SIM_SEED=<seed> cargo xtest -p aptos-consensus random_scenarios_test -- --nocapture
+
+mod harness;
+mod node;
+mod scenario;
+mod simulator_test;
+
+pub use harness::{Commit, SimulationFailure, SimulationReport, Simulator};
+pub use scenario::{Fault, Scenario, Step};
@@ -0,0 +1,267 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block_storage::BlockStore,
+    error::QuorumStoreError,
+    experimental::buffer_manager::OrderedBlocks,
+    liveness::{
+        proposal_generator::{ChainHealthBackoffConfig, ProposalGenerator},
+        rotating_proposer_election::RotatingProposer,
+        round_state::{ExponentialTimeInterval, RoundState},
+    },
+    metrics_safety_rules::MetricsSafetyRules,
+    network::NetworkSender,
+    network_interface::{ConsensusMsg, ConsensusNetworkClient, DIRECT_SEND, RPC},
+    payload_manager::PayloadManager,
+    persistent_liveness_storage::RecoveryData,
+    round_manager::RoundManager,
+    state_replication::PayloadClient,
+    test_utils::{MockStateComputer, MockStorage},
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
+use aptos_config::{config::ConsensusConfig, network_id::NetworkId};
+use aptos_consensus_types::common::{Author, Payload, PayloadFilter, Round};
+use aptos_infallible::Mutex;
+use aptos_network::{
+    application::{interface::NetworkClient, storage::PeersAndMetadata},
+    peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+    protocols::network::{self, Event},
+    ProtocolId,
+};
+use aptos_safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
+use aptos_secure_storage::Storage;
+use aptos_types::{
+    epoch_state::EpochState, on_chain_config::OnChainConsensusConfig,
+    transaction::SignedTransaction, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier, waypoint::Waypoint, PeerId,
+};
+use futures::{channel::mpsc, future::BoxFuture};
+use maplit::hashmap;
+use std::{sync::Arc, time::Duration};
+
+/// The initial round timeout of the simulated nodes
+const ROUND_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Proposes empty payloads, so that the proposed blocks only depend on the simulation
+struct EmptyPayloadClient;
+
+#[async_trait::async_trait]
+impl PayloadClient for EmptyPayloadClient {
+    async fn pull_payload(
+        &self,
+        _max_items: u64,
+        _max_bytes: u64,
+        _exclude: PayloadFilter,
+        _wait_callback: BoxFuture<'static, ()>,
+        _pending_ordering: bool,
+        _pending_uncommitted_blocks: usize,
+        _recent_max_fill_fraction: f32,
+    ) -> Result<Payload, QuorumStoreError> {
+        Ok(Payload::empty(false))
+    }
+}
+
+/// The in-memory state of a running node, which is lost when the node crashes
+pub struct NodeInstance {
+    pub round_manager: RoundManager,
+    pub block_store: Arc<BlockStore>,
+    pub state_computer: Arc<MockStateComputer>,
+    /// The messages and RPCs sent by the node to other nodes
+    pub network_reqs_rx: aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+    /// The messages sent by the node to itself
+    pub self_receiver: aptos_channels::Receiver<Event<ConsensusMsg>>,
+    pub timeout_receiver: aptos_channels::Receiver<Round>,
+    pub ordered_blocks_receiver: mpsc::UnboundedReceiver<OrderedBlocks>,
+    _state_sync_receiver: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
+}
+
+/// A simulated consensus node. Its consensus DB and safety data survive crashes.
+pub struct SimulatedNode {
+    pub id: usize,
+    signer: ValidatorSigner,
+    proposers: Vec<Author>,
+    storage: Arc<MockStorage>,
+    safety_rules_manager: SafetyRulesManager,
+    /// The running instance of the node (`None` while the node is crashed)
+    pub instance: Option<NodeInstance>,
+}
+
+impl SimulatedNode {
+    pub async fn start(
+        id: usize,
+        signer: ValidatorSigner,
+        proposers: Vec<Author>,
+        validators: &ValidatorVerifier,
+        waypoint: Waypoint,
+        time_service: SimulatedTimeService,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        block_retriever_seed: u64,
+    ) -> Self {
+        let (initial_data, storage) = MockStorage::start_for_testing(validators.into());
+        let safety_storage = PersistentSafetyStorage::initialize(
+            Storage::from(aptos_secure_storage::InMemoryStorage::new()),
+            signer.author(),
+            signer.private_key().clone(),
+            waypoint,
+            true,
+        );
+        let mut node = Self {
+            id,
+            signer,
+            proposers,
+            storage,
+            safety_rules_manager: SafetyRulesManager::new_local(safety_storage),
+            instance: None,
+        };
+        node.start_instance(
+            initial_data,
+            time_service,
+            peers_and_metadata,
+            block_retriever_seed,
+        )
+        .await;
+        node
+    }
+
+    pub fn author(&self) -> Author {
+        self.signer.author()
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.instance.is_some()
+    }
+
+    pub fn crash(&mut self) {
+        self.instance = None;
+    }
+
+    /// Restarts the node from its consensus DB (if it is crashed)
+    pub async fn restart(
+        &mut self,
+        time_service: SimulatedTimeService,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        block_retriever_seed: u64,
+    ) {
+        if self.is_up() {
+            return;
+        }
+        let recovery_data = self
+            .storage
+            .try_start()
+            .unwrap_or_else(|e| panic!("Failed to restart node {}: {}", self.id, e));
+        self.start_instance(
+            recovery_data,
+            time_service,
+            peers_and_metadata,
+            block_retriever_seed,
+        )
+        .await;
+    }
+
+    async fn start_instance(
+        &mut self,
+        initial_data: RecoveryData,
+        time_service: SimulatedTimeService,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        block_retriever_seed: u64,
+    ) {
+        let epoch_state = EpochState {
+            epoch: 1,
+            verifier: self.storage.get_validator_set().into(),
+        };
+        let validators = epoch_state.verifier.clone();
+        let time_service: Arc<dyn TimeService> = Arc::new(time_service);
+        let author = self.author();
+
+        // The queues are large enough to never drop the messages of a single event
+        let (network_reqs_tx, network_reqs_rx) =
+            aptos_channel::new(QueueStyle::FIFO, 1_024, None);
+        let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let network_sender = network::NetworkSender::new(
+            PeerManagerRequestSender::new(network_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        );
+        let network_client = NetworkClient::new(
+            DIRECT_SEND.into(),
+            RPC.into(),
+            hashmap! {NetworkId::Validator => network_sender},
+            peers_and_metadata,
+        );
+        let (self_sender, self_receiver) = aptos_channels::new_test(1_024);
+        let network = NetworkSender::new(
+            author,
+            ConsensusNetworkClient::new(network_client),
+            self_sender,
+            validators,
+        );
+
+        let last_vote_sent = initial_data.last_vote();
+        let (ordered_blocks_tx, ordered_blocks_receiver) = mpsc::unbounded::<OrderedBlocks>();
+        let (state_sync_client, _state_sync_receiver) = mpsc::unbounded();
+        let state_computer = Arc::new(MockStateComputer::new(
+            state_sync_client,
+            ordered_blocks_tx,
+            self.storage.clone(),
+        ));
+        let block_store = Arc::new(BlockStore::new(
+            self.storage.clone(),
+            initial_data,
+            state_computer.clone(),
+            10, // max pruned blocks in mem
+            time_service.clone(),
+            10,
+            Arc::from(PayloadManager::DirectMempool),
+        ));
+
+        let proposal_generator = ProposalGenerator::new(
+            author,
+            block_store.clone(),
+            Arc::new(EmptyPayloadClient),
+            time_service.clone(),
+            10,
+            1000,
+            10,
+            ChainHealthBackoffConfig::new_no_backoff(),
+            false,
+        );
+        let (timeout_sender, timeout_receiver) = aptos_channels::new_test(1_024);
+        let round_state = RoundState::new(
+            Box::new(ExponentialTimeInterval::new(ROUND_TIMEOUT, 1.2, 6)),
+            time_service,
+            timeout_sender,
+        );
+        let mut safety_rules =
+            MetricsSafetyRules::new(self.safety_rules_manager.client(), self.storage.clone());
+        safety_rules.perform_initialize().unwrap();
+        let (round_manager_tx, _) = aptos_channel::new(QueueStyle::LIFO, 1, None);
+
+        let mut round_manager = RoundManager::new(
+            epoch_state,
+            block_store.clone(),
+            round_state,
+            Box::new(RotatingProposer::new(self.proposers.clone(), 1)),
+            proposal_generator,
+            Arc::new(Mutex::new(safety_rules)),
+            network,
+            self.storage.clone(),
+            OnChainConsensusConfig::default(),
+            round_manager_tx,
+            ConsensusConfig::default(),
+        );
+        round_manager.set_block_retriever_seed(block_retriever_seed);
+        round_manager.init(last_vote_sent).await;
+
+        self.instance = Some(NodeInstance {
+            round_manager,
+            block_store,
+            state_computer,
+            network_reqs_rx,
+            self_receiver,
+            timeout_receiver,
+            ordered_blocks_receiver,
+            _state_sync_receiver,
+        });
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, format_err, Context};
+use rand::{rngs::StdRng, Rng};
+use std::{fmt, str::FromStr, time::Duration};
+
+/// A change of the network conditions (or of a node) at some point of a simulation
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fault {
+    /// Splits the nodes into groups that can only reach the nodes of their own group.
+    /// Nodes that aren't in any group are isolated.
+    Partition(Vec<Vec<usize>>),
+    /// Drops every message with the given probability
+    Drop(f64),
+    /// Delays the messages from `from` to `to` (`None` matches every node)
+    Delay {
+        from: Option<usize>,
+        to: Option<usize>,
+        delay: Duration,
+    },
+    /// Delays every message by a random duration of up to the given jitter, so that
+    /// messages overtake each other
+    Reorder(Duration),
+    /// Stops the node, dropping all its in-memory state
+    Crash(usize),
+    /// Restarts a crashed node from its persisted storage
+    Restart(usize),
+    /// Removes all partitions, drops, delays and reorderings (crashed nodes stay down)
+    Heal,
+}
+
+/// A fault, and the time (since the start of the simulation) at which it is applied
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub at: Duration,
+    pub fault: Fault,
+}
+
+/// A scripted simulation scenario.
+///
+/// Scenarios can be built in code, or parsed from (and rendered to) a line-based
+/// script, e.g.:
+/// ```text
+/// nodes 4
+/// duration 10s
+/// latency 5ms 20ms
+/// expect-progress 3
+/// at 1s partition 0 1 | 2 3
+/// at 2s drop 0.1
+/// at 2s delay 0 * 200ms
+/// at 3s reorder 50ms
+/// at 3s crash 3
+/// at 5s restart 3
+/// at 6s heal
+/// ```
+/// Lines starting with `#` are comments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scenario {
+    /// The number of consensus nodes
+    pub num_nodes: usize,
+    /// The simulated time to run for
+    pub duration: Duration,
+    /// The range of the latency of every message
+    pub min_latency: Duration,
+    pub max_latency: Duration,
+    /// The number of rounds the highest committed round of every live node must
+    /// advance by after the last step
+    pub expected_progress: u64,
+    /// The steps of the scenario, ordered by time
+    pub steps: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn new(num_nodes: usize) -> Self {
+        Self {
+            num_nodes,
+            duration: Duration::from_secs(10),
+            min_latency: Duration::from_millis(5),
+            max_latency: Duration::from_millis(20),
+            expected_progress: 3,
+            steps: vec![],
+        }
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn latency(mut self, min_latency: Duration, max_latency: Duration) -> Self {
+        self.min_latency = min_latency;
+        self.max_latency = max_latency;
+        self
+    }
+
+    pub fn expect_progress(mut self, rounds: u64) -> Self {
+        self.expected_progress = rounds;
+        self
+    }
+
+    /// Applies the fault at the given time. Steps at the same time are applied in the
+    /// order they were added.
+    pub fn at(mut self, at: Duration, fault: Fault) -> Self {
+        self.add_step(at, fault);
+        self
+    }
+
+    fn add_step(&mut self, at: Duration, fault: Fault) {
+        let index = self.steps.partition_point(|step| step.at <= at);
+        self.steps.insert(index, Step { at, fault });
+    }
+
+    /// Generates a random scenario: a few random faults during the first 4 seconds of
+    /// the simulation, after which the network is healed and all crashed nodes restart.
+    pub fn random(num_nodes: usize, rng: &mut StdRng) -> Self {
+        let duration = Duration::from_secs(10);
+        let heal_at = Duration::from_secs(4);
+        let max_crashed = (num_nodes - 1) / 3;
+        let mut scenario = Self::new(num_nodes).duration(duration);
+        let mut crashed = vec![];
+        for _ in 0..rng.gen_range(1, 6) {
+            let at = Duration::from_millis(rng.gen_range(0, heal_at.as_millis() as u64));
+            let fault = match rng.gen_range(0, 5) {
+                0 => {
+                    let mut groups = vec![vec![], vec![]];
+                    for node in 0..num_nodes {
+                        groups[rng.gen_range(0, 2)].push(node);
+                    }
+                    Fault::Partition(groups)
+                },
+                1 => Fault::Drop(f64::from(rng.gen_range(1u8, 5)) / 10.0),
+                2 => Fault::Delay {
+                    from: Some(rng.gen_range(0, num_nodes)),
+                    to: None,
+                    delay: Duration::from_millis(rng.gen_range(100, 2_000)),
+                },
+                3 => Fault::Reorder(Duration::from_millis(rng.gen_range(10, 500))),
+                _ => {
+                    let node = rng.gen_range(0, num_nodes);
+                    if crashed.len() >= max_crashed || crashed.contains(&node) {
+                        continue;
+                    }
+                    crashed.push(node);
+                    Fault::Crash(node)
+                },
+            };
+            scenario = scenario.at(at, fault);
+        }
+        scenario = scenario.at(heal_at, Fault::Heal);
+        for node in crashed {
+            scenario = scenario.at(heal_at, Fault::Restart(node));
+        }
+        scenario
+    }
+
+    /// Checks that the scenario is consistent, e.g., that it only refers to existing nodes
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(self.num_nodes > 0, "A scenario needs at least one node");
+        ensure!(
+            self.min_latency <= self.max_latency,
+            "The minimum latency can't exceed the maximum latency"
+        );
+        for step in &self.steps {
+            let nodes: Vec<usize> = match &step.fault {
+                Fault::Partition(groups) => groups.iter().flatten().copied().collect(),
+                Fault::Delay { from, to, .. } => from.iter().chain(to.iter()).copied().collect(),
+                Fault::Crash(node) | Fault::Restart(node) => vec![*node],
+                Fault::Drop(probability) => {
+                    ensure!(
+                        (0.0..=1.0).contains(probability),
+                        "Invalid drop probability: {}",
+                        probability
+                    );
+                    vec![]
+                },
+                Fault::Reorder(_) | Fault::Heal => vec![],
+            };
+            if let Some(node) = nodes.into_iter().find(|node| *node >= self.num_nodes) {
+                bail!("Unknown node {} at {:?}", node, step.at);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Scenario {
+    type Err = anyhow::Error;
+
+    fn from_str(script: &str) -> anyhow::Result<Self> {
+        let mut scenario = Scenario::new(0);
+        for (index, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let words: Vec<_> = line.split_whitespace().collect();
+            parse_line(&mut scenario, &words)
+                .with_context(|| format!("Invalid line {}: {}", index + 1, line))?;
+        }
+        scenario.validate()?;
+        Ok(scenario)
+    }
+}
+
+fn parse_line(scenario: &mut Scenario, words: &[&str]) -> anyhow::Result<()> {
+    match words {
+        ["nodes", num_nodes] => scenario.num_nodes = num_nodes.parse()?,
+        ["duration", duration] => scenario.duration = parse_duration(duration)?,
+        ["latency", min_latency, max_latency] => {
+            scenario.min_latency = parse_duration(min_latency)?;
+            scenario.max_latency = parse_duration(max_latency)?;
+        },
+        ["expect-progress", rounds] => scenario.expected_progress = rounds.parse()?,
+        ["at", at, fault @ ..] => {
+            scenario.add_step(parse_duration(at)?, parse_fault(fault)?)
+        },
+        _ => bail!("Unknown statement"),
+    }
+    Ok(())
+}
+
+fn parse_fault(words: &[&str]) -> anyhow::Result<Fault> {
+    Ok(match words {
+        ["partition", groups @ ..] => Fault::Partition(
+            groups
+                .split(|word| *word == "|")
+                .map(|group| group.iter().map(|node| node.parse()).collect())
+                .collect::<Result<_, _>>()?,
+        ),
+        ["drop", probability] => Fault::Drop(probability.parse()?),
+        ["delay", from, to, delay] => Fault::Delay {
+            from: parse_node_pattern(from)?,
+            to: parse_node_pattern(to)?,
+            delay: parse_duration(delay)?,
+        },
+        ["reorder", jitter] => Fault::Reorder(parse_duration(jitter)?),
+        ["crash", node] => Fault::Crash(node.parse()?),
+        ["restart", node] => Fault::Restart(node.parse()?),
+        ["heal"] => Fault::Heal,
+        _ => bail!("Unknown fault"),
+    })
+}
+
+fn parse_node_pattern(word: &str) -> anyhow::Result<Option<usize>> {
+    if word == "*" {
+        Ok(None)
+    } else {
+        Ok(Some(word.parse()?))
+    }
+}
+
+fn parse_duration(word: &str) -> anyhow::Result<Duration> {
+    let invalid_duration = || format_err!("Invalid duration: {}", word);
+    if let Some(millis) = word.strip_suffix("ms") {
+        Ok(Duration::from_millis(
+            millis.parse().map_err(|_| invalid_duration())?,
+        ))
+    } else if let Some(secs) = word.strip_suffix('s') {
+        Ok(Duration::from_secs(
+            secs.parse().map_err(|_| invalid_duration())?,
+        ))
+    } else {
+        Err(invalid_duration())
+    }
+}
+
+struct DisplayDuration(Duration);
+
+impl fmt::Display for DisplayDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.subsec_millis() == 0 {
+            write!(f, "{}s", self.0.as_secs())
+        } else {
+            write!(f, "{}ms", self.0.as_millis())
+        }
+    }
+}
+
+fn display_node_pattern(node: &Option<usize>) -> String {
+    node.map_or_else(|| "*".to_string(), |node| node.to_string())
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Fault::Partition(groups) => {
+                let groups: Vec<_> = groups
+                    .iter()
+                    .map(|group| {
+                        group
+                            .iter()
+                            .map(|node| node.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect();
+                write!(f, "partition {}", groups.join(" | "))
+            },
+            Fault::Drop(probability) => write!(f, "drop {}", probability),
+            Fault::Delay { from, to, delay } => write!(
+                f,
+                "delay {} {} {}",
+                display_node_pattern(from),
+                display_node_pattern(to),
+                DisplayDuration(*delay)
+            ),
+            Fault::Reorder(jitter) => write!(f, "reorder {}", DisplayDuration(*jitter)),
+            Fault::Crash(node) => write!(f, "crash {}", node),
+            Fault::Restart(node) => write!(f, "restart {}", node),
+            Fault::Heal => write!(f, "heal"),
+        }
+    }
+}
+
+impl fmt::Display for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "nodes {}", self.num_nodes)?;
+        writeln!(f, "duration {}", DisplayDuration(self.duration))?;
+        writeln!(
+            f,
+            "latency {} {}",
+            DisplayDuration(self.min_latency),
+            DisplayDuration(self.max_latency)
+        )?;
+        writeln!(f, "expect-progress {}", self.expected_progress)?;
+        for step in &self.steps {
+            writeln!(f, "at {} {}", DisplayDuration(step.at), step.fault)?;
+        }
+        Ok(())
+    }
+}
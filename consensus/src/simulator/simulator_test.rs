@@ -0,0 +1,168 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    simulator::{Fault, Scenario, SimulationReport, Simulator},
+    test_utils::{consensus_runtime, timed_block_on},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{str::FromStr, time::Duration};
+use tokio::runtime::Runtime;
+
+/// The environment variable to replay the random scenarios of a single seed
+const SEED_ENV_VAR: &str = "CONSENSUS_SIM_SEED";
+
+fn simulate(runtime: &Runtime, scenario: Scenario, seed: u64) -> SimulationReport {
+    timed_block_on(runtime, async move {
+        Simulator::new(scenario, seed).await.run().await
+    })
+    .unwrap_or_else(|failure| panic!("{}", failure))
+}
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn scenario_script_test() {
+    let script = "
+        # Isolate a minority, then heal
+        nodes 4
+        duration 8s
+        latency 10ms 30ms
+        expect-progress 5
+        at 1s partition 0 1 2 | 3
+        at 2s delay 0 * 150ms
+        at 2s drop 0.2
+        at 3s reorder 50ms
+        at 3s crash 1
+        at 5s restart 1
+        at 6s heal
+    ";
+    let scenario = Scenario::new(4)
+        .duration(secs(8))
+        .latency(Duration::from_millis(10), Duration::from_millis(30))
+        .expect_progress(5)
+        .at(secs(6), Fault::Heal)
+        .at(secs(1), Fault::Partition(vec![vec![0, 1, 2], vec![3]]))
+        .at(secs(2), Fault::Delay {
+            from: Some(0),
+            to: None,
+            delay: Duration::from_millis(150),
+        })
+        .at(secs(2), Fault::Drop(0.2))
+        .at(secs(3), Fault::Reorder(Duration::from_millis(50)))
+        .at(secs(3), Fault::Crash(1))
+        .at(secs(5), Fault::Restart(1));
+    assert_eq!(Scenario::from_str(script).unwrap(), scenario);
+
+    // Rendered scenarios can be parsed back
+    assert_eq!(Scenario::from_str(&scenario.to_string()).unwrap(), scenario);
+}
+
+#[test]
+fn invalid_scenario_script_test() {
+    for script in [
+        "duration 1s",
+        "nodes 4\nat 1s crash 4",
+        "nodes 4\nat 1s partition 0 1 | 5",
+        "nodes 4\nat 1s drop 1.5",
+        "nodes 4\nat 1s explode 0",
+        "nodes 4\nat 1 heal",
+        "nodes 4\nlatency 20ms 10ms",
+    ] {
+        assert!(Scenario::from_str(script).is_err(), "{}", script);
+    }
+}
+
+#[test]
+fn fault_free_test() {
+    let runtime = consensus_runtime();
+    let report = simulate(&runtime, Scenario::new(4).duration(secs(5)), 0);
+
+    // Every node commits
+    for node in 0..4 {
+        assert!(report.commits.iter().any(|commit| commit.node == node));
+    }
+    assert_eq!(report.num_dropped_messages, 0);
+}
+
+#[test]
+fn partition_and_heal_test() {
+    let runtime = consensus_runtime();
+    let scenario = Scenario::new(4)
+        .duration(secs(8))
+        .at(secs(1), Fault::Partition(vec![vec![0, 1], vec![2, 3]]))
+        .at(secs(3), Fault::Heal);
+    let report = simulate(&runtime, scenario, 1);
+
+    // No side of the partition has a quorum (the messages that were in flight when
+    // the partition started may still complete a commit)
+    assert!(!report.commits.iter().any(|commit| {
+        commit.time >= Duration::from_millis(1_200) && commit.time < secs(3)
+    }));
+    assert!(report.commits.iter().any(|commit| commit.time >= secs(3)));
+}
+
+#[test]
+fn crash_and_restart_test() {
+    let runtime = consensus_runtime();
+    let scenario = Scenario::new(4)
+        .duration(secs(8))
+        .at(secs(1), Fault::Crash(3))
+        .at(secs(3), Fault::Restart(3));
+    let report = simulate(&runtime, scenario, 2);
+
+    // The other nodes keep committing while the node is down, and the node catches up
+    // after its restart
+    assert!(report.commits.iter().any(|commit| {
+        commit.node != 3 && commit.time > secs(1) && commit.time < secs(3)
+    }));
+    assert!(report
+        .commits
+        .iter()
+        .any(|commit| commit.node == 3 && commit.time > secs(3)));
+}
+
+#[test]
+fn deterministic_replay_test() {
+    let runtime = consensus_runtime();
+    let scenario = Scenario::new(4)
+        .duration(secs(5))
+        .at(secs(1), Fault::Drop(0.2))
+        .at(secs(1), Fault::Reorder(Duration::from_millis(100)))
+        .at(secs(2), Fault::Heal);
+
+    let report = simulate(&runtime, scenario.clone(), 3);
+    assert!(report.num_dropped_messages > 0);
+    assert_eq!(simulate(&runtime, scenario, 3), report);
+}
+
+#[test]
+fn deterministic_replay_with_block_retrieval_test() {
+    let runtime = consensus_runtime();
+    // The restarted node retrieves the blocks it missed while messages are dropped, so
+    // some retrievals fail and are retried with other peers
+    let scenario = Scenario::new(4)
+        .duration(secs(6))
+        .at(secs(1), Fault::Crash(3))
+        .at(secs(3), Fault::Drop(0.3))
+        .at(secs(3), Fault::Restart(3))
+        .at(secs(4), Fault::Heal);
+
+    let report = simulate(&runtime, scenario.clone(), 4);
+    assert_eq!(simulate(&runtime, scenario, 4), report);
+}
+
+#[test]
+fn random_scenarios_test() {
+    let seeds: Vec<u64> = match std::env::var(SEED_ENV_VAR) {
+        Ok(seed) => vec![seed.parse().expect("Invalid seed")],
+        Err(_) => (0..3).collect(),
+    };
+    let runtime = consensus_runtime();
+    for seed in seeds {
+        let scenario = Scenario::random(4, &mut StdRng::seed_from_u64(seed));
+        simulate(&runtime, scenario, seed);
+    }
+}
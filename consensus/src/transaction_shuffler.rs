@@ -1,11 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::sender_aware_shuffler::SenderAwareShuffler;
+use crate::{
+    gas_and_dependency_aware_shuffler::{GasAndDependencyAwareShuffler, PayloadAccessSetEstimator},
+    sender_aware_shuffler::SenderAwareShuffler,
+};
 use aptos_types::{
     on_chain_config::{
         TransactionShufflerType,
-        TransactionShufflerType::{NoShuffling, SenderAwareV1},
+        TransactionShufflerType::{GasAndDependencyAwareV1, NoShuffling, SenderAwareV1},
     },
     transaction::SignedTransaction,
 };
@@ -13,6 +16,7 @@ use std::sync::Arc;
 
 /// Interface to shuffle transactions
 pub trait TransactionShuffler: Send + Sync {
+    /// Reorders the transactions of a block
     fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction>;
 }
 
@@ -25,6 +29,16 @@ impl TransactionShuffler for NoOpShuffler {
     }
 }
 
+/// Creates the shuffler configured on-chain.
+///
+/// The gas and dependency aware shuffler estimates access sets from the transaction payloads
+/// only, rather than with the read/write set analysis (see `ReadWriteSetAnalysisEstimator`).
+/// The shuffled order must be a pure function of the block: every validator shuffles each block
+/// once when executing it and again when committing it (see `ExecutionProxy`), and the analysis
+/// would resolve the called modules from whatever state the node has at that time, which can
+/// differ between validators and between the two calls. Benchmarks comparing the two estimators
+/// on the executed blocks live in the executor benchmark (`--shuffler-type`), and the cost of
+/// shuffling a block is measured by the `transaction_shuffler` bench of this crate.
 pub fn create_transaction_shuffler(
     shuffler_type: TransactionShufflerType,
 ) -> Arc<dyn TransactionShuffler> {
@@ -33,5 +47,11 @@ pub fn create_transaction_shuffler(
         SenderAwareV1(confict_window_size) => {
             Arc::new(SenderAwareShuffler::new(confict_window_size as usize))
         },
+        GasAndDependencyAwareV1(conflict_window_size) => {
+            Arc::new(GasAndDependencyAwareShuffler::new(
+                conflict_window_size as usize,
+                PayloadAccessSetEstimator,
+            ))
+        },
     }
 }
@@ -6,11 +6,12 @@ use crate::{
     counters,
     epoch_manager::EpochManager,
     experimental::buffer_manager::OrderedBlocks,
+    liveness::proposal_simulator::ProposalSimulationService,
     network::NetworkTask,
     network_interface::{ConsensusNetworkClient, DIRECT_SEND, RPC},
     network_tests::{NetworkPlayground, TwinId},
     payload_manager::PayloadManager,
-    quorum_store::quorum_store_db::MockQuorumStoreDB,
+    quorum_store::{inspection::QuorumStoreInspector, quorum_store_db::MockQuorumStoreDB},
     test_utils::{MockStateComputer, MockStorage},
     util::time_service::ClockTimeService,
 };
@@ -153,6 +154,9 @@ impl SMRNode {
             quorum_store_storage,
             reconfig_listener,
             bounded_executor,
+            None,
+            ProposalSimulationService::default(),
+            QuorumStoreInspector::default(),
         );
         let (network_task, network_receiver) =
             NetworkTask::new(network_service_events, self_receiver);
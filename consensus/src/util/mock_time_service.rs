@@ -121,6 +121,40 @@ impl SimulatedTimeService {
             futures::executor::block_on(t.run());
         }
     }
+
+    /// Advances the current time to `time` (if it is later than the current time), and
+    /// runs the pending tasks with a deadline up to `time` in the order of their deadlines
+    #[allow(dead_code)]
+    pub fn advance_to(&self, time: Duration) {
+        let mut due_tasks: Vec<_> = {
+            let mut inner = self.inner.lock();
+            inner.time_limit = std::cmp::max(inner.time_limit, time);
+            inner.now = std::cmp::min(std::cmp::max(inner.now, time), inner.max);
+            let time_limit = inner.time_limit;
+            let (due_tasks, pending) = std::mem::take(&mut inner.pending)
+                .into_iter()
+                .partition(|(deadline, _)| *deadline <= time_limit);
+            inner.pending = pending;
+            due_tasks
+        };
+        // The sort is stable, so tasks with the same deadline run in the order they
+        // were scheduled
+        due_tasks.sort_by_key(|(deadline, _)| *deadline);
+        for (_, mut t) in due_tasks {
+            futures::executor::block_on(t.run());
+        }
+    }
+
+    /// Returns the earliest deadline of the pending tasks (if any)
+    #[allow(dead_code)]
+    pub fn next_deadline(&self) -> Option<Duration> {
+        self.inner
+            .lock()
+            .pending
+            .iter()
+            .map(|(deadline, _)| *deadline)
+            .min()
+    }
 }
 
 impl Clone for SimulatedTimeService {
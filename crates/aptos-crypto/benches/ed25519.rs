@@ -7,7 +7,7 @@ extern crate criterion;
 
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
-    traits::{Signature, SigningKey, Uniform},
+    traits::{signing_message, Signature, SigningKey, Uniform},
     PrivateKey,
 };
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
@@ -35,6 +35,9 @@ fn benchmark_groups(c: &mut Criterion) {
 
     sig_verify_struct(&mut group);
     sig_verify_zero_bytes(&mut group);
+    for batch_size in [16, 128, 1024] {
+        sig_batch_verify_arbitrary_msgs(&mut group, batch_size);
+    }
     pk_deserialize(&mut group);
     sig_deserialize(&mut group);
     small_subgroup_check(&mut group);
@@ -81,6 +84,32 @@ fn sig_verify_zero_bytes<M: Measurement>(g: &mut BenchmarkGroup<M>) {
     });
 }
 
+/// Benchmarks the time to batch verify signatures over distinct messages and public keys, as
+/// during mempool ingress. The throughput is to be compared with `sig_verify_zero_bytes`.
+fn sig_batch_verify_arbitrary_msgs<M: Measurement>(g: &mut BenchmarkGroup<M>, batch_size: usize) {
+    let mut csprng: ThreadRng = thread_rng();
+
+    let batch: Vec<_> = (0..batch_size)
+        .map(|_| {
+            let priv_key = Ed25519PrivateKey::generate(&mut csprng);
+            let msg = random_message(&mut csprng);
+            let sig = priv_key.sign(&msg).unwrap();
+            (signing_message(&msg).unwrap(), priv_key.public_key(), sig)
+        })
+        .collect();
+
+    g.throughput(Throughput::Elements(batch_size as u64));
+    g.bench_function(format!("sig_batch_verify/{}", batch_size), move |b| {
+        b.iter(|| {
+            let batch: Vec<_> = batch
+                .iter()
+                .map(|(msg, pub_key, sig)| (msg.as_slice(), pub_key, sig))
+                .collect();
+            Ed25519Signature::batch_verify_arbitrary_msgs(&batch)
+        })
+    });
+}
+
 /// Benchmarks the time to check if an EdwardsPoint is in a small subgroup.
 fn small_subgroup_check<M: Measurement>(g: &mut BenchmarkGroup<M>) {
     let point = ED25519_BASEPOINT_POINT;
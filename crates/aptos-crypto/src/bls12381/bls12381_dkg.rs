@@ -0,0 +1,201 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides the building blocks of Pedersen's distributed key generation (DKG), also
+//! known as Joint-Feldman (https://link.springer.com/chapter/10.1007/3-540-46416-6_47), which lets
+//! `n` players generate the shares of a (t, n) threshold BLS12381 private key (see
+//! `bls12381_threshold`) without any party ever learning the private key:
+//!
+//!  1. Each dealer deals a random secret via Feldman's verifiable secret sharing (VSS): it
+//!     publishes a `Transcript` committing to its polynomial, and privately sends each player its
+//!     `PrivateKeyShare`.
+//!  2. Each player checks the transcripts via `Transcript::verify`, and the shares it received
+//!     via `Transcript::verify_share`, and complains about dealers whose shares do not verify.
+//!  3. Once the qualified dealers are agreed upon, their transcripts are combined via
+//!     `Transcript::aggregate`, and each player combines the shares it received from them via
+//!     `PrivateKeyShare::aggregate`. The aggregated transcript gives the threshold public key, and
+//!     the public key share of every player.
+//!
+//! The private channels between players, and the complaint round, are out of the scope of this
+//! module.
+//!
+//! NOTE: As shown by Gennaro et al. (https://link.springer.com/article/10.1007/s00145-006-0347-3),
+//! a rushing adversary can bias the distribution of the public key generated by this protocol.
+//! This does not affect the security of threshold BLS signatures, but may affect other uses of
+//! the public key.
+
+use crate::bls12381::{
+    self,
+    bls12381_threshold::{
+        g1_from_public_key, public_key_from_g1, random_polynomial, random_scalar, PlayerId,
+        PrivateKeyShare, PublicKeyShare, ThresholdConfig,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use ark_bls12_381::{Fr, G1Projective};
+use ark_ec::{CurveGroup, Group, VariableBaseMSM};
+use ark_ff::One;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The public part of one or more dealings: commitments to the coefficients of the dealers'
+/// polynomials, which the players' shares are verified against
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    config: ThresholdConfig,
+    /// The dealers whose dealings are aggregated in this transcript, in increasing order
+    dealers: Vec<PlayerId>,
+    /// The commitments `a_k G1` to the coefficients `a_k` of the polynomial, from the constant
+    /// one, whose commitment is the public key
+    commitments: Vec<bls12381::PublicKey>,
+}
+
+impl Transcript {
+    /// Deals a random secret as `dealer`, returning the transcript to publish and the share of
+    /// each player, to be sent to them privately.
+    pub fn deal<R: RngCore + CryptoRng>(
+        config: &ThresholdConfig,
+        dealer: PlayerId,
+        rng: &mut R,
+    ) -> Result<(Transcript, Vec<PrivateKeyShare>)> {
+        config.check_player(dealer)?;
+
+        let polynomial = random_polynomial(random_scalar(rng), config, rng);
+        let commitments = polynomial
+            .iter()
+            .map(|coefficient| {
+                public_key_from_g1(&(G1Projective::generator() * coefficient).into())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let transcript = Transcript {
+            config: *config,
+            dealers: vec![dealer],
+            commitments,
+        };
+        Ok((transcript, config.shares_of_polynomial(&polynomial)))
+    }
+
+    /// The threshold configuration the transcript was dealt for.
+    pub fn config(&self) -> &ThresholdConfig {
+        &self.config
+    }
+
+    /// The dealers whose dealings are aggregated in this transcript.
+    pub fn dealers(&self) -> &[PlayerId] {
+        &self.dealers
+    }
+
+    /// The public key of the secret dealt, i.e., the threshold public key once the transcripts of
+    /// all qualified dealers have been aggregated.
+    pub fn public_key(&self) -> Result<&bls12381::PublicKey> {
+        self.commitments
+            .first()
+            .ok_or_else(|| anyhow!("Transcript has no commitments"))
+    }
+
+    /// Checks that the transcript is well-formed, and subgroup-checks its commitments, which must
+    /// be done before deriving keys from a transcript received from a dealer.
+    pub fn verify(&self) -> Result<()> {
+        ensure!(
+            self.commitments.len() == self.config.threshold() as usize,
+            "Expected {} commitments, got {}",
+            self.config.threshold(),
+            self.commitments.len()
+        );
+        ensure!(!self.dealers.is_empty(), "Transcript has no dealer");
+        for dealer in &self.dealers {
+            self.config.check_player(*dealer)?;
+        }
+        ensure!(
+            self.dealers.windows(2).all(|pair| pair[0] < pair[1]),
+            "Dealers are not in increasing order"
+        );
+        for commitment in &self.commitments {
+            commitment.subgroup_check()?;
+        }
+        Ok(())
+    }
+
+    /// Verifies a share received privately from the dealers of the transcript, by checking that
+    /// its public key is the evaluation of the committed polynomial "in the exponent".
+    pub fn verify_share(&self, share: &PrivateKeyShare) -> Result<()> {
+        let expected = self.public_key_share(share.player())?;
+        ensure!(
+            PublicKeyShare::from(share) == expected,
+            "Share of player {} does not match the transcript",
+            share.player()
+        );
+        Ok(())
+    }
+
+    /// Returns the public key share of `player`, which its signature shares verify under.
+    pub fn public_key_share(&self, player: PlayerId) -> Result<PublicKeyShare> {
+        self.config.check_player(player)?;
+
+        let x = Fr::from(player);
+        let powers: Vec<_> = std::iter::successors(Some(Fr::one()), |power| Some(*power * x))
+            .take(self.commitments.len())
+            .collect();
+        let commitments = self
+            .commitments
+            .iter()
+            .map(g1_from_public_key)
+            .collect::<Result<Vec<_>>>()?;
+
+        let point = G1Projective::msm(&commitments, &powers)
+            .map_err(|_| anyhow!("Mismatched number of commitments and powers"))?;
+        Ok(PublicKeyShare::new(
+            player,
+            public_key_from_g1(&point.into())?,
+        ))
+    }
+
+    /// Aggregates the transcripts of distinct dealers for the same threshold configuration, so
+    /// that the dealt secret is the sum of theirs.
+    ///
+    /// WARNING: This function assumes the transcripts have been verified via `Transcript::verify`.
+    pub fn aggregate(transcripts: &[Transcript]) -> Result<Transcript> {
+        let first = transcripts
+            .first()
+            .ok_or_else(|| anyhow!("No transcripts to aggregate"))?;
+        ensure!(
+            transcripts
+                .iter()
+                .all(|transcript| transcript.config == first.config),
+            "Transcripts are for different threshold configurations"
+        );
+        // Summing commitments pairwise would silently drop the extra ones of a malformed transcript
+        ensure!(
+            transcripts.iter().all(|transcript| transcript.commitments.len()
+                == first.config.threshold() as usize),
+            "Transcripts must have {} commitments",
+            first.config.threshold()
+        );
+
+        let mut dealers = BTreeSet::new();
+        for dealer in transcripts
+            .iter()
+            .flat_map(|transcript| &transcript.dealers)
+        {
+            ensure!(dealers.insert(*dealer), "Duplicate dealer {}", dealer);
+        }
+
+        let mut sums = vec![G1Projective::default(); first.commitments.len()];
+        for transcript in transcripts {
+            for (sum, commitment) in sums.iter_mut().zip(&transcript.commitments) {
+                *sum += g1_from_public_key(commitment)?;
+            }
+        }
+        let commitments = G1Projective::normalize_batch(&sums)
+            .iter()
+            .map(public_key_from_g1)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Transcript {
+            config: first.config,
+            dealers: dealers.into_iter().collect(),
+            commitments,
+        })
+    }
+}
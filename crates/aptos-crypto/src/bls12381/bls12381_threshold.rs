@@ -0,0 +1,606 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides APIs for (t, n) threshold Boneh-Lynn-Shacham (BLS) signatures over
+//! BLS12-381 (https://www.iacr.org/archive/pkc2003/25670031/25670031.pdf), where a private key is
+//! Shamir-secret-shared amongst `n` players, such that any `t` of them can sign.
+//!
+//! Player `i` (numbered from 1 to `n`) holds a `PrivateKeyShare` with the evaluation `f(i)` of a
+//! random polynomial `f` of degree `t - 1`, whose constant term `f(0)` is the private key. Its
+//! `SignatureShare`s can be verified under its `PublicKeyShare`, and any `t` signature shares on
+//! the same message can be combined via Lagrange interpolation into a regular
+//! `bls12381::Signature`, which verifies under the `bls12381::PublicKey` of `f(0)`.
+//!
+//! The shares are either dealt by a trusted dealer via `ThresholdConfig::split_private_key`, or
+//! generated without a trusted dealer via the distributed key generation (DKG) in
+//! `bls12381_dkg`.
+//!
+//! # Examples
+//!
+//! ```
+//! use aptos_crypto::{
+//!     bls12381::{PrivateKey, ThresholdConfig},
+//!     traits::{Signature, SigningKey, Uniform},
+//!     PrivateKey as _,
+//! };
+//! use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+//! use rand_core::OsRng;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let config = ThresholdConfig::new(2, 3).unwrap();
+//! let private_key = PrivateKey::generate(&mut OsRng);
+//! let shares = config.split_private_key(&private_key, &mut OsRng);
+//!
+//! let signature_shares: Vec<_> = shares[1..]
+//!     .iter()
+//!     .map(|share| share.sign(&message).unwrap())
+//!     .collect();
+//! for (share, signature_share) in shares[1..].iter().zip(signature_shares.iter()) {
+//!     assert!(signature_share.verify(&message, &share.public_key()).is_ok());
+//! }
+//!
+//! let signature = config.combine_signature_shares(&signature_shares).unwrap();
+//! assert!(signature.verify(&message, &private_key.public_key()).is_ok());
+//! ```
+
+use crate::{
+    bls12381,
+    hash::CryptoHash,
+    traits::{self, Signature as _, SigningKey as _},
+    CryptoMaterialError, Length, ValidCryptoMaterial,
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use ark_bls12_381::{Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::VariableBaseMSM;
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, convert::TryFrom};
+
+/// The index of a player, from 1 to `n`. Index 0 is reserved for the private key itself, as the
+/// evaluation of the polynomial at 0.
+pub type PlayerId = u16;
+
+/// The length of a serialized `PlayerId`.
+const PLAYER_ID_LENGTH: usize = 2;
+
+/// The parameters of a (t, n) threshold scheme: any `t` of the `n` players can sign.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdConfig {
+    threshold: u16,
+    num_players: u16,
+}
+
+#[derive(SerializeKey, DeserializeKey, SilentDebug, SilentDisplay)]
+/// The share of a threshold BLS12381 private key held by a player
+pub struct PrivateKeyShare {
+    player: PlayerId,
+    private_key: bls12381::PrivateKey,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SerializeKey, DeserializeKey)]
+/// The public key of a player's `PrivateKeyShare`, which its signature shares verify under
+pub struct PublicKeyShare {
+    player: PlayerId,
+    public_key: bls12381::PublicKey,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SerializeKey, DeserializeKey)]
+/// A signature share from a player, to be combined with the shares of other players
+pub struct SignatureShare {
+    player: PlayerId,
+    signature: bls12381::Signature,
+}
+
+//////////////////////////////////////////////
+// Implementation of ThresholdConfig struct //
+//////////////////////////////////////////////
+
+impl ThresholdConfig {
+    /// Construct a new ThresholdConfig, for `1 <= threshold <= num_players`.
+    pub fn new(threshold: u16, num_players: u16) -> std::result::Result<Self, CryptoMaterialError> {
+        if threshold == 0 || threshold > num_players {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        Ok(Self {
+            threshold,
+            num_players,
+        })
+    }
+
+    /// The number of players needed to sign.
+    pub fn threshold(&self) -> u16 {
+        self.threshold
+    }
+
+    /// The total number of players.
+    pub fn num_players(&self) -> u16 {
+        self.num_players
+    }
+
+    /// Splits `private_key` into a share for each player, as a trusted dealer. The dealer is
+    /// trusted to forget the private key and the shares once they are distributed.
+    pub fn split_private_key<R: RngCore + CryptoRng>(
+        &self,
+        private_key: &bls12381::PrivateKey,
+        rng: &mut R,
+    ) -> Vec<PrivateKeyShare> {
+        let polynomial = random_polynomial(scalar_from_private_key(private_key), self, rng);
+        self.shares_of_polynomial(&polynomial)
+    }
+
+    /// Combines `threshold` signature shares from distinct players on the same message into a
+    /// signature for the threshold private key. Extra shares are ignored.
+    ///
+    /// For performance reasons, we do not subgroup-check the signature shares here, since the
+    /// verification of the returned signature includes such a subgroup check. As a result,
+    /// adversarial signature shares cannot lead to forgeries, but they do lead to an invalid
+    /// signature: to identify them, the signature shares should be verified individually.
+    pub fn combine_signature_shares(
+        &self,
+        shares: &[SignatureShare],
+    ) -> Result<bls12381::Signature> {
+        let shares = self.first_threshold_shares(shares)?;
+        let players: Vec<_> = shares.iter().map(|share| share.player).collect();
+        let points = shares
+            .iter()
+            .map(|share| g2_from_signature(&share.signature))
+            .collect::<Result<Vec<_>>>()?;
+
+        let combined = G2Projective::msm(&points, &self.lagrange_coefficients(&players)?)
+            .map_err(|_| anyhow!("Mismatched number of signature shares and coefficients"))?;
+        signature_from_g2(&combined.into())
+    }
+
+    /// Combines `threshold` public key shares from distinct players into the threshold public
+    /// key. Extra shares are ignored.
+    ///
+    /// WARNING: This function assumes the public key shares have been subgroup-checked, e.g.,
+    /// when derived from a verified DKG `Transcript`.
+    pub fn combine_public_key_shares(
+        &self,
+        shares: &[PublicKeyShare],
+    ) -> Result<bls12381::PublicKey> {
+        let shares = self.first_threshold_shares(shares)?;
+        let players: Vec<_> = shares.iter().map(|share| share.player).collect();
+        let points = shares
+            .iter()
+            .map(|share| g1_from_public_key(&share.public_key))
+            .collect::<Result<Vec<_>>>()?;
+
+        let combined = G1Projective::msm(&points, &self.lagrange_coefficients(&players)?)
+            .map_err(|_| anyhow!("Mismatched number of public key shares and coefficients"))?;
+        public_key_from_g1(&combined.into())
+    }
+
+    /// Checks that `player` is one of the players, i.e., between 1 and `num_players`.
+    pub(crate) fn check_player(&self, player: PlayerId) -> Result<()> {
+        ensure!(
+            (1..=self.num_players).contains(&player),
+            "Player {} is not between 1 and {}",
+            player,
+            self.num_players
+        );
+        Ok(())
+    }
+
+    /// Returns the shares of all the players for `polynomial`, of degree `threshold - 1`.
+    pub(crate) fn shares_of_polynomial(&self, polynomial: &[Fr]) -> Vec<PrivateKeyShare> {
+        (1..=self.num_players)
+            .map(|player| PrivateKeyShare {
+                player,
+                // The polynomial is random, so that a share is zero with negligible probability
+                private_key: private_key_from_scalar(&evaluate(polynomial, player))
+                    .expect("Share should not be zero"),
+            })
+            .collect()
+    }
+
+    /// Returns the Lagrange coefficients to interpolate a polynomial at 0 from its evaluations at
+    /// `players`, i.e., `l_i = prod_{j != i} x_j / (x_j - x_i)`.
+    pub(crate) fn lagrange_coefficients(&self, players: &[PlayerId]) -> Result<Vec<Fr>> {
+        let mut distinct_players = BTreeSet::new();
+        for player in players {
+            self.check_player(*player)?;
+            ensure!(
+                distinct_players.insert(*player),
+                "Duplicate player {}",
+                player
+            );
+        }
+
+        Ok(players
+            .iter()
+            .map(|i| {
+                let (numerator, denominator) = players.iter().filter(|j| *j != i).fold(
+                    (Fr::one(), Fr::one()),
+                    |(numerator, denominator), j| {
+                        (
+                            numerator * Fr::from(*j),
+                            denominator * (Fr::from(*j) - Fr::from(*i)),
+                        )
+                    },
+                );
+                // The players are distinct, so the denominator is not zero
+                numerator
+                    * denominator
+                        .inverse()
+                        .expect("Denominator should not be zero")
+            })
+            .collect())
+    }
+
+    /// Returns the first `threshold` shares, checking there are enough of them.
+    fn first_threshold_shares<'a, T>(&self, shares: &'a [T]) -> Result<&'a [T]> {
+        ensure!(
+            shares.len() >= self.threshold as usize,
+            "Expected at least {} shares, got {}",
+            self.threshold,
+            shares.len()
+        );
+        Ok(&shares[..self.threshold as usize])
+    }
+}
+
+////////////////////////////////////////////////////////////
+// Implementation of private-and-public key share structs //
+////////////////////////////////////////////////////////////
+
+impl PrivateKeyShare {
+    /// The length of a serialized PrivateKeyShare struct.
+    pub const LENGTH: usize = PLAYER_ID_LENGTH + bls12381::PrivateKey::LENGTH;
+
+    /// The player holding this share.
+    pub fn player(&self) -> PlayerId {
+        self.player
+    }
+
+    /// Serialize a PrivateKeyShare.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.player.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&self.private_key.to_bytes());
+        bytes
+    }
+
+    /// Sums the shares dealt to the same player by several dealers, as in the last step of the
+    /// DKG in `bls12381_dkg`.
+    pub fn aggregate(shares: &[PrivateKeyShare]) -> Result<PrivateKeyShare> {
+        let player = shares
+            .first()
+            .ok_or_else(|| anyhow!("No private key shares to aggregate"))?
+            .player;
+        ensure!(
+            shares.iter().all(|share| share.player == player),
+            "Private key shares are for different players"
+        );
+
+        let sum = shares
+            .iter()
+            .map(|share| scalar_from_private_key(&share.private_key))
+            .fold(Fr::zero(), |sum, scalar| sum + scalar);
+        Ok(PrivateKeyShare {
+            player,
+            private_key: private_key_from_scalar(&sum)?,
+        })
+    }
+}
+
+impl PublicKeyShare {
+    /// The length of a serialized PublicKeyShare struct.
+    pub const LENGTH: usize = PLAYER_ID_LENGTH + bls12381::PublicKey::LENGTH;
+
+    /// The player whose private key share this is the public key of.
+    pub fn player(&self) -> PlayerId {
+        self.player
+    }
+
+    /// The public key of the private key share.
+    pub fn public_key(&self) -> &bls12381::PublicKey {
+        &self.public_key
+    }
+
+    /// Serialize a PublicKeyShare.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.player.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&self.public_key.to_bytes());
+        bytes
+    }
+
+    /// Constructs the public key share of `player` from its public key.
+    pub(crate) fn new(player: PlayerId, public_key: bls12381::PublicKey) -> Self {
+        Self { player, public_key }
+    }
+}
+
+impl SignatureShare {
+    /// The length of a serialized SignatureShare struct.
+    pub const LENGTH: usize = PLAYER_ID_LENGTH + bls12381::Signature::LENGTH;
+
+    /// The player who produced this signature share.
+    pub fn player(&self) -> PlayerId {
+        self.player
+    }
+
+    /// Serialize a SignatureShare.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.player.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&self.signature.to_bytes());
+        bytes
+    }
+}
+
+/// Splits serialized share material into its player, which must not be 0, and the rest.
+fn split_player(
+    bytes: &[u8],
+    length: usize,
+) -> std::result::Result<(PlayerId, &[u8]), CryptoMaterialError> {
+    if bytes.len() != length {
+        return Err(CryptoMaterialError::WrongLengthError);
+    }
+    let player = PlayerId::from_le_bytes([bytes[0], bytes[1]]);
+    if player == 0 {
+        return Err(CryptoMaterialError::ValidationError);
+    }
+    Ok((player, &bytes[PLAYER_ID_LENGTH..]))
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl traits::PrivateKey for PrivateKeyShare {
+    type PublicKeyMaterial = PublicKeyShare;
+}
+
+impl traits::SigningKey for PrivateKeyShare {
+    type SignatureMaterial = SignatureShare;
+    type VerifyingKeyMaterial = PublicKeyShare;
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<SignatureShare, CryptoMaterialError> {
+        Ok(SignatureShare {
+            player: self.player,
+            signature: self.private_key.sign(message)?,
+        })
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> SignatureShare {
+        SignatureShare {
+            player: self.player,
+            signature: self.private_key.sign_arbitrary_message(message),
+        }
+    }
+}
+
+impl ValidCryptoMaterial for PrivateKeyShare {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+impl Length for PrivateKeyShare {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl TryFrom<&[u8]> for PrivateKeyShare {
+    type Error = CryptoMaterialError;
+
+    /// Deserializes a PrivateKeyShare from a sequence of bytes.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+        let (player, private_key) = split_player(bytes, Self::LENGTH)?;
+        Ok(Self {
+            player,
+            private_key: bls12381::PrivateKey::try_from(private_key)?,
+        })
+    }
+}
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(PrivateKeyShare: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for PrivateKeyShare {
+    fn clone(&self) -> Self {
+        let serialized: &[u8] = &(self.to_bytes());
+        PrivateKeyShare::try_from(serialized).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+impl From<&PrivateKeyShare> for PublicKeyShare {
+    fn from(private_key_share: &PrivateKeyShare) -> Self {
+        Self {
+            player: private_key_share.player,
+            public_key: bls12381::PublicKey::from(&private_key_share.private_key),
+        }
+    }
+}
+
+impl traits::PublicKey for PublicKeyShare {
+    type PrivateKeyMaterial = PrivateKeyShare;
+}
+
+impl traits::VerifyingKey for PublicKeyShare {
+    type SignatureMaterial = SignatureShare;
+    type SigningKeyMaterial = PrivateKeyShare;
+}
+
+impl ValidCryptoMaterial for PublicKeyShare {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+impl Length for PublicKeyShare {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl TryFrom<&[u8]> for PublicKeyShare {
+    type Error = CryptoMaterialError;
+
+    /// Deserializes a PublicKeyShare from a sequence of bytes.
+    ///
+    /// WARNING: Does NOT subgroup-check the public key! Public key shares should be derived from
+    /// a verified DKG `Transcript`, or be subgroup-checked via `bls12381::PublicKey::subgroup_check`.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+        let (player, public_key) = split_player(bytes, Self::LENGTH)?;
+        Ok(Self {
+            player,
+            public_key: bls12381::PublicKey::try_from(public_key)?,
+        })
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl traits::Signature for SignatureShare {
+    type SigningKeyMaterial = PrivateKeyShare;
+    type VerifyingKeyMaterial = PublicKeyShare;
+
+    /// Serializes the message of type `T` to bytes and calls `SignatureShare::verify_arbitrary_msg`.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &PublicKeyShare,
+    ) -> Result<()> {
+        self.verify_arbitrary_msg(&traits::signing_message(message)?, public_key)
+    }
+
+    /// Verifies a signature share under the public key share of the same player. Does not assume
+    /// the signature share to be subgroup-checked.
+    ///
+    /// WARNING: This function does assume the public key share has been subgroup-checked.
+    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &PublicKeyShare) -> Result<()> {
+        ensure!(
+            self.player == public_key.player,
+            "Signature share from player {} does not match public key share of player {}",
+            self.player,
+            public_key.player
+        );
+        self.signature
+            .verify_arbitrary_msg(message, &public_key.public_key)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+impl ValidCryptoMaterial for SignatureShare {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+}
+
+impl Length for SignatureShare {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl TryFrom<&[u8]> for SignatureShare {
+    type Error = CryptoMaterialError;
+
+    /// Deserializes a SignatureShare from a sequence of bytes.
+    ///
+    /// WARNING: Does NOT subgroup-check the signature share! Instead, this will be done implicitly
+    /// when verifying it, or the signature it is combined into.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+        let (player, signature) = split_player(bytes, Self::LENGTH)?;
+        Ok(Self {
+            player,
+            signature: bls12381::Signature::try_from(signature)?,
+        })
+    }
+}
+
+/////////////////////////////////////////////////////
+// Conversions between blst and arkworks materials //
+/////////////////////////////////////////////////////
+
+// NOTE: blst and arkworks both serialize BLS12-381 points in the compressed format of zcash, and
+// scalars in big-endian for blst.
+
+/// Returns a uniformly random scalar.
+pub(crate) fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> Fr {
+    // Reducing 64 random bytes modulo the order of the group has a negligible bias
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Fr::from_le_bytes_mod_order(&bytes)
+}
+
+/// Returns a random polynomial of degree `threshold - 1` such that `f(0) = secret`, as its
+/// coefficients from the constant one.
+pub(crate) fn random_polynomial<R: RngCore + CryptoRng>(
+    secret: Fr,
+    config: &ThresholdConfig,
+    rng: &mut R,
+) -> Vec<Fr> {
+    std::iter::once(secret)
+        .chain((1..config.threshold).map(|_| random_scalar(rng)))
+        .collect()
+}
+
+/// Evaluates the polynomial with coefficients `polynomial` at `player`, via Horner's method.
+fn evaluate(polynomial: &[Fr], player: PlayerId) -> Fr {
+    let x = Fr::from(player);
+    polynomial
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |result, coefficient| result * x + coefficient)
+}
+
+fn scalar_from_private_key(private_key: &bls12381::PrivateKey) -> Fr {
+    Fr::from_be_bytes_mod_order(&private_key.to_bytes())
+}
+
+fn private_key_from_scalar(
+    scalar: &Fr,
+) -> std::result::Result<bls12381::PrivateKey, CryptoMaterialError> {
+    // A zero scalar is not a valid private key, and is rejected here
+    bls12381::PrivateKey::try_from(scalar.into_bigint().to_bytes_be().as_slice())
+}
+
+pub(crate) fn g1_from_public_key(public_key: &bls12381::PublicKey) -> Result<G1Affine> {
+    G1Affine::deserialize_compressed_unchecked(&public_key.to_bytes()[..])
+        .map_err(|e| anyhow!("{:?}", e))
+}
+
+pub(crate) fn public_key_from_g1(point: &G1Affine) -> Result<bls12381::PublicKey> {
+    let mut bytes = Vec::with_capacity(bls12381::PublicKey::LENGTH);
+    point
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| anyhow!("{:?}", e))?;
+    Ok(bls12381::PublicKey::try_from(bytes.as_slice())?)
+}
+
+fn g2_from_signature(signature: &bls12381::Signature) -> Result<G2Affine> {
+    G2Affine::deserialize_compressed_unchecked(&signature.to_bytes()[..])
+        .map_err(|e| anyhow!("{:?}", e))
+}
+
+fn signature_from_g2(point: &G2Affine) -> Result<bls12381::Signature> {
+    let mut bytes = Vec::with_capacity(bls12381::Signature::LENGTH);
+    point
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| anyhow!("{:?}", e))?;
+    Ok(bls12381::Signature::try_from(bytes.as_slice())?)
+}
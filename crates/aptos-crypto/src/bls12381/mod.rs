@@ -419,12 +419,18 @@
 /// Domain separation tag (DST) for hashing a message before signing it.
 pub const DST_BLS_SIG_IN_G2_WITH_POP: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
 
+pub mod bls12381_dkg;
 pub mod bls12381_keys;
 pub mod bls12381_pop;
 pub mod bls12381_sigs;
+pub mod bls12381_threshold;
 pub mod bls12381_validatable;
 
+pub use bls12381_dkg::Transcript;
 pub use bls12381_keys::{PrivateKey, PublicKey};
 pub use bls12381_pop::ProofOfPossession;
 pub use bls12381_sigs::Signature;
+pub use bls12381_threshold::{
+    PlayerId, PrivateKeyShare, PublicKeyShare, SignatureShare, ThresholdConfig,
+};
 pub use bls12381_validatable::UnvalidatedPublicKey;
@@ -0,0 +1,57 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for ECDSA signatures over the secp256k1 curve, as used by
+//! Bitcoin and Ethereum wallets and hardware security modules.
+//!
+//! Messages are hashed with SHA3-256 before being signed. Signature verification rejects
+//! signatures with a high S component, so that signatures are not malleable.
+//!
+//! # Examples
+//!
+//! ```
+//! use aptos_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use aptos_crypto::{
+//!     secp256k1_ecdsa::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//!     test_utils::KeyPair
+//! };
+//! use rand_core::OsRng;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng = OsRng;
+//! let kp = KeyPair::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>::generate(&mut rng);
+//!
+//! let signature = kp.private_key.sign(&message).unwrap();
+//! assert!(signature.verify(&message, &kp.public_key).is_ok());
+//! ```
+
+use crate::HashValue;
+
+/// The length of the Secp256k1EcdsaPrivateKey
+pub const SECP256K1_ECDSA_PRIVATE_KEY_LENGTH: usize = 32;
+/// The length of the Secp256k1EcdsaPublicKey, in its uncompressed form
+pub const SECP256K1_ECDSA_PUBLIC_KEY_LENGTH: usize = 65;
+/// The length of the Secp256k1EcdsaSignature
+pub const SECP256K1_ECDSA_SIGNATURE_LENGTH: usize = 64;
+
+/// The message actually signed for `message`: ECDSA signs a 32 byte digest, rather than the
+/// message itself.
+fn message_digest(message: &[u8]) -> libsecp256k1::Message {
+    libsecp256k1::Message::parse(HashValue::sha3_256_of(message).as_ref())
+}
+
+pub mod secp256k1_ecdsa_keys;
+pub mod secp256k1_ecdsa_sigs;
+
+#[cfg(any(test, feature = "fuzzing"))]
+pub use secp256k1_ecdsa_keys::keypair_strategy;
+pub use secp256k1_ecdsa_keys::{
+    Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPrivateKey as PrivateKey, Secp256k1EcdsaPublicKey,
+    Secp256k1EcdsaPublicKey as PublicKey,
+};
+pub use secp256k1_ecdsa_sigs::{Secp256k1EcdsaSignature, Secp256k1EcdsaSignature as Signature};
@@ -0,0 +1,154 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file implements traits for secp256k1 ECDSA signatures.
+
+use crate::{
+    hash::CryptoHash,
+    secp256k1_ecdsa::{
+        message_digest, Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey,
+        SECP256K1_ECDSA_SIGNATURE_LENGTH,
+    },
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey};
+use core::convert::TryFrom;
+use serde::Serialize;
+use std::fmt;
+
+/// A secp256k1 ECDSA signature, as the concatenation of its R and S components
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Secp256k1EcdsaSignature(pub(crate) libsecp256k1::Signature);
+
+impl Secp256k1EcdsaSignature {
+    /// The length of the Secp256k1EcdsaSignature
+    pub const LENGTH: usize = SECP256K1_ECDSA_SIGNATURE_LENGTH;
+
+    /// Serialize a Secp256k1EcdsaSignature.
+    pub fn to_bytes(&self) -> [u8; SECP256K1_ECDSA_SIGNATURE_LENGTH] {
+        self.0.serialize()
+    }
+
+    /// Deserialize a Secp256k1EcdsaSignature without any validation checks (malleability)
+    /// apart from expected signature size, and R and S lower than the order of the curve.
+    pub(crate) fn from_bytes_unchecked(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256k1EcdsaSignature, CryptoMaterialError> {
+        if bytes.len() != SECP256K1_ECDSA_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        match libsecp256k1::Signature::parse_standard_slice(bytes) {
+            Ok(signature) => Ok(Secp256k1EcdsaSignature(signature)),
+            Err(_) => Err(CryptoMaterialError::DeserializationError),
+        }
+    }
+
+    /// return an all-zero signature (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        Self::from_bytes_unchecked(&[0u8; Self::LENGTH]).unwrap()
+    }
+
+    /// Check for correct size and signature malleability issues.
+    ///
+    /// If (R, S) is a valid ECDSA signature, so is (R, -S), which lets anyone produce a distinct
+    /// yet valid signature for the same message and key. As in Bitcoin, only the signature with
+    /// the lower S (i.e., S no greater than half the order of the curve) is considered canonical.
+    pub fn check_s_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+        let signature = Secp256k1EcdsaSignature::from_bytes_unchecked(bytes)?;
+        if signature.0.s.is_high() {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        Ok(())
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Secp256k1EcdsaSignature {
+    type SigningKeyMaterial = Secp256k1EcdsaPrivateKey;
+    type VerifyingKeyMaterial = Secp256k1EcdsaPublicKey;
+
+    /// Verifies that the provided signature is valid for the provided message, rejecting
+    /// signatures with a high S component.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256k1EcdsaPublicKey,
+    ) -> Result<()> {
+        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`, the
+    /// message being hashed with SHA3-256 beforehand.
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Secp256k1EcdsaPublicKey,
+    ) -> Result<()> {
+        Secp256k1EcdsaSignature::check_s_malleability(&self.to_bytes())?;
+
+        if libsecp256k1::verify(&message_digest(message), &self.0, &public_key.0) {
+            Ok(())
+        } else {
+            Err(anyhow!("Secp256k1 ECDSA signature verification failed"))
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.serialize().to_vec()
+    }
+}
+
+impl Length for Secp256k1EcdsaSignature {
+    fn length(&self) -> usize {
+        SECP256K1_ECDSA_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256k1EcdsaSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Secp256k1EcdsaSignature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let encoded_signature = self.to_bytes();
+        state.write(&encoded_signature);
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256k1EcdsaSignature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256k1EcdsaSignature, CryptoMaterialError> {
+        // Mauled signatures are rejected early, they are checked again during verification
+        Secp256k1EcdsaSignature::check_s_malleability(bytes)?;
+        Secp256k1EcdsaSignature::from_bytes_unchecked(bytes)
+    }
+}
+
+// Those are required by the implementation of hash above
+impl PartialEq for Secp256k1EcdsaSignature {
+    fn eq(&self, other: &Secp256k1EcdsaSignature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Secp256k1EcdsaSignature {}
+
+impl fmt::Display for Secp256k1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256k1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256k1EcdsaSignature({})", self)
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for ECDSA signatures over the secp256r1 curve, also known as
+//! P-256 or prime256v1, as used by WebAuthn authenticators such as device passkeys. This module
+//! wraps the [ring](https://github.com/briansmith/ring) library, and validates keys with the
+//! [p256](https://docs.rs/p256) crate.
+//!
+//! Messages are hashed with SHA2-256 before being signed, as in the ES256 algorithm of WebAuthn.
+//! Signature verification rejects signatures with a high S component, so that signatures are not
+//! malleable: since authenticators do not normalize S, signatures obtained from them should be
+//! parsed via `Secp256r1EcdsaSignature::from_der`, which does.
+//!
+//! # Examples
+//!
+//! ```
+//! use aptos_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use aptos_crypto::{
+//!     secp256r1_ecdsa::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//!     test_utils::KeyPair
+//! };
+//! use rand_core::OsRng;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng = OsRng;
+//! let kp = KeyPair::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>::generate(&mut rng);
+//!
+//! let signature = kp.private_key.sign(&message).unwrap();
+//! assert!(signature.verify(&message, &kp.public_key).is_ok());
+//! ```
+
+/// The length of the Secp256r1EcdsaPrivateKey
+pub const SECP256R1_ECDSA_PRIVATE_KEY_LENGTH: usize = 32;
+/// The length of the Secp256r1EcdsaPublicKey, in its uncompressed form
+pub const SECP256R1_ECDSA_PUBLIC_KEY_LENGTH: usize = 65;
+/// The length of the Secp256r1EcdsaSignature
+pub const SECP256R1_ECDSA_SIGNATURE_LENGTH: usize = 64;
+
+pub mod secp256r1_ecdsa_keys;
+pub mod secp256r1_ecdsa_sigs;
+
+#[cfg(any(test, feature = "fuzzing"))]
+pub use secp256r1_ecdsa_keys::keypair_strategy;
+pub use secp256r1_ecdsa_keys::{
+    Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPrivateKey as PrivateKey, Secp256r1EcdsaPublicKey,
+    Secp256r1EcdsaPublicKey as PublicKey,
+};
+pub use secp256r1_ecdsa_sigs::{Secp256r1EcdsaSignature, Secp256r1EcdsaSignature as Signature};
@@ -0,0 +1,282 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file implements traits for secp256r1 ECDSA private keys and public keys.
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+use crate::{
+    hash::CryptoHash,
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaSignature, SECP256R1_ECDSA_PRIVATE_KEY_LENGTH,
+        SECP256R1_ECDSA_PUBLIC_KEY_LENGTH,
+    },
+    traits::*,
+};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use core::convert::TryFrom;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+use ring::{
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair as _, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde::Serialize;
+use std::fmt;
+
+/// A secp256r1 ECDSA private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Secp256r1EcdsaPrivateKey {
+    bytes: [u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH],
+    key_pair: EcdsaKeyPair,
+}
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Secp256r1EcdsaPrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Secp256r1EcdsaPrivateKey {
+    fn clone(&self) -> Self {
+        let serialized: &[u8] = &(self.to_bytes());
+        Secp256r1EcdsaPrivateKey::try_from(serialized).unwrap()
+    }
+}
+
+/// A secp256r1 ECDSA public key
+#[derive(DeserializeKey, Clone, Eq, Hash, PartialEq, SerializeKey)]
+pub struct Secp256r1EcdsaPublicKey(pub(crate) [u8; SECP256R1_ECDSA_PUBLIC_KEY_LENGTH]);
+
+impl Secp256r1EcdsaPrivateKey {
+    /// The length of the Secp256r1EcdsaPrivateKey
+    pub const LENGTH: usize = SECP256R1_ECDSA_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a Secp256r1EcdsaPrivateKey, as a big-endian scalar.
+    pub fn to_bytes(&self) -> [u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH] {
+        self.bytes
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256r1EcdsaSignature {
+        // ring mixes the private key and the message into the randomness of the nonce
+        let signature = self
+            .key_pair
+            .sign(&SystemRandom::new(), message)
+            .expect("Signing with a valid secp256r1 ECDSA key should not fail");
+        // Only low S signatures are accepted, see Secp256r1EcdsaSignature::check_s_malleability
+        Secp256r1EcdsaSignature::from_bytes_unchecked(signature.as_ref())
+            .expect("ring should produce signatures of the expected length")
+            .normalize_s()
+    }
+}
+
+impl Secp256r1EcdsaPublicKey {
+    /// The length of the Secp256r1EcdsaPublicKey
+    pub const LENGTH: usize = SECP256R1_ECDSA_PUBLIC_KEY_LENGTH;
+
+    /// Serialize a Secp256r1EcdsaPublicKey, in its uncompressed form.
+    pub fn to_bytes(&self) -> [u8; SECP256R1_ECDSA_PUBLIC_KEY_LENGTH] {
+        self.0
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for Secp256r1EcdsaPrivateKey {
+    type PublicKeyMaterial = Secp256r1EcdsaPublicKey;
+}
+
+impl SigningKey for Secp256r1EcdsaPrivateKey {
+    type SignatureMaterial = Secp256r1EcdsaSignature;
+    type VerifyingKeyMaterial = Secp256r1EcdsaPublicKey;
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<Secp256r1EcdsaSignature, CryptoMaterialError> {
+        Ok(Secp256r1EcdsaPrivateKey::sign_arbitrary_message(
+            self,
+            signing_message(message)?.as_ref(),
+        ))
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Secp256r1EcdsaSignature {
+        Secp256r1EcdsaPrivateKey::sign_arbitrary_message(self, message)
+    }
+}
+
+impl Uniform for Secp256r1EcdsaPrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng + ::rand_core::CryptoRng + ::rand_core::RngCore,
+    {
+        // Almost all 32 byte strings are valid scalars, only zero and values above the order of
+        // the curve are rejected
+        let mut bytes = [0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            if let Ok(private_key) = Secp256r1EcdsaPrivateKey::try_from(&bytes[..]) {
+                return private_key;
+            }
+        }
+    }
+}
+
+impl PartialEq<Self> for Secp256r1EcdsaPrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Secp256r1EcdsaPrivateKey {}
+
+impl TryFrom<&[u8]> for Secp256r1EcdsaPrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256r1EcdsaPrivateKey. This method will check for private key validity:
+    /// i.e., correct key length, and a non-zero scalar lower than the order of the curve.
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256r1EcdsaPrivateKey, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        // ring can't derive the public key of a private key, so the scalar is range checked, and
+        // its public key derived, by p256
+        let secret_key = p256::SecretKey::from_slice(bytes)
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        let public_key = secret_key.public_key().to_encoded_point(false);
+        let key_pair = EcdsaKeyPair::from_private_key_and_public_key(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            bytes,
+            public_key.as_bytes(),
+        )
+        .map_err(|_| CryptoMaterialError::DeserializationError)?;
+
+        let mut private_key_bytes = [0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH];
+        private_key_bytes.copy_from_slice(bytes);
+        Ok(Secp256r1EcdsaPrivateKey {
+            bytes: private_key_bytes,
+            key_pair,
+        })
+    }
+}
+
+impl Length for Secp256r1EcdsaPrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1EcdsaPrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Genesis for Secp256r1EcdsaPrivateKey {
+    fn genesis() -> Self {
+        let mut buf = [0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH];
+        buf[SECP256R1_ECDSA_PRIVATE_KEY_LENGTH - 1] = 1;
+        Self::try_from(buf.as_ref()).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+// Implementing From<&PrivateKey<...>> allows to derive a public key in a more elegant fashion
+impl From<&Secp256r1EcdsaPrivateKey> for Secp256r1EcdsaPublicKey {
+    fn from(private_key: &Secp256r1EcdsaPrivateKey) -> Self {
+        // ring's public keys are in their uncompressed form
+        let mut bytes = [0u8; SECP256R1_ECDSA_PUBLIC_KEY_LENGTH];
+        bytes.copy_from_slice(private_key.key_pair.public_key().as_ref());
+        Secp256r1EcdsaPublicKey(bytes)
+    }
+}
+
+// We deduce PublicKey from this
+impl PublicKey for Secp256r1EcdsaPublicKey {
+    type PrivateKeyMaterial = Secp256r1EcdsaPrivateKey;
+}
+
+// We deduce VerifyingKey from pointing to the signature material
+// we get the ability to do `pubkey.validate(msg, signature)`
+impl VerifyingKey for Secp256r1EcdsaPublicKey {
+    type SignatureMaterial = Secp256r1EcdsaSignature;
+    type SigningKeyMaterial = Secp256r1EcdsaPrivateKey;
+}
+
+impl fmt::Display for Secp256r1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Secp256r1EcdsaPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256r1EcdsaPublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256r1EcdsaPublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Secp256r1EcdsaPublicKey. This method will check for public key validity:
+    /// i.e., correct key length, and a point of the curve other than the identity. Only the
+    /// uncompressed form is accepted, so that a public key has a single representation, and thus
+    /// a single authentication key.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256r1EcdsaPublicKey, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        // The SEC1 tag of uncompressed points
+        if bytes[0] != 0x04 {
+            return Err(CryptoMaterialError::DeserializationError);
+        }
+        p256::PublicKey::from_sec1_bytes(bytes)
+            .map_err(|_| CryptoMaterialError::PointNotOnCurveError)?;
+        let mut public_key_bytes = [0u8; SECP256R1_ECDSA_PUBLIC_KEY_LENGTH];
+        public_key_bytes.copy_from_slice(bytes);
+        Ok(Secp256r1EcdsaPublicKey(public_key_bytes))
+    }
+}
+
+impl Length for Secp256r1EcdsaPublicKey {
+    fn length(&self) -> usize {
+        SECP256R1_ECDSA_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1EcdsaPublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+/////////////
+// Fuzzing //
+/////////////
+
+/// Produces a uniformly random secp256r1 ECDSA keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy(
+) -> impl Strategy<Value = KeyPair<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>> {
+    test_utils::uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()
+}
+
+/// Produces a uniformly random secp256r1 ECDSA public key
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for Secp256r1EcdsaPublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        keypair_strategy().prop_map(|v| v.public_key).boxed()
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file implements traits for secp256r1 ECDSA signatures.
+
+use crate::{
+    hash::CryptoHash,
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey, SECP256R1_ECDSA_SIGNATURE_LENGTH,
+    },
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey};
+use core::convert::TryFrom;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_FIXED};
+use serde::Serialize;
+use std::fmt;
+
+/// The length of each of the R and S components of a signature
+const SCALAR_LENGTH: usize = SECP256R1_ECDSA_SIGNATURE_LENGTH / 2;
+
+/// The order of the secp256r1 curve, in big-endian
+const ORDER: [u8; SCALAR_LENGTH] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xBC, 0xE6, 0xFA, 0xAD, 0xA7, 0x17, 0x9E, 0x84, 0xF3, 0xB9, 0xCA, 0xC2, 0xFC, 0x63, 0x25, 0x51,
+];
+
+/// Half the order of the secp256r1 curve, rounded down, in big-endian
+const HALF_ORDER: [u8; SCALAR_LENGTH] = [
+    0x7F, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 0x00, 0x00, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xDE, 0x73, 0x7D, 0x56, 0xD3, 0x8B, 0xCF, 0x42, 0x79, 0xDC, 0xE5, 0x61, 0x7E, 0x31, 0x92, 0xA8,
+];
+
+/// A secp256r1 ECDSA signature, as the concatenation of its big-endian R and S components
+#[derive(DeserializeKey, Clone, Eq, Hash, PartialEq, SerializeKey)]
+pub struct Secp256r1EcdsaSignature(pub(crate) [u8; SECP256R1_ECDSA_SIGNATURE_LENGTH]);
+
+impl Secp256r1EcdsaSignature {
+    /// The length of the Secp256r1EcdsaSignature
+    pub const LENGTH: usize = SECP256R1_ECDSA_SIGNATURE_LENGTH;
+
+    /// Serialize a Secp256r1EcdsaSignature.
+    pub fn to_bytes(&self) -> [u8; SECP256R1_ECDSA_SIGNATURE_LENGTH] {
+        self.0
+    }
+
+    /// Deserialize a Secp256r1EcdsaSignature without any validation checks (malleability)
+    /// apart from expected signature size.
+    pub(crate) fn from_bytes_unchecked(
+        bytes: &[u8],
+    ) -> std::result::Result<Secp256r1EcdsaSignature, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_ECDSA_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let mut signature = [0u8; SECP256R1_ECDSA_SIGNATURE_LENGTH];
+        signature.copy_from_slice(bytes);
+        Ok(Secp256r1EcdsaSignature(signature))
+    }
+
+    /// Deserialize a signature in the ASN.1 DER form produced by WebAuthn authenticators, i.e., a
+    /// sequence of the R and S integers, replacing a high S by its low counterpart.
+    pub fn from_der(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+        let (tag, contents, rest) = der_element(bytes)?;
+        if tag != DER_SEQUENCE_TAG || !rest.is_empty() {
+            return Err(CryptoMaterialError::DeserializationError);
+        }
+        let (r, contents) = der_scalar(contents)?;
+        let (s, contents) = der_scalar(contents)?;
+        if !contents.is_empty() {
+            return Err(CryptoMaterialError::DeserializationError);
+        }
+
+        let mut signature = [0u8; SECP256R1_ECDSA_SIGNATURE_LENGTH];
+        signature[..SCALAR_LENGTH].copy_from_slice(&r);
+        signature[SCALAR_LENGTH..].copy_from_slice(&s);
+        Ok(Secp256r1EcdsaSignature(signature).normalize_s())
+    }
+
+    /// return an all-zero signature (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        Self::from_bytes_unchecked(&[0u8; Self::LENGTH]).unwrap()
+    }
+
+    /// Check for correct size and signature malleability issues.
+    ///
+    /// If (R, S) is a valid ECDSA signature, so is (R, -S), which lets anyone produce a distinct
+    /// yet valid signature for the same message and key. Only the signature with the lower S
+    /// (i.e., S no greater than half the order of the curve) is considered canonical.
+    pub fn check_s_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+        let signature = Secp256r1EcdsaSignature::from_bytes_unchecked(bytes)?;
+        if signature.s() > &HALF_ORDER {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        Ok(())
+    }
+
+    /// Replaces a high S by its low counterpart `order - S`, which is also valid.
+    pub(crate) fn normalize_s(mut self) -> Self {
+        if self.s() > &HALF_ORDER {
+            let mut borrow = 0u16;
+            for i in (0..SCALAR_LENGTH).rev() {
+                let s = &mut self.0[SCALAR_LENGTH + i];
+                let difference = 0x100 + ORDER[i] as u16 - *s as u16 - borrow;
+                *s = difference as u8;
+                borrow = if difference < 0x100 { 1 } else { 0 };
+            }
+        }
+        self
+    }
+
+    fn s(&self) -> &[u8; SCALAR_LENGTH] {
+        self.0[SCALAR_LENGTH..]
+            .try_into()
+            .expect("A signature is twice as long as a scalar")
+    }
+}
+
+/// The DER tags of the elements of a signature
+const DER_SEQUENCE_TAG: u8 = 0x30;
+const DER_INTEGER_TAG: u8 = 0x02;
+
+/// Splits the first DER element of `bytes` into its tag and contents, and returns the remaining
+/// bytes. Signatures are short enough for their lengths to always be in the short form.
+fn der_element(bytes: &[u8]) -> std::result::Result<(u8, &[u8], &[u8]), CryptoMaterialError> {
+    match bytes {
+        [tag, length, rest @ ..] if *length < 0x80 && rest.len() >= *length as usize => {
+            let (contents, rest) = rest.split_at(*length as usize);
+            Ok((*tag, contents, rest))
+        },
+        _ => Err(CryptoMaterialError::DeserializationError),
+    }
+}
+
+/// Parses a DER integer at the start of `bytes` as a big-endian scalar, and returns the remaining
+/// bytes. Its range is only checked when verifying the signature.
+fn der_scalar(
+    bytes: &[u8],
+) -> std::result::Result<([u8; SCALAR_LENGTH], &[u8]), CryptoMaterialError> {
+    let (tag, mut integer, rest) = der_element(bytes)?;
+    if tag != DER_INTEGER_TAG || integer.is_empty() {
+        return Err(CryptoMaterialError::DeserializationError);
+    }
+    // Positive integers with their high bit set are prefixed with a zero byte
+    if integer.len() > 1 && integer[0] == 0 {
+        integer = &integer[1..];
+    }
+    if integer.len() > SCALAR_LENGTH {
+        return Err(CryptoMaterialError::DeserializationError);
+    }
+    let mut scalar = [0u8; SCALAR_LENGTH];
+    scalar[SCALAR_LENGTH - integer.len()..].copy_from_slice(integer);
+    Ok((scalar, rest))
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Secp256r1EcdsaSignature {
+    type SigningKeyMaterial = Secp256r1EcdsaPrivateKey;
+    type VerifyingKeyMaterial = Secp256r1EcdsaPublicKey;
+
+    /// Verifies that the provided signature is valid for the provided message, rejecting
+    /// signatures with a high S component.
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`, the
+    /// message being hashed with SHA2-256 beforehand. This also checks that `public_key` is a
+    /// valid curve point.
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        Secp256r1EcdsaSignature::check_s_malleability(&self.to_bytes())?;
+
+        UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, &public_key.0[..])
+            .verify(message, &self.0)
+            .map_err(|_| anyhow!("Secp256r1 ECDSA signature verification failed"))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl Length for Secp256r1EcdsaSignature {
+    fn length(&self) -> usize {
+        SECP256R1_ECDSA_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1EcdsaSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256r1EcdsaSignature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> std::result::Result<Secp256r1EcdsaSignature, CryptoMaterialError> {
+        // Mauled signatures are rejected early, they are checked again during verification
+        Secp256r1EcdsaSignature::check_s_malleability(bytes)?;
+        Secp256r1EcdsaSignature::from_bytes_unchecked(bytes)
+    }
+}
+
+impl fmt::Display for Secp256r1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Secp256r1EcdsaSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256r1EcdsaSignature({})", self)
+    }
+}
@@ -307,7 +307,7 @@ pub trait Genesis: PrivateKey {
 pub(crate) mod private {
     pub trait Sealed {}
 
-    // Implement for the ed25519, multi-ed25519 signatures
+    // Implement for the ed25519, multi-ed25519, secp256k1 and secp256r1 ECDSA signatures
     impl Sealed for crate::ed25519::Ed25519PrivateKey {}
     impl Sealed for crate::ed25519::Ed25519PublicKey {}
     impl Sealed for crate::ed25519::Ed25519Signature {}
@@ -316,8 +316,20 @@ pub(crate) mod private {
     impl Sealed for crate::multi_ed25519::MultiEd25519PublicKey {}
     impl Sealed for crate::multi_ed25519::MultiEd25519Signature {}
 
+    impl Sealed for crate::secp256k1_ecdsa::Secp256k1EcdsaPrivateKey {}
+    impl Sealed for crate::secp256k1_ecdsa::Secp256k1EcdsaPublicKey {}
+    impl Sealed for crate::secp256k1_ecdsa::Secp256k1EcdsaSignature {}
+
+    impl Sealed for crate::secp256r1_ecdsa::Secp256r1EcdsaPrivateKey {}
+    impl Sealed for crate::secp256r1_ecdsa::Secp256r1EcdsaPublicKey {}
+    impl Sealed for crate::secp256r1_ecdsa::Secp256r1EcdsaSignature {}
+
     impl Sealed for crate::bls12381::PrivateKey {}
     impl Sealed for crate::bls12381::PublicKey {}
     impl Sealed for crate::bls12381::Signature {}
     impl Sealed for crate::bls12381::ProofOfPossession {}
+
+    impl Sealed for crate::bls12381::PrivateKeyShare {}
+    impl Sealed for crate::bls12381::PublicKeyShare {}
+    impl Sealed for crate::bls12381::SignatureShare {}
 }
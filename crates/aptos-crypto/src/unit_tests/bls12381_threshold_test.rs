@@ -0,0 +1,285 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bls12381,
+    bls12381::{PrivateKeyShare, PublicKeyShare, SignatureShare, ThresholdConfig, Transcript},
+    test_utils::TestAptosCrypto,
+    PrivateKey, Signature, SigningKey, Uniform,
+};
+use ark_bls12_381::Fr;
+use ark_ff::One;
+use proptest::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_core::OsRng;
+use std::convert::TryFrom;
+
+// A 2-out-of-3 sharing of a fixed private key, computed independently: the private key, and
+// the shares `f(1)`, `f(2)` and `f(3)` of players 1, 2 and 3.
+const PRIVATE_KEY: &str = "39561d1177a70b85dd5ccbe075600692e7dafb46f39eaede87080385996a758e";
+const SHARES: [&str; 3] = [
+    "23e1f97e4b62e20d9459f08ae71698aac3cefcfc79bfd61e34380c3b879b4363",
+    "0e6dd5eb1f1eb8954b57153558cd2ac29fc2feb1ffe0fd5de16814f175cc1138",
+    "6ce759ab1c780c65358e11e7d42594dfcf74a46a8600809c8e981da663fcdf0e",
+];
+
+fn test_message() -> TestAptosCrypto {
+    TestAptosCrypto("Hello threshold world".to_string())
+}
+
+fn known_answer_shares() -> Vec<PrivateKeyShare> {
+    SHARES
+        .iter()
+        .zip(1u16..)
+        .map(|(share, player)| {
+            let mut bytes = player.to_le_bytes().to_vec();
+            bytes.extend(hex::decode(share).unwrap());
+            PrivateKeyShare::try_from(bytes.as_slice()).unwrap()
+        })
+        .collect()
+}
+
+/// Tests that any 2 of the known-answer shares combine into the signature, and the public key, of
+/// the shared private key.
+#[test]
+fn bls12381_threshold_known_answer() {
+    let config = ThresholdConfig::new(2, 3).unwrap();
+    let private_key =
+        bls12381::PrivateKey::try_from(hex::decode(PRIVATE_KEY).unwrap().as_slice()).unwrap();
+    let shares = known_answer_shares();
+    let message = test_message();
+    let expected_signature = private_key.sign(&message).unwrap();
+
+    for players in [[0, 1], [0, 2], [1, 2], [2, 0]] {
+        let signature_shares: Vec<_> = players
+            .iter()
+            .map(|i| shares[*i].sign(&message).unwrap())
+            .collect();
+        assert_eq!(
+            config.combine_signature_shares(&signature_shares).unwrap(),
+            expected_signature
+        );
+
+        let public_key_shares: Vec<_> = players.iter().map(|i| shares[*i].public_key()).collect();
+        assert_eq!(
+            config
+                .combine_public_key_shares(&public_key_shares)
+                .unwrap(),
+            private_key.public_key()
+        );
+    }
+}
+
+/// Tests the Lagrange coefficients to interpolate at 0 from players 1, 2 and 3, which are
+/// 3, -3 and 1.
+#[test]
+fn bls12381_threshold_lagrange_coefficients() {
+    let config = ThresholdConfig::new(3, 3).unwrap();
+    assert_eq!(
+        config.lagrange_coefficients(&[1, 2, 3]).unwrap(),
+        vec![Fr::from(3u64), -Fr::from(3u64), Fr::one()]
+    );
+
+    assert!(config.lagrange_coefficients(&[1, 1, 3]).is_err());
+    assert!(config.lagrange_coefficients(&[0, 1, 2]).is_err());
+    assert!(config.lagrange_coefficients(&[1, 2, 4]).is_err());
+}
+
+/// Tests that invalid threshold configurations are rejected.
+#[test]
+fn bls12381_threshold_config() {
+    assert!(ThresholdConfig::new(0, 3).is_err());
+    assert!(ThresholdConfig::new(4, 3).is_err());
+    assert!(ThresholdConfig::new(1, 1).is_ok());
+    assert!(ThresholdConfig::new(3, 3).is_ok());
+}
+
+/// Tests that a signature share does not verify under the public key share of another player, nor
+/// on another message.
+#[test]
+fn bls12381_threshold_sigshare_verify() {
+    let shares = known_answer_shares();
+    let message = test_message();
+    let message_wrong = TestAptosCrypto("Wello threshold horld".to_string());
+
+    let signature_share = shares[0].sign(&message).unwrap();
+    assert!(signature_share
+        .verify(&message, &shares[0].public_key())
+        .is_ok());
+    assert!(signature_share
+        .verify(&message_wrong, &shares[0].public_key())
+        .is_err());
+    assert!(signature_share
+        .verify(&message, &shares[1].public_key())
+        .is_err());
+}
+
+/// Tests the (de)serialization of shares, and that player 0 is rejected.
+#[test]
+fn bls12381_threshold_serialize_shares() {
+    let shares = known_answer_shares();
+    let private_key_share = &shares[2];
+    let public_key_share = private_key_share.public_key();
+    let signature_share = private_key_share.sign(&test_message()).unwrap();
+
+    let bytes = private_key_share.to_bytes();
+    assert_eq!(bytes.len(), PrivateKeyShare::LENGTH);
+    assert_eq!(
+        PrivateKeyShare::try_from(bytes.as_slice())
+            .unwrap()
+            .to_bytes(),
+        bytes
+    );
+
+    let bytes = public_key_share.to_bytes();
+    assert_eq!(bytes.len(), PublicKeyShare::LENGTH);
+    assert_eq!(
+        PublicKeyShare::try_from(bytes.as_slice()).unwrap(),
+        public_key_share
+    );
+
+    let bytes = signature_share.to_bytes();
+    assert_eq!(bytes.len(), SignatureShare::LENGTH);
+    assert_eq!(
+        SignatureShare::try_from(bytes.as_slice()).unwrap(),
+        signature_share
+    );
+
+    let mut bytes = signature_share.to_bytes();
+    bytes[..2].copy_from_slice(&0u16.to_le_bytes());
+    assert!(SignatureShare::try_from(bytes.as_slice()).is_err());
+    assert!(SignatureShare::try_from(&bytes[1..]).is_err());
+}
+
+/// Tests a DKG between 4 dealers for a 3-out-of-5 threshold key: the aggregated transcript gives
+/// the public key that the combined signatures of the players verify under.
+#[test]
+fn bls12381_dkg_should_verify() {
+    let mut rng = OsRng;
+    let config = ThresholdConfig::new(3, 5).unwrap();
+    let message = test_message();
+
+    let (transcripts, dealt_shares): (Vec<_>, Vec<_>) = [1, 2, 4, 5]
+        .iter()
+        .map(|dealer| Transcript::deal(&config, *dealer, &mut rng).unwrap())
+        .unzip();
+    for (transcript, shares) in transcripts.iter().zip(&dealt_shares) {
+        assert!(transcript.verify().is_ok());
+        for share in shares {
+            assert!(transcript.verify_share(share).is_ok());
+        }
+    }
+
+    let transcript = Transcript::aggregate(&transcripts).unwrap();
+    assert!(transcript.verify().is_ok());
+    assert_eq!(transcript.dealers(), &[1u16, 2, 4, 5]);
+
+    let shares: Vec<_> = (0..config.num_players() as usize)
+        .map(|i| {
+            let received: Vec<_> = dealt_shares
+                .iter()
+                .map(|shares| shares[i].clone())
+                .collect();
+            PrivateKeyShare::aggregate(&received).unwrap()
+        })
+        .collect();
+    for share in &shares {
+        assert!(transcript.verify_share(share).is_ok());
+        assert_eq!(
+            transcript.public_key_share(share.player()).unwrap(),
+            share.public_key()
+        );
+    }
+
+    let signature_shares: Vec<_> = shares[2..]
+        .iter()
+        .map(|share| share.sign(&message).unwrap())
+        .collect();
+    let signature = config.combine_signature_shares(&signature_shares).unwrap();
+    assert!(signature
+        .verify(&message, transcript.public_key().unwrap())
+        .is_ok());
+
+    // A share from another dealing does not verify under the transcript
+    let (_, other_shares) = Transcript::deal(&config, 3, &mut rng).unwrap();
+    assert!(transcript.verify_share(&other_shares[0]).is_err());
+}
+
+/// Tests that malformed transcripts are rejected, and that transcripts from the same dealer, or
+/// for different configurations, cannot be aggregated.
+#[test]
+fn bls12381_dkg_invalid_transcripts() {
+    let mut rng = OsRng;
+    let config = ThresholdConfig::new(2, 3).unwrap();
+
+    assert!(Transcript::deal(&config, 0, &mut rng).is_err());
+    assert!(Transcript::deal(&config, 4, &mut rng).is_err());
+
+    let (transcript, _) = Transcript::deal(&config, 1, &mut rng).unwrap();
+    let (transcript_same_dealer, _) = Transcript::deal(&config, 1, &mut rng).unwrap();
+    assert!(Transcript::aggregate(&[transcript.clone(), transcript_same_dealer]).is_err());
+
+    let other_config = ThresholdConfig::new(3, 3).unwrap();
+    let (transcript_other_config, _) = Transcript::deal(&other_config, 2, &mut rng).unwrap();
+    assert!(Transcript::aggregate(&[transcript.clone(), transcript_other_config]).is_err());
+    assert!(Transcript::aggregate(&[]).is_err());
+
+    // A transcript with too few commitments does not verify, nor can it be aggregated
+    let truncated = drop_last_commitment(&transcript, 2);
+    assert!(truncated.verify().is_err());
+    assert!(truncated.public_key().is_ok());
+    let (other_transcript, _) = Transcript::deal(&config, 2, &mut rng).unwrap();
+    assert!(Transcript::aggregate(&[other_transcript.clone(), truncated.clone()]).is_err());
+    assert!(Transcript::aggregate(&[truncated.clone(), other_transcript]).is_err());
+
+    // Nor does a transcript without commitments give a public key
+    let empty = drop_last_commitment(&truncated, 1);
+    assert!(empty.verify().is_err());
+    assert!(empty.public_key().is_err());
+}
+
+/// Drops the last of the `num_commitments` commitments of a transcript, which can't be done
+/// through its API.
+fn drop_last_commitment(transcript: &Transcript, num_commitments: usize) -> Transcript {
+    let mut bytes = bcs::to_bytes(transcript).unwrap();
+    assert_eq!(&bcs::from_bytes::<Transcript>(&bytes).unwrap(), transcript);
+    // Drops the last commitment, and decrements the length of the commitments, which precedes
+    // the remaining ones. Each commitment is serialized with a 1-byte length prefix.
+    let commitment_length = 1 + bls12381::PublicKey::LENGTH;
+    bytes.truncate(bytes.len() - commitment_length);
+    let length_index = bytes.len() - (num_commitments - 1) * commitment_length - 1;
+    bytes[length_index] -= 1;
+    bcs::from_bytes::<Transcript>(&bytes).unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    /// Tests that any `threshold` shares of a random private key combine into a signature
+    /// that verifies under its public key, while fewer shares are rejected.
+    #[test]
+    fn bls12381_threshold_combine(
+        threshold in 1u16..5,
+        extra_players in 0u16..3,
+        seed in any::<[u8; 32]>(),
+    ) {
+        let mut rng = StdRng::from_seed(seed);
+        let config = ThresholdConfig::new(threshold, threshold + extra_players).unwrap();
+        let private_key = bls12381::PrivateKey::generate(&mut rng);
+        let message = test_message();
+
+        let shares = config.split_private_key(&private_key, &mut rng);
+        prop_assert_eq!(shares.len(), config.num_players() as usize);
+
+        let signature_shares: Vec<_> = shares
+            .iter()
+            .rev()
+            .map(|share| share.sign(&message).unwrap())
+            .collect();
+        let signature = config.combine_signature_shares(&signature_shares).unwrap();
+        prop_assert!(signature.verify(&message, &private_key.public_key()).is_ok());
+
+        let too_few = &signature_shares[..threshold as usize - 1];
+        prop_assert!(config.combine_signature_shares(too_few).is_err());
+    }
+}
@@ -323,6 +323,90 @@ proptest! {
         prop_assert!(Ed25519Signature::batch_verify(&message, signatures).is_err());
     }
 
+    #[test]
+    fn test_batch_verify_arbitrary_msgs(
+        messages in proptest::array::uniform10(vec(proptest::num::u8::ANY, 0..64)),
+        keypairs in proptest::array::uniform10(uniform_keypair_strategy::<Ed25519PrivateKey, Ed25519PublicKey>())
+    ) {
+        let signatures: Vec<Ed25519Signature> = keypairs.iter().zip(messages.iter()).map(|(keypair, message)| {
+            keypair.private_key.sign_arbitrary_message(message)
+        }).collect();
+        let mut batch: Vec<(&[u8], &Ed25519PublicKey, &Ed25519Signature)> = messages
+            .iter()
+            .zip(keypairs.iter())
+            .zip(signatures.iter())
+            .map(|((message, keypair), signature)| (message.as_slice(), &keypair.public_key, signature))
+            .collect();
+        prop_assert!(Ed25519Signature::batch_verify_arbitrary_msgs(&batch).is_ok());
+        prop_assert!(Ed25519Signature::batch_verify_arbitrary_msgs(&[]).is_ok());
+
+        // A signature over another message invalidates the whole batch
+        let other_message = b"some other message".to_vec();
+        batch[3].0 = &other_message;
+        prop_assert!(Ed25519Signature::batch_verify_arbitrary_msgs(&batch).is_err());
+    }
+
+    #[test]
+    fn test_batch_verify_arbitrary_msgs_small_order(
+        (r_point, pub_point, message) in small_order_pk_with_adversarial_message(),
+        keypair in uniform_keypair_strategy::<Ed25519PrivateKey, Ed25519PublicKey>()
+    ) {
+        // A zero S signature with a small order R and public key satisfies the verification
+        // equation, and must be rejected as it is by verify_strict
+        let mut sig_bytes = [0u8; ED25519_SIGNATURE_LENGTH];
+        sig_bytes[..32].copy_from_slice(&r_point.compress().to_bytes());
+        let signature = Ed25519Signature::from_bytes_unchecked(&sig_bytes).unwrap();
+        let public_key =
+            Ed25519PublicKey::from_bytes_unchecked(&pub_point.compress().to_bytes()).unwrap();
+        let message = signing_message(&message).unwrap();
+
+        let valid_signature = keypair.private_key.sign_arbitrary_message(&message);
+        prop_assert!(Ed25519Signature::batch_verify_arbitrary_msgs(&[
+            (&message, &keypair.public_key, &valid_signature),
+            (&message, &public_key, &signature),
+        ]).is_err());
+    }
+
+    #[test]
+    fn test_batch_verify_arbitrary_msgs_torsion_r(
+        secret in any::<[u8; 32]>(),
+        nonce in any::<[u8; 32]>(),
+        idx in 1usize..8usize,
+        keypair in uniform_keypair_strategy::<Ed25519PrivateKey, Ed25519PublicKey>()
+    ) {
+        // A signer can add a torsion component to R, so that the signature satisfies the
+        // combined equation for some batch coefficients. verify_strict rejects it, and so must
+        // batch verification, whatever the coefficients.
+        let message = b"hello_world";
+        let a = Scalar::from_bytes_mod_order(secret);
+        let public_key_bytes = ED25519_BASEPOINT_POINT.mul(a).compress().to_bytes();
+        let public_key = Ed25519PublicKey::try_from(&public_key_bytes[..]).unwrap();
+        let r = Scalar::from_bytes_mod_order(nonce);
+        let torsion_component = CompressedEdwardsY(EIGHT_TORSION[idx]).decompress().unwrap();
+        let r_bytes = ED25519_BASEPOINT_POINT.mul(r).add(torsion_component).compress().to_bytes();
+
+        let mut h = Sha512::default();
+        h.update(r_bytes);
+        h.update(public_key_bytes);
+        h.update(message);
+        let mut output = [0u8; 64];
+        output.copy_from_slice(h.finalize().as_slice());
+        let k = Scalar::from_bytes_mod_order_wide(&output);
+        let mut sig_bytes = [0u8; ED25519_SIGNATURE_LENGTH];
+        sig_bytes[..32].copy_from_slice(&r_bytes);
+        sig_bytes[32..].copy_from_slice(&(r + k * a).to_bytes());
+        let signature = Ed25519Signature::try_from(&sig_bytes[..]).unwrap();
+        prop_assert!(signature.verify_arbitrary_msg(message, &public_key).is_err());
+
+        let valid_signature = keypair.private_key.sign_arbitrary_message(message);
+        for _ in 0..16 {
+            prop_assert!(Ed25519Signature::batch_verify_arbitrary_msgs(&[
+                (&message[..], &keypair.public_key, &valid_signature),
+                (&message[..], &public_key, &signature),
+            ]).is_err());
+        }
+    }
+
     #[test]
     fn test_keys_custom_serialisation(
         keypair in uniform_keypair_strategy::<Ed25519PrivateKey, Ed25519PublicKey>()
@@ -3,6 +3,7 @@
 
 mod bcs_test;
 mod bls12381_test;
+mod bls12381_threshold_test;
 mod compat_test;
 mod cross_test;
 mod cryptohasher;
@@ -11,3 +12,5 @@ mod hash_test;
 mod hkdf_test;
 mod multi_ed25519_test;
 mod noise_test;
+mod secp256k1_ecdsa_test;
+mod secp256r1_ecdsa_test;
@@ -0,0 +1,140 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    secp256k1_ecdsa::{
+        Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature,
+        SECP256K1_ECDSA_PRIVATE_KEY_LENGTH, SECP256K1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256K1_ECDSA_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy},
+    traits::*,
+};
+use core::convert::TryFrom;
+use proptest::{collection::vec, prelude::*};
+
+proptest! {
+    #[test]
+    fn test_keys_encode(keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()) {
+        {
+            let encoded = keypair.private_key.to_encoded_string().unwrap();
+            prop_assert_eq!(2 + 2 * SECP256K1_ECDSA_PRIVATE_KEY_LENGTH, encoded.len());
+            let decoded = Secp256k1EcdsaPrivateKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.private_key), decoded.ok());
+        }
+        {
+            let encoded = keypair.public_key.to_encoded_string().unwrap();
+            prop_assert_eq!(2 + 2 * SECP256K1_ECDSA_PUBLIC_KEY_LENGTH, encoded.len());
+            let decoded = Secp256k1EcdsaPublicKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.public_key), decoded.ok());
+        }
+    }
+
+    #[test]
+    fn test_keys_custom_serialisation(
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        {
+            let serialized: &[u8] = &(keypair.private_key.to_bytes());
+            prop_assert_eq!(SECP256K1_ECDSA_PRIVATE_KEY_LENGTH, serialized.len());
+            let deserialized = Secp256k1EcdsaPrivateKey::try_from(serialized);
+            prop_assert_eq!(Some(keypair.private_key), deserialized.ok());
+        }
+        {
+            let serialized: &[u8] = &(keypair.public_key.to_bytes());
+            prop_assert_eq!(SECP256K1_ECDSA_PUBLIC_KEY_LENGTH, serialized.len());
+            let deserialized = Secp256k1EcdsaPublicKey::try_from(serialized);
+            prop_assert_eq!(Some(keypair.public_key), deserialized.ok());
+        }
+    }
+
+    #[test]
+    fn test_signature_verification_custom_serialisation(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(SECP256K1_ECDSA_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = Secp256k1EcdsaSignature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify(&message, &keypair.public_key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_verification_from_arbitrary(
+        msg in vec(proptest::num::u8::ANY, 1..128),
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign_arbitrary_message(&msg);
+        prop_assert!(signature.verify_arbitrary_msg(&msg, &keypair.public_key).is_ok());
+
+        // The signature is bound to the message and to the key
+        let mut other_msg = msg.clone();
+        other_msg[0] ^= 1;
+        prop_assert!(signature.verify_arbitrary_msg(&other_msg, &keypair.public_key).is_err());
+        let other_public_key = Secp256k1EcdsaPrivateKey::generate_for_testing().public_key();
+        prop_assert!(signature.verify_arbitrary_msg(&msg, &other_public_key).is_err());
+    }
+
+    // Check for low S.
+    #[test]
+    fn test_signature_malleability(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+        prop_assert!(!signature.0.s.is_high());
+
+        // (R, -S) is also a valid ECDSA signature for the message, but with a high S
+        let mut mauled = libsecp256k1::Signature::parse_standard(&signature.to_bytes()).unwrap();
+        mauled.s = -mauled.s;
+        let message_digest = libsecp256k1::Message::parse(
+            crate::HashValue::sha3_256_of(&signing_message(&message).unwrap()).as_ref(),
+        );
+        prop_assert!(libsecp256k1::verify(&message_digest, &mauled, &keypair.public_key.0));
+
+        // try_from will fail on malleable signatures, and so will verification
+        let serialized_mauled: &[u8] = &mauled.serialize();
+        prop_assert_eq!(
+            Secp256k1EcdsaSignature::try_from(serialized_mauled),
+            Err(CryptoMaterialError::CanonicalRepresentationError)
+        );
+        let sig_unchecked = Secp256k1EcdsaSignature::from_bytes_unchecked(serialized_mauled).unwrap();
+        prop_assert!(sig_unchecked.verify(&message, &keypair.public_key).is_err());
+    }
+}
+
+#[test]
+fn test_public_key_deserialization() {
+    let public_key = Secp256k1EcdsaPrivateKey::generate_for_testing().public_key();
+    let serialized = public_key.to_bytes();
+
+    // Only the uncompressed form is accepted
+    let compressed = public_key.0.serialize_compressed();
+    assert_eq!(
+        Secp256k1EcdsaPublicKey::try_from(&compressed[..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+
+    // The point must be on the curve
+    let mut invalid = serialized;
+    invalid[SECP256K1_ECDSA_PUBLIC_KEY_LENGTH - 1] ^= 1;
+    assert_eq!(
+        Secp256k1EcdsaPublicKey::try_from(&invalid[..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+}
+
+#[test]
+fn test_private_key_deserialization() {
+    // Zero is not a valid private key
+    let zero = [0u8; SECP256K1_ECDSA_PRIVATE_KEY_LENGTH];
+    assert_eq!(
+        Secp256k1EcdsaPrivateKey::try_from(&zero[..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+    assert_eq!(
+        Secp256k1EcdsaPrivateKey::try_from(&zero[1..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+}
@@ -0,0 +1,205 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    secp256r1_ecdsa::{
+        Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature,
+        SECP256R1_ECDSA_PRIVATE_KEY_LENGTH, SECP256R1_ECDSA_PUBLIC_KEY_LENGTH,
+        SECP256R1_ECDSA_SIGNATURE_LENGTH,
+    },
+    test_utils::{random_serializable_struct, uniform_keypair_strategy},
+    traits::*,
+};
+use core::convert::TryFrom;
+use proptest::{collection::vec, prelude::*};
+
+// A signature on `MESSAGE` produced by an independent ES256 implementation, in the DER form that
+// WebAuthn authenticators produce, with a high S; and the same signature with S normalized.
+const PRIVATE_KEY: &str = "1f0b1e8c7c4e0d6a3f9a2b5c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e";
+const PUBLIC_KEY: &str = "04fe5425df2969cda8f49495962e0de253a6f5c7d93e52d61ea4c9d0325b9c73df\
+                          3edc3c6d4149ef3fd5742da9d6936996fae9048c108fe9b74b581072e427909f";
+const MESSAGE: &[u8] = b"Hello passkeys";
+const DER_SIGNATURE: &str = "304502207253467f8617ed740f9059fc5bae12a0c5b56975f267dda8afb22e3a9f\
+                             547d0902210080b28b85e33f1690d9c9b28664cb8954c61849571891bb879b1c42\
+                             100bf371f4";
+const SIGNATURE: &str = "7253467f8617ed740f9059fc5bae12a0c5b56975f267dda8afb22e3a9f547d09\
+                         7f4d74791cc0e97026364d799b3476aaf6ceb1568e85e2fd589d88b2f06fb35d";
+
+proptest! {
+    #[test]
+    fn test_keys_encode(keypair in uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()) {
+        {
+            let encoded = keypair.private_key.to_encoded_string().unwrap();
+            prop_assert_eq!(2 + 2 * SECP256R1_ECDSA_PRIVATE_KEY_LENGTH, encoded.len());
+            let decoded = Secp256r1EcdsaPrivateKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.private_key), decoded.ok());
+        }
+        {
+            let encoded = keypair.public_key.to_encoded_string().unwrap();
+            prop_assert_eq!(2 + 2 * SECP256R1_ECDSA_PUBLIC_KEY_LENGTH, encoded.len());
+            let decoded = Secp256r1EcdsaPublicKey::from_encoded_string(&encoded);
+            prop_assert_eq!(Some(keypair.public_key), decoded.ok());
+        }
+    }
+
+    #[test]
+    fn test_signature_verification_custom_serialisation(
+        message in random_serializable_struct(),
+        keypair in uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign(&message).unwrap();
+        let serialized: &[u8] = &(signature.to_bytes());
+        prop_assert_eq!(SECP256R1_ECDSA_SIGNATURE_LENGTH, serialized.len());
+        let deserialized = Secp256r1EcdsaSignature::try_from(serialized).unwrap();
+        prop_assert!(deserialized.verify(&message, &keypair.public_key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_verification_from_arbitrary(
+        msg in vec(proptest::num::u8::ANY, 1..128),
+        keypair in uniform_keypair_strategy::<Secp256r1EcdsaPrivateKey, Secp256r1EcdsaPublicKey>()
+    ) {
+        let signature = keypair.private_key.sign_arbitrary_message(&msg);
+        prop_assert!(signature.verify_arbitrary_msg(&msg, &keypair.public_key).is_ok());
+
+        // The signature is bound to the message and to the key
+        let mut other_msg = msg.clone();
+        other_msg[0] ^= 1;
+        prop_assert!(signature.verify_arbitrary_msg(&other_msg, &keypair.public_key).is_err());
+        let other_public_key = Secp256r1EcdsaPrivateKey::generate_for_testing().public_key();
+        prop_assert!(signature.verify_arbitrary_msg(&msg, &other_public_key).is_err());
+    }
+}
+
+#[test]
+fn test_known_answer() {
+    let private_key =
+        Secp256r1EcdsaPrivateKey::try_from(hex::decode(PRIVATE_KEY).unwrap().as_slice()).unwrap();
+    let public_key =
+        Secp256r1EcdsaPublicKey::try_from(hex::decode(PUBLIC_KEY).unwrap().as_slice()).unwrap();
+    assert_eq!(private_key.public_key(), public_key);
+
+    // The DER signature has a high S, which is normalized when parsing it
+    let signature =
+        Secp256r1EcdsaSignature::from_der(&hex::decode(DER_SIGNATURE).unwrap()).unwrap();
+    assert_eq!(hex::encode(signature.to_bytes()), SIGNATURE);
+    assert!(signature.verify_arbitrary_msg(MESSAGE, &public_key).is_ok());
+    assert!(signature
+        .verify_arbitrary_msg(b"Hello passwords", &public_key)
+        .is_err());
+
+    // Our own signatures verify too, and always have a low S
+    for _ in 0..16 {
+        let signature = private_key.sign_arbitrary_message(MESSAGE);
+        assert!(Secp256r1EcdsaSignature::check_s_malleability(&signature.to_bytes()).is_ok());
+        assert!(signature.verify_arbitrary_msg(MESSAGE, &public_key).is_ok());
+    }
+}
+
+// Check for low S.
+#[test]
+fn test_signature_malleability() {
+    let public_key =
+        Secp256r1EcdsaPublicKey::try_from(hex::decode(PUBLIC_KEY).unwrap().as_slice()).unwrap();
+
+    // The original signature is valid, but with a high S, so try_from and verification fail
+    let mut mauled = [0u8; SECP256R1_ECDSA_SIGNATURE_LENGTH];
+    let der_signature = hex::decode(DER_SIGNATURE).unwrap();
+    mauled[..32].copy_from_slice(&der_signature[4..36]);
+    mauled[32..].copy_from_slice(&der_signature[der_signature.len() - 32..]);
+    assert_eq!(
+        Secp256r1EcdsaSignature::try_from(&mauled[..]),
+        Err(CryptoMaterialError::CanonicalRepresentationError)
+    );
+    let sig_unchecked = Secp256r1EcdsaSignature::from_bytes_unchecked(&mauled).unwrap();
+    assert!(sig_unchecked
+        .verify_arbitrary_msg(MESSAGE, &public_key)
+        .is_err());
+
+    // Normalizing S gives back the canonical signature
+    assert_eq!(
+        hex::encode(sig_unchecked.normalize_s().to_bytes()),
+        SIGNATURE
+    );
+}
+
+#[test]
+fn test_der_deserialization() {
+    let der_signature = hex::decode(DER_SIGNATURE).unwrap();
+    let truncated = &der_signature[..der_signature.len() - 1];
+    assert!(Secp256r1EcdsaSignature::from_der(truncated).is_err());
+    let trailing = [der_signature.clone(), vec![0]].concat();
+    assert!(Secp256r1EcdsaSignature::from_der(&trailing).is_err());
+
+    let mut not_a_sequence = der_signature.clone();
+    not_a_sequence[0] = 0x31;
+    assert!(Secp256r1EcdsaSignature::from_der(&not_a_sequence).is_err());
+
+    let mut not_an_integer = der_signature;
+    not_an_integer[2] = 0x03;
+    assert!(Secp256r1EcdsaSignature::from_der(&not_an_integer).is_err());
+}
+
+#[test]
+fn test_public_key_deserialization() {
+    // The genesis private key is 1, whose public key is the generator of the curve
+    assert_eq!(
+        hex::encode(Secp256r1EcdsaPrivateKey::genesis().public_key().to_bytes()),
+        "046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296\
+         4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"
+    );
+
+    // Only the uncompressed form is accepted
+    let serialized = hex::decode(PUBLIC_KEY).unwrap();
+    assert_eq!(
+        Secp256r1EcdsaPublicKey::try_from(&serialized[..33]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+    let mut compressed_tag = serialized.clone();
+    compressed_tag[0] = 0x03;
+    assert_eq!(
+        Secp256r1EcdsaPublicKey::try_from(&compressed_tag[..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+
+    // Points off the curve are rejected
+    let mut invalid = serialized;
+    invalid[SECP256R1_ECDSA_PUBLIC_KEY_LENGTH - 1] ^= 1;
+    assert_eq!(
+        Secp256r1EcdsaPublicKey::try_from(&invalid[..]),
+        Err(CryptoMaterialError::PointNotOnCurveError)
+    );
+    let mut zero = [0u8; SECP256R1_ECDSA_PUBLIC_KEY_LENGTH];
+    zero[0] = 0x04;
+    assert_eq!(
+        Secp256r1EcdsaPublicKey::try_from(&zero[..]),
+        Err(CryptoMaterialError::PointNotOnCurveError)
+    );
+}
+
+#[test]
+fn test_private_key_deserialization() {
+    // Zero is not a valid private key, nor is the order of the curve
+    let zero = [0u8; SECP256R1_ECDSA_PRIVATE_KEY_LENGTH];
+    assert_eq!(
+        Secp256r1EcdsaPrivateKey::try_from(&zero[..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+    let order =
+        hex::decode("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551").unwrap();
+    assert_eq!(
+        Secp256r1EcdsaPrivateKey::try_from(&order[..]),
+        Err(CryptoMaterialError::DeserializationError)
+    );
+    assert_eq!(
+        Secp256r1EcdsaPrivateKey::try_from(&zero[1..]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+
+    // The order of the curve minus one is the largest valid private key
+    let mut largest = order;
+    largest[SECP256R1_ECDSA_PRIVATE_KEY_LENGTH - 1] -= 1;
+    let private_key = Secp256r1EcdsaPrivateKey::try_from(&largest[..]).unwrap();
+    assert_eq!(private_key.to_bytes().as_slice(), largest.as_slice());
+    assert!(Secp256r1EcdsaPublicKey::try_from(&private_key.public_key().to_bytes()[..]).is_ok());
+}
@@ -4,7 +4,7 @@
 use aptos_crypto::{
     bls12381,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-    x25519, CryptoMaterialError, PrivateKey, Uniform,
+    secp256k1_ecdsa, x25519, CryptoMaterialError, PrivateKey, Uniform,
 };
 use aptos_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
 use rand::{
@@ -40,6 +40,11 @@ impl KeyGen {
         bls12381::PrivateKey::generate(&mut self.0)
     }
 
+    /// Generate a secp256k1 ECDSA private key.
+    pub fn generate_secp256k1_ecdsa_private_key(&mut self) -> secp256k1_ecdsa::PrivateKey {
+        secp256k1_ecdsa::PrivateKey::generate(&mut self.0)
+    }
+
     /// Generate an Ed25519 key pair.
     pub fn generate_ed25519_keypair(&mut self) -> (Ed25519PrivateKey, Ed25519PublicKey) {
         let private_key = self.generate_ed25519_private_key();
@@ -83,6 +83,10 @@ pub enum CliError {
     MoveTestError,
     #[error("Move Prover failed: {0}")]
     MoveProverError(String),
+    #[error("Move formatting failed: {0}")]
+    MoveFormatError(String),
+    #[error("Move lint failed: {0}")]
+    MoveLintError(String),
     #[error("Unable to parse '{0}': error: {1}")]
     UnableToParse(&'static str, String),
     #[error("Unable to read file '{0}', error: {1}")]
@@ -108,6 +112,8 @@ impl CliError {
             CliError::MoveCompilationError(_) => "MoveCompilationError",
             CliError::MoveTestError => "MoveTestError",
             CliError::MoveProverError(_) => "MoveProverError",
+            CliError::MoveFormatError(_) => "MoveFormatError",
+            CliError::MoveLintError(_) => "MoveLintError",
             CliError::UnableToParse(_, _) => "UnableToParse",
             CliError::UnableToReadFile(_, _) => "UnableToReadFile",
             CliError::UnexpectedError(_) => "UnexpectedError",
@@ -355,6 +361,8 @@ pub enum KeyType {
     X25519,
     /// A BLS12381 key for consensus
     Bls12381,
+    /// A secp256k1 ECDSA key used for signing
+    Secp256k1Ecdsa,
 }
 
 impl Display for KeyType {
@@ -363,6 +371,7 @@ impl Display for KeyType {
             KeyType::Ed25519 => "ed25519",
             KeyType::X25519 => "x25519",
             KeyType::Bls12381 => "bls12381",
+            KeyType::Secp256k1Ecdsa => "secp256k1-ecdsa",
         };
         write!(f, "{}", str)
     }
@@ -376,7 +385,10 @@ impl FromStr for KeyType {
             "ed25519" => Ok(KeyType::Ed25519),
             "x25519" => Ok(KeyType::X25519),
             "bls12381" => Ok(KeyType::Bls12381),
-            _ => Err("Invalid key type: Must be one of [ed25519, x25519]"),
+            "secp256k1-ecdsa" | "secp256k1_ecdsa" => Ok(KeyType::Secp256k1Ecdsa),
+            _ => {
+                Err("Invalid key type: Must be one of [ed25519, x25519, bls12381, secp256k1-ecdsa]")
+            },
         }
     }
 }
@@ -31,8 +31,13 @@ use crate::{
 };
 use aptos_crypto::HashValue;
 use aptos_framework::{
-    build_model, docgen::DocgenOptions, extended_checks, natives::code::UpgradePolicy,
-    prover::ProverOptions, BuildOptions, BuiltPackage,
+    build_model,
+    docgen::DocgenOptions,
+    extended_checks,
+    lint::{LintDiagnostic, LintLevel, LintOptions},
+    natives::code::UpgradePolicy,
+    prover::ProverOptions,
+    BuildOptions, BuiltPackage,
 };
 use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters};
 use aptos_rest_client::aptos_api_types::{EntryFunctionId, MoveType, ViewRequest};
@@ -49,7 +54,7 @@ use codespan_reporting::{
 };
 use itertools::Itertools;
 use move_cli::{self, base::test::UnitTestResult};
-use move_command_line_common::env::MOVE_HOME;
+use move_command_line_common::{env::MOVE_HOME, files::find_move_filenames};
 use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, TypeTag},
@@ -85,6 +90,8 @@ pub enum MoveTool {
     Download(DownloadPackage),
     List(ListPackage),
     Clean(CleanPackage),
+    Fmt(FormatPackage),
+    Lint(LintPackage),
     VerifyPackage(VerifyPackage),
     Run(RunFunction),
     RunScript(RunScript),
@@ -110,6 +117,8 @@ impl MoveTool {
             MoveTool::Download(tool) => tool.execute_serialized().await,
             MoveTool::List(tool) => tool.execute_serialized().await,
             MoveTool::Clean(tool) => tool.execute_serialized().await,
+            MoveTool::Fmt(tool) => tool.execute_serialized().await,
+            MoveTool::Lint(tool) => tool.execute_serialized().await,
             MoveTool::VerifyPackage(tool) => tool.execute_serialized().await,
             MoveTool::Run(tool) => tool.execute_serialized().await,
             MoveTool::RunScript(tool) => tool.execute_serialized().await,
@@ -421,6 +430,21 @@ pub struct TestPackage {
     /// Dump storage state on failure.
     #[clap(long = "dump")]
     pub dump_state: bool,
+
+    /// Run the tests against mutants of the package and report the mutants which survive
+    ///
+    /// A mutant changes one instruction of a function of the package, e.g. flips a comparison,
+    /// swaps an arithmetic operator, changes a constant by one or removes an `assert!`. A mutant
+    /// survives if all tests still pass against it.
+    #[clap(long)]
+    pub mutate: bool,
+
+    /// The seed from which the arguments of `#[fuzz]` tests are generated
+    ///
+    /// A random seed is used by default, and reported along with the counterexample of a failing
+    /// `#[fuzz]` test so that the failure can be reproduced.
+    #[clap(long)]
+    pub seed: Option<u64>,
 }
 
 #[async_trait]
@@ -463,6 +487,8 @@ impl CliCommand<&'static str> for TestPackage {
                 report_stacktrace_on_abort: true,
                 report_storage_on_error: self.dump_state,
                 ignore_compile_warnings: self.ignore_compile_warnings,
+                mutate: self.mutate,
+                seed: self.seed,
                 ..UnitTestingConfig::default_with_bound(None)
             },
             // TODO(Gas): we may want to switch to non-zero costs in the future
@@ -558,6 +584,57 @@ impl CliCommand<&'static str> for ProvePackage {
     }
 }
 
+/// Lints a Move package
+///
+/// Reports code which compiles but is likely a mistake. Findings of a rule can be suppressed
+/// in the code with `#[lint_allow(<rule>)]` on a module, struct or function. Linting fails if
+/// any finding is reported at the error level, see `--deny`.
+#[derive(Parser)]
+pub struct LintPackage {
+    #[clap(flatten)]
+    move_options: MovePackageDir,
+
+    #[clap(flatten)]
+    lint_options: LintOptions,
+}
+
+#[async_trait]
+impl CliCommand<Vec<LintDiagnostic>> for LintPackage {
+    fn command_name(&self) -> &'static str {
+        "LintPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<LintDiagnostic>> {
+        let LintPackage {
+            move_options,
+            lint_options,
+        } = self;
+
+        let result = task::spawn_blocking(move || {
+            lint_options.lint(
+                move_options.get_package_path()?.as_path(),
+                move_options.named_addresses(),
+                move_options.bytecode_version,
+            )
+        })
+        .await
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let diagnostics = result.map_err(|e| CliError::MoveLintError(format!("{:#}", e)))?;
+        let errors = diagnostics
+            .iter()
+            .filter(|diag| diag.level == LintLevel::Error)
+            .count();
+        if errors > 0 {
+            return Err(CliError::MoveLintError(format!(
+                "{} of {} findings are errors",
+                errors,
+                diagnostics.len()
+            )));
+        }
+        Ok(diagnostics)
+    }
+}
+
 /// Documents a Move package
 ///
 /// This converts the content of the package into markdown for documentation.
@@ -1085,6 +1162,66 @@ impl CliCommand<&'static str> for CleanPackage {
     }
 }
 
+/// Formats the Move sources of a package
+///
+/// Formats the `.move` files in the sources, scripts, tests and examples directories of the
+/// package in place, and returns the files which changed.
+#[derive(Parser)]
+pub struct FormatPackage {
+    /// Path to a move package (the folder with a Move.toml file)
+    #[clap(long, parse(from_os_str))]
+    pub(crate) package_dir: Option<PathBuf>,
+    /// Only check that the sources are formatted, without changing them
+    ///
+    /// Fails if any of the sources is not formatted.
+    #[clap(long)]
+    pub(crate) check: bool,
+}
+
+#[async_trait]
+impl CliCommand<Vec<String>> for FormatPackage {
+    fn command_name(&self) -> &'static str {
+        "FormatPackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<String>> {
+        let package_dir = dir_default_to_current(self.package_dir)?;
+        let source_dirs: Vec<PathBuf> = [
+            SourcePackageLayout::Sources,
+            SourcePackageLayout::Scripts,
+            SourcePackageLayout::Tests,
+            SourcePackageLayout::Examples,
+        ]
+        .iter()
+        .map(|layout| package_dir.join(layout.path()))
+        .filter(|dir| dir.exists())
+        .collect();
+        let files = find_move_filenames(&source_dirs, false)
+            .map_err(|err| CliError::MoveFormatError(err.to_string()))?;
+
+        let mut changed = vec![];
+        for file in files {
+            let source =
+                std::fs::read_to_string(&file).map_err(|e| CliError::IO(file.clone(), e))?;
+            let formatted = move_formatter::format_source(&file, &source)
+                .map_err(|err| CliError::MoveFormatError(err.to_string()))?;
+            if formatted != source {
+                if !self.check {
+                    write_to_file(Path::new(&file), &file, formatted.as_bytes())?;
+                }
+                changed.push(file);
+            }
+        }
+        if self.check && !changed.is_empty() {
+            return Err(CliError::MoveFormatError(format!(
+                "Sources are not formatted: {}",
+                changed.join(", ")
+            )));
+        }
+        Ok(changed)
+    }
+}
+
 /// Run a Move function
 #[derive(Parser)]
 pub struct RunFunction {
@@ -2,14 +2,29 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{common::types::load_account_arg, move_tool::CachedPackageRegistry};
+use anyhow::{bail, Context};
 use aptos_framework::UPGRADE_POLICY_CUSTOM_FIELD;
+use aptos_rest_client::Client;
 use futures::executor::block_on;
+use move_binary_format::CompiledModule;
+use move_command_line_common::files::MOVE_COMPILED_EXTENSION;
+use move_compiler::compiled_unit::CompiledUnit;
 use move_package::{
-    compilation::package_layout::CompiledPackageLayout, package_hooks::PackageHooks,
-    source_package::parsed_manifest::CustomDepInfo,
+    package_hooks::PackageHooks,
+    source_package::parsed_manifest::{CustomDepInfo, CustomDepPin},
 };
 use move_symbol_pool::Symbol;
 use reqwest::Url;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+};
+
+/// The file in a downloaded package which records the version it was downloaded at.
+const REGISTRY_PIN_FILE: &str = "Registry.toml";
+
+/// The directory in a downloaded package which holds the bytecode published at that version.
+const REGISTRY_MODULES_DIR: &str = "registry_modules";
 
 pub fn register_package_hooks() {
     move_package::package_hooks::register_package_hooks(Box::new(AptosPackageHooks {}))
@@ -30,25 +45,263 @@ impl PackageHooks for AptosPackageHooks {
         &self,
         _dep_name: Symbol,
         info: &CustomDepInfo,
+        pin: Option<&CustomDepPin>,
+    ) -> anyhow::Result<CustomDepPin> {
+        block_on(maybe_download_package(info, pin))
+    }
+
+    fn verify_custom_dependency(
+        &self,
+        dep_name: Symbol,
+        info: &CustomDepInfo,
+        pin: &CustomDepPin,
+        units: &[&CompiledUnit],
     ) -> anyhow::Result<()> {
-        block_on(maybe_download_package(info))
+        let compiled = units
+            .iter()
+            .filter_map(|unit| match unit {
+                CompiledUnit::Module(module) => {
+                    Some((module.name.as_str().to_string(), &module.module))
+                },
+                CompiledUnit::Script(_) => None,
+            })
+            .collect();
+        verify_package_bytecode(dep_name, info, pin, compiled)
+    }
+}
+
+/// Downloads the package from the registry at the ledger version of `pin`, or the latest one if
+/// it is not pinned, unless it was already downloaded at that version. The pin of a package is
+/// its ledger version and source digest.
+async fn maybe_download_package(
+    info: &CustomDepInfo,
+    pin: Option<&CustomDepPin>,
+) -> anyhow::Result<CustomDepPin> {
+    let pin_file = info.download_to.join(REGISTRY_PIN_FILE);
+    if let Some(pin) = pin {
+        if pin_file.exists()
+            && toml::from_str::<CustomDepPin>(&fs::read_to_string(&pin_file)?)? == *pin
+        {
+            return Ok(pin.clone());
+        }
+    }
+
+    let url = Url::parse(info.node_url.as_str())?;
+    let address = load_account_arg(info.package_address.as_str())?;
+    let client = Client::new(url.clone());
+    let version = match pin {
+        Some(pin) => pin.version,
+        None => client.get_ledger_information().await?.into_inner().version,
+    };
+    let registry = CachedPackageRegistry::create_at_version(url, address, version).await?;
+    let package = registry.get_package(info.package_name).await?;
+    if let Some(pin) = pin {
+        if package.source_digest() != pin.digest {
+            bail!(
+                "package `{}` has source digest {} at version {}, but the lock file expects {}",
+                info.package_name,
+                package.source_digest(),
+                version,
+                pin.digest
+            )
+        }
+    }
+
+    if info.download_to.exists() {
+        fs::remove_dir_all(&info.download_to)?;
     }
+    package.save_package_to_disk(info.download_to.as_path())?;
+    let modules_dir = info.download_to.join(REGISTRY_MODULES_DIR);
+    fs::create_dir_all(&modules_dir)?;
+    for name in package.module_names() {
+        let bytecode = client
+            .get_account_module_bcs_at_version(address, name, version)
+            .await?
+            .into_inner();
+        fs::write(
+            modules_dir
+                .join(name)
+                .with_extension(MOVE_COMPILED_EXTENSION),
+            bytecode,
+        )?;
+    }
+    let pin = CustomDepPin {
+        version,
+        digest: package.source_digest().to_string(),
+    };
+    fs::write(pin_file, toml::to_string(&pin)?)?;
+    Ok(pin)
 }
 
-async fn maybe_download_package(info: &CustomDepInfo) -> anyhow::Result<()> {
-    if !info
-        .download_to
-        .join(CompiledPackageLayout::BuildInfo.path())
-        .exists()
+/// Checks that the modules compiled from a downloaded package are the ones published at the
+/// version it was downloaded at, and that no published module is missing.
+fn verify_package_bytecode(
+    dep_name: Symbol,
+    info: &CustomDepInfo,
+    pin: &CustomDepPin,
+    compiled: BTreeMap<String, &CompiledModule>,
+) -> anyhow::Result<()> {
+    if compiled.is_empty() {
+        bail!("no modules were compiled from package `{}`", dep_name)
+    }
+
+    let modules_dir = info.download_to.join(REGISTRY_MODULES_DIR);
+    let mut published = BTreeSet::new();
+    for entry in fs::read_dir(&modules_dir)
+        .with_context(|| format!("no modules were downloaded for package `{}`", dep_name))?
     {
-        let registry = CachedPackageRegistry::create(
-            Url::parse(info.node_url.as_str())?,
-            load_account_arg(info.package_address.as_str())?,
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str())
+            == Some(MOVE_COMPILED_EXTENSION)
+        {
+            if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                published.insert(name.to_string());
+            }
+        }
+    }
+    if let Some(name) = published.iter().find(|name| !compiled.contains_key(*name)) {
+        bail!(
+            "module `{}` of package `{}` is published at version {}, but not compiled from its \
+             sources",
+            name,
+            dep_name,
+            pin.version
+        )
+    }
+
+    for (name, module) in compiled {
+        if !published.contains(&name) {
+            bail!(
+                "module `{}` of package `{}` is not published at version {}",
+                name,
+                dep_name,
+                pin.version
+            )
+        }
+        let bytecode = fs::read(
+            modules_dir
+                .join(&name)
+                .with_extension(MOVE_COMPILED_EXTENSION),
+        )?;
+        let published_module = CompiledModule::deserialize(&bytecode)?;
+        if !is_same_code(module, &published_module) {
+            bail!(
+                "module `{}` of package `{}` does not compile to the bytecode published at \
+                 version {}, which may be due to a different compiler version",
+                name,
+                dep_name,
+                pin.version
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether two modules have the same code, ignoring their bytecode version and metadata,
+/// which are added when publishing.
+fn is_same_code(compiled: &CompiledModule, published: &CompiledModule) -> bool {
+    let mut compiled = compiled.clone();
+    compiled.version = published.version;
+    compiled.metadata = published.metadata.clone();
+    compiled == *published
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::{basic_test_module, empty_module};
+    use move_core_types::metadata::Metadata;
+    use std::path::Path;
+
+    fn verify(
+        download_to: &Path,
+        compiled: &[(&str, &CompiledModule)],
+        published: &[(&str, &CompiledModule)],
+    ) -> anyhow::Result<()> {
+        let modules_dir = download_to.join(REGISTRY_MODULES_DIR);
+        fs::create_dir_all(&modules_dir).unwrap();
+        for (name, module) in published {
+            let mut bytecode = vec![];
+            module.serialize(&mut bytecode).unwrap();
+            fs::write(
+                modules_dir
+                    .join(name)
+                    .with_extension(MOVE_COMPILED_EXTENSION),
+                bytecode,
+            )
+            .unwrap();
+        }
+        let info = CustomDepInfo {
+            node_url: Symbol::from("http://localhost:8080"),
+            package_address: Symbol::from("0x1"),
+            package_name: Symbol::from("Package"),
+            download_to: download_to.to_path_buf(),
+        };
+        let pin = CustomDepPin {
+            version: 10,
+            digest: String::new(),
+        };
+        let compiled = compiled
+            .iter()
+            .map(|(name, module)| (name.to_string(), *module))
+            .collect();
+        verify_package_bytecode(Symbol::from("Package"), &info, &pin, compiled)
+    }
+
+    #[test]
+    fn test_verify_package_bytecode_same_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = basic_test_module();
+        // The metadata added when publishing is ignored
+        let mut published = module.clone();
+        published.metadata = vec![Metadata {
+            key: b"key".to_vec(),
+            value: b"value".to_vec(),
+        }];
+        verify(dir.path(), &[("A", &module), ("B", &empty_module())], &[
+            ("A", &published),
+            ("B", &empty_module()),
+        ])
+        .unwrap();
+    }
+
+    #[test]
+    fn test_verify_package_bytecode_different_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = verify(dir.path(), &[("A", &empty_module())], &[(
+            "A",
+            &basic_test_module(),
+        )])
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("does not compile to the bytecode published"));
+    }
+
+    #[test]
+    fn test_verify_package_bytecode_missing_compiled_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = verify(dir.path(), &[("A", &empty_module())], &[
+            ("A", &empty_module()),
+            ("B", &empty_module()),
+        ])
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("module `B` of package `Package` is published at version 10"));
+    }
+
+    #[test]
+    fn test_verify_package_bytecode_missing_published_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = verify(
+            dir.path(),
+            &[("A", &empty_module()), ("B", &empty_module())],
+            &[("A", &empty_module())],
         )
-        .await?;
-        let package = registry.get_package(info.package_name).await?;
-        package.save_package_to_disk(info.download_to.as_path())
-    } else {
-        Ok(())
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("module `B` of package `Package` is not published at version 10"));
     }
 }
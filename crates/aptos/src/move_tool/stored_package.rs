@@ -42,6 +42,24 @@ impl CachedPackageRegistry {
         Ok(Self { inner })
     }
 
+    /// Creates a new registry from the state at the given ledger version.
+    pub async fn create_at_version(
+        url: Url,
+        addr: AccountAddress,
+        version: u64,
+    ) -> anyhow::Result<Self> {
+        let client = Client::new(url);
+        let inner = client
+            .get_account_resource_at_version_bcs::<PackageRegistry>(
+                addr,
+                "0x1::code::PackageRegistry",
+                version,
+            )
+            .await?
+            .into_inner();
+        Ok(Self { inner })
+    }
+
     /// Returns the list of packages in this registry by name.
     pub fn package_names(&self) -> Vec<&str> {
         self.inner
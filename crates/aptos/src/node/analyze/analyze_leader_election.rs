@@ -0,0 +1,99 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::fetch_metadata::EpochInfo;
+use aptos_consensus::leader_election_analysis::{ElectionReport, EpochHistory};
+use aptos_types::account_address::AccountAddress;
+use std::collections::BTreeSet;
+
+pub struct AnalyzeLeaderElection {}
+
+impl AnalyzeLeaderElection {
+    pub fn epoch_history(epoch_info: &EpochInfo) -> EpochHistory {
+        EpochHistory {
+            epoch: epoch_info.epoch,
+            validators: epoch_info
+                .validators
+                .iter()
+                .map(|v| (v.address, v.voting_power))
+                .collect(),
+            blocks: epoch_info.blocks.iter().map(|b| b.event.clone()).collect(),
+        }
+    }
+
+    /// Print the summary of each report side by side, followed by the leader shares of each validator
+    pub fn print_comparison_table(reports: &[ElectionReport]) {
+        let print_row = |name: &str, values: Vec<String>| {
+            println!(
+                "{: <66} | {}",
+                name,
+                values
+                    .iter()
+                    .map(|v| format!("{: <24}", v))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            );
+        };
+
+        print_row("config", reports.iter().map(|r| r.name.clone()).collect());
+        print_row(
+            "rounds",
+            reports
+                .iter()
+                .map(|r| r.total_rounds().to_string())
+                .collect(),
+        );
+        print_row(
+            "% failed rounds",
+            reports
+                .iter()
+                .map(|r| format!("{:.3}%", 100.0 * r.failed_round_rate()))
+                .collect(),
+        );
+        print_row(
+            "fairness index",
+            reports
+                .iter()
+                .map(|r| format!("{:.4}", r.fairness_index()))
+                .collect(),
+        );
+        print_row(
+            "min / max of fair share",
+            reports
+                .iter()
+                .map(|r| {
+                    let (min, max) = r.fairness_range();
+                    format!("{:.2} / {:.2}", min, max)
+                })
+                .collect(),
+        );
+
+        let validators: BTreeSet<&AccountAddress> =
+            reports.iter().flat_map(|r| r.validators.keys()).collect();
+        println!();
+        print_row(
+            "% rounds as leader (% of them failed)",
+            reports.iter().map(|r| r.name.clone()).collect(),
+        );
+        for validator in validators {
+            print_row(
+                &validator.to_hex_literal(),
+                reports
+                    .iter()
+                    .map(|r| {
+                        let stats = r.validators.get(validator).copied().unwrap_or_default();
+                        format!(
+                            "{:6.3}% ({:.1}%)",
+                            100.0 * r.leader_share(validator),
+                            if stats.elected_rounds > 0 {
+                                100.0 * stats.failed_rounds as f64 / stats.elected_rounds as f64
+                            } else {
+                                0.0
+                            }
+                        )
+                    })
+                    .collect(),
+            );
+        }
+    }
+}
@@ -6,8 +6,12 @@ use aptos_rest_client::{
     aptos_api_types::{IdentifierWrapper, MoveResource, WriteSetChange},
     Client as RestClient, Transaction, VersionedNewBlockEvent,
 };
-use aptos_types::account_address::AccountAddress;
-use std::str::FromStr;
+use aptos_storage_interface::{DbReader, Order};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{new_block_event_key, NewBlockEvent},
+};
+use std::{collections::BTreeMap, str::FromStr};
 
 const MAX_FETCH_BATCH_SIZE: u16 = 1000;
 
@@ -130,34 +134,17 @@ impl FetchMetadata {
         Err(anyhow!("Couldn't find ValidatorSet in the transaction"))
     }
 
-    pub async fn fetch_new_block_events(
-        client: &RestClient,
+    fn wanted_epoch_range(
         start_epoch: Option<i64>,
         end_epoch: Option<i64>,
-    ) -> Result<Vec<EpochInfo>> {
-        let (last_events, state) = client
-            .get_new_block_events_bcs(None, Some(1))
-            .await?
-            .into_parts();
-        let mut start_seq_num = state.oldest_block_height;
-        assert_eq!(last_events.len(), 1, "{:?}", last_events);
-        let last_event = last_events.first().unwrap();
-        let last_seq_num = last_event.sequence_number;
-
+        last_epoch: u64,
+        oldest_fetchable_epoch: u64,
+    ) -> (u64, u64) {
         let wanted_start_epoch = {
             let mut wanted_start_epoch = start_epoch.unwrap_or(2);
             if wanted_start_epoch < 0 {
-                wanted_start_epoch = last_event.event.epoch() as i64 + wanted_start_epoch + 1;
+                wanted_start_epoch = last_epoch as i64 + wanted_start_epoch + 1;
             }
-
-            let oldest_event = client
-                .get_new_block_events_bcs(Some(start_seq_num), Some(1))
-                .await?
-                .into_inner()
-                .into_iter()
-                .next()
-                .ok_or_else(|| anyhow!("No blocks at oldest_block_height {}", start_seq_num))?;
-            let oldest_fetchable_epoch = std::cmp::max(oldest_event.event.epoch() + 1, 2);
             if oldest_fetchable_epoch > wanted_start_epoch as u64 {
                 println!(
                     "Oldest full epoch that can be retreived is {} ",
@@ -171,13 +158,40 @@ impl FetchMetadata {
         let wanted_end_epoch = {
             let mut wanted_end_epoch = end_epoch.unwrap_or(i64::MAX);
             if wanted_end_epoch < 0 {
-                wanted_end_epoch = last_event.event.epoch() as i64 + wanted_end_epoch + 1;
+                wanted_end_epoch = last_epoch as i64 + wanted_end_epoch + 1;
             }
-            std::cmp::min(
-                last_event.event.epoch() + 1,
-                std::cmp::max(2, wanted_end_epoch) as u64,
-            )
+            std::cmp::min(last_epoch + 1, std::cmp::max(2, wanted_end_epoch) as u64)
         };
+        (wanted_start_epoch, wanted_end_epoch)
+    }
+
+    pub async fn fetch_new_block_events(
+        client: &RestClient,
+        start_epoch: Option<i64>,
+        end_epoch: Option<i64>,
+    ) -> Result<Vec<EpochInfo>> {
+        let (last_events, state) = client
+            .get_new_block_events_bcs(None, Some(1))
+            .await?
+            .into_parts();
+        let mut start_seq_num = state.oldest_block_height;
+        assert_eq!(last_events.len(), 1, "{:?}", last_events);
+        let last_event = last_events.first().unwrap();
+        let last_seq_num = last_event.sequence_number;
+
+        let oldest_event = client
+            .get_new_block_events_bcs(Some(start_seq_num), Some(1))
+            .await?
+            .into_inner()
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No blocks at oldest_block_height {}", start_seq_num))?;
+        let (wanted_start_epoch, wanted_end_epoch) = FetchMetadata::wanted_epoch_range(
+            start_epoch,
+            end_epoch,
+            last_event.event.epoch(),
+            std::cmp::max(oldest_event.event.epoch() + 1, 2),
+        );
 
         if wanted_start_epoch > 2 {
             let mut search_end = last_seq_num;
@@ -334,4 +348,105 @@ impl FetchMetadata {
             }
         }
     }
+
+    /// Same as fetch_new_block_events, but reads from a local AptosDB
+    pub fn fetch_new_block_events_from_db(
+        db: &dyn DbReader,
+        start_epoch: Option<i64>,
+        end_epoch: Option<i64>,
+    ) -> Result<Vec<EpochInfo>> {
+        let latest_ledger_info = db.get_latest_ledger_info()?;
+        let last_epoch = latest_ledger_info.ledger_info().next_block_epoch();
+        let ledger_version = latest_ledger_info.ledger_info().version();
+        let (wanted_start_epoch, wanted_end_epoch) =
+            FetchMetadata::wanted_epoch_range(start_epoch, end_epoch, last_epoch, 2);
+
+        println!(
+            "Reading NewBlockEvents from db, wanting epochs [{}, {}), last version: {} and epoch: {}",
+            wanted_start_epoch, wanted_end_epoch, ledger_version, last_epoch,
+        );
+        let mut result: Vec<EpochInfo> = vec![];
+        if wanted_start_epoch >= wanted_end_epoch {
+            return Ok(result);
+        }
+
+        // Events can only be iterated from the latest one backwards, as we don't know
+        // the sequence number of the first block of the wanted start epoch.
+        let mut blocks: BTreeMap<u64, Vec<VersionedNewBlockEvent>> = BTreeMap::new();
+        let mut cursor = u64::max_value();
+        // Whether older events were pruned, making the oldest fetched epoch partial
+        let mut pruned = false;
+        'fetch: loop {
+            let events = match db.get_events(
+                &new_block_event_key(),
+                cursor,
+                Order::Descending,
+                MAX_FETCH_BATCH_SIZE as u64,
+                ledger_version,
+            ) {
+                Ok(events) => events,
+                Err(e) => {
+                    println!(
+                        "Failed to read new_block_events before {}, stopping. {:?}",
+                        cursor, e
+                    );
+                    pruned = true;
+                    break;
+                },
+            };
+            if events.is_empty() {
+                break;
+            }
+            for event in events {
+                let sequence_number = event.event.sequence_number();
+                let new_block_event = bcs::from_bytes::<NewBlockEvent>(event.event.event_data())?;
+                let epoch = new_block_event.epoch();
+                if epoch < wanted_start_epoch {
+                    break 'fetch;
+                }
+                if epoch < wanted_end_epoch {
+                    blocks
+                        .entry(epoch)
+                        .or_default()
+                        .push(VersionedNewBlockEvent {
+                            event: new_block_event,
+                            version: event.transaction_version,
+                            sequence_number,
+                        });
+                }
+                if sequence_number == 0 {
+                    break 'fetch;
+                }
+                cursor = sequence_number - 1;
+            }
+        }
+
+        let oldest_epoch = blocks.keys().next().copied();
+        for (epoch, mut epoch_blocks) in blocks {
+            let proof = db.get_epoch_ending_ledger_infos(epoch - 1, epoch)?;
+            let epoch_state = proof
+                .ledger_info_with_sigs
+                .first()
+                .and_then(|ledger_info| ledger_info.ledger_info().next_epoch_state())
+                .ok_or_else(|| anyhow!("No validator set found for epoch {}", epoch))?;
+            let validators = epoch_state
+                .verifier
+                .get_ordered_account_addresses_iter()
+                .enumerate()
+                .map(|(index, address)| ValidatorInfo {
+                    address,
+                    voting_power: epoch_state.verifier.get_voting_power(&address).unwrap_or(0),
+                    validator_index: index as u16,
+                })
+                .collect();
+            epoch_blocks.reverse();
+            result.push(EpochInfo {
+                epoch,
+                blocks: epoch_blocks,
+                validators,
+                partial: epoch == last_epoch || (pruned && Some(epoch) == oldest_epoch),
+            });
+        }
+        Ok(result)
+    }
 }
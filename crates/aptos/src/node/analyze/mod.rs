@@ -1,5 +1,6 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod analyze_leader_election;
 pub mod analyze_validators;
 pub mod fetch_metadata;
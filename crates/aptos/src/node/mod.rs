@@ -10,11 +10,14 @@ use crate::{
             OptionalPoolAddressArgs, PoolAddressArgs, ProfileOptions, PromptOptions, RestOptions,
             TransactionOptions, TransactionSummary,
         },
-        utils::{prompt_yes_with_override, read_from_file},
+        utils::{
+            check_if_file_exists, prompt_yes_with_override, read_from_file, write_to_file,
+        },
     },
     config::GlobalConfig,
     genesis::git::from_yaml,
     node::analyze::{
+        analyze_leader_election::AnalyzeLeaderElection,
         analyze_validators::{AnalyzeValidators, ValidatorStats},
         fetch_metadata::FetchMetadata,
     },
@@ -26,20 +29,29 @@ use aptos_backup_cli::{
     utils::{ConcurrentDownloadsOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt, RocksdbOpt},
 };
 use aptos_cached_packages::aptos_stdlib;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{
+    ConsensusConfig, NodeConfig, RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_consensus::leader_election_analysis::{replay, ElectionConfig, ElectionReport};
 use aptos_crypto::{bls12381, bls12381::PublicKey, x25519, ValidCryptoMaterialStringExt};
+use aptos_db::AptosDB;
 use aptos_faucet_core::server::{FunderKeyEnum, RunConfig};
 use aptos_genesis::config::{HostAndPort, OperatorConfiguration};
 use aptos_network_checker::args::{
     validate_address, CheckEndpointArgs, HandshakeArgs, NodeAddressArgs,
 };
 use aptos_rest_client::{aptos_api_types::VersionedEvent, Client, State};
+use aptos_safety_rules::{PersistentSafetyStorage, SlashingProtectionData};
 use aptos_types::{
     account_address::AccountAddress,
     account_config::{BlockResource, CORE_CODE_ADDRESS},
     chain_id::ChainId,
     network_address::NetworkAddress,
-    on_chain_config::{ConfigurationResource, ConsensusScheme, ValidatorSet},
+    on_chain_config::{
+        ConfigurationResource, ConsensusScheme, OnChainConsensusConfig, ProposerElectionType,
+        ValidatorSet,
+    },
     stake_pool::StakePool,
     staking_contract::StakingContractStore,
     validator_info::ValidatorInfo,
@@ -58,7 +70,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
-    path::PathBuf,
+    path::{Path, PathBuf},
     pin::Pin,
     sync::Arc,
     thread,
@@ -88,7 +100,10 @@ pub enum NodeTool {
     UpdateConsensusKey(UpdateConsensusKey),
     UpdateValidatorNetworkAddresses(UpdateValidatorNetworkAddresses),
     AnalyzeValidatorPerformance(AnalyzeValidatorPerformance),
+    SimulateLeaderElection(SimulateLeaderElection),
     BootstrapDbFromBackup(BootstrapDbFromBackup),
+    ExportSlashingProtectionData(ExportSlashingProtectionData),
+    ImportSlashingProtectionData(ImportSlashingProtectionData),
 }
 
 impl NodeTool {
@@ -109,7 +124,10 @@ impl NodeTool {
             UpdateConsensusKey(tool) => tool.execute_serialized().await,
             UpdateValidatorNetworkAddresses(tool) => tool.execute_serialized().await,
             AnalyzeValidatorPerformance(tool) => tool.execute_serialized().await,
+            SimulateLeaderElection(tool) => tool.execute_serialized().await,
             BootstrapDbFromBackup(tool) => tool.execute_serialized().await,
+            ExportSlashingProtectionData(tool) => tool.execute_serialized().await,
+            ImportSlashingProtectionData(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -1473,6 +1491,118 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
     }
 }
 
+/// Replay historical leader elections with alternate configs
+///
+/// Re-runs leader election over the rounds committed in the given epochs, and compares
+/// failed proposal rates, the share of rounds each validator led and how fair that is
+/// relative to voting power, against what happened on chain.
+#[derive(Parser)]
+pub struct SimulateLeaderElection {
+    /// First epoch to replay
+    ///
+    /// Defaults to the first epoch
+    #[clap(long, default_value = "-2")]
+    pub start_epoch: i64,
+
+    /// Last epoch to replay
+    ///
+    /// Defaults to the latest epoch
+    #[clap(long)]
+    pub end_epoch: Option<i64>,
+
+    /// YAML file with the list of leader election configs to replay
+    ///
+    /// Each entry has a `name`, a `proposer_election_type` in the on-chain consensus config
+    /// format, and optionally an `exclude_round`. Defaults to the default on-chain config
+    /// and a rotating proposer.
+    #[clap(long, parse(from_os_str))]
+    pub config_file: Option<PathBuf>,
+
+    /// Read block metadata from the AptosDB in this directory, instead of the REST API
+    #[clap(long, parse(from_os_str))]
+    pub db_dir: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for SimulateLeaderElection {
+    fn command_name(&self) -> &'static str {
+        "SimulateLeaderElection"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let configs: Vec<ElectionConfig> = if let Some(ref file) = self.config_file {
+            from_yaml(&String::from_utf8(read_from_file(file)?).map_err(CliError::from)?)?
+        } else {
+            vec![
+                ElectionConfig::from_on_chain_config(
+                    "default".to_string(),
+                    &OnChainConsensusConfig::default(),
+                ),
+                ElectionConfig {
+                    name: "rotating".to_string(),
+                    proposer_election_type: ProposerElectionType::RotatingProposer(1),
+                    exclude_round: 0,
+                },
+            ]
+        };
+
+        let epochs = if let Some(ref db_dir) = self.db_dir {
+            let db = AptosDB::open(
+                db_dir,
+                true, /* readonly */
+                NO_OP_STORAGE_PRUNER_CONFIG,
+                RocksdbConfigs::default(),
+                false, /* indexer */
+                BUFFERED_STATE_TARGET_ITEMS,
+                DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            )?;
+            FetchMetadata::fetch_new_block_events_from_db(
+                &db,
+                Some(self.start_epoch),
+                self.end_epoch,
+            )?
+        } else {
+            let client = self.rest_options.client(&self.profile_options)?;
+            FetchMetadata::fetch_new_block_events(&client, Some(self.start_epoch), self.end_epoch)
+                .await?
+        };
+        if epochs.is_empty() {
+            println!("No data found for given input");
+            return Ok(());
+        }
+        println!(
+            "Replaying {}epochs [{}, {}]:",
+            if epochs.iter().any(|e| e.partial) {
+                "partial "
+            } else {
+                ""
+            },
+            epochs.first().unwrap().epoch,
+            epochs.last().unwrap().epoch
+        );
+
+        let history: Vec<_> = epochs
+            .iter()
+            .map(AnalyzeLeaderElection::epoch_history)
+            .collect();
+        let window_for_chain_health = ConsensusConfig::default().window_for_chain_health;
+        let mut reports = vec![ElectionReport::from_history(
+            "on-chain".to_string(),
+            &history,
+        )?];
+        for config in &configs {
+            reports.push(replay(config, &history, window_for_chain_health)?);
+        }
+        AnalyzeLeaderElection::print_comparison_table(&reports);
+        Ok(())
+    }
+}
+
 /// Bootstrap AptosDB from a backup
 ///
 /// Enables users to load from a backup to catch their node's DB up to a known state.
@@ -1543,6 +1673,90 @@ impl CliCommand<()> for BootstrapDbFromBackup {
     }
 }
 
+/// Exports the slashing protection data of a validator
+///
+/// The data holds the safety data (e.g., the last voted round) and the waypoint in the
+/// validator's SafetyRules storage. Import it on a new SafetyRules host (with
+/// `import-slashing-protection-data`) before starting the validator there, so that the new host
+/// never signs a vote or timeout that conflicts with one signed by the old host.
+#[derive(Parser)]
+pub struct ExportSlashingProtectionData {
+    /// Node config file of the validator
+    ///
+    /// The SafetyRules storage is read from the `consensus.safety_rules.backend` of this config.
+    #[clap(long, parse(from_os_str))]
+    node_config_file: PathBuf,
+
+    /// File to write the slashing protection data to
+    #[clap(long, parse(from_os_str))]
+    output_file: PathBuf,
+
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<SlashingProtectionData> for ExportSlashingProtectionData {
+    fn command_name(&self) -> &'static str {
+        "ExportSlashingProtectionData"
+    }
+
+    async fn execute(self) -> CliTypedResult<SlashingProtectionData> {
+        check_if_file_exists(&self.output_file, self.prompt_options)?;
+        let data = load_safety_rules_storage(&self.node_config_file)?
+            .export_slashing_protection_data()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let bytes = serde_json::to_vec_pretty(&data)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        write_to_file(&self.output_file, "Slashing protection data", &bytes)?;
+        Ok(data)
+    }
+}
+
+/// Imports the slashing protection data of a validator
+///
+/// The data must have been exported (with `export-slashing-protection-data`) from another
+/// SafetyRules host of the same validator. It is merged with the safety data in this host's
+/// storage, so that nothing signed by either host can be signed again. Stop the validator
+/// before importing.
+#[derive(Parser)]
+pub struct ImportSlashingProtectionData {
+    /// Node config file of the validator
+    ///
+    /// The SafetyRules storage is read from the `consensus.safety_rules.backend` of this config.
+    #[clap(long, parse(from_os_str))]
+    node_config_file: PathBuf,
+
+    /// File to read the slashing protection data from
+    #[clap(long, parse(from_os_str))]
+    input_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<()> for ImportSlashingProtectionData {
+    fn command_name(&self) -> &'static str {
+        "ImportSlashingProtectionData"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let bytes = read_from_file(&self.input_file)?;
+        let data: SlashingProtectionData = serde_json::from_slice(&bytes)
+            .map_err(|err| CliError::UnableToParse("Slashing protection data", err.to_string()))?;
+        load_safety_rules_storage(&self.node_config_file)?
+            .import_slashing_protection_data(&data)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+}
+
+/// Opens the SafetyRules storage configured in the given node config
+fn load_safety_rules_storage(node_config_file: &Path) -> CliTypedResult<PersistentSafetyStorage> {
+    let node_config = NodeConfig::load(node_config_file).map_err(|err| {
+        CliError::ConfigLoadError(format!("{}", node_config_file.display()), err.to_string())
+    })?;
+    aptos_safety_rules::existing_storage(&node_config.consensus.safety_rules)
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))
+}
+
 /// Checks the network connectivity of a node
 ///
 /// Checks network connectivity by dialing the node and attempting
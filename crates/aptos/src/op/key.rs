@@ -163,7 +163,7 @@ impl NetworkKeyInputOptions {
 /// key encoded with the `encoding`.
 #[derive(Debug, Parser)]
 pub struct GenerateKey {
-    /// Key type to generate. Must be one of [x25519, ed25519, bls12381]
+    /// Key type to generate. Must be one of [x25519, ed25519, bls12381, secp256k1-ecdsa]
     #[clap(long, default_value_t = KeyType::Ed25519)]
     pub(crate) key_type: KeyType,
 
@@ -201,6 +201,10 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for GenerateKey {
                 let private_key = keygen.generate_bls12381_private_key();
                 self.save_params.save_bls_key(&private_key, "bls12381")
             },
+            KeyType::Secp256k1Ecdsa => {
+                let private_key = keygen.generate_secp256k1_ecdsa_private_key();
+                self.save_params.save_key(&private_key, "secp256k1_ecdsa")
+            },
         }
     }
 }
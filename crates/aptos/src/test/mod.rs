@@ -837,6 +837,8 @@ impl CliTestFramework {
             ignore_compile_warnings: false,
             compute_coverage: false,
             dump_state: false,
+            mutate: false,
+            seed: None,
         }
         .execute()
         .await
@@ -4,6 +4,10 @@
 use crate::{gather_metrics, json_encoder::JsonEncoder, NUM_METRICS};
 use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
+use aptos_consensus::{
+    quorum_store_inspection::QuorumStoreInspector, ProposalSimulationError,
+    ProposalSimulationService,
+};
 use aptos_logger::debug;
 use hyper::{
     service::{make_service_fn, service_fn},
@@ -89,9 +93,74 @@ pub fn get_all_metrics() -> HashMap<String, String> {
     get_metrics(all_metric_families)
 }
 
+/// Parses the value of the given integer parameter in the query string, if it's set
+pub fn parse_u64_query_param(query: Option<&str>, name: &str) -> Result<Option<u64>, String> {
+    let value = query.and_then(|query| {
+        query
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    });
+    value
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|error| format!("Invalid value for {}: {}", name, error))
+        })
+        .transpose()
+}
+
+async fn simulate_proposal(
+    query: Option<&str>,
+    proposal_simulation_service: &ProposalSimulationService,
+) -> (StatusCode, Body, &'static str) {
+    let limits = || {
+        Ok::<_, String>((
+            parse_u64_query_param(query, "max_block_txns")?,
+            parse_u64_query_param(query, "max_block_bytes")?,
+        ))
+    };
+    let (max_block_txns, max_block_bytes) = match limits() {
+        Ok(limits) => limits,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Body::from(error),
+                CONTENT_TYPE_TEXT,
+            )
+        },
+    };
+    match proposal_simulation_service
+        .simulate_proposal(max_block_txns, max_block_bytes)
+        .await
+    {
+        Ok(simulation) => {
+            let encoded_simulation = serde_json::to_string(&simulation).unwrap();
+            (
+                StatusCode::OK,
+                Body::from(encoded_simulation),
+                CONTENT_TYPE_JSON,
+            )
+        },
+        Err(error @ ProposalSimulationError::InProgress) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            Body::from(error.to_string()),
+            CONTENT_TYPE_TEXT,
+        ),
+        Err(error) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Body::from(error.to_string()),
+            CONTENT_TYPE_TEXT,
+        ),
+    }
+}
+
 async fn serve_requests(
     req: Request<Body>,
     node_config: NodeConfig,
+    proposal_simulation_service: ProposalSimulationService,
+    quorum_store_inspector: QuorumStoreInspector,
 ) -> Result<Response<Body>, hyper::Error> {
     // Process the request and get the response components
     let (status_code, body, content_type) = match req.uri().path() {
@@ -137,6 +206,44 @@ async fn serve_requests(
                 CONTENT_TYPE_JSON,
             )
         },
+        "/proposal_simulation" => {
+            // Simulates the proposal of the next round, with the limits in the query if set
+            if node_config.inspection_service.expose_proposal_simulation {
+                simulate_proposal(req.uri().query(), &proposal_simulation_service).await
+            } else {
+                (
+                    StatusCode::FORBIDDEN,
+                    Body::from(DISABLED_ENDPOINT_MESSAGE),
+                    CONTENT_TYPE_TEXT,
+                )
+            }
+        },
+        "/quorum_store_batches" => {
+            // Exposes the batches persisted by the quorum store
+            if node_config.inspection_service.expose_quorum_store_batches {
+                match quorum_store_inspector.list_batches().await {
+                    Ok(batches) => {
+                        let encoded_batches = serde_json::to_string(&batches).unwrap();
+                        (
+                            StatusCode::OK,
+                            Body::from(encoded_batches),
+                            CONTENT_TYPE_JSON,
+                        )
+                    },
+                    Err(error) => (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Body::from(error.to_string()),
+                        CONTENT_TYPE_TEXT,
+                    ),
+                }
+            } else {
+                (
+                    StatusCode::FORBIDDEN,
+                    Body::from(DISABLED_ENDPOINT_MESSAGE),
+                    CONTENT_TYPE_TEXT,
+                )
+            }
+        },
         "/system_information" => {
             // Exposes the system and build information
             if node_config.inspection_service.expose_system_information {
@@ -194,7 +301,11 @@ async fn serve_requests(
     }))
 }
 
-pub fn start_inspection_service(node_config: NodeConfig) {
+pub fn start_inspection_service(
+    node_config: NodeConfig,
+    proposal_simulation_service: ProposalSimulationService,
+    quorum_store_inspector: QuorumStoreInspector,
+) {
     // Fetch the service port and address
     let service_port = node_config.inspection_service.port;
     let service_address = node_config.inspection_service.address.clone();
@@ -215,9 +326,16 @@ pub fn start_inspection_service(node_config: NodeConfig) {
     thread::spawn(move || {
         let make_service = make_service_fn(move |_conn| {
             let node_config = node_config.clone();
+            let proposal_simulation_service = proposal_simulation_service.clone();
+            let quorum_store_inspector = quorum_store_inspector.clone();
             async move {
                 Ok::<_, Infallible>(service_fn(move |request| {
-                    serve_requests(request, node_config.clone())
+                    serve_requests(
+                        request,
+                        node_config.clone(),
+                        proposal_simulation_service.clone(),
+                        quorum_store_inspector.clone(),
+                    )
                 }))
             }
         });
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::inspection_service::get_all_metrics;
+use crate::inspection_service::{get_all_metrics, parse_u64_query_param};
 use assert_approx_eq::assert_approx_eq;
 use once_cell::sync::Lazy;
 use prometheus::{proto::MetricFamily, register_int_counter, Counter, IntCounter, Opts, Registry};
@@ -70,3 +70,15 @@ fn get_all_metrics_test() {
     }
 }
 }
+
+#[test]
+fn parse_u64_query_param_test() {
+    let query = Some("max_block_txns=100&max_block_bytes=abc&flag");
+    assert_eq!(
+        parse_u64_query_param(query, "max_block_txns").unwrap(),
+        Some(100)
+    );
+    assert!(parse_u64_query_param(query, "max_block_bytes").is_err());
+    assert_eq!(parse_u64_query_param(query, "flag").unwrap(), None);
+    assert_eq!(parse_u64_query_param(None, "max_block_txns").unwrap(), None);
+}
@@ -0,0 +1,100 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use aptos_consensus::{
+    gas_and_dependency_aware_shuffler::{
+        GasAndDependencyAwareShuffler, PayloadAccessSetEstimator, ReadWriteSetAnalysisEstimator,
+    },
+    sender_aware_shuffler::SenderAwareShuffler,
+    transaction_shuffler::TransactionShuffler,
+};
+use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReader};
+use aptos_types::transaction::SignedTransaction;
+use aptos_vm::{
+    data_cache::AsMoveResolver,
+    read_write_set_analysis::{add_on_functions_list, ReadWriteSetAnalysis},
+};
+use read_write_set_dynamic::NormalizedReadWriteSetAnalysis;
+use std::{str::FromStr, sync::Arc};
+
+/// The consensus transaction shuffler to apply to the generated blocks, to compare their effect
+/// on the execution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShufflerType {
+    NoShuffling,
+    SenderAware,
+    GasAndDependencyAware,
+    /// Same as `GasAndDependencyAware`, estimating access sets with the read/write set analysis
+    /// of the framework instead of the transaction payloads.
+    GasAndDependencyAwareWithAnalysis,
+}
+
+impl FromStr for ShufflerType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "no_shuffling" => Self::NoShuffling,
+            "sender_aware" => Self::SenderAware,
+            "gas_and_dependency_aware" => Self::GasAndDependencyAware,
+            "gas_and_dependency_aware_with_analysis" => Self::GasAndDependencyAwareWithAnalysis,
+            _ => bail!("Unknown shuffler type {}", s),
+        })
+    }
+}
+
+pub struct BlockShuffler {
+    shuffler_type: ShufflerType,
+    conflict_window_size: usize,
+    db: Arc<dyn DbReader>,
+    // Only computed for `ShufflerType::GasAndDependencyAwareWithAnalysis`, as it is slow
+    analysis: Option<NormalizedReadWriteSetAnalysis>,
+}
+
+impl BlockShuffler {
+    pub fn new(
+        shuffler_type: ShufflerType,
+        conflict_window_size: usize,
+        db: Arc<dyn DbReader>,
+    ) -> Result<Self> {
+        let analysis = if shuffler_type == ShufflerType::GasAndDependencyAwareWithAnalysis {
+            let modules = aptos_cached_packages::head_release_bundle().compiled_modules();
+            Some(read_write_set::analyze(&modules)?.normalize_all_scripts(add_on_functions_list()))
+        } else {
+            None
+        };
+        Ok(Self {
+            shuffler_type,
+            conflict_window_size,
+            db,
+            analysis,
+        })
+    }
+
+    pub fn shuffle(&self, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+        match self.shuffler_type {
+            ShufflerType::NoShuffling => txns,
+            ShufflerType::SenderAware => {
+                SenderAwareShuffler::new(self.conflict_window_size).shuffle(txns)
+            },
+            ShufflerType::GasAndDependencyAware => GasAndDependencyAwareShuffler::new(
+                self.conflict_window_size,
+                PayloadAccessSetEstimator,
+            )
+            .shuffle(txns),
+            ShufflerType::GasAndDependencyAwareWithAnalysis => {
+                // The analysis resolves the modules called by the transactions from the state
+                let state_view = self.db.latest_state_checkpoint_view().unwrap();
+                let resolver = state_view.as_move_resolver();
+                let analysis =
+                    ReadWriteSetAnalysis::new(self.analysis.as_ref().unwrap(), &resolver);
+                GasAndDependencyAwareShuffler::new(
+                    self.conflict_window_size,
+                    ReadWriteSetAnalysisEstimator::new(analysis),
+                )
+                .shuffle(txns)
+            },
+        }
+    }
+}
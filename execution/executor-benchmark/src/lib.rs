@@ -4,6 +4,7 @@
 
 mod account_generator;
 pub mod benchmark_transaction;
+pub mod block_shuffler;
 pub mod db_generator;
 pub mod fake_executor;
 mod metrics;
@@ -13,8 +14,11 @@ pub mod transaction_executor;
 pub mod transaction_generator;
 
 use crate::{
-    benchmark_transaction::BenchmarkTransaction, pipeline::Pipeline,
-    transaction_committer::TransactionCommitter, transaction_executor::TransactionExecutor,
+    benchmark_transaction::BenchmarkTransaction,
+    block_shuffler::{BlockShuffler, ShufflerType},
+    pipeline::Pipeline,
+    transaction_committer::TransactionCommitter,
+    transaction_executor::TransactionExecutor,
     transaction_generator::TransactionGenerator,
 };
 use aptos_config::config::{NodeConfig, PrunerConfig};
@@ -71,6 +75,8 @@ pub fn run_benchmark<V>(
     verify_sequence_numbers: bool,
     pruner_config: PrunerConfig,
     use_state_kv_db: bool,
+    shuffler_type: ShufflerType,
+    conflict_window_size: usize,
 ) where
     V: TransactionBlockExecutor<BenchmarkTransaction> + 'static,
 {
@@ -94,14 +100,26 @@ pub fn run_benchmark<V>(
         version,
     );
 
+    let block_shuffler = BlockShuffler::new(shuffler_type, conflict_window_size, db.reader.clone())
+        .expect("Failed to create the block shuffler.");
+
     let start_time = Instant::now();
-    generator.run_transfer(block_size, num_transfer_blocks, transactions_per_sender);
+    generator.run_transfer(
+        block_size,
+        num_transfer_blocks,
+        transactions_per_sender,
+        &block_shuffler,
+    );
     generator.drop_sender();
     pipeline.join();
 
     let elapsed = start_time.elapsed().as_secs_f32();
     let delta_v = db.reader.get_latest_version().unwrap() - version;
-    info!("Overall TPS: transfer: {} txn/s", delta_v as f32 / elapsed,);
+    info!(
+        "Overall TPS: transfer ({:?} shuffling): {} txn/s",
+        shuffler_type,
+        delta_v as f32 / elapsed,
+    );
 
     if verify_sequence_numbers {
         generator.verify_sequence_numbers(db.reader);
@@ -210,6 +228,7 @@ fn add_accounts_impl<V>(
 
 #[cfg(test)]
 mod tests {
+    use crate::block_shuffler::ShufflerType;
     use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
     use aptos_temppath::TempPath;
     use aptos_vm::AptosVM;
@@ -239,6 +258,8 @@ mod tests {
             true,
             NO_OP_STORAGE_PRUNER_CONFIG,
             false,
+            ShufflerType::GasAndDependencyAware,
+            32, /* conflict_window_size */
         );
     }
 }
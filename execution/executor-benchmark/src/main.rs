@@ -8,7 +8,8 @@ use aptos_config::config::{
 };
 use aptos_executor::block_executor::TransactionBlockExecutor;
 use aptos_executor_benchmark::{
-    benchmark_transaction::BenchmarkTransaction, fake_executor::FakeExecutor,
+    benchmark_transaction::BenchmarkTransaction, block_shuffler::ShufflerType,
+    fake_executor::FakeExecutor,
 };
 use aptos_metrics_core::{register_int_gauge, IntGauge};
 use aptos_push_metrics::MetricsPusher;
@@ -167,6 +168,17 @@ enum Command {
 
         #[structopt(long, parse(from_os_str))]
         checkpoint_dir: PathBuf,
+
+        #[structopt(
+            long,
+            default_value = "no_shuffling",
+            about = "consensus transaction shuffler to order the transfer blocks with: no_shuffling, \
+            sender_aware, gas_and_dependency_aware or gas_and_dependency_aware_with_analysis"
+        )]
+        shuffler_type: ShufflerType,
+
+        #[structopt(long, default_value = "32")]
+        conflict_window_size: usize,
     },
     AddAccounts {
         #[structopt(long, parse(from_os_str))]
@@ -207,6 +219,8 @@ where
             blocks,
             data_dir,
             checkpoint_dir,
+            shuffler_type,
+            conflict_window_size,
         } => {
             aptos_executor_benchmark::run_benchmark::<E>(
                 opt.block_size,
@@ -217,6 +231,8 @@ where
                 opt.verify_sequence_numbers,
                 opt.pruner_opt.pruner_config(),
                 opt.use_state_kv_db,
+                shuffler_type,
+                conflict_window_size,
             );
         },
         Command::AddAccounts {
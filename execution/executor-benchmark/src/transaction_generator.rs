@@ -5,6 +5,7 @@
 use crate::{
     account_generator::{AccountCache, AccountGenerator},
     benchmark_transaction::{AccountCreationInfo, BenchmarkTransaction, ExtraInfo, TransferInfo},
+    block_shuffler::BlockShuffler,
 };
 use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue};
 use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
@@ -23,6 +24,7 @@ use itertools::Itertools;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashMap,
     fs::File,
     io::{Read, Write},
     iter::once,
@@ -231,9 +233,15 @@ impl TransactionGenerator {
         block_size: usize,
         num_transfer_blocks: usize,
         transactions_per_sender: usize,
+        block_shuffler: &BlockShuffler,
     ) {
         assert!(self.block_sender.is_some());
-        self.gen_transfer_transactions(block_size, num_transfer_blocks, transactions_per_sender);
+        self.gen_transfer_transactions(
+            block_size,
+            num_transfer_blocks,
+            transactions_per_sender,
+            block_shuffler,
+        );
     }
 
     pub fn create_seed_accounts(
@@ -349,14 +357,17 @@ impl TransactionGenerator {
         println!("[{}] done.", now_fmt!());
     }
 
-    /// Generates transactions for random pairs of accounts.
+    /// Generates transactions for random pairs of accounts, and orders each block with
+    /// `block_shuffler`.
     pub fn gen_transfer_transactions(
         &mut self,
         block_size: usize,
         num_blocks: usize,
         transactions_per_sender: usize,
+        block_shuffler: &BlockShuffler,
     ) {
         for _ in 0..num_blocks {
+            let mut transfer_infos = HashMap::new();
             // TODO: handle when block_size isn't divisible by transactions_per_sender
             let transactions: Vec<_> = (0..(block_size / transactions_per_sender))
                 .into_iter()
@@ -373,17 +384,27 @@ impl TransactionGenerator {
                             let txn = sender.sign_with_transaction_builder(
                                 self.transaction_factory.transfer(receiver, amount),
                             );
-                            BenchmarkTransaction::new(
-                                Transaction::UserTransaction(txn),
-                                ExtraInfo::TransferInfo(TransferInfo::new(
-                                    sender.address(),
-                                    receiver,
-                                    amount,
-                                )),
-                            )
+                            transfer_infos.insert(
+                                (txn.sender(), txn.sequence_number()),
+                                TransferInfo::new(sender.address(), receiver, amount),
+                            );
+                            txn
                         })
                         .collect::<Vec<_>>()
                 })
+                .collect();
+            let transactions: Vec<_> = block_shuffler
+                .shuffle(transactions)
+                .into_iter()
+                .map(|txn| {
+                    let transfer_info = transfer_infos
+                        .remove(&(txn.sender(), txn.sequence_number()))
+                        .unwrap();
+                    BenchmarkTransaction::new(
+                        Transaction::UserTransaction(txn),
+                        ExtraInfo::TransferInfo(transfer_info),
+                    )
+                })
                 .chain(once(
                     Transaction::StateCheckpoint(HashValue::random()).into(),
                 ))
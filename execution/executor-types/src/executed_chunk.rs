@@ -198,10 +198,12 @@ impl ExecutedChunk {
 
         let mut transaction_info_hashes = Vec::new();
         let mut reconfig_events = Vec::new();
+        let mut gas_used = 0;
 
         for (_, txn_data) in &self.to_commit {
             transaction_info_hashes.push(txn_data.txn_info_hash());
-            reconfig_events.extend(txn_data.reconfig_events.iter().cloned())
+            reconfig_events.extend(txn_data.reconfig_events.iter().cloned());
+            gas_used += txn_data.gas_used();
         }
 
         StateComputeResult::new(
@@ -214,6 +216,7 @@ impl ExecutedChunk {
             self.status.clone(),
             transaction_info_hashes,
             reconfig_events,
+            gas_used,
         )
     }
 }
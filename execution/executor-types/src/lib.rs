@@ -43,14 +43,17 @@ mod parsed_transaction_output;
 
 pub trait ChunkExecutorTrait: Send + Sync {
     /// Verifies the transactions based on the provided proofs and ledger info. If the transactions
-    /// are valid, executes them and returns the executed result for commit.
+    /// are valid, executes them and updates the ledger, so the result can be committed.
     fn execute_chunk(
         &self,
         txn_list_with_proof: TransactionListWithProof,
         // Target LI that has been verified independently: the proofs are relative to this version.
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
-    ) -> Result<()>;
+    ) -> Result<()> {
+        self.enqueue_chunk_by_execution(txn_list_with_proof, verified_target_li, epoch_change_li)?;
+        self.update_ledger()
+    }
 
     /// Similar to `execute_chunk`, but instead of executing transactions, apply the transaction
     /// outputs directly to get the executed result.
@@ -60,8 +63,40 @@ pub trait ChunkExecutorTrait: Send + Sync {
         // Target LI that has been verified independently: the proofs are relative to this version.
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.enqueue_chunk_by_transaction_outputs(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )?;
+        self.update_ledger()
+    }
+
+    /// Verifies the transactions based on the provided proofs and ledger info, executes them on
+    /// top of the latest state (which can be ahead of the latest ledger) and queues the result for
+    /// the ledger update.
+    fn enqueue_chunk_by_execution(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        // Target LI that has been verified independently: the proofs are relative to this version.
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()>;
 
+    /// Similar to `enqueue_chunk_by_execution`, but instead of executing transactions, apply the
+    /// transaction outputs directly to the latest state.
+    fn enqueue_chunk_by_transaction_outputs(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        // Target LI that has been verified independently: the proofs are relative to this version.
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()>;
+
+    /// Updates the ledger (i.e., the transaction infos and accumulator) with the oldest enqueued
+    /// chunk, and verifies it against the proofs it was received with, so it can be committed.
+    fn update_ledger(&self) -> Result<()>;
+
     /// Commit a previously executed chunk. Returns a chunk commit notification.
     fn commit_chunk(&self) -> Result<ChunkCommitNotification>;
 
@@ -253,6 +288,9 @@ pub struct StateComputeResult {
     transaction_info_hashes: Vec<HashValue>,
 
     reconfig_events: Vec<ContractEvent>,
+
+    /// The total gas used by the transactions of the block.
+    gas_used: u64,
 }
 
 impl StateComputeResult {
@@ -266,6 +304,7 @@ impl StateComputeResult {
         compute_status: Vec<TransactionStatus>,
         transaction_info_hashes: Vec<HashValue>,
         reconfig_events: Vec<ContractEvent>,
+        gas_used: u64,
     ) -> Self {
         Self {
             root_hash,
@@ -277,6 +316,7 @@ impl StateComputeResult {
             compute_status,
             transaction_info_hashes,
             reconfig_events,
+            gas_used,
         }
     }
 
@@ -294,6 +334,7 @@ impl StateComputeResult {
             compute_status: vec![],
             transaction_info_hashes: vec![],
             reconfig_events: vec![],
+            gas_used: 0,
         }
     }
 
@@ -360,6 +401,10 @@ impl StateComputeResult {
     pub fn reconfig_events(&self) -> &[ContractEvent] {
         &self.reconfig_events
     }
+
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
 }
 
 pub struct ProofReader {
@@ -9,6 +9,7 @@ use aptos_types::{
 };
 use std::ops::Deref;
 
+#[derive(Clone)]
 pub struct ParsedTransactionOutput {
     output: TransactionOutput,
     reconfig_events: Vec<ContractEvent>,
@@ -6,17 +6,22 @@
 
 use crate::{
     components::{
-        apply_chunk_output::{ensure_no_discard, ensure_no_retry},
-        chunk_commit_queue::ChunkCommitQueue,
+        apply_chunk_output::{
+            ensure_no_discard, ensure_no_retry, ApplyChunkOutput, StateCheckpointOutput,
+        },
+        chunk_commit_queue::{ChunkCommitQueue, ChunkToUpdateLedger},
         chunk_output::ChunkOutput,
+        chunk_result_cache::{ChunkResultCache, ChunkResultKey},
     },
     logging::{LogEntry, LogSchema},
     metrics::{
         APTOS_EXECUTOR_APPLY_CHUNK_SECONDS, APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS,
-        APTOS_EXECUTOR_EXECUTE_CHUNK_SECONDS, APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS,
+        APTOS_EXECUTOR_EXECUTE_CHUNK_SECONDS, APTOS_EXECUTOR_UPDATE_LEDGER_CHUNK_SECONDS,
+        APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS,
     },
 };
-use anyhow::Result;
+use anyhow::{ensure, Result};
+use aptos_crypto::hash::TransactionAccumulatorHasher;
 use aptos_executor_types::{
     ChunkCommitNotification, ChunkExecutorTrait, ExecutedChunk, ParsedTransactionOutput,
     TransactionReplayer, VerifyExecutionMode,
@@ -25,12 +30,13 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_state_view::StateViewId;
 use aptos_storage_interface::{
-    cached_state_view::CachedStateView, sync_proof_fetcher::SyncProofFetcher, DbReaderWriter,
-    ExecutedTrees,
+    cached_state_view::CachedStateView, state_delta::StateDelta,
+    sync_proof_fetcher::SyncProofFetcher, DbReaderWriter, ExecutedTrees,
 };
 use aptos_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
+    proof::accumulator::InMemoryAccumulator,
     transaction::{
         Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
         TransactionOutputListWithProof, TransactionStatus, Version,
@@ -45,13 +51,20 @@ use std::{iter::once, marker::PhantomData, sync::Arc};
 pub struct ChunkExecutor<V> {
     db: DbReaderWriter,
     inner: RwLock<Option<ChunkExecutorInner<V>>>,
+    // Outlives resets, as state sync resets the executor before retrying a failed chunk
+    result_cache: Arc<ChunkResultCache>,
 }
 
 impl<V: VMExecutor> ChunkExecutor<V> {
     pub fn new(db: DbReaderWriter) -> Self {
+        Self::new_with_result_cache(db, Arc::new(ChunkResultCache::default()))
+    }
+
+    pub fn new_with_result_cache(db: DbReaderWriter, result_cache: Arc<ChunkResultCache>) -> Self {
         Self {
             db,
             inner: RwLock::new(None),
+            result_cache,
         }
     }
 
@@ -64,7 +77,7 @@ impl<V: VMExecutor> ChunkExecutor<V> {
 }
 
 impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
-    fn execute_chunk(
+    fn enqueue_chunk_by_execution(
         &self,
         txn_list_with_proof: TransactionListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
@@ -75,20 +88,32 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
             .read()
             .as_ref()
             .expect("not reset")
-            .execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)
+            .enqueue_chunk_by_execution(txn_list_with_proof, verified_target_li, epoch_change_li)
     }
 
-    fn apply_chunk(
+    fn enqueue_chunk_by_transaction_outputs(
         &self,
         txn_output_list_with_proof: TransactionOutputListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()> {
-        self.inner.read().as_ref().expect("not reset").apply_chunk(
-            txn_output_list_with_proof,
-            verified_target_li,
-            epoch_change_li,
-        )
+        self.inner
+            .read()
+            .as_ref()
+            .expect("not reset")
+            .enqueue_chunk_by_transaction_outputs(
+                txn_output_list_with_proof,
+                verified_target_li,
+                epoch_change_li,
+            )
+    }
+
+    fn update_ledger(&self) -> Result<()> {
+        self.inner
+            .read()
+            .as_ref()
+            .expect("not reset")
+            .update_ledger()
     }
 
     fn commit_chunk(&self) -> Result<ChunkCommitNotification> {
@@ -100,7 +125,10 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
     }
 
     fn reset(&self) -> Result<()> {
-        *self.inner.write() = Some(ChunkExecutorInner::new(self.db.clone())?);
+        *self.inner.write() = Some(ChunkExecutorInner::new(
+            self.db.clone(),
+            self.result_cache.clone(),
+        )?);
         Ok(())
     }
 
@@ -112,45 +140,43 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
 struct ChunkExecutorInner<V> {
     db: DbReaderWriter,
     commit_queue: Mutex<ChunkCommitQueue>,
+    result_cache: Arc<ChunkResultCache>,
     _phantom: PhantomData<V>,
 }
 
 impl<V: VMExecutor> ChunkExecutorInner<V> {
-    pub fn new(db: DbReaderWriter) -> Result<Self> {
+    pub fn new(db: DbReaderWriter, result_cache: Arc<ChunkResultCache>) -> Result<Self> {
         let commit_queue = Mutex::new(ChunkCommitQueue::new_from_db(&db.reader)?);
         Ok(Self {
             db,
             commit_queue,
+            result_cache,
             _phantom: PhantomData,
         })
     }
 
-    fn state_view(&self, latest_view: &ExecutedTrees) -> Result<CachedStateView> {
-        latest_view.verified_state_view(
+    fn state_view(&self, latest_state: &StateDelta) -> Result<CachedStateView> {
+        let next_version = latest_state.current_version.map_or(0, |v| v + 1);
+        CachedStateView::new(
             StateViewId::ChunkExecution {
-                first_version: latest_view.txn_accumulator().num_leaves(),
+                first_version: next_version,
             },
             Arc::clone(&self.db.reader),
+            next_version,
+            latest_state.current.clone(),
             Arc::new(SyncProofFetcher::new(self.db.reader.clone())),
         )
     }
 
-    fn apply_chunk_output_for_state_sync(
-        verified_target_li: &LedgerInfoWithSignatures,
-        epoch_change_li: Option<&LedgerInfoWithSignatures>,
-        latest_view: &ExecutedTrees,
+    fn apply_chunk_output_to_state(
+        latest_state: &StateDelta,
         chunk_output: ChunkOutput,
-        transaction_infos: &[TransactionInfo],
-    ) -> Result<ExecutedChunk> {
-        let (mut executed_chunk, to_discard, to_retry) =
-            chunk_output.apply_to_ledger(latest_view)?;
+    ) -> Result<StateCheckpointOutput> {
+        let (state_checkpoint_output, to_discard, to_retry) =
+            chunk_output.into_state_checkpoint_output(latest_state)?;
         ensure_no_discard(to_discard)?;
         ensure_no_retry(to_retry)?;
-        executed_chunk.ledger_info = executed_chunk
-            .maybe_select_chunk_ending_ledger_info(verified_target_li, epoch_change_li)?;
-        executed_chunk.ensure_transaction_infos_match(transaction_infos)?;
-
-        Ok(executed_chunk)
+        Ok(state_checkpoint_output)
     }
 
     fn commit_chunk_impl(&self) -> Result<Arc<ExecutedChunk>> {
@@ -179,7 +205,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
     }
 
     // ************************* Block Executor Implementation *************************
-    fn execute_chunk(
+    fn enqueue_chunk_by_execution(
         &self,
         txn_list_with_proof: TransactionListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
@@ -189,36 +215,50 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
 
         let num_txns = txn_list_with_proof.transactions.len();
         let first_version_in_request = txn_list_with_proof.first_transaction_version;
-        let (_persisted_view, latest_view) = self.commit_queue.lock().persisted_and_latest_view();
+        let latest_state = self.commit_queue.lock().latest_state();
 
         let (txn_info_list_with_proof, txns_to_skip, transactions) = verify_chunk(
             txn_list_with_proof,
             verified_target_li,
             first_version_in_request,
-            &latest_view,
+            &latest_state,
             num_txns,
         )?;
 
-        // Execute transactions.
-        let state_view = self.state_view(&latest_view)?;
-        let chunk_output = {
-            let _timer = APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
-            ChunkOutput::by_transaction_execution::<V>(transactions, state_view)?
+        // Execute transactions, unless the same transactions were already executed on top of the
+        // same state, e.g. by an attempt that failed verification or commit.
+        let cache_key = ChunkResultKey::new(&latest_state, &transactions);
+        let state_checkpoint_output = match self.result_cache.get(&cache_key) {
+            Some(state_checkpoint_output) => state_checkpoint_output,
+            None => {
+                let state_view = self.state_view(&latest_state)?;
+                let chunk_output = {
+                    let _timer = APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
+                    ChunkOutput::by_transaction_execution::<V>(transactions, state_view)?
+                };
+                let state_checkpoint_output =
+                    Self::apply_chunk_output_to_state(&latest_state, chunk_output)?;
+                self.result_cache
+                    .insert(cache_key, state_checkpoint_output.clone());
+                state_checkpoint_output
+            },
         };
-        let executed_chunk = Self::apply_chunk_output_for_state_sync(
-            verified_target_li,
-            epoch_change_li,
-            &latest_view,
-            chunk_output,
-            &txn_info_list_with_proof.transaction_infos[txns_to_skip..],
-        )?;
 
-        // Add result to commit queue.
-        self.commit_queue.lock().enqueue(executed_chunk);
+        // Add result to the ledger update queue.
+        self.commit_queue
+            .lock()
+            .enqueue_chunk_to_update_ledger(ChunkToUpdateLedger {
+                state_checkpoint_output,
+                first_version_in_request,
+                txns_to_skip,
+                txn_infos_with_proof: txn_info_list_with_proof,
+                verified_target_li: verified_target_li.clone(),
+                epoch_change_li: epoch_change_li.cloned(),
+            });
 
         info!(
             LogSchema::new(LogEntry::ChunkExecutor)
-                .local_synced_version(latest_view.version().unwrap_or(0))
+                .local_synced_version(latest_state.current_version.unwrap_or(0))
                 .first_version_in_request(first_version_in_request)
                 .num_txns_in_request(num_txns),
             "Executed transaction chunk!",
@@ -227,7 +267,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         Ok(())
     }
 
-    fn apply_chunk(
+    fn enqueue_chunk_by_transaction_outputs(
         &self,
         txn_output_list_with_proof: TransactionOutputListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
@@ -237,38 +277,38 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
 
         let num_txns = txn_output_list_with_proof.transactions_and_outputs.len();
         let first_version_in_request = txn_output_list_with_proof.first_transaction_output_version;
-        let (_persisted_view, latest_view) = self.commit_queue.lock().persisted_and_latest_view();
+        let latest_state = self.commit_queue.lock().latest_state();
 
         // Verify input transaction list.
         txn_output_list_with_proof
             .verify(verified_target_li.ledger_info(), first_version_in_request)?;
 
         // Skip transactions already in ledger.
-        let txns_to_skip = txn_output_list_with_proof.proof.verify_extends_ledger(
-            latest_view.txn_accumulator().num_leaves(),
-            latest_view.txn_accumulator().root_hash(),
-            first_version_in_request,
-        )?;
+        let txns_to_skip = num_txns_to_skip(first_version_in_request, num_txns, &latest_state)?;
         let mut txns_and_outputs = txn_output_list_with_proof.transactions_and_outputs;
         txns_and_outputs.drain(..txns_to_skip);
 
         // Apply transaction outputs.
-        let state_view = self.state_view(&latest_view)?;
+        let state_view = self.state_view(&latest_state)?;
         let chunk_output = ChunkOutput::by_transaction_output(txns_and_outputs, state_view)?;
-        let executed_chunk = Self::apply_chunk_output_for_state_sync(
-            verified_target_li,
-            epoch_change_li,
-            &latest_view,
-            chunk_output,
-            &txn_output_list_with_proof.proof.transaction_infos[txns_to_skip..],
-        )?;
+        let state_checkpoint_output =
+            Self::apply_chunk_output_to_state(&latest_state, chunk_output)?;
 
-        // Add result to commit queue.
-        self.commit_queue.lock().enqueue(executed_chunk);
+        // Add result to the ledger update queue.
+        self.commit_queue
+            .lock()
+            .enqueue_chunk_to_update_ledger(ChunkToUpdateLedger {
+                state_checkpoint_output,
+                first_version_in_request,
+                txns_to_skip,
+                txn_infos_with_proof: txn_output_list_with_proof.proof,
+                verified_target_li: verified_target_li.clone(),
+                epoch_change_li: epoch_change_li.cloned(),
+            });
 
         info!(
             LogSchema::new(LogEntry::ChunkExecutor)
-                .local_synced_version(latest_view.version().unwrap_or(0))
+                .local_synced_version(latest_state.current_version.unwrap_or(0))
                 .first_version_in_request(first_version_in_request)
                 .num_txns_in_request(num_txns),
             "Applied transaction output chunk!",
@@ -277,6 +317,47 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         Ok(())
     }
 
+    fn update_ledger(&self) -> Result<()> {
+        let _timer = APTOS_EXECUTOR_UPDATE_LEDGER_CHUNK_SECONDS.start_timer();
+
+        let (latest_txn_accumulator, chunk) =
+            self.commit_queue.lock().next_chunk_to_update_ledger()?;
+        let ChunkToUpdateLedger {
+            state_checkpoint_output,
+            first_version_in_request,
+            txns_to_skip,
+            txn_infos_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        } = chunk;
+
+        // The transactions were skipped based on the version of the latest state, check that
+        // the ones skipped are indeed in the ledger.
+        verify_chunk_extends_ledger(
+            &txn_infos_with_proof,
+            first_version_in_request,
+            txns_to_skip,
+            &latest_txn_accumulator,
+        )?;
+
+        let mut executed_chunk = ApplyChunkOutput::calculate_ledger_update(
+            state_checkpoint_output,
+            &latest_txn_accumulator,
+        );
+        executed_chunk.ledger_info = executed_chunk
+            .maybe_select_chunk_ending_ledger_info(&verified_target_li, epoch_change_li.as_ref())?;
+        executed_chunk.ensure_transaction_infos_match(
+            &txn_infos_with_proof.transaction_infos[txns_to_skip..],
+        )?;
+
+        // Add result to commit queue.
+        self.commit_queue
+            .lock()
+            .enqueue_chunk_to_commit(executed_chunk);
+
+        Ok(())
+    }
+
     fn commit_chunk(&self) -> Result<ChunkCommitNotification> {
         let _timer = APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS.start_timer();
         let executed_chunk = self.commit_chunk_impl()?;
@@ -288,14 +369,38 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
     }
 }
 
+/// Returns the number of transactions at the beginning of a chunk that are already applied to
+/// the latest state. The chunk must not start after the latest state.
+fn num_txns_to_skip(
+    first_version_in_request: Option<Version>,
+    num_txns: usize,
+    latest_state: &StateDelta,
+) -> Result<usize> {
+    let next_version = latest_state.current_version.map_or(0, |v| v + 1);
+    let first_version = match first_version_in_request {
+        Some(first_version) => first_version,
+        None => return Ok(0),
+    };
+    ensure!(
+        first_version <= next_version,
+        "Chunk starts at version {}, after the next version {}.",
+        first_version,
+        next_version,
+    );
+    Ok(std::cmp::min(
+        (next_version - first_version) as usize,
+        num_txns,
+    ))
+}
+
 /// Verifies the transaction list proof against the ledger info and returns transactions
-/// that are not already applied in the ledger.
+/// that are not already applied to the latest state.
 #[cfg(not(feature = "consensus-only-perf-test"))]
 fn verify_chunk(
     txn_list_with_proof: TransactionListWithProof,
     verified_target_li: &LedgerInfoWithSignatures,
     first_version_in_request: Option<u64>,
-    latest_view: &ExecutedTrees,
+    latest_state: &StateDelta,
     num_txns: usize,
 ) -> Result<
     (
@@ -312,11 +417,7 @@ fn verify_chunk(
     let txn_info_with_proof = txn_list_with_proof.proof;
 
     // Skip transactions already in ledger
-    let txns_to_skip = txn_info_with_proof.verify_extends_ledger(
-        latest_view.txn_accumulator().num_leaves(),
-        latest_view.txn_accumulator().root_hash(),
-        first_version_in_request,
-    )?;
+    let txns_to_skip = num_txns_to_skip(first_version_in_request, num_txns, latest_state)?;
 
     let mut transactions = txn_list;
     transactions.drain(..txns_to_skip);
@@ -341,7 +442,7 @@ fn verify_chunk(
     txn_list_with_proof: TransactionListWithProof,
     _verified_target_li: &LedgerInfoWithSignatures,
     _first_version_in_request: Option<u64>,
-    _latest_view: &ExecutedTrees,
+    _latest_state: &StateDelta,
     _num_txns: usize,
 ) -> Result<
     (
@@ -359,6 +460,41 @@ fn verify_chunk(
     ))
 }
 
+/// Verifies that the transactions skipped when the chunk was enqueued are the ones in the
+/// ledger, i.e., that the chunk extends the ledger the ledger update appends it to.
+#[cfg(not(feature = "consensus-only-perf-test"))]
+fn verify_chunk_extends_ledger(
+    txn_infos_with_proof: &aptos_types::proof::TransactionInfoListWithProof,
+    first_version_in_request: Option<Version>,
+    txns_to_skip: usize,
+    latest_txn_accumulator: &InMemoryAccumulator<TransactionAccumulatorHasher>,
+) -> Result<()> {
+    let txns_in_ledger = txn_infos_with_proof.verify_extends_ledger(
+        latest_txn_accumulator.num_leaves(),
+        latest_txn_accumulator.root_hash(),
+        first_version_in_request,
+    )?;
+    ensure!(
+        txns_in_ledger == txns_to_skip,
+        "{} transactions of the chunk are in the ledger, but {} were skipped.",
+        txns_in_ledger,
+        txns_to_skip,
+    );
+    Ok(())
+}
+
+/// In consensus-only mode, the proofs are empty (see `verify_chunk`), and no transactions are
+/// skipped.
+#[cfg(feature = "consensus-only-perf-test")]
+fn verify_chunk_extends_ledger(
+    _txn_infos_with_proof: &aptos_types::proof::TransactionInfoListWithProof,
+    _first_version_in_request: Option<Version>,
+    _txns_to_skip: usize,
+    _latest_txn_accumulator: &InMemoryAccumulator<TransactionAccumulatorHasher>,
+) -> Result<()> {
+    Ok(())
+}
+
 impl<V: VMExecutor> TransactionReplayer for ChunkExecutor<V> {
     fn replay(
         &self,
@@ -428,7 +564,9 @@ impl<V: VMExecutor> TransactionReplayer for ChunkExecutorInner<V> {
             )?;
         }
 
-        self.commit_queue.lock().enqueue(executed_chunk);
+        self.commit_queue
+            .lock()
+            .enqueue_chunk_to_commit(executed_chunk);
         Ok(())
     }
 
@@ -519,7 +657,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         verify_execution_mode: &VerifyExecutionMode,
     ) -> Result<Version> {
         // Execute transactions.
-        let state_view = self.state_view(latest_view)?;
+        let state_view = self.state_view(latest_view.state())?;
         let txns = transactions
             .iter()
             .take((end_version - begin_version) as usize)
@@ -585,7 +723,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         })
         .collect();
 
-        let state_view = self.state_view(latest_view)?;
+        let state_view = self.state_view(latest_view.state())?;
         let chunk_output = ChunkOutput::by_transaction_output(txns_and_outputs, state_view)?;
         let (executed_batch, to_discard, to_retry) = chunk_output.apply_to_ledger(latest_view)?;
         ensure_no_discard(to_discard)?;
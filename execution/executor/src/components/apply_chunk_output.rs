@@ -10,7 +10,7 @@ use crate::{
 };
 use anyhow::{ensure, Result};
 use aptos_crypto::{
-    hash::{CryptoHash, EventAccumulatorHasher},
+    hash::{CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher},
     HashValue,
 };
 use aptos_executor_types::{
@@ -18,8 +18,9 @@ use aptos_executor_types::{
     TransactionData,
 };
 use aptos_logger::error;
-use aptos_storage_interface::ExecutedTrees;
+use aptos_storage_interface::{state_delta::StateDelta, ExecutedTrees};
 use aptos_types::{
+    epoch_state::EpochState,
     proof::accumulator::InMemoryAccumulator,
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{Transaction, TransactionInfo, TransactionOutput, TransactionStatus},
@@ -27,6 +28,19 @@ use aptos_types::{
 use rayon::prelude::*;
 use std::{collections::HashMap, iter::repeat, sync::Arc};
 
+/// The transactions of a chunk to keep and the state resulting from them, i.e., the output of
+/// applying a chunk to the state before its ledger update.
+#[derive(Clone)]
+pub struct StateCheckpointOutput {
+    pub status: Vec<TransactionStatus>,
+    pub to_keep: Vec<(Transaction, ParsedTransactionOutput)>,
+    pub state_updates_vec: Vec<HashMap<StateKey, Option<StateValue>>>,
+    pub state_checkpoint_hashes: Vec<Option<HashValue>>,
+    pub result_state: StateDelta,
+    /// If set, this is the new epoch info that should be changed to if this is committed.
+    pub next_epoch_state: Option<EpochState>,
+}
+
 pub struct ApplyChunkOutput;
 
 impl ApplyChunkOutput {
@@ -34,6 +48,17 @@ impl ApplyChunkOutput {
         chunk_output: ChunkOutput,
         base_view: &ExecutedTrees,
     ) -> Result<(ExecutedChunk, Vec<Transaction>, Vec<Transaction>)> {
+        let (state_checkpoint_output, to_discard, to_retry) =
+            Self::calculate_state_checkpoint(chunk_output, base_view.state())?;
+        let executed_chunk =
+            Self::calculate_ledger_update(state_checkpoint_output, base_view.txn_accumulator());
+        Ok((executed_chunk, to_discard, to_retry))
+    }
+
+    pub fn calculate_state_checkpoint(
+        chunk_output: ChunkOutput,
+        base_state: &StateDelta,
+    ) -> Result<(StateCheckpointOutput, Vec<Transaction>, Vec<Transaction>)> {
         let ChunkOutput {
             state_cache,
             transactions,
@@ -52,10 +77,37 @@ impl ApplyChunkOutput {
             let _timer = APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
                 .with_label_values(&["apply_write_set"])
                 .start_timer();
-            InMemoryStateCalculator::new(base_view.state(), state_cache)
+            InMemoryStateCalculator::new(base_state, state_cache)
                 .calculate_for_transaction_chunk(&to_keep, new_epoch)?
         };
 
+        Ok((
+            StateCheckpointOutput {
+                status,
+                to_keep,
+                state_updates_vec,
+                state_checkpoint_hashes,
+                result_state,
+                next_epoch_state,
+            },
+            to_discard,
+            to_retry,
+        ))
+    }
+
+    pub fn calculate_ledger_update(
+        state_checkpoint_output: StateCheckpointOutput,
+        base_txn_accumulator: &Arc<InMemoryAccumulator<TransactionAccumulatorHasher>>,
+    ) -> ExecutedChunk {
+        let StateCheckpointOutput {
+            status,
+            to_keep,
+            state_updates_vec,
+            state_checkpoint_hashes,
+            result_state,
+            next_epoch_state,
+        } = state_checkpoint_output;
+
         // Calculate TransactionData and TransactionInfo, i.e. the ledger history diff.
         let _timer = APTOS_EXECUTOR_OTHER_TIMERS_SECONDS
             .with_label_values(&["calculate_ledger_diff"])
@@ -64,20 +116,16 @@ impl ApplyChunkOutput {
             Self::assemble_ledger_diff(to_keep, state_updates_vec, state_checkpoint_hashes);
         let result_view = ExecutedTrees::new(
             result_state,
-            Arc::new(base_view.txn_accumulator().append(&transaction_info_hashes)),
+            Arc::new(base_txn_accumulator.append(&transaction_info_hashes)),
         );
 
-        Ok((
-            ExecutedChunk {
-                status,
-                to_commit,
-                result_view,
-                next_epoch_state,
-                ledger_info: None,
-            },
-            to_discard,
-            to_retry,
-        ))
+        ExecutedChunk {
+            status,
+            to_commit,
+            result_view,
+            next_epoch_state,
+            ledger_info: None,
+        }
     }
 
     fn sort_transactions(
@@ -4,13 +4,39 @@
 
 #![forbid(unsafe_code)]
 
+use crate::components::apply_chunk_output::StateCheckpointOutput;
 use anyhow::{anyhow, Result};
+use aptos_crypto::hash::TransactionAccumulatorHasher;
 use aptos_executor_types::ExecutedChunk;
-use aptos_storage_interface::{DbReader, ExecutedTrees};
+use aptos_storage_interface::{state_delta::StateDelta, DbReader, ExecutedTrees};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{accumulator::InMemoryAccumulator, TransactionInfoListWithProof},
+    transaction::Version,
+};
 use std::{collections::VecDeque, sync::Arc};
 
+/// A chunk applied to the state, waiting for the ledger update, along with what's needed to verify
+/// it against the proofs it was received with.
+pub struct ChunkToUpdateLedger {
+    pub state_checkpoint_output: StateCheckpointOutput,
+    /// Version of the first transaction in the request, none if the request is empty.
+    pub first_version_in_request: Option<Version>,
+    /// Number of transactions in the request that were already in the ledger.
+    pub txns_to_skip: usize,
+    pub txn_infos_with_proof: TransactionInfoListWithProof,
+    pub verified_target_li: LedgerInfoWithSignatures,
+    pub epoch_change_li: Option<LedgerInfoWithSignatures>,
+}
+
+/// Chunks go through three stages: they are applied to the latest state, then to the latest
+/// transaction accumulator, and finally committed. Each stage has its own queue so the stages can
+/// run concurrently, which means the latest state can be ahead of the latest accumulator.
 pub struct ChunkCommitQueue {
     persisted_view: ExecutedTrees,
+    latest_state: StateDelta,
+    latest_txn_accumulator: Arc<InMemoryAccumulator<TransactionAccumulatorHasher>>,
+    chunks_to_update_ledger: VecDeque<ChunkToUpdateLedger>,
     chunks_to_commit: VecDeque<Arc<ExecutedChunk>>,
 }
 
@@ -22,7 +48,10 @@ impl ChunkCommitQueue {
 
     pub fn new(persisted_view: ExecutedTrees) -> Self {
         Self {
+            latest_state: persisted_view.state().clone(),
+            latest_txn_accumulator: persisted_view.txn_accumulator().clone(),
             persisted_view,
+            chunks_to_update_ledger: VecDeque::new(),
             chunks_to_commit: VecDeque::new(),
         }
     }
@@ -31,6 +60,7 @@ impl ChunkCommitQueue {
         (self.persisted_view.clone(), self.latest_view())
     }
 
+    /// The view after the last chunk whose ledger has been updated.
     pub fn latest_view(&self) -> ExecutedTrees {
         self.chunks_to_commit
             .back()
@@ -38,6 +68,42 @@ impl ChunkCommitQueue {
             .unwrap_or_else(|| self.persisted_view.clone())
     }
 
+    /// The state after the last chunk applied, whose ledger may not have been updated yet.
+    pub fn latest_state(&self) -> StateDelta {
+        self.latest_state.clone()
+    }
+
+    pub fn enqueue_chunk_to_update_ledger(&mut self, chunk: ChunkToUpdateLedger) {
+        self.latest_state = chunk.state_checkpoint_output.result_state.clone();
+        self.chunks_to_update_ledger.push_back(chunk);
+    }
+
+    /// Removes the next chunk to update the ledger for, and returns it along with the accumulator
+    /// to append it to. If the update fails, the chunks applied on top of it can't be updated
+    /// either, and the queue has to be recreated from the db.
+    pub fn next_chunk_to_update_ledger(
+        &mut self,
+    ) -> Result<(
+        Arc<InMemoryAccumulator<TransactionAccumulatorHasher>>,
+        ChunkToUpdateLedger,
+    )> {
+        let chunk = self
+            .chunks_to_update_ledger
+            .pop_front()
+            .ok_or_else(|| anyhow!("Ledger update queue is empty."))?;
+        Ok((self.latest_txn_accumulator.clone(), chunk))
+    }
+
+    pub fn enqueue_chunk_to_commit(&mut self, chunk: ExecutedChunk) {
+        self.latest_txn_accumulator = chunk.result_view.txn_accumulator().clone();
+        // Chunks can also be enqueued without going through the ledger update stage, e.g. when
+        // replaying transactions, in which case they also advance the latest state.
+        if self.chunks_to_update_ledger.is_empty() {
+            self.latest_state = chunk.result_view.state().clone();
+        }
+        self.chunks_to_commit.push_back(Arc::new(chunk))
+    }
+
     pub fn next_chunk_to_commit(&self) -> Result<(ExecutedTrees, Arc<ExecutedChunk>)> {
         Ok((
             self.persisted_view.clone(),
@@ -48,10 +114,6 @@ impl ChunkCommitQueue {
         ))
     }
 
-    pub fn enqueue(&mut self, chunk: ExecutedChunk) {
-        self.chunks_to_commit.push_back(Arc::new(chunk))
-    }
-
     pub fn dequeue(&mut self) -> Result<()> {
         let committed_chunk = self
             .chunks_to_commit
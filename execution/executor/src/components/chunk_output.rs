@@ -4,12 +4,16 @@
 
 #![forbid(unsafe_code)]
 
-use crate::{components::apply_chunk_output::ApplyChunkOutput, metrics};
+use crate::{
+    components::apply_chunk_output::{ApplyChunkOutput, StateCheckpointOutput},
+    metrics,
+};
 use anyhow::Result;
 use aptos_executor_types::ExecutedChunk;
 use aptos_logger::{sample, sample::SampleRate, trace, warn};
 use aptos_storage_interface::{
     cached_state_view::{CachedStateView, StateCache},
+    state_delta::StateDelta,
     ExecutedTrees,
 };
 use aptos_types::{
@@ -85,6 +89,19 @@ impl ChunkOutput {
         ApplyChunkOutput::apply(self, base_view)
     }
 
+    /// Applies the output to the state only, leaving the ledger update to a later stage.
+    pub fn into_state_checkpoint_output(
+        self,
+        base_state: &StateDelta,
+    ) -> Result<(StateCheckpointOutput, Vec<Transaction>, Vec<Transaction>)> {
+        fail_point!("executor::vm_execute_chunk", |_| {
+            Err(anyhow::anyhow!(
+                "Injected error in into_state_checkpoint_output."
+            ))
+        });
+        ApplyChunkOutput::calculate_state_checkpoint(self, base_state)
+    }
+
     pub fn trace_log_transaction_status(&self) {
         let status: Vec<_> = self
             .transaction_outputs
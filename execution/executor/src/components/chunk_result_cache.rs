@@ -0,0 +1,78 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use crate::{
+    components::apply_chunk_output::StateCheckpointOutput,
+    metrics::APTOS_EXECUTOR_CHUNK_RESULT_CACHE,
+};
+use aptos_crypto::{
+    hash::{CryptoHash, DefaultHasher},
+    HashValue,
+};
+use aptos_infallible::Mutex;
+use aptos_storage_interface::state_delta::StateDelta;
+use aptos_types::transaction::{Transaction, Version};
+use lru::LruCache;
+
+/// Number of speculative chunk results kept by default. State sync only has a few chunks in
+/// flight, and a chunk is retried right after it fails.
+pub const DEFAULT_CHUNK_RESULT_CACHE_SIZE: usize = 8;
+
+/// Identifies the result of executing a list of transactions on top of a given state.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ChunkResultKey {
+    parent_state_root: HashValue,
+    parent_version: Option<Version>,
+    chunk_hash: HashValue,
+}
+
+impl ChunkResultKey {
+    pub fn new(parent_state: &StateDelta, transactions: &[Transaction]) -> Self {
+        let mut hasher = DefaultHasher::new(b"ChunkResultKey");
+        for txn in transactions {
+            hasher.update(txn.hash().as_ref());
+        }
+        Self {
+            parent_state_root: parent_state.current.root_hash(),
+            parent_version: parent_state.current_version,
+            chunk_hash: hasher.finish(),
+        }
+    }
+}
+
+/// Bounded cache of executed chunks applied to the state, before their ledger update, where they
+/// are verified against the transaction infos and assigned a ledger info. Execution is
+/// deterministic, so a chunk retried after a verification or commit failure, possibly fetched from
+/// another peer, can reuse the result instead of being executed by the VM again.
+pub struct ChunkResultCache {
+    cache: Mutex<LruCache<ChunkResultKey, StateCheckpointOutput>>,
+}
+
+impl ChunkResultCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub fn get(&self, key: &ChunkResultKey) -> Option<StateCheckpointOutput> {
+        let output = self.cache.lock().get(key).cloned();
+        let result = if output.is_some() { "hit" } else { "miss" };
+        APTOS_EXECUTOR_CHUNK_RESULT_CACHE
+            .with_label_values(&[result])
+            .inc();
+        output
+    }
+
+    pub fn insert(&self, key: ChunkResultKey, output: StateCheckpointOutput) {
+        self.cache.lock().put(key, output);
+    }
+}
+
+impl Default for ChunkResultCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHUNK_RESULT_CACHE_SIZE)
+    }
+}
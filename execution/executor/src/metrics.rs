@@ -30,6 +30,17 @@ pub static APTOS_EXECUTOR_APPLY_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static APTOS_EXECUTOR_UPDATE_LEDGER_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_executor_update_ledger_chunk_seconds",
+        // metric description
+        "The time spent in seconds of updating the ledger with a chunk in Aptos executor",
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
@@ -90,6 +101,17 @@ pub static APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(
     .unwrap()
 });
 
+pub static APTOS_EXECUTOR_CHUNK_RESULT_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_executor_chunk_result_cache",
+        // metric description
+        "Count of the executed chunk cache lookups in the chunk executor, by result (hit or miss)",
+        &["result"]
+    )
+    .unwrap()
+});
+
 pub static APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
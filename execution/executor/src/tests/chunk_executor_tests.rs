@@ -7,20 +7,29 @@
 use crate::{
     block_executor::BlockExecutor,
     chunk_executor::ChunkExecutor,
+    components::chunk_result_cache::{ChunkResultCache, ChunkResultKey},
     db_bootstrapper::{generate_waypoint, maybe_bootstrap},
     mock_vm::{encode_mint_transaction, MockVM},
     tests,
 };
+use anyhow::bail;
 use aptos_crypto::HashValue;
 use aptos_db::AptosDB;
 use aptos_executor_types::{BlockExecutorTrait, ChunkExecutorTrait};
-use aptos_storage_interface::DbReaderWriter;
+use aptos_storage_interface::{state_delta::StateDelta, DbReaderWriter, DbWriter};
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     test_helpers::transaction_test_helpers::block,
-    transaction::{Transaction, TransactionListWithProof, TransactionOutputListWithProof},
+    transaction::{
+        Transaction, TransactionListWithProof, TransactionOutputListWithProof, TransactionToCommit,
+        Version,
+    },
 };
 use rand::Rng;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 pub struct TestExecutor {
     _path: aptos_temppath::TempPath,
@@ -241,6 +250,164 @@ fn test_executor_execute_and_commit_chunk_restart() {
     }
 }
 
+#[test]
+fn test_executor_execute_chunk_retry_uses_cached_result() {
+    let (chunks, ledger_info) = tests::create_transaction_chunks(vec![1..31]);
+    let TestExecutor {
+        _path,
+        db,
+        executor: _,
+    } = TestExecutor::new();
+    let result_cache = Arc::new(ChunkResultCache::default());
+    let executor = ChunkExecutor::<MockVM>::new_with_result_cache(db.clone(), result_cache.clone());
+    let cache_key = ChunkResultKey::new(
+        db.reader.get_latest_executed_trees().unwrap().state(),
+        &chunks[0].transactions,
+    );
+    assert!(result_cache.get(&cache_key).is_none());
+
+    // The executed chunk is cached before it is committed.
+    executor
+        .execute_chunk(chunks[0].clone(), &ledger_info, None)
+        .unwrap();
+    assert!(result_cache.get(&cache_key).is_some());
+
+    // Simulate a failed commit, after which state sync resets the executor and retries the chunk.
+    executor.reset().unwrap();
+    executor
+        .execute_chunk(chunks[0].clone(), &ledger_info, None)
+        .unwrap();
+    executor.commit_chunk().unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li, ledger_info);
+}
+
+#[test]
+fn test_executor_pipelined_chunks() {
+    let (chunks, ledger_info) = tests::create_transaction_chunks(vec![1..11, 11..21, 21..31]);
+    let TestExecutor {
+        _path,
+        db,
+        executor,
+    } = TestExecutor::new();
+
+    // Chunks are executed ahead of the ledger updates, which are ahead of the commits.
+    executor
+        .enqueue_chunk_by_execution(chunks[0].clone(), &ledger_info, None)
+        .unwrap();
+    executor
+        .enqueue_chunk_by_execution(chunks[1].clone(), &ledger_info, None)
+        .unwrap();
+    executor.update_ledger().unwrap();
+    executor.commit_chunk().unwrap();
+    executor
+        .enqueue_chunk_by_execution(chunks[2].clone(), &ledger_info, None)
+        .unwrap();
+    executor.update_ledger().unwrap();
+    executor.update_ledger().unwrap();
+    assert!(executor.update_ledger().is_err());
+    assert_eq!(
+        db.reader
+            .get_latest_executed_trees()
+            .unwrap()
+            .num_transactions(),
+        11
+    );
+
+    executor.commit_chunk().unwrap();
+    executor.commit_chunk().unwrap();
+    assert!(executor.commit_chunk().is_err());
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li, ledger_info);
+}
+
+/// Fails `save_transactions` once `fail_commit` is set, as if the node crashed while committing.
+struct CrashingDbWriter {
+    db: Arc<dyn DbWriter>,
+    fail_commit: AtomicBool,
+}
+
+impl DbWriter for CrashingDbWriter {
+    fn save_transactions(
+        &self,
+        txns_to_commit: &[TransactionToCommit],
+        first_version: Version,
+        base_state_version: Option<Version>,
+        ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+        sync_commit: bool,
+        latest_in_memory_state: StateDelta,
+    ) -> anyhow::Result<()> {
+        if self.fail_commit.load(Ordering::SeqCst) {
+            // Write the transactions without the ledger info, and crash before the commit is
+            // acknowledged.
+            self.db.save_transactions(
+                txns_to_commit,
+                first_version,
+                base_state_version,
+                None,
+                sync_commit,
+                latest_in_memory_state,
+            )?;
+            bail!("Crashed!");
+        }
+        self.db.save_transactions(
+            txns_to_commit,
+            first_version,
+            base_state_version,
+            ledger_info_with_sigs,
+            sync_commit,
+            latest_in_memory_state,
+        )
+    }
+}
+
+#[test]
+fn test_executor_pipelined_chunks_crash_mid_commit() {
+    let (chunks, ledger_info) = tests::create_transaction_chunks(vec![1..11, 11..21, 21..31]);
+    let TestExecutor {
+        _path,
+        db,
+        executor: _,
+    } = TestExecutor::new();
+    let writer = Arc::new(CrashingDbWriter {
+        db: db.writer.clone(),
+        fail_commit: AtomicBool::new(false),
+    });
+    let executor = ChunkExecutor::<MockVM>::new(DbReaderWriter {
+        reader: db.reader.clone(),
+        writer: writer.clone(),
+    });
+
+    // Fill the pipeline, and crash while committing the second chunk.
+    for chunk in &chunks {
+        executor
+            .enqueue_chunk_by_execution(chunk.clone(), &ledger_info, None)
+            .unwrap();
+    }
+    executor.update_ledger().unwrap();
+    executor.update_ledger().unwrap();
+    executor.commit_chunk().unwrap();
+    writer.fail_commit.store(true, Ordering::SeqCst);
+    assert!(executor.commit_chunk().is_err());
+    drop(executor);
+
+    // The crashed chunk was written before the crash, so a restarted executor resumes after it.
+    assert_eq!(
+        db.reader
+            .get_latest_executed_trees()
+            .unwrap()
+            .num_transactions(),
+        21
+    );
+    let executor = ChunkExecutor::<MockVM>::new(db.clone());
+    executor
+        .execute_chunk(chunks[2].clone(), &ledger_info, None)
+        .unwrap();
+    executor.commit_chunk().unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li, ledger_info);
+}
+
 #[test]
 #[cfg_attr(feature = "consensus-only-perf-test", ignore)]
 fn test_executor_execute_and_commit_chunk_local_result_mismatch() {
@@ -330,10 +330,10 @@ fn validate_and_add_transactions<NetworkClient, TransactionValidator>(
     let vm_validation_timer = counters::PROCESS_TXN_BREAKDOWN_LATENCY
         .with_label_values(&[counters::VM_VALIDATION_LABEL])
         .start_timer();
-    let validation_results = transactions
-        .iter()
-        .map(|t| smp.validator.read().validate_transaction(t.0.clone()))
-        .collect::<Vec<_>>();
+    let validation_results = smp
+        .validator
+        .read()
+        .validate_transactions(transactions.iter().map(|t| t.0.clone()).collect());
     vm_validation_timer.stop_and_record();
     {
         let mut mempool = smp.mempool.lock();
@@ -12,10 +12,11 @@
 //! long as the latter is in its trusted peers set.
 use aptos_config::{
     config::{
-        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RateLimitConfig, RoleType,
-        CONNECTION_BACKOFF_BASE, CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS,
-        MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS,
-        MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
+        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, PriorityClassConfig,
+        RateLimitConfig, RoleType, TrafficCaptureConfig, CONNECTION_BACKOFF_BASE,
+        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS,
+        MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS,
+        MAX_OUTBOUND_QUEUED_MESSAGES, NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
 };
@@ -30,7 +31,7 @@ use aptos_network::{
     logging::NetworkSchema,
     peer_manager::{
         builder::{AuthenticationMode, PeerManagerBuilder},
-        ConnectionRequestSender,
+        ConnectionRequestSender, PeerManagerError,
     },
     protocols::{
         health_checker::{self, builder::HealthCheckerBuilder},
@@ -40,7 +41,7 @@ use aptos_network::{
         },
     },
 };
-use aptos_network_discovery::DiscoveryChangeListener;
+use aptos_network_discovery::{DiscoveryChangeListener, SystemDnsResolver};
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
 use std::{clone::Clone, collections::HashSet, sync::Arc, time::Duration};
@@ -90,6 +91,10 @@ impl NetworkBuilder {
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_priority_classes: Vec<PriorityClassConfig>,
+        max_outbound_queued_messages: usize,
+        interleave_outbound_streams: bool,
+        traffic_capture_config: Option<TrafficCaptureConfig>,
     ) -> Self {
         // A network cannot exist without a PeerManager
         // TODO:  construct this in create and pass it to new() as a parameter. The complication is manual construction of NetworkBuilder in various tests.
@@ -109,6 +114,10 @@ impl NetworkBuilder {
             inbound_rate_limit_config,
             outbound_rate_limit_config,
             tcp_buffer_cfg,
+            outbound_priority_classes,
+            max_outbound_queued_messages,
+            interleave_outbound_streams,
+            traffic_capture_config,
         );
 
         NetworkBuilder {
@@ -151,6 +160,10 @@ impl NetworkBuilder {
             None,
             None,
             TCPBufferCfg::default(),
+            PriorityClassConfig::default_classes(),
+            MAX_OUTBOUND_QUEUED_MESSAGES,
+            true,
+            None,
         );
 
         builder.add_connectivity_manager(
@@ -209,6 +222,10 @@ impl NetworkBuilder {
                 config.outbound_rx_buffer_size_bytes,
                 config.outbound_tx_buffer_size_bytes,
             ),
+            config.outbound_priority_classes.clone(),
+            config.max_outbound_queued_messages,
+            config.interleave_outbound_streams,
+            config.traffic_capture.clone(),
         );
 
         network_builder.add_connection_monitoring(
@@ -268,14 +285,15 @@ impl NetworkBuilder {
         network_builder
     }
 
-    /// Create the configured Networking components.
-    pub fn build(&mut self, executor: Handle) -> &mut Self {
+    /// Create the configured Networking components. This fails if the traffic
+    /// capture can't be started.
+    pub fn build(&mut self, executor: Handle) -> Result<&mut Self, PeerManagerError> {
         assert_eq!(self.state, State::CREATED);
         self.state = State::BUILT;
         self.executor = Some(executor);
         self.peer_manager_builder
-            .build(self.executor.as_mut().expect("Executor must exist"));
-        self
+            .build(self.executor.as_mut().expect("Executor must exist"))?;
+        Ok(self)
     }
 
     /// Start the built Networking components.
@@ -408,6 +426,22 @@ impl NetworkBuilder {
                 Duration::from_secs(rest_discovery.interval_secs),
                 self.time_service.clone(),
             ),
+            DiscoveryMethod::Dns(dns_discovery) => DiscoveryChangeListener::dns(
+                self.network_context,
+                conn_mgr_reqs_tx,
+                dns_discovery,
+                Arc::new(SystemDnsResolver::default()),
+                self.time_service.clone(),
+            ),
+            DiscoveryMethod::Kubernetes(kubernetes_discovery) => {
+                DiscoveryChangeListener::kubernetes(
+                    self.network_context,
+                    conn_mgr_reqs_tx,
+                    kubernetes_discovery,
+                    Arc::new(SystemDnsResolver::default()),
+                    self.time_service.clone(),
+                )
+            },
             DiscoveryMethod::None => return,
         };
 
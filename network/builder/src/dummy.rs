@@ -108,7 +108,10 @@ pub fn setup_network() -> DummyNetwork {
 
     let (listener_sender, mut listener_events) =
         network_builder.add_client_and_service::<_, DummyNetworkEvents>(&dummy_network_config());
-    network_builder.build(runtime.handle().clone()).start();
+    network_builder
+        .build(runtime.handle().clone())
+        .unwrap()
+        .start();
     let listener_network_client = NetworkClient::new(
         vec![TEST_DIRECT_SEND_PROTOCOL],
         vec![TEST_RPC_PROTOCOL],
@@ -141,7 +144,10 @@ pub fn setup_network() -> DummyNetwork {
 
     let (dialer_sender, mut dialer_events) =
         network_builder.add_client_and_service::<_, DummyNetworkEvents>(&dummy_network_config());
-    network_builder.build(runtime.handle().clone()).start();
+    network_builder
+        .build(runtime.handle().clone())
+        .unwrap()
+        .start();
     let dialer_network_client = NetworkClient::new(
         vec![TEST_DIRECT_SEND_PROTOCOL],
         vec![TEST_RPC_PROTOCOL],
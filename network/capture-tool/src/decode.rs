@@ -0,0 +1,194 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_network::{
+    peer::{CaptureDirection, CapturedMessage},
+    protocols::{
+        health_checker::HealthCheckerMsg,
+        wire::{handshake::v1::ProtocolId, messaging::v1::NetworkMessage},
+    },
+};
+use aptos_peer_monitoring_service_types::PeerMonitoringServiceMessage;
+use aptos_storage_service_types::StorageServiceMessage;
+use aptos_types::PeerId;
+use serde::Serialize;
+use std::{collections::HashMap, fmt};
+
+/// The maximum number of payload bytes shown in text summaries
+const MAX_TEXT_PAYLOAD_BYTES: usize = 32;
+
+/// A human-readable view of a [`CapturedMessage`]
+#[derive(Clone, Debug, Serialize)]
+pub struct DecodedMessage {
+    pub timestamp_usecs: u64,
+    pub network_id: String,
+    pub remote_peer_id: PeerId,
+    pub direction: CaptureDirection,
+    /// The protocol of the message. For RPC responses, this is inferred from the
+    /// matching request (if it was captured).
+    pub protocol_id: Option<ProtocolId>,
+    pub message_type: &'static str,
+    pub request_id: Option<u32>,
+    pub data_len: usize,
+    pub payload: DecodedPayload,
+}
+
+/// The payload of a message, decoded as far as the protocol allows
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case", tag = "encoding", content = "value")]
+pub enum DecodedPayload {
+    /// No payload (e.g., error messages)
+    Empty,
+    /// A message of a known type, converted to JSON
+    Json(serde_json::Value),
+    /// The hex encoded (and decompressed) BCS bytes of a message of unknown type
+    Bcs(String),
+    /// The payload could not be decoded
+    Undecodable { error: String, raw: String },
+}
+
+impl fmt::Display for DecodedMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let protocol = self.protocol_id.map_or_else(
+            || "Unknown".to_string(),
+            |protocol_id| protocol_id.to_string(),
+        );
+        write!(
+            f,
+            "{} {} {:?} {} {} {}",
+            self.timestamp_usecs,
+            self.network_id,
+            self.direction,
+            self.remote_peer_id,
+            protocol,
+            self.message_type
+        )?;
+        if let Some(request_id) = self.request_id {
+            write!(f, " request_id={}", request_id)?;
+        }
+        write!(f, " len={}", self.data_len)?;
+        match &self.payload {
+            DecodedPayload::Empty => Ok(()),
+            DecodedPayload::Json(value) => write!(f, " {}", value),
+            DecodedPayload::Bcs(bytes) => write!(f, " bcs={}", truncate_hex(bytes)),
+            DecodedPayload::Undecodable { error, raw } => {
+                write!(f, " error={:?} raw={}", error, truncate_hex(raw))
+            },
+        }
+    }
+}
+
+fn truncate_hex(bytes: &str) -> String {
+    if bytes.len() > 2 * MAX_TEXT_PAYLOAD_BYTES {
+        format!("{}...", &bytes[..2 * MAX_TEXT_PAYLOAD_BYTES])
+    } else {
+        bytes.to_string()
+    }
+}
+
+/// Decodes captured messages. RPC responses don't carry their protocol on the
+/// wire, so the decoder remembers the protocols of the RPC requests it has seen.
+#[derive(Default)]
+pub struct Decoder {
+    /// The protocols of pending RPC requests, keyed by peer, the direction of the
+    /// request and the request id.
+    pending_requests: HashMap<(PeerId, CaptureDirection, u32), ProtocolId>,
+}
+
+impl Decoder {
+    pub fn decode(&mut self, captured_message: CapturedMessage) -> DecodedMessage {
+        let CapturedMessage {
+            timestamp_usecs,
+            network_id,
+            remote_peer_id,
+            direction,
+            mut protocol_id,
+            message,
+        } = captured_message;
+
+        let data_len = message.data_len();
+        let (message_type, request_id, payload) = match message {
+            NetworkMessage::Error(error_code) => (
+                "Error",
+                None,
+                DecodedPayload::Json(serde_json::to_value(error_code).unwrap_or_default()),
+            ),
+            NetworkMessage::RpcRequest(request) => {
+                self.pending_requests.insert(
+                    (remote_peer_id, direction, request.request_id),
+                    request.protocol_id,
+                );
+                (
+                    "RpcRequest",
+                    Some(request.request_id),
+                    decode_payload(request.protocol_id, &request.raw_request),
+                )
+            },
+            NetworkMessage::RpcResponse(response) => {
+                let request_direction = match direction {
+                    CaptureDirection::Inbound => CaptureDirection::Outbound,
+                    CaptureDirection::Outbound => CaptureDirection::Inbound,
+                };
+                protocol_id = protocol_id.or_else(|| {
+                    self.pending_requests.remove(&(
+                        remote_peer_id,
+                        request_direction,
+                        response.request_id,
+                    ))
+                });
+                let payload = match protocol_id {
+                    Some(protocol_id) => decode_payload(protocol_id, &response.raw_response),
+                    None => DecodedPayload::Bcs(hex::encode(&response.raw_response)),
+                };
+                ("RpcResponse", Some(response.request_id), payload)
+            },
+            NetworkMessage::DirectSendMsg(message) => (
+                "DirectSend",
+                None,
+                decode_payload(message.protocol_id, &message.raw_msg),
+            ),
+        };
+
+        DecodedMessage {
+            timestamp_usecs,
+            network_id: network_id.to_string(),
+            remote_peer_id,
+            direction,
+            protocol_id,
+            message_type,
+            request_id,
+            data_len,
+            payload,
+        }
+    }
+}
+
+/// Decodes the payload of a message into JSON for the protocols whose message
+/// types are known, and into raw BCS bytes otherwise.
+fn decode_payload(protocol_id: ProtocolId, bytes: &[u8]) -> DecodedPayload {
+    let result = match protocol_id {
+        ProtocolId::HealthCheckerRpc => to_json(protocol_id.from_bytes::<HealthCheckerMsg>(bytes)),
+        ProtocolId::PeerMonitoringServiceRpc => {
+            to_json(protocol_id.from_bytes::<PeerMonitoringServiceMessage>(bytes))
+        },
+        ProtocolId::StorageServiceRpc => {
+            to_json(protocol_id.from_bytes::<StorageServiceMessage>(bytes))
+        },
+        _ => match protocol_id.bcs_payload(bytes) {
+            Ok(Some(bcs_bytes)) => Ok(DecodedPayload::Bcs(hex::encode(bcs_bytes))),
+            Ok(None) => protocol_id
+                .from_bytes::<serde_json::Value>(bytes)
+                .map(DecodedPayload::Json),
+            Err(error) => Err(error),
+        },
+    };
+    result.unwrap_or_else(|error| DecodedPayload::Undecodable {
+        error: error.to_string(),
+        raw: hex::encode(bytes),
+    })
+}
+
+fn to_json<T: Serialize>(message: Result<T>) -> Result<DecodedPayload> {
+    Ok(DecodedPayload::Json(serde_json::to_value(message?)?))
+}
@@ -0,0 +1,248 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tooling for the network traffic captures recorded by nodes with a
+//! `traffic_capture` config (see [`aptos_network::peer::TrafficCapture`]).
+//!
+//! Captures can be decoded into human-readable text or JSON, and replayed against
+//! a node under test (see [`replay()`]).
+
+#![forbid(unsafe_code)]
+
+mod decode;
+mod replay;
+#[cfg(test)]
+mod tests;
+
+pub use decode::{DecodedMessage, DecodedPayload, Decoder};
+pub use replay::{replay, ReplayOptions};
+
+use anyhow::{Context, Result};
+use aptos_config::{
+    config::{RoleType, HANDSHAKE_VERSION},
+    network_id::{NetworkContext, NetworkId},
+};
+use aptos_crypto::{x25519, Uniform};
+use aptos_network::{
+    noise::HandshakeAuthMode,
+    peer::{CaptureDirection, CaptureFileReader, CapturedMessage, CAPTURE_FILE_EXTENSION},
+    protocols::wire::handshake::v1::{ProtocolId, ProtocolIdSet},
+    transport::{AptosNetTransport, APTOS_TCP_TRANSPORT},
+};
+use aptos_time_service::TimeService;
+use aptos_types::{
+    account_address::from_identity_public_key, chain_id::ChainId, network_address::NetworkAddress,
+    PeerId,
+};
+use clap::{ArgEnum, Parser};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputFormat {
+    /// A one-line summary of each message
+    Text,
+    /// One JSON object per message
+    Json,
+}
+
+/// Decodes network traffic capture files
+#[derive(Debug, Parser)]
+pub struct DecodeArgs {
+    /// Capture files, or directories of capture files, in the order to decode them
+    #[clap(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// Only decode the messages exchanged with this peer
+    #[clap(long)]
+    pub peer_id: Option<PeerId>,
+    /// Only decode the messages of this protocol (e.g., "ConsensusRpcBcs")
+    #[clap(long)]
+    pub protocol: Option<String>,
+    #[clap(arg_enum, long, default_value = "text")]
+    pub format: OutputFormat,
+}
+
+impl DecodeArgs {
+    /// Decodes the capture files, and writes the decoded messages to `output`
+    pub fn execute(&self, output: &mut impl Write) -> Result<()> {
+        let protocol_id = self
+            .protocol
+            .as_ref()
+            .map(|protocol_name| {
+                ProtocolId::all()
+                    .iter()
+                    .find(|protocol_id| protocol_id.as_str() == protocol_name)
+                    .copied()
+                    .with_context(|| format!("Unknown protocol: {}", protocol_name))
+            })
+            .transpose()?;
+
+        let mut decoder = Decoder::default();
+        for message in read_captures(&self.paths)? {
+            // Decode every message, so that the protocols of RPC responses can be
+            // inferred from their requests.
+            let message = decoder.decode(message?);
+            if self
+                .peer_id
+                .map_or(false, |peer_id| peer_id != message.remote_peer_id)
+                || protocol_id.map_or(false, |protocol_id| {
+                    Some(protocol_id) != message.protocol_id
+                })
+            {
+                continue;
+            }
+
+            match self.format {
+                OutputFormat::Text => writeln!(output, "{}", message)?,
+                OutputFormat::Json => writeln!(output, "{}", serde_json::to_string(&message)?)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// The messages received by the capturing node
+    Inbound,
+    /// The messages sent by the capturing node
+    Outbound,
+}
+
+impl From<Direction> for CaptureDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Inbound => CaptureDirection::Inbound,
+            Direction::Outbound => CaptureDirection::Outbound,
+        }
+    }
+}
+
+/// Replays network traffic capture files against a node under test
+#[derive(Debug, Parser)]
+pub struct ReplayArgs {
+    /// Capture files, or directories of capture files, in the order to replay them
+    #[clap(required = true)]
+    pub paths: Vec<PathBuf>,
+    /// The full network address of the node under test, including its noise key
+    /// (e.g., "/ip4/127.0.0.1/tcp/6182/noise-ik/<pubkey>/handshake/0")
+    #[clap(long)]
+    pub address: NetworkAddress,
+    /// The peer id of the node under test. Defaults to the peer id derived from the
+    /// noise key of its address.
+    #[clap(long)]
+    pub target_peer_id: Option<PeerId>,
+    /// The network of the node under test to connect to. The node must accept
+    /// connections from unknown peers on it (e.g., the public network).
+    #[clap(long, default_value = "public")]
+    pub network_id: NetworkId,
+    /// The chain id of the node under test
+    #[clap(long)]
+    pub chain_id: ChainId,
+    /// Only replay the messages exchanged with this peer
+    #[clap(long)]
+    pub peer_id: Option<PeerId>,
+    #[clap(arg_enum, long, default_value = "inbound")]
+    pub direction: Direction,
+    /// The replay speed, relative to the capture (e.g., 2.0 replays twice as fast).
+    /// If not set, messages are replayed as fast as possible.
+    #[clap(long)]
+    pub speed: Option<f64>,
+}
+
+impl ReplayArgs {
+    /// Connects to the node under test with a fresh identity, and replays the
+    /// capture files on the connection. Returns the number of messages replayed.
+    pub async fn execute(&self) -> Result<usize> {
+        let messages = read_captures(&self.paths)?.collect::<Result<Vec<_>>>()?;
+
+        let identity_key = x25519::PrivateKey::generate(&mut rand::rngs::OsRng);
+        let peer_id = from_identity_public_key(identity_key.public_key());
+        let transport = AptosNetTransport::new(
+            APTOS_TCP_TRANSPORT.clone(),
+            NetworkContext::new(RoleType::FullNode, self.network_id, peer_id),
+            TimeService::real(),
+            identity_key,
+            HandshakeAuthMode::server_only(&[self.network_id]),
+            HANDSHAKE_VERSION,
+            self.chain_id,
+            ProtocolIdSet::all_known(),
+            false,
+        );
+
+        let target_peer_id = match self.target_peer_id {
+            Some(target_peer_id) => target_peer_id,
+            None => from_identity_public_key(
+                self.address
+                    .find_noise_proto()
+                    .with_context(|| format!("Missing noise key in address: {}", self.address))?,
+            ),
+        };
+        let connection = transport
+            .dial(target_peer_id, self.address.clone())?
+            .await
+            .with_context(|| format!("Failed to connect to {}", self.address))?;
+
+        let options = ReplayOptions {
+            remote_peer_id: self.peer_id,
+            direction: self.direction.into(),
+            speed: self.speed,
+            ..ReplayOptions::default()
+        };
+        replay(connection.socket, messages, &options).await
+    }
+}
+
+/// Returns the capture files in the given paths. Directories are expanded into the
+/// capture files they contain, sorted by name (i.e., by network and capture index).
+pub fn capture_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+
+        let mut directory_files = vec![];
+        for entry in std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read directory: {:?}", path))?
+        {
+            let file = entry?.path();
+            if file
+                .extension()
+                .map_or(false, |extension| extension == CAPTURE_FILE_EXTENSION)
+            {
+                directory_files.push(file);
+            }
+        }
+        directory_files.sort();
+        files.extend(directory_files);
+    }
+    Ok(files)
+}
+
+/// Reads all messages of the capture files in the given paths, in order
+pub fn read_captures(paths: &[PathBuf]) -> Result<impl Iterator<Item = Result<CapturedMessage>>> {
+    let readers = capture_files(paths)?
+        .into_iter()
+        .map(|file| open_capture(&file))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(readers.into_iter().flat_map(|(file, reader)| {
+        reader.map(move |message| {
+            message.with_context(|| format!("Failed to read capture file: {:?}", file))
+        })
+    }))
+}
+
+fn open_capture(
+    file: &Path,
+) -> Result<(
+    PathBuf,
+    CaptureFileReader<std::io::BufReader<std::fs::File>>,
+)> {
+    let reader = CaptureFileReader::open(file)
+        .with_context(|| format!("Failed to open capture file: {:?}", file))?;
+    Ok((file.to_path_buf(), reader))
+}
@@ -0,0 +1,39 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use anyhow::Result;
+use aptos_network_capture_tool::{DecodeArgs, ReplayArgs};
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "aptos-network-capture",
+    about = "Inspects and replays network traffic captures"
+)]
+struct Args {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Decodes capture files into human-readable text or JSON
+    Decode(DecodeArgs),
+    /// Replays capture files against a node under test
+    Replay(ReplayArgs),
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    match args.command {
+        Command::Decode(decode_args) => decode_args.execute(&mut std::io::stdout().lock()),
+        Command::Replay(replay_args) => {
+            let runtime = tokio::runtime::Runtime::new()?;
+            let num_replayed = runtime.block_on(replay_args.execute())?;
+            println!("Replayed {} messages", num_replayed);
+            Ok(())
+        },
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Result};
+use aptos_network::{
+    constants::{MAX_FRAME_SIZE, MAX_MESSAGE_SIZE},
+    peer::{CaptureDirection, CapturedMessage},
+    protocols::{
+        stream::OutboundStream,
+        wire::messaging::v1::{MultiplexMessage, MultiplexMessageSink},
+    },
+};
+use aptos_types::PeerId;
+use futures::{io::AsyncWrite, SinkExt};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Selects the captured messages to replay, and how fast to replay them
+#[derive(Clone, Debug)]
+pub struct ReplayOptions {
+    /// Only replay the messages exchanged with this peer. If `None`, the messages
+    /// of all peers are replayed.
+    pub remote_peer_id: Option<PeerId>,
+    /// Only replay the messages with this direction. Replaying the inbound messages
+    /// of a capture sends the node under test what the capturing node received.
+    pub direction: CaptureDirection,
+    /// The replay speed, relative to the capture (e.g., 2.0 replays twice as fast).
+    /// If `None`, messages are replayed as fast as possible.
+    pub speed: Option<f64>,
+    pub max_frame_size: usize,
+    pub max_message_size: usize,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            remote_peer_id: None,
+            direction: CaptureDirection::Inbound,
+            speed: None,
+            max_frame_size: MAX_FRAME_SIZE,
+            max_message_size: MAX_MESSAGE_SIZE,
+        }
+    }
+}
+
+/// Replays captured messages on an established connection to a node under test,
+/// e.g., one end of a `MemorySocket` pair whose other end is handed to a `Peer`.
+/// Messages larger than a frame are streamed, as the node would have received them.
+/// The connection is closed once all messages are replayed, and the number of
+/// messages replayed is returned.
+pub async fn replay<TSocket: AsyncWrite + Unpin>(
+    socket: TSocket,
+    messages: impl IntoIterator<Item = CapturedMessage>,
+    options: &ReplayOptions,
+) -> Result<usize> {
+    if let Some(speed) = options.speed {
+        if !(speed > 0.0 && speed.is_finite()) {
+            return Err(anyhow!("Invalid replay speed: {}", speed));
+        }
+    }
+
+    let mut sink = MultiplexMessageSink::new(socket, options.max_frame_size, None);
+    let mut outbound_stream = OutboundStream::new(options.max_frame_size, options.max_message_size);
+    let start_time = Instant::now();
+    let mut first_timestamp_usecs = None;
+    let mut num_replayed = 0;
+
+    let messages = messages.into_iter().filter(|message| {
+        message.direction == options.direction
+            && options
+                .remote_peer_id
+                .map_or(true, |peer_id| peer_id == message.remote_peer_id)
+    });
+    for captured_message in messages {
+        // Keep the original spacing between messages, adjusted by the speed
+        if let Some(speed) = options.speed {
+            let first_timestamp_usecs =
+                *first_timestamp_usecs.get_or_insert(captured_message.timestamp_usecs);
+            let offset_usecs = captured_message
+                .timestamp_usecs
+                .saturating_sub(first_timestamp_usecs);
+            let offset = Duration::from_micros(offset_usecs).div_f64(speed);
+            tokio::time::sleep_until(start_time + offset).await;
+        }
+
+        let message = captured_message.message;
+        if outbound_stream.should_stream(&message) {
+            for stream_message in outbound_stream.stream_message(message)? {
+                sink.send(&MultiplexMessage::Stream(stream_message)).await?;
+            }
+        } else {
+            sink.send(&MultiplexMessage::Message(message)).await?;
+        }
+        num_replayed += 1;
+    }
+
+    sink.close().await?;
+    Ok(num_replayed)
+}
@@ -0,0 +1,201 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{replay, DecodedPayload, Decoder, ReplayOptions};
+use aptos_config::network_id::NetworkId;
+use aptos_memsocket::MemorySocket;
+use aptos_network::{
+    peer::{CaptureDirection, CapturedMessage},
+    protocols::{
+        stream::{InboundStreamBuffer, StreamMessage},
+        wire::{
+            handshake::v1::ProtocolId,
+            messaging::v1::{
+                DirectSendMsg, MultiplexMessage, MultiplexMessageStream, NetworkMessage,
+                RpcRequest, RpcResponse,
+            },
+        },
+    },
+};
+use aptos_peer_monitoring_service_types::{
+    PeerMonitoringServiceMessage, PeerMonitoringServiceRequest,
+};
+use aptos_types::PeerId;
+use futures::StreamExt;
+
+fn captured_message(
+    timestamp_usecs: u64,
+    remote_peer_id: PeerId,
+    direction: CaptureDirection,
+    message: NetworkMessage,
+) -> CapturedMessage {
+    CapturedMessage {
+        timestamp_usecs,
+        network_id: NetworkId::Validator,
+        remote_peer_id,
+        direction,
+        protocol_id: message.protocol_id(),
+        message,
+    }
+}
+
+fn direct_send(raw_msg: Vec<u8>) -> NetworkMessage {
+    NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: ProtocolId::ConsensusDirectSendBcs,
+        priority: 0,
+        raw_msg,
+    })
+}
+
+#[test]
+fn test_decode_rpc() {
+    let peer_id = PeerId::random();
+    let protocol_id = ProtocolId::PeerMonitoringServiceRpc;
+    let request = PeerMonitoringServiceMessage::Request(
+        PeerMonitoringServiceRequest::GetServerProtocolVersion,
+    );
+    let request = captured_message(
+        1,
+        peer_id,
+        CaptureDirection::Outbound,
+        NetworkMessage::RpcRequest(RpcRequest {
+            protocol_id,
+            request_id: 7,
+            priority: 0,
+            raw_request: protocol_id.to_bytes(&request).unwrap(),
+        }),
+    );
+    let response = captured_message(
+        2,
+        peer_id,
+        CaptureDirection::Inbound,
+        NetworkMessage::RpcResponse(RpcResponse {
+            request_id: 7,
+            priority: 0,
+            raw_response: vec![1, 2, 3],
+        }),
+    );
+
+    let mut decoder = Decoder::default();
+    let decoded_request = decoder.decode(request);
+    assert_eq!(decoded_request.protocol_id, Some(protocol_id));
+    assert_eq!(decoded_request.request_id, Some(7));
+    match decoded_request.payload {
+        DecodedPayload::Json(value) => {
+            assert_eq!(
+                value,
+                serde_json::json!({"Request": "GetServerProtocolVersion"})
+            )
+        },
+        payload => panic!("Unexpected payload: {:?}", payload),
+    }
+
+    // The protocol of the response should be inferred from the request, even if
+    // the response can't be decoded
+    let decoded_response = decoder.decode(response.clone());
+    assert_eq!(decoded_response.protocol_id, Some(protocol_id));
+    assert!(matches!(
+        decoded_response.payload,
+        DecodedPayload::Undecodable { .. }
+    ));
+    assert!(decoded_response
+        .to_string()
+        .contains("PeerMonitoringServiceRpc"));
+
+    // Unmatched responses keep their raw bytes
+    let decoded_response = decoder.decode(response);
+    assert_eq!(decoded_response.protocol_id, None);
+    match decoded_response.payload {
+        DecodedPayload::Bcs(bytes) => assert_eq!(bytes, "010203"),
+        payload => panic!("Unexpected payload: {:?}", payload),
+    }
+}
+
+#[tokio::test]
+async fn test_replay() {
+    let max_frame_size = 1024;
+    let max_message_size = 16 * 1024;
+    let peer_id = PeerId::random();
+    let other_peer_id = PeerId::random();
+    let messages = vec![
+        captured_message(
+            0,
+            peer_id,
+            CaptureDirection::Inbound,
+            direct_send(vec![0; 8]),
+        ),
+        captured_message(
+            1,
+            peer_id,
+            CaptureDirection::Outbound,
+            direct_send(vec![1; 8]),
+        ),
+        captured_message(
+            2,
+            other_peer_id,
+            CaptureDirection::Inbound,
+            direct_send(vec![2]),
+        ),
+        // A message larger than a frame should be streamed
+        captured_message(
+            3,
+            peer_id,
+            CaptureDirection::Inbound,
+            direct_send(vec![3; 4096]),
+        ),
+        captured_message(
+            4,
+            peer_id,
+            CaptureDirection::Inbound,
+            direct_send(vec![4; 8]),
+        ),
+    ];
+    let expected_messages: Vec<_> = [0, 3, 4]
+        .iter()
+        .map(|index: &usize| messages[*index].message.clone())
+        .collect();
+
+    let (replay_socket, node_socket) = MemorySocket::new_pair();
+    let options = ReplayOptions {
+        remote_peer_id: Some(peer_id),
+        speed: Some(1000.0),
+        max_frame_size,
+        max_message_size,
+        ..ReplayOptions::default()
+    };
+    let replay_messages = replay(replay_socket, messages, &options);
+
+    // Read the replayed messages, reassembling streamed messages
+    let receive = async move {
+        let mut node_stream = MultiplexMessageStream::new(node_socket, max_frame_size, None);
+        let mut inbound_stream = InboundStreamBuffer::new(max_message_size / max_frame_size, 1);
+        let mut received_messages = vec![];
+        while let Some(message) = node_stream.next().await {
+            match message.unwrap() {
+                MultiplexMessage::Message(message) => received_messages.push(message),
+                MultiplexMessage::Stream(StreamMessage::Header(header)) => {
+                    inbound_stream.new_stream(header).unwrap()
+                },
+                MultiplexMessage::Stream(StreamMessage::Fragment(fragment)) => {
+                    if let Some(message) = inbound_stream.append_fragment(fragment).unwrap() {
+                        received_messages.push(message);
+                    }
+                },
+            }
+        }
+        received_messages
+    };
+    let (num_replayed, received_messages) = futures::join!(replay_messages, receive);
+    assert_eq!(num_replayed.unwrap(), 3);
+    assert_eq!(received_messages, expected_messages);
+}
+
+#[tokio::test]
+async fn test_replay_invalid_speed() {
+    let (replay_socket, _node_socket) = MemorySocket::new_pair();
+    let options = ReplayOptions {
+        speed: Some(0.0),
+        ..ReplayOptions::default()
+    };
+    assert!(replay(replay_socket, vec![], &options).await.is_err());
+}
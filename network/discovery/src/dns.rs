@@ -0,0 +1,585 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{counters::DISCOVERY_COUNTS, DiscoveryError};
+use aptos_config::{
+    config::{
+        DnsDiscovery, DnsPeer, DnsRecord, KubernetesDiscovery, Peer, PeerSet, HANDSHAKE_VERSION,
+    },
+    network_id::NetworkContext,
+};
+use aptos_logger::prelude::*;
+use aptos_network::{counters::inc_by_with_context, logging::NetworkSchema};
+use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
+use aptos_types::{network_address::NetworkAddress, PeerId};
+use async_trait::async_trait;
+use futures::{future::BoxFuture, Future, Stream};
+use once_cell::sync::OnceCell;
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use trust_dns_resolver::{error::ResolveError, TokioAsyncResolver};
+
+/// A single SRV record, i.e., a host and port providing a service
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SrvRecord {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+}
+
+/// Resolves DNS records into socket addresses. This allows tests to replace
+/// the system resolver with a local stub.
+#[async_trait]
+pub trait DnsResolver: Send + Sync {
+    /// Resolves the A/AAAA records of the given host
+    async fn lookup_host(&self, name: &str, port: u16) -> Result<Vec<SocketAddr>, DiscoveryError>;
+
+    /// Resolves the SRV records of the given name
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DiscoveryError>;
+}
+
+/// Resolves records with the nameservers (and options) of the system configuration,
+/// i.e., `/etc/resolv.conf` on Unix. The resolver is created on first use, so that
+/// configuration errors are reported (and retried) as resolution failures.
+#[derive(Default)]
+pub struct SystemDnsResolver {
+    resolver: OnceCell<TokioAsyncResolver>,
+}
+
+impl SystemDnsResolver {
+    fn resolver(&self) -> Result<&TokioAsyncResolver, DiscoveryError> {
+        self.resolver
+            .get_or_try_init(TokioAsyncResolver::tokio_from_system_conf)
+            .map_err(dns_error)
+    }
+}
+
+#[async_trait]
+impl DnsResolver for SystemDnsResolver {
+    async fn lookup_host(&self, name: &str, port: u16) -> Result<Vec<SocketAddr>, DiscoveryError> {
+        let lookup = self.resolver()?.lookup_ip(name).await.map_err(dns_error)?;
+        Ok(lookup.iter().map(|ip| SocketAddr::new(ip, port)).collect())
+    }
+
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DiscoveryError> {
+        let lookup = self.resolver()?.srv_lookup(name).await.map_err(dns_error)?;
+        Ok(lookup
+            .iter()
+            .map(|srv| SrvRecord {
+                priority: srv.priority(),
+                weight: srv.weight(),
+                port: srv.port(),
+                target: srv.target().to_utf8(),
+            })
+            .collect())
+    }
+}
+
+fn dns_error(error: ResolveError) -> DiscoveryError {
+    DiscoveryError::Dns(error.to_string())
+}
+
+/// The result of resolving the record of each peer, in the order of the peers
+type Resolutions = Vec<Result<Vec<SocketAddr>, DiscoveryError>>;
+
+/// A discovery stream that periodically resolves the DNS records of a set of
+/// peers with known identities.
+pub struct DnsStream {
+    network_context: NetworkContext,
+    peers: Arc<Vec<DnsPeer>>,
+    resolver: Arc<dyn DnsResolver>,
+    interval: Pin<Box<Interval>>,
+    /// The resolution in progress, if any
+    pending_resolution: Option<BoxFuture<'static, Resolutions>>,
+    /// The last successfully resolved addresses of each peer, which are kept
+    /// whenever a resolution fails.
+    resolved_addresses: HashMap<PeerId, Vec<NetworkAddress>>,
+}
+
+impl DnsStream {
+    pub(crate) fn new(
+        network_context: NetworkContext,
+        dns_discovery: &DnsDiscovery,
+        resolver: Arc<dyn DnsResolver>,
+        time_service: TimeService,
+    ) -> Self {
+        DnsStream {
+            network_context,
+            peers: Arc::new(dns_discovery.peers.clone()),
+            resolver,
+            interval: Box::pin(
+                time_service.interval(Duration::from_secs(dns_discovery.interval_secs)),
+            ),
+            pending_resolution: None,
+            resolved_addresses: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn kubernetes(
+        network_context: NetworkContext,
+        kubernetes_discovery: &KubernetesDiscovery,
+        resolver: Arc<dyn DnsResolver>,
+        time_service: TimeService,
+    ) -> Self {
+        let dns_discovery = DnsDiscovery {
+            peers: kubernetes_peers(kubernetes_discovery),
+            interval_secs: kubernetes_discovery.interval_secs,
+        };
+        Self::new(network_context, &dns_discovery, resolver, time_service)
+    }
+
+    /// Updates the addresses of all peers with the given resolutions. Peers that
+    /// failed to resolve keep their previously resolved addresses.
+    fn update_peers(&mut self, resolutions: Resolutions) -> Result<PeerSet, DiscoveryError> {
+        let mut peer_set = PeerSet::new();
+        let mut last_error = None;
+        for (dns_peer, resolution) in self.peers.iter().zip(resolutions) {
+            match resolution {
+                Ok(socket_addrs) => {
+                    let addresses = socket_addrs
+                        .into_iter()
+                        .map(|socket_addr| {
+                            NetworkAddress::from(socket_addr)
+                                .append_prod_protos(dns_peer.public_key, HANDSHAKE_VERSION)
+                        })
+                        .collect();
+                    self.resolved_addresses.insert(dns_peer.peer_id, addresses);
+                },
+                Err(error) => {
+                    inc_by_with_context(
+                        &DISCOVERY_COUNTS,
+                        &self.network_context,
+                        "dns_resolution_failure",
+                        1,
+                    );
+                    warn!(
+                        NetworkSchema::new(&self.network_context),
+                        "{} Failed to resolve {:?} for peer {}: {:?}",
+                        self.network_context,
+                        dns_peer.record,
+                        dns_peer.peer_id,
+                        error
+                    );
+                    last_error = Some(error);
+                },
+            }
+
+            if let Some(addresses) = self.resolved_addresses.get(&dns_peer.peer_id) {
+                let keys: HashSet<_> = [dns_peer.public_key].into_iter().collect();
+                peer_set.insert(
+                    dns_peer.peer_id,
+                    Peer::new(addresses.clone(), keys, dns_peer.role),
+                );
+            }
+        }
+
+        // Only fail if none of the peers could ever be resolved
+        match last_error {
+            Some(error) if peer_set.is_empty() && !self.peers.is_empty() => Err(error),
+            _ => Ok(peer_set),
+        }
+    }
+}
+
+impl Stream for DnsStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Wait for delay, or add the delay for next call, then start resolving
+        // the records
+        if self.pending_resolution.is_none() {
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            let peers = self.peers.clone();
+            let resolver = self.resolver.clone();
+            self.pending_resolution = Some(Box::pin(async move {
+                resolve_peers(resolver.as_ref(), &peers).await
+            }));
+        }
+
+        let resolutions =
+            futures::ready!(self.pending_resolution.as_mut().unwrap().as_mut().poll(cx));
+        self.pending_resolution = None;
+        Poll::Ready(Some(self.update_peers(resolutions)))
+    }
+}
+
+/// Resolves the records of all peers concurrently
+async fn resolve_peers(resolver: &dyn DnsResolver, peers: &[DnsPeer]) -> Resolutions {
+    futures::future::join_all(
+        peers
+            .iter()
+            .map(|dns_peer| resolve_addresses(resolver, &dns_peer.record)),
+    )
+    .await
+}
+
+/// Returns the peers of the given StatefulSet, addressed by their pod DNS names
+fn kubernetes_peers(kubernetes_discovery: &KubernetesDiscovery) -> Vec<DnsPeer> {
+    kubernetes_discovery
+        .peers
+        .iter()
+        .enumerate()
+        .map(|(ordinal, peer)| DnsPeer {
+            peer_id: peer.peer_id,
+            public_key: peer.public_key,
+            role: kubernetes_discovery.role,
+            record: DnsRecord::Host {
+                name: kubernetes_discovery.pod_dns_name(ordinal),
+                port: kubernetes_discovery.port,
+            },
+        })
+        .collect()
+}
+
+/// Resolves the given record into socket addresses, ordered by SRV priority
+async fn resolve_addresses(
+    resolver: &dyn DnsResolver,
+    record: &DnsRecord,
+) -> Result<Vec<SocketAddr>, DiscoveryError> {
+    let mut socket_addrs = match record {
+        DnsRecord::Host { name, port } => resolver.lookup_host(name, *port).await?,
+        DnsRecord::Srv { name } => {
+            let mut srv_records = resolver.lookup_srv(name).await?;
+            srv_records.sort_by_key(|srv_record| srv_record.priority);
+
+            // Targets that fail to resolve are skipped, as long as another one resolves
+            let mut socket_addrs = vec![];
+            let mut last_error = None;
+            for srv_record in srv_records {
+                match resolver
+                    .lookup_host(&srv_record.target, srv_record.port)
+                    .await
+                {
+                    Ok(target_addrs) => socket_addrs.extend(target_addrs),
+                    Err(error) => {
+                        warn!(
+                            "Failed to resolve the SRV target {}:{} of {}: {:?}",
+                            srv_record.target, srv_record.port, name, error
+                        );
+                        last_error = Some(error);
+                    },
+                }
+            }
+            match last_error {
+                Some(error) if socket_addrs.is_empty() => return Err(error),
+                _ => socket_addrs,
+            }
+        },
+    };
+
+    // Remove duplicates, while keeping the resolution order
+    let mut seen = HashSet::new();
+    socket_addrs.retain(|socket_addr| seen.insert(*socket_addr));
+    if socket_addrs.is_empty() {
+        return Err(DiscoveryError::Dns(format!(
+            "No addresses found for {:?}",
+            record
+        )));
+    }
+    Ok(socket_addrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscoveryChangeListener;
+    use aptos_config::config::{KubernetesPeer, PeerRole};
+    use aptos_crypto::{x25519, Uniform};
+    use aptos_network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+    use futures::StreamExt;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{net::IpAddr, sync::Mutex};
+
+    /// A resolver with fixed records
+    #[derive(Default)]
+    struct StubResolver {
+        hosts: Mutex<HashMap<String, Vec<IpAddr>>>,
+        srv_records: HashMap<String, Vec<SrvRecord>>,
+    }
+
+    impl StubResolver {
+        fn set_host(&self, name: &str, ips: Vec<IpAddr>) {
+            self.hosts.lock().unwrap().insert(name.into(), ips);
+        }
+
+        fn remove_host(&self, name: &str) {
+            self.hosts.lock().unwrap().remove(name);
+        }
+    }
+
+    #[async_trait]
+    impl DnsResolver for StubResolver {
+        async fn lookup_host(
+            &self,
+            name: &str,
+            port: u16,
+        ) -> Result<Vec<SocketAddr>, DiscoveryError> {
+            self.hosts
+                .lock()
+                .unwrap()
+                .get(name)
+                .map(|ips| ips.iter().map(|ip| SocketAddr::new(*ip, port)).collect())
+                .ok_or_else(|| DiscoveryError::Dns(format!("Unknown host: {}", name)))
+        }
+
+        async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DiscoveryError> {
+            self.srv_records
+                .get(name)
+                .cloned()
+                .ok_or_else(|| DiscoveryError::Dns(format!("Unknown SRV name: {}", name)))
+        }
+    }
+
+    fn public_key(rng: &mut StdRng) -> x25519::PublicKey {
+        x25519::PrivateKey::generate(rng).public_key()
+    }
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    fn address(ip: &str, port: u16, public_key: x25519::PublicKey) -> NetworkAddress {
+        NetworkAddress::from(SocketAddr::new(self::ip(ip), port))
+            .append_prod_protos(public_key, HANDSHAKE_VERSION)
+    }
+
+    #[tokio::test]
+    async fn test_dns_listener() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let host_peer = DnsPeer {
+            peer_id: PeerId::random(),
+            public_key: public_key(&mut rng),
+            role: PeerRole::Upstream,
+            record: DnsRecord::Host {
+                name: "fullnode.example.com".into(),
+                port: 6182,
+            },
+        };
+        let srv_peer = DnsPeer {
+            peer_id: PeerId::random(),
+            public_key: public_key(&mut rng),
+            role: PeerRole::Upstream,
+            record: DnsRecord::Srv {
+                name: "_aptos._tcp.example.com".into(),
+            },
+        };
+
+        let mut resolver = StubResolver::default();
+        resolver
+            .srv_records
+            .insert("_aptos._tcp.example.com".into(), vec![
+                SrvRecord {
+                    priority: 20,
+                    weight: 0,
+                    port: 6183,
+                    target: "backup.example.com".into(),
+                },
+                SrvRecord {
+                    priority: 10,
+                    weight: 0,
+                    port: 6182,
+                    target: "primary.example.com".into(),
+                },
+            ]);
+        resolver.set_host("fullnode.example.com", vec![ip("10.0.0.1"), ip("10.0.0.2")]);
+        resolver.set_host("primary.example.com", vec![ip("10.0.1.1")]);
+        resolver.set_host("backup.example.com", vec![ip("10.0.1.2")]);
+        let resolver = Arc::new(resolver);
+
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) = aptos_channels::new(
+            1,
+            &aptos_network::counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS,
+        );
+        let listener = DiscoveryChangeListener::dns(
+            NetworkContext::mock(),
+            conn_mgr_reqs_tx,
+            &DnsDiscovery {
+                peers: vec![host_peer.clone(), srv_peer.clone()],
+                interval_secs: 1,
+            },
+            resolver.clone(),
+            TimeService::real(),
+        );
+        tokio::spawn(Box::pin(listener).run());
+
+        // All records should be resolved, with the SRV targets ordered by priority
+        let mut expected_peers = PeerSet::new();
+        expected_peers.insert(
+            host_peer.peer_id,
+            Peer::new(
+                vec![
+                    address("10.0.0.1", 6182, host_peer.public_key),
+                    address("10.0.0.2", 6182, host_peer.public_key),
+                ],
+                HashSet::new(),
+                PeerRole::Upstream,
+            ),
+        );
+        expected_peers.insert(
+            srv_peer.peer_id,
+            Peer::new(
+                vec![
+                    address("10.0.1.1", 6182, srv_peer.public_key),
+                    address("10.0.1.2", 6183, srv_peer.public_key),
+                ],
+                HashSet::new(),
+                PeerRole::Upstream,
+            ),
+        );
+        match conn_mgr_reqs_rx.next().await {
+            Some(ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::Dns, peers)) => {
+                assert_eq!(peers, expected_peers)
+            },
+            request => panic!("Unexpected request: {:?}", request),
+        }
+
+        // Rotate the addresses of the host
+        resolver.set_host("fullnode.example.com", vec![ip("10.0.0.3")]);
+        expected_peers.insert(
+            host_peer.peer_id,
+            Peer::new(
+                vec![address("10.0.0.3", 6182, host_peer.public_key)],
+                HashSet::new(),
+                PeerRole::Upstream,
+            ),
+        );
+        loop {
+            match conn_mgr_reqs_rx.next().await {
+                Some(ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::Dns, peers)) => {
+                    if peers == expected_peers {
+                        break;
+                    }
+                },
+                request => panic!("Unexpected request: {:?}", request),
+            }
+        }
+
+        // A failed resolution should keep the last known addresses
+        resolver.remove_host("fullnode.example.com");
+        match conn_mgr_reqs_rx.next().await {
+            Some(ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::Dns, peers)) => {
+                assert_eq!(peers, expected_peers)
+            },
+            request => panic!("Unexpected request: {:?}", request),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kubernetes_peers() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let peers: Vec<_> = (0..2)
+            .map(|_| KubernetesPeer {
+                peer_id: PeerId::random(),
+                public_key: public_key(&mut rng),
+            })
+            .collect();
+        let kubernetes_discovery = KubernetesDiscovery {
+            stateful_set: "fullnode".into(),
+            service: "fullnode-headless".into(),
+            namespace: "aptos".into(),
+            cluster_domain: "cluster.local".into(),
+            port: 6182,
+            role: PeerRole::Upstream,
+            peers: peers.clone(),
+            interval_secs: 10,
+        };
+
+        let resolver = StubResolver::default();
+        resolver.set_host(
+            "fullnode-0.fullnode-headless.aptos.svc.cluster.local",
+            vec![ip("10.1.0.1")],
+        );
+        resolver.set_host(
+            "fullnode-1.fullnode-headless.aptos.svc.cluster.local",
+            vec![ip("10.1.0.2")],
+        );
+        let mut stream = DnsStream::kubernetes(
+            NetworkContext::mock(),
+            &kubernetes_discovery,
+            Arc::new(resolver),
+            TimeService::mock(),
+        );
+
+        let resolutions = resolve_peers(stream.resolver.as_ref(), &stream.peers).await;
+        let peer_set = stream.update_peers(resolutions).unwrap();
+        assert_eq!(peer_set.len(), 2);
+        for (ordinal, ip) in ["10.1.0.1", "10.1.0.2"].iter().enumerate() {
+            let peer = peer_set.get(&peers[ordinal].peer_id).unwrap();
+            assert_eq!(peer.addresses, vec![address(
+                ip,
+                6182,
+                peers[ordinal].public_key
+            )]);
+            assert_eq!(peer.role, PeerRole::Upstream);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolution_failure() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let dns_discovery = DnsDiscovery {
+            peers: vec![DnsPeer {
+                peer_id: PeerId::random(),
+                public_key: public_key(&mut rng),
+                role: PeerRole::Upstream,
+                record: DnsRecord::Srv {
+                    name: "_aptos._tcp.example.com".into(),
+                },
+            }],
+            interval_secs: 10,
+        };
+        let mut stream = DnsStream::new(
+            NetworkContext::mock(),
+            &dns_discovery,
+            Arc::new(StubResolver::default()),
+            TimeService::mock(),
+        );
+        let resolutions = resolve_peers(stream.resolver.as_ref(), &stream.peers).await;
+        assert!(stream.update_peers(resolutions).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_srv_target_failure() {
+        let srv_record = |priority, target: &str| SrvRecord {
+            priority,
+            weight: 0,
+            port: 6182,
+            target: target.into(),
+        };
+        let mut resolver = StubResolver::default();
+        resolver
+            .srv_records
+            .insert("_aptos._tcp.example.com".into(), vec![
+                srv_record(10, "missing.example.com"),
+                srv_record(20, "node.example.com"),
+            ]);
+        resolver
+            .srv_records
+            .insert("_missing._tcp.example.com".into(), vec![srv_record(
+                10,
+                "missing.example.com",
+            )]);
+        resolver.set_host("node.example.com", vec![ip("10.0.1.1")]);
+
+        // Targets that fail to resolve are skipped
+        let record = DnsRecord::Srv {
+            name: "_aptos._tcp.example.com".into(),
+        };
+        assert_eq!(resolve_addresses(&resolver, &record).await.unwrap(), vec![
+            SocketAddr::new(ip("10.0.1.1"), 6182)
+        ]);
+
+        // The resolution fails if no target resolves
+        let record = DnsRecord::Srv {
+            name: "_missing._tcp.example.com".into(),
+        };
+        assert!(resolve_addresses(&resolver, &record).await.is_err());
+    }
+}
@@ -3,10 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters::DISCOVERY_COUNTS, file::FileStream, rest::RestStream,
+    counters::DISCOVERY_COUNTS, dns::DnsStream, file::FileStream, rest::RestStream,
     validator_set::ValidatorSetStream,
 };
-use aptos_config::{config::PeerSet, network_id::NetworkContext};
+use aptos_config::{
+    config::{DnsDiscovery, KubernetesDiscovery, PeerSet},
+    network_id::NetworkContext,
+};
 use aptos_crypto::x25519;
 use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_logger::prelude::*;
@@ -20,12 +23,16 @@ use futures::{Stream, StreamExt};
 use std::{
     path::Path,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
 use tokio::runtime::Handle;
 
+pub use dns::{DnsResolver, SrvRecord, SystemDnsResolver};
+
 mod counters;
+mod dns;
 mod file;
 mod rest;
 mod validator_set;
@@ -35,6 +42,7 @@ pub enum DiscoveryError {
     IO(std::io::Error),
     Parsing(String),
     Rest(aptos_rest_client::error::RestError),
+    Dns(String),
 }
 
 /// A union type for all implementations of `DiscoveryChangeListenerTrait`
@@ -49,6 +57,7 @@ enum DiscoveryChangeStream {
     ValidatorSet(ValidatorSetStream),
     File(FileStream),
     Rest(RestStream),
+    Dns(DnsStream),
 }
 
 impl Stream for DiscoveryChangeStream {
@@ -59,6 +68,7 @@ impl Stream for DiscoveryChangeStream {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
             Self::Rest(stream) => Pin::new(stream).poll_next(cx),
+            Self::Dns(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -124,6 +134,48 @@ impl DiscoveryChangeListener {
         }
     }
 
+    pub fn dns(
+        network_context: NetworkContext,
+        update_channel: aptos_channels::Sender<ConnectivityRequest>,
+        dns_discovery: &DnsDiscovery,
+        resolver: Arc<dyn DnsResolver>,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::Dns(DnsStream::new(
+            network_context,
+            dns_discovery,
+            resolver,
+            time_service,
+        ));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Dns,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
+    pub fn kubernetes(
+        network_context: NetworkContext,
+        update_channel: aptos_channels::Sender<ConnectivityRequest>,
+        kubernetes_discovery: &KubernetesDiscovery,
+        resolver: Arc<dyn DnsResolver>,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::Dns(DnsStream::kubernetes(
+            network_context,
+            kubernetes_discovery,
+            resolver,
+            time_service,
+        ));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Kubernetes,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         spawn_named!("DiscoveryChangeListener", executor, Box::pin(self).run());
     }
@@ -130,6 +130,8 @@ pub enum DiscoverySource {
     OnChainValidatorSet,
     File,
     Rest,
+    Dns,
+    Kubernetes,
     Config,
 }
 
@@ -146,6 +148,8 @@ impl fmt::Display for DiscoverySource {
             DiscoverySource::File => "File",
             DiscoverySource::Config => "Config",
             DiscoverySource::Rest => "Rest",
+            DiscoverySource::Dns => "Dns",
+            DiscoverySource::Kubernetes => "Kubernetes",
         })
     }
 }
@@ -397,24 +397,70 @@ pub static PENDING_WIRE_MESSAGES: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
-/// Counter of messages pending in queue to be sent out on the multiplex channel
-pub static PENDING_MULTIPLEX_MESSAGE: Lazy<IntGauge> = Lazy::new(|| {
-    register_int_gauge!(
-        "aptos_network_pending_multiplex_messages",
-        "Number of pending multiplex messages"
+/// Gauge of outbound messages queued in the peer writers, by priority class
+pub static APTOS_NETWORK_PENDING_OUTBOUND_MESSAGES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_network_pending_outbound_messages",
+        "Number of outbound messages queued for scheduling, by priority class",
+        &["role_type", "network_id", "peer_id", "priority_class"]
     )
     .unwrap()
 });
 
-/// Counter of stream messages pending in queue to be sent out on the multiplex channel
-pub static PENDING_MULTIPLEX_STREAM: Lazy<IntGauge> = Lazy::new(|| {
-    register_int_gauge!(
-        "aptos_network_pending_multiplex_stream",
-        "Number of pending multiplex stream messages"
+pub fn pending_outbound_messages(
+    network_context: &NetworkContext,
+    priority_class: &str,
+) -> IntGauge {
+    APTOS_NETWORK_PENDING_OUTBOUND_MESSAGES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        priority_class,
+    ])
+}
+
+/// Counter of outbound messages dropped by the peer writers because their priority class
+/// was full
+pub static APTOS_NETWORK_DROPPED_OUTBOUND_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_dropped_outbound_messages",
+        "Number of outbound messages dropped because their priority class queue was full",
+        &["role_type", "network_id", "peer_id", "priority_class"]
     )
     .unwrap()
 });
 
+pub fn dropped_outbound_messages(
+    network_context: &NetworkContext,
+    priority_class: &str,
+) -> IntCounter {
+    APTOS_NETWORK_DROPPED_OUTBOUND_MESSAGES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        priority_class,
+    ])
+}
+
+/// Time outbound messages spend queued in the peer writers, by priority class
+pub static APTOS_NETWORK_OUTBOUND_QUEUE_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_outbound_queue_delay_seconds",
+        "Time outbound messages spend queued before being written, by priority class",
+        &["role_type", "network_id", "peer_id", "priority_class"]
+    )
+    .unwrap()
+});
+
+pub fn outbound_queue_delay(network_context: &NetworkContext, priority_class: &str) -> Histogram {
+    APTOS_NETWORK_OUTBOUND_QUEUE_DELAY.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        priority_class,
+    ])
+}
+
 /// Counter of pending requests in Direct Send
 pub static PENDING_DIRECT_SEND_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -0,0 +1,409 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in capture of the traffic exchanged with remote peers, for debugging.
+//!
+//! When enabled (see [`TrafficCaptureConfig`]), every [`Peer`](crate::peer::Peer) records
+//! the decrypted [`NetworkMessage`]s it sends and receives, before fragmentation and after
+//! reassembly of streamed messages. Each record is a [`CapturedMessage`], serialized with
+//! BCS and prefixed by its length (as a little-endian `u32`). Records are appended to a set
+//! of rotating files in the configured directory, named `<network_id>-<index>.capture`.
+//! Outbound messages are recorded when they're written to the connection.
+//!
+//! Records are written by a dedicated thread, fed by a bounded channel, so that capturing
+//! never blocks the peers on file IO. Records are dropped if the thread falls behind.
+//!
+//! Capture files are read back with [`CaptureFileReader`].
+
+use crate::{protocols::wire::messaging::v1::NetworkMessage, ProtocolId};
+use aptos_config::{
+    config::TrafficCaptureConfig,
+    network_id::{NetworkContext, NetworkId},
+};
+use aptos_logger::prelude::*;
+use aptos_types::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// The file extension of capture files
+pub const CAPTURE_FILE_EXTENSION: &str = "capture";
+
+/// The number of records that can be waiting for the capture thread
+const CAPTURE_CHANNEL_SIZE: usize = 1024;
+
+/// The maximum size of a single record, to avoid allocating huge buffers when
+/// reading corrupted files.
+const MAX_RECORD_SIZE: usize = 256 * 1024 * 1024; /* 256 MiB */
+
+/// The direction of a captured message, from the point of view of the capturing node.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum CaptureDirection {
+    Inbound,
+    Outbound,
+}
+
+/// A single message exchanged with a remote peer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CapturedMessage {
+    /// The time at which the message was sent or received (since the unix epoch)
+    pub timestamp_usecs: u64,
+    pub network_id: NetworkId,
+    pub remote_peer_id: PeerId,
+    pub direction: CaptureDirection,
+    /// The protocol of the message, if known. Inbound RPC responses don't carry
+    /// their protocol on the wire.
+    pub protocol_id: Option<ProtocolId>,
+    pub message: NetworkMessage,
+}
+
+/// A borrowed [`CapturedMessage`], which avoids copying messages in order to record them.
+/// Both have the same BCS representation.
+#[derive(Serialize)]
+#[serde(rename = "CapturedMessage")]
+struct CapturedMessageRef<'a> {
+    timestamp_usecs: u64,
+    network_id: NetworkId,
+    remote_peer_id: PeerId,
+    direction: CaptureDirection,
+    protocol_id: Option<ProtocolId>,
+    message: &'a NetworkMessage,
+}
+
+/// A request to the capture thread
+enum CaptureCommand {
+    Record(Vec<u8>),
+    /// Flushes all previous records to the capture files, then acknowledges
+    Flush(SyncSender<()>),
+}
+
+/// Records the traffic of all peers of a network. This is shared by all `Peer` actors.
+pub struct TrafficCapture {
+    network_context: NetworkContext,
+    peers: HashSet<PeerId>,
+    protocols: HashSet<ProtocolId>,
+    command_tx: Option<SyncSender<CaptureCommand>>,
+    capture_thread: Option<JoinHandle<()>>,
+}
+
+impl TrafficCapture {
+    pub fn new(network_context: NetworkContext, config: &TrafficCaptureConfig) -> io::Result<Self> {
+        let protocols = config
+            .protocols
+            .iter()
+            .filter_map(|protocol_name| {
+                let protocol_id = ProtocolId::all()
+                    .iter()
+                    .find(|protocol_id| protocol_id.as_str() == protocol_name)
+                    .copied();
+                if protocol_id.is_none() {
+                    warn!(
+                        "Ignoring unknown protocol {} in the traffic capture config",
+                        protocol_name
+                    );
+                }
+                protocol_id
+            })
+            .collect();
+        let writer = CaptureWriter::new(
+            config.path.clone(),
+            network_context.network_id(),
+            config.max_file_size_bytes,
+            config.max_files,
+        )?;
+        let (command_tx, command_rx) = mpsc::sync_channel(CAPTURE_CHANNEL_SIZE);
+        let capture_thread = std::thread::Builder::new()
+            .name(format!("capture-{}", network_context.network_id()))
+            .spawn(move || run_capture_thread(network_context, writer, command_rx))?;
+        info!(
+            "{} Capturing network traffic into {:?}",
+            network_context, config.path
+        );
+
+        Ok(Self {
+            network_context,
+            peers: config.peers.clone(),
+            protocols,
+            command_tx: Some(command_tx),
+            capture_thread: Some(capture_thread),
+        })
+    }
+
+    /// Returns true iff the messages of the given peer and protocol should be captured
+    pub(crate) fn should_capture(
+        &self,
+        remote_peer_id: PeerId,
+        protocol_id: Option<ProtocolId>,
+    ) -> bool {
+        let capture_peer = self.peers.is_empty() || self.peers.contains(&remote_peer_id);
+        let capture_protocol = self.protocols.is_empty()
+            || protocol_id.map_or(false, |protocol_id| self.protocols.contains(&protocol_id));
+        capture_peer && capture_protocol
+    }
+
+    /// Records a message sent to, or received from, the given peer. Failures are
+    /// logged, as capturing must never interfere with the connection.
+    pub fn record(
+        &self,
+        timestamp: Duration,
+        remote_peer_id: PeerId,
+        direction: CaptureDirection,
+        protocol_id: Option<ProtocolId>,
+        message: &NetworkMessage,
+    ) {
+        if !self.should_capture(remote_peer_id, protocol_id) {
+            return;
+        }
+
+        let captured_message = CapturedMessageRef {
+            timestamp_usecs: timestamp.as_micros() as u64,
+            network_id: self.network_context.network_id(),
+            remote_peer_id,
+            direction,
+            protocol_id,
+            message,
+        };
+        let error = match bcs::to_bytes(&captured_message) {
+            Ok(record) => match self.command_tx().try_send(CaptureCommand::Record(record)) {
+                Ok(()) => return,
+                Err(TrySendError::Full(_)) => "the capture thread is falling behind".to_string(),
+                Err(TrySendError::Disconnected(_)) => "the capture thread exited".to_string(),
+            },
+            Err(error) => error.to_string(),
+        };
+        sample!(SampleRate::Duration(Duration::from_secs(60)), {
+            warn!(
+                "{} Failed to capture message for peer {}: {}",
+                self.network_context, remote_peer_id, error
+            );
+        });
+    }
+
+    /// Waits until all previously recorded messages are written to the capture files
+    pub fn flush(&self) {
+        let (ack_tx, ack_rx) = mpsc::sync_channel(1);
+        if self
+            .command_tx()
+            .send(CaptureCommand::Flush(ack_tx))
+            .is_ok()
+        {
+            let _ = ack_rx.recv();
+        }
+    }
+
+    fn command_tx(&self) -> &SyncSender<CaptureCommand> {
+        self.command_tx
+            .as_ref()
+            .expect("The capture channel is only closed on drop!")
+    }
+}
+
+impl Drop for TrafficCapture {
+    fn drop(&mut self) {
+        // Closing the channel stops the capture thread once all records are written
+        self.command_tx.take();
+        if let Some(capture_thread) = self.capture_thread.take() {
+            let _ = capture_thread.join();
+        }
+    }
+}
+
+/// Writes the records received on the channel, until it is closed. Files are flushed
+/// whenever the channel is drained, so that captures are complete even if the node crashes.
+fn run_capture_thread(
+    network_context: NetworkContext,
+    mut writer: CaptureWriter,
+    command_rx: Receiver<CaptureCommand>,
+) {
+    let log_error = |error: io::Error| {
+        sample!(SampleRate::Duration(Duration::from_secs(60)), {
+            warn!(
+                "{} Failed to write network traffic capture: {}",
+                network_context, error
+            );
+        });
+    };
+
+    while let Ok(command) = command_rx.recv() {
+        let mut next_command = Some(command);
+        while let Some(command) = next_command {
+            match command {
+                CaptureCommand::Record(record) => {
+                    if let Err(error) = writer.write_record(&record) {
+                        log_error(error);
+                    }
+                },
+                CaptureCommand::Flush(ack_tx) => {
+                    if let Err(error) = writer.flush() {
+                        log_error(error);
+                    }
+                    let _ = ack_tx.send(());
+                },
+            }
+            next_command = command_rx.try_recv().ok();
+        }
+        if let Err(error) = writer.flush() {
+            log_error(error);
+        }
+    }
+}
+
+/// Appends length-prefixed records to a set of rotating capture files.
+struct CaptureWriter {
+    directory: PathBuf,
+    network_id: NetworkId,
+    max_file_size: u64,
+    max_files: usize,
+    /// The index of the current capture file
+    file_index: u64,
+    file: Option<BufWriter<File>>,
+    file_size: u64,
+}
+
+impl CaptureWriter {
+    fn new(
+        directory: PathBuf,
+        network_id: NetworkId,
+        max_file_size: u64,
+        max_files: usize,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+
+        // Never overwrite the files of a previous run
+        let file_index = capture_files(&directory, network_id)?
+            .last()
+            .map_or(0, |(index, _)| index + 1);
+        Ok(Self {
+            directory,
+            network_id,
+            max_file_size,
+            max_files: max_files.max(1),
+            file_index,
+            file: None,
+            file_size: 0,
+        })
+    }
+
+    fn write_record(&mut self, record: &[u8]) -> io::Result<()> {
+        if self.file.is_some() && self.file_size >= self.max_file_size {
+            self.rotate()?;
+        }
+        if self.file.is_none() {
+            let path = capture_file_path(&self.directory, self.network_id, self.file_index);
+            self.file = Some(BufWriter::new(File::create(path)?));
+            self.file_size = 0;
+        }
+        let file = self.file.as_mut().expect("The capture file must be open!");
+
+        file.write_all(&(record.len() as u32).to_le_bytes())?;
+        file.write_all(record)?;
+        self.file_size += (4 + record.len()) as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Closes the current capture file, and deletes the oldest files beyond the limit
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        self.file_index += 1;
+
+        let files = capture_files(&self.directory, self.network_id)?;
+        // The next file is created on the next write, so keep one less
+        let num_to_delete = (files.len() + 1).saturating_sub(self.max_files);
+        for (_, path) in files.into_iter().take(num_to_delete) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+fn capture_file_path(directory: &Path, network_id: NetworkId, index: u64) -> PathBuf {
+    directory.join(format!(
+        "{}-{:08}.{}",
+        network_id, index, CAPTURE_FILE_EXTENSION
+    ))
+}
+
+/// Returns the capture files of the given network in the directory, ordered by index
+fn capture_files(directory: &Path, network_id: NetworkId) -> io::Result<Vec<(u64, PathBuf)>> {
+    let prefix = format!("{}-", network_id);
+    let suffix = format!(".{}", CAPTURE_FILE_EXTENSION);
+    let mut files = vec![];
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let index = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.strip_prefix(&prefix))
+            .and_then(|file_name| file_name.strip_suffix(&suffix))
+            .and_then(|index| index.parse::<u64>().ok());
+        if let Some(index) = index {
+            files.push((index, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Reads the [`CapturedMessage`]s of a capture file, in the order they were recorded.
+pub struct CaptureFileReader<R> {
+    reader: R,
+}
+
+impl CaptureFileReader<BufReader<File>> {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Ok(Self::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: Read> CaptureFileReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Reads the next record, or returns `None` at the end of the file
+    fn read_record(&mut self) -> io::Result<Option<CapturedMessage>> {
+        let mut length = [0u8; 4];
+        match self.reader.read_exact(&mut length) {
+            Ok(()) => {},
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error),
+        }
+        let length = u32::from_le_bytes(length) as usize;
+        if length > MAX_RECORD_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Capture record is too large: {} bytes", length),
+            ));
+        }
+
+        let mut record = vec![0u8; length];
+        self.reader.read_exact(&mut record)?;
+        bcs::from_bytes(&record)
+            .map(Some)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl<R: Read> Iterator for CaptureFileReader<R> {
+    type Item = io::Result<CapturedMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
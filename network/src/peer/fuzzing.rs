@@ -4,7 +4,7 @@
 
 use crate::{
     constants,
-    peer::Peer,
+    peer::{Peer, PriorityClasses},
     protocols::wire::{
         handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
         messaging::v1::{MultiplexMessage, MultiplexMessageSink},
@@ -13,7 +13,10 @@ use crate::{
     transport::{Connection, ConnectionId, ConnectionMetadata},
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{PeerRole, MAX_OUTBOUND_QUEUED_MESSAGES},
+    network_id::NetworkContext,
+};
 use aptos_memsocket::MemorySocket;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_proptest_helpers::ValueGenerator;
@@ -110,6 +113,10 @@ pub fn fuzz(data: &[u8]) {
         constants::MAX_MESSAGE_SIZE,
         None,
         None,
+        PriorityClasses::default(),
+        MAX_OUTBOUND_QUEUED_MESSAGES,
+        true,
+        None,
     );
     executor.spawn(peer.start());
 
@@ -13,6 +13,12 @@
 //! [`Peer`] owns the actual underlying connection socket and is reponsible for
 //! the socket's shutdown, graceful or otherwise.
 //!
+//! Outbound messages are written by a separate writer task, which schedules them
+//! across the configured priority classes (see [`OutboundScheduler`]).
+//!
+//! If traffic capture is enabled, all messages sent and received are also recorded
+//! (see [`TrafficCapture`]).
+//!
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
@@ -35,7 +41,7 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::aptos_channel;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::{config::MAX_CONCURRENT_STREAMS, network_id::NetworkContext};
 use aptos_logger::prelude::*;
 use aptos_rate_limiter::rate_limit::SharedBucket;
 use aptos_short_hex_str::AsShortHexStr;
@@ -45,21 +51,31 @@ use bytes::Bytes;
 use futures::{
     self,
     channel::oneshot,
+    future::FutureExt,
     io::{AsyncRead, AsyncWrite},
-    stream::StreamExt,
+    stream::{FusedStream, StreamExt},
     SinkExt,
 };
-use futures_util::stream::select;
 use serde::Serialize;
-use std::{fmt, panic, time::Duration};
+use std::{fmt, panic, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
 
+mod capture;
+mod scheduler;
 #[cfg(test)]
 mod test;
 
+pub use capture::{
+    CaptureDirection, CaptureFileReader, CapturedMessage, TrafficCapture, CAPTURE_FILE_EXTENSION,
+};
+pub use scheduler::{OutboundMessage, OutboundScheduler, PriorityClasses};
+
+/// Size of the channel of write requests to the peer writer
+const WRITE_REQUESTS_CHANNEL_SIZE: usize = 1024;
+
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing;
 
@@ -143,6 +159,14 @@ pub struct Peer<TSocket> {
     outbound_rate_limiter: Option<SharedBucket>,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// Priority classes used to schedule outbound messages
+    priority_classes: PriorityClasses,
+    /// The maximum number of outbound frames queued for scheduling per priority class
+    max_queued_messages: usize,
+    /// Whether the outbound streams of different priority classes may be interleaved
+    interleave_streams: bool,
+    /// Optional capture of the messages exchanged with the remote peer
+    traffic_capture: Option<Arc<TrafficCapture>>,
 }
 
 impl<TSocket> Peer<TSocket>
@@ -165,6 +189,10 @@ where
         max_message_size: usize,
         inbound_rate_limiter: Option<SharedBucket>,
         outbound_rate_limiter: Option<SharedBucket>,
+        priority_classes: PriorityClasses,
+        max_queued_messages: usize,
+        interleave_streams: bool,
+        traffic_capture: Option<Arc<TrafficCapture>>,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -199,7 +227,11 @@ where
             max_message_size,
             inbound_rate_limiter,
             outbound_rate_limiter,
-            inbound_stream: InboundStreamBuffer::new(max_fragments),
+            inbound_stream: InboundStreamBuffer::new(max_fragments, MAX_CONCURRENT_STREAMS),
+            priority_classes,
+            max_queued_messages,
+            interleave_streams,
+            traffic_capture,
         }
     }
 
@@ -245,6 +277,10 @@ where
             writer,
             self.max_frame_size,
             self.max_message_size,
+            self.priority_classes.clone(),
+            self.max_queued_messages,
+            self.interleave_streams,
+            self.traffic_capture.clone(),
         );
 
         // Start main Peer event loop.
@@ -315,6 +351,10 @@ where
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
     // them and immediately closes the connection.
+    //
+    // Pending messages are moved from the first channel into an `OutboundScheduler`, which
+    // decides the order in which they are written based on their priority classes.
+    #[allow(clippy::too_many_arguments)]
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
@@ -323,30 +363,134 @@ where
         mut writer: MultiplexMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
         max_frame_size: usize,
         max_message_size: usize,
-    ) -> (aptos_channels::Sender<NetworkMessage>, oneshot::Sender<()>) {
+        priority_classes: PriorityClasses,
+        max_queued_messages: usize,
+        interleave_streams: bool,
+        traffic_capture: Option<Arc<TrafficCapture>>,
+    ) -> (aptos_channels::Sender<OutboundMessage>, oneshot::Sender<()>) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<NetworkMessage>, _) =
-            aptos_channels::new(1024, &counters::PENDING_WIRE_MESSAGES);
+        let (write_reqs_tx, mut write_reqs_rx): (aptos_channels::Sender<OutboundMessage>, _) =
+            aptos_channels::new(
+                WRITE_REQUESTS_CHANNEL_SIZE,
+                &counters::PENDING_WIRE_MESSAGES,
+            );
         let (close_tx, mut close_rx) = oneshot::channel();
 
-        let (mut msg_tx, msg_rx) = aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_MESSAGE);
-        let (stream_msg_tx, stream_msg_rx) =
-            aptos_channels::new(1024, &counters::PENDING_MULTIPLEX_STREAM);
-
-        // this task ends when the multiplex task ends (by dropping the senders)
+        // this task ends when the write requests channel is closed (and all queued
+        // messages are written), or when it receives a close instruction
         let writer_task = async move {
-            let mut stream = select(msg_rx, stream_msg_rx);
             let log_context =
                 NetworkSchema::new(&network_context).connection_metadata(&connection_metadata);
-            while let Some(message) = stream.next().await {
-                if let Err(err) = writer.send(&message).await {
-                    warn!(
-                        log_context,
-                        error = %err,
-                        "{} Error in sending message to peer: {}",
-                        network_context,
-                        remote_peer_id.short_str(),
-                    );
+            let mut outbound_stream = OutboundStream::new(max_frame_size, max_message_size);
+            let mut scheduler = OutboundScheduler::new(
+                network_context,
+                priority_classes,
+                max_queued_messages,
+                interleave_streams,
+            );
+            let mut enqueue = |scheduler: &mut OutboundScheduler, message: OutboundMessage| {
+                // Captured messages are recorded once they're written, so keep the original
+                let capture = traffic_capture
+                    .as_ref()
+                    .filter(|traffic_capture| {
+                        traffic_capture.should_capture(remote_peer_id, message.protocol_id)
+                    })
+                    .map(|_| OutboundMessage {
+                        protocol_id: message.protocol_id,
+                        message: message.message.clone(),
+                    });
+                let now = time_service.now();
+                let class = scheduler.classes().class_of(message.protocol_id);
+                if scheduler.is_class_full(class) {
+                    // Only drop the messages of the full class, so that a backlog of bulk
+                    // messages doesn't hold up the other classes in the channel.
+                    let class_name = scheduler.classes().name(class);
+                    counters::dropped_outbound_messages(&network_context, class_name).inc();
+                    sample!(SampleRate::Duration(Duration::from_secs(60)), {
+                        warn!(
+                            log_context,
+                            "{} Dropping outbound message to peer: {}, priority class {} is full",
+                            network_context,
+                            remote_peer_id.short_str(),
+                            class_name,
+                        );
+                    });
+                    return;
+                }
+                let message = message.message;
+                if !outbound_stream.should_stream(&message) {
+                    scheduler.push(class, message, capture, now);
+                    return;
+                }
+                match outbound_stream.stream_message(message) {
+                    Ok(stream_messages) => {
+                        scheduler.push_stream(class, stream_messages, capture, now);
+                    },
+                    Err(err) => {
+                        warn!(
+                            log_context,
+                            error = %err,
+                            "{} Error in streaming message to peer: {}",
+                            network_context,
+                            remote_peer_id.short_str(),
+                        );
+                    },
+                }
+            };
+
+            loop {
+                // Only wait for new messages when there's nothing left to write
+                if scheduler.is_empty() {
+                    if write_reqs_rx.is_terminated() {
+                        break;
+                    }
+                    futures::select! {
+                        maybe_message = write_reqs_rx.next() => {
+                            match maybe_message {
+                                Some(message) => enqueue(&mut scheduler, message),
+                                None => break,
+                            }
+                        },
+                        _ = close_rx => {
+                            break;
+                        }
+                    }
+                }
+
+                // Move all pending messages into the scheduler so that the next message
+                // to write is picked across all priority classes. The channel is always
+                // drained (messages of full classes are dropped), so that a backlog in one
+                // class never keeps the messages of other classes waiting in the channel.
+                for _ in 0..WRITE_REQUESTS_CHANNEL_SIZE {
+                    match write_reqs_rx.next().now_or_never() {
+                        Some(Some(message)) => enqueue(&mut scheduler, message),
+                        _ => break,
+                    }
+                }
+
+                if !matches!(close_rx.try_recv(), Ok(None)) {
+                    break;
+                }
+
+                if let Some((message, capture)) = scheduler.pop(time_service.now()) {
+                    if let (Some(traffic_capture), Some(capture)) = (&traffic_capture, capture) {
+                        traffic_capture.record(
+                            time_service.now_unix_time(),
+                            remote_peer_id,
+                            CaptureDirection::Outbound,
+                            capture.protocol_id,
+                            &capture.message,
+                        );
+                    }
+                    if let Err(err) = writer.send(&message).await {
+                        warn!(
+                            log_context,
+                            error = %err,
+                            "{} Error in sending message to peer: {}",
+                            network_context,
+                            remote_peer_id.short_str(),
+                        );
+                    }
                 }
             }
             info!(
@@ -392,35 +536,7 @@ where
                 },
             }
         };
-        let multiplex_task = async move {
-            let mut outbound_stream =
-                OutboundStream::new(max_frame_size, max_message_size, stream_msg_tx);
-            loop {
-                futures::select! {
-                    message = write_reqs_rx.select_next_some() => {
-                        // either channel full would block the other one
-                        let result = if outbound_stream.should_stream(&message) {
-                            outbound_stream.stream_message(message).await
-                        } else {
-                            msg_tx.send(MultiplexMessage::Message(message)).await.map_err(|_| anyhow::anyhow!("Writer task ended"))
-                        };
-                        if let Err(err) = result {
-                            warn!(
-                                error = %err,
-                                "{} Error in sending message to peer: {}",
-                                network_context,
-                                remote_peer_id.short_str(),
-                            );
-                        }
-                    },
-                    _ = close_rx => {
-                        break;
-                    }
-                }
-            }
-        };
         executor.spawn(writer_task);
-        executor.spawn(multiplex_task);
         (write_reqs_tx, close_tx)
     }
 
@@ -428,6 +544,16 @@ where
         &mut self,
         message: NetworkMessage,
     ) -> Result<(), PeerManagerError> {
+        if let Some(traffic_capture) = &self.traffic_capture {
+            traffic_capture.record(
+                self.time_service.now_unix_time(),
+                self.remote_peer_id(),
+                CaptureDirection::Inbound,
+                message.protocol_id(),
+                &message,
+            );
+        }
+
         match message {
             NetworkMessage::DirectSendMsg(message) => self.handle_inbound_direct_send(message),
             NetworkMessage::Error(error_msg) => {
@@ -483,7 +609,7 @@ where
     async fn handle_inbound_message(
         &mut self,
         message: Result<MultiplexMessage, ReadError>,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -505,7 +631,9 @@ where
                     let error_code = ErrorCode::parsing_error(*message_type, *protocol_id);
                     let message = NetworkMessage::Error(error_code);
 
-                    write_reqs_tx.send(message).await?;
+                    write_reqs_tx
+                        .send(OutboundMessage::without_protocol(message))
+                        .await?;
                     return Err(err.into());
                 },
                 ReadError::IoError(_) => {
@@ -564,7 +692,7 @@ where
     async fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut aptos_channels::Sender<NetworkMessage>,
+        write_reqs_tx: &mut aptos_channels::Sender<OutboundMessage>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -588,7 +716,10 @@ where
                     raw_msg: Vec::from(message.mdata.as_ref()),
                 });
 
-                match write_reqs_tx.send(message).await {
+                match write_reqs_tx
+                    .send(OutboundMessage::new(protocol_id, message))
+                    .await
+                {
                     Ok(_) => {
                         counters::direct_send_messages(&self.network_context, SENT_LABEL).inc();
                         counters::direct_send_bytes(&self.network_context, SENT_LABEL)
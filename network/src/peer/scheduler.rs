@@ -0,0 +1,397 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Priority scheduling of outbound messages for the [`Peer`](crate::peer::Peer) writer.
+//!
+//! Every outbound message is tagged with the [`ProtocolId`] it belongs to, and each protocol
+//! is mapped to a priority class (see [`PriorityClassConfig`]). The [`OutboundScheduler`]
+//! keeps one queue per class and serves them with weighted fair queuing: each message is
+//! stamped with a virtual finish time (its size divided by the weight of its class) and the
+//! writer always sends the queued message with the smallest finish time (self-clocked fair
+//! queuing). A backlog of large messages in one class (e.g., storage service responses)
+//! therefore only delays the messages of other classes (e.g., consensus votes) in proportion
+//! to the class weights, instead of in FIFO order.
+//!
+//! Streamed messages are queued in a separate stream queue per class, and their fragments
+//! are only produced as they are sent. Every header and fragment is stamped using the
+//! weight of its class and counts against the queue limit of its class. Streams of the
+//! same class are sent one after the other, while streams of different classes may be
+//! interleaved (up to [`MAX_CONCURRENT_STREAMS`] at once), so that a large bulk stream
+//! doesn't block a streamed consensus message. Peers that can't reassemble interleaved
+//! streams are only sent one stream at a time (see `NetworkConfig::interleave_outbound_streams`).
+
+use crate::{
+    counters,
+    protocols::{
+        stream::OutboundStreamMessages,
+        wire::messaging::v1::{MultiplexMessage, NetworkMessage},
+    },
+    ProtocolId,
+};
+use aptos_config::{
+    config::{PriorityClassConfig, MAX_CONCURRENT_STREAMS},
+    network_id::NetworkContext,
+};
+use aptos_logger::prelude::*;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
+
+/// Message sizes are scaled by this factor before being divided by the class weight, so
+/// that small messages still advance the virtual time of their class.
+const VIRTUAL_TIME_SCALE: u128 = 1 << 16;
+
+/// A [`NetworkMessage`] queued for the writer task, tagged with the protocol it belongs to.
+#[derive(Debug)]
+pub struct OutboundMessage {
+    /// The protocol of the message. This is `None` for messages that don't belong to
+    /// any application protocol (e.g., `NetworkMessage::Error`).
+    pub protocol_id: Option<ProtocolId>,
+    pub message: NetworkMessage,
+}
+
+impl OutboundMessage {
+    pub fn new(protocol_id: ProtocolId, message: NetworkMessage) -> Self {
+        Self {
+            protocol_id: Some(protocol_id),
+            message,
+        }
+    }
+
+    pub fn without_protocol(message: NetworkMessage) -> Self {
+        Self {
+            protocol_id: None,
+            message,
+        }
+    }
+}
+
+/// The priority classes of a network, resolved from the [`PriorityClassConfig`]s.
+#[derive(Clone, Debug)]
+pub struct PriorityClasses {
+    names: Vec<String>,
+    weights: Vec<u32>,
+    classes_by_protocol: HashMap<ProtocolId, usize>,
+}
+
+impl PriorityClasses {
+    pub fn new(configs: &[PriorityClassConfig]) -> Self {
+        let mut names = Vec::with_capacity(configs.len());
+        let mut weights = Vec::with_capacity(configs.len());
+        let mut classes_by_protocol = HashMap::new();
+        for (class, config) in configs.iter().enumerate() {
+            names.push(config.name.clone());
+            weights.push(config.weight.max(1));
+            for protocol_name in &config.protocols {
+                match ProtocolId::all()
+                    .iter()
+                    .find(|protocol_id| protocol_id.as_str() == protocol_name)
+                {
+                    Some(protocol_id) => {
+                        if classes_by_protocol.insert(*protocol_id, class).is_some() {
+                            warn!(
+                                "Protocol {} is assigned to multiple outbound priority classes! Using class: {}",
+                                protocol_name, config.name
+                            );
+                        }
+                    },
+                    None => warn!(
+                        "Ignoring unknown protocol {} in outbound priority class: {}",
+                        protocol_name, config.name
+                    ),
+                }
+            }
+        }
+
+        // Everything shares a single class if no classes are configured
+        if names.is_empty() {
+            names.push("default".into());
+            weights.push(1);
+        }
+
+        Self {
+            names,
+            weights,
+            classes_by_protocol,
+        }
+    }
+
+    /// Returns the class of the given protocol. Messages without a protocol, or with
+    /// a protocol that isn't assigned to any class, fall into the last class.
+    pub fn class_of(&self, protocol_id: Option<ProtocolId>) -> usize {
+        protocol_id
+            .and_then(|protocol_id| self.classes_by_protocol.get(&protocol_id).copied())
+            .unwrap_or(self.names.len() - 1)
+    }
+
+    pub fn name(&self, class: usize) -> &str {
+        &self.names[class]
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn weight(&self, class: usize) -> u32 {
+        self.weights[class]
+    }
+}
+
+impl Default for PriorityClasses {
+    fn default() -> Self {
+        Self::new(&PriorityClassConfig::default_classes())
+    }
+}
+
+/// A message waiting in one of the scheduler queues.
+struct QueuedMessage {
+    finish_time: u128,
+    enqueue_time: Instant,
+    message: NetworkMessage,
+    /// The original message, if it is captured once written
+    capture: Option<OutboundMessage>,
+}
+
+/// A streamed message waiting in one of the scheduler stream queues. Its header and
+/// fragments are produced one at a time, as they are sent.
+struct QueuedStream {
+    /// The finish time of the next header or fragment of the stream
+    next_finish_time: u128,
+    enqueue_time: Instant,
+    messages: OutboundStreamMessages,
+    /// True iff the header of the stream has been sent
+    started: bool,
+    /// The original message, if it is captured once written (i.e., with its last fragment)
+    capture: Option<OutboundMessage>,
+}
+
+/// Weighted fair queuing over the priority classes of outbound messages.
+pub struct OutboundScheduler {
+    network_context: NetworkContext,
+    classes: PriorityClasses,
+    /// One queue of messages that fit into a single frame per class.
+    queues: Vec<VecDeque<QueuedMessage>>,
+    /// One queue of streamed messages per class. Only the stream at the head of a queue
+    /// is sent, so that the remote peer reassembles at most one stream per class.
+    stream_queues: Vec<VecDeque<QueuedStream>>,
+    /// The finish time of the last message queued for each class.
+    last_finish_times: Vec<u128>,
+    /// The finish time of the last message that was sent.
+    virtual_time: u128,
+    /// The number of frames (i.e., messages, stream headers and fragments) queued per class.
+    num_queued: Vec<usize>,
+    /// The maximum number of frames queued per class.
+    max_queued: usize,
+    /// True iff the streams of different classes may be interleaved on the wire.
+    interleave_streams: bool,
+}
+
+impl OutboundScheduler {
+    pub fn new(
+        network_context: NetworkContext,
+        classes: PriorityClasses,
+        max_queued: usize,
+        interleave_streams: bool,
+    ) -> Self {
+        let num_classes = classes.num_classes();
+        Self {
+            network_context,
+            classes,
+            queues: (0..num_classes).map(|_| VecDeque::new()).collect(),
+            stream_queues: (0..num_classes).map(|_| VecDeque::new()).collect(),
+            last_finish_times: vec![0; num_classes],
+            virtual_time: 0,
+            num_queued: vec![0; num_classes],
+            max_queued: max_queued.max(1),
+            interleave_streams,
+        }
+    }
+
+    pub fn classes(&self) -> &PriorityClasses {
+        &self.classes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true iff no more messages of the given class should be queued until some
+    /// are sent, i.e., the class has reached its limit of queued frames.
+    pub fn is_class_full(&self, class: usize) -> bool {
+        self.num_queued[class] >= self.max_queued
+    }
+
+    /// Returns the number of queued frames (across all classes)
+    pub fn len(&self) -> usize {
+        self.num_queued.iter().sum()
+    }
+
+    /// Queues a message that fits into a single frame
+    pub fn push(
+        &mut self,
+        class: usize,
+        message: NetworkMessage,
+        capture: Option<OutboundMessage>,
+        now: Instant,
+    ) {
+        let start_time = self.start_time(class);
+        let finish_time = start_time + self.virtual_duration(class, message.data_len(), 1);
+        self.last_finish_times[class] = finish_time;
+
+        self.queues[class].push_back(QueuedMessage {
+            finish_time,
+            enqueue_time: now,
+            message,
+            capture,
+        });
+        self.add_queued(class, 1);
+    }
+
+    /// Queues a streamed message. The virtual time of the whole stream is reserved for
+    /// its class, and every header and fragment counts against the class limit.
+    pub fn push_stream(
+        &mut self,
+        class: usize,
+        messages: OutboundStreamMessages,
+        capture: Option<OutboundMessage>,
+        now: Instant,
+    ) {
+        let next_data_len = match messages.next_data_len() {
+            Some(next_data_len) => next_data_len,
+            None => return,
+        };
+        let num_messages = messages.len();
+        let start_time = self.start_time(class);
+        self.last_finish_times[class] =
+            start_time + self.virtual_duration(class, messages.remaining_data_len(), num_messages);
+
+        self.stream_queues[class].push_back(QueuedStream {
+            next_finish_time: start_time + self.virtual_duration(class, next_data_len, 1),
+            enqueue_time: now,
+            messages,
+            started: false,
+            capture,
+        });
+        self.add_queued(class, num_messages);
+    }
+
+    fn start_time(&self, class: usize) -> u128 {
+        self.virtual_time.max(self.last_finish_times[class])
+    }
+
+    /// Returns the virtual time it takes to send the given number of bytes (in the given
+    /// number of frames) for the given class.
+    fn virtual_duration(&self, class: usize, num_bytes: usize, num_frames: usize) -> u128 {
+        let size = (num_bytes as u128).saturating_add(num_frames as u128);
+        size * VIRTUAL_TIME_SCALE / self.classes.weight(class) as u128
+    }
+
+    fn add_queued(&mut self, class: usize, num_frames: usize) {
+        self.num_queued[class] += num_frames;
+        counters::pending_outbound_messages(&self.network_context, self.classes.name(class))
+            .add(num_frames as i64);
+    }
+
+    /// Returns true iff the stream at the head of the given class may send its next
+    /// message. Streams of the same class are never interleaved, and streams of different
+    /// classes are only interleaved if enabled.
+    fn can_send_stream(&self, class: usize) -> bool {
+        let stream = match self.stream_queues[class].front() {
+            Some(stream) => stream,
+            None => return false,
+        };
+        if stream.started {
+            return true;
+        }
+        let num_started_streams = self
+            .stream_queues
+            .iter()
+            .filter(|queue| queue.front().map_or(false, |stream| stream.started))
+            .count();
+        if self.interleave_streams {
+            num_started_streams < MAX_CONCURRENT_STREAMS
+        } else {
+            num_started_streams == 0
+        }
+    }
+
+    /// Removes and returns the next message to write, i.e., the message (or stream header
+    /// or fragment) at the head of a queue with the smallest virtual finish time, with its
+    /// capture (if any). Streamed messages are captured along with their last fragment.
+    pub fn pop(&mut self, now: Instant) -> Option<(MultiplexMessage, Option<OutboundMessage>)> {
+        let next_message = (0..self.classes.num_classes())
+            .filter_map(|class| {
+                self.queues[class]
+                    .front()
+                    .map(|head| (class, false, head.finish_time))
+            })
+            .chain(
+                (0..self.classes.num_classes())
+                    .filter(|class| self.can_send_stream(*class))
+                    .filter_map(|class| {
+                        self.stream_queues[class]
+                            .front()
+                            .map(|head| (class, true, head.next_finish_time))
+                    }),
+            )
+            .min_by_key(|(_, _, finish_time)| *finish_time);
+        let (class, is_stream, finish_time) = next_message?;
+
+        let (message, capture, enqueue_time) = if is_stream {
+            self.pop_stream_message(class)?
+        } else {
+            let queued_message = self.queues[class].pop_front()?;
+            (
+                MultiplexMessage::Message(queued_message.message),
+                queued_message.capture,
+                queued_message.enqueue_time,
+            )
+        };
+        self.num_queued[class] -= 1;
+        self.virtual_time = self.virtual_time.max(finish_time);
+
+        let class_name = self.classes.name(class);
+        counters::pending_outbound_messages(&self.network_context, class_name).dec();
+        counters::outbound_queue_delay(&self.network_context, class_name)
+            .observe(now.saturating_duration_since(enqueue_time).as_secs_f64());
+        Some((message, capture))
+    }
+
+    /// Produces the next header or fragment of the stream at the head of the given class
+    fn pop_stream_message(
+        &mut self,
+        class: usize,
+    ) -> Option<(MultiplexMessage, Option<OutboundMessage>, Instant)> {
+        let stream = self.stream_queues[class].front_mut()?;
+        let message = stream.messages.next()?;
+        stream.started = true;
+        let enqueue_time = stream.enqueue_time;
+        match stream.messages.next_data_len() {
+            Some(next_data_len) => {
+                let duration = self.virtual_duration(class, next_data_len, 1);
+                let stream = self.stream_queues[class].front_mut()?;
+                stream.next_finish_time += duration;
+                Some((MultiplexMessage::Stream(message), None, enqueue_time))
+            },
+            None => {
+                let stream = self.stream_queues[class].pop_front()?;
+                Some((
+                    MultiplexMessage::Stream(message),
+                    stream.capture,
+                    enqueue_time,
+                ))
+            },
+        }
+    }
+}
+
+impl Drop for OutboundScheduler {
+    fn drop(&mut self) {
+        // Messages still queued are discarded, so remove them from the pending gauges
+        for (class, num_queued) in self.num_queued.iter().enumerate() {
+            counters::pending_outbound_messages(&self.network_context, self.classes.name(class))
+                .sub(*num_queued as i64);
+        }
+    }
+}
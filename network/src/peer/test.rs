@@ -7,11 +7,15 @@ use crate::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
     },
-    peer::{DisconnectReason, Peer, PeerNotification, PeerRequest},
+    peer::{
+        CaptureDirection, CaptureFileReader, CapturedMessage, DisconnectReason, OutboundMessage,
+        OutboundScheduler, Peer, PeerNotification, PeerRequest, PriorityClasses, TrafficCapture,
+    },
     peer_manager::TransportNotification,
     protocols::{
         direct_send::Message,
         rpc::{error::RpcError, InboundRpcRequest, OutboundRpcRequest},
+        stream::{OutboundStream, StreamMessage},
         wire::{
             handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
@@ -24,10 +28,14 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{PeerRole, TrafficCaptureConfig, MAX_OUTBOUND_QUEUED_MESSAGES},
+    network_id::NetworkContext,
+};
 use aptos_memsocket::MemorySocket;
 use aptos_netcore::transport::ConnectionOrigin;
-use aptos_time_service::{MockTimeService, TimeService};
+use aptos_temppath::TempPath;
+use aptos_time_service::{MockTimeService, TimeService, TimeServiceTrait};
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use bytes::Bytes;
 use futures::{
@@ -37,8 +45,14 @@ use futures::{
     stream::{StreamExt, TryStreamExt},
     SinkExt,
 };
-use std::{collections::HashSet, str::FromStr, time::Duration};
-use tokio::runtime::{Handle, Runtime};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::runtime::{Builder, Handle, Runtime};
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
@@ -92,6 +106,10 @@ fn build_test_peer(
         MAX_MESSAGE_SIZE,
         None,
         None,
+        PriorityClasses::default(),
+        MAX_OUTBOUND_QUEUED_MESSAGES,
+        true,
+        None,
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
@@ -282,6 +300,158 @@ fn peer_recv_message() {
     rt.block_on(future::join3(peer.start(), server, client));
 }
 
+fn build_test_capture(path: &Path, max_file_size_bytes: u64, max_files: usize) -> TrafficCapture {
+    let config = TrafficCaptureConfig {
+        path: path.to_path_buf(),
+        max_file_size_bytes,
+        max_files,
+        ..TrafficCaptureConfig::default()
+    };
+    TrafficCapture::new(NetworkContext::mock(), &config).unwrap()
+}
+
+fn read_capture_files(path: &Path) -> Vec<(PathBuf, Vec<CapturedMessage>)> {
+    let mut files: Vec<_> = std::fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .map(|file| {
+            let messages = CaptureFileReader::open(&file)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            (file, messages)
+        })
+        .collect()
+}
+
+// Messages sent and received by a Peer should be recorded when traffic capture
+// is enabled.
+#[test]
+fn peer_capture_traffic() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (mut peer, mut peer_handle, mut connection, _connection_notifs_rx, _peer_notifs_rx) =
+        build_test_peer(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+        );
+    let capture_path = TempPath::new();
+    capture_path.create_as_dir().unwrap();
+    let capture = Arc::new(build_test_capture(capture_path.path(), 1024 * 1024, 2));
+    peer.traffic_capture = Some(capture.clone());
+    let remote_peer_id = peer.remote_peer_id();
+    let (mut client_sink, mut client_stream) = build_network_sink_stream(&mut connection);
+
+    let outbound_msg = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_msg: Vec::from("hello"),
+    });
+    let inbound_msg = NetworkMessage::RpcResponse(RpcResponse {
+        request_id: 0,
+        priority: 0,
+        raw_response: Vec::from("world"),
+    });
+
+    let client = async {
+        // Receive the outbound message, then reply with an (unexpected) response
+        let msg = client_stream.next().await.unwrap().unwrap();
+        assert_eq!(msg, MultiplexMessage::Message(outbound_msg.clone()));
+        client_sink
+            .send(&MultiplexMessage::Message(inbound_msg.clone()))
+            .await
+            .unwrap();
+        client_sink.close().await.unwrap();
+    };
+    let server = async {
+        peer_handle.send_direct_send(Message {
+            protocol_id: PROTOCOL,
+            mdata: Bytes::from("hello"),
+        });
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+    capture.flush();
+
+    // Both messages should have been captured, in order
+    let capture_files = read_capture_files(capture_path.path());
+    assert_eq!(capture_files.len(), 1);
+    let captured_messages = &capture_files[0].1;
+    assert_eq!(captured_messages.len(), 2);
+    assert_eq!(captured_messages[0].remote_peer_id, remote_peer_id);
+    assert_eq!(captured_messages[0].direction, CaptureDirection::Outbound);
+    assert_eq!(captured_messages[0].protocol_id, Some(PROTOCOL));
+    assert_eq!(captured_messages[0].message, outbound_msg);
+    assert_eq!(captured_messages[1].remote_peer_id, remote_peer_id);
+    assert_eq!(captured_messages[1].direction, CaptureDirection::Inbound);
+    assert_eq!(captured_messages[1].protocol_id, None);
+    assert_eq!(captured_messages[1].message, inbound_msg);
+}
+
+// Capture files should be rotated once they're full, and only the most recent
+// files should be kept.
+#[test]
+fn capture_file_rotation() {
+    let capture_path = TempPath::new();
+    capture_path.create_as_dir().unwrap();
+    let capture = build_test_capture(capture_path.path(), 1, 2);
+
+    let peer_id = PeerId::random();
+    let messages: Vec<_> = (0..5u8)
+        .map(|index| {
+            NetworkMessage::DirectSendMsg(DirectSendMsg {
+                protocol_id: PROTOCOL,
+                priority: 0,
+                raw_msg: vec![index],
+            })
+        })
+        .collect();
+    for (index, message) in messages.iter().enumerate() {
+        capture.record(
+            Duration::from_secs(index as u64),
+            peer_id,
+            CaptureDirection::Outbound,
+            Some(PROTOCOL),
+            message,
+        );
+    }
+
+    // Each file holds a single message, and only the last two files are kept
+    capture.flush();
+    let capture_files = read_capture_files(capture_path.path());
+    assert_eq!(capture_files.len(), 2);
+    for ((_, captured_messages), (index, message)) in capture_files
+        .iter()
+        .zip(messages.iter().enumerate().skip(3))
+    {
+        assert_eq!(captured_messages.len(), 1);
+        assert_eq!(
+            captured_messages[0].timestamp_usecs,
+            index as u64 * 1_000_000
+        );
+        assert_eq!(&captured_messages[0].message, message);
+    }
+
+    // A new capture never overwrites the files of a previous one
+    drop(capture);
+    let capture = build_test_capture(capture_path.path(), 1, 2);
+    capture.record(
+        Duration::from_secs(5),
+        peer_id,
+        CaptureDirection::Inbound,
+        Some(PROTOCOL),
+        &messages[0],
+    );
+    capture.flush();
+    let capture_files = read_capture_files(capture_path.path());
+    assert_eq!(capture_files.len(), 3);
+    assert_eq!(capture_files[2].1[0].direction, CaptureDirection::Inbound);
+}
+
 // Two connected Peer actors should be able to send/recv a DirectSend from each
 // other and then shutdown gracefully.
 #[test]
@@ -949,3 +1119,315 @@ fn peers_send_multiplex() {
 
     rt.block_on(future::join3(peer_a.start(), peer_b.start(), test));
 }
+
+fn consensus_message() -> NetworkMessage {
+    NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: ProtocolId::ConsensusDirectSendBcs,
+        priority: 0,
+        raw_msg: Vec::from("vote"),
+    })
+}
+
+fn storage_service_response(size: usize) -> NetworkMessage {
+    NetworkMessage::RpcResponse(RpcResponse {
+        request_id: 0,
+        priority: 0,
+        raw_response: vec![0; size],
+    })
+}
+
+// Consensus messages should overtake a backlog of bulk messages in the scheduler,
+// and backlogged classes should share the bandwidth according to their weights.
+#[test]
+fn scheduler_weighted_fair_queuing() {
+    let classes = PriorityClasses::default();
+    let consensus_class = classes.class_of(Some(ProtocolId::ConsensusDirectSendBcs));
+    let bulk_class = classes.class_of(Some(ProtocolId::StorageServiceRpc));
+    assert_eq!(classes.weight(consensus_class), 16);
+    assert_eq!(classes.weight(bulk_class), 1);
+
+    // Messages without a (known) protocol fall into the last class
+    assert_eq!(classes.class_of(None), classes.num_classes() - 1);
+
+    let time_service = TimeService::mock();
+    let mut scheduler = OutboundScheduler::new(
+        NetworkContext::mock(),
+        classes,
+        MAX_OUTBOUND_QUEUED_MESSAGES,
+        true,
+    );
+
+    // Queue a large backlog of bulk messages, followed by a single consensus message
+    for _ in 0..100 {
+        scheduler.push(
+            bulk_class,
+            storage_service_response(64 * 1024),
+            None,
+            time_service.now(),
+        );
+    }
+    scheduler.push(
+        consensus_class,
+        consensus_message(),
+        None,
+        time_service.now(),
+    );
+    assert_eq!(scheduler.len(), 101);
+
+    // The consensus message should be sent first
+    assert_eq!(
+        scheduler.pop(time_service.now()),
+        Some((MultiplexMessage::Message(consensus_message()), None))
+    );
+
+    // Queue an equally sized backlog of consensus messages
+    let large_consensus_message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: ProtocolId::ConsensusDirectSendBcs,
+        priority: 0,
+        raw_msg: vec![1; 64 * 1024],
+    });
+    for _ in 0..100 {
+        scheduler.push(
+            consensus_class,
+            large_consensus_message.clone(),
+            None,
+            time_service.now(),
+        );
+    }
+
+    // Consensus should receive (roughly) 16 times the bandwidth of bulk traffic
+    let mut num_consensus_messages = 0;
+    for _ in 0..34 {
+        let (message, _) = scheduler.pop(time_service.now()).unwrap();
+        if message == MultiplexMessage::Message(large_consensus_message.clone()) {
+            num_consensus_messages += 1;
+        }
+    }
+    assert!(num_consensus_messages >= 30);
+
+    // Everything should be drained eventually
+    while scheduler.pop(time_service.now()).is_some() {}
+    assert!(scheduler.is_empty());
+}
+
+// A consensus message queued behind a large backlog of storage service responses
+// should be written to the wire before the backlog.
+#[test]
+fn peer_send_consensus_not_starved_by_bulk() {
+    ::aptos_logger::Logger::init_for_testing();
+
+    // Use a single threaded runtime so that all messages are queued before the
+    // writer task gets to run.
+    let rt = Builder::new_current_thread().enable_all().build().unwrap();
+    let (socket, connection) = MemorySocket::new_pair();
+    let writer = MultiplexMessageSink::new(socket, MAX_FRAME_SIZE, None);
+    let (mut write_reqs_tx, _close_tx) = Peer::<MemorySocket>::start_writer_task(
+        rt.handle(),
+        TimeService::mock(),
+        ConnectionMetadata::mock(PeerId::random()),
+        NetworkContext::mock(),
+        writer,
+        MAX_FRAME_SIZE,
+        MAX_MESSAGE_SIZE,
+        PriorityClasses::default(),
+        MAX_OUTBOUND_QUEUED_MESSAGES,
+        true,
+        None,
+    );
+    let mut reader = MultiplexMessageStream::new(connection, MAX_FRAME_SIZE, None);
+
+    let num_bulk_messages = 200;
+    rt.block_on(async move {
+        // Queue the bulk messages (including a streamed one), followed by a consensus message
+        for _ in 0..num_bulk_messages {
+            let message = storage_service_response(64 * 1024);
+            write_reqs_tx
+                .send(OutboundMessage::new(ProtocolId::StorageServiceRpc, message))
+                .await
+                .unwrap();
+        }
+        let message = storage_service_response(2 * MAX_FRAME_SIZE);
+        write_reqs_tx
+            .send(OutboundMessage::new(ProtocolId::StorageServiceRpc, message))
+            .await
+            .unwrap();
+        write_reqs_tx
+            .send(OutboundMessage::new(
+                ProtocolId::ConsensusDirectSendBcs,
+                consensus_message(),
+            ))
+            .await
+            .unwrap();
+
+        // The consensus message should be the first message on the wire
+        let first_message = reader.next().await.unwrap().unwrap();
+        assert_eq!(
+            first_message,
+            MultiplexMessage::Message(consensus_message())
+        );
+
+        // All bulk messages should follow: the regular ones and the stream header
+        // and its fragments.
+        let mut num_messages = 0;
+        while num_messages < num_bulk_messages + 3 {
+            let message = reader.next().await.unwrap().unwrap();
+            assert_ne!(message, MultiplexMessage::Message(consensus_message()));
+            num_messages += 1;
+        }
+    });
+}
+
+// A consensus message should not wait in the write requests channel behind a flood of
+// bulk messages that exceeds the queue limit of the bulk class.
+#[test]
+fn peer_send_consensus_not_blocked_by_full_bulk_class() {
+    ::aptos_logger::Logger::init_for_testing();
+
+    // Use a single threaded runtime and a socket that only buffers a single byte, so that
+    // the writer task is stuck writing the first bulk message while the rest are queued.
+    let rt = Builder::new_current_thread().enable_all().build().unwrap();
+    let (socket, connection) = tokio::io::duplex(1);
+    let writer = MultiplexMessageSink::new(socket.compat_write(), MAX_FRAME_SIZE, None);
+    let (mut write_reqs_tx, _close_tx) = Peer::<MemorySocket>::start_writer_task(
+        rt.handle(),
+        TimeService::mock(),
+        ConnectionMetadata::mock(PeerId::random()),
+        NetworkContext::mock(),
+        writer,
+        MAX_FRAME_SIZE,
+        MAX_MESSAGE_SIZE,
+        PriorityClasses::default(),
+        MAX_OUTBOUND_QUEUED_MESSAGES,
+        true,
+        None,
+    );
+    let mut reader = MultiplexMessageStream::new(connection.compat(), MAX_FRAME_SIZE, None);
+
+    let num_bulk_messages = MAX_OUTBOUND_QUEUED_MESSAGES + 100;
+    rt.block_on(async move {
+        // Flood the bulk class past its queue limit, followed by a consensus message
+        for _ in 0..num_bulk_messages {
+            let message = storage_service_response(1024);
+            write_reqs_tx
+                .send(OutboundMessage::new(ProtocolId::StorageServiceRpc, message))
+                .await
+                .unwrap();
+        }
+        write_reqs_tx
+            .send(OutboundMessage::new(
+                ProtocolId::ConsensusDirectSendBcs,
+                consensus_message(),
+            ))
+            .await
+            .unwrap();
+
+        // Only the bulk message that was being written can go out before the consensus
+        // message.
+        let first_message = reader.next().await.unwrap().unwrap();
+        assert_eq!(
+            first_message,
+            MultiplexMessage::Message(storage_service_response(1024))
+        );
+        let second_message = reader.next().await.unwrap().unwrap();
+        assert_eq!(
+            second_message,
+            MultiplexMessage::Message(consensus_message())
+        );
+    });
+}
+
+fn pop_stream_fragment(
+    scheduler: &mut OutboundScheduler,
+    time_service: &TimeService,
+) -> (StreamMessage, Option<OutboundMessage>) {
+    match scheduler.pop(time_service.now()) {
+        Some((MultiplexMessage::Stream(stream_message), capture)) => (stream_message, capture),
+        message => panic!("Expected a stream message, got: {:?}", message),
+    }
+}
+
+// A streamed consensus message should not wait behind a large bulk stream, and the
+// headers and fragments of streams should count against the class limits.
+#[test]
+fn scheduler_interleaves_streams_by_class() {
+    let classes = PriorityClasses::default();
+    let consensus_class = classes.class_of(Some(ProtocolId::ConsensusDirectSendBcs));
+    let bulk_class = classes.class_of(Some(ProtocolId::StorageServiceRpc));
+    let time_service = TimeService::mock();
+    let mut outbound_stream = OutboundStream::new(1024, 255 * 960);
+
+    for interleave_streams in [true, false] {
+        let mut scheduler = OutboundScheduler::new(
+            NetworkContext::mock(),
+            classes.clone(),
+            100,
+            interleave_streams,
+        );
+
+        // Queue a large bulk stream and start sending it
+        let bulk_stream = outbound_stream
+            .stream_message(storage_service_response(200 * 960))
+            .unwrap();
+        let num_bulk_messages = bulk_stream.len();
+        scheduler.push_stream(bulk_class, bulk_stream, None, time_service.now());
+        assert_eq!(scheduler.len(), num_bulk_messages);
+        assert!(scheduler.is_class_full(bulk_class));
+        assert!(!scheduler.is_class_full(consensus_class));
+        let bulk_request_id = match pop_stream_fragment(&mut scheduler, &time_service) {
+            (StreamMessage::Header(header), None) => header.request_id,
+            message => panic!("Expected the bulk stream header, got: {:?}", message),
+        };
+        pop_stream_fragment(&mut scheduler, &time_service);
+
+        // Queue a streamed consensus message
+        let consensus_message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id: ProtocolId::ConsensusDirectSendBcs,
+            priority: 0,
+            raw_msg: vec![1; 3 * 960],
+        });
+        let capture = OutboundMessage::new(
+            ProtocolId::ConsensusDirectSendBcs,
+            consensus_message.clone(),
+        );
+        let consensus_stream = outbound_stream.stream_message(consensus_message).unwrap();
+        let num_consensus_messages = consensus_stream.len();
+        scheduler.push_stream(
+            consensus_class,
+            consensus_stream,
+            Some(capture),
+            time_service.now(),
+        );
+
+        // Send until the consensus stream completes (it's captured with its last fragment)
+        let mut num_sent_bulk_messages = 2;
+        let mut num_sent_consensus_messages = 0;
+        loop {
+            let (stream_message, capture) = pop_stream_fragment(&mut scheduler, &time_service);
+            let request_id = match &stream_message {
+                StreamMessage::Header(header) => header.request_id,
+                StreamMessage::Fragment(fragment) => fragment.request_id,
+            };
+            if request_id == bulk_request_id {
+                num_sent_bulk_messages += 1;
+                continue;
+            }
+            num_sent_consensus_messages += 1;
+            if capture.is_some() {
+                break;
+            }
+        }
+        assert_eq!(num_sent_consensus_messages, num_consensus_messages);
+        if interleave_streams {
+            // At most one more bulk fragment is sent before the consensus stream completes
+            assert!(num_sent_bulk_messages <= 3);
+        } else {
+            // Otherwise, the consensus stream must wait for the bulk stream to complete
+            assert_eq!(num_sent_bulk_messages, num_bulk_messages);
+        }
+
+        // Everything should be drained eventually
+        while scheduler.pop(time_service.now()).is_some() {}
+        assert!(scheduler.is_empty());
+        assert!(!scheduler.is_class_full(bulk_class));
+    }
+}
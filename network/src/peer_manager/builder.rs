@@ -7,9 +7,10 @@ use crate::{
     counters,
     counters::NETWORK_RATE_LIMIT_METRICS,
     noise::{stream::NoiseStream, HandshakeAuthMode},
+    peer::{PriorityClasses, TrafficCapture},
     peer_manager::{
         conn_notifs_channel, ConnectionRequest, ConnectionRequestSender, PeerManager,
-        PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
+        PeerManagerError, PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
     },
     protocols::{
         network::{NetworkClientConfig, NetworkServiceConfig},
@@ -20,7 +21,7 @@ use crate::{
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{RateLimitConfig, HANDSHAKE_VERSION},
+    config::{PriorityClassConfig, RateLimitConfig, TrafficCaptureConfig, HANDSHAKE_VERSION},
     network_id::NetworkContext,
 };
 use aptos_crypto::x25519;
@@ -86,6 +87,10 @@ struct PeerManagerContext {
     inbound_rate_limit_config: Option<RateLimitConfig>,
     outbound_rate_limit_config: Option<RateLimitConfig>,
     tcp_buffer_cfg: TCPBufferCfg,
+    outbound_priority_classes: Vec<PriorityClassConfig>,
+    max_outbound_queued_messages: usize,
+    interleave_outbound_streams: bool,
+    traffic_capture_config: Option<TrafficCaptureConfig>,
 }
 
 impl PeerManagerContext {
@@ -111,6 +116,10 @@ impl PeerManagerContext {
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_priority_classes: Vec<PriorityClassConfig>,
+        max_outbound_queued_messages: usize,
+        interleave_outbound_streams: bool,
+        traffic_capture_config: Option<TrafficCaptureConfig>,
     ) -> Self {
         Self {
             pm_reqs_tx,
@@ -130,6 +139,10 @@ impl PeerManagerContext {
             inbound_rate_limit_config,
             outbound_rate_limit_config,
             tcp_buffer_cfg,
+            outbound_priority_classes,
+            max_outbound_queued_messages,
+            interleave_outbound_streams,
+            traffic_capture_config,
         }
     }
 
@@ -190,6 +203,10 @@ impl PeerManagerBuilder {
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
         tcp_buffer_cfg: TCPBufferCfg,
+        outbound_priority_classes: Vec<PriorityClassConfig>,
+        max_outbound_queued_messages: usize,
+        interleave_outbound_streams: bool,
+        traffic_capture_config: Option<TrafficCaptureConfig>,
     ) -> Self {
         // Setup channel to send requests to peer manager.
         let (pm_reqs_tx, pm_reqs_rx) = aptos_channel::new(
@@ -227,6 +244,10 @@ impl PeerManagerBuilder {
                 inbound_rate_limit_config,
                 outbound_rate_limit_config,
                 tcp_buffer_cfg,
+                outbound_priority_classes,
+                max_outbound_queued_messages,
+                interleave_outbound_streams,
+                traffic_capture_config,
             )),
             peer_manager: None,
             listen_address,
@@ -259,7 +280,7 @@ impl PeerManagerBuilder {
 
     /// Create the configured transport and start PeerManager.
     /// Return the actual NetworkAddress over which this peer is listening.
-    pub fn build(&mut self, executor: &Handle) -> &mut Self {
+    pub fn build(&mut self, executor: &Handle) -> Result<&mut Self, PeerManagerError> {
         use aptos_types::network_address::Protocol::*;
 
         let transport_context = self
@@ -301,7 +322,7 @@ impl PeerManagerBuilder {
                         enable_proxy_protocol,
                     ),
                     executor,
-                )))
+                )?))
             },
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             [Memory(_)] => Some(TransportPeerManager::Memory(self.build_with_transport(
@@ -317,7 +338,7 @@ impl PeerManagerBuilder {
                     enable_proxy_protocol,
                 ),
                 executor,
-            ))),
+            )?)),
             _ => panic!(
                 "{} Unsupported listen_address: '{}', expected '/memory/<port>', \
                  '/ip4/<addr>/tcp/<port>', or '/ip6/<addr>/tcp/<port>'.",
@@ -325,7 +346,7 @@ impl PeerManagerBuilder {
             ),
         };
 
-        self
+        Ok(self)
     }
 
     /// Given a transport build and launch PeerManager.
@@ -334,7 +355,7 @@ impl PeerManagerBuilder {
         &mut self,
         transport: TTransport,
         executor: &Handle,
-    ) -> PeerManager<TTransport, TSocket>
+    ) -> Result<PeerManager<TTransport, TSocket>, PeerManagerError>
     where
         TTransport: Transport<Output = Connection<TSocket>> + Send + 'static,
         TSocket: transport::TSocket,
@@ -353,6 +374,11 @@ impl PeerManagerBuilder {
             "outbound",
             pm_context.outbound_rate_limit_config,
         );
+        let traffic_capture = pm_context
+            .traffic_capture_config
+            .as_ref()
+            .map(|config| TrafficCapture::new(self.network_context, config).map(Arc::new))
+            .transpose()?;
         let peer_mgr = PeerManager::new(
             executor.clone(),
             self.time_service.clone(),
@@ -373,12 +399,16 @@ impl PeerManagerBuilder {
             pm_context.inbound_connection_limit,
             inbound_rate_limiters,
             outbound_rate_limiters,
+            PriorityClasses::new(&pm_context.outbound_priority_classes),
+            pm_context.max_outbound_queued_messages,
+            pm_context.interleave_outbound_streams,
+            traffic_capture,
         );
 
         // PeerManager constructor appends a public key to the listen_address.
         self.listen_address = peer_mgr.listen_addr().clone();
 
-        peer_mgr
+        Ok(peer_mgr)
     }
 
     fn start_peer_manager<TTransport, TSocket>(
@@ -15,7 +15,7 @@ use crate::{
     constants,
     counters::{self},
     logging::*,
-    peer::{Peer, PeerNotification, PeerRequest},
+    peer::{Peer, PeerNotification, PeerRequest, PriorityClasses, TrafficCapture},
     transport::{
         Connection, ConnectionId, ConnectionMetadata, TSocket as TransportTSocket,
         TRANSPORT_TIMEOUT,
@@ -127,6 +127,14 @@ where
     inbound_rate_limiters: IpAddrTokenBucketLimiter,
     /// Keyed storage of all outbound rate limiters
     outbound_rate_limiters: IpAddrTokenBucketLimiter,
+    /// Priority classes used by peers to schedule outbound messages
+    outbound_priority_classes: PriorityClasses,
+    /// Maximum number of outbound frames queued for scheduling per priority class of each peer
+    max_outbound_queued_messages: usize,
+    /// Whether the outbound streams of different priority classes may be interleaved
+    interleave_outbound_streams: bool,
+    /// Optional capture of the traffic exchanged with all peers
+    traffic_capture: Option<Arc<TrafficCapture>>,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -157,6 +165,10 @@ where
         inbound_connection_limit: usize,
         inbound_rate_limiters: IpAddrTokenBucketLimiter,
         outbound_rate_limiters: IpAddrTokenBucketLimiter,
+        outbound_priority_classes: PriorityClasses,
+        max_outbound_queued_messages: usize,
+        interleave_outbound_streams: bool,
+        traffic_capture: Option<Arc<TrafficCapture>>,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = aptos_channels::new(
             channel_size,
@@ -201,6 +213,10 @@ where
             inbound_connection_limit,
             inbound_rate_limiters,
             outbound_rate_limiters,
+            outbound_priority_classes,
+            max_outbound_queued_messages,
+            interleave_outbound_streams,
+            traffic_capture,
         }
     }
 
@@ -726,6 +742,10 @@ where
             self.max_message_size,
             Some(inbound_rate_limiter),
             Some(outbound_rate_limiter),
+            self.outbound_priority_classes.clone(),
+            self.max_outbound_queued_messages,
+            self.interleave_outbound_streams,
+            self.traffic_capture.clone(),
         );
         self.executor.spawn(peer.start());
 
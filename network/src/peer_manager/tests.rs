@@ -5,7 +5,7 @@
 use crate::{
     application::storage::PeersAndMetadata,
     constants,
-    peer::DisconnectReason,
+    peer::{DisconnectReason, PriorityClasses},
     peer_manager::{
         conn_notifs_channel, error::PeerManagerError, ConnectionNotification, ConnectionRequest,
         PeerManager, PeerManagerNotification, PeerManagerRequest, TransportNotification,
@@ -24,7 +24,7 @@ use crate::{
 use anyhow::anyhow;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{PeerRole, MAX_INBOUND_CONNECTIONS},
+    config::{PeerRole, MAX_INBOUND_CONNECTIONS, MAX_OUTBOUND_QUEUED_MESSAGES},
     network_id::{NetworkContext, NetworkId},
 };
 use aptos_memsocket::MemorySocket;
@@ -118,6 +118,10 @@ fn build_test_peer_manager(
         MAX_INBOUND_CONNECTIONS,
         TokenBucketRateLimiter::open("inbound"),
         TokenBucketRateLimiter::open("outbound"),
+        PriorityClasses::default(),
+        MAX_OUTBOUND_QUEUED_MESSAGES,
+        true,
+        None,
     );
 
     (
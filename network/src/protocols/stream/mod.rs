@@ -1,15 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::protocols::wire::messaging::v1::{MultiplexMessage, NetworkMessage};
+use crate::protocols::wire::messaging::v1::NetworkMessage;
 use anyhow::{bail, ensure};
-use aptos_channels::Sender;
 use aptos_id_generator::{IdGenerator, U32IdGenerator};
-use futures_util::SinkExt;
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug};
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
@@ -58,41 +56,62 @@ impl Debug for StreamFragment {
     }
 }
 
+/// Reassembles the streamed messages received from a peer. Streams of different priority
+/// classes may be interleaved by the sender, so up to `max_streams` streams are buffered at
+/// once (keyed by their request id). Fragments of the same stream must arrive in order.
 pub struct InboundStreamBuffer {
-    stream: Option<InboundStream>,
+    streams: HashMap<u32, InboundStream>,
     max_fragments: usize,
+    max_streams: usize,
 }
 
 impl InboundStreamBuffer {
-    pub fn new(max_fragments: usize) -> Self {
+    pub fn new(max_fragments: usize, max_streams: usize) -> Self {
         Self {
-            stream: None,
+            streams: HashMap::new(),
             max_fragments,
+            max_streams: max_streams.max(1),
         }
     }
 
     pub fn new_stream(&mut self, header: StreamHeader) -> anyhow::Result<()> {
-        if let Some(old) = self
-            .stream
-            .replace(InboundStream::new(header, self.max_fragments)?)
-        {
-            bail!("Discard existing stream {}", old.request_id)
-        } else {
-            Ok(())
+        let request_id = header.request_id;
+        let stream = InboundStream::new(header, self.max_fragments)?;
+        if self.streams.insert(request_id, stream).is_some() {
+            bail!("Discard existing stream {}", request_id)
+        }
+        if self.streams.len() > self.max_streams {
+            self.streams.remove(&request_id);
+            bail!(
+                "Discard stream {}, exceeds the max concurrent streams limit {}",
+                request_id,
+                self.max_streams
+            )
         }
+        Ok(())
     }
 
     pub fn append_fragment(
         &mut self,
         fragment: StreamFragment,
     ) -> anyhow::Result<Option<NetworkMessage>> {
+        let request_id = fragment.request_id;
         let stream = self
-            .stream
-            .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("No stream exist"))?;
-        let stream_end = stream.append_fragment(fragment)?;
+            .streams
+            .get_mut(&request_id)
+            .ok_or_else(|| anyhow::anyhow!("No stream exist for request {}", request_id))?;
+        let stream_end = match stream.append_fragment(fragment) {
+            Ok(stream_end) => stream_end,
+            Err(error) => {
+                self.streams.remove(&request_id);
+                return Err(error);
+            },
+        };
         if stream_end {
-            Ok(Some(self.stream.take().unwrap().message))
+            Ok(self
+                .streams
+                .remove(&request_id)
+                .map(|stream| stream.message))
         } else {
             Ok(None)
         }
@@ -100,7 +119,6 @@ impl InboundStreamBuffer {
 }
 
 pub struct InboundStream {
-    request_id: u32,
     num_fragments: u8,
     current_fragment_id: u8,
     message: NetworkMessage,
@@ -117,7 +135,6 @@ impl InboundStream {
             "Stream header exceeds max fragments limit"
         );
         Ok(Self {
-            request_id: header.request_id,
             num_fragments: header.num_fragments,
             current_fragment_id: 0,
             message: header.message,
@@ -125,10 +142,6 @@ impl InboundStream {
     }
 
     fn append_fragment(&mut self, mut fragment: StreamFragment) -> anyhow::Result<bool> {
-        ensure!(
-            self.request_id == fragment.request_id,
-            "Stream fragment from a different request"
-        );
         ensure!(
             self.current_fragment_id + 1 == fragment.fragment_id,
             "Unexpected fragment id, expected {}, got {}",
@@ -151,15 +164,10 @@ pub struct OutboundStream {
     request_id_gen: U32IdGenerator,
     max_frame_size: usize,
     max_message_size: usize,
-    stream_tx: Sender<MultiplexMessage>,
 }
 
 impl OutboundStream {
-    pub fn new(
-        max_frame_size: usize,
-        max_message_size: usize,
-        stream_tx: Sender<MultiplexMessage>,
-    ) -> Self {
+    pub fn new(max_frame_size: usize, max_message_size: usize) -> Self {
         // some buffer for headers
         let max_frame_size = max_frame_size - 64;
         assert!(
@@ -172,7 +180,6 @@ impl OutboundStream {
             request_id_gen: U32IdGenerator::new(),
             max_frame_size,
             max_message_size,
-            stream_tx,
         }
     }
 
@@ -180,7 +187,13 @@ impl OutboundStream {
         message.data_len() > self.max_frame_size
     }
 
-    pub async fn stream_message(&mut self, mut message: NetworkMessage) -> anyhow::Result<()> {
+    /// Splits the given message into a stream header followed by its fragments. The
+    /// fragments are only copied out of the message as they are returned, and must be
+    /// sent in order.
+    pub fn stream_message(
+        &mut self,
+        mut message: NetworkMessage,
+    ) -> anyhow::Result<OutboundStreamMessages> {
         ensure!(
             message.data_len() <= self.max_message_size,
             "Message length {} exceed size limit {}",
@@ -208,29 +221,86 @@ impl OutboundStream {
                 message.raw_msg.split_off(self.max_frame_size)
             },
         };
-        let chunks = rest.chunks(self.max_frame_size);
+        let num_fragments = (rest.len() + self.max_frame_size - 1) / self.max_frame_size;
         ensure!(
-            chunks.len() <= u8::MAX as usize,
+            num_fragments <= u8::MAX as usize,
             "Number of fragments overflowed"
         );
-        let header = StreamMessage::Header(StreamHeader {
-            request_id,
-            num_fragments: chunks.len() as u8,
-            message,
-        });
-        self.stream_tx
-            .send(MultiplexMessage::Stream(header))
-            .await?;
-        for (index, chunk) in chunks.enumerate() {
-            let message = StreamMessage::Fragment(StreamFragment {
+        Ok(OutboundStreamMessages {
+            header: Some(StreamHeader {
                 request_id,
-                fragment_id: index as u8 + 1,
-                raw_data: Vec::from(chunk),
-            });
-            self.stream_tx
-                .send(MultiplexMessage::Stream(message))
-                .await?;
+                num_fragments: num_fragments as u8,
+                message,
+            }),
+            request_id,
+            rest,
+            offset: 0,
+            next_fragment_id: 1,
+            max_frame_size: self.max_frame_size,
+        })
+    }
+}
+
+/// The header and fragments of a streamed message, produced lazily (see
+/// [`OutboundStream::stream_message`]).
+pub struct OutboundStreamMessages {
+    header: Option<StreamHeader>,
+    request_id: u32,
+    rest: Vec<u8>,
+    offset: usize,
+    next_fragment_id: u8,
+    max_frame_size: usize,
+}
+
+impl OutboundStreamMessages {
+    /// Returns the size of the payload that remains to be sent
+    pub fn remaining_data_len(&self) -> usize {
+        let header_len = self
+            .header
+            .as_ref()
+            .map_or(0, |header| header.message.data_len());
+        header_len + self.rest.len() - self.offset
+    }
+
+    /// Returns the size of the payload carried by the next message (if any)
+    pub fn next_data_len(&self) -> Option<usize> {
+        match &self.header {
+            Some(header) => Some(header.message.data_len()),
+            None if self.offset < self.rest.len() => {
+                Some((self.rest.len() - self.offset).min(self.max_frame_size))
+            },
+            None => None,
         }
-        Ok(())
     }
 }
+
+impl Iterator for OutboundStreamMessages {
+    type Item = StreamMessage;
+
+    fn next(&mut self) -> Option<StreamMessage> {
+        if let Some(header) = self.header.take() {
+            return Some(StreamMessage::Header(header));
+        }
+        if self.offset >= self.rest.len() {
+            return None;
+        }
+        let end = (self.offset + self.max_frame_size).min(self.rest.len());
+        let fragment = StreamFragment {
+            request_id: self.request_id,
+            fragment_id: self.next_fragment_id,
+            raw_data: self.rest[self.offset..end].to_vec(),
+        };
+        self.offset = end;
+        self.next_fragment_id = self.next_fragment_id.wrapping_add(1);
+        Some(StreamMessage::Fragment(fragment))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let num_fragments =
+            (self.rest.len() - self.offset + self.max_frame_size - 1) / self.max_frame_size;
+        let len = num_fragments + usize::from(self.header.is_some());
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for OutboundStreamMessages {}
@@ -56,6 +56,8 @@ pub enum ProtocolId {
     PeerMonitoringServiceRpc = 10,
     ConsensusRpcCompressed = 11,
     ConsensusDirectSendCompressed = 12,
+    ConsensusObserver = 13,
+    ConsensusObserverRpc = 14,
 }
 
 /// The encoding types for Protocols
@@ -82,6 +84,8 @@ impl ProtocolId {
             PeerMonitoringServiceRpc => "PeerMonitoringServiceRpc",
             ConsensusRpcCompressed => "ConsensusRpcCompressed",
             ConsensusDirectSendCompressed => "ConsensusDirectSendCompressed",
+            ConsensusObserver => "ConsensusObserver",
+            ConsensusObserverRpc => "ConsensusObserverRpc",
         }
     }
 
@@ -100,6 +104,8 @@ impl ProtocolId {
             ProtocolId::PeerMonitoringServiceRpc,
             ProtocolId::ConsensusRpcCompressed,
             ProtocolId::ConsensusDirectSendCompressed,
+            ProtocolId::ConsensusObserver,
+            ProtocolId::ConsensusObserverRpc,
         ]
     }
 
@@ -107,9 +113,10 @@ impl ProtocolId {
     fn encoding(self) -> Encoding {
         match self {
             ProtocolId::ConsensusDirectSendJson | ProtocolId::ConsensusRpcJson => Encoding::Json,
-            ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
-                Encoding::CompressedBcs(RECURSION_LIMIT)
-            },
+            ProtocolId::ConsensusDirectSendCompressed
+            | ProtocolId::ConsensusRpcCompressed
+            | ProtocolId::ConsensusObserver
+            | ProtocolId::ConsensusObserverRpc => Encoding::CompressedBcs(RECURSION_LIMIT),
             ProtocolId::MempoolDirectSend => Encoding::CompressedBcs(USER_INPUT_RECURSION_LIMIT),
             ProtocolId::MempoolRpc => Encoding::Bcs(USER_INPUT_RECURSION_LIMIT),
             _ => Encoding::Bcs(RECURSION_LIMIT),
@@ -119,9 +126,10 @@ impl ProtocolId {
     /// Returns the compression client label based on the current protocol id
     fn get_compression_client(self) -> CompressionClient {
         match self {
-            ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
-                CompressionClient::Consensus
-            },
+            ProtocolId::ConsensusDirectSendCompressed
+            | ProtocolId::ConsensusRpcCompressed
+            | ProtocolId::ConsensusObserver
+            | ProtocolId::ConsensusObserverRpc => CompressionClient::Consensus,
             ProtocolId::MempoolDirectSend => CompressionClient::Mempool,
             protocol_id => unreachable!(
                 "The given protocol ({:?}) should not be using compression!",
@@ -169,6 +177,26 @@ impl ProtocolId {
         }
     }
 
+    /// Returns the raw BCS bytes of an encoded message (i.e., decompresses the message
+    /// if required), or `None` if the protocol doesn't use BCS. This is useful to
+    /// inspect messages without knowing their type.
+    pub fn bcs_payload(&self, bytes: &[u8]) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.encoding() {
+            Encoding::Bcs(_) => Ok(Some(bytes.to_vec())),
+            Encoding::CompressedBcs(_) => {
+                let compression_client = self.get_compression_client();
+                aptos_compression::decompress(
+                    &bytes.to_vec(),
+                    compression_client,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )
+                .map(Some)
+                .map_err(|e| anyhow!("{:?}", e))
+            },
+            Encoding::Json => Ok(None),
+        }
+    }
+
     fn bcs_encode<T: Serialize>(&self, value: &T, limit: usize) -> anyhow::Result<Vec<u8>> {
         bcs::to_bytes_with_limit(value, limit).map_err(|e| anyhow!("{:?}", e))
     }
@@ -63,6 +63,15 @@ impl NetworkMessage {
             NetworkMessage::DirectSendMsg(message) => message.raw_msg.len(),
         }
     }
+
+    /// The protocol of the message, if it's carried in the message itself
+    pub fn protocol_id(&self) -> Option<ProtocolId> {
+        match self {
+            NetworkMessage::Error(_) | NetworkMessage::RpcResponse(_) => None,
+            NetworkMessage::RpcRequest(request) => Some(request.protocol_id),
+            NetworkMessage::DirectSendMsg(message) => Some(message.protocol_id),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -4,7 +4,9 @@
 
 use super::*;
 use crate::{
-    protocols::stream::{InboundStreamBuffer, OutboundStream, StreamFragment, StreamHeader},
+    protocols::stream::{
+        InboundStreamBuffer, OutboundStream, StreamFragment, StreamHeader, StreamMessage,
+    },
     testutils::fake_socket::{ReadOnlyTestSocket, ReadWriteTestSocket},
 };
 use aptos_memsocket::MemorySocket;
@@ -76,6 +78,62 @@ fn stream_message() {
     ],);
 }
 
+#[test]
+fn inbound_stream_interleaved() {
+    let messages: Vec<_> = (0..3_u8)
+        .map(|index| {
+            NetworkMessage::DirectSendMsg(DirectSendMsg {
+                protocol_id: ProtocolId::MempoolDirectSend,
+                priority: 0,
+                raw_msg: vec![index; 1000],
+            })
+        })
+        .collect();
+
+    // Fragments of the streams are produced lazily, and are sized by the frame size
+    let mut outbound_stream = OutboundStream::new(128, 64 * 255);
+    let streams: Vec<Vec<StreamMessage>> = messages
+        .iter()
+        .map(|message| {
+            let stream_messages = outbound_stream.stream_message(message.clone()).unwrap();
+            assert_eq!(stream_messages.len(), 16);
+            stream_messages.collect()
+        })
+        .collect();
+
+    // A third concurrent stream exceeds the limit of the buffer
+    let mut inbound_stream = InboundStreamBuffer::new(255, 2);
+    let mut stream_messages: Vec<_> = streams.into_iter().map(Vec::into_iter).collect();
+    for stream in stream_messages.iter_mut().take(2) {
+        match stream.next().unwrap() {
+            StreamMessage::Header(header) => inbound_stream.new_stream(header).unwrap(),
+            StreamMessage::Fragment(_) => panic!("Expected a stream header"),
+        }
+    }
+    match stream_messages[2].next().unwrap() {
+        StreamMessage::Header(header) => inbound_stream.new_stream(header).unwrap_err(),
+        StreamMessage::Fragment(_) => panic!("Expected a stream header"),
+    };
+
+    // The fragments of the first two streams can be interleaved
+    let mut received = vec![];
+    loop {
+        let mut appended_fragment = false;
+        for stream in stream_messages.iter_mut().take(2) {
+            if let Some(StreamMessage::Fragment(fragment)) = stream.next() {
+                appended_fragment = true;
+                if let Some(message) = inbound_stream.append_fragment(fragment).unwrap() {
+                    received.push(message);
+                }
+            }
+        }
+        if !appended_fragment {
+            break;
+        }
+    }
+    assert_eq!(received, messages[..2].to_vec());
+}
+
 #[test]
 fn aptosnet_wire_test_vectors() {
     let message = MultiplexMessage::Message(NetworkMessage::DirectSendMsg(DirectSendMsg {
@@ -235,16 +293,18 @@ proptest! {
 
         let mut message_tx = MultiplexMessageSink::new(socket_tx, 128, None);
         let message_rx = MultiplexMessageStream::new(socket_rx, 128, None);
-        let (stream_tx, stream_rx) = aptos_channels::new_test(1024);
+        let (mut stream_tx, stream_rx) = aptos_channels::new_test(1024);
         let (mut msg_tx, msg_rx) = aptos_channels::new_test(1024);
-        let mut outbound_stream = OutboundStream::new(128, 64 * 255, stream_tx);
-        let mut inbound_stream = InboundStreamBuffer::new(255);
+        let mut outbound_stream = OutboundStream::new(128, 64 * 255);
+        let mut inbound_stream = InboundStreamBuffer::new(255, 1);
 
         let messages_clone = messages.clone();
         let f_stream_all = async move {
             for message in messages_clone {
                 if outbound_stream.should_stream(&message) {
-                    outbound_stream.stream_message(message).await.unwrap();
+                    for stream_message in outbound_stream.stream_message(message).unwrap() {
+                        stream_tx.send(MultiplexMessage::Stream(stream_message)).await.unwrap();
+                    }
                 } else {
                     msg_tx.send(MultiplexMessage::Message(message)).await.unwrap();
                 }
@@ -11,8 +11,10 @@
 //! * `crypto` - Types used for signing and verifying
 //! * `move_types` - Includes types used when interacting with the Move VM
 //! * `rest_client` - The Aptos API Client, used for sending requests to the Aptos Blockchain.
+//! * `secp256k1_ecdsa` - Includes helpers for signing transactions with secp256k1 ECDSA keys
 //! * `transaction_builder` - Includes helpers for constructing transactions
 //! * `types` - Includes types for Aptos on-chain data structures
+//! * `webauthn` - Includes helpers for signing transactions with passkeys
 //!
 //! ## Example
 //!
@@ -36,6 +38,10 @@ pub mod rest_client {
     pub use aptos_rest_client::*;
 }
 
+pub mod secp256k1_ecdsa;
+
 pub mod transaction_builder;
 
 pub mod types;
+
+pub mod webauthn;
@@ -0,0 +1,164 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers to sign transactions with secp256k1 ECDSA keys.
+//!
+//! Accounts authenticated by a secp256k1 ECDSA key, e.g. the key of an existing Ethereum or
+//! Bitcoin wallet, have an address derived from the secp256k1 ECDSA authentication key scheme.
+//! They sign transactions with a `Secp256k1EcdsaLocalAccount` instead of a `LocalAccount`.
+
+use crate::{
+    crypto::{
+        secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey},
+        traits::Uniform,
+    },
+    transaction_builder::TransactionBuilder,
+    types::{
+        account_address::AccountAddress,
+        transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+    },
+};
+
+/// Local representation of an account authenticated by a secp256k1 ECDSA key. It holds the
+/// private / public key pair and the address of the account.
+#[derive(Debug)]
+pub struct Secp256k1EcdsaLocalAccount {
+    /// Address of the account.
+    address: AccountAddress,
+    private_key: Secp256k1EcdsaPrivateKey,
+    public_key: Secp256k1EcdsaPublicKey,
+    /// Latest known sequence number of the account, it can be different from validator.
+    sequence_number: u64,
+}
+
+impl Secp256k1EcdsaLocalAccount {
+    /// Create a new representation of the account of `private_key` locally, with the address
+    /// derived from its authentication key. Note: This function does not actually create an
+    /// account on the Aptos blockchain.
+    pub fn new(private_key: Secp256k1EcdsaPrivateKey, sequence_number: u64) -> Self {
+        let public_key = Secp256k1EcdsaPublicKey::from(&private_key);
+        let address = AuthenticationKey::secp256k1_ecdsa(&public_key).derived_address();
+        Self {
+            address,
+            private_key,
+            public_key,
+            sequence_number,
+        }
+    }
+
+    /// Generate a new account locally. Note: This function does not actually create an account
+    /// on the Aptos blockchain.
+    pub fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+    {
+        Self::new(Secp256k1EcdsaPrivateKey::generate(rng), 0)
+    }
+
+    pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
+        txn.sign_secp256k1_ecdsa(&self.private_key, self.public_key.clone())
+            .expect("Signing a txn can't fail")
+            .into_inner()
+    }
+
+    pub fn sign_with_transaction_builder(
+        &mut self,
+        builder: TransactionBuilder,
+    ) -> SignedTransaction {
+        let raw_txn = builder
+            .sender(self.address())
+            .sequence_number(self.sequence_number())
+            .build();
+        *self.sequence_number_mut() += 1;
+        self.sign_transaction(raw_txn)
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.address
+    }
+
+    pub fn private_key(&self) -> &Secp256k1EcdsaPrivateKey {
+        &self.private_key
+    }
+
+    pub fn public_key(&self) -> &Secp256k1EcdsaPublicKey {
+        &self.public_key
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        AuthenticationKey::secp256k1_ecdsa(&self.public_key)
+    }
+
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    pub fn sequence_number_mut(&mut self) -> &mut u64 {
+        &mut self.sequence_number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transaction_builder::TransactionFactory,
+        types::{chain_id::ChainId, transaction::authenticator::TransactionAuthenticator},
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_sign_transaction() {
+        let mut account = Secp256k1EcdsaLocalAccount::generate(&mut StdRng::from_seed([0; 32]));
+        let factory = TransactionFactory::new(ChainId::new(4));
+        let txn = account.sign_with_transaction_builder(factory.transfer(AccountAddress::TWO, 10));
+        assert_eq!(account.sequence_number(), 1);
+        assert_eq!(txn.sender(), account.address());
+        assert_eq!(txn.sequence_number(), 0);
+        assert!(matches!(
+            txn.authenticator(),
+            TransactionAuthenticator::Secp256k1Ecdsa { public_key, .. }
+                if &public_key == account.public_key()
+        ));
+        assert!(txn.clone().check_signature().is_ok());
+
+        // An account imported from the key bytes signs for the same address
+        let private_key =
+            Secp256k1EcdsaPrivateKey::try_from(account.private_key().to_bytes().as_slice())
+                .unwrap();
+        let imported = Secp256k1EcdsaLocalAccount::new(private_key, 1);
+        assert_eq!(imported.address(), account.address());
+        assert_eq!(
+            imported.address(),
+            imported.authentication_key().derived_address()
+        );
+
+        // The signature is bound to the transaction and to the key
+        let raw_txn = factory
+            .transfer(AccountAddress::TWO, 10)
+            .sender(account.address())
+            .sequence_number(1)
+            .build();
+        let other = Secp256k1EcdsaLocalAccount::generate(&mut StdRng::from_seed([1; 32]));
+        let signature = match other.sign_transaction(raw_txn.clone()).authenticator() {
+            TransactionAuthenticator::Secp256k1Ecdsa { signature, .. } => signature,
+            authenticator => panic!("Unexpected authenticator: {:?}", authenticator),
+        };
+        let forged = SignedTransaction::new_secp256k1_ecdsa(
+            raw_txn,
+            account.public_key().clone(),
+            signature.clone(),
+        );
+        assert!(forged.check_signature().is_err());
+        let tampered = SignedTransaction::new_secp256k1_ecdsa(
+            factory
+                .transfer(AccountAddress::TWO, 11)
+                .sender(account.address())
+                .sequence_number(1)
+                .build(),
+            other.public_key().clone(),
+            signature,
+        );
+        assert!(tampered.check_signature().is_err());
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers to sign transactions with passkeys.
+//!
+//! A passkey signs the challenge of a transaction, as returned by `transaction_challenge`, when it
+//! is passed as the `challenge` of `navigator.credentials.get()`. The fields of the resulting
+//! `AuthenticatorAssertionResponse` are then turned into a `SignedTransaction` by
+//! `signed_transaction`.
+
+use crate::{
+    crypto::{
+        secp256r1_ecdsa::{Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature},
+        HashValue,
+    },
+    types::transaction::{
+        webauthn::{self, PartialAuthenticatorAssertionResponse},
+        RawTransaction, SignedTransaction,
+    },
+};
+use anyhow::Result;
+
+/// Returns the challenge a passkey signs to authenticate `raw_txn`.
+pub fn transaction_challenge(raw_txn: &RawTransaction) -> HashValue {
+    webauthn::challenge(raw_txn).expect("Serializing a transaction can't fail")
+}
+
+/// Builds a transaction signed by the passkey of `public_key`, from its assertion on the challenge
+/// of `raw_txn`. The signature is in the DER form returned by authenticators, and the assertion is
+/// verified before building the transaction.
+pub fn signed_transaction(
+    raw_txn: RawTransaction,
+    public_key: Secp256r1EcdsaPublicKey,
+    authenticator_data: Vec<u8>,
+    client_data_json: Vec<u8>,
+    der_signature: &[u8],
+) -> Result<SignedTransaction> {
+    let signature = Secp256r1EcdsaSignature::from_der(der_signature)?;
+    let assertion =
+        PartialAuthenticatorAssertionResponse::new(signature, authenticator_data, client_data_json);
+    assertion.verify(&raw_txn, &public_key)?;
+    Ok(SignedTransaction::new_webauthn(
+        raw_txn, public_key, assertion,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{account_address::AccountAddress, chain_id::ChainId};
+    use aptos_cached_packages::aptos_stdlib;
+
+    // An assertion of a passkey, made in a browser, on the challenge of `raw_transaction`
+    const PUBLIC_KEY: &str = "04254b4aac44935df13a1112d3bcb2adde9814850f8623dec38447a184911e92d7\
+                              570cdfe38780ed5eaaab059353aa8c348d4fb79df42abc3dc368ca6df4fb8030";
+    const CHALLENGE: &str = "57cf5d690341ed82064d6ee35da03b38b63df9fc2bb76e57975b159af8adb938";
+    const AUTHENTICATOR_DATA: &str =
+        "49960de5880e8c687434170f6476605b8fe4aeb9a28632c7995cf3ba831d97630500000001";
+    const CLIENT_DATA_JSON: &str = r#"{"type":"webauthn.get","challenge":"V89daQNB7YIGTW7jXaA7OLY9-fwrt25Xl1sVmvituTg","origin":"http://localhost:5173","crossOrigin":false}"#;
+    const DER_SIGNATURE: &str = "304502200c4847750a6e5098854e436de20f44359696bd98aa0caa5b571e55d6\
+                                 ee972a0e022100aeede73d069755579c6d280b7023d5b79a501ec722454b01\
+                                 788f2d693cbab9e2";
+    // The signature has a high S, which is normalized
+    const SIGNATURE: &str = "0c4847750a6e5098854e436de20f44359696bd98aa0caa5b571e55d6ee972a0e\
+                             511218c1f968aaa96392d7f48fdc2a482296dbe684d253837b2a9d59bfa86b6f";
+
+    fn raw_transaction(sequence_number: u64) -> RawTransaction {
+        RawTransaction::new(
+            AccountAddress::from_hex_literal("0xcafe").unwrap(),
+            sequence_number,
+            aptos_stdlib::aptos_account_transfer(AccountAddress::TWO, 100),
+            2000,
+            100,
+            1_700_000_000,
+            ChainId::new(4),
+        )
+    }
+
+    fn public_key() -> Secp256r1EcdsaPublicKey {
+        Secp256r1EcdsaPublicKey::try_from(hex::decode(PUBLIC_KEY).unwrap().as_slice()).unwrap()
+    }
+
+    fn sign(
+        raw_txn: RawTransaction,
+        authenticator_data: Vec<u8>,
+        client_data_json: &str,
+    ) -> Result<SignedTransaction> {
+        signed_transaction(
+            raw_txn,
+            public_key(),
+            authenticator_data,
+            client_data_json.as_bytes().to_vec(),
+            &hex::decode(DER_SIGNATURE).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_transaction_challenge() {
+        assert_eq!(
+            transaction_challenge(&raw_transaction(7)).to_hex(),
+            CHALLENGE
+        );
+        assert_ne!(
+            transaction_challenge(&raw_transaction(8)).to_hex(),
+            CHALLENGE
+        );
+    }
+
+    #[test]
+    fn test_signed_transaction() {
+        let authenticator_data = hex::decode(AUTHENTICATOR_DATA).unwrap();
+        let txn = sign(raw_transaction(7), authenticator_data, CLIENT_DATA_JSON).unwrap();
+        assert!(txn.clone().check_signature().is_ok());
+
+        // The transaction carries the normalized signature
+        let signature =
+            Secp256r1EcdsaSignature::try_from(hex::decode(SIGNATURE).unwrap().as_slice()).unwrap();
+        let assertion = PartialAuthenticatorAssertionResponse::new(
+            signature,
+            hex::decode(AUTHENTICATOR_DATA).unwrap(),
+            CLIENT_DATA_JSON.as_bytes().to_vec(),
+        );
+        assert_eq!(
+            txn,
+            SignedTransaction::new_webauthn(raw_transaction(7), public_key(), assertion)
+        );
+    }
+
+    #[test]
+    fn test_invalid_assertions() {
+        let authenticator_data = hex::decode(AUTHENTICATOR_DATA).unwrap();
+
+        // The assertion is bound to the transaction
+        assert!(sign(
+            raw_transaction(8),
+            authenticator_data.clone(),
+            CLIENT_DATA_JSON
+        )
+        .is_err());
+
+        // Assertions made when creating a credential are rejected
+        let creation = CLIENT_DATA_JSON.replace("webauthn.get", "webauthn.create");
+        assert!(sign(raw_transaction(7), authenticator_data.clone(), &creation).is_err());
+
+        // And so are assertions made without the user being present
+        let mut absent = authenticator_data;
+        absent[32] &= !0x01;
+        assert!(sign(raw_transaction(7), absent, CLIENT_DATA_JSON).is_err());
+    }
+}
@@ -15,12 +15,22 @@
 //!
 //! Internally both the client and server leverage a NetworkStream that communications in blocks
 //! where a block is a length prefixed array of bytes.
-
+//!
+//! Clients and servers can optionally authenticate each other with pinned x25519 keys. In that
+//! case every new connection starts with a Noise IK handshake (using the service name as the
+//! prologue), the server only accepts clients with a trusted static key, and all the blocks that
+//! follow are encrypted.
+
+use aptos_crypto::{
+    noise::{self, NoiseConfig, NoiseError, NoiseSession},
+    x25519,
+};
 use aptos_logger::{info, trace, warn, Schema};
 use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
 use once_cell::sync::Lazy;
 use serde::Serialize;
 use std::{
+    collections::HashSet,
     io::{Read, Write},
     net::{Shutdown, SocketAddr, TcpListener, TcpStream},
     thread, time,
@@ -56,6 +66,7 @@ enum LogEvent {
     ConnectionAttempt,
     ConnectionSuccessful,
     ConnectionFailed,
+    HandshakeFailed,
     DisconnectedPeerOnRead,
     DisconnectedPeerOnWrite,
     Shutdown,
@@ -137,16 +148,34 @@ fn increment_counter(
 pub enum Error {
     #[error("Already called shutdown")]
     AlreadyShutdown,
+    #[error("Unable to connect to {0} within {1} ms")]
+    ConnectionTimeout(SocketAddr, u64),
     #[error("Found data that is too large to decode: {0}")]
     DataTooLarge(usize),
     #[error("Internal network error:")]
     NetworkError(#[from] std::io::Error),
+    #[error("Noise error: {0}")]
+    NoiseError(#[from] NoiseError),
     #[error("No active stream")]
     NoActiveStream,
     #[error("Overflow error: {0}")]
     OverflowError(String),
     #[error("Remote stream cleanly closed")]
     RemoteStreamClosed,
+    #[error("Rejected a peer with an untrusted public key: {0}")]
+    UntrustedPeer(x25519::PublicKey),
+}
+
+/// The static key of a client, and the pinned key of the server it connects to
+struct ClientAuthentication {
+    config: NoiseConfig,
+    server_public_key: x25519::PublicKey,
+}
+
+/// The static key of a server, and the pinned keys of the clients it accepts
+struct ServerAuthentication {
+    config: NoiseConfig,
+    trusted_clients: HashSet<x25519::PublicKey>,
 }
 
 pub struct NetworkClient {
@@ -155,6 +184,7 @@ pub struct NetworkClient {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    authentication: Option<ClientAuthentication>,
 }
 
 impl NetworkClient {
@@ -164,6 +194,25 @@ impl NetworkClient {
             server,
             stream: None,
             timeout_ms,
+            authentication: None,
+        }
+    }
+
+    /// Creates a client that authenticates itself with `identity` and only talks to a server
+    /// owning `server_public_key`
+    pub fn new_authenticated(
+        service: &'static str,
+        server: SocketAddr,
+        timeout_ms: u64,
+        identity: x25519::PrivateKey,
+        server_public_key: x25519::PublicKey,
+    ) -> Self {
+        Self {
+            authentication: Some(ClientAuthentication {
+                config: NoiseConfig::new(identity),
+                server_public_key,
+            }),
+            ..Self::new(service, server, timeout_ms)
         }
     }
 
@@ -239,6 +288,7 @@ impl NetworkClient {
             .remote_peer(&self.server));
 
             let timeout = std::time::Duration::from_millis(self.timeout_ms);
+            let deadline = time::Instant::now() + timeout;
             let mut stream = TcpStream::connect_timeout(&self.server, timeout);
 
             let sleeptime = time::Duration::from_millis(100);
@@ -252,13 +302,30 @@ impl NetworkClient {
                 .error(&err.into())
                 .remote_peer(&self.server));
 
+                if time::Instant::now() + sleeptime > deadline {
+                    return Err(Error::ConnectionTimeout(self.server, self.timeout_ms));
+                }
                 thread::sleep(sleeptime);
                 stream = TcpStream::connect_timeout(&self.server, timeout);
             }
 
             let stream = stream?;
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, self.server, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, self.server, self.timeout_ms);
+            if let Some(authentication) = &self.authentication {
+                if let Err(err) = stream.initiate_handshake(self.service, authentication) {
+                    self.increment_counter(Method::Connect, MethodResult::Failure);
+                    warn!(SecureNetLogSchema::new(
+                        self.service,
+                        NetworkMode::Client,
+                        LogEvent::HandshakeFailed,
+                    )
+                    .error(&err)
+                    .remote_peer(&self.server));
+                    return Err(err);
+                }
+            }
+            self.stream = Some(stream);
             self.increment_counter(Method::Connect, MethodResult::Success);
             info!(SecureNetLogSchema::new(
                 self.service,
@@ -278,6 +345,7 @@ pub struct NetworkServer {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    authentication: Option<ServerAuthentication>,
 }
 
 impl NetworkServer {
@@ -288,6 +356,25 @@ impl NetworkServer {
             listener: Some(listener.unwrap()),
             stream: None,
             timeout_ms,
+            authentication: None,
+        }
+    }
+
+    /// Creates a server that authenticates itself with `identity` and only accepts clients
+    /// owning one of the `trusted_clients` keys
+    pub fn new_authenticated(
+        service: &'static str,
+        listen: SocketAddr,
+        timeout_ms: u64,
+        identity: x25519::PrivateKey,
+        trusted_clients: HashSet<x25519::PublicKey>,
+    ) -> Self {
+        Self {
+            authentication: Some(ServerAuthentication {
+                config: NoiseConfig::new(identity),
+                trusted_clients,
+            }),
+            ..Self::new(service, listen, timeout_ms)
         }
     }
 
@@ -391,6 +478,22 @@ impl NetworkServer {
                 },
             };
 
+            stream.set_nodelay(true)?;
+            let mut stream = NetworkStream::new(stream, stream_addr, self.timeout_ms);
+            if let Some(authentication) = &self.authentication {
+                if let Err(err) = stream.respond_to_handshake(self.service, authentication) {
+                    self.increment_counter(Method::Connect, MethodResult::Failure);
+                    warn!(SecureNetLogSchema::new(
+                        self.service,
+                        NetworkMode::Server,
+                        LogEvent::HandshakeFailed,
+                    )
+                    .error(&err)
+                    .remote_peer(&stream_addr));
+                    return Err(err);
+                }
+            }
+
             self.increment_counter(Method::Connect, MethodResult::Success);
             info!(SecureNetLogSchema::new(
                 self.service,
@@ -398,9 +501,7 @@ impl NetworkServer {
                 LogEvent::ConnectionSuccessful,
             )
             .remote_peer(&stream_addr));
-
-            stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, stream_addr, self.timeout_ms));
+            self.stream = Some(stream);
         }
 
         self.stream.as_mut().ok_or(Error::NoActiveStream)
@@ -412,6 +513,8 @@ struct NetworkStream {
     remote: SocketAddr,
     buffer: Vec<u8>,
     temp_buffer: [u8; 1024],
+    /// The Noise session encrypting the messages of an authenticated stream
+    session: Option<NoiseSession>,
 }
 
 impl NetworkStream {
@@ -426,11 +529,103 @@ impl NetworkStream {
             remote,
             buffer: Vec::new(),
             temp_buffer: [0; 1024],
+            session: None,
         }
     }
 
+    /// Runs the initiator side of the Noise handshake, after which all messages are encrypted
+    fn initiate_handshake(
+        &mut self,
+        service: &'static str,
+        authentication: &ClientAuthentication,
+    ) -> Result<(), Error> {
+        let mut rng = rand::rngs::OsRng;
+        let mut init_message = vec![0; noise::handshake_init_msg_len(0)];
+        let state = authentication.config.initiate_connection(
+            &mut rng,
+            service.as_bytes(),
+            authentication.server_public_key,
+            None,
+            &mut init_message,
+        )?;
+        self.write_block(&init_message)?;
+
+        let response = self.read_block()?;
+        let (_, session) = authentication
+            .config
+            .finalize_connection(state, &response)?;
+        self.session = Some(session);
+        Ok(())
+    }
+
+    /// Runs the responder side of the Noise handshake, rejecting untrusted clients
+    fn respond_to_handshake(
+        &mut self,
+        service: &'static str,
+        authentication: &ServerAuthentication,
+    ) -> Result<(), Error> {
+        let init_message = self.read_block()?;
+        let (client_public_key, state, _) = authentication
+            .config
+            .parse_client_init_message(service.as_bytes(), &init_message)?;
+        if !authentication.trusted_clients.contains(&client_public_key) {
+            return Err(Error::UntrustedPeer(client_public_key));
+        }
+
+        let mut rng = rand::rngs::OsRng;
+        let mut response = vec![0; noise::handshake_resp_msg_len(0)];
+        let session =
+            authentication
+                .config
+                .respond_to_client(&mut rng, state, None, &mut response)?;
+        self.write_block(&response)?;
+        self.session = Some(session);
+        Ok(())
+    }
+
     /// Blocking read until able to successfully read an entire message
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        let block = self.read_block()?;
+        let session = match &mut self.session {
+            Some(session) => session,
+            None => return Ok(block),
+        };
+
+        // Every encrypted chunk but the last one has the maximum size
+        let mut data = Vec::with_capacity(block.len());
+        for chunk in block.chunks(noise::MAX_SIZE_NOISE_MSG) {
+            let mut chunk = chunk.to_vec();
+            data.extend_from_slice(session.read_message_in_place(&mut chunk)?);
+        }
+        Ok(data)
+    }
+
+    /// Blocking write until able to successfully send an entire message
+    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        let session = match &mut self.session {
+            Some(session) => session,
+            None => return self.write_block(data),
+        };
+
+        // Noise messages are limited in size, so large messages are encrypted in chunks. An
+        // empty message is still sent as a single (empty) chunk.
+        let max_chunk_len = noise::MAX_SIZE_NOISE_MSG - noise::AES_GCM_TAGLEN;
+        let mut block = Vec::with_capacity(noise::encrypted_len(data.len()));
+        let mut chunks: Vec<&[u8]> = data.chunks(max_chunk_len).collect();
+        if chunks.is_empty() {
+            chunks.push(&[]);
+        }
+        for chunk in chunks {
+            let mut chunk = chunk.to_vec();
+            let auth_tag = session.write_message_in_place(&mut chunk)?;
+            block.extend_from_slice(&chunk);
+            block.extend_from_slice(&auth_tag);
+        }
+        self.write_block(&block)
+    }
+
+    /// Blocking read until able to successfully read an entire block
+    fn read_block(&mut self) -> Result<Vec<u8>, Error> {
         let result = self.read_buffer();
         if !result.is_empty() {
             return Ok(result);
@@ -458,8 +653,8 @@ impl NetworkStream {
         Ok(self.stream.shutdown(Shutdown::Both)?)
     }
 
-    /// Blocking write until able to successfully send an entire message
-    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+    /// Blocking write until able to successfully send an entire block
+    fn write_block(&mut self, data: &[u8]) -> Result<(), Error> {
         let u32_max = u32::max_value() as usize;
         if u32_max <= data.len() {
             return Err(Error::DataTooLarge(data.len()));
@@ -519,11 +714,44 @@ impl NetworkStream {
 mod test {
     use super::*;
     use aptos_config::utils;
+    use aptos_crypto::Uniform;
+    use rand::{rngs::StdRng, SeedableRng};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
     /// Read, Write, Connect timeout in milliseconds.
     const TIMEOUT: u64 = 5_000;
 
+    fn generate_key(seed: u8) -> x25519::PrivateKey {
+        x25519::PrivateKey::generate(&mut StdRng::from_seed([seed; 32]))
+    }
+
+    fn authenticated_pair(
+        server_addr: SocketAddr,
+        trusted_client_seed: u8,
+        client_seed: u8,
+    ) -> (NetworkServer, NetworkClient) {
+        let server_key = generate_key(0);
+        let server_public_key = server_key.public_key();
+        let trusted_clients = [generate_key(trusted_client_seed).public_key()]
+            .into_iter()
+            .collect();
+        let server = NetworkServer::new_authenticated(
+            "test",
+            server_addr,
+            TIMEOUT,
+            server_key,
+            trusted_clients,
+        );
+        let client = NetworkClient::new_authenticated(
+            "test",
+            server_addr,
+            TIMEOUT,
+            generate_key(client_seed),
+            server_public_key,
+        );
+        (server, client)
+    }
+
     #[test]
     fn test_ping() {
         let server_port = utils::get_available_port();
@@ -657,4 +885,52 @@ mod test {
         let result2 = server2.read().unwrap();
         assert_eq!(data2, result2);
     }
+
+    #[test]
+    fn test_authenticated_ping() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let (mut server, mut client) = authenticated_pair(server_addr, 1, 1);
+
+        // The handshake blocks until the server accepts, so the client runs on its own thread
+        let client = thread::spawn(move || {
+            let data = vec![0, 1, 2, 3];
+            client.write(&data).unwrap();
+            let result = client.read().unwrap();
+            assert_eq!(vec![4, 5, 6, 7], result);
+
+            // Messages larger than a single Noise message are split into chunks
+            let data = vec![8; 3 * noise::MAX_SIZE_NOISE_MSG];
+            client.write(&data).unwrap();
+        });
+
+        let result = server.read().unwrap();
+        assert_eq!(vec![0, 1, 2, 3], result);
+        server.write(&[4, 5, 6, 7]).unwrap();
+        let result = server.read().unwrap();
+        assert_eq!(vec![8; 3 * noise::MAX_SIZE_NOISE_MSG], result);
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn test_untrusted_client() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let (mut server, mut client) = authenticated_pair(server_addr, 1, 2);
+
+        let client = thread::spawn(move || client.write(&[0, 1, 2, 3]));
+        assert!(matches!(server.read(), Err(Error::UntrustedPeer(_))));
+        client.join().unwrap().unwrap_err();
+    }
+
+    #[test]
+    fn test_connection_timeout() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut client = NetworkClient::new("test", server_addr, 500);
+        assert!(matches!(
+            client.write(&[0, 1, 2, 3]),
+            Err(Error::ConnectionTimeout(_, 500))
+        ));
+    }
 }
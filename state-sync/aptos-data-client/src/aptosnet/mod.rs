@@ -8,13 +8,13 @@ use crate::{
             increment_request_counter, set_gauge, start_request_timer, DataType, PRIORITIZED_PEER,
             REGULAR_PEER,
         },
-        state::{ErrorType, PeerStates},
+        state::{ErrorType, InFlightDataRequestGuard, PeerStates},
     },
     AptosDataClient, Error, GlobalDataSummary, Response, ResponseCallback, ResponseContext,
     ResponseError, ResponseId, Result,
 };
 use aptos_config::{
-    config::{AptosDataClientConfig, BaseConfig},
+    config::{AptosDataClientConfig, BaseConfig, PeerSelectionConfig},
     network_id::PeerNetworkId,
 };
 use aptos_id_generator::{IdGenerator, U64IdGenerator};
@@ -69,11 +69,18 @@ const REGULAR_PEER_SAMPLE_FREQ: u64 = 3;
 /// 3. Routes requests to peers that advertise availability for that data.
 /// 4. Maintains peer scores based on each peer's observed quality of service
 ///    and upper client reports of invalid or malicious data.
-/// 5. Selects high quality peers to send each request to.
+/// 5. Selects high quality peers to send each request to (weighing each peer's
+///    latency, throughput, error history and load).
 /// 6. Exposes a condensed data summary of our peers' data advertisements.
 ///
 /// The client currently assumes 1-request => 1-response. Streaming responses
-/// are handled at an upper layer.
+/// are handled at an upper layer. Likewise, each request is sent to a single
+/// peer: large syncs are split into chunk requests by the data streaming
+/// service, and its concurrent requests are spread across peers (see 5).
+/// The client doesn't split a single request across several peers itself, as
+/// every response carries a proof for exactly the data it returns, and the
+/// proofs of partial responses can't be reassembled into the proof of the
+/// original request.
 ///
 /// The client is expected to be cloneable and usable from many concurrent tasks
 /// and/or threads.
@@ -89,11 +96,14 @@ pub struct AptosNetDataClient {
     global_summary_cache: Arc<RwLock<GlobalDataSummary>>,
     /// Used for generating the next request/response id.
     response_id_generator: Arc<U64IdGenerator>,
+    /// Used for measuring response times.
+    time_service: TimeService,
 }
 
 impl AptosNetDataClient {
     pub fn new(
         data_client_config: AptosDataClientConfig,
+        peer_selection_config: PeerSelectionConfig,
         base_config: BaseConfig,
         time_service: TimeService,
         storage_service_client: StorageServiceClient<NetworkClient<StorageServiceMessage>>,
@@ -105,10 +115,12 @@ impl AptosNetDataClient {
             peer_states: Arc::new(RwLock::new(PeerStates::new(
                 base_config,
                 data_client_config,
+                peer_selection_config,
                 storage_service_client.get_peers_and_metadata(),
             ))),
             global_summary_cache: Arc::new(RwLock::new(GlobalDataSummary::empty())),
             response_id_generator: Arc::new(U64IdGenerator::new()),
+            time_service: time_service.clone(),
         };
         let poller = DataSummaryPoller::new(
             client.clone(),
@@ -171,20 +183,28 @@ impl AptosNetDataClient {
         &self,
         request: &StorageServiceRequest,
     ) -> Result<PeerNetworkId, Error> {
-        // All requests should be sent to prioritized peers (if possible).
-        // If none can handle the request, fall back to the regular peers.
+        // All requests should be sent to preferred peers (if possible), and
+        // then to prioritized peers. If none can handle the request, fall
+        // back to the regular peers.
         let (priority_peers, regular_peers) = self.get_priority_and_regular_peers()?;
-        let priority_serviceable = self.identify_serviceable(priority_peers, request);
-        let serviceable_peers = if !priority_serviceable.is_empty() {
-            priority_serviceable
-        } else {
-            self.identify_serviceable(regular_peers, request)
-        };
-
-        // Randomly select a peer to handle the request
-        serviceable_peers
-            .choose(&mut rand::thread_rng())
+        let preferred_peers = priority_peers
+            .iter()
+            .chain(regular_peers.iter())
+            .filter(|peer| self.peer_states.read().is_preferred_peer(peer))
             .copied()
+            .collect();
+        let mut serviceable_peers = self.identify_serviceable(preferred_peers, request);
+        if serviceable_peers.is_empty() {
+            serviceable_peers = self.identify_serviceable(priority_peers, request);
+        }
+        if serviceable_peers.is_empty() {
+            serviceable_peers = self.identify_serviceable(regular_peers, request);
+        }
+
+        // Select a peer to handle the request (weighted by quality of service)
+        self.peer_states
+            .read()
+            .choose_peer_for_request(&serviceable_peers)
             .ok_or_else(|| {
                 Error::DataIsUnavailable(
                     format!("No connected peers are advertising that they can serve this data! Request: {:?}",request),
@@ -281,11 +301,13 @@ impl AptosNetDataClient {
         // Get all connected peers
         let all_connected_peers = self.get_all_connected_peers()?;
 
-        // Filter the peers based on priority
+        // Filter the peers based on priority (ignoring all denied peers)
         let mut priority_peers = vec![];
         let mut regular_peers = vec![];
         for peer in all_connected_peers {
-            if self.peer_states.read().is_priority_peer(&peer) {
+            if self.peer_states.read().is_denied_peer(&peer) {
+                continue;
+            } else if self.peer_states.read().is_priority_peer(&peer) {
                 priority_peers.push(peer);
             } else {
                 regular_peers.push(peer);
@@ -386,7 +408,15 @@ impl AptosNetDataClient {
         );
         increment_request_counter(&metrics::SENT_REQUESTS, &request.get_label(), peer);
 
+        // Track the in-flight data requests (storage summary and version
+        // requests are tracked separately, as polls).
+        let is_data_request = !request.data_request.is_storage_summary_request()
+            && !request.data_request.is_protocol_version_request();
+        let mut in_flight_data_request =
+            is_data_request.then(|| InFlightDataRequestGuard::new(peer, self.peer_states.clone()));
+
         // Send the request and process the result
+        let request_start_time = self.time_service.now();
         let result = self
             .storage_service_client
             .send_request(
@@ -395,6 +425,14 @@ impl AptosNetDataClient {
                 request.clone(),
             )
             .await;
+        if let (Some(in_flight_data_request), Ok(response)) =
+            (in_flight_data_request.as_mut(), result.as_ref())
+        {
+            let response_time = self.time_service.now().duration_since(request_start_time);
+            in_flight_data_request
+                .set_response_time_and_bytes(response_time.as_secs_f64(), response.get_num_bytes());
+        }
+        drop(in_flight_data_request); // Mark the data request as complete
         match result {
             Ok(response) => {
                 trace!(
@@ -6,9 +6,10 @@ use crate::{
     AdvertisedData, GlobalDataSummary, OptimalChunkSizes, ResponseError,
 };
 use aptos_config::{
-    config::{AptosDataClientConfig, BaseConfig},
+    config::{AptosDataClientConfig, BaseConfig, PeerSelectionConfig},
     network_id::{NetworkId, PeerNetworkId},
 };
+use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::application::storage::PeersAndMetadata;
@@ -16,6 +17,7 @@ use aptos_storage_service_types::{
     requests::StorageServiceRequest, responses::StorageServerSummary,
 };
 use itertools::Itertools;
+use rand::seq::SliceRandom;
 use std::{
     cmp::min,
     collections::{HashMap, HashSet},
@@ -35,6 +37,18 @@ const MALICIOUS_MULTIPLIER: f64 = 0.8;
 /// Ignore a peer when their score dips below this threshold.
 const IGNORE_PEER_THRESHOLD: f64 = 25.0;
 
+/// The weight of each new observation in the moving averages of response
+/// latencies and throughputs.
+const RESPONSE_METRICS_SMOOTHING_FACTOR: f64 = 0.2;
+/// The latency (in seconds) assumed for peers that have no latency measurements.
+const DEFAULT_LATENCY_SECS: f64 = 0.5;
+/// The minimum latency (in seconds) used for peer selection. This prevents
+/// any single (very close) peer from receiving all requests.
+const MIN_LATENCY_SECS: f64 = 0.01;
+/// The minimum throughput multiplier used for peer selection (i.e., relative
+/// to the peer with the highest throughput).
+const MIN_THROUGHPUT_MULTIPLIER: f64 = 0.1;
+
 pub(crate) enum ErrorType {
     /// A response or error that's not actively malicious but also doesn't help
     /// us make progress, e.g., timeouts, remote errors, invalid data, etc...
@@ -62,6 +76,14 @@ pub struct PeerState {
     storage_summary: Option<StorageServerSummary>,
     /// For now, a simplified port of the original state-sync v1 scoring system.
     score: f64,
+    /// The moving average of the response latencies (in seconds) for data
+    /// requests sent to this peer, or `None` if no responses were received.
+    response_latency_secs: Option<f64>,
+    /// The moving average of the response throughputs (in bytes per second)
+    /// for data requests sent to this peer.
+    response_throughput_bytes_per_sec: Option<f64>,
+    /// The number of data requests currently in-flight to this peer.
+    num_in_flight_data_requests: u64,
 }
 
 impl Default for PeerState {
@@ -69,6 +91,9 @@ impl Default for PeerState {
         Self {
             storage_summary: None,
             score: STARTING_SCORE,
+            response_latency_secs: None,
+            response_throughput_bytes_per_sec: None,
+            num_in_flight_data_requests: 0,
         }
     }
 }
@@ -101,6 +126,63 @@ impl PeerState {
         };
         self.score = f64::max(self.score * multiplier, MIN_SCORE);
     }
+
+    /// Updates the response latency and throughput averages of the peer
+    fn update_response_metrics(&mut self, response_time_secs: f64, num_response_bytes: u64) {
+        self.response_latency_secs = Some(update_moving_average(
+            self.response_latency_secs,
+            response_time_secs,
+        ));
+        if response_time_secs > 0.0 {
+            let throughput_bytes_per_sec = num_response_bytes as f64 / response_time_secs;
+            self.response_throughput_bytes_per_sec = Some(update_moving_average(
+                self.response_throughput_bytes_per_sec,
+                throughput_bytes_per_sec,
+            ));
+        }
+    }
+
+    /// Returns the weight of the peer when selecting peers for data requests.
+    /// Peers with higher scores (i.e., fewer errors), lower latencies, higher
+    /// throughputs and fewer in-flight requests are more likely to be selected.
+    fn get_selection_weight(
+        &self,
+        ping_latency_secs: Option<f64>,
+        max_throughput_bytes_per_sec: Option<f64>,
+    ) -> f64 {
+        // Prefer the latency measured by the peer monitoring service (as it isn't
+        // affected by the size of the responses), and fall back to our own.
+        let latency_secs = ping_latency_secs
+            .or(self.response_latency_secs)
+            .unwrap_or(DEFAULT_LATENCY_SECS)
+            .max(MIN_LATENCY_SECS);
+
+        // Compare the throughput of the peer to the fastest peer
+        let throughput_multiplier = match (
+            self.response_throughput_bytes_per_sec,
+            max_throughput_bytes_per_sec,
+        ) {
+            (Some(throughput), Some(max_throughput)) if max_throughput > 0.0 => {
+                (throughput / max_throughput).max(MIN_THROUGHPUT_MULTIPLIER)
+            },
+            _ => 1.0,
+        };
+
+        // Spread requests across peers by penalizing those with in-flight requests
+        let in_flight_divisor = (self.num_in_flight_data_requests + 1) as f64;
+        (self.score / MAX_SCORE) * throughput_multiplier / (latency_secs * in_flight_divisor)
+    }
+}
+
+/// Returns the new moving average after adding the given value
+fn update_moving_average(average: Option<f64>, value: f64) -> f64 {
+    match average {
+        Some(average) => {
+            (1.0 - RESPONSE_METRICS_SMOOTHING_FACTOR) * average
+                + RESPONSE_METRICS_SMOOTHING_FACTOR * value
+        },
+        None => value,
+    }
 }
 
 /// Contains all of the unbanned peers' most recent [`StorageServerSummary`] data
@@ -109,6 +191,7 @@ impl PeerState {
 pub(crate) struct PeerStates {
     base_config: BaseConfig,
     data_client_config: AptosDataClientConfig,
+    peer_selection_config: PeerSelectionConfig,
     peer_to_state: HashMap<PeerNetworkId, PeerState>,
     in_flight_priority_polls: HashSet<PeerNetworkId>, // The priority peers with in-flight polls
     in_flight_regular_polls: HashSet<PeerNetworkId>,  // The regular peers with in-flight polls
@@ -119,11 +202,13 @@ impl PeerStates {
     pub fn new(
         base_config: BaseConfig,
         data_client_config: AptosDataClientConfig,
+        peer_selection_config: PeerSelectionConfig,
         peers_and_metadata: Arc<PeersAndMetadata>,
     ) -> Self {
         Self {
             base_config,
             data_client_config,
+            peer_selection_config,
             peer_to_state: HashMap::new(),
             in_flight_priority_polls: HashSet::new(),
             in_flight_regular_polls: HashSet::new(),
@@ -138,6 +223,11 @@ impl PeerStates {
         peer: &PeerNetworkId,
         request: &StorageServiceRequest,
     ) -> bool {
+        // Denied peers are never sent requests
+        if self.is_denied_peer(peer) {
+            return false;
+        }
+
         // Storage services can always respond to data advertisement requests.
         // We need this outer check, since we need to be able to send data summary
         // requests to new peers (who don't have a peer state yet).
@@ -154,6 +244,103 @@ impl PeerStates {
             .unwrap_or(false)
     }
 
+    /// Returns true iff the given peer is denied (i.e., it should never be
+    /// polled or sent requests).
+    pub fn is_denied_peer(&self, peer: &PeerNetworkId) -> bool {
+        self.peer_selection_config
+            .denied_peers
+            .contains(&peer.peer_id())
+    }
+
+    /// Returns true iff the given peer is preferred (i.e., it should be sent
+    /// requests before all other peers).
+    pub fn is_preferred_peer(&self, peer: &PeerNetworkId) -> bool {
+        self.peer_selection_config
+            .preferred_peers
+            .contains(&peer.peer_id())
+    }
+
+    /// Selects a peer (from the given serviceable peers) to send a request to.
+    /// Peers are selected at random, weighted by their expected quality of
+    /// service. Each request is sent to a single peer, but large syncs are
+    /// split into chunk requests by the data streaming service (with several
+    /// in-flight at once), so the in-flight penalty spreads those concurrent
+    /// chunks across multiple fast peers, instead of overloading the fastest one.
+    pub fn choose_peer_for_request(&self, peers: &[PeerNetworkId]) -> Option<PeerNetworkId> {
+        // Select a peer using the weights
+        let max_throughput_bytes_per_sec = self.get_max_throughput_bytes_per_sec(peers);
+        let mut rng = rand::thread_rng();
+        peers
+            .choose_weighted(&mut rng, |peer| {
+                self.get_selection_weight(peer, max_throughput_bytes_per_sec)
+            })
+            .ok()
+            .or_else(|| peers.choose(&mut rng))
+            .copied()
+    }
+
+    /// Returns the highest response throughput amongst the given peers (if any)
+    pub fn get_max_throughput_bytes_per_sec(&self, peers: &[PeerNetworkId]) -> Option<f64> {
+        peers
+            .iter()
+            .filter_map(|peer| {
+                self.peer_to_state
+                    .get(peer)
+                    .and_then(|peer_state| peer_state.response_throughput_bytes_per_sec)
+            })
+            .reduce(f64::max)
+    }
+
+    /// Returns the selection weight of the given peer (see `PeerState::get_selection_weight`)
+    pub fn get_selection_weight(
+        &self,
+        peer: &PeerNetworkId,
+        max_throughput_bytes_per_sec: Option<f64>,
+    ) -> f64 {
+        let ping_latency_secs = self
+            .peers_and_metadata
+            .get_metadata_for_peer(*peer)
+            .ok()
+            .and_then(|peer_metadata| {
+                peer_metadata
+                    .get_peer_monitoring_metadata()
+                    .average_ping_latency_secs
+            });
+        match self.peer_to_state.get(peer) {
+            Some(peer_state) => {
+                peer_state.get_selection_weight(ping_latency_secs, max_throughput_bytes_per_sec)
+            },
+            None => PeerState::default()
+                .get_selection_weight(ping_latency_secs, max_throughput_bytes_per_sec),
+        }
+    }
+
+    /// Marks a data request as started for the specified peer
+    pub fn data_request_started(&mut self, peer: PeerNetworkId) {
+        self.peer_to_state
+            .entry(peer)
+            .or_default()
+            .num_in_flight_data_requests += 1;
+    }
+
+    /// Marks a data request as complete for the specified peer. If the request
+    /// was successful, the response time and size are used to update the peer's
+    /// latency and throughput averages.
+    pub fn data_request_completed(
+        &mut self,
+        peer: PeerNetworkId,
+        response_time_and_bytes: Option<(f64, u64)>,
+    ) {
+        // The peer state may have been garbage collected (if the peer disconnected)
+        if let Some(peer_state) = self.peer_to_state.get_mut(&peer) {
+            peer_state.num_in_flight_data_requests =
+                peer_state.num_in_flight_data_requests.saturating_sub(1);
+            if let Some((response_time_secs, num_response_bytes)) = response_time_and_bytes {
+                peer_state.update_response_metrics(response_time_secs, num_response_bytes);
+            }
+        }
+    }
+
     /// Updates the score of the peer according to a successful operation
     pub fn update_score_success(&mut self, peer: PeerNetworkId) {
         let old_score = self.peer_to_state.entry(peer).or_default().score;
@@ -369,6 +556,15 @@ impl PeerStates {
         }
     }
 
+    #[cfg(test)]
+    /// Returns the number of in-flight data requests to the given peer
+    pub fn num_in_flight_data_requests(&self, peer: &PeerNetworkId) -> u64 {
+        self.peer_to_state
+            .get(peer)
+            .map(|peer_state| peer_state.num_in_flight_data_requests)
+            .unwrap_or_default()
+    }
+
     #[cfg(test)]
     /// Returns a copy of the peer to states map for test purposes
     pub fn get_peer_to_states(&self) -> HashMap<PeerNetworkId, PeerState> {
@@ -376,6 +572,39 @@ impl PeerStates {
     }
 }
 
+/// Tracks a data request that is in-flight to a peer. The request is marked as
+/// complete when the guard is dropped, so the in-flight count can't leak if the
+/// request future is dropped (e.g., when a data stream aborts its tasks).
+pub(crate) struct InFlightDataRequestGuard {
+    peer: PeerNetworkId,
+    peer_states: Arc<RwLock<PeerStates>>,
+    response_time_and_bytes: Option<(f64, u64)>,
+}
+
+impl InFlightDataRequestGuard {
+    pub fn new(peer: PeerNetworkId, peer_states: Arc<RwLock<PeerStates>>) -> Self {
+        peer_states.write().data_request_started(peer);
+        Self {
+            peer,
+            peer_states,
+            response_time_and_bytes: None,
+        }
+    }
+
+    /// Records the response time and size of the request (once it succeeds)
+    pub fn set_response_time_and_bytes(&mut self, response_time_secs: f64, num_bytes: u64) {
+        self.response_time_and_bytes = Some((response_time_secs, num_bytes));
+    }
+}
+
+impl Drop for InFlightDataRequestGuard {
+    fn drop(&mut self) {
+        self.peer_states
+            .write()
+            .data_request_completed(self.peer, self.response_time_and_bytes.take());
+    }
+}
+
 /// To calculate the optimal chunk size, we take the median for each
 /// chunk size parameter. This works well when we have an honest
 /// majority that mostly agrees on the same chunk sizes.
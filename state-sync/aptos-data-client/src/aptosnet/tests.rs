@@ -5,13 +5,17 @@ use super::{AptosDataClient, AptosNetDataClient, DataSummaryPoller, Error};
 use crate::aptosnet::{poll_peer, state::calculate_optimal_chunk_sizes};
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{AptosDataClientConfig, BaseConfig, RoleType},
+    config::{AptosDataClientConfig, BaseConfig, PeerSelectionConfig, RoleType},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_crypto::HashValue;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
-    application::{interface::NetworkClient, metadata::ConnectionState, storage::PeersAndMetadata},
+    application::{
+        interface::NetworkClient,
+        metadata::{ConnectionState, PeerMonitoringMetadata},
+        storage::PeersAndMetadata,
+    },
     peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
     protocols::{
         network::{NetworkSender, NewNetworkSender},
@@ -84,6 +88,20 @@ impl MockNetwork {
         base_config: Option<BaseConfig>,
         data_client_config: Option<AptosDataClientConfig>,
         networks: Option<Vec<NetworkId>>,
+    ) -> (Self, MockTimeService, AptosNetDataClient, DataSummaryPoller) {
+        Self::new_with_peer_selection_config(
+            base_config,
+            data_client_config,
+            PeerSelectionConfig::default(),
+            networks,
+        )
+    }
+
+    fn new_with_peer_selection_config(
+        base_config: Option<BaseConfig>,
+        data_client_config: Option<AptosDataClientConfig>,
+        peer_selection_config: PeerSelectionConfig,
+        networks: Option<Vec<NetworkId>>,
     ) -> (Self, MockTimeService, AptosNetDataClient, DataSummaryPoller) {
         // Setup the request managers
         let queue_cfg = aptos_channel::Config::new(10).queue_style(QueueStyle::FIFO);
@@ -116,6 +134,7 @@ impl MockNetwork {
         let data_client_config = data_client_config.unwrap_or_default();
         let (client, poller) = AptosNetDataClient::new(
             data_client_config,
+            peer_selection_config,
             base_config,
             mock_time.clone(),
             storage_service_client,
@@ -181,8 +200,17 @@ impl MockNetwork {
         self.update_peer_state(peer, ConnectionState::Connected);
     }
 
-    /// Updates the state of the given peer
+    /// Updates the average ping latency of the given peer (as measured
+    /// by the peer monitoring service)
+    fn update_peer_latency(&mut self, peer: PeerNetworkId, average_ping_latency_secs: f64) {
+        let peer_monitoring_metadata =
+            PeerMonitoringMetadata::new(Some(average_ping_latency_secs), None, None);
+        self.peers_and_metadata
+            .update_peer_monitoring_metadata(peer, peer_monitoring_metadata)
+            .unwrap();
+    }
 
+    /// Updates the state of the given peer
     fn update_peer_state(&mut self, peer: PeerNetworkId, state: ConnectionState) {
         self.peers_and_metadata
             .update_connection_state(peer, state)
@@ -901,6 +929,191 @@ async fn all_peer_request_selection() {
     assert!(peer_for_request == priority_peer_1 || peer_for_request == priority_peer_2);
 }
 
+#[tokio::test]
+async fn denied_peer_request_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let denied_peer_id = PeerId::random();
+    let peer_selection_config = PeerSelectionConfig {
+        denied_peers: [denied_peer_id].into_iter().collect(),
+        ..Default::default()
+    };
+    let (mut mock_network, _, client, _) =
+        MockNetwork::new_with_peer_selection_config(None, None, peer_selection_config, None);
+
+    // Add a denied priority peer and verify it is never polled or sent requests
+    let denied_peer = PeerNetworkId::new(NetworkId::Validator, denied_peer_id);
+    add_connected_peer(&mut mock_network, denied_peer);
+    client.update_summary(denied_peer, mock_storage_summary(100));
+    let storage_request = create_transaction_outputs_request(100);
+    assert_matches!(
+        client.choose_peer_for_request(&storage_request),
+        Err(Error::DataIsUnavailable(_))
+    );
+    assert_none!(fetch_peer_to_poll(client.clone(), true).unwrap());
+
+    // Add a regular peer and verify it is always selected
+    let regular_peer = mock_network.add_peer(false);
+    client.update_summary(regular_peer, mock_storage_summary(100));
+    for _ in 0..10 {
+        assert_eq!(
+            client.choose_peer_for_request(&storage_request),
+            Ok(regular_peer)
+        );
+    }
+    assert_eq!(
+        fetch_peer_to_poll(client.clone(), false).unwrap(),
+        Some(regular_peer)
+    );
+}
+
+#[tokio::test]
+async fn preferred_peer_request_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let preferred_peer_id = PeerId::random();
+    let peer_selection_config = PeerSelectionConfig {
+        preferred_peers: [preferred_peer_id].into_iter().collect(),
+        ..Default::default()
+    };
+    let (mut mock_network, _, client, _) =
+        MockNetwork::new_with_peer_selection_config(None, None, peer_selection_config, None);
+
+    // Add a priority peer and a preferred (regular) peer
+    let priority_peer = mock_network.add_peer(true);
+    let preferred_peer = PeerNetworkId::new(NetworkId::Public, preferred_peer_id);
+    add_connected_peer(&mut mock_network, preferred_peer);
+
+    // Advertise the data for both peers and verify the preferred peer is selected
+    client.update_summary(priority_peer, mock_storage_summary(100));
+    client.update_summary(preferred_peer, mock_storage_summary(100));
+    let storage_request = create_transaction_outputs_request(100);
+    for _ in 0..10 {
+        assert_eq!(
+            client.choose_peer_for_request(&storage_request),
+            Ok(preferred_peer)
+        );
+    }
+
+    // Remove the advertised data for the preferred peer and verify the priority peer is selected
+    client.update_summary(preferred_peer, mock_storage_summary(0));
+    assert_eq!(
+        client.choose_peer_for_request(&storage_request),
+        Ok(priority_peer)
+    );
+}
+
+#[tokio::test]
+async fn latency_aware_peer_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+
+    // Add two priority peers that advertise the data
+    let fast_peer = mock_network.add_peer(true);
+    let slow_peer = mock_network.add_peer(true);
+    for peer in [fast_peer, slow_peer] {
+        client.update_summary(peer, mock_storage_summary(100));
+    }
+
+    // Update the peer latencies and verify the fast peer is heavily weighted
+    mock_network.update_peer_latency(fast_peer, 0.01);
+    mock_network.update_peer_latency(slow_peer, 1.0);
+    let peers = [fast_peer, slow_peer];
+    let weights = get_selection_weights(&client, &peers);
+    let (fast_peer_weight, slow_peer_weight) = (weights[0], weights[1]);
+    assert!(fast_peer_weight > 50.0 * slow_peer_weight);
+
+    // Verify the fast peer is chosen for (almost) all requests
+    let storage_request = create_transaction_outputs_request(100);
+    let num_requests = 100;
+    let mut num_fast_peer_requests = 0;
+    for _ in 0..num_requests {
+        if client.choose_peer_for_request(&storage_request).unwrap() == fast_peer {
+            num_fast_peer_requests += 1;
+        }
+    }
+    assert!(num_fast_peer_requests > num_requests * 3 / 4);
+
+    // Start several requests for the fast peer and verify the weight decreases
+    for _ in 0..3 {
+        client.peer_states.write().data_request_started(fast_peer);
+    }
+    let loaded_fast_peer_weight = get_selection_weights(&client, &peers)[0];
+    assert!(loaded_fast_peer_weight < fast_peer_weight / 3.0);
+
+    // Complete the requests and verify the weight is restored
+    for _ in 0..3 {
+        client
+            .peer_states
+            .write()
+            .data_request_completed(fast_peer, None);
+    }
+    assert_eq!(get_selection_weights(&client, &peers)[0], fast_peer_weight);
+}
+
+#[tokio::test]
+async fn throughput_aware_peer_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+
+    // Add two priority peers with the same latency
+    let high_throughput_peer = mock_network.add_peer(true);
+    let low_throughput_peer = mock_network.add_peer(true);
+    let peers = [high_throughput_peer, low_throughput_peer];
+    for peer in peers {
+        client.update_summary(peer, mock_storage_summary(100));
+        mock_network.update_peer_latency(peer, 0.1);
+    }
+    let weights = get_selection_weights(&client, &peers);
+    assert_eq!(weights[0], weights[1]);
+
+    // Complete requests with different throughputs for each peer
+    for (peer, num_response_bytes) in [
+        (high_throughput_peer, 1_000_000),
+        (low_throughput_peer, 1_000),
+    ] {
+        let mut peer_states = client.peer_states.write();
+        peer_states.data_request_started(peer);
+        peer_states.data_request_completed(peer, Some((1.0, num_response_bytes)));
+    }
+
+    // Verify the high throughput peer is more heavily weighted
+    let weights = get_selection_weights(&client, &peers);
+    assert!(weights[0] > 5.0 * weights[1]);
+}
+
+#[tokio::test]
+async fn dropped_request_completes_in_flight_data_request() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None, None, None);
+
+    // Add a peer that advertises the data
+    let peer = mock_network.add_peer(true);
+    client.update_summary(peer, mock_storage_summary(100));
+
+    // Send a data request to the peer
+    let request_client = client.clone();
+    let request_handle = tokio::spawn(async move {
+        request_client
+            .send_request_to_peer(peer, create_transaction_outputs_request(100), 10_000)
+            .await
+    });
+
+    // Wait for the request to reach the network and verify it's in-flight
+    let _network_request = mock_network.next_request().await.unwrap();
+    assert_eq!(
+        client.peer_states.read().num_in_flight_data_requests(&peer),
+        1
+    );
+
+    // Drop the request future before a response is received (e.g., as a data
+    // stream would when it's terminated) and verify the request is complete.
+    request_handle.abort();
+    assert!(request_handle.await.unwrap_err().is_cancelled());
+    assert_eq!(
+        client.peer_states.read().num_in_flight_data_requests(&peer),
+        0
+    );
+}
+
 #[tokio::test]
 async fn validator_peer_prioritization() {
     ::aptos_logger::Logger::init_for_testing();
@@ -1504,6 +1717,39 @@ async fn optimal_chunk_size_calculations() {
     assert_eq!(400, optimal_chunk_sizes.transaction_output_chunk_size);
 }
 
+/// Adds the given peer to the network peer DB
+fn add_connected_peer(mock_network: &mut MockNetwork, peer: PeerNetworkId) {
+    let mut connection_metadata = ConnectionMetadata::mock(peer.peer_id());
+    connection_metadata
+        .application_protocols
+        .insert(ProtocolId::StorageServiceRpc);
+    mock_network
+        .peers_and_metadata
+        .insert_connection_metadata(peer, connection_metadata)
+        .unwrap();
+}
+
+/// Creates a transaction outputs request that ends at the given version
+fn create_transaction_outputs_request(end_version: Version) -> StorageServiceRequest {
+    let data_request =
+        DataRequest::GetTransactionOutputsWithProof(TransactionOutputsWithProofRequest {
+            proof_version: end_version,
+            start_version: 0,
+            end_version,
+        });
+    StorageServiceRequest::new(data_request, true)
+}
+
+/// Returns the peer selection weights of the given peers (in order)
+fn get_selection_weights(client: &AptosNetDataClient, peers: &[PeerNetworkId]) -> Vec<f64> {
+    let peer_states = client.peer_states.read();
+    let max_throughput_bytes_per_sec = peer_states.get_max_throughput_bytes_per_sec(peers);
+    peers
+        .iter()
+        .map(|peer| peer_states.get_selection_weight(peer, max_throughput_bytes_per_sec))
+        .collect()
+}
+
 /// A helper method that fetches peers to poll depending on the peer priority
 fn fetch_peer_to_poll(
     client: AptosNetDataClient,
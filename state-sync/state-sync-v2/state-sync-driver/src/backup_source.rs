@@ -0,0 +1,846 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+};
+use anyhow::{anyhow, ensure};
+use aptos_backup_cli::{
+    backup_types::{
+        epoch_ending::manifest::EpochEndingBackup, state_snapshot::manifest::StateSnapshotBackup,
+        transaction::manifest::TransactionBackup,
+    },
+    metadata::{
+        cache::{sync_and_load, MetadataCacheOpt},
+        view::MetadataView,
+    },
+    storage::{
+        command_adapter::{config::CommandAdapterConfig, CommandAdapter},
+        local_fs::LocalFs,
+        BackupStorage, FileHandle, FileHandleRef,
+    },
+    utils::{read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt},
+};
+use aptos_config::config::BackupStorageConfig;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_data_streaming_service::{
+    data_notification::{DataNotification, DataPayload},
+    data_stream::{DataStreamId, DataStreamListener},
+};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    contract_event::ContractEvent,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{
+        SparseMerkleRangeProof, TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
+        TransactionAccumulatorRangeProof, TransactionInfoListWithProof, TransactionInfoWithProof,
+    },
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
+    transaction::{
+        Transaction, TransactionInfo, TransactionOutput, TransactionOutputListWithProof,
+        TransactionStatus, Version,
+    },
+    write_set::WriteSet,
+};
+use async_trait::async_trait;
+use futures::{channel::mpsc, Future, SinkExt};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::io::BufReader;
+
+// The maximum number of notifications buffered along each backup data stream
+const BACKUP_STREAM_CHANNEL_SIZE: usize = 10;
+
+// The number of metadata files to download concurrently from the backup storage
+const CONCURRENT_METADATA_DOWNLOADS: usize = 8;
+
+/// The interface of a source of bootstrapping data that is read from a backup
+/// (instead of the network). Data is delivered along data streams, which always
+/// end with an end of stream notification.
+#[async_trait]
+pub trait BackupSourceInterface: Send + Sync {
+    /// Returns the highest epoch that has ended in the backup (if any)
+    async fn highest_epoch_ending_epoch(&mut self) -> Result<Option<u64>, Error>;
+
+    /// Returns the highest transaction version in the backup (if any)
+    async fn highest_transaction_version(&mut self) -> Result<Option<Version>, Error>;
+
+    /// Returns the version of the latest state snapshot in the backup that is
+    /// at one of the given epoch ending versions (if any).
+    async fn find_latest_state_snapshot(
+        &mut self,
+        epoch_ending_versions: BTreeSet<Version>,
+    ) -> Result<Option<Version>, Error>;
+
+    /// Returns a data stream of all epoch ending ledger infos in the backup,
+    /// starting at the given epoch.
+    async fn get_all_epoch_ending_ledger_infos(
+        &mut self,
+        start_epoch: u64,
+    ) -> Result<DataStreamListener, Error>;
+
+    /// Returns a data stream with the transaction output at the version of the
+    /// given state snapshot. The output is proven by the snapshot proof, so it
+    /// should be verified against the ledger info at the snapshot version.
+    async fn get_transaction_output(
+        &mut self,
+        version: Version,
+    ) -> Result<DataStreamListener, Error>;
+
+    /// Returns a data stream of all transaction outputs in the backup, starting
+    /// at the given version. Each payload is sent with the ledger info its proof
+    /// is relative to (i.e., the one it was backed up with), and ends at the
+    /// latest at the next of the given epoch ending versions.
+    async fn get_all_transaction_outputs(
+        &mut self,
+        start_version: Version,
+        epoch_ending_versions: BTreeSet<Version>,
+    ) -> Result<DataStreamListener, Error>;
+
+    /// Returns a data stream of all state values in the state snapshot at the
+    /// given version, starting at the given index.
+    async fn get_all_state_values(
+        &mut self,
+        version: Version,
+        start_index: u64,
+    ) -> Result<DataStreamListener, Error>;
+}
+
+/// A source of bootstrapping data that reads from a backup storage (i.e., one
+/// written by the backup-cli), instead of the network. Data is delivered along
+/// data streams (like those of the data streaming service), so that the
+/// bootstrapper verifies it using the same proofs as data fetched from peers.
+pub struct BackupSource {
+    // The id generator for data streams and notifications
+    id_generator: Arc<AtomicU64>,
+
+    // The directory in which to cache the backup metadata (a temporary
+    // directory if none is given).
+    metadata_cache_dir: Option<PathBuf>,
+
+    // The metadata of the backup storage (synced on first use)
+    metadata_view: Option<MetadataView>,
+
+    // The backup storage to read from (created on first use)
+    storage: Option<Arc<dyn BackupStorage>>,
+
+    // The config of the backup storage
+    storage_config: BackupStorageConfig,
+}
+
+impl BackupSource {
+    pub fn new(storage_config: BackupStorageConfig, metadata_cache_dir: Option<PathBuf>) -> Self {
+        Self {
+            id_generator: Arc::new(AtomicU64::new(0)),
+            metadata_cache_dir,
+            metadata_view: None,
+            storage: None,
+            storage_config,
+        }
+    }
+
+    /// Returns the backup storage (creating it if required)
+    fn get_storage(&mut self) -> Result<Arc<dyn BackupStorage>, Error> {
+        if self.storage.is_none() {
+            let storage: Arc<dyn BackupStorage> = match &self.storage_config {
+                BackupStorageConfig::CommandAdapter(config_path) => {
+                    let adapter_config = fs::read_to_string(config_path)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|config| CommandAdapterConfig::load_from_str(&config))
+                        .map_err(|error| {
+                            Error::BackupStorageError(format!(
+                                "Failed to load the command adapter config at {:?}! Error: {:?}",
+                                config_path, error
+                            ))
+                        })?;
+                    Arc::new(CommandAdapter::new(adapter_config))
+                },
+                BackupStorageConfig::LocalFs(directory) => {
+                    Arc::new(LocalFs::new(directory.clone()))
+                },
+            };
+            self.storage = Some(storage);
+        }
+
+        Ok(self
+            .storage
+            .clone()
+            .expect("The backup storage must have been created!"))
+    }
+
+    /// Returns the metadata of the backup storage (syncing it if required)
+    async fn get_metadata_view(&mut self) -> Result<&MetadataView, Error> {
+        if self.metadata_view.is_none() {
+            info!(LogSchema::new(LogEntry::BackupSource)
+                .message("Syncing the metadata of the backup storage!"));
+            let metadata_view = sync_and_load(
+                &MetadataCacheOpt::new(self.metadata_cache_dir.as_ref()),
+                self.get_storage()?,
+                CONCURRENT_METADATA_DOWNLOADS,
+            )
+            .await
+            .map_err(|error| {
+                Error::BackupStorageError(format!(
+                    "Failed to sync the backup metadata! Error: {:?}",
+                    error
+                ))
+            })?;
+            self.metadata_view = Some(metadata_view);
+        }
+
+        Ok(self
+            .metadata_view
+            .as_ref()
+            .expect("The backup metadata must have been synced!"))
+    }
+
+    /// Creates a new data stream and returns the listener and sender
+    fn create_data_stream(&self) -> (DataStreamListener, BackupStreamSender) {
+        let data_stream_id = self.id_generator.fetch_add(1, Ordering::Relaxed);
+        let (notification_sender, notification_receiver) =
+            mpsc::channel(BACKUP_STREAM_CHANNEL_SIZE);
+        let stream_sender = BackupStreamSender {
+            id_generator: self.id_generator.clone(),
+            notification_sender,
+        };
+        (
+            DataStreamListener::new(data_stream_id, notification_receiver),
+            stream_sender,
+        )
+    }
+}
+
+#[async_trait]
+impl BackupSourceInterface for BackupSource {
+    async fn highest_epoch_ending_epoch(&mut self) -> Result<Option<u64>, Error> {
+        let storage_state = self
+            .get_metadata_view()
+            .await?
+            .get_storage_state()
+            .map_err(|error| {
+                Error::BackupStorageError(format!(
+                    "Failed to get the backup storage state! Error: {:?}",
+                    error
+                ))
+            })?;
+        Ok(storage_state.latest_epoch_ending_epoch)
+    }
+
+    async fn highest_transaction_version(&mut self) -> Result<Option<Version>, Error> {
+        self.get_metadata_view()
+            .await?
+            .max_transaction_version()
+            .map_err(|error| {
+                Error::BackupStorageError(format!(
+                    "Failed to get the highest transaction version! Error: {:?}",
+                    error
+                ))
+            })
+    }
+
+    async fn find_latest_state_snapshot(
+        &mut self,
+        epoch_ending_versions: BTreeSet<Version>,
+    ) -> Result<Option<Version>, Error> {
+        let metadata_view = self.get_metadata_view().await?;
+
+        let mut max_version = Version::MAX;
+        loop {
+            let state_snapshot = metadata_view
+                .select_state_snapshot(max_version)
+                .map_err(|error| {
+                    Error::BackupStorageError(format!(
+                        "Failed to select a state snapshot! Error: {:?}",
+                        error
+                    ))
+                })?;
+            match state_snapshot {
+                Some(state_snapshot) if epoch_ending_versions.contains(&state_snapshot.version) => {
+                    return Ok(Some(state_snapshot.version))
+                },
+                Some(state_snapshot) if state_snapshot.version > 0 => {
+                    max_version = state_snapshot.version - 1;
+                },
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    async fn get_all_epoch_ending_ledger_infos(
+        &mut self,
+        start_epoch: u64,
+    ) -> Result<DataStreamListener, Error> {
+        let manifests = self
+            .get_metadata_view()
+            .await?
+            .select_epoch_ending_backups(Version::MAX)
+            .map_err(|error| {
+                Error::BackupStorageError(format!(
+                    "Failed to select the epoch ending backups! Error: {:?}",
+                    error
+                ))
+            })?
+            .into_iter()
+            .filter(|backup| backup.last_epoch >= start_epoch)
+            .map(|backup| backup.manifest)
+            .collect();
+
+        let (data_stream_listener, stream_sender) = self.create_data_stream();
+        spawn_data_stream(
+            data_stream_listener.data_stream_id,
+            stream_sender.clone(),
+            stream_epoch_ending_ledger_infos(
+                self.get_storage()?,
+                manifests,
+                start_epoch,
+                stream_sender,
+            ),
+        );
+        Ok(data_stream_listener)
+    }
+
+    async fn get_transaction_output(
+        &mut self,
+        version: Version,
+    ) -> Result<DataStreamListener, Error> {
+        let metadata_view = self.get_metadata_view().await?;
+        let snapshot_manifest = metadata_view
+            .expect_state_snapshot(version)
+            .map_err(|error| {
+                Error::BackupStorageError(format!(
+                    "Failed to find the state snapshot! Error: {:?}",
+                    error
+                ))
+            })?
+            .manifest;
+        let transaction_manifests = metadata_view
+            .select_transaction_backups(version, version)
+            .map_err(|error| {
+                Error::BackupRestoreFailed(format!(
+                    "The transaction backups don't cover the snapshot version {}! Error: {:?}",
+                    version, error
+                ))
+            })?
+            .into_iter()
+            .map(|backup| backup.manifest)
+            .collect();
+
+        let (data_stream_listener, stream_sender) = self.create_data_stream();
+        spawn_data_stream(
+            data_stream_listener.data_stream_id,
+            stream_sender.clone(),
+            stream_transaction_output(
+                self.get_storage()?,
+                snapshot_manifest,
+                transaction_manifests,
+                version,
+                stream_sender,
+            ),
+        );
+        Ok(data_stream_listener)
+    }
+
+    async fn get_all_transaction_outputs(
+        &mut self,
+        start_version: Version,
+        epoch_ending_versions: BTreeSet<Version>,
+    ) -> Result<DataStreamListener, Error> {
+        let manifests = self
+            .get_metadata_view()
+            .await?
+            .select_transaction_backups(start_version, Version::MAX)
+            .map_err(|error| {
+                // The selection only fails if the backups have gaps, which
+                // retrying won't fix.
+                Error::BackupRestoreFailed(format!(
+                    "The transaction backups don't cover the versions from {}! Error: {:?}",
+                    start_version, error
+                ))
+            })?
+            .into_iter()
+            .map(|backup| backup.manifest)
+            .collect();
+
+        let (data_stream_listener, stream_sender) = self.create_data_stream();
+        spawn_data_stream(
+            data_stream_listener.data_stream_id,
+            stream_sender.clone(),
+            stream_transaction_outputs(
+                self.get_storage()?,
+                manifests,
+                start_version,
+                epoch_ending_versions,
+                stream_sender,
+            ),
+        );
+        Ok(data_stream_listener)
+    }
+
+    async fn get_all_state_values(
+        &mut self,
+        version: Version,
+        start_index: u64,
+    ) -> Result<DataStreamListener, Error> {
+        let snapshot_manifest = self
+            .get_metadata_view()
+            .await?
+            .expect_state_snapshot(version)
+            .map_err(|error| {
+                Error::BackupStorageError(format!(
+                    "Failed to find the state snapshot! Error: {:?}",
+                    error
+                ))
+            })?
+            .manifest;
+
+        let (data_stream_listener, stream_sender) = self.create_data_stream();
+        spawn_data_stream(
+            data_stream_listener.data_stream_id,
+            stream_sender.clone(),
+            stream_state_values(
+                self.get_storage()?,
+                snapshot_manifest,
+                start_index,
+                stream_sender,
+            ),
+        );
+        Ok(data_stream_listener)
+    }
+}
+
+/// Sends the data read from the backup storage along a data stream
+#[derive(Clone)]
+struct BackupStreamSender {
+    id_generator: Arc<AtomicU64>,
+    notification_sender: mpsc::Sender<DataNotification>,
+}
+
+impl BackupStreamSender {
+    async fn send(&mut self, data_payload: DataPayload) -> anyhow::Result<()> {
+        let data_notification = DataNotification {
+            notification_id: self.id_generator.fetch_add(1, Ordering::Relaxed),
+            data_payload,
+        };
+        self.notification_sender.send(data_notification).await?;
+        Ok(())
+    }
+}
+
+/// Spawns a task that streams data from the backup storage. The stream always
+/// ends with an end of stream notification (even on failure), after which the
+/// bootstrapper will create a new stream for any missing data.
+fn spawn_data_stream(
+    data_stream_id: DataStreamId,
+    mut stream_sender: BackupStreamSender,
+    stream_data: impl Future<Output = anyhow::Result<()>> + Send + 'static,
+) {
+    tokio::spawn(async move {
+        if let Err(error) = stream_data.await {
+            warn!(LogSchema::new(LogEntry::BackupSource).message(&format!(
+                "Failed to stream data from the backup storage! Data stream ID: {:?}, error: {:?}",
+                data_stream_id, error
+            )));
+        }
+
+        // The stream may have already been dropped by the bootstrapper
+        let _ = stream_sender.send(DataPayload::EndOfStream).await;
+    });
+}
+
+/// Streams the epoch ending ledger infos (starting at the given epoch) in the
+/// given epoch ending backups.
+async fn stream_epoch_ending_ledger_infos(
+    storage: Arc<dyn BackupStorage>,
+    manifests: Vec<FileHandle>,
+    start_epoch: u64,
+    mut stream_sender: BackupStreamSender,
+) -> anyhow::Result<()> {
+    for manifest in manifests {
+        let backup: EpochEndingBackup = storage.load_json_file(&manifest).await?;
+        backup.verify()?;
+
+        for chunk in backup.chunks {
+            if chunk.last_epoch < start_epoch {
+                continue;
+            }
+            let ledger_infos: Vec<LedgerInfoWithSignatures> =
+                read_records(&storage, &chunk.ledger_infos).await?;
+            let ledger_infos = ledger_infos
+                .into_iter()
+                .filter(|ledger_info| ledger_info.ledger_info().epoch() >= start_epoch)
+                .collect();
+            stream_sender
+                .send(DataPayload::EpochEndingLedgerInfos(ledger_infos))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Streams the transaction output at the version of the given state snapshot.
+/// The transaction (and its output) is read from the transaction backups, and
+/// the proof is taken from the state snapshot backup.
+async fn stream_transaction_output(
+    storage: Arc<dyn BackupStorage>,
+    snapshot_manifest: FileHandle,
+    transaction_manifests: Vec<FileHandle>,
+    version: Version,
+    mut stream_sender: BackupStreamSender,
+) -> anyhow::Result<()> {
+    let snapshot: StateSnapshotBackup = storage.load_json_file(&snapshot_manifest).await?;
+    let (transaction_info_with_proof, _): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+        storage.load_bcs_file(&snapshot.proof).await?;
+
+    for manifest in transaction_manifests {
+        let backup: TransactionBackup = storage.load_json_file(&manifest).await?;
+        backup.verify()?;
+
+        let chunk = backup
+            .chunks
+            .into_iter()
+            .find(|chunk| chunk.first_version <= version && version <= chunk.last_version);
+        if let Some(chunk) = chunk {
+            let mut file = BufReader::new(storage.open_for_read(&chunk.transactions).await?);
+            let mut record_version = chunk.first_version;
+            while let Some(record_bytes) = file.read_record_bytes().await? {
+                if record_version == version {
+                    let (transaction, _, events, write_set): (
+                        Transaction,
+                        TransactionInfo,
+                        Vec<ContractEvent>,
+                        WriteSet,
+                    ) = bcs::from_bytes(&record_bytes)?;
+                    let transaction_output_list_with_proof = create_transaction_output_list(
+                        version,
+                        transaction,
+                        events,
+                        write_set,
+                        transaction_info_with_proof,
+                    );
+                    return stream_sender
+                        .send(DataPayload::TransactionOutputsWithProof(
+                            transaction_output_list_with_proof,
+                        ))
+                        .await;
+                }
+                record_version += 1;
+            }
+        }
+    }
+
+    Err(anyhow!(
+        "The transaction at version {} was not found in the backup!",
+        version
+    ))
+}
+
+/// Streams the transaction outputs (starting at the given version) in the given
+/// transaction backups. Chunks are split at the given epoch ending versions, so
+/// that no payload spans an epoch change.
+async fn stream_transaction_outputs(
+    storage: Arc<dyn BackupStorage>,
+    manifests: Vec<FileHandle>,
+    start_version: Version,
+    epoch_ending_versions: BTreeSet<Version>,
+    mut stream_sender: BackupStreamSender,
+) -> anyhow::Result<()> {
+    let mut next_version = start_version;
+    for manifest in manifests {
+        let backup: TransactionBackup = storage.load_json_file(&manifest).await?;
+        backup.verify()?;
+
+        for chunk in backup.chunks {
+            if chunk.last_version < next_version {
+                continue;
+            }
+            ensure!(
+                chunk.first_version <= next_version,
+                "The transaction backups are missing versions! Expected version: {}, chunk: [{}, {}]",
+                next_version,
+                chunk.first_version,
+                chunk.last_version
+            );
+
+            // Read the chunk and its proof
+            let mut transactions_and_outputs = vec![];
+            let mut transaction_infos = vec![];
+            let records: Vec<(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)> =
+                read_records(&storage, &chunk.transactions).await?;
+            for (transaction, transaction_info, events, write_set) in records {
+                let transaction_output =
+                    create_transaction_output(&transaction_info, events, write_set);
+                transactions_and_outputs.push((transaction, transaction_output));
+                transaction_infos.push(transaction_info);
+            }
+            ensure!(
+                chunk.first_version + transaction_infos.len() as u64 == chunk.last_version + 1,
+                "The transaction backup chunk is missing transactions! Chunk: [{}, {}], transactions: {}",
+                chunk.first_version,
+                chunk.last_version,
+                transaction_infos.len()
+            );
+            let (range_proof, ledger_info): (
+                TransactionAccumulatorRangeProof,
+                LedgerInfoWithSignatures,
+            ) = storage.load_bcs_file(&chunk.proof).await?;
+            let leaf_hashes: Vec<HashValue> = transaction_infos
+                .iter()
+                .map(|transaction_info| transaction_info.hash())
+                .collect();
+
+            // Skip any versions that have already been synced
+            let num_versions_to_skip = (next_version - chunk.first_version) as usize;
+            transactions_and_outputs.drain(..num_versions_to_skip);
+            transaction_infos.drain(..num_versions_to_skip);
+
+            // Send the chunk, split at the epoch ending versions
+            while next_version <= chunk.last_version {
+                let end_version = epoch_ending_versions
+                    .range(next_version..)
+                    .next()
+                    .map_or(chunk.last_version, |epoch_ending_version| {
+                        (*epoch_ending_version).min(chunk.last_version)
+                    });
+                let proof = create_sub_range_proof(
+                    &range_proof,
+                    chunk.first_version,
+                    &leaf_hashes,
+                    next_version,
+                    end_version,
+                )?;
+                let num_versions = (end_version - next_version + 1) as usize;
+                let transaction_output_list_with_proof = TransactionOutputListWithProof::new(
+                    transactions_and_outputs.drain(..num_versions).collect(),
+                    Some(next_version),
+                    TransactionInfoListWithProof::new(
+                        proof,
+                        transaction_infos.drain(..num_versions).collect(),
+                    ),
+                );
+                stream_sender
+                    .send(DataPayload::ContinuousTransactionOutputsWithProof(
+                        ledger_info.clone(),
+                        transaction_output_list_with_proof,
+                    ))
+                    .await?;
+                next_version = end_version + 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the output of a backed up transaction
+fn create_transaction_output(
+    transaction_info: &TransactionInfo,
+    events: Vec<ContractEvent>,
+    write_set: WriteSet,
+) -> TransactionOutput {
+    TransactionOutput::new(
+        write_set,
+        events,
+        transaction_info.gas_used(),
+        TransactionStatus::Keep(transaction_info.status().clone()),
+    )
+}
+
+/// Creates a transaction output list (with proof) for the single transaction
+/// at the given version.
+fn create_transaction_output_list(
+    version: Version,
+    transaction: Transaction,
+    events: Vec<ContractEvent>,
+    write_set: WriteSet,
+    transaction_info_with_proof: TransactionInfoWithProof,
+) -> TransactionOutputListWithProof {
+    let transaction_info = transaction_info_with_proof.transaction_info;
+    let transaction_output = create_transaction_output(&transaction_info, events, write_set);
+    let range_proof = create_range_proof(
+        &transaction_info_with_proof.ledger_info_to_transaction_info_proof,
+        version,
+    );
+
+    TransactionOutputListWithProof::new(
+        vec![(transaction, transaction_output)],
+        Some(version),
+        TransactionInfoListWithProof::new(range_proof, vec![transaction_info]),
+    )
+}
+
+/// Converts the proof of a single transaction info into a range proof (for the
+/// same transaction info). Both proofs contain the same siblings (from the leaf
+/// to the root), but the range proof splits them by side.
+pub(crate) fn create_range_proof(
+    proof: &TransactionAccumulatorProof,
+    version: Version,
+) -> TransactionAccumulatorRangeProof {
+    let mut left_siblings = vec![];
+    let mut right_siblings = vec![];
+    for (level, sibling) in proof.siblings().iter().enumerate() {
+        // The node on the path is a right child iff the bit at its level is set
+        if (version >> level) & 1 == 1 {
+            left_siblings.push(*sibling);
+        } else {
+            right_siblings.push(*sibling);
+        }
+    }
+    TransactionAccumulatorRangeProof::new(left_siblings, right_siblings)
+}
+
+/// Returns the range proof for the leaves in [`sub_first_version`, `sub_last_version`],
+/// given the leaves in [`first_version`, `first_version + leaf_hashes.len()`) and
+/// their range proof. Both proofs are relative to the same root: the siblings of
+/// the sub range are either siblings of the range, or nodes computed from its leaves.
+pub(crate) fn create_sub_range_proof(
+    proof: &TransactionAccumulatorRangeProof,
+    first_version: Version,
+    leaf_hashes: &[HashValue],
+    sub_first_version: Version,
+    sub_last_version: Version,
+) -> anyhow::Result<TransactionAccumulatorRangeProof> {
+    ensure!(
+        first_version <= sub_first_version
+            && sub_first_version <= sub_last_version
+            && sub_last_version < first_version + leaf_hashes.len() as u64,
+        "The sub range [{}, {}] is not within the range of {} leaves starting at {}!",
+        sub_first_version,
+        sub_last_version,
+        leaf_hashes.len(),
+        first_version
+    );
+
+    let mut left_siblings = proof.left_siblings().iter();
+    let mut right_siblings = proof.right_siblings().iter();
+    let mut sub_left_siblings = vec![];
+    let mut sub_right_siblings = vec![];
+
+    // Walk up the accumulator (like the proof verification), computing the
+    // nodes of each level that cover the range.
+    let mut first_position = first_version;
+    let mut sub_first_position = sub_first_version;
+    let mut sub_last_position = sub_last_version;
+    let mut nodes = leaf_hashes.to_vec();
+    while nodes.len() > 1
+        || !left_siblings.as_slice().is_empty()
+        || !right_siblings.as_slice().is_empty()
+    {
+        // Complete the level with the siblings of the range, so that it only holds pairs
+        if first_position % 2 == 1 {
+            let left_sibling = left_siblings
+                .next()
+                .ok_or_else(|| anyhow!("The range proof is missing a left sibling!"))?;
+            nodes.insert(0, *left_sibling);
+            first_position -= 1;
+        }
+        if nodes.len() % 2 == 1 {
+            let right_sibling = right_siblings
+                .next()
+                .ok_or_else(|| anyhow!("The range proof is missing a right sibling!"))?;
+            nodes.push(*right_sibling);
+        }
+
+        // Take the siblings of the sub range from the completed level
+        if sub_first_position % 2 == 1 {
+            sub_left_siblings.push(nodes[(sub_first_position - 1 - first_position) as usize]);
+        }
+        if sub_last_position % 2 == 0 {
+            sub_right_siblings.push(nodes[(sub_last_position + 1 - first_position) as usize]);
+        }
+
+        nodes = nodes
+            .chunks_exact(2)
+            .map(|pair| TransactionAccumulatorInternalNode::new(pair[0], pair[1]).hash())
+            .collect();
+        first_position /= 2;
+        sub_first_position /= 2;
+        sub_last_position /= 2;
+    }
+
+    Ok(TransactionAccumulatorRangeProof::new(
+        sub_left_siblings,
+        sub_right_siblings,
+    ))
+}
+
+/// Streams the state values (starting at the given index) in the given state
+/// snapshot backup.
+async fn stream_state_values(
+    storage: Arc<dyn BackupStorage>,
+    snapshot_manifest: FileHandle,
+    start_index: u64,
+    mut stream_sender: BackupStreamSender,
+) -> anyhow::Result<()> {
+    let snapshot: StateSnapshotBackup = storage.load_json_file(&snapshot_manifest).await?;
+
+    for chunk in snapshot.chunks {
+        let last_index = chunk.last_idx as u64;
+        if last_index < start_index {
+            continue;
+        }
+        let mut raw_values: Vec<(StateKey, StateValue)> =
+            read_records(&storage, &chunk.blobs).await?;
+        let proof: SparseMerkleRangeProof = storage.load_bcs_file(&chunk.proof).await?;
+
+        // Skip any state values before the start index. The proof only
+        // contains the siblings to the right of the chunk, so it still holds.
+        let mut first_index = chunk.first_idx as u64;
+        let mut first_key = chunk.first_key;
+        if first_index < start_index {
+            let num_values_to_skip = (start_index - first_index) as usize;
+            ensure!(
+                num_values_to_skip < raw_values.len(),
+                "The state snapshot chunk is missing state values! Chunk: [{}, {}], values: {}",
+                first_index,
+                last_index,
+                raw_values.len()
+            );
+            raw_values.drain(..num_values_to_skip);
+            first_index = start_index;
+            first_key = raw_values[0].0.hash();
+        }
+
+        let state_value_chunk_with_proof = StateValueChunkWithProof {
+            first_index,
+            last_index,
+            first_key,
+            last_key: chunk.last_key,
+            raw_values,
+            proof,
+            root_hash: snapshot.root_hash,
+        };
+        stream_sender
+            .send(DataPayload::StateValuesWithProof(
+                state_value_chunk_with_proof,
+            ))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Reads all BCS serialized (and length prefixed) records in the given file
+async fn read_records<T: DeserializeOwned>(
+    storage: &Arc<dyn BackupStorage>,
+    file_handle: &FileHandleRef,
+) -> anyhow::Result<Vec<T>> {
+    let mut file = BufReader::new(storage.open_for_read(file_handle).await?);
+    let mut records = vec![];
+    while let Some(record_bytes) = file.read_record_bytes().await? {
+        records.push(bcs::from_bytes(&record_bytes)?);
+    }
+    Ok(records)
+}
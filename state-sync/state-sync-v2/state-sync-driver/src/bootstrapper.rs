@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_source::BackupSourceInterface,
     driver::DriverConfiguration,
     error::Error,
     logging::{LogEntry, LogSchema},
@@ -221,6 +222,40 @@ impl VerifiedEpochStates {
         Ok(highest_known_ledger_info)
     }
 
+    /// Returns the epoch of the latest verified epoch state
+    pub fn latest_epoch(&self) -> u64 {
+        self.latest_epoch_state.epoch
+    }
+
+    /// Verifies the given ledger info against the verified epoch state of its
+    /// epoch (i.e., the latest epoch state, or the next epoch state of an
+    /// epoch ending ledger info fetched from the network).
+    pub fn verify_ledger_info(
+        &self,
+        ledger_info_with_signatures: &LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        let epoch = ledger_info_with_signatures.ledger_info().epoch();
+        let epoch_state = if epoch == self.latest_epoch_state.epoch {
+            Some(&self.latest_epoch_state)
+        } else {
+            self.new_epoch_ending_ledger_infos
+                .values()
+                .filter_map(|ledger_info| ledger_info.ledger_info().next_epoch_state())
+                .find(|epoch_state| epoch_state.epoch == epoch)
+        };
+        let epoch_state = epoch_state.ok_or_else(|| {
+            Error::VerificationError(format!(
+                "No verified epoch state was found for epoch: {:?}",
+                epoch
+            ))
+        })?;
+        epoch_state
+            .verify(ledger_info_with_signatures)
+            .map_err(|error| {
+                Error::VerificationError(format!("Ledger info failed verification: {:?}", error))
+            })
+    }
+
     /// Returns the next epoch ending version after the given version (if one
     /// exists).
     pub fn next_epoch_ending_version(&self, version: Version) -> Option<Version> {
@@ -281,12 +316,24 @@ impl StateValueSyncer {
 
 /// A simple component that manages the bootstrapping of the node
 pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
-    // The currently active data stream (provided by the data streaming service)
+    // The currently active data stream (provided by the data streaming service,
+    // or the backup source when restoring from a backup).
     active_data_stream: Option<DataStreamListener>,
 
+    // The source of data when restoring from a backup (instead of the network)
+    backup_source: Option<Box<dyn BackupSourceInterface>>,
+
+    // The error that stopped the restore from the backup (if any). This is
+    // terminal, e.g., the backup can't cover the versions the node requires.
+    backup_restore_error: Option<Error>,
+
     // The channel used to notify a listener of successful bootstrapping
     bootstrap_notifier_channel: Option<oneshot::Sender<Result<(), Error>>>,
 
+    // If all verifiable transactions in the backup have been restored
+    // (i.e., the rest must be synced from peers).
+    restored_backup_transactions: bool,
+
     // If the node has completed bootstrapping
     bootstrapped: bool,
 
@@ -325,6 +372,7 @@ impl<
     > Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient>
 {
     pub fn new(
+        backup_source: Option<Box<dyn BackupSourceInterface>>,
         driver_configuration: DriverConfiguration,
         metadata_storage: MetadataStorage,
         output_fallback_handler: OutputFallbackHandler,
@@ -340,7 +388,10 @@ impl<
         Self {
             state_value_syncer: StateValueSyncer::new(),
             active_data_stream: None,
+            backup_source,
+            backup_restore_error: None,
             bootstrap_notifier_channel: None,
+            restored_backup_transactions: false,
             bootstrapped: false,
             driver_configuration,
             metadata_storage,
@@ -363,6 +414,16 @@ impl<
         self.bootstrapped
     }
 
+    /// Returns true iff the bootstrapper can make progress without any peers
+    /// (i.e., the node is still restoring from a backup).
+    pub fn can_bootstrap_without_peers(&self) -> bool {
+        !self.is_bootstrapped()
+            && matches!(
+                self.get_bootstrapping_mode(),
+                BootstrappingMode::RestoreFromBackup
+            )
+    }
+
     /// Marks bootstrapping as complete and notifies any listeners
     pub async fn bootstrapping_complete(&mut self) -> Result<(), Error> {
         info!(LogSchema::new(LogEntry::Bootstrapper)
@@ -371,6 +432,16 @@ impl<
         self.notify_listeners_if_bootstrapped().await
     }
 
+    /// Stops restoring from the backup and notifies any listeners of the error
+    async fn backup_restore_failed(&mut self, error: Error) -> Result<(), Error> {
+        error!(LogSchema::new(LogEntry::Bootstrapper)
+            .error(&error)
+            .message("Failed to restore from the backup! Bootstrapping will not continue."));
+        self.backup_restore_error = Some(error.clone());
+        self.notify_listeners_if_bootstrapped().await?;
+        Err(error)
+    }
+
     /// Subscribes the specified channel to bootstrap completion notifications
     pub async fn subscribe_to_bootstrap_notifications(
         &mut self,
@@ -386,7 +457,8 @@ impl<
         self.notify_listeners_if_bootstrapped().await
     }
 
-    /// Notifies any listeners if we've now bootstrapped
+    /// Notifies any listeners if we've now bootstrapped (or failed to restore
+    /// from the backup).
     async fn notify_listeners_if_bootstrapped(&mut self) -> Result<(), Error> {
         if self.is_bootstrapped() {
             if let Some(notifier_channel) = self.bootstrap_notifier_channel.take() {
@@ -399,6 +471,15 @@ impl<
             }
             self.reset_active_stream(None).await?;
             self.storage_synchronizer.finish_chunk_executor(); // The bootstrapper is now complete
+        } else if let Some(backup_restore_error) = &self.backup_restore_error {
+            if let Some(notifier_channel) = self.bootstrap_notifier_channel.take() {
+                if let Err(error) = notifier_channel.send(Err(backup_restore_error.clone())) {
+                    return Err(Error::CallbackSendFailed(format!(
+                        "Bootstrap notification error: {:?}",
+                        error
+                    )));
+                }
+            }
         }
 
         Ok(())
@@ -414,6 +495,9 @@ impl<
                 "The bootstrapper should not attempt to make progress!".into(),
             ));
         }
+        if let Some(error) = &self.backup_restore_error {
+            return Err(error.clone());
+        }
 
         if self.active_data_stream.is_some() {
             // We have an active data stream. Process any notifications!
@@ -426,8 +510,13 @@ impl<
             );
         } else {
             // Fetch a new data stream to start streaming data
-            self.initialize_active_data_stream(global_data_summary)
-                .await?;
+            let result = self
+                .initialize_active_data_stream(global_data_summary)
+                .await;
+            if let Err(error @ Error::BackupRestoreFailed(_)) = result {
+                return self.backup_restore_failed(error).await;
+            }
+            result?;
         }
 
         // Check if we've now bootstrapped
@@ -453,14 +542,36 @@ impl<
 
         // Always fetch the new epoch ending ledger infos first
         if self.should_fetch_epoch_ending_ledger_infos() {
+            return match self.get_bootstrapping_mode() {
+                BootstrappingMode::RestoreFromBackup => {
+                    self.fetch_epoch_ending_ledger_infos_from_backup().await
+                },
+                _ => {
+                    self.fetch_epoch_ending_ledger_infos(global_data_summary)
+                        .await
+                },
+            };
+        }
+
+        // When restoring from a backup, the transactions in the backup are
+        // restored once the state snapshot has been restored.
+        let highest_synced_version = utils::fetch_latest_synced_version(self.storage.clone())?;
+        if matches!(
+            self.get_bootstrapping_mode(),
+            BootstrappingMode::RestoreFromBackup
+        ) && highest_synced_version > GENESIS_TRANSACTION_VERSION
+        {
             return self
-                .fetch_epoch_ending_ledger_infos(global_data_summary)
+                .fetch_missing_transaction_outputs_from_backup(highest_synced_version)
                 .await;
         }
 
-        // Get the highest synced and known ledger info versions
-        let highest_synced_version = utils::fetch_latest_synced_version(self.storage.clone())?;
-        let highest_known_ledger_info = self.get_highest_known_ledger_info()?;
+        // Get the highest known ledger info version. When restoring from a
+        // backup, the highest known ledger info is the one we can restore.
+        let highest_known_ledger_info = match self.get_bootstrapping_mode() {
+            BootstrappingMode::RestoreFromBackup => self.get_backup_ledger_info_to_sync().await?,
+            _ => self.get_highest_known_ledger_info()?,
+        };
         let highest_known_ledger_version = highest_known_ledger_info.ledger_info().version();
 
         // If we've already synced to the highest known version, there's nothing to do
@@ -477,7 +588,7 @@ impl<
 
         // Bootstrap according to the mode
         match self.get_bootstrapping_mode() {
-            BootstrappingMode::DownloadLatestStates => {
+            BootstrappingMode::DownloadLatestStates | BootstrappingMode::RestoreFromBackup => {
                 self.fetch_missing_state_snapshot_data(
                     highest_synced_version,
                     highest_known_ledger_info,
@@ -549,6 +660,52 @@ impl<
         }
     }
 
+    /// Fetches the transaction outputs in the backup that follow the highest
+    /// synced version. Once there are no more (verifiable) transactions in the
+    /// backup, bootstrapping is complete and the node syncs from its peers.
+    async fn fetch_missing_transaction_outputs_from_backup(
+        &mut self,
+        highest_synced_version: Version,
+    ) -> Result<(), Error> {
+        let highest_backup_version = self
+            .get_backup_source()?
+            .highest_transaction_version()
+            .await?;
+        if self.restored_backup_transactions
+            || highest_backup_version.map_or(true, |version| version <= highest_synced_version)
+        {
+            info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "Restored all transactions in the backup! Highest synced version: {}, \
+                highest backup version: {:?}.",
+                highest_synced_version, highest_backup_version
+            )));
+            return self.bootstrapping_complete().await;
+        }
+
+        // Stream the transaction outputs, split at the epoch ending versions
+        let next_version = highest_synced_version.checked_add(1).ok_or_else(|| {
+            Error::IntegerOverflow("The next output version has overflown!".into())
+        })?;
+        let epoch_ending_versions = self
+            .verified_epoch_states
+            .all_epoch_ending_ledger_infos()
+            .iter()
+            .map(|ledger_info| ledger_info.ledger_info().version())
+            .collect();
+        let data_stream = self
+            .get_backup_source()?
+            .get_all_transaction_outputs(next_version, epoch_ending_versions)
+            .await?;
+        self.speculative_stream_state = Some(SpeculativeStreamState::new(
+            utils::fetch_latest_epoch_state(self.storage.clone())?,
+            None,
+            highest_synced_version,
+        ));
+        self.active_data_stream = Some(data_stream);
+
+        Ok(())
+    }
+
     /// Attempts to fetch a data notification from the active stream
     async fn fetch_next_data_notification(&mut self) -> Result<DataNotification, Error> {
         let max_stream_wait_time_ms = self.driver_configuration.config.max_stream_wait_time_ms;
@@ -612,6 +769,22 @@ impl<
                     )
                     .await?;
                 },
+                DataPayload::ContinuousTransactionOutputsWithProof(
+                    ledger_info_with_signatures,
+                    transaction_outputs_with_proof,
+                ) => {
+                    self.process_backup_transaction_outputs_payload(
+                        data_notification.notification_id,
+                        ledger_info_with_signatures,
+                        transaction_outputs_with_proof,
+                    )
+                    .await?;
+
+                    // The stream is reset once the rest of the backup can't be verified
+                    if self.active_data_stream.is_none() {
+                        return Ok(());
+                    }
+                },
                 _ => {
                     return self
                         .handle_end_of_stream_or_invalid_payload(data_notification)
@@ -646,13 +819,22 @@ impl<
         let target_ledger_info_version = target_ledger_info.ledger_info().version();
         let data_stream = if self.state_value_syncer.transaction_output_to_sync.is_none() {
             // Fetch the transaction info first, before the states
-            self.streaming_client
-                .get_all_transaction_outputs(
-                    target_ledger_info_version,
-                    target_ledger_info_version,
-                    target_ledger_info_version,
-                )
-                .await?
+            match self.get_bootstrapping_mode() {
+                BootstrappingMode::RestoreFromBackup => {
+                    self.get_backup_source()?
+                        .get_transaction_output(target_ledger_info_version)
+                        .await?
+                },
+                _ => {
+                    self.streaming_client
+                        .get_all_transaction_outputs(
+                            target_ledger_info_version,
+                            target_ledger_info_version,
+                            target_ledger_info_version,
+                        )
+                        .await?
+                },
+            }
         } else {
             // Identify the next state index to fetch
             let next_state_index_to_process = if existing_snapshot_progress {
@@ -676,12 +858,24 @@ impl<
             // Fetch the missing state values
             self.state_value_syncer
                 .update_next_state_index_to_process(next_state_index_to_process);
-            self.streaming_client
-                .get_all_state_values(
-                    target_ledger_info_version,
-                    Some(next_state_index_to_process),
-                )
-                .await?
+            match self.get_bootstrapping_mode() {
+                BootstrappingMode::RestoreFromBackup => {
+                    self.get_backup_source()?
+                        .get_all_state_values(
+                            target_ledger_info_version,
+                            next_state_index_to_process,
+                        )
+                        .await?
+                },
+                _ => {
+                    self.streaming_client
+                        .get_all_state_values(
+                            target_ledger_info_version,
+                            Some(next_state_index_to_process),
+                        )
+                        .await?
+                },
+            }
         };
         self.active_data_stream = Some(data_stream);
 
@@ -788,20 +982,7 @@ impl<
             })?;
 
         // Fetch the highest epoch end known locally
-        let highest_known_ledger_info = self.get_highest_known_ledger_info()?;
-        let highest_known_ledger_info = highest_known_ledger_info.ledger_info();
-        let highest_local_epoch_end = if highest_known_ledger_info.ends_epoch() {
-            highest_known_ledger_info.epoch()
-        } else if highest_known_ledger_info.epoch() > 0 {
-            highest_known_ledger_info
-                .epoch()
-                .checked_sub(1)
-                .ok_or_else(|| {
-                    Error::IntegerOverflow("The highest local epoch end has overflown!".into())
-                })?
-        } else {
-            unreachable!("Genesis should always end the first epoch!");
-        };
+        let highest_local_epoch_end = self.get_highest_local_epoch_end()?;
 
         // Compare the highest local epoch end to the highest advertised epoch end
         if highest_local_epoch_end > highest_advertised_epoch_end {
@@ -837,6 +1018,115 @@ impl<
         Ok(())
     }
 
+    /// Fetches all epoch ending ledger infos (from the current epoch to the
+    /// highest epoch that has ended in the backup).
+    async fn fetch_epoch_ending_ledger_infos_from_backup(&mut self) -> Result<(), Error> {
+        // If our storage has already synced beyond our waypoint, the waypoint is verified
+        self.verify_waypoint_if_synced()?;
+
+        // Get the highest epoch that has ended in the backup
+        let highest_backup_epoch_end = self
+            .get_backup_source()?
+            .highest_epoch_ending_epoch()
+            .await?
+            .ok_or_else(|| {
+                Error::BackupStorageError(
+                    "No epoch ending ledger infos found in the backup!".into(),
+                )
+            })?;
+
+        // Compare the highest local epoch end to the highest epoch end in the backup
+        let highest_local_epoch_end = self.get_highest_local_epoch_end()?;
+        if highest_local_epoch_end < highest_backup_epoch_end {
+            info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "Found higher epoch ending ledger infos in the backup! Local: {:?}, backup: {:?}",
+                highest_local_epoch_end, highest_backup_epoch_end
+            )));
+            let next_epoch_end = highest_local_epoch_end.checked_add(1).ok_or_else(|| {
+                Error::IntegerOverflow("The next epoch end has overflown!".into())
+            })?;
+            let epoch_ending_stream = self
+                .get_backup_source()?
+                .get_all_epoch_ending_ledger_infos(next_epoch_end)
+                .await?;
+            self.active_data_stream = Some(epoch_ending_stream);
+        } else if self.verified_epoch_states.verified_waypoint() {
+            info!(LogSchema::new(LogEntry::Bootstrapper)
+                .message("No new epoch ending ledger infos to fetch from the backup!"));
+            self.verified_epoch_states
+                .set_fetched_epoch_ending_ledger_infos();
+        } else {
+            return Err(Error::BackupStorageError("Our waypoint is unverified, but there's no higher epoch ending ledger infos in the backup!".into()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the epoch ending ledger info at the version of the latest state
+    /// snapshot in the backup (i.e., the ledger info to restore the node to).
+    /// Only snapshots at verified epoch ending versions can be restored.
+    async fn get_backup_ledger_info_to_sync(&mut self) -> Result<LedgerInfoWithSignatures, Error> {
+        let epoch_ending_versions = self
+            .verified_epoch_states
+            .all_epoch_ending_ledger_infos()
+            .iter()
+            .map(|ledger_info| ledger_info.ledger_info().version())
+            .collect();
+        let snapshot_version = self
+            .get_backup_source()?
+            .find_latest_state_snapshot(epoch_ending_versions)
+            .await?;
+        if let Some(ledger_info_to_sync) = snapshot_version.and_then(|version| {
+            self.verified_epoch_states
+                .get_epoch_ending_ledger_info(version)
+        }) {
+            return Ok(ledger_info_to_sync);
+        }
+
+        // Otherwise, the node may have already synced beyond all snapshots in the backup
+        let latest_synced_ledger_info =
+            utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
+        if latest_synced_ledger_info.ledger_info().version() > GENESIS_TRANSACTION_VERSION {
+            Ok(latest_synced_ledger_info)
+        } else {
+            Err(Error::BackupStorageError(
+                "No state snapshot was found in the backup at a verified epoch ending version!"
+                    .into(),
+            ))
+        }
+    }
+
+    /// Returns the highest epoch that has ended locally (including the
+    /// newly fetched epoch ending ledger infos).
+    fn get_highest_local_epoch_end(&self) -> Result<u64, Error> {
+        let highest_known_ledger_info = self.get_highest_known_ledger_info()?;
+        let highest_known_ledger_info = highest_known_ledger_info.ledger_info();
+        if highest_known_ledger_info.ends_epoch() {
+            Ok(highest_known_ledger_info.epoch())
+        } else if highest_known_ledger_info.epoch() > 0 {
+            highest_known_ledger_info
+                .epoch()
+                .checked_sub(1)
+                .ok_or_else(|| {
+                    Error::IntegerOverflow("The highest local epoch end has overflown!".into())
+                })
+        } else {
+            unreachable!("Genesis should always end the first epoch!");
+        }
+    }
+
+    /// Marks the waypoint as verified if our storage has already synced beyond
+    /// it. Returns true iff the waypoint was marked as verified.
+    fn verify_waypoint_if_synced(&mut self) -> Result<bool, Error> {
+        let latest_ledger_info = utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
+        let waypoint_version = self.driver_configuration.waypoint.version();
+        if latest_ledger_info.ledger_info().version() >= waypoint_version {
+            self.verified_epoch_states.set_verified_waypoint();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     /// Verifies that connected peers have advertised data beyond our waypoint
     /// or that our waypoint is trivially satisfiable.
     fn verify_waypoint_is_satisfiable(
@@ -844,12 +1134,10 @@ impl<
         global_data_summary: &GlobalDataSummary,
     ) -> Result<(), Error> {
         // If our storage has already synced beyond our waypoint, nothing needs to be checked
-        let latest_ledger_info = utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
-        let waypoint_version = self.driver_configuration.waypoint.version();
-        if latest_ledger_info.ledger_info().version() >= waypoint_version {
-            self.verified_epoch_states.set_verified_waypoint();
+        if self.verify_waypoint_if_synced()? {
             return Ok(());
         }
+        let waypoint_version = self.driver_configuration.waypoint.version();
 
         // Get the highest advertised synced ledger info version
         let highest_advertised_ledger_info = global_data_summary
@@ -927,9 +1215,7 @@ impl<
     ) -> Result<(), Error> {
         // Verify that we're expecting state value payloads
         let bootstrapping_mode = self.get_bootstrapping_mode();
-        if self.should_fetch_epoch_ending_ledger_infos()
-            || !matches!(bootstrapping_mode, BootstrappingMode::DownloadLatestStates)
-        {
+        if self.should_fetch_epoch_ending_ledger_infos() || !bootstrapping_mode.is_fast_sync() {
             self.reset_active_stream(Some(NotificationAndFeedback::new(
                 notification_id,
                 NotificationFeedback::InvalidPayloadData,
@@ -946,8 +1232,17 @@ impl<
 
         // Initialize the state value synchronizer (if not already done)
         if !self.state_value_syncer.initialized_state_snapshot_receiver {
-            // Fetch all verified epoch change proofs
-            let epoch_change_proofs = self.verified_epoch_states.all_epoch_ending_ledger_infos();
+            // Fetch all verified epoch change proofs (up to the ledger info to sync).
+            // When restoring from a backup, we may know of higher epochs.
+            let ledger_info_to_sync_version = ledger_info_to_sync.ledger_info().version();
+            let epoch_change_proofs = self
+                .verified_epoch_states
+                .all_epoch_ending_ledger_infos()
+                .into_iter()
+                .filter(|ledger_info| {
+                    ledger_info.ledger_info().version() <= ledger_info_to_sync_version
+                })
+                .collect();
 
             // Initialize the state value synchronizer
             let _join_handle = self.storage_synchronizer.initialize_state_synchronizer(
@@ -1078,7 +1373,7 @@ impl<
         // Verify that we're expecting transaction or output payloads
         let bootstrapping_mode = self.get_bootstrapping_mode();
         if self.should_fetch_epoch_ending_ledger_infos()
-            || (matches!(bootstrapping_mode, BootstrappingMode::DownloadLatestStates)
+            || (bootstrapping_mode.is_fast_sync()
                 && self.state_value_syncer.transaction_output_to_sync.is_some())
         {
             self.reset_active_stream(Some(NotificationAndFeedback::new(
@@ -1092,7 +1387,7 @@ impl<
         }
 
         // If we're state syncing, we expect a single transaction info
-        if matches!(bootstrapping_mode, BootstrappingMode::DownloadLatestStates) {
+        if bootstrapping_mode.is_fast_sync() {
             return self
                 .verify_transaction_info_to_sync(
                     notification_id,
@@ -1218,6 +1513,99 @@ impl<
         Ok(())
     }
 
+    /// Process a transaction output payload restored from the backup. The payload
+    /// is proven by the ledger info it was backed up with, which is verified
+    /// against the epoch state of its epoch.
+    async fn process_backup_transaction_outputs_payload(
+        &mut self,
+        notification_id: NotificationId,
+        ledger_info_with_signatures: LedgerInfoWithSignatures,
+        transaction_outputs_with_proof: TransactionOutputListWithProof,
+    ) -> Result<(), Error> {
+        // Verify that we're expecting transaction outputs from the backup
+        if self.should_fetch_epoch_ending_ledger_infos()
+            || !matches!(
+                self.get_bootstrapping_mode(),
+                BootstrappingMode::RestoreFromBackup
+            )
+            || self.speculative_stream_state.is_none()
+        {
+            self.reset_active_stream(Some(NotificationAndFeedback::new(
+                notification_id,
+                NotificationFeedback::InvalidPayloadData,
+            )))
+            .await?;
+            return Err(Error::InvalidPayload(
+                "Received an unexpected continuous transaction output payload!".into(),
+            ));
+        }
+
+        // Verify the payload starting version
+        let expected_start_version = self
+            .get_speculative_stream_state()?
+            .expected_next_version()?;
+        let payload_start_version = self
+            .verify_payload_start_version(
+                notification_id,
+                transaction_outputs_with_proof.first_transaction_output_version,
+                expected_start_version,
+            )
+            .await?;
+
+        // Transactions proven by a ledger info of an unknown epoch can only be
+        // synced from peers (once the epoch ending ledger infos are known).
+        let proof_epoch = ledger_info_with_signatures.ledger_info().epoch();
+        if proof_epoch > self.verified_epoch_states.latest_epoch() {
+            info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "The remaining transactions in the backup can't be verified! Proof epoch: {}, \
+                latest verified epoch: {}.",
+                proof_epoch,
+                self.verified_epoch_states.latest_epoch()
+            )));
+            self.restored_backup_transactions = true;
+            return self.reset_active_stream(None).await;
+        }
+
+        // Verify the proof ledger info
+        if let Err(error) = self
+            .verified_epoch_states
+            .verify_ledger_info(&ledger_info_with_signatures)
+        {
+            self.reset_active_stream(Some(NotificationAndFeedback::new(
+                notification_id,
+                NotificationFeedback::PayloadProofFailed,
+            )))
+            .await?;
+            return Err(error);
+        }
+
+        // Apply and commit the transaction outputs
+        let num_transaction_outputs = transaction_outputs_with_proof
+            .transactions_and_outputs
+            .len();
+        let payload_end_version = payload_start_version
+            .checked_add(num_transaction_outputs as u64)
+            .and_then(|version| version.checked_sub(1)) // end_version = start + num outputs - 1
+            .ok_or_else(|| {
+                Error::IntegerOverflow("The payload end version has overflown!".into())
+            })?;
+        let end_of_epoch_ledger_info = self
+            .verified_epoch_states
+            .get_epoch_ending_ledger_info(payload_end_version);
+        utils::apply_transaction_outputs(
+            self.storage_synchronizer.clone(),
+            notification_id,
+            ledger_info_with_signatures,
+            end_of_epoch_ledger_info,
+            transaction_outputs_with_proof,
+        )
+        .await?;
+        self.get_speculative_stream_state()?
+            .update_synced_version(payload_end_version);
+
+        Ok(())
+    }
+
     /// Verifies the payload contains the transaction info we require to
     /// download all state values.
     async fn verify_transaction_info_to_sync(
@@ -1438,6 +1826,13 @@ impl<
         }
     }
 
+    /// Returns the backup source (when restoring from a backup)
+    fn get_backup_source(&mut self) -> Result<&mut dyn BackupSourceInterface, Error> {
+        self.backup_source
+            .as_deref_mut()
+            .ok_or_else(|| Error::UnexpectedError("The backup source is missing!".into()))
+    }
+
     /// Returns the speculative stream state
     fn get_speculative_stream_state(&mut self) -> Result<&mut SpeculativeStreamState, Error> {
         self.speculative_stream_state.as_mut().ok_or_else(|| {
@@ -1491,13 +1886,19 @@ impl<
         notification_and_feedback: Option<NotificationAndFeedback>,
     ) -> Result<(), Error> {
         if let Some(active_data_stream) = &self.active_data_stream {
-            let data_stream_id = active_data_stream.data_stream_id;
-            utils::terminate_stream_with_feedback(
-                &mut self.streaming_client,
-                data_stream_id,
-                notification_and_feedback,
-            )
-            .await?;
+            // Streams from the backup source are terminated by dropping them
+            if !matches!(
+                self.get_bootstrapping_mode(),
+                BootstrappingMode::RestoreFromBackup
+            ) {
+                let data_stream_id = active_data_stream.data_stream_id;
+                utils::terminate_stream_with_feedback(
+                    &mut self.streaming_client,
+                    data_stream_id,
+                    notification_and_feedback,
+                )
+                .await?;
+            }
         }
 
         self.active_data_stream = None;
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_source::BackupSourceInterface,
     bootstrapper::Bootstrapper,
     continuous_syncer::ContinuousSyncer,
     driver_client::{ClientNotificationListener, DriverNotification},
@@ -57,14 +58,24 @@ pub struct DriverConfiguration {
 
     // The trusted waypoint for the node
     pub waypoint: Waypoint,
+
+    // The time without consensus notifications after which state sync stops
+    // waiting for the consensus observer (if the node runs an observer)
+    pub consensus_observer_fallback_timeout: Option<Duration>,
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        role: RoleType,
+        waypoint: Waypoint,
+        consensus_observer_fallback_timeout: Option<Duration>,
+    ) -> Self {
         Self {
             config,
             role,
             waypoint,
+            consensus_observer_fallback_timeout,
         }
     }
 }
@@ -107,6 +118,9 @@ pub struct StateSyncDriver<
     // The handler for notifications to mempool
     mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
 
+    // The timestamp of the last (valid) notification from consensus
+    last_consensus_notification_time: Option<Instant>,
+
     // The timestamp at which the driver started executing
     start_time: Option<Instant>,
 
@@ -130,6 +144,7 @@ impl<
     StateSyncDriver<DataClient, MempoolNotifier, MetadataStorage, StorageSyncer, StreamingClient>
 {
     pub fn new(
+        backup_source: Option<Box<dyn BackupSourceInterface>>,
         client_notification_listener: ClientNotificationListener,
         commit_notification_listener: CommitNotificationListener,
         consensus_notification_handler: ConsensusNotificationHandler,
@@ -147,6 +162,7 @@ impl<
         let output_fallback_handler =
             OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
         let bootstrapper = Bootstrapper::new(
+            backup_source,
             driver_configuration.clone(),
             metadata_storage,
             output_fallback_handler.clone(),
@@ -173,6 +189,7 @@ impl<
             error_notification_listener,
             event_subscription_service,
             mempool_notification_handler,
+            last_consensus_notification_time: None,
             start_time: None,
             storage,
             storage_synchronizer,
@@ -213,9 +230,12 @@ impl<
 
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
-        // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // Verify the notification: full nodes shouldn't receive notifications (unless
+        // they run a consensus observer) and consensus should only send notifications
+        // after bootstrapping!
+        let result = if self.driver_configuration.role == RoleType::FullNode
+            && !self.is_consensus_observer()
+        {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
@@ -250,6 +270,7 @@ impl<
                 .message("Error encountered when handling the consensus notification!"));
             return;
         }
+        self.last_consensus_notification_time = Some(self.time_service.now());
 
         // Handle the notification
         let result = match notification {
@@ -504,6 +525,9 @@ impl<
         if !self.active_sync_request() {
             self.continuous_syncer.reset_active_stream(None).await?;
             self.storage_synchronizer.finish_chunk_executor(); // Consensus is now in control
+
+            // The sync may have taken longer than the observer fallback timeout
+            self.last_consensus_notification_time = Some(self.time_service.now());
         }
         Ok(())
     }
@@ -518,9 +542,36 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
-    /// Returns true iff consensus is currently executing
+    /// Returns true iff this node runs a consensus observer
+    fn is_consensus_observer(&self) -> bool {
+        self.driver_configuration
+            .consensus_observer_fallback_timeout
+            .is_some()
+    }
+
+    /// Returns true iff this node runs a consensus observer that notified us
+    /// recently. Otherwise, the observer has fallen back to state sync.
+    fn is_consensus_observer_active(&self) -> bool {
+        match (
+            self.driver_configuration
+                .consensus_observer_fallback_timeout,
+            self.last_consensus_notification_time,
+        ) {
+            (Some(fallback_timeout), Some(last_notification_time)) => {
+                self.time_service
+                    .now()
+                    .duration_since(last_notification_time)
+                    < fallback_timeout
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns true iff consensus (or the consensus observer) is currently executing
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator() && self.bootstrapper.is_bootstrapped() && !self.active_sync_request()
+        (self.is_validator() || self.is_consensus_observer_active())
+            && self.bootstrapper.is_bootstrapped()
+            && !self.active_sync_request()
     }
 
     /// Checks if the connection deadline has passed. If so, validators with
@@ -560,8 +611,9 @@ impl<
     /// Checks that state sync is making progress
     async fn drive_progress(&mut self) {
         // Fetch the global data summary and verify we have active peers
+        // (unless we're bootstrapping from a backup, which doesn't need peers).
         let global_data_summary = self.aptos_data_client.get_global_data_summary();
-        if global_data_summary.is_empty() {
+        if global_data_summary.is_empty() && !self.bootstrapper.can_bootstrap_without_peers() {
             trace!(LogSchema::new(LogEntry::Driver).message(
                 "The global data summary is empty! It's likely that we have no active peers."
             ));
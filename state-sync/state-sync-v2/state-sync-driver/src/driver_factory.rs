@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_source::{BackupSource, BackupSourceInterface},
     driver::{DriverConfiguration, StateSyncDriver},
     driver_client::{ClientNotificationListener, DriverClient, DriverNotification},
     metadata_storage::MetadataStorageInterface,
@@ -12,7 +13,7 @@ use crate::{
     },
     storage_synchronizer::StorageSynchronizer,
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{BootstrappingMode, NodeConfig};
 use aptos_consensus_notifications::ConsensusNotificationListener;
 use aptos_data_client::aptosnet::AptosNetDataClient;
 use aptos_data_streaming_service::streaming_client::StreamingServiceClient;
@@ -24,9 +25,12 @@ use aptos_storage_interface::DbReaderWriter;
 use aptos_time_service::TimeService;
 use aptos_types::{move_resource::MoveStorage, waypoint::Waypoint};
 use futures::{channel::mpsc, executor::block_on};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::runtime::Runtime;
 
+// The directory (in the storage directory) in which to cache the backup metadata
+const BACKUP_METADATA_CACHE_DIR: &str = "backup_metadata_cache";
+
 /// Creates a new state sync driver and client
 pub struct DriverFactory {
     client_notification_sender: mpsc::UnboundedSender<DriverNotification>,
@@ -90,7 +94,7 @@ impl DriverFactory {
 
         // Create the storage synchronizer
         let event_subscription_service = Arc::new(Mutex::new(event_subscription_service));
-        let (storage_synchronizer, _, _) = StorageSynchronizer::new(
+        let (storage_synchronizer, _, _, _) = StorageSynchronizer::new(
             node_config.state_sync.state_sync_driver,
             chunk_executor,
             commit_notification_sender,
@@ -103,14 +107,44 @@ impl DriverFactory {
         );
 
         // Create the driver configuration
+        let consensus_observer_fallback_timeout = node_config
+            .consensus_observer
+            .observer_enabled
+            .then(|| {
+                Duration::from_millis(
+                    node_config
+                        .consensus_observer
+                        .state_sync_fallback_timeout_ms(),
+                )
+            });
         let driver_configuration = DriverConfiguration::new(
             node_config.state_sync.state_sync_driver,
             node_config.base.role,
             waypoint,
+            consensus_observer_fallback_timeout,
         );
 
+        // Create the backup source (if we're restoring from a backup). The config
+        // validation guarantees the backup storage exists in this mode.
+        let backup_source = node_config
+            .state_sync
+            .backup_storage
+            .as_ref()
+            .filter(|_| {
+                node_config.state_sync.state_sync_driver.bootstrapping_mode
+                    == BootstrappingMode::RestoreFromBackup
+            })
+            .map(|backup_storage_config| {
+                let metadata_cache_dir = node_config.storage.dir().join(BACKUP_METADATA_CACHE_DIR);
+                Box::new(BackupSource::new(
+                    backup_storage_config.clone(),
+                    Some(metadata_cache_dir),
+                )) as Box<dyn BackupSourceInterface>
+            });
+
         // Create the state sync driver
         let state_sync_driver = StateSyncDriver::new(
+            backup_source,
             client_notification_listener,
             commit_notification_listener,
             consensus_notification_handler,
@@ -13,6 +13,10 @@ pub enum Error {
     AlreadyBootstrapped(String),
     #[error("Advertised data error: {0}")]
     AdvertisedDataError(String),
+    #[error("Backup storage error: {0}")]
+    BackupStorageError(String),
+    #[error("The backup can't be restored: {0}")]
+    BackupRestoreFailed(String),
     #[error("State sync has not yet finished bootstrapping! Error: {0}")]
     BootstrapNotComplete(String),
     #[error("Failed to send callback: {0}")]
@@ -51,6 +55,8 @@ impl Error {
         match self {
             Error::AlreadyBootstrapped(_) => "already_boostrapped",
             Error::AdvertisedDataError(_) => "advertised_data_error",
+            Error::BackupStorageError(_) => "backup_storage_error",
+            Error::BackupRestoreFailed(_) => "backup_restore_failed",
             Error::BootstrapNotComplete(_) => "bootstrap_not_complete",
             Error::CallbackSendFailed(_) => "callback_send_failed",
             Error::CriticalDataStreamTimeout(_) => "critical_data_stream_timeout",
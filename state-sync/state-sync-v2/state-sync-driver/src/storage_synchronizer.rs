@@ -130,7 +130,7 @@ pub struct StorageSynchronizer<ChunkExecutor, MetadataStorage> {
     // The storage to write metadata about the syncing progress
     metadata_storage: MetadataStorage,
 
-    // The number of storage data chunks pending execute/apply, or commit
+    // The number of storage data chunks pending execute/apply, ledger update, or commit
     pending_data_chunks: Arc<AtomicU64>,
 
     // An optional runtime on which to spawn the storage synchronizer threads
@@ -171,7 +171,8 @@ impl<
         MetadataStorage: MetadataStorageInterface + Clone,
     > StorageSynchronizer<ChunkExecutor, MetadataStorage>
 {
-    /// Returns a new storage synchronizer alongside the executor and committer handles
+    /// Returns a new storage synchronizer alongside the executor, ledger updater and committer
+    /// handles
     pub fn new<MempoolNotifier: MempoolNotificationSender>(
         driver_config: StateSyncDriverConfig,
         chunk_executor: Arc<ChunkExecutor>,
@@ -182,13 +183,20 @@ impl<
         metadata_storage: MetadataStorage,
         storage: DbReaderWriter,
         runtime: Option<&Runtime>,
-    ) -> (Self, JoinHandle<()>, JoinHandle<()>) {
+    ) -> (Self, JoinHandle<()>, JoinHandle<()>, JoinHandle<()>) {
         // Create a channel to notify the executor when data chunks are ready
         let max_pending_data_chunks = driver_config.max_pending_data_chunks as usize;
         let (executor_notifier, executor_listener) = mpsc::channel(max_pending_data_chunks);
 
-        // Create a channel to notify the committer when executed chunks are ready
-        let (committer_notifier, committer_listener) = mpsc::channel(max_pending_data_chunks);
+        // Create a channel to notify the ledger updater when executed chunks are ready
+        let max_pending_chunks_to_update_ledger =
+            driver_config.max_pending_chunks_to_update_ledger as usize;
+        let (ledger_updater_notifier, ledger_updater_listener) =
+            mpsc::channel(max_pending_chunks_to_update_ledger);
+
+        // Create a channel to notify the committer when ledger updated chunks are ready
+        let max_pending_chunks_to_commit = driver_config.max_pending_chunks_to_commit as usize;
+        let (committer_notifier, committer_listener) = mpsc::channel(max_pending_chunks_to_commit);
 
         // Create a shared pending data chunk counter
         let pending_transaction_chunks = Arc::new(AtomicU64::new(0));
@@ -199,12 +207,23 @@ impl<
             chunk_executor.clone(),
             error_notification_sender.clone(),
             executor_listener,
+            ledger_updater_notifier,
+            pending_transaction_chunks.clone(),
+            runtime.clone(),
+        );
+
+        // Spawn the ledger updater that updates the ledger with executed chunks, while the
+        // executor executes the next ones.
+        let ledger_updater_handle = spawn_ledger_updater(
+            chunk_executor.clone(),
+            error_notification_sender.clone(),
+            ledger_updater_listener,
             committer_notifier,
             pending_transaction_chunks.clone(),
             runtime.clone(),
         );
 
-        // Spawn the committer that commits executed (but pending) chunks
+        // Spawn the committer that commits ledger updated (but pending) chunks
         let committer_handle = spawn_committer(
             chunk_executor.clone(),
             committer_listener,
@@ -233,7 +252,12 @@ impl<
             storage,
         };
 
-        (storage_synchronizer, executor_handle, committer_handle)
+        (
+            storage_synchronizer,
+            executor_handle,
+            ledger_updater_handle,
+            committer_handle,
+        )
     }
 
     /// Notifies the executor of new data chunks
@@ -382,7 +406,7 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     chunk_executor: Arc<ChunkExecutor>,
     error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
     mut executor_listener: mpsc::Receiver<StorageDataChunk>,
-    mut committer_notifier: mpsc::Sender<NotificationId>,
+    mut ledger_updater_notifier: mpsc::Sender<NotificationId>,
     pending_transaction_chunks: Arc<AtomicU64>,
     runtime: Option<Handle>,
 ) -> JoinHandle<()> {
@@ -488,11 +512,12 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
                 },
             };
 
-            // Notify the committer of new executed chunks
+            // Notify the ledger updater of new executed chunks
             match result {
                 Ok(()) => {
-                    if let Err(error) = committer_notifier.send(notification_id).await {
-                        let error = format!("Failed to notify the committer! Error: {:?}", error);
+                    if let Err(error) = ledger_updater_notifier.send(notification_id).await {
+                        let error =
+                            format!("Failed to notify the ledger updater! Error: {:?}", error);
                         send_storage_synchronizer_error(
                             error_notification_sender.clone(),
                             notification_id,
@@ -523,7 +548,59 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     spawn(runtime, executor)
 }
 
-/// Spawns a dedicated committer that commits executed (but pending) chunks
+/// Spawns a dedicated ledger updater that updates the ledger with executed chunks
+fn spawn_ledger_updater<ChunkExecutor: ChunkExecutorTrait + 'static>(
+    chunk_executor: Arc<ChunkExecutor>,
+    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+    mut ledger_updater_listener: mpsc::Receiver<NotificationId>,
+    mut committer_notifier: mpsc::Sender<NotificationId>,
+    pending_transaction_chunks: Arc<AtomicU64>,
+    runtime: Option<Handle>,
+) -> JoinHandle<()> {
+    // Create a ledger updater
+    let ledger_updater = async move {
+        while let Some(notification_id) = ledger_updater_listener.next().await {
+            // Update the ledger with the executed chunk
+            let timer = metrics::start_timer(
+                &metrics::STORAGE_SYNCHRONIZER_LATENCIES,
+                metrics::STORAGE_SYNCHRONIZER_UPDATE_LEDGER,
+            );
+            let result = update_ledger(chunk_executor.clone()).await;
+            drop(timer);
+
+            // Notify the committer of new ledger updated chunks
+            match result {
+                Ok(()) => {
+                    if let Err(error) = committer_notifier.send(notification_id).await {
+                        let error = format!("Failed to notify the committer! Error: {:?}", error);
+                        send_storage_synchronizer_error(
+                            error_notification_sender.clone(),
+                            notification_id,
+                            error,
+                        )
+                        .await;
+                        decrement_pending_data_chunks(pending_transaction_chunks.clone());
+                    }
+                },
+                Err(error) => {
+                    let error = format!("Failed to update the ledger! Error: {:?}", error);
+                    send_storage_synchronizer_error(
+                        error_notification_sender.clone(),
+                        notification_id,
+                        error,
+                    )
+                    .await;
+                    decrement_pending_data_chunks(pending_transaction_chunks.clone());
+                },
+            }
+        }
+    };
+
+    // Spawn the ledger updater
+    spawn(runtime, ledger_updater)
+}
+
+/// Spawns a dedicated committer that commits ledger updated (but pending) chunks
 fn spawn_committer<
     ChunkExecutor: ChunkExecutorTrait + 'static,
     MempoolNotifier: MempoolNotificationSender,
@@ -760,7 +837,7 @@ async fn apply_output_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
     end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
 ) -> anyhow::Result<()> {
     tokio::task::spawn_blocking(move || {
-        chunk_executor.apply_chunk(
+        chunk_executor.enqueue_chunk_by_transaction_outputs(
             outputs_with_proof,
             &target_ledger_info,
             end_of_epoch_ledger_info.as_ref(),
@@ -780,7 +857,7 @@ async fn execute_transaction_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
     end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
 ) -> anyhow::Result<()> {
     tokio::task::spawn_blocking(move || {
-        chunk_executor.execute_chunk(
+        chunk_executor.enqueue_chunk_by_execution(
             transactions_with_proof,
             &target_ledger_info,
             end_of_epoch_ledger_info.as_ref(),
@@ -790,6 +867,17 @@ async fn execute_transaction_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
     .expect("Spawn_blocking(execute_transaction_chunk) failed!")
 }
 
+/// Spawns a dedicated task that updates the ledger with an executed chunk.
+/// We use `spawn_blocking` so that the heavy synchronous function
+/// doesn't block the async thread.
+async fn update_ledger<ChunkExecutor: ChunkExecutorTrait + 'static>(
+    chunk_executor: Arc<ChunkExecutor>,
+) -> anyhow::Result<()> {
+    tokio::task::spawn_blocking(move || chunk_executor.update_ledger())
+        .await
+        .expect("Spawn_blocking(update_ledger) failed!")
+}
+
 /// Spawns a dedicated task that commits a data chunk. We use
 /// `spawn_blocking` so that the heavy synchronous function doesn't
 /// block the async thread.
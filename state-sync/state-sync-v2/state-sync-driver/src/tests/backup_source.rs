@@ -0,0 +1,94 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::backup_source::{create_range_proof, create_sub_range_proof};
+use aptos_crypto::{
+    hash::{CryptoHash, ACCUMULATOR_PLACEHOLDER_HASH},
+    HashValue,
+};
+use aptos_types::proof::{
+    TransactionAccumulatorInternalNode, TransactionAccumulatorProof,
+    TransactionAccumulatorRangeProof,
+};
+
+#[test]
+fn test_create_range_proof() {
+    // Create an accumulator with three leaves:
+    //          root
+    //         /    \
+    //       ab      c_
+    //      /  \    /  \
+    //     a    b  c    placeholder
+    let leaves: Vec<_> = (0..3).map(|_| HashValue::random()).collect();
+    let placeholder = *ACCUMULATOR_PLACEHOLDER_HASH;
+    let ab = hash_internal(leaves[0], leaves[1]);
+    let c_ = hash_internal(leaves[2], placeholder);
+    let root_hash = hash_internal(ab, c_);
+
+    // Create the accumulator proofs for each leaf (with siblings from the bottom up)
+    let accumulator_proofs = vec![
+        TransactionAccumulatorProof::new(vec![leaves[1], c_]),
+        TransactionAccumulatorProof::new(vec![leaves[0], c_]),
+        TransactionAccumulatorProof::new(vec![placeholder, ab]),
+    ];
+
+    // Verify the range proofs for each leaf
+    for (version, accumulator_proof) in accumulator_proofs.iter().enumerate() {
+        let version = version as u64;
+        accumulator_proof
+            .verify(root_hash, leaves[version as usize], version)
+            .unwrap();
+        create_range_proof(accumulator_proof, version)
+            .verify(root_hash, Some(version), &[leaves[version as usize]])
+            .unwrap();
+    }
+}
+
+fn hash_internal(left: HashValue, right: HashValue) -> HashValue {
+    TransactionAccumulatorInternalNode::new(left, right).hash()
+}
+
+#[test]
+fn test_create_sub_range_proof() {
+    // Create an accumulator with five leaves
+    let leaves: Vec<_> = (0..5).map(|_| HashValue::random()).collect();
+    let placeholder = *ACCUMULATOR_PLACEHOLDER_HASH;
+    let ab = hash_internal(leaves[0], leaves[1]);
+    let cd = hash_internal(leaves[2], leaves[3]);
+    let e_ = hash_internal(leaves[4], placeholder);
+    let abcd = hash_internal(ab, cd);
+    let e___ = hash_internal(e_, placeholder);
+    let root_hash = hash_internal(abcd, e___);
+
+    // Create the range proof for the leaves [1, 4]
+    let range_proof =
+        TransactionAccumulatorRangeProof::new(vec![leaves[0]], vec![placeholder, placeholder]);
+    range_proof
+        .verify(root_hash, Some(1), &leaves[1..5])
+        .unwrap();
+
+    // Verify the sub range proofs for all sub ranges
+    for sub_first_version in 1..5 {
+        for sub_last_version in sub_first_version..5 {
+            let sub_range_proof = create_sub_range_proof(
+                &range_proof,
+                1,
+                &leaves[1..5],
+                sub_first_version,
+                sub_last_version,
+            )
+            .unwrap();
+            sub_range_proof
+                .verify(
+                    root_hash,
+                    Some(sub_first_version),
+                    &leaves[sub_first_version as usize..=sub_last_version as usize],
+                )
+                .unwrap();
+        }
+    }
+
+    // Verify that sub ranges outside the range are rejected
+    assert!(create_sub_range_proof(&range_proof, 1, &leaves[1..5], 0, 2).is_err());
+    assert!(create_sub_range_proof(&range_proof, 1, &leaves[1..5], 2, 5).is_err());
+}
@@ -7,12 +7,14 @@ use crate::{
     error::Error,
     tests::{
         mocks::{
-            create_mock_db_reader, create_mock_streaming_client, create_ready_storage_synchronizer,
+            create_mock_backup_source, create_mock_db_reader, create_mock_storage_synchronizer,
+            create_mock_streaming_client, create_ready_storage_synchronizer, MockBackupSource,
             MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient,
         },
         utils::{
             create_data_stream_listener, create_empty_epoch_state, create_epoch_ending_ledger_info,
-            create_full_node_driver_configuration, create_global_summary,
+            create_epoch_state, create_full_node_driver_configuration, create_global_summary,
+            create_ledger_info_at_epoch, create_output_list_at_version,
             create_output_list_with_proof, create_random_epoch_ending_ledger_info,
             create_transaction_info, create_transaction_list_with_proof,
         },
@@ -32,8 +34,11 @@ use aptos_types::{
 };
 use claims::{assert_matches, assert_none, assert_ok};
 use futures::{channel::oneshot, FutureExt, SinkExt};
-use mockall::{predicate::eq, Sequence};
-use std::{sync::Arc, time::Duration};
+use mockall::{
+    predicate::{always, eq},
+    Sequence,
+};
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
 
 #[tokio::test]
 async fn test_bootstrap_genesis_waypoint() {
@@ -649,6 +654,240 @@ async fn test_fetch_epoch_ending_ledger_infos() {
     assert_matches!(error, Error::DataStreamNotificationTimeout(_));
 }
 
+#[tokio::test]
+async fn test_restore_from_backup() {
+    // Create test data (the node has already synced to version 100 in epoch 1)
+    let synced_version = 100;
+    let epoch_ending_ledger_info = create_ledger_info_at_epoch(101, 1, true);
+    let proof_ledger_info = create_ledger_info_at_epoch(105, 2, false);
+    let unverifiable_ledger_info = create_ledger_info_at_epoch(110, 3, false);
+    let output_list_with_proof = create_output_list_at_version(101);
+
+    // Create a driver configuration that restores from a backup
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the mock backup source
+    let mut mock_backup_source = create_mock_backup_source();
+    let (mut notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let (mut notification_sender_2, data_stream_listener_2) = create_data_stream_listener();
+    mock_backup_source
+        .expect_highest_epoch_ending_epoch()
+        .returning(|| Ok(Some(1)));
+    mock_backup_source
+        .expect_get_all_epoch_ending_ledger_infos()
+        .times(1)
+        .with(eq(1))
+        .return_once(move |_| Ok(data_stream_listener_1));
+    mock_backup_source
+        .expect_highest_transaction_version()
+        .returning(|| Ok(Some(110)));
+    mock_backup_source
+        .expect_get_all_transaction_outputs()
+        .times(1)
+        .with(eq(synced_version + 1), eq(BTreeSet::from([101])))
+        .return_once(move |_, _| Ok(data_stream_listener_2));
+
+    // Create the mock storage synchronizer (that applies the verified outputs)
+    let mut mock_storage_synchronizer = create_ready_storage_synchronizer(true);
+    let output_list_with_proof_clone = output_list_with_proof.clone();
+    let proof_ledger_info_clone = proof_ledger_info.clone();
+    let epoch_ending_ledger_info_clone = epoch_ending_ledger_info.clone();
+    mock_storage_synchronizer
+        .expect_clone()
+        .times(1)
+        .return_once(move || {
+            let mut mock_storage_synchronizer = create_mock_storage_synchronizer();
+            mock_storage_synchronizer
+                .expect_apply_transaction_outputs()
+                .times(1)
+                .with(
+                    always(),
+                    eq(output_list_with_proof_clone),
+                    eq(proof_ledger_info_clone),
+                    eq(Some(epoch_ending_ledger_info_clone)),
+                )
+                .return_once(|_, _, _, _| Ok(()));
+            mock_storage_synchronizer
+        });
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_backup_source(
+        driver_configuration,
+        mock_backup_source,
+        mock_storage_synchronizer,
+        synced_version,
+    );
+
+    // Subscribe to a bootstrapped notification
+    let (bootstrap_notification_sender, bootstrap_notification_receiver) = oneshot::channel();
+    bootstrapper
+        .subscribe_to_bootstrap_notifications(bootstrap_notification_sender)
+        .await
+        .unwrap();
+
+    // Send the epoch ending ledger infos in the backup
+    let data_notification = DataNotification {
+        notification_id: 0,
+        data_payload: DataPayload::EpochEndingLedgerInfos(vec![epoch_ending_ledger_info]),
+    };
+    notification_sender_1.send(data_notification).await.unwrap();
+    let data_notification = DataNotification {
+        notification_id: 1,
+        data_payload: DataPayload::EndOfStream,
+    };
+    notification_sender_1.send(data_notification).await.unwrap();
+
+    // Send the transaction outputs in the backup (the second output is
+    // proven by a ledger info in an epoch that is not yet verified).
+    let data_notification = DataNotification {
+        notification_id: 2,
+        data_payload: DataPayload::ContinuousTransactionOutputsWithProof(
+            proof_ledger_info,
+            output_list_with_proof,
+        ),
+    };
+    notification_sender_2.send(data_notification).await.unwrap();
+    let data_notification = DataNotification {
+        notification_id: 3,
+        data_payload: DataPayload::ContinuousTransactionOutputsWithProof(
+            unverifiable_ledger_info,
+            create_output_list_at_version(102),
+        ),
+    };
+    notification_sender_2.send(data_notification).await.unwrap();
+
+    // Drive progress (without any advertised data) and verify we're now bootstrapped
+    drive_progress(&mut bootstrapper, &GlobalDataSummary::empty(), true)
+        .await
+        .unwrap();
+    assert!(bootstrapper.is_bootstrapped());
+    verify_bootstrap_notification(bootstrap_notification_receiver);
+}
+
+#[tokio::test]
+async fn test_restore_from_backup_corrupt_epoch_ending_ledger_infos() {
+    // Create a driver configuration that restores from a backup
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the mock backup source
+    let mut mock_backup_source = create_mock_backup_source();
+    let mut expectation_sequence = Sequence::new();
+    let (mut notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let (_notification_sender_2, data_stream_listener_2) = create_data_stream_listener();
+    mock_backup_source
+        .expect_highest_epoch_ending_epoch()
+        .returning(|| Ok(Some(1)));
+    mock_backup_source
+        .expect_get_all_epoch_ending_ledger_infos()
+        .times(1)
+        .with(eq(1))
+        .return_once(move |_| Ok(data_stream_listener_1))
+        .in_sequence(&mut expectation_sequence);
+    mock_backup_source
+        .expect_get_all_epoch_ending_ledger_infos()
+        .times(1)
+        .with(eq(1))
+        .return_once(move |_| Ok(data_stream_listener_2))
+        .in_sequence(&mut expectation_sequence);
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_backup_source(
+        driver_configuration,
+        mock_backup_source,
+        create_ready_storage_synchronizer(true),
+        100,
+    );
+
+    // Drive progress to initialize the epoch ending data stream
+    let global_data_summary = GlobalDataSummary::empty();
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+
+    // Send a corrupt epoch ending payload along the stream (the epoch is invalid)
+    let data_notification = DataNotification {
+        notification_id: 0,
+        data_payload: DataPayload::EpochEndingLedgerInfos(vec![create_ledger_info_at_epoch(
+            101, 5, true,
+        )]),
+    };
+    notification_sender_1.send(data_notification).await.unwrap();
+
+    // Drive progress again and ensure we get a verification error
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+    assert_eq!(bootstrapper.get_verified_epoch_states().latest_epoch(), 1);
+
+    // Drive progress to restore the epoch ending ledger infos again
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    assert!(!bootstrapper.is_bootstrapped());
+}
+
+#[tokio::test]
+async fn test_restore_from_backup_missing_transactions() {
+    // Create a driver configuration that restores from a backup
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the mock backup source (where the transaction backups have gaps)
+    let synced_version = 100;
+    let mut mock_backup_source = create_mock_backup_source();
+    mock_backup_source
+        .expect_highest_epoch_ending_epoch()
+        .returning(|| Ok(Some(0)));
+    mock_backup_source
+        .expect_highest_transaction_version()
+        .returning(|| Ok(Some(200)));
+    mock_backup_source
+        .expect_get_all_transaction_outputs()
+        .times(1)
+        .with(eq(synced_version + 1), eq(BTreeSet::new()))
+        .return_once(|_, _| {
+            Err(Error::BackupRestoreFailed(
+                "The transaction backups don't cover the versions from 101!".into(),
+            ))
+        });
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_backup_source(
+        driver_configuration,
+        mock_backup_source,
+        create_ready_storage_synchronizer(true),
+        synced_version,
+    );
+
+    // Subscribe to a bootstrapped notification
+    let (bootstrap_notification_sender, bootstrap_notification_receiver) = oneshot::channel();
+    bootstrapper
+        .subscribe_to_bootstrap_notifications(bootstrap_notification_sender)
+        .await
+        .unwrap();
+
+    // Drive progress and verify the restore fails
+    let global_data_summary = GlobalDataSummary::empty();
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, true)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::BackupRestoreFailed(_));
+
+    // Verify the listener is notified of the failure
+    let notification = bootstrap_notification_receiver.now_or_never().unwrap();
+    assert_matches!(notification.unwrap(), Err(Error::BackupRestoreFailed(_)));
+
+    // Drive progress again and verify the restore is not retried
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::BackupRestoreFailed(_));
+    assert!(!bootstrapper.is_bootstrapped());
+}
+
 #[tokio::test]
 async fn test_snapshot_sync_epoch_change() {
     // Create test data
@@ -1188,6 +1427,7 @@ fn create_bootstrapper(
 
     // Create the bootstrapper
     let bootstrapper = Bootstrapper::new(
+        None,
         driver_configuration,
         metadata_storage,
         output_fallback_handler.clone(),
@@ -1230,6 +1470,7 @@ fn create_bootstrapper_with_storage(
         OutputFallbackHandler::new(driver_configuration.clone(), TimeService::mock());
 
     Bootstrapper::new(
+        None,
         driver_configuration,
         mock_metadata_storage,
         output_fallback_handler,
@@ -1239,6 +1480,50 @@ fn create_bootstrapper_with_storage(
     )
 }
 
+/// Creates a bootstrapper for testing that restores from the given backup
+/// source (the node has already synced to `latest_synced_version`).
+fn create_bootstrapper_with_backup_source(
+    driver_configuration: DriverConfiguration,
+    mock_backup_source: MockBackupSource,
+    mock_storage_synchronizer: MockStorageSynchronizer,
+    latest_synced_version: Version,
+) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
+    // Initialize the logger for tests
+    aptos_logger::Logger::init_for_testing();
+
+    // Create the mock metadata storage
+    let mut metadata_storage = MockMetadataStorage::new();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(|| Ok(None));
+
+    // Create the mock db reader with the node in epoch 1
+    let mut mock_database_reader = create_mock_db_reader();
+    mock_database_reader
+        .expect_get_latest_epoch_state()
+        .returning(|| Ok(create_epoch_state(1)));
+    mock_database_reader
+        .expect_get_latest_ledger_info()
+        .returning(|| Ok(create_epoch_ending_ledger_info()));
+    mock_database_reader
+        .expect_get_latest_transaction_info_option()
+        .returning(move || Ok(Some((latest_synced_version, create_transaction_info()))));
+
+    // Create the output fallback handler
+    let output_fallback_handler =
+        OutputFallbackHandler::new(driver_configuration.clone(), TimeService::mock());
+
+    Bootstrapper::new(
+        Some(Box::new(mock_backup_source)),
+        driver_configuration,
+        metadata_storage,
+        output_fallback_handler,
+        create_mock_streaming_client(),
+        Arc::new(mock_database_reader),
+        mock_storage_synchronizer,
+    )
+}
+
 /// Drives progress for the given bootstrapper. If `until_bootstrapped`
 /// is true this method will continue to drive the bootstrapper until
 /// bootstrapping is complete.
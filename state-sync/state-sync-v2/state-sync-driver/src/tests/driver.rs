@@ -78,6 +78,24 @@ async fn test_consensus_commit_notification() {
     assert_err!(result);
 }
 
+#[tokio::test]
+#[timeout(120_000)]
+async fn test_consensus_observer_notification() {
+    // Create a driver for a full node that runs a consensus observer
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::FullNode;
+    node_config.consensus_observer.observer_enabled = true;
+    let (_full_node_driver, consensus_notifier, _, _, _, _) =
+        create_driver_for_tests(node_config, Waypoint::default(), None).await;
+
+    // Verify that the notification is only rejected because the node isn't bootstrapped
+    let error = consensus_notifier
+        .notify_new_commit(vec![create_transaction()], vec![])
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("BootstrapNotComplete"));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[timeout(120_000)]
 async fn test_mempool_commit_notifications() {
@@ -303,6 +321,7 @@ async fn create_driver_for_tests(
     ));
     let (aptos_data_client, _) = AptosNetDataClient::new(
         node_config.state_sync.aptos_data_client,
+        node_config.state_sync.peer_selection.clone(),
         node_config.base.clone(),
         time_service.clone(),
         network_client,
@@ -74,6 +74,7 @@ fn test_new_initialized_configs() {
     ));
     let (aptos_data_client, _) = AptosNetDataClient::new(
         node_config.state_sync.aptos_data_client,
+        node_config.state_sync.peer_selection.clone(),
         node_config.base.clone(),
         TimeService::mock(),
         network_client,
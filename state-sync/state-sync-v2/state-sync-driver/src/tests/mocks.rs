@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_source::BackupSourceInterface,
     error::Error,
     metadata_storage::MetadataStorageInterface,
     storage_synchronizer::StorageSynchronizerInterface,
@@ -44,12 +45,17 @@ use aptos_types::{
 };
 use async_trait::async_trait;
 use mockall::mock;
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 use tokio::task::JoinHandle;
 
 // TODO(joshlind): if we see these as generally useful, we should
 // modify the definitions in the rest of the code.
 
+/// Creates a mock backup source
+pub fn create_mock_backup_source() -> MockBackupSource {
+    MockBackupSource::new()
+}
+
 /// Creates a mock chunk executor
 pub fn create_mock_executor() -> MockChunkExecutor {
     MockChunkExecutor::new()
@@ -122,24 +128,66 @@ pub fn create_ready_storage_synchronizer(expect_reset_executor: bool) -> MockSto
     mock_storage_synchronizer
 }
 
+// This automatically creates a MockBackupSource.
+mock! {
+    pub BackupSource {}
+    #[async_trait]
+    impl BackupSourceInterface for BackupSource {
+        async fn highest_epoch_ending_epoch(&mut self) -> Result<Option<u64>, crate::error::Error>;
+
+        async fn highest_transaction_version(
+            &mut self,
+        ) -> Result<Option<Version>, crate::error::Error>;
+
+        async fn find_latest_state_snapshot(
+            &mut self,
+            epoch_ending_versions: BTreeSet<Version>,
+        ) -> Result<Option<Version>, crate::error::Error>;
+
+        async fn get_all_epoch_ending_ledger_infos(
+            &mut self,
+            start_epoch: u64,
+        ) -> Result<DataStreamListener, crate::error::Error>;
+
+        async fn get_transaction_output(
+            &mut self,
+            version: Version,
+        ) -> Result<DataStreamListener, crate::error::Error>;
+
+        async fn get_all_transaction_outputs(
+            &mut self,
+            start_version: Version,
+            epoch_ending_versions: BTreeSet<Version>,
+        ) -> Result<DataStreamListener, crate::error::Error>;
+
+        async fn get_all_state_values(
+            &mut self,
+            version: Version,
+            start_index: u64,
+        ) -> Result<DataStreamListener, crate::error::Error>;
+    }
+}
+
 // This automatically creates a MockChunkExecutor.
 mock! {
     pub ChunkExecutor {}
     impl ChunkExecutorTrait for ChunkExecutor {
-        fn execute_chunk<'a>(
+        fn enqueue_chunk_by_execution<'a>(
             &self,
             txn_list_with_proof: TransactionListWithProof,
             verified_target_li: &LedgerInfoWithSignatures,
             epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
         ) -> Result<()>;
 
-        fn apply_chunk<'a>(
+        fn enqueue_chunk_by_transaction_outputs<'a>(
             &self,
             txn_output_list_with_proof: TransactionOutputListWithProof,
             verified_target_li: &LedgerInfoWithSignatures,
             epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
         ) -> anyhow::Result<()>;
 
+        fn update_ledger(&self) -> Result<()>;
+
         fn commit_chunk(&self) -> Result<ChunkCommitNotification>;
 
         fn reset(&self) -> Result<()>;
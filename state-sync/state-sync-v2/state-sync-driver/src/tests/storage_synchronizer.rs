@@ -49,7 +49,7 @@ async fn test_apply_transaction_outputs() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
     chunk_executor
-        .expect_apply_chunk()
+        .expect_enqueue_chunk_by_transaction_outputs()
         .with(always(), always(), always())
         .returning(|_, _, _| Ok(()));
     let expected_commit_return = Ok(ChunkCommitNotification {
@@ -57,12 +57,13 @@ async fn test_apply_transaction_outputs() {
         committed_transactions: vec![transaction_to_commit.clone()],
         reconfiguration_occurred: false,
     });
+    chunk_executor.expect_update_ledger().returning(|| Ok(()));
     chunk_executor
         .expect_commit_chunk()
         .return_once(move || expected_commit_return);
 
     // Create the storage synchronizer
-    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _) =
+    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _, _) =
         create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
 
     // Subscribe to the expected event
@@ -98,12 +99,12 @@ async fn test_apply_transaction_outputs_error() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
     chunk_executor
-        .expect_apply_chunk()
+        .expect_enqueue_chunk_by_transaction_outputs()
         .with(always(), always(), always())
         .returning(|_, _, _| Err(format_err!("Failed to apply chunk!")));
 
     // Create the storage synchronizer
-    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _, _) =
         create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
 
     // Attempt to apply a chunk of outputs
@@ -128,15 +129,49 @@ async fn test_commit_chunk_error() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
     chunk_executor
-        .expect_execute_chunk()
+        .expect_enqueue_chunk_by_execution()
         .with(always(), always(), always())
         .returning(|_, _, _| Ok(()));
+    chunk_executor.expect_update_ledger().returning(|| Ok(()));
     chunk_executor
         .expect_commit_chunk()
         .return_once(|| Err(format_err!("Failed to commit chunk!")));
 
     // Create the storage synchronizer
-    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Attempt to execute a chunk of transactions
+    let notification_id = 100;
+    storage_synchronizer
+        .execute_transactions(
+            notification_id,
+            create_transaction_list_with_proof(),
+            create_epoch_ending_ledger_info(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Verify we get an error notification and that there's no pending data
+    verify_error_notification(&mut error_listener, notification_id).await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_update_ledger_error() {
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_enqueue_chunk_by_execution()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    chunk_executor
+        .expect_update_ledger()
+        .return_once(|| Err(format_err!("Failed to update the ledger!")));
+
+    // Create the storage synchronizer
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _, _) =
         create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
 
     // Attempt to execute a chunk of transactions
@@ -165,7 +200,7 @@ async fn test_execute_transactions() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
     chunk_executor
-        .expect_execute_chunk()
+        .expect_enqueue_chunk_by_execution()
         .with(always(), always(), always())
         .returning(|_, _, _| Ok(()));
     let expected_commit_return = Ok(ChunkCommitNotification {
@@ -173,12 +208,13 @@ async fn test_execute_transactions() {
         committed_transactions: vec![transaction_to_commit.clone()],
         reconfiguration_occurred: false,
     });
+    chunk_executor.expect_update_ledger().returning(|| Ok(()));
     chunk_executor
         .expect_commit_chunk()
         .return_once(move || expected_commit_return);
 
     // Create the storage synchronizer
-    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _) =
+    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _, _) =
         create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
 
     // Subscribe to the expected event
@@ -214,12 +250,12 @@ async fn test_execute_transactions_error() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
     chunk_executor
-        .expect_execute_chunk()
+        .expect_enqueue_chunk_by_execution()
         .with(always(), always(), always())
         .returning(|_, _, _| Err(format_err!("Failed to execute chunk!")));
 
     // Create the storage synchronizer
-    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _, _) =
         create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
 
     // Attempt to execute a chunk of transactions
@@ -247,7 +283,7 @@ async fn test_initialize_state_synchronizer_missing_info() {
     output_list_with_proof.proof.transaction_infos = vec![]; // This is invalid!
 
     // Create the storage synchronizer
-    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+    let (_, _, _, _, mut storage_synchronizer, _, _, _) = create_storage_synchronizer(
         create_mock_executor(),
         create_mock_reader_writer(None, None),
     );
@@ -275,7 +311,7 @@ async fn test_initialize_state_synchronizer_receiver_error() {
         .returning(|_, _| Err(format_err!("Failed to get snapshot receiver!")));
 
     // Create the storage synchronizer
-    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+    let (_, _, _, _, mut storage_synchronizer, _, _, _) = create_storage_synchronizer(
         create_mock_executor(),
         create_mock_reader_writer(None, Some(db_writer)),
     );
@@ -339,7 +375,7 @@ async fn test_save_states_completion() {
         .returning(|_, _, _| Ok(()));
 
     // Create the storage synchronizer
-    let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _) =
+    let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _, _) =
         create_storage_synchronizer(
             chunk_executor,
             create_mock_reader_writer(None, Some(db_writer)),
@@ -404,7 +440,7 @@ async fn test_save_states_dropped_error_listener() {
         .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
 
     // Create the storage synchronizer (drop all listeners)
-    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+    let (_, _, _, _, mut storage_synchronizer, _, _, _) = create_storage_synchronizer(
         create_mock_executor(),
         create_mock_reader_writer(None, Some(db_writer)),
     );
@@ -445,10 +481,11 @@ async fn test_save_states_invalid_chunk() {
         .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
 
     // Create the storage synchronizer
-    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
-        create_mock_executor(),
-        create_mock_reader_writer(None, Some(db_writer)),
-    );
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _, _) =
+        create_storage_synchronizer(
+            create_mock_executor(),
+            create_mock_reader_writer(None, Some(db_writer)),
+        );
 
     // Initialize the state synchronizer
     let _ = storage_synchronizer
@@ -471,7 +508,7 @@ async fn test_save_states_invalid_chunk() {
 #[should_panic]
 fn test_save_states_without_initialize() {
     // Create the storage synchronizer
-    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+    let (_, _, _, _, mut storage_synchronizer, _, _, _) = create_storage_synchronizer(
         create_mock_executor(),
         create_mock_reader_writer(None, None),
     );
@@ -493,6 +530,7 @@ fn create_storage_synchronizer(
     StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
     JoinHandle<()>,
     JoinHandle<()>,
+    JoinHandle<()>,
 ) {
     aptos_logger::Logger::init_for_testing();
 
@@ -517,17 +555,18 @@ fn create_storage_synchronizer(
     let metadata_storage = PersistentMetadataStorage::new(db_path.path());
 
     // Create the storage synchronizer
-    let (storage_synchronizer, executor_handle, committer_handle) = StorageSynchronizer::new(
-        StateSyncDriverConfig::default(),
-        Arc::new(mock_chunk_executor),
-        commit_notification_sender,
-        error_notification_sender,
-        event_subscription_service.clone(),
-        mempool_notification_handler,
-        metadata_storage,
-        mock_reader_writer,
-        None,
-    );
+    let (storage_synchronizer, executor_handle, ledger_updater_handle, committer_handle) =
+        StorageSynchronizer::new(
+            StateSyncDriverConfig::default(),
+            Arc::new(mock_chunk_executor),
+            commit_notification_sender,
+            error_notification_sender,
+            event_subscription_service.clone(),
+            mempool_notification_handler,
+            metadata_storage,
+            mock_reader_writer,
+            None,
+        );
 
     (
         commit_notification_listener,
@@ -536,6 +575,7 @@ fn create_storage_synchronizer(
         mempool_notification_listener,
         storage_synchronizer,
         executor_handle,
+        ledger_updater_handle,
         committer_handle,
     )
 }
@@ -71,6 +71,7 @@ pub fn create_full_node_driver_configuration() -> DriverConfiguration {
         config,
         role,
         waypoint,
+        consensus_observer_fallback_timeout: None,
     }
 }
 
@@ -83,6 +84,27 @@ pub fn create_global_summary(highest_ended_epoch: Epoch) -> GlobalDataSummary {
     global_data_summary
 }
 
+/// Creates a new ledger info with signatures at the specified version and
+/// epoch. If `ends_epoch` is true, the ledger info ends the epoch.
+pub fn create_ledger_info_at_epoch(
+    version: Version,
+    epoch: Epoch,
+    ends_epoch: bool,
+) -> LedgerInfoWithSignatures {
+    let next_epoch_state = ends_epoch.then(|| create_epoch_state(epoch + 1));
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::zero(),
+        HashValue::random(),
+        version,
+        0,
+        next_epoch_state,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::random());
+    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
+}
+
 /// Creates a new ledger info with signatures at the specified version
 pub fn create_ledger_info_at_version(version: Version) -> LedgerInfoWithSignatures {
     let block_info = BlockInfo::new(0, 0, HashValue::zero(), HashValue::zero(), version, 0, None);
@@ -101,6 +123,13 @@ pub fn create_output_list_with_proof() -> TransactionOutputListWithProof {
     )
 }
 
+/// Creates a test transaction output list with proof at the specified version
+pub fn create_output_list_at_version(version: Version) -> TransactionOutputListWithProof {
+    let mut output_list_with_proof = create_output_list_with_proof();
+    output_list_with_proof.first_transaction_output_version = Some(version);
+    output_list_with_proof
+}
+
 /// Creates a random epoch ending ledger info with the specified values
 pub fn create_random_epoch_ending_ledger_info(
     version: Version,
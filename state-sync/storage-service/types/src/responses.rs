@@ -111,6 +111,19 @@ impl StorageServiceResponse {
     pub fn is_compressed(&self) -> bool {
         matches!(self, Self::CompressedResponse(_, _))
     }
+
+    /// Returns the number of data bytes in the response (i.e., the
+    /// compressed size for compressed responses)
+    pub fn get_num_bytes(&self) -> u64 {
+        match self {
+            StorageServiceResponse::CompressedResponse(_, compressed_data) => {
+                compressed_data.len() as u64
+            },
+            StorageServiceResponse::RawResponse(data_response) => {
+                bcs::serialized_size(data_response).unwrap_or_default() as u64
+            },
+        }
+    }
 }
 
 /// A useful type to hold optional transaction data
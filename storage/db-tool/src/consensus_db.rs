@@ -0,0 +1,205 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::debugger::latest_ledger_info;
+use anyhow::Result;
+use aptos_consensus::consensus_db_inspection::{
+    dump, prune_blocks_below_root, validate, BlockSummary,
+};
+use aptos_crypto::HashValue;
+use clap::Parser;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+#[derive(Parser)]
+pub struct ConsensusDbDir {
+    /// Storage directory of the node, containing the consensus db and the ledger db
+    #[clap(long, parse(from_os_str))]
+    db_dir: PathBuf,
+}
+
+#[derive(clap::Subcommand)]
+#[clap(about = "Inspect and repair the blocks, quorum certs and votes persisted by consensus.")]
+pub enum Cmd {
+    Dump(Dump),
+    Validate(Validate),
+    LastVote(LastVote),
+    Prune(Prune),
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Self::Dump(cmd) => cmd.run(),
+            Self::Validate(cmd) => cmd.run(),
+            Self::LastVote(cmd) => cmd.run(),
+            Self::Prune(cmd) => cmd.run(),
+        }
+    }
+}
+
+fn print_block(block: &BlockSummary, depth: usize) {
+    println!(
+        "{}{} epoch {} round {} author {} timestamp {} payload {}",
+        "  ".repeat(depth),
+        block.id,
+        block.epoch,
+        block.round,
+        block
+            .author
+            .map_or_else(|| "none".to_string(), |author| author.to_hex_literal()),
+        block.timestamp_usecs,
+        block.payload_size,
+    );
+}
+
+/// Prints the blocks as trees, each block below its parent. Blocks whose parent isn't persisted,
+/// e.g. the children of the root, start a new tree.
+fn print_block_tree(blocks: &[BlockSummary]) {
+    let ids: HashSet<_> = blocks.iter().map(|block| block.id).collect();
+    let mut children: HashMap<HashValue, Vec<&BlockSummary>> = HashMap::new();
+    for block in blocks {
+        children.entry(block.parent_id).or_default().push(block);
+    }
+    let mut stack: Vec<_> = blocks
+        .iter()
+        .rev()
+        .filter(|block| !ids.contains(&block.parent_id))
+        .map(|block| (block, 0))
+        .collect();
+    while let Some((block, depth)) = stack.pop() {
+        print_block(block, depth);
+        if let Some(children) = children.get(&block.id) {
+            stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+    }
+}
+
+#[derive(Parser)]
+#[clap(about = "Print the persisted block tree and quorum certs.")]
+pub struct Dump {
+    #[clap(flatten)]
+    db_dir: ConsensusDbDir,
+
+    #[clap(long, help = "Print the content of the db as JSON.")]
+    json: bool,
+}
+
+impl Dump {
+    pub fn run(self) -> Result<()> {
+        let dump = dump(&self.db_dir.db_dir)?;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&dump)?);
+            return Ok(());
+        }
+        println!("Blocks ({}):", dump.blocks.len());
+        print_block_tree(&dump.blocks);
+        println!("Quorum certs ({}):", dump.quorum_certs.len());
+        for qc in &dump.quorum_certs {
+            println!(
+                "{} epoch {} round {} commits {} round {}",
+                qc.certified_block_id, qc.epoch, qc.round, qc.commit_block_id, qc.commit_round
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Check that the persisted blocks and quorum certs chain correctly, and that the root \
+    can be found from the latest committed ledger info."
+)]
+pub struct Validate {
+    #[clap(flatten)]
+    db_dir: ConsensusDbDir,
+
+    #[clap(long, help = "Print the report as JSON.")]
+    json: bool,
+}
+
+impl Validate {
+    pub fn run(self) -> Result<()> {
+        let report = validate(
+            &self.db_dir.db_dir,
+            latest_ledger_info(&self.db_dir.db_dir)?,
+        )?;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+        match &report.root {
+            Some(root) => {
+                print!("Root: ");
+                print_block(root, 0);
+            },
+            None => println!("Root: not found"),
+        }
+        println!("Issues ({}):", report.issues.len());
+        for issue in &report.issues {
+            println!("{}", issue);
+        }
+        println!("Blocks to prune ({}):", report.blocks_to_prune.len());
+        for id in &report.blocks_to_prune {
+            println!("{}", id);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[clap(about = "Print the last vote and the highest timeout certificate.")]
+pub struct LastVote {
+    #[clap(flatten)]
+    db_dir: ConsensusDbDir,
+}
+
+impl LastVote {
+    pub fn run(self) -> Result<()> {
+        let dump = dump(&self.db_dir.db_dir)?;
+        match dump.last_vote {
+            Some(vote) => println!("Last vote: {}", vote),
+            None => println!("Last vote: none"),
+        }
+        match dump.highest_2chain_timeout_certificate {
+            Some(tc) => println!("Highest timeout certificate: {}", tc),
+            None => println!("Highest timeout certificate: none"),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Delete the blocks and quorum certs that don't descend from the committed root, as \
+    the node does on recovery. The node must be stopped, as the consensus db can't be opened for \
+    writing while it is running."
+)]
+pub struct Prune {
+    #[clap(flatten)]
+    db_dir: ConsensusDbDir,
+
+    #[clap(long, help = "Only list the blocks that would be deleted.")]
+    dry_run: bool,
+}
+
+impl Prune {
+    pub fn run(self) -> Result<()> {
+        let pruned = prune_blocks_below_root(
+            &self.db_dir.db_dir,
+            latest_ledger_info(&self.db_dir.db_dir)?,
+            self.dry_run,
+        )?;
+        for id in &pruned {
+            println!("{}", id);
+        }
+        if self.dry_run {
+            println!("{} blocks would be deleted.", pruned.len());
+        } else {
+            println!("Deleted {} blocks.", pruned.len());
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,23 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{consensus_db, quorum_store};
 use anyhow::Result;
-use aptos_db::db_debugger::{checkpoint, ledger, state_tree, truncate};
+use aptos_config::config::{
+    RocksdbConfigs, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::{
+    db_debugger::{checkpoint, ledger, state_tree, truncate},
+    AptosDB,
+};
+use aptos_storage_interface::DbReader;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
 use clap::Parser;
+use std::path::Path;
 
-/// List snapshots, print nodes, make DB checkpoints and validate ledger hash
+/// List snapshots, print nodes, make DB checkpoints, validate ledger hash and inspect the
+/// consensus and quorum store dbs
 #[derive(Parser)]
 pub enum Command {
     #[clap(subcommand)]
@@ -14,6 +26,10 @@ pub enum Command {
     #[clap(subcommand)]
     Ledger(ledger::Cmd),
     Truncate(truncate::Cmd),
+    #[clap(subcommand)]
+    ConsensusDb(consensus_db::Cmd),
+    #[clap(subcommand)]
+    QuorumStore(quorum_store::Cmd),
 }
 
 impl Command {
@@ -23,6 +39,23 @@ impl Command {
             Command::Checkpoint(cmd) => cmd.run(),
             Command::Ledger(cmd) => cmd.run(),
             Command::Truncate(cmd) => cmd.run(),
+            Command::ConsensusDb(cmd) => cmd.run(),
+            Command::QuorumStore(cmd) => cmd.run(),
         }
     }
 }
+
+/// Reads the latest committed ledger info from the ledger db in the storage directory of a node,
+/// which is what consensus recovers from
+pub(crate) fn latest_ledger_info(db_dir: &Path) -> Result<LedgerInfoWithSignatures> {
+    let db = AptosDB::open(
+        db_dir,
+        true, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        false, /* indexer */
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )?;
+    db.get_latest_ledger_info()
+}
@@ -5,7 +5,9 @@ extern crate core;
 
 mod backup;
 mod backup_maintenance;
+mod consensus_db;
 mod debugger;
+mod quorum_store;
 mod replay_verify;
 mod restore;
 #[cfg(test)]
@@ -0,0 +1,149 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::debugger::latest_ledger_info;
+use anyhow::{ensure, format_err, Result};
+use aptos_consensus::quorum_store_inspection::{
+    export_batch, garbage_collect_expired_batches, list_batches, BatchSummary,
+};
+use aptos_crypto::HashValue;
+use clap::Parser;
+use std::{fs, path::PathBuf};
+
+#[derive(Parser)]
+pub struct QuorumStoreDbDir {
+    /// Storage directory of the node, containing the quorum store db
+    #[clap(long, parse(from_os_str))]
+    db_dir: PathBuf,
+}
+
+#[derive(clap::Subcommand)]
+#[clap(about = "Inspect and maintain the batches persisted by the quorum store.")]
+pub enum Cmd {
+    List(List),
+    Export(Export),
+    Gc(Gc),
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Self::List(cmd) => cmd.run(),
+            Self::Export(cmd) => cmd.run(),
+            Self::Gc(cmd) => cmd.run(),
+        }
+    }
+}
+
+fn print_summaries(summaries: &[BatchSummary], json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(summaries)?);
+        return Ok(());
+    }
+    println!(
+        "{: <64} | {: <66} | {: >6} | {: >16} | {: >6} | {: >8} | proof",
+        "digest", "author", "epoch", "expiration", "txns", "bytes"
+    );
+    for summary in summaries {
+        println!(
+            "{: <64} | {: <66} | {: >6} | {: >16} | {: >6} | {: >8} | {:?}",
+            summary.digest.to_hex(),
+            summary.author.to_hex_literal(),
+            summary.epoch,
+            summary.expiration,
+            summary.num_txns,
+            summary.num_bytes,
+            summary.proof_status,
+        );
+    }
+    println!("{} batches", summaries.len());
+    Ok(())
+}
+
+#[derive(Parser)]
+#[clap(about = "List the persisted batches, ordered by expiration.")]
+pub struct List {
+    #[clap(flatten)]
+    db_dir: QuorumStoreDbDir,
+
+    #[clap(long, help = "Print the batches as JSON.")]
+    json: bool,
+}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        print_summaries(&list_batches(&self.db_dir.db_dir)?, self.json)
+    }
+}
+
+#[derive(Parser)]
+#[clap(about = "Export a persisted batch and its transactions as JSON.")]
+pub struct Export {
+    #[clap(flatten)]
+    db_dir: QuorumStoreDbDir,
+
+    #[clap(long)]
+    digest: HashValue,
+
+    #[clap(long, parse(from_os_str), help = "Print to stdout if not set.")]
+    output_file: Option<PathBuf>,
+}
+
+impl Export {
+    pub fn run(self) -> Result<()> {
+        let batch = export_batch(&self.db_dir.db_dir, self.digest)?
+            .ok_or_else(|| format_err!("Batch {} not found.", self.digest))?;
+        let json = serde_json::to_string_pretty(&batch)?;
+        match self.output_file {
+            Some(output_file) => {
+                ensure!(!output_file.exists(), "Output file already exists.");
+                fs::write(output_file, json)?;
+            },
+            None => println!("{}", json),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[clap(
+    about = "Delete the expired batches. The node must be stopped, as the quorum store db can't \
+    be opened for writing while it is running."
+)]
+pub struct Gc {
+    #[clap(flatten)]
+    db_dir: QuorumStoreDbDir,
+
+    #[clap(
+        long,
+        help = "Delete the batches that expired at or before this timestamp, in microseconds. \
+        [Defaults to the timestamp of the latest committed block]"
+    )]
+    expiration_cutoff_usecs: Option<u64>,
+
+    #[clap(long, help = "Only list the batches that would be deleted.")]
+    dry_run: bool,
+
+    #[clap(long, help = "Print the batches as JSON.")]
+    json: bool,
+}
+
+impl Gc {
+    pub fn run(self) -> Result<()> {
+        let expiration_cutoff = match self.expiration_cutoff_usecs {
+            Some(expiration_cutoff) => expiration_cutoff,
+            // Batches expire relative to block timestamps, and the node uses the same cutoff
+            // when it cleans up the quorum store db on restart
+            None => latest_ledger_info(&self.db_dir.db_dir)?
+                .ledger_info()
+                .timestamp_usecs(),
+        };
+        let expired =
+            garbage_collect_expired_batches(&self.db_dir.db_dir, expiration_cutoff, self.dry_run)?;
+        print_summaries(&expired, self.json)?;
+        if !self.dry_run {
+            println!("Deleted {} expired batches.", expired.len());
+        }
+        Ok(())
+    }
+}
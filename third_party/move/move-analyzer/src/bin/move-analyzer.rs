@@ -8,12 +8,15 @@ use crossbeam::channel::{bounded, select};
 use lsp_server::{Connection, Message, Notification, Request, Response};
 use lsp_types::{
     notification::Notification as _, request::Request as _, CompletionOptions, Diagnostic,
-    HoverProviderCapability, OneOf, SaveOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
-    TextDocumentSyncOptions, TypeDefinitionProviderCapability, WorkDoneProgressOptions,
+    HoverProviderCapability, OneOf, RenameOptions, SaveOptions, SignatureHelpOptions,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TypeDefinitionProviderCapability, WorkDoneProgressOptions,
 };
 use move_analyzer::{
     completion::on_completion_request,
     context::Context,
+    inlay_hints::{on_inlay_hint_request, INLAY_HINT_REQUEST_METHOD},
+    signature_help::on_signature_help_request,
     symbols,
     vfs::{on_text_document_sync_notification, VirtualFileSystem},
 };
@@ -51,7 +54,7 @@ fn main() {
     let symbols = Arc::new(Mutex::new(symbols::Symbolicator::empty_symbols()));
     let mut context = Context {
         connection,
-        files: VirtualFileSystem::default(),
+        files: Arc::new(Mutex::new(VirtualFileSystem::default())),
         symbols: symbols.clone(),
     };
 
@@ -60,19 +63,15 @@ fn main() {
         .initialize_start()
         .expect("could not start connection initialization");
 
-    let capabilities = serde_json::to_value(lsp_types::ServerCapabilities {
+    let mut capabilities = serde_json::to_value(lsp_types::ServerCapabilities {
         // The server receives notifications from the client as users open, close,
         // and modify documents.
         text_document_sync: Some(TextDocumentSyncCapability::Options(
             TextDocumentSyncOptions {
                 open_close: Some(true),
-                // TODO: We request that the language server client send us the entire text of any
-                // files that are modified. We ought to use the "incremental" sync kind, which would
-                // have clients only send us what has changed and where, thereby requiring far less
-                // data be sent "over the wire." However, to do so, our language server would need
-                // to be capable of applying deltas to its view of the client's open files. See the
-                // 'move_analyzer::vfs' module for details.
-                change: Some(TextDocumentSyncKind::Full),
+                // Clients only send us what has changed in modified files, and where, which the
+                // 'move_analyzer::vfs' module applies to its view of the client's open files.
+                change: Some(TextDocumentSyncKind::Incremental),
                 will_save: None,
                 will_save_wait_until: None,
                 save: Some(
@@ -106,9 +105,31 @@ fn main() {
         )),
         references_provider: Some(OneOf::Left(symbols::DEFS_AND_REFS_SUPPORT)),
         document_symbol_provider: Some(OneOf::Left(true)),
+        // Renaming relies on references, and the client can check beforehand whether the
+        // identifier under the cursor can be renamed.
+        rename_provider: symbols::DEFS_AND_REFS_SUPPORT.then_some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
+        // The signature of a function is shown when starting to write its arguments, and when
+        // moving on to the next argument.
+        signature_help_provider: Some(SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+            retrigger_characters: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         ..Default::default()
     })
     .expect("could not serialize server capabilities");
+    // Inlay hints are not supported by lsp-types yet.
+    capabilities.as_object_mut().unwrap().insert(
+        "inlayHintProvider".to_string(),
+        serde_json::Value::Bool(true),
+    );
 
     let (diag_sender, diag_receiver) = bounded::<Result<BTreeMap<Symbol, Vec<Diagnostic>>>>(0);
     let mut symbolicator_runner = symbols::SymbolicatorRunner::idle();
@@ -117,7 +138,8 @@ fn main() {
             serde_json::from_value(client_response)
                 .expect("could not deserialize client capabilities");
 
-        symbolicator_runner = symbols::SymbolicatorRunner::new(symbols.clone(), diag_sender);
+        symbolicator_runner =
+            symbols::SymbolicatorRunner::new(context.files.clone(), symbols.clone(), diag_sender);
 
         // If initialization information from the client contains a path to the directory being
         // opened, try to initialize symbols before sending response to the client. Do not bother
@@ -131,9 +153,10 @@ fn main() {
                 thread::Builder::new()
                     .stack_size(symbols::STACK_SIZE_BYTES)
                     .spawn(move || {
-                        if let Ok((Some(new_symbols), _)) =
-                            symbols::Symbolicator::get_symbols(p.as_path())
-                        {
+                        if let Ok((Some(new_symbols), _)) = symbols::Symbolicator::get_symbols(
+                            &VirtualFileSystem::default(),
+                            p.as_path(),
+                        ) {
                             let mut old_symbols = symbols.lock().unwrap();
                             (*old_symbols).merge(new_symbols);
                         }
@@ -234,6 +257,18 @@ fn on_request(context: &Context, request: &Request) {
         lsp_types::request::References::METHOD => {
             symbols::on_references_request(context, request, &context.symbols.lock().unwrap());
         },
+        lsp_types::request::PrepareRenameRequest::METHOD => {
+            symbols::on_prepare_rename_request(context, request, &context.symbols.lock().unwrap());
+        },
+        lsp_types::request::Rename::METHOD => {
+            symbols::on_rename_request(context, request, &context.symbols.lock().unwrap());
+        },
+        lsp_types::request::SignatureHelpRequest::METHOD => {
+            on_signature_help_request(context, request, &context.symbols.lock().unwrap());
+        },
+        INLAY_HINT_REQUEST_METHOD => {
+            on_inlay_hint_request(context, request, &context.symbols.lock().unwrap());
+        },
         lsp_types::request::HoverRequest::METHOD => {
             symbols::on_hover_request(context, request, &context.symbols.lock().unwrap());
         },
@@ -259,7 +294,7 @@ fn on_notification(
         | lsp_types::notification::DidSaveTextDocument::METHOD
         | lsp_types::notification::DidCloseTextDocument::METHOD => {
             on_text_document_sync_notification(
-                &mut context.files,
+                &mut context.files.lock().unwrap(),
                 symbolicator_runner,
                 notification,
             )
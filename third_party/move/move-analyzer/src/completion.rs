@@ -156,7 +156,8 @@ pub fn on_completion_request(context: &Context, request: &Request, symbols: &Sym
         .uri
         .to_file_path()
         .unwrap();
-    let buffer = context.files.get(&path);
+    let files = context.files.lock().unwrap();
+    let buffer = files.get(&path);
     if buffer.is_none() {
         eprintln!(
             "Could not read '{:?}' when handling completion request",
@@ -10,8 +10,9 @@ use std::sync::{Arc, Mutex};
 pub struct Context {
     /// The connection with the language server's client.
     pub connection: Connection,
-    /// The files that the language server is providing information about.
-    pub files: VirtualFileSystem,
+    /// The files that the language server is providing information about, shared with the
+    /// symbolicator which compiles them as they are being edited.
+    pub files: Arc<Mutex<VirtualFileSystem>>,
     /// Symbolication information
     pub symbols: Arc<Mutex<Symbols>>,
 }
@@ -0,0 +1,80 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inlay hints show the inferred types of local variables whose types are not given explicitly
+//! (e.g., `let x = 42` is displayed as `let x: u64 = 42`).
+//!
+//! Inlay hints were introduced in version 3.17 of the Language Server Protocol, which is not
+//! supported by the `lsp-types` crate version in use, so the request and the hints are defined
+//! here.
+
+use crate::{
+    context::Context,
+    symbols::{type_to_ide_string, Symbols},
+};
+use lsp_server::Request;
+use lsp_types::{Position, Range, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+
+/// Method of inlay hint requests
+pub const INLAY_HINT_REQUEST_METHOD: &str = "textDocument/inlayHint";
+
+/// Kind of inlay hints for types
+const INLAY_HINT_KIND_TYPE: u32 = 1;
+
+/// Parameters of inlay hint requests
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InlayHintParams {
+    /// The document for which hints are requested
+    text_document: TextDocumentIdentifier,
+    /// The (visible) part of the document for which hints are requested
+    range: Range,
+}
+
+/// A hint displayed in a document
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InlayHint {
+    /// Position where the hint is displayed
+    position: Position,
+    /// Text of the hint
+    label: String,
+    /// Kind of the hint
+    kind: u32,
+}
+
+/// Handles inlay hint request of the language server
+pub fn on_inlay_hint_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<InlayHintParams>(request.params.clone())
+        .expect("could not deserialize inlay hint request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    let range = parameters.range;
+
+    // positions of hints are only meaningful for the version of a file which was symbolicated
+    let up_to_date = match context.files.lock().unwrap().get(&fpath) {
+        Some(buffer) => symbols.is_symbolicated(buffer),
+        None => true,
+    };
+    let hints = match symbols.file_type_hints().get(&fpath) {
+        Some(type_hints) if up_to_date && range.start <= range.end => type_hints
+            .range(range.start..=range.end)
+            .map(|(position, t)| InlayHint {
+                position: *position,
+                label: format!(": {}", type_to_ide_string(t)),
+                kind: INLAY_HINT_KIND_TYPE,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    let response = lsp_server::Response::new_ok(request.id.clone(), hints);
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send inlay hint response: {:?}", err);
+    }
+}
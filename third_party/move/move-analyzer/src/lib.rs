@@ -8,6 +8,8 @@ extern crate move_ir_types;
 pub mod completion;
 pub mod context;
 pub mod diagnostics;
+pub mod inlay_hints;
+pub mod signature_help;
 pub mod symbols;
 pub mod utils;
 pub mod vfs;
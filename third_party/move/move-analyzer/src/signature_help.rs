@@ -0,0 +1,288 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signature help shows the signature of the function being called, and which of its parameters is
+//! being written, while the arguments of a call are typed.
+//!
+//! The call surrounding the cursor is found by lexing the source buffer up to the cursor, as the
+//! code being typed is usually not complete enough to be parsed. The function is then resolved
+//! using symbolication information if it is available at the call site, or by name otherwise.
+
+use crate::{
+    context::Context,
+    symbols::{type_to_ide_string, IdentType, Symbols},
+    vfs::byte_offset,
+};
+use lsp_server::Request;
+use lsp_types::{
+    Documentation, ParameterInformation, ParameterLabel, Position, SignatureHelp,
+    SignatureHelpParams, SignatureInformation,
+};
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    parser::lexer::{Lexer, Tok},
+    shared::Identifier,
+};
+use move_symbol_pool::Symbol;
+use std::path::PathBuf;
+
+/// A token of a source buffer, and the byte offset where it starts
+struct Token<'a> {
+    tok: Tok,
+    content: &'a str,
+    start: usize,
+}
+
+/// The call whose arguments are being typed
+#[derive(Debug, PartialEq, Eq)]
+struct Call<'a> {
+    /// Name of the module in which the function is called (if any)
+    module: Option<&'a str>,
+    /// Name of the called function
+    name: &'a str,
+    /// Byte offset of the function name
+    name_start: usize,
+    /// Index of the argument being typed
+    arg_idx: u32,
+}
+
+/// Handles signature help request of the language server
+pub fn on_signature_help_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<SignatureHelpParams>(request.params.clone())
+        .expect("could not deserialize signature help request");
+
+    let fpath = parameters
+        .text_document_position_params
+        .text_document
+        .uri
+        .to_file_path()
+        .unwrap();
+    let position = parameters.text_document_position_params.position;
+
+    let signature_help = match context.files.lock().unwrap().get(&fpath) {
+        Some(buffer) => signature_help(buffer, &fpath, &position, symbols),
+        None => {
+            eprintln!(
+                "Could not read '{:?}' when handling signature help request",
+                fpath
+            );
+            None
+        },
+    };
+
+    let response = lsp_server::Response::new_ok(request.id.clone(), signature_help);
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send signature help response: {:?}", err);
+    }
+}
+
+/// Computes the signature help for the call surrounding the given position of a buffer (if any)
+fn signature_help(
+    buffer: &str,
+    fpath: &PathBuf,
+    position: &Position,
+    symbols: &Symbols,
+) -> Option<SignatureHelp> {
+    let call = enclosing_call(&buffer[..byte_offset(buffer, position)])?;
+    let (ident_type, doc_string) = resolve_call(buffer, fpath, &call, symbols)?;
+    let (arg_names, arg_types) = match &ident_type {
+        IdentType::FunctionType(_, _, _, arg_names, arg_types, _, _) => (arg_names, arg_types),
+        IdentType::RegularType(_) => return None,
+    };
+
+    // parameters are identified by their offsets (in UTF-16 code units) in the label of the
+    // signature, in which they are separated by ", "
+    let label = format!("{}", ident_type);
+    let mut offset = label[..label.find('(')? + 1].encode_utf16().count() as u32;
+    let mut parameters = vec![];
+    for (name, t) in arg_names.iter().zip(arg_types) {
+        let len = format!("{}: {}", name, type_to_ide_string(t))
+            .encode_utf16()
+            .count() as u32;
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([offset, offset + len]),
+            documentation: None,
+        });
+        offset += len + 2;
+    }
+
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation: if doc_string.is_empty() {
+                None
+            } else {
+                Some(Documentation::String(doc_string))
+            },
+            parameters: Some(parameters),
+            active_parameter: None,
+        }],
+        active_signature: Some(0),
+        active_parameter: Some(call.arg_idx),
+    })
+}
+
+/// Finds the call whose arguments are being typed at the end of the given source code, if any
+fn enclosing_call(source: &str) -> Option<Call> {
+    let mut lexer = Lexer::new(source, FileHash::new(source));
+    let mut tokens = vec![];
+    // the source code is likely incomplete, so stop at the first token that cannot be lexed
+    while lexer.advance().is_ok() && lexer.peek() != Tok::EOF {
+        tokens.push(Token {
+            tok: lexer.peek(),
+            content: lexer.content(),
+            start: lexer.start_loc(),
+        });
+    }
+
+    // look for the unmatched opening parenthesis of the call, counting the commas separating its
+    // arguments on the way
+    let mut depth = 0;
+    let mut arg_idx = 0;
+    let mut idx = tokens.len();
+    loop {
+        idx = idx.checked_sub(1)?;
+        match tokens[idx].tok {
+            Tok::RParen | Tok::RBracket | Tok::RBrace => depth += 1,
+            Tok::LParen | Tok::LBracket | Tok::LBrace if depth > 0 => depth -= 1,
+            // the cursor is in a vector or a block rather than directly in a call
+            Tok::LBracket | Tok::LBrace => return None,
+            Tok::LParen => break,
+            Tok::Semicolon if depth == 0 => return None,
+            Tok::Comma if depth == 0 => arg_idx += 1,
+            // skip type arguments of calls among the arguments (e.g., `f(a, g<u8, u64>(b), c`)
+            Tok::Greater | Tok::GreaterGreater
+                if depth == 0 && tokens.get(idx + 1).map(|t| t.tok) == Some(Tok::LParen) =>
+            {
+                if let Some(start) = type_args_start(&tokens, idx) {
+                    idx = start;
+                }
+            },
+            _ => (),
+        }
+    }
+
+    // the opening parenthesis follows the name of the function and, optionally, type arguments
+    let mut name_idx = idx.checked_sub(1)?;
+    if matches!(tokens[name_idx].tok, Tok::Greater | Tok::GreaterGreater) {
+        name_idx = type_args_start(&tokens, name_idx)?.checked_sub(1)?;
+    }
+    let name = &tokens[name_idx];
+    if name.tok != Tok::Identifier {
+        return None;
+    }
+    let module = match name_idx.checked_sub(1).map(|i| &tokens[i]) {
+        // a function declaration rather than a call
+        Some(Token { tok: Tok::Fun, .. }) => return None,
+        Some(Token {
+            tok: Tok::ColonColon,
+            ..
+        }) => {
+            let module = tokens.get(name_idx.checked_sub(2)?)?;
+            if module.tok != Tok::Identifier {
+                return None;
+            }
+            Some(module.content)
+        },
+        _ => None,
+    };
+    Some(Call {
+        module,
+        name: name.content,
+        name_start: name.start,
+        arg_idx,
+    })
+}
+
+/// Returns the index of the `<` token starting the list of type arguments which ends with the `>`
+/// (or `>>`) token at the given index
+fn type_args_start(tokens: &[Token], end_idx: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut idx = end_idx;
+    loop {
+        match tokens[idx].tok {
+            Tok::Greater => depth += 1,
+            Tok::GreaterGreater => depth += 2,
+            Tok::Less => depth -= 1,
+            // only expressions (or code not supported here) can appear in type arguments
+            Tok::LParen | Tok::RParen | Tok::LBrace | Tok::RBrace | Tok::Semicolon => return None,
+            _ => (),
+        }
+        if depth <= 0 {
+            return Some(idx);
+        }
+        idx = idx.checked_sub(1)?;
+    }
+}
+
+/// Resolves the called function to its type and doc string
+fn resolve_call(
+    buffer: &str,
+    fpath: &PathBuf,
+    call: &Call,
+    symbols: &Symbols,
+) -> Option<(IdentType, String)> {
+    // use symbolication information at the call site if it is for the called function (the buffer
+    // may have been edited since it was symbolicated)
+    let prefix = &buffer[..call.name_start];
+    let line = prefix.matches('\n').count() as u32;
+    let col = prefix[prefix.rfind('\n').map_or(0, |i| i + 1)..]
+        .chars()
+        .count() as u32;
+    if let Some(u) = symbols.use_def(fpath, line, col) {
+        if let IdentType::FunctionType(_, name, ..) = u.use_type() {
+            if name.as_str() == call.name {
+                return Some((u.use_type().clone(), u.doc_string().to_string()));
+            }
+        }
+    }
+
+    // otherwise, look for the function by name in the modules of the file, or in the (first) module
+    // with the given name
+    let mods = symbols.file_mods().values().flatten();
+    let fun_def = match call.module {
+        None | Some("Self") => symbols
+            .file_mods()
+            .get(fpath)?
+            .iter()
+            .find_map(|m| m.functions().get(&Symbol::from(call.name))),
+        Some(module) => mods
+            .filter(|m| m.name().module.value().as_str() == module)
+            .find_map(|m| m.functions().get(&Symbol::from(call.name))),
+    }?;
+    Some((fun_def.ident_type().clone(), String::new()))
+}
+
+#[test]
+fn enclosing_call_test() {
+    let call = |module, name, name_start, arg_idx| {
+        Some(Call {
+            module,
+            name,
+            name_start,
+            arg_idx,
+        })
+    };
+
+    assert_eq!(enclosing_call("foo("), call(None, "foo", 0, 0));
+    assert_eq!(enclosing_call("x = foo(a, "), call(None, "foo", 4, 1));
+    assert_eq!(enclosing_call("M::foo(a, b"), call(Some("M"), "foo", 3, 1));
+    assert_eq!(
+        enclosing_call("0x1::M::foo<u8, vector<u64>>(a, "),
+        call(Some("M"), "foo", 8, 1)
+    );
+    // nested calls, vectors, blocks and type arguments in arguments are skipped
+    assert_eq!(
+        enclosing_call("foo(bar(a, b), vector[1, 2], { c; d }, baz<u8, u64>(e), "),
+        call(None, "foo", 0, 4)
+    );
+    assert_eq!(enclosing_call("foo(bar(a, "), call(None, "bar", 4, 1));
+    assert_eq!(enclosing_call("foo(a); b"), None);
+    assert_eq!(enclosing_call("foo(vector[a, "), None);
+    assert_eq!(enclosing_call("fun foo(a: u8, "), None);
+    assert_eq!(enclosing_call("if (a"), None);
+}
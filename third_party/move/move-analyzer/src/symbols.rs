@@ -50,23 +50,28 @@ use crate::{
     context::Context,
     diagnostics::{lsp_diagnostics, lsp_empty_diagnostics},
     utils::get_loc,
+    vfs::VirtualFileSystem,
 };
 use anyhow::{anyhow, Result};
 use codespan_reporting::files::SimpleFiles;
 use crossbeam::channel::Sender;
 use derivative::*;
 use im::ordmap::OrdMap;
-use lsp_server::{Request, RequestId};
+use lsp_server::{ErrorCode, Request, RequestId, Response};
 use lsp_types::{
     request::GotoTypeDefinitionParams, Diagnostic, DocumentSymbol, DocumentSymbolParams,
     GotoDefinitionParams, Hover, HoverContents, HoverParams, LanguageString, Location,
-    MarkedString, Position, Range, ReferenceParams, SymbolKind,
+    MarkedString, Position, PrepareRenameResponse, Range, ReferenceParams, RenameParams,
+    SymbolKind, TextDocumentPositionParams, TextEdit, WorkspaceEdit,
 };
 use move_command_line_common::files::FileHash;
 use move_compiler::{
-    expansion::ast::{Address, Fields, ModuleIdent, ModuleIdent_},
+    expansion::ast::{Address, Attributes, Fields, ModuleIdent, ModuleIdent_},
     naming::ast::{StructDefinition, StructFields, TParam, Type, TypeName_, Type_},
-    parser::ast::StructName,
+    parser::{
+        ast::{StructName, Var},
+        keywords::KEYWORDS,
+    },
     shared::Identifier,
     typing::ast::{
         BuiltinFunction_, Exp, ExpListItem, Function, FunctionBody_, LValue, LValueList, LValue_,
@@ -84,6 +89,7 @@ use std::{
     path::{Path, PathBuf},
     sync::{Arc, Condvar, Mutex},
     thread,
+    time::Duration,
 };
 use tempfile::tempdir;
 use url::Url;
@@ -94,6 +100,9 @@ pub const DEFS_AND_REFS_SUPPORT: bool = true;
 // Building Move code requires a larger stack size on Windows (16M has been chosen somewhat
 // arbitrarily)
 pub const STACK_SIZE_BYTES: usize = 16 * 1024 * 1024;
+/// How long symbolication waits for edits to stop before (re)compiling a package, so that a burst
+/// of keystrokes results in a single compilation
+pub const DEBOUNCE_DELAY: Duration = Duration::from_millis(250);
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Copy)]
 /// Location of a definition's identifier
@@ -163,6 +172,7 @@ struct FieldDef {
 struct StructDef {
     name_start: Position,
     field_defs: Vec<FieldDef>,
+    attrs: Vec<String>,
 }
 
 #[derive(Derivative, Debug, Clone, PartialEq, Eq)]
@@ -207,6 +217,8 @@ pub struct Symbolicator {
     type_params: BTreeMap<Symbol, DefLoc>,
     /// Current processed module (always set before module processing starts)
     current_mod: Option<ModuleIdent>,
+    /// Inferred types of local variables in the current processed module
+    type_hints: TypeHints,
 }
 
 /// Maps a line number to a list of use-def pairs on a given line (use-def set is sorted by
@@ -214,6 +226,10 @@ pub struct Symbolicator {
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct UseDefMap(BTreeMap<u32, BTreeSet<UseDef>>);
 
+/// Maps positions right after the names of local variables, whose types are not given explicitly,
+/// to the (inferred) types of these variables
+pub type TypeHints = BTreeMap<Position, Type>;
+
 /// Maps a function name to its usage definition
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct FunctionIdentTypeMap(BTreeMap<String, IdentType>);
@@ -228,6 +244,8 @@ pub struct Symbols {
     file_name_mapping: BTreeMap<FileHash, Symbol>,
     /// A mapping from filePath to ModuleDefs
     file_mods: BTreeMap<PathBuf, BTreeSet<ModuleDefs>>,
+    /// Inferred types of local variables in a file
+    file_type_hints: BTreeMap<PathBuf, TypeHints>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -243,11 +261,21 @@ pub struct SymbolicatorRunner {
 }
 
 impl ModuleDefs {
+    pub fn name(&self) -> &ModuleIdent_ {
+        &self.name
+    }
+
     pub fn functions(&self) -> &BTreeMap<Symbol, FunctionDef> {
         &self.functions
     }
 }
 
+impl FunctionDef {
+    pub fn ident_type(&self) -> &IdentType {
+        &self.ident_type
+    }
+}
+
 impl fmt::Display for IdentType {
     fn fmt(&self, f: &mut fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -307,7 +335,7 @@ fn arg_list_to_ide_string(names: &[Symbol], types: &[Type]) -> String {
         .join(", ")
 }
 
-fn type_to_ide_string(sp!(_, t): &Type) -> String {
+pub(crate) fn type_to_ide_string(sp!(_, t): &Type) -> String {
     match t {
         Type_::Unit => "()".to_string(),
         Type_::Ref(m, r) => format!("&{} {}", if *m { "mut" } else { "" }, type_to_ide_string(r)),
@@ -369,8 +397,10 @@ impl SymbolicatorRunner {
         SymbolicatorRunner { mtx_cvar }
     }
 
-    /// Create a new runner
+    /// Create a new runner, compiling the files being edited in the IDE (if any) instead of their
+    /// versions on disk
     pub fn new(
+        ide_files: Arc<Mutex<VirtualFileSystem>>,
         symbols: Arc<Mutex<Symbols>>,
         sender: Sender<Result<BTreeMap<Symbol, Vec<Diagnostic>>>>,
     ) -> Self {
@@ -412,6 +442,10 @@ impl SymbolicatorRunner {
                         }
                     };
                     if let Some(starting_path) = starting_path_opt {
+                        let starting_path = match Self::debounce(mtx, cvar, starting_path) {
+                            Some(p) => p,
+                            None => break,
+                        };
                         let root_dir = Self::root_dir(&starting_path);
                         if root_dir.is_none() && !missing_manifests.contains(&starting_path) {
                             eprintln!("reporting missing manifest");
@@ -430,7 +464,12 @@ impl SymbolicatorRunner {
                             continue;
                         }
                         eprintln!("symbolication started");
-                        match Symbolicator::get_symbols(root_dir.unwrap().as_path()) {
+                        // work on a snapshot of the files to let the IDE keep editing them
+                        let ide_files_snapshot = ide_files.lock().unwrap().clone();
+                        match Symbolicator::get_symbols(
+                            &ide_files_snapshot,
+                            root_dir.unwrap().as_path(),
+                        ) {
                             Ok((symbols_opt, lsp_diagnostics)) => {
                                 eprintln!("symbolication finished");
                                 if let Some(new_symbols) = symbols_opt {
@@ -440,8 +479,7 @@ impl SymbolicatorRunner {
                                     //
                                     // TODO: we may consider "unloading" symbolication information when
                                     // files/directories are being closed but as with other performance
-                                    // optimizations (e.g. incrementalizatino of the vfs), let's wait
-                                    // until we know we actually need it
+                                    // optimizations, let's wait until we know we actually need it
                                     let mut old_symbols = symbols.lock().unwrap();
                                     (*old_symbols).merge(new_symbols);
                                 }
@@ -465,6 +503,32 @@ impl SymbolicatorRunner {
         runner
     }
 
+    /// Waits until no new symbolication request has been made for `DEBOUNCE_DELAY` and returns
+    /// the starting path of the latest request, or `None` if the runner should quit instead
+    fn debounce(
+        mtx: &Mutex<RunnerState>,
+        cvar: &Condvar,
+        mut starting_path: PathBuf,
+    ) -> Option<PathBuf> {
+        let mut symbolicate = mtx.lock().unwrap();
+        loop {
+            let (guard, wait_result) = cvar.wait_timeout(symbolicate, DEBOUNCE_DELAY).unwrap();
+            symbolicate = guard;
+            match symbolicate.clone() {
+                RunnerState::Quit => return None,
+                RunnerState::Run(path) => {
+                    *symbolicate = RunnerState::Wait;
+                    starting_path = path;
+                },
+                RunnerState::Wait => {
+                    if wait_result.timed_out() {
+                        return Some(starting_path);
+                    }
+                },
+            }
+        }
+    }
+
     pub fn run(&self, starting_path: PathBuf) {
         eprintln!("scheduling run for {:?}", starting_path);
         let (mtx, cvar) = &*self.mtx_cvar;
@@ -534,6 +598,16 @@ impl UseDef {
     }
 }
 
+impl UseDef {
+    pub fn use_type(&self) -> &IdentType {
+        &self.use_type
+    }
+
+    pub fn doc_string(&self) -> &str {
+        &self.doc_string
+    }
+}
+
 impl Ord for UseDef {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.col_start.cmp(&other.col_start)
@@ -590,6 +664,17 @@ impl FunctionIdentTypeMap {
 
 impl Symbols {
     pub fn merge(&mut self, other: Self) {
+        // references from files that have been re-symbolicated may no longer exist
+        let updated_files: BTreeSet<_> = other.file_name_mapping.values().collect();
+        let file_name_mapping = &self.file_name_mapping;
+        self.references.retain(|_, uses| {
+            uses.retain(|u| {
+                file_name_mapping
+                    .get(&u.fhash)
+                    .map_or(true, |fname| !updated_files.contains(fname))
+            });
+            !uses.is_empty()
+        });
         for (k, v) in other.references {
             self.references
                 .entry(k)
@@ -599,11 +684,54 @@ impl Symbols {
         self.file_use_defs.extend(other.file_use_defs);
         self.file_name_mapping.extend(other.file_name_mapping);
         self.file_mods.extend(other.file_mods);
+        self.file_type_hints.extend(other.file_type_hints);
     }
 
     pub fn file_mods(&self) -> &BTreeMap<PathBuf, BTreeSet<ModuleDefs>> {
         &self.file_mods
     }
+
+    pub fn file_type_hints(&self) -> &BTreeMap<PathBuf, TypeHints> {
+        &self.file_type_hints
+    }
+
+    /// Checks whether symbolication information is available for the given contents of a file
+    /// (rather than for another version of it)
+    pub fn is_symbolicated(&self, contents: &str) -> bool {
+        self.file_name_mapping
+            .contains_key(&FileHash::new(contents))
+    }
+
+    /// Returns the use-def pair for the identifier at the given position of a file, if any
+    pub fn use_def(&self, fpath: &PathBuf, line: u32, col: u32) -> Option<UseDef> {
+        self.file_use_defs
+            .get(fpath)?
+            .get(line)?
+            .into_iter()
+            .filter(|u| col >= u.col_start && col <= u.col_end)
+            .last()
+    }
+
+    /// Returns the use-def pair for the identifier at the given position of a file if it can be
+    /// renamed, that is if it is defined in the same package as the file
+    fn renamable_use_def(
+        &self,
+        fpath: &PathBuf,
+        line: u32,
+        col: u32,
+    ) -> std::result::Result<UseDef, String> {
+        let u = self
+            .use_def(fpath, line, col)
+            .ok_or_else(|| "No identifier to rename at this position".to_string())?;
+        let pkg_dir = SymbolicatorRunner::root_dir(fpath)
+            .ok_or_else(|| "Only identifiers in a package can be renamed".to_string())?;
+        let canonicalize = |p: &Path| dunce::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+        let pkg_dir = canonicalize(&pkg_dir);
+        match self.file_name_mapping.get(&u.def_loc.fhash) {
+            Some(p) if canonicalize(Path::new(p.as_str())).starts_with(&pkg_dir) => Ok(u),
+            _ => Err("Only identifiers defined in the current package can be renamed".to_string()),
+        }
+    }
 }
 
 impl Symbolicator {
@@ -612,6 +740,7 @@ impl Symbolicator {
     /// actually (re)computed and the diagnostics are returned, the old symbolic information should
     /// be retained even if it's getting out-of-date.
     pub fn get_symbols(
+        ide_files: &VirtualFileSystem,
         pkg_path: &Path,
     ) -> Result<(Option<Symbols>, BTreeMap<Symbol, Vec<Diagnostic>>)> {
         let build_config = move_package::BuildConfig {
@@ -627,26 +756,26 @@ impl Symbolicator {
         let resolution_graph =
             build_config.resolution_graph_for_package(pkg_path, &mut Vec::new())?;
 
-        // get source files to be able to correlate positions (in terms of byte offsets) with actual
-        // file locations (in terms of line/column numbers)
-        let source_files = &resolution_graph.file_sources();
-        let mut files = SimpleFiles::new();
-        let mut file_id_mapping = HashMap::new();
-        let mut file_id_to_lines = HashMap::new();
-        let mut file_name_mapping = BTreeMap::new();
-        for (fhash, (fname, source)) in source_files {
-            let id = files.add(*fname, source.clone());
-            file_id_mapping.insert(*fhash, id);
-            file_name_mapping.insert(*fhash, *fname);
-            let lines: Vec<String> = source.lines().map(String::from).collect();
-            file_id_to_lines.insert(id, lines);
+        // compile the contents of source files being edited in the IDE rather than their (possibly
+        // stale) contents on disk
+        let mut source_overlay = BTreeMap::new();
+        for (fname, _) in resolution_graph.file_sources().values() {
+            let fpath = dunce::canonicalize(fname.as_str())
+                .unwrap_or_else(|_| PathBuf::from(fname.as_str()));
+            if let Some(buffer) = ide_files.get(&fpath) {
+                source_overlay.insert(*fname, buffer.to_string());
+            }
         }
 
         let build_plan = BuildPlan::create(resolution_graph)?;
+        let mut source_files = HashMap::new();
         let mut typed_ast = None;
         let mut diagnostics = None;
         build_plan.compile_with_driver(&mut std::io::sink(), None, |compiler| {
-            let (files, compilation_result) = compiler.run::<PASS_TYPING>()?;
+            let (files, compilation_result) = compiler
+                .set_source_overlay(std::mem::take(&mut source_overlay))
+                .run::<PASS_TYPING>()?;
+            source_files = files.clone();
             let (_, compiler) = match compilation_result {
                 Ok(v) => v,
                 Err(diags) => {
@@ -680,6 +809,20 @@ impl Symbolicator {
             Ok((files, units))
         })?;
 
+        // get source files, as they were compiled, to be able to correlate positions (in terms of
+        // byte offsets) with actual file locations (in terms of line/column numbers)
+        let mut files = SimpleFiles::new();
+        let mut file_id_mapping = HashMap::new();
+        let mut file_id_to_lines = HashMap::new();
+        let mut file_name_mapping = BTreeMap::new();
+        for (fhash, (fname, source)) in &source_files {
+            let id = files.add(*fname, source.clone());
+            file_id_mapping.insert(*fhash, id);
+            file_name_mapping.insert(*fhash, *fname);
+            let lines: Vec<String> = source.lines().map(String::from).collect();
+            file_id_to_lines.insert(id, lines);
+        }
+
         let mut ide_diagnostics = lsp_empty_diagnostics(&file_name_mapping);
         if let Some((compiler_diagnostics, failure)) = diagnostics {
             let lsp_diagnostics = lsp_diagnostics(
@@ -738,10 +881,12 @@ impl Symbolicator {
             file_id_to_lines,
             type_params: BTreeMap::new(),
             current_mod: None,
+            type_hints: BTreeMap::new(),
         };
 
         let mut references = BTreeMap::new();
         let mut file_use_defs = BTreeMap::new();
+        let mut file_type_hints = BTreeMap::new();
         let mut function_ident_type = FunctionIdentTypeMap::new();
 
         for (pos, module_ident, module_def) in modules {
@@ -763,9 +908,13 @@ impl Symbolicator {
                 .unwrap_or_else(|_| PathBuf::from(fpath.as_str()));
 
             file_use_defs
-                .entry(fpath_buffer)
+                .entry(fpath_buffer.clone())
                 .or_insert_with(UseDefMap::new)
                 .extend(use_defs.elements());
+            file_type_hints
+                .entry(fpath_buffer)
+                .or_insert_with(BTreeMap::new)
+                .extend(std::mem::take(&mut symbolicator.type_hints));
         }
 
         let symbols = Symbols {
@@ -773,6 +922,7 @@ impl Symbolicator {
             file_use_defs,
             file_name_mapping,
             file_mods,
+            file_type_hints,
         };
 
         eprintln!("get_symbols load complete");
@@ -787,6 +937,7 @@ impl Symbolicator {
             references: BTreeMap::new(),
             file_name_mapping: BTreeMap::new(),
             file_mods: BTreeMap::new(),
+            file_type_hints: BTreeMap::new(),
         }
     }
 
@@ -835,6 +986,7 @@ impl Symbolicator {
             structs.insert(*name, StructDef {
                 name_start,
                 field_defs,
+                attrs: Self::get_attrs(&def.attributes, files, file_id_mapping),
             });
         }
 
@@ -884,12 +1036,7 @@ impl Symbolicator {
             functions.insert(*name, FunctionDef {
                 name: *name,
                 start: name_start,
-                attrs: fun
-                    .attributes
-                    .clone()
-                    .iter()
-                    .map(|(_loc, name, _attr)| name.to_string())
-                    .collect(),
+                attrs: Self::get_attrs(&fun.attributes, files, file_id_mapping),
                 ident_type,
             });
         }
@@ -1127,6 +1274,31 @@ impl Symbolicator {
         get_loc(&pos.file_hash(), pos.start(), files, file_id_mapping)
     }
 
+    /// Returns the attributes of a definition as they appear in the source code (e.g.,
+    /// `resource_group_member(group = 0x1::object::ObjectGroup)` for Aptos resource group members)
+    fn get_attrs(
+        attributes: &Attributes,
+        files: &SimpleFiles<Symbol, String>,
+        file_id_mapping: &HashMap<FileHash, usize>,
+    ) -> Vec<String> {
+        attributes
+            .iter()
+            .map(|(_loc, name, attr)| {
+                file_id_mapping
+                    .get(&attr.loc.file_hash())
+                    .and_then(|id| files.get(*id).ok())
+                    .and_then(|f| {
+                        f.source()
+                            .get(attr.loc.start() as usize..attr.loc.end() as usize)
+                    })
+                    .map_or_else(
+                        || name.to_string(),
+                        |s| s.split_whitespace().collect::<Vec<_>>().join(" "),
+                    )
+            })
+            .collect()
+    }
+
     /// Extracts the docstring (/// or /** ... */) for a given definition by traversing up from the line definition
     fn extract_doc_string(&self, name_start: &Position, file_hash: &FileHash) -> String {
         let mut doc_string = String::new();
@@ -1145,6 +1317,17 @@ impl Symbolicator {
         }
 
         let mut iter = (name_start.line - 1) as usize;
+        // skip attributes (e.g. `#[view]`), possibly spanning multiple lines, between the docstring
+        // and the definition
+        while file_lines[iter].trim().ends_with(']') {
+            match (0..=iter)
+                .rev()
+                .find(|i| file_lines[*i].trim_start().starts_with("#["))
+            {
+                Some(attr_start) if attr_start > 0 => iter = attr_start - 1,
+                _ => return doc_string,
+            }
+        }
         let mut line_before = file_lines[iter].trim();
 
         // Detect the two different types of docstrings
@@ -1197,7 +1380,7 @@ impl Symbolicator {
 
     /// Get symbols for a sequence representing function body
     fn seq_item_symbols(
-        &self,
+        &mut self,
         scope: &mut OrdMap<Symbol, DefLoc>,
         seq_item: &SequenceItem,
         references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
@@ -1207,7 +1390,8 @@ impl Symbolicator {
         match &seq_item.value {
             I::Seq(e) => self.exp_symbols(e, scope, references, use_defs),
             I::Declare(lvalues) => {
-                self.lvalue_list_symbols(true, lvalues, scope, references, use_defs)
+                self.lvalue_list_symbols(true, lvalues, scope, references, use_defs);
+                self.add_type_hints(lvalues);
             },
             I::Bind(lvalues, opt_types, e) => {
                 // process RHS first to avoid accidentally binding its identifiers to LHS (which now
@@ -1220,6 +1404,7 @@ impl Symbolicator {
                     }
                 }
                 self.lvalue_list_symbols(true, lvalues, scope, references, use_defs);
+                self.add_type_hints(lvalues);
             },
         }
     }
@@ -1327,7 +1512,7 @@ impl Symbolicator {
 
     /// Get symbols for an expression
     fn exp_symbols(
-        &self,
+        &mut self,
         exp: &Exp,
         scope: &mut OrdMap<Symbol, DefLoc>,
         references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
@@ -1518,7 +1703,7 @@ impl Symbolicator {
     }
 
     fn mod_call_symbols(
-        &self,
+        &mut self,
         mod_call: &ModuleCall,
         scope: &mut OrdMap<Symbol, DefLoc>,
         references: &mut BTreeMap<DefLoc, BTreeSet<UseLoc>>,
@@ -1553,7 +1738,7 @@ impl Symbolicator {
 
     /// Get symbols for the pack expression
     fn pack_symbols(
-        &self,
+        &mut self,
         ident: &ModuleIdent,
         name: &StructName,
         tparams: &Vec<Type>,
@@ -1594,6 +1779,50 @@ impl Symbolicator {
 
     /// Helper functions
 
+    /// Records the types of local variables defined by a `let`, unless they are given explicitly
+    fn add_type_hints(&mut self, lvalues: &LValueList) {
+        // the type annotation, if any, follows the list of variables (e.g., `let (a, b): (u8, u8)`)
+        let annotated = self
+            .file_id_mapping
+            .get(&lvalues.loc.file_hash())
+            .and_then(|id| self.files.get(*id).ok())
+            .and_then(|f| f.source().get(lvalues.loc.end() as usize..))
+            .map_or(true, |rest| rest.trim_start().starts_with(':'));
+        if annotated {
+            return;
+        }
+        let mut vars = vec![];
+        for lval in &lvalues.value {
+            Self::lvalue_vars(lval, &mut vars);
+        }
+        for (var, t) in vars {
+            if let Type_::Anything | Type_::Var(_) | Type_::UnresolvedError = t.value {
+                continue;
+            }
+            if let Some(start) = Self::get_start_loc(&var.loc(), &self.files, &self.file_id_mapping)
+            {
+                let end = Position {
+                    line: start.line,
+                    character: start.character + var.value().len() as u32,
+                };
+                self.type_hints.insert(end, t.clone());
+            }
+        }
+    }
+
+    /// Collects the variables (and their types) defined by an lvalue
+    fn lvalue_vars<'a>(lval: &'a LValue, vars: &mut Vec<(&'a Var, &'a Type)>) {
+        match &lval.value {
+            LValue_::Var(var, t) => vars.push((var, t)),
+            LValue_::Unpack(_, _, _, fields) | LValue_::BorrowUnpack(_, _, _, _, fields) => {
+                for (_, _, (_, (_, lvalue))) in fields {
+                    Self::lvalue_vars(lvalue, vars);
+                }
+            },
+            LValue_::Ignore => (),
+        }
+    }
+
     /// Add type parameter to a scope holding type params
     fn add_type_param(
         &mut self,
@@ -2163,6 +2392,105 @@ pub fn on_references_request(context: &Context, request: &Request, symbols: &Sym
     );
 }
 
+/// Handles prepare rename request of the language server
+pub fn on_prepare_rename_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<TextDocumentPositionParams>(request.params.clone())
+        .expect("could not deserialize prepare rename request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    let loc = parameters.position;
+
+    let response = match symbols.renamable_use_def(&fpath, loc.line, loc.character) {
+        Ok(u) => {
+            let range = Range {
+                start: Position {
+                    line: loc.line,
+                    character: u.col_start,
+                },
+                end: Position {
+                    line: loc.line,
+                    character: u.col_end,
+                },
+            };
+            Response::new_ok(request.id.clone(), PrepareRenameResponse::Range(range))
+        },
+        Err(msg) => Response::new_err(request.id.clone(), ErrorCode::InvalidRequest as i32, msg),
+    };
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send prepare rename response: {:?}", err);
+    }
+}
+
+/// Handles rename request of the language server by renaming the definition of an identifier and
+/// all its uses
+pub fn on_rename_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<RenameParams>(request.params.clone())
+        .expect("could not deserialize rename request");
+
+    let fpath = parameters
+        .text_document_position
+        .text_document
+        .uri
+        .to_file_path()
+        .unwrap();
+    let loc = parameters.text_document_position.position;
+    let new_name = parameters.new_name;
+
+    let response = if !is_valid_identifier(&new_name) {
+        Response::new_err(
+            request.id.clone(),
+            ErrorCode::InvalidParams as i32,
+            format!("'{}' is not a valid identifier", new_name),
+        )
+    } else {
+        match symbols.renamable_use_def(&fpath, loc.line, loc.character) {
+            Ok(u) => {
+                let mut changes = HashMap::new();
+                for ref_loc in symbols.references.get(&u.def_loc).into_iter().flatten() {
+                    let path = symbols.file_name_mapping.get(&ref_loc.fhash).unwrap();
+                    let range = Range {
+                        start: ref_loc.start,
+                        end: Position {
+                            line: ref_loc.start.line,
+                            character: ref_loc.col_end,
+                        },
+                    };
+                    changes
+                        .entry(Url::from_file_path(path.as_str()).unwrap())
+                        .or_insert_with(Vec::new)
+                        .push(TextEdit::new(range, new_name.clone()));
+                }
+                Response::new_ok(request.id.clone(), WorkspaceEdit::new(changes))
+            },
+            Err(msg) => {
+                Response::new_err(request.id.clone(), ErrorCode::InvalidRequest as i32, msg)
+            },
+        }
+    };
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send rename response: {:?}", err);
+    }
+}
+
+/// Checks whether a name is a Move identifier that is not a keyword
+fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || (c == '_' && name.len() > 1) => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && !KEYWORDS.contains(&name)
+        },
+        _ => false,
+    }
+}
+
 /// Handles hover request of the language server
 pub fn on_hover_request(context: &Context, request: &Request, symbols: &Symbols) {
     let parameters = serde_json::from_value::<HoverParams>(request.params.clone())
@@ -2211,22 +2539,10 @@ pub fn on_use_request(
     id: RequestId,
     use_def_action: impl Fn(&UseDef) -> Option<serde_json::Value>,
 ) {
-    let mut result = None;
-
-    let mut use_def_found = false;
-    if let Some(mod_symbols) = symbols.file_use_defs.get(use_fpath) {
-        if let Some(uses) = mod_symbols.get(use_line) {
-            for u in uses {
-                if use_col >= u.col_start && use_col <= u.col_end {
-                    result = use_def_action(&u);
-                    use_def_found = true;
-                }
-            }
-        }
-    }
-    if !use_def_found {
-        result = Some(serde_json::to_value(Option::<lsp_types::Location>::None).unwrap());
-    }
+    let result = match symbols.use_def(use_fpath, use_line, use_col) {
+        Some(u) => use_def_action(&u),
+        None => Some(serde_json::to_value(Option::<lsp_types::Location>::None).unwrap()),
+    };
 
     eprintln!("about to send use response");
     // unwrap will succeed based on the logic above which the compiler is unable to figure out
@@ -2293,12 +2609,13 @@ pub fn on_document_symbol_request(context: &Context, request: &Request, symbols:
                 end: struct_def.name_start,
             };
 
+            let detail = attrs_to_ide_string(&struct_def.attrs);
             let mut fields: Vec<DocumentSymbol> = vec![];
             handle_struct_fields(struct_def, &mut fields);
 
             children.push(DocumentSymbol {
                 name: sym.clone().to_string(),
-                detail: None,
+                detail,
                 kind: SymbolKind::Struct,
                 range: struct_range,
                 selection_range: struct_range,
@@ -2316,10 +2633,7 @@ pub fn on_document_symbol_request(context: &Context, request: &Request, symbols:
                 end: func_def.start,
             };
 
-            let mut detail = None;
-            if !func_def.attrs.is_empty() {
-                detail = Some(format!("{:?}", func_def.attrs));
-            }
+            let detail = attrs_to_ide_string(&func_def.attrs);
 
             children.push(DocumentSymbol {
                 name: sym.clone().to_string(),
@@ -2356,6 +2670,16 @@ pub fn on_document_symbol_request(context: &Context, request: &Request, symbols:
     }
 }
 
+/// Helper function to display attributes of a definition (if any) as they would be written in the
+/// source code
+fn attrs_to_ide_string(attrs: &[String]) -> Option<String> {
+    if attrs.is_empty() {
+        None
+    } else {
+        Some(format!("#[{}]", attrs.join(", ")))
+    }
+}
+
 /// Helper function to handle struct fields
 #[allow(deprecated)]
 fn handle_struct_fields(struct_def: StructDef, fields: &mut Vec<DocumentSymbol>) {
@@ -2459,7 +2783,8 @@ fn docstring_test() {
 
     path.push("tests/symbols");
 
-    let (symbols_opt, _) = Symbolicator::get_symbols(path.as_path()).unwrap();
+    let (symbols_opt, _) =
+        Symbolicator::get_symbols(&VirtualFileSystem::default(), path.as_path()).unwrap();
     let symbols = symbols_opt.unwrap();
 
     let mut fpath = path.clone();
@@ -2683,7 +3008,8 @@ fn symbols_test() {
 
     path.push("tests/symbols");
 
-    let (symbols_opt, _) = Symbolicator::get_symbols(path.as_path()).unwrap();
+    let (symbols_opt, _) =
+        Symbolicator::get_symbols(&VirtualFileSystem::default(), path.as_path()).unwrap();
     let symbols = symbols_opt.unwrap();
 
     let mut fpath = path.clone();
@@ -3669,3 +3995,136 @@ fn symbols_test() {
         None,
     );
 }
+
+#[test]
+/// Tests symbolication of definitions with Aptos-specific attributes
+fn attributes_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) =
+        Symbolicator::get_symbols(&VirtualFileSystem::default(), path.as_path()).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M8.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+
+    let mod_symbols = symbols.file_use_defs.get(&cpath).unwrap();
+
+    // doc strings are found before or after attributes
+    assert_use_def_with_doc_string(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        4,
+        11,
+        4,
+        11,
+        "M8.move",
+        "Symbols::M8::Group",
+        Some((4, 11, "M8.move")),
+        "A resource group\n",
+    );
+    assert_use_def_with_doc_string(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        10,
+        11,
+        10,
+        11,
+        "M8.move",
+        "Symbols::M8::Member",
+        Some((10, 11, "M8.move")),
+        "A member of the resource group\n",
+    );
+    assert_use_def_with_doc_string(
+        mod_symbols,
+        &symbols.file_name_mapping,
+        0,
+        22,
+        15,
+        22,
+        15,
+        "M8.move",
+        "fun Symbols::M8::value(addr: address): u64 acquires Symbols::M8::Member",
+        None,
+        "A view function\n",
+    );
+
+    let mod_defs = symbols.file_mods[&cpath].iter().next().unwrap();
+    let struct_attrs = |name: &str| &mod_defs.structs[&Symbol::from(name)].attrs;
+    let group_attr = "resource_group(scope = global)";
+    assert_eq!(struct_attrs("Group"), &vec![group_attr]);
+    let member_attr = "resource_group_member(group = Symbols::M8::Group)";
+    assert_eq!(struct_attrs("Member"), &vec![member_attr]);
+    assert_eq!(struct_attrs("Changed"), &vec!["event"]);
+    let fun_attrs = &mod_defs.functions[&Symbol::from("value")].attrs;
+    assert_eq!(fun_attrs, &vec!["view"]);
+
+    // types are only inferred for variables without explicit types
+    let type_hints = symbols
+        .file_type_hints
+        .get(&cpath)
+        .unwrap()
+        .iter()
+        .map(|(pos, t)| (pos.line, pos.character, type_to_ide_string(t)))
+        .collect::<Vec<_>>();
+    let u64_str = "u64".to_string();
+    assert_eq!(
+        type_hints,
+        vec![(27, 13, u64_str.clone()), (29, 30, u64_str)]
+    );
+}
+
+#[test]
+/// Tests symbolication of files being edited in the IDE
+fn ide_files_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) =
+        Symbolicator::get_symbols(&VirtualFileSystem::default(), path.as_path()).unwrap();
+    let mut symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M8.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+
+    // the `value` function can be renamed from its use in the `inferred` function
+    let value_use = symbols.renamable_use_def(&cpath, 27, 18).unwrap();
+    assert_eq!(value_use.def_loc.start, Position::new(22, 15));
+    assert_eq!(symbols.references.get(&value_use.def_loc).unwrap().len(), 2);
+    assert!(symbols.renamable_use_def(&cpath, 27, 4).is_err());
+
+    // remove the use of the `value` function and the explicit types of the tuple
+    let mut ide_files = VirtualFileSystem::default();
+    let contents = std::fs::read_to_string(&cpath)
+        .unwrap()
+        .replace("let v = value(addr);", "let v = 42;")
+        .replace("let (a, b): (u64, u64) =", "let (a, b) =");
+    ide_files.update(cpath.clone(), &contents);
+
+    let (symbols_opt, _) = Symbolicator::get_symbols(&ide_files, path.as_path()).unwrap();
+    symbols.merge(symbols_opt.unwrap());
+    assert!(symbols.is_symbolicated(&contents));
+
+    // the use of the `value` function is gone
+    let value_def = symbols.renamable_use_def(&cpath, 22, 15).unwrap();
+    assert_ne!(value_def.def_loc, value_use.def_loc);
+    assert!(!symbols.references.contains_key(&value_use.def_loc));
+    assert_eq!(symbols.references.get(&value_def.def_loc).unwrap().len(), 1);
+    let type_hints = symbols.file_type_hints.get(&cpath).unwrap();
+    assert_eq!(
+        type_hints.keys().cloned().collect::<Vec<_>>(),
+        vec![
+            Position::new(27, 13),
+            Position::new(28, 14),
+            Position::new(28, 17),
+            Position::new(29, 30)
+        ]
+    );
+}
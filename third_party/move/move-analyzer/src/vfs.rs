@@ -14,12 +14,12 @@ use crate::symbols;
 use lsp_server::Notification;
 use lsp_types::{
     notification::Notification as _, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
-    DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, Position, TextDocumentContentChangeEvent,
 };
 use std::path::PathBuf;
 
 /// A mapping from identifiers (file names, potentially, but not necessarily) to their contents.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct VirtualFileSystem {
     files: std::collections::HashMap<PathBuf, String>,
 }
@@ -32,21 +32,55 @@ impl VirtualFileSystem {
     }
 
     /// Inserts or overwrites the buffer corresponding to the given identifier.
-    ///
-    /// TODO: A far more efficient "virtual file system" would update its buffers with changes sent
-    /// from the client, instead of completely replacing them each time. The rust-analyzer has a
-    /// 'vfs' module that is capable of doing just that, but it is not published on crates.io. If
-    /// we could help get it published, we could use it here.
     pub fn update(&mut self, identifier: PathBuf, content: &str) {
         self.files.insert(identifier, content.to_string());
     }
 
+    /// Applies a change sent by the client to the buffer corresponding to the given identifier.
+    /// A change without a range replaces the whole buffer, and changes to buffers that are not
+    /// present in the system are ignored.
+    pub fn apply_change(&mut self, identifier: &PathBuf, change: &TextDocumentContentChangeEvent) {
+        if let Some(buffer) = self.files.get_mut(identifier) {
+            match change.range {
+                Some(range) => {
+                    let start = byte_offset(buffer, &range.start);
+                    let end = byte_offset(buffer, &range.end).max(start);
+                    buffer.replace_range(start..end, &change.text);
+                },
+                None => *buffer = change.text.clone(),
+            }
+        }
+    }
+
     /// Removes the buffer and its identifier from the system.
     pub fn remove(&mut self, identifier: &PathBuf) {
         self.files.remove(identifier);
     }
 }
 
+/// Converts an LSP position, whose character offset counts UTF-16 code units, to a byte offset
+/// in the given text. Positions past the end of a line or of the text are clamped to it.
+pub fn byte_offset(text: &str, position: &Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+    let line = &text[line_start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let mut utf16_offset = 0;
+    for (i, c) in line.char_indices() {
+        if utf16_offset >= position.character as usize {
+            return line_start + i;
+        }
+        utf16_offset += c.len_utf16();
+    }
+    line_start + line.len()
+}
+
 /// Updates the given virtual file system based on the text document sync notification that was sent.
 pub fn on_text_document_sync_notification(
     files: &mut VirtualFileSystem,
@@ -69,10 +103,11 @@ pub fn on_text_document_sync_notification(
             let parameters =
                 serde_json::from_value::<DidChangeTextDocumentParams>(notification.params.clone())
                     .expect("could not deserialize notification");
-            files.update(
-                parameters.text_document.uri.to_file_path().unwrap(),
-                &parameters.content_changes.last().unwrap().text,
-            );
+            let file_path = parameters.text_document.uri.to_file_path().unwrap();
+            for change in &parameters.content_changes {
+                files.apply_change(&file_path, change);
+            }
+            symbolicator_runner.run(file_path);
         },
         lsp_types::notification::DidSaveTextDocument::METHOD => {
             let parameters =
@@ -88,9 +123,77 @@ pub fn on_text_document_sync_notification(
             let parameters =
                 serde_json::from_value::<DidCloseTextDocumentParams>(notification.params.clone())
                     .expect("could not deserialize notification");
-            files.remove(&parameters.text_document.uri.to_file_path().unwrap());
+            let file_path = parameters.text_document.uri.to_file_path().unwrap();
+            files.remove(&file_path);
+            // the file's contents on disk may differ from the closed buffer
+            symbolicator_runner.run(file_path);
         },
         _ => eprintln!("invalid notification '{}'", notification.method),
     }
     eprintln!("text document notification handled");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Range;
+
+    fn change(
+        range: Option<((u32, u32), (u32, u32))>,
+        text: &str,
+    ) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: range.map(|((l1, c1), (l2, c2))| {
+                Range::new(Position::new(l1, c1), Position::new(l2, c2))
+            }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_byte_offset() {
+        let text = "module 0x1::M {\r\n    // \u{1F600} é\n}";
+        assert_eq!(byte_offset(text, &Position::new(0, 0)), 0);
+        assert_eq!(byte_offset(text, &Position::new(0, 7)), 7);
+        // past the end of a line, before its "\r\n"
+        assert_eq!(byte_offset(text, &Position::new(0, 100)), 15);
+        assert_eq!(byte_offset(text, &Position::new(1, 4)), 21);
+        // the emoji is two UTF-16 code units, and four bytes
+        assert_eq!(byte_offset(text, &Position::new(1, 7)), 24);
+        assert_eq!(byte_offset(text, &Position::new(1, 9)), 28);
+        assert_eq!(byte_offset(text, &Position::new(1, 10)), 29);
+        assert_eq!(byte_offset(text, &Position::new(2, 1)), text.len());
+        assert_eq!(byte_offset(text, &Position::new(5, 0)), text.len());
+    }
+
+    #[test]
+    fn test_apply_change() {
+        let path = PathBuf::from("M.move");
+        let mut files = VirtualFileSystem::default();
+        files.update(path.clone(), "module 0x1::M {\n    fun f() {}\n}\n");
+
+        files.apply_change(&path, &change(Some(((1, 8), (1, 9))), "foo"));
+        assert_eq!(
+            files.get(&path),
+            Some("module 0x1::M {\n    fun foo() {}\n}\n")
+        );
+
+        files.apply_change(&path, &change(Some(((1, 14), (1, 14))), " abort 0 "));
+        assert_eq!(
+            files.get(&path),
+            Some("module 0x1::M {\n    fun foo() { abort 0 }\n}\n")
+        );
+
+        files.apply_change(&path, &change(Some(((0, 15), (2, 0))), ""));
+        assert_eq!(files.get(&path), Some("module 0x1::M {}\n"));
+
+        files.apply_change(&path, &change(None, "module 0x1::N {}"));
+        assert_eq!(files.get(&path), Some("module 0x1::N {}"));
+
+        // changes to unknown buffers are ignored
+        let other = PathBuf::from("N.move");
+        files.apply_change(&other, &change(None, "module 0x1::N {}"));
+        assert_eq!(files.get(&other), None);
+    }
+}
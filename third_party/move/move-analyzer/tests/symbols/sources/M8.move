@@ -0,0 +1,33 @@
+module Symbols::M8 {
+
+    #[resource_group(scope = global)]
+    /// A resource group
+    struct Group {}
+
+    /// A member of the resource group
+    #[resource_group_member(
+        group = Symbols::M8::Group
+    )]
+    struct Member has key {
+        value: u64,
+    }
+
+    /// An event emitted on changes
+    #[event]
+    struct Changed has drop, store {
+        value: u64,
+    }
+
+    /// A view function
+    #[view]
+    public fun value(addr: address): u64 acquires Member {
+        borrow_global<Member>(addr).value
+    }
+
+    fun inferred(addr: address): u64 acquires Member {
+        let v = value(addr);
+        let (a, b): (u64, u64) = (v, 1);
+        let Changed { value: c } = Changed { value: a + b };
+        c
+    }
+}
@@ -8,7 +8,7 @@ pub(crate) mod filter;
 pub mod keywords;
 pub mod lexer;
 pub(crate) mod merge_spec_modules;
-pub(crate) mod syntax;
+pub mod syntax;
 
 use crate::{
     attr_derivation,
@@ -21,7 +21,7 @@ use comments::*;
 use move_command_line_common::files::{find_move_filenames, FileHash};
 use move_symbol_pool::Symbol;
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fs::File,
     io::Read,
 };
@@ -31,6 +31,7 @@ pub(crate) fn parse_program(
     named_address_maps: NamedAddressMaps,
     targets: Vec<IndexedPackagePath>,
     deps: Vec<IndexedPackagePath>,
+    source_overlay: &BTreeMap<Symbol, String>,
 ) -> anyhow::Result<(
     FilesSourceText,
     Result<(parser::ast::Program, CommentMap), Diagnostics>,
@@ -76,7 +77,8 @@ pub(crate) fn parse_program(
         named_address_map,
     } in targets
     {
-        let (defs, comments, ds, file_hash) = parse_file(compilation_env, &mut files, path)?;
+        let (defs, comments, ds, file_hash) =
+            parse_file(compilation_env, &mut files, path, source_overlay)?;
         source_definitions.extend(defs.into_iter().map(|def| PackageDefinition {
             package,
             named_address_map,
@@ -92,7 +94,7 @@ pub(crate) fn parse_program(
         named_address_map,
     } in deps
     {
-        let (defs, _, ds, _) = parse_file(compilation_env, &mut files, path)?;
+        let (defs, _, ds, _) = parse_file(compilation_env, &mut files, path, source_overlay)?;
         lib_definitions.extend(defs.into_iter().map(|def| PackageDefinition {
             package,
             named_address_map,
@@ -175,6 +177,7 @@ fn parse_file(
     compilation_env: &mut CompilationEnv,
     files: &mut FilesSourceText,
     fname: Symbol,
+    source_overlay: &BTreeMap<Symbol, String>,
 ) -> anyhow::Result<(
     Vec<parser::ast::Definition>,
     MatchedFileCommentMap,
@@ -182,10 +185,16 @@ fn parse_file(
     FileHash,
 )> {
     let mut diags = Diagnostics::new();
-    let mut f = File::open(fname.as_str())
-        .map_err(|err| std::io::Error::new(err.kind(), format!("{}: {}", err, fname)))?;
-    let mut source_buffer = String::new();
-    f.read_to_string(&mut source_buffer)?;
+    let source_buffer = match source_overlay.get(&fname) {
+        Some(source) => source.clone(),
+        None => {
+            let mut f = File::open(fname.as_str())
+                .map_err(|err| std::io::Error::new(err.kind(), format!("{}: {}", err, fname)))?;
+            let mut source_buffer = String::new();
+            f.read_to_string(&mut source_buffer)?;
+            source_buffer
+        },
+    };
     let file_hash = FileHash::new(&source_buffer);
     let buffer = match verify_string(file_hash, &source_buffer) {
         Err(ds) => {
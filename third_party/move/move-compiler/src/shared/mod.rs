@@ -439,6 +439,8 @@ pub mod known_attributes {
         Test,
         // This test is expected to fail
         ExpectedFailure,
+        // This test is run with generated values for its parameters
+        Fuzz,
     }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -477,6 +479,7 @@ pub mod known_attributes {
                 TestingAttribute::EXPECTED_FAILURE => {
                     Self::Testing(TestingAttribute::ExpectedFailure)
                 },
+                TestingAttribute::FUZZ => Self::Testing(TestingAttribute::Fuzz),
                 VerificationAttribute::VERIFY_ONLY => {
                     Self::Verification(VerificationAttribute::VerifyOnly)
                 },
@@ -509,9 +512,11 @@ pub mod known_attributes {
         pub const ARITHMETIC_ERROR_NAME: &'static str = "arithmetic_error";
         pub const ERROR_LOCATION: &'static str = "location";
         pub const EXPECTED_FAILURE: &'static str = "expected_failure";
+        pub const FUZZ: &'static str = "fuzz";
         pub const MAJOR_STATUS_NAME: &'static str = "major_status";
         pub const MINOR_STATUS_NAME: &'static str = "minor_status";
         pub const OUT_OF_GAS_NAME: &'static str = "out_of_gas";
+        pub const RUNS_NAME: &'static str = "runs";
         pub const TEST: &'static str = "test";
         pub const TEST_ONLY: &'static str = "test_only";
         pub const VECTOR_ERROR_NAME: &'static str = "vector_error";
@@ -521,6 +526,7 @@ pub mod known_attributes {
                 Self::Test => Self::TEST,
                 Self::TestOnly => Self::TEST_ONLY,
                 Self::ExpectedFailure => Self::EXPECTED_FAILURE,
+                Self::Fuzz => Self::FUZZ,
             }
         }
 
@@ -541,10 +547,13 @@ pub mod known_attributes {
                 Lazy::new(|| IntoIterator::into_iter([AttributePosition::Function]).collect());
             static EXPECTED_FAILURE_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
                 Lazy::new(|| IntoIterator::into_iter([AttributePosition::Function]).collect());
+            static FUZZ_POSITIONS: Lazy<BTreeSet<AttributePosition>> =
+                Lazy::new(|| IntoIterator::into_iter([AttributePosition::Function]).collect());
             match self {
                 TestingAttribute::TestOnly => &TEST_ONLY_POSITIONS,
                 TestingAttribute::Test => &TEST_POSITIONS,
                 TestingAttribute::ExpectedFailure => &EXPECTED_FAILURE_POSITIONS,
+                TestingAttribute::Fuzz => &FUZZ_POSITIONS,
             }
         }
 
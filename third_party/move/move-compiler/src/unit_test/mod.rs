@@ -11,6 +11,7 @@ use move_core_types::{
     account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
     value::MoveValue, vm_status::StatusCode,
 };
+use move_symbol_pool::Symbol;
 use std::{collections::BTreeMap, fmt};
 
 pub mod filter_test_members;
@@ -36,6 +37,17 @@ pub struct TestCase {
     pub test_name: TestName,
     pub arguments: Vec<MoveValue>,
     pub expected_failure: Option<ExpectedFailure>,
+    // for a #[fuzz] test, how to generate its arguments, which are then not in `arguments`
+    pub fuzz: Option<FuzzConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    // number of times the test is run with generated arguments
+    pub runs: u64,
+    // the parameters of the test, with the values assigned in the #[test] attribute. The others
+    // are generated
+    pub parameters: Vec<(Symbol, Option<MoveValue>)>,
 }
 
 #[derive(Debug, Clone)]
@@ -14,7 +14,7 @@ use crate::{
         unique_map::UniqueMap,
         CompilationEnv, Identifier, NumericalAddress,
     },
-    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestCase},
+    unit_test::{ExpectedFailure, ExpectedMoveError, FuzzConfig, ModuleTestPlan, TestCase},
 };
 use move_core_types::{
     account_address::AccountAddress as MoveAddress, language_storage::ModuleId, u256::U256,
@@ -126,6 +126,7 @@ fn build_test_info<'func>(
     let test_attribute_opt = get_attrs(TestingAttribute::Test);
     let abort_attribute_opt = get_attrs(TestingAttribute::ExpectedFailure);
     let test_only_attribute_opt = get_attrs(TestingAttribute::TestOnly);
+    let fuzz_attribute_opt = get_attrs(TestingAttribute::Fuzz);
 
     let test_attribute = match test_attribute_opt {
         None => {
@@ -140,6 +141,17 @@ fn build_test_info<'func>(
                     (abort_attribute.loc, abort_msg),
                 ))
             }
+            // nor can fuzzing
+            if let Some(fuzz_attribute) = fuzz_attribute_opt {
+                let fn_msg = "Only functions defined as a test with #[test] can also have a \
+                              #[fuzz] attribute";
+                let fuzz_msg = "Attributed as #[fuzz] here";
+                context.env.add_diag(diag!(
+                    Attributes::InvalidUsage,
+                    (fn_loc, fn_msg),
+                    (fuzz_attribute.loc, fuzz_msg),
+                ))
+            }
             return None;
         },
         Some(test_attribute) => test_attribute,
@@ -158,9 +170,26 @@ fn build_test_info<'func>(
     }
 
     let test_annotation_params = parse_test_attribute(context, test_attribute, 0);
+    // The parameters of a #[fuzz] test which are not assigned in the #[test] attribute are
+    // generated when running it
+    let fuzz = fuzz_attribute_opt.map(|fuzz_attribute| FuzzConfig {
+        runs: parse_fuzz_attribute(context, fuzz_attribute).unwrap_or(DEFAULT_FUZZ_RUNS),
+        parameters: function
+            .signature
+            .parameters
+            .iter()
+            .map(|(var, _)| {
+                (
+                    var.value(),
+                    test_annotation_params.get(&var.value()).cloned(),
+                )
+            })
+            .collect(),
+    });
     let mut arguments = Vec::new();
     for (var, _) in &function.signature.parameters {
         match test_annotation_params.get(&var.value()) {
+            _ if fuzz.is_some() => (),
             Some(value) => arguments.push(value.clone()),
             None => {
                 let missing_param_msg = "Missing test parameter assignment in test. Expected a \
@@ -184,6 +213,7 @@ fn build_test_info<'func>(
         test_name: fn_name.to_string(),
         arguments,
         expected_failure,
+        fuzz,
     })
 }
 
@@ -251,6 +281,43 @@ fn parse_test_attribute(
     }
 }
 
+const DEFAULT_FUZZ_RUNS: u64 = 100;
+
+fn parse_fuzz_attribute(context: &mut Context, sp!(aloc, fuzz_attr): &E::Attribute) -> Option<u64> {
+    use E::Attribute_ as EA;
+    match fuzz_attr {
+        EA::Name(_) => Some(DEFAULT_FUZZ_RUNS),
+        EA::Assigned(_, value) => {
+            let invalid_assignment_msg = "Invalid fuzz assignment";
+            let expected_msg = "Expect a #[fuzz(runs=...)] attribute for the number of runs";
+            context.env.add_diag(diag!(
+                Attributes::InvalidValue,
+                (value.loc, invalid_assignment_msg),
+                (*aloc, expected_msg),
+            ));
+            None
+        },
+        EA::Parameterized(_, attrs) => {
+            let mut runs = DEFAULT_FUZZ_RUNS;
+            for (loc, name, attr) in attrs.iter() {
+                match &attr.value {
+                    EA::Assigned(_, value) if name.to_string() == TestingAttribute::RUNS_NAME => {
+                        let (_, u) = convert_attribute_value_u64(context, loc, value)?;
+                        runs = u;
+                    },
+                    _ => {
+                        let msg = format!("Unused attribute for {}", TestingAttribute::Fuzz.name());
+                        context
+                            .env
+                            .add_diag(diag!(UnusedItem::Attribute, (loc, msg)));
+                    },
+                }
+            }
+            Some(runs)
+        },
+    }
+}
+
 const BAD_ABORT_VALUE_WARNING: &str = "WARNING: passes for an abort from any module.";
 const INVALID_VALUE: &str = "Invalid value in attribute assignment";
 
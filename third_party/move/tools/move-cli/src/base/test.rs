@@ -76,6 +76,13 @@ pub struct Test {
     /// Collect coverage information for later use with the various `move coverage` subcommands
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
+    /// Run the tests against mutants of the package, e.g. with a comparison flipped or an
+    /// assertion removed, and report the mutants for which all tests still pass
+    #[clap(long = "mutate")]
+    pub mutate: bool,
+    /// The seed from which the arguments of #[fuzz] tests are generated, random by default
+    #[clap(long = "seed")]
+    pub seed: Option<u64>,
 
     /// Use the EVM-based execution backend.
     /// Does not work with --stackless.
@@ -104,6 +111,8 @@ impl Test {
             check_stackless_vm,
             verbose_mode,
             compute_coverage,
+            mutate,
+            seed,
             #[cfg(feature = "evm-backend")]
             evm,
         } = self;
@@ -117,6 +126,8 @@ impl Test {
             check_stackless_vm,
             verbose: verbose_mode,
             ignore_compile_warnings,
+            mutate,
+            seed,
             #[cfg(feature = "evm-backend")]
             evm,
 
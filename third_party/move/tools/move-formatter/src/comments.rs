@@ -0,0 +1,121 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::doc::Doc;
+use anyhow::{bail, Result};
+use move_command_line_common::files::FileHash;
+use move_compiler::parser::lexer::{Lexer, Tok};
+
+/// A comment of the source, which the parser skips.
+#[derive(Clone, Debug)]
+pub(crate) struct Comment {
+    /// The byte offsets of the comment in the source.
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub is_line: bool,
+    /// Whether the comment starts its line.
+    pub own_line: bool,
+    /// Whether code follows a block comment on its last line.
+    pub code_after: bool,
+    /// The column of the comment in the source, in characters.
+    col: usize,
+}
+
+impl Comment {
+    /// Returns the comment with the indentation of its continuation lines relative to its first
+    /// line.
+    pub fn doc(&self) -> Doc {
+        let lines = self
+            .text
+            .split('\n')
+            .enumerate()
+            .map(|(idx, line)| {
+                if idx == 0 {
+                    return (0, line.trim_end().to_string());
+                }
+                let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+                (indent.saturating_sub(self.col), line.trim().to_string())
+            })
+            .collect();
+        Doc::Lines(lines)
+    }
+
+    /// Returns the text of the comment without the whitespace the formatter may change.
+    pub fn normalized(&self) -> String {
+        self.text
+            .split('\n')
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Collects the comments of the source.
+pub(crate) fn comments(source: &str) -> Result<Vec<Comment>> {
+    let mut comments = vec![];
+    let mut lexer = Lexer::new(source, FileHash::empty());
+    loop {
+        if lexer.advance().is_err() {
+            bail!("invalid token at offset {}", lexer.previous_end_loc());
+        }
+        collect(
+            source,
+            lexer.previous_end_loc(),
+            lexer.start_loc(),
+            &mut comments,
+        );
+        if lexer.peek() == Tok::EOF {
+            break;
+        }
+    }
+    Ok(comments)
+}
+
+/// Collects the comments in `source[start..end]`, which the lexer skipped as whitespace.
+fn collect(source: &str, start: usize, end: usize, comments: &mut Vec<Comment>) {
+    let bytes = source.as_bytes();
+    let mut pos = start;
+    while pos < end {
+        let rest = &source[pos..end];
+        let len = if rest.starts_with("/*") {
+            let mut depth = 0;
+            let mut cur = pos;
+            loop {
+                if bytes[cur..].starts_with(b"/*") {
+                    depth += 1;
+                    cur += 2;
+                } else if bytes[cur..].starts_with(b"*/") {
+                    depth -= 1;
+                    cur += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    cur += 1;
+                }
+            }
+            cur - pos
+        } else if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        let line_start = source[..pos].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = source[pos + len..]
+            .find('\n')
+            .map_or(source.len(), |idx| pos + len + idx);
+        let is_line = rest.starts_with("//");
+        comments.push(Comment {
+            start: pos,
+            end: pos + len,
+            text: source[pos..pos + len].to_string(),
+            is_line,
+            own_line: source[line_start..pos].trim().is_empty(),
+            code_after: !is_line && !source[pos + len..line_end].trim().is_empty(),
+            col: source[line_start..pos].chars().count(),
+        });
+        pos += len;
+    }
+}
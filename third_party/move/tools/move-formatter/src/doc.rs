@@ -0,0 +1,252 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Documents describe a layout independent of the line width: the groups of a document are
+//! printed on one line if they fit, and otherwise break at their lines.
+
+/// The maximal width of a line.
+const WIDTH: usize = 100;
+/// The number of spaces per level of nesting.
+const INDENT: usize = 4;
+
+#[derive(Clone, Debug)]
+pub(crate) enum Doc {
+    Text(String),
+    /// A space, or a line break if the enclosing group breaks.
+    Line,
+    /// Nothing, or a line break if the enclosing group breaks.
+    SoftLine,
+    /// A line break, which also breaks all enclosing groups.
+    HardLine,
+    /// Indents the lines of the document by one more level.
+    Nest(Box<Doc>),
+    Group {
+        doc: Box<Doc>,
+        /// Whether the group contains a hard line break.
+        breaks: bool,
+    },
+    /// The first document if the enclosing group breaks, and the second one otherwise.
+    IfBreak(Box<Doc>, Box<Doc>),
+    /// Text printed at the end of the current line, like a trailing line comment.
+    LineSuffix(String),
+    /// Breaks all enclosing groups without printing anything.
+    BreakParent,
+    /// A block comment spanning several lines, whose continuation lines keep their indentation
+    /// relative to the first line.
+    Lines(Vec<(usize, String)>),
+    Concat(Vec<Doc>),
+}
+
+impl Doc {
+    pub fn nil() -> Doc {
+        Doc::Concat(vec![])
+    }
+
+    pub fn text(text: impl Into<String>) -> Doc {
+        Doc::Text(text.into())
+    }
+
+    pub fn nest(doc: Doc) -> Doc {
+        Doc::Nest(Box::new(doc))
+    }
+
+    pub fn group(doc: Doc) -> Doc {
+        let breaks = doc.breaks();
+        Doc::Group {
+            doc: Box::new(doc),
+            breaks,
+        }
+    }
+
+    pub fn if_break(broken: Doc, flat: Doc) -> Doc {
+        Doc::IfBreak(Box::new(broken), Box::new(flat))
+    }
+
+    /// Returns whether the document contains a hard line break outside of nested groups, which
+    /// record their own.
+    fn breaks(&self) -> bool {
+        match self {
+            Doc::HardLine | Doc::BreakParent => true,
+            Doc::Lines(lines) => lines.len() > 1,
+            Doc::Nest(doc) => doc.breaks(),
+            Doc::Group { breaks, .. } => *breaks,
+            Doc::IfBreak(broken, flat) => broken.breaks() || flat.breaks(),
+            Doc::Concat(docs) => docs.iter().any(Doc::breaks),
+            Doc::Text(_) | Doc::Line | Doc::SoftLine | Doc::LineSuffix(_) => false,
+        }
+    }
+
+    /// Returns whether the document prints nothing.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Doc::Text(text) => text.is_empty(),
+            Doc::Concat(docs) => docs.iter().all(Doc::is_empty),
+            _ => false,
+        }
+    }
+
+    /// Lays out the document.
+    pub fn render(&self) -> String {
+        Renderer::default().run(self)
+    }
+}
+
+/// Concatenates documents.
+macro_rules! docs {
+    ($($doc:expr),* $(,)?) => {
+        $crate::doc::Doc::Concat(vec![$($crate::doc::Doc::from($doc)),*])
+    };
+}
+pub(crate) use docs;
+
+impl From<&str> for Doc {
+    fn from(text: &str) -> Doc {
+        Doc::text(text)
+    }
+}
+
+impl From<String> for Doc {
+    fn from(text: String) -> Doc {
+        Doc::Text(text)
+    }
+}
+
+impl From<Vec<Doc>> for Doc {
+    fn from(docs: Vec<Doc>) -> Doc {
+        Doc::Concat(docs)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+#[derive(Default)]
+struct Renderer {
+    out: String,
+    /// The width of the current line.
+    col: usize,
+    suffixes: Vec<String>,
+}
+
+impl Renderer {
+    fn run(mut self, doc: &Doc) -> String {
+        let mut cmds = vec![(0, Mode::Break, doc)];
+        while let Some((indent, mode, doc)) = cmds.pop() {
+            match doc {
+                Doc::Text(text) => self.write(text),
+                Doc::Line | Doc::SoftLine if mode == Mode::Flat => {
+                    if matches!(doc, Doc::Line) {
+                        self.write(" ");
+                    }
+                },
+                Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                    self.newline(indent);
+                },
+                Doc::Nest(doc) => cmds.push((indent + INDENT, mode, doc)),
+                Doc::Group { doc, breaks } => {
+                    let mode = if mode == Mode::Flat
+                        || (!breaks
+                            && self.fits(indent, doc, &cmds, WIDTH.saturating_sub(self.col)))
+                    {
+                        Mode::Flat
+                    } else {
+                        Mode::Break
+                    };
+                    cmds.push((indent, mode, doc));
+                },
+                Doc::IfBreak(broken, flat) => cmds.push((
+                    indent,
+                    mode,
+                    if mode == Mode::Break { broken } else { flat },
+                )),
+                Doc::LineSuffix(text) => self.suffixes.push(text.clone()),
+                Doc::BreakParent => {},
+                Doc::Lines(lines) => {
+                    for (idx, (extra, line)) in lines.iter().enumerate() {
+                        if idx > 0 {
+                            self.newline(if line.is_empty() { 0 } else { indent + extra });
+                        }
+                        self.write(line);
+                    }
+                },
+                Doc::Concat(docs) => cmds.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+            }
+        }
+        self.newline(0);
+        let mut out = self.out.trim_end().to_string();
+        out.push('\n');
+        out
+    }
+
+    fn write(&mut self, text: &str) {
+        self.out.push_str(text);
+        self.col += text.chars().count();
+    }
+
+    fn newline(&mut self, indent: usize) {
+        for suffix in std::mem::take(&mut self.suffixes) {
+            self.write(&suffix);
+        }
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        self.out.push('\n');
+        self.out.push_str(&" ".repeat(indent));
+        self.col = indent;
+    }
+
+    /// Returns whether the document fits in `width` when printed flat, together with the rest
+    /// of the line.
+    fn fits(&self, indent: usize, doc: &Doc, rest: &[(usize, Mode, &Doc)], width: usize) -> bool {
+        let mut width = width as isize;
+        let mut rest_idx = rest.len();
+        let mut cmds = vec![(indent, Mode::Flat, doc)];
+        loop {
+            let (indent, mode, doc) = match cmds.pop() {
+                Some(cmd) => cmd,
+                None if rest_idx == 0 => return true,
+                None => {
+                    rest_idx -= 1;
+                    rest[rest_idx]
+                },
+            };
+            match doc {
+                Doc::Text(text) => width -= text.chars().count() as isize,
+                Doc::Line | Doc::SoftLine if mode == Mode::Flat => {
+                    if matches!(doc, Doc::Line) {
+                        width -= 1;
+                    }
+                },
+                Doc::Line | Doc::SoftLine | Doc::HardLine => return mode == Mode::Break,
+                Doc::Nest(doc) => cmds.push((indent, mode, doc)),
+                Doc::Group { doc, breaks } => {
+                    let mode = if *breaks { Mode::Break } else { mode };
+                    cmds.push((indent, mode, doc));
+                },
+                Doc::IfBreak(broken, flat) => cmds.push((
+                    indent,
+                    mode,
+                    if mode == Mode::Break { broken } else { flat },
+                )),
+                Doc::LineSuffix(_) => {},
+                Doc::BreakParent => {
+                    if mode == Mode::Flat {
+                        return false;
+                    }
+                },
+                Doc::Lines(lines) => {
+                    width -= lines[0].1.chars().count() as isize;
+                    if lines.len() > 1 {
+                        return mode == Mode::Break && width >= 0;
+                    }
+                },
+                Doc::Concat(docs) => cmds.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+            }
+            if width < 0 {
+                return false;
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A formatter for Move source files.
+//!
+//! The formatter parses the source and lays out its AST, so the output only depends on the
+//! program and its comments, and not on how the source was wrapped. The layout
+//! - indents by four spaces per level of nesting,
+//! - places each member of a module, and each statement of a block, on a line of its own, with
+//!   blank lines between the members of a module,
+//! - keeps lists, like arguments and fields, on one line if they fit in 100 columns, and
+//!   otherwise places each element on a line of its own, with a trailing comma,
+//! - breaks chains of binary operators before the operators.
+//!
+//! Comments stay next to the construct they precede, or follow on the same line. The result of
+//! formatting formatted source is the source itself. Sources which do not parse are rejected.
+
+mod comments;
+mod doc;
+mod printer;
+
+use anyhow::{bail, Result};
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    diagnostics::report_diagnostics_to_buffer,
+    parser::{ast::Definition, syntax::parse_file_string},
+    shared::CompilationEnv,
+    Flags,
+};
+use move_symbol_pool::Symbol;
+use std::collections::HashMap;
+
+/// Formats the Move source `source`, using `file_name` to report parse errors.
+pub fn format_source(file_name: &str, source: &str) -> Result<String> {
+    let defs = parse(file_name, source)?;
+    let comments = comments::comments(source)?;
+    let expected_comments: Vec<_> = comments.iter().map(comments::Comment::normalized).collect();
+    let formatted = printer::print(source, comments, &defs);
+
+    // Formatting must not change the meaning of the source, nor drop comments.
+    let formatted_defs = parse(file_name, &formatted)?;
+    if strip_locs(&defs) != strip_locs(&formatted_defs) {
+        bail!("formatting {} changed its syntax tree", file_name);
+    }
+    let actual_comments: Vec<_> = comments::comments(&formatted)?
+        .iter()
+        .map(comments::Comment::normalized)
+        .collect();
+    if expected_comments != actual_comments {
+        bail!("formatting {} changed its comments", file_name);
+    }
+    Ok(formatted)
+}
+
+/// Prints the definitions without their locations, which formatting changes.
+fn strip_locs(defs: &[Definition]) -> String {
+    let printed = format!("{:?}", defs);
+    let mut stripped = String::with_capacity(printed.len());
+    let mut rest = printed.as_str();
+    while let Some(idx) = rest.find("Loc {") {
+        stripped.push_str(&rest[..idx]);
+        let len = rest[idx..].find('}').expect("locations are closed");
+        rest = &rest[idx + len + 1..];
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+fn parse(file_name: &str, source: &str) -> Result<Vec<Definition>> {
+    let file_hash = FileHash::new(source);
+    let mut env = CompilationEnv::new(Flags::empty());
+    match parse_file_string(&mut env, file_hash, source) {
+        Ok((defs, _)) => Ok(defs),
+        Err(diags) => {
+            let files = HashMap::from([(file_hash, (Symbol::from(file_name), source.to_string()))]);
+            let buffer = report_diagnostics_to_buffer(&files, diags);
+            bail!("{}", String::from_utf8_lossy(&buffer));
+        },
+    }
+}
@@ -0,0 +1,1520 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lays out the AST of a source, with its comments attached to the closest construct.
+
+use crate::{
+    comments::Comment,
+    doc::{docs, Doc},
+};
+use move_compiler::{
+    parser::ast::*,
+    shared::{Identifier, Name},
+};
+use move_ir_types::location::{Loc, Spanned};
+
+/// The precedence of unary expressions, like `!e` and `&mut e`.
+const UNARY: u32 = 13;
+/// The precedence of terms, which never need parentheses.
+const TERM: u32 = 14;
+
+/// Spec variables whose names are keywords of spec blocks, which need an explicit `local`.
+const SPEC_KEYWORDS: &[&str] = &[
+    "aborts_if",
+    "aborts_with",
+    "apply",
+    "assert",
+    "assume",
+    "axiom",
+    "decreases",
+    "emits",
+    "ensures",
+    "global",
+    "include",
+    "local",
+    "modifies",
+    "pragma",
+    "requires",
+    "succeeds_if",
+    "update",
+];
+
+/// Lays out the definitions of `source`.
+pub(crate) fn print(source: &str, comments: Vec<Comment>, defs: &[Definition]) -> String {
+    let mut printer = Printer {
+        source,
+        comments,
+        next: 0,
+    };
+    let mut docs = vec![];
+    for (idx, def) in defs.iter().enumerate() {
+        if idx > 0 {
+            docs.push(Doc::HardLine);
+            docs.push(Doc::HardLine);
+        }
+        docs.push(printer.definition(def));
+    }
+    if defs.is_empty() {
+        docs.push(printer.leading(source.len()));
+    } else {
+        docs.push(printer.dangling(source.len()));
+    }
+    let doc = Doc::Concat(docs);
+    if doc.is_empty() {
+        return String::new();
+    }
+    doc.render()
+}
+
+/// A member of a module or script.
+#[derive(Clone, Copy)]
+enum Member<'a> {
+    Use(&'a UseDecl),
+    Friend(&'a FriendDecl),
+    Constant(&'a Constant),
+    Struct(&'a StructDefinition),
+    Function(&'a Function),
+    Spec(&'a SpecBlock),
+}
+
+impl<'a> From<&'a ModuleMember> for Member<'a> {
+    fn from(member: &'a ModuleMember) -> Self {
+        match member {
+            ModuleMember::Use(use_decl) => Member::Use(use_decl),
+            ModuleMember::Friend(friend) => Member::Friend(friend),
+            ModuleMember::Constant(constant) => Member::Constant(constant),
+            ModuleMember::Struct(struct_def) => Member::Struct(struct_def),
+            ModuleMember::Function(function) => Member::Function(function),
+            ModuleMember::Spec(spec) => Member::Spec(spec),
+        }
+    }
+}
+
+impl<'a> Member<'a> {
+    /// Returns the kind of members which follow each other without blank lines, if the member
+    /// is one. Declarations without bodies are grouped this way.
+    fn compact_kind(self) -> Option<u8> {
+        match self {
+            Member::Use(_) => Some(0),
+            Member::Friend(_) => Some(1),
+            Member::Constant(_) => Some(2),
+            Member::Spec(spec) => match singleton(spec) {
+                Some(SpecBlockMember_::Function {
+                    body:
+                        Spanned {
+                            value: FunctionBody_::Native,
+                            ..
+                        },
+                    ..
+                }) => Some(3),
+                _ => None,
+            },
+            Member::Function(function) => match function.body.value {
+                FunctionBody_::Native => Some(4),
+                FunctionBody_::Defined(_) => None,
+            },
+            Member::Struct(_) => None,
+        }
+    }
+
+    fn start(self) -> usize {
+        let (attributes, loc) = match self {
+            Member::Use(use_decl) => (&use_decl.attributes, use_ident(use_decl).loc),
+            Member::Friend(friend) => (&friend.attributes, friend.loc),
+            Member::Constant(constant) => (&constant.attributes, constant.loc),
+            Member::Struct(struct_def) => (&struct_def.attributes, struct_def.loc),
+            Member::Function(function) => (&function.attributes, function.loc),
+            Member::Spec(spec) => (&spec.value.attributes, spec.loc),
+        };
+        attributes
+            .first()
+            .map_or(start(loc), |attribute| start(attribute.loc))
+    }
+}
+
+/// Returns the member of a spec block written as a module member of its own, like
+/// `spec fun f(): u64;` or `invariant x > 0;`.
+fn singleton(spec: &SpecBlock) -> Option<&SpecBlockMember_> {
+    let target = &spec.value.target;
+    match (&target.value, spec.value.members.as_slice()) {
+        (SpecBlockTarget_::Module, [member]) if target.loc.start() == target.loc.end() => {
+            Some(&member.value)
+        },
+        _ => None,
+    }
+}
+
+fn use_ident(use_decl: &UseDecl) -> &ModuleIdent {
+    match &use_decl.use_ {
+        Use::Module(ident, _) | Use::Members(ident, _) => ident,
+    }
+}
+
+fn start(loc: Loc) -> usize {
+    loc.start() as usize
+}
+
+fn end(loc: Loc) -> usize {
+    loc.end() as usize
+}
+
+fn is_block(exp: &Exp) -> bool {
+    matches!(exp.value, Exp_::Block(_))
+}
+
+fn precedence(exp: &Exp_) -> u32 {
+    match exp {
+        Exp_::Lambda(..) | Exp_::Quant(..) | Exp_::Assign(..) => 0,
+        Exp_::BinopExp(_, op, _) => binop_precedence(op.value),
+        Exp_::UnaryExp(..)
+        | Exp_::Borrow(..)
+        | Exp_::Dereference(_)
+        | Exp_::Move(_)
+        | Exp_::Copy(_) => UNARY,
+        _ => TERM,
+    }
+}
+
+/// Returns whether the expression extends as far to the right as possible, so that it needs
+/// parentheses unless it ends its context.
+fn is_open(exp: &Exp_) -> bool {
+    matches!(
+        exp,
+        Exp_::Lambda(..)
+            | Exp_::Quant(..)
+            | Exp_::Assign(..)
+            | Exp_::IfElse(..)
+            | Exp_::While(..)
+            | Exp_::Loop(_)
+            | Exp_::Return(_)
+            | Exp_::Abort(_)
+    )
+}
+
+fn binop_precedence(op: BinOp_) -> u32 {
+    match op {
+        BinOp_::Implies | BinOp_::Iff => 2,
+        BinOp_::Or => 3,
+        BinOp_::And => 4,
+        BinOp_::Eq | BinOp_::Neq | BinOp_::Lt | BinOp_::Gt | BinOp_::Le | BinOp_::Ge => 5,
+        BinOp_::Range => 6,
+        BinOp_::BitOr => 7,
+        BinOp_::Xor => 8,
+        BinOp_::BitAnd => 9,
+        BinOp_::Shl | BinOp_::Shr => 10,
+        BinOp_::Add | BinOp_::Sub => 11,
+        BinOp_::Mul | BinOp_::Div | BinOp_::Mod => 12,
+    }
+}
+
+fn name(name: &Name) -> String {
+    name.value.to_string()
+}
+
+fn abilities(abilities: &[Ability], sep: &str) -> String {
+    abilities
+        .iter()
+        .map(|ability| match ability.value {
+            Ability_::Copy => "copy",
+            Ability_::Drop => "drop",
+            Ability_::Store => "store",
+            Ability_::Key => "key",
+        })
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+fn type_parameters(params: &[(Name, Vec<Ability>)]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let params: Vec<_> = params
+        .iter()
+        .map(|(param, constraints)| type_parameter(param, constraints))
+        .collect();
+    format!("<{}>", params.join(", "))
+}
+
+fn type_parameter(param: &Name, constraints: &[Ability]) -> String {
+    if constraints.is_empty() {
+        name(param)
+    } else {
+        format!("{}: {}", param, abilities(constraints, " + "))
+    }
+}
+
+/// Joins the documents with commas, breaking after the commas if the enclosing group breaks.
+fn comma_separated(docs: Vec<Doc>) -> Doc {
+    let mut joined = vec![];
+    for (idx, doc) in docs.into_iter().enumerate() {
+        if idx > 0 {
+            joined.push(Doc::text(","));
+            joined.push(Doc::Line);
+        }
+        joined.push(doc);
+    }
+    Doc::Concat(joined)
+}
+
+/// How a list between delimiters is laid out.
+#[derive(Clone, Copy)]
+enum ListStyle {
+    /// Without spaces inside the delimiters, like `f(a, b)`.
+    Tight,
+    /// With spaces inside the delimiters, like `S { a, b }`.
+    Spaced,
+    /// With one element per line.
+    Broken,
+}
+
+struct Printer<'a> {
+    source: &'a str,
+    comments: Vec<Comment>,
+    /// The index of the first comment which is not printed yet.
+    next: usize,
+}
+
+impl<'a> Printer<'a> {
+    //**********************************************************************************************
+    // Comments
+    //**********************************************************************************************
+
+    /// Returns the next comment if it starts before `pos`.
+    fn next_before(&self, pos: usize) -> Option<&Comment> {
+        self.comments
+            .get(self.next)
+            .filter(|comment| comment.start < pos)
+    }
+
+    /// Prints the comments before `pos`, for a construct starting at `pos`.
+    fn leading(&mut self, pos: usize) -> Doc {
+        let mut docs = vec![];
+        while let Some(comment) = self.next_before(pos) {
+            docs.push(comment.doc());
+            docs.push(
+                if comment.is_line || !comment.code_after {
+                    Doc::HardLine
+                } else {
+                    Doc::text(" ")
+                },
+            );
+            self.next += 1;
+        }
+        Doc::Concat(docs)
+    }
+
+    /// Prints the comments which follow a construct ending at `pos` on its last line, with at most
+    /// a separator in between.
+    fn trailing(&mut self, mut pos: usize) -> Doc {
+        let mut docs = vec![];
+        while let Some(comment) = self.comments.get(self.next) {
+            if comment.own_line
+                || comment.start < pos
+                || self.source[pos..comment.start]
+                    .chars()
+                    .any(|c| !matches!(c, ',' | ';' | ' ' | '\t'))
+            {
+                break;
+            }
+            docs.extend(self.suffix(comment));
+            pos = comment.end;
+            self.next += 1;
+        }
+        Doc::Concat(docs)
+    }
+
+    /// Prints the comments which follow an opening brace on its line, for a block whose first
+    /// construct starts at `pos`.
+    fn after_open(&mut self, pos: usize) -> Doc {
+        let mut docs = vec![];
+        while let Some(comment) = self.next_before(pos) {
+            if comment.own_line {
+                break;
+            }
+            docs.extend(self.suffix(comment));
+            self.next += 1;
+        }
+        Doc::Concat(docs)
+    }
+
+    fn suffix(&self, comment: &Comment) -> Vec<Doc> {
+        if comment.is_line {
+            vec![
+                Doc::LineSuffix(format!(" {}", comment.text)),
+                Doc::BreakParent,
+            ]
+        } else {
+            vec![Doc::text(" "), comment.doc()]
+        }
+    }
+
+    /// Prints the comments before `pos` on lines of their own, after the last construct of a
+    /// block ending at `pos`.
+    fn dangling(&mut self, pos: usize) -> Doc {
+        let mut docs = vec![];
+        while let Some(comment) = self.next_before(pos) {
+            docs.push(Doc::HardLine);
+            docs.push(comment.doc());
+            self.next += 1;
+        }
+        Doc::Concat(docs)
+    }
+
+    /// Returns the position of the next `ch` from `pos` which is not part of a comment.
+    fn find(&self, mut pos: usize, ch: char) -> usize {
+        loop {
+            let found = pos + self.source[pos..].find(ch).expect("unbalanced source");
+            match self
+                .comments
+                .iter()
+                .find(|comment| comment.start <= found && found < comment.end)
+            {
+                Some(comment) => pos = comment.end,
+                None => return found,
+            }
+        }
+    }
+
+    fn slice(&self, loc: Loc) -> &'a str {
+        &self.source[loc.usize_range()]
+    }
+
+    //**********************************************************************************************
+    // Lists
+    //**********************************************************************************************
+
+    /// Prints a list between delimiters, with one element per line if it does not fit on one
+    /// line, or if the style requires it. `close` is the position of the closing delimiter, if known.
+    fn list<T>(
+        &mut self,
+        (open, close_text): (&str, &str),
+        style: ListStyle,
+        items: &[T],
+        span: impl Fn(&T) -> (usize, usize),
+        mut item: impl FnMut(&mut Self, &T) -> Doc,
+        close: Option<usize>,
+    ) -> Doc {
+        let line = || match style {
+            ListStyle::Tight => Doc::SoftLine,
+            ListStyle::Spaced | ListStyle::Broken => Doc::Line,
+        };
+        let mut docs = vec![];
+        if matches!(style, ListStyle::Broken) && !items.is_empty() {
+            docs.push(Doc::BreakParent);
+        }
+        let after_open = match items.first() {
+            Some(first) => self.after_open(span(first).0),
+            None => Doc::nil(),
+        };
+        for (idx, elem) in items.iter().enumerate() {
+            let (elem_start, elem_end) = span(elem);
+            if idx > 0 {
+                docs.push(Doc::text(","));
+                docs.push(Doc::Line);
+            }
+            docs.push(self.leading(elem_start));
+            docs.push(item(self, elem));
+            docs.push(self.trailing(elem_end));
+        }
+        if !items.is_empty() {
+            docs.push(Doc::if_break(Doc::text(","), Doc::nil()));
+        }
+        if let Some(close) = close {
+            docs.push(self.dangling(close));
+        }
+        let docs = Doc::Concat(docs);
+        if docs.is_empty() {
+            return docs![open, close_text];
+        }
+        Doc::group(docs![
+            open,
+            after_open,
+            Doc::nest(docs![line(), docs]),
+            line(),
+            close_text
+        ])
+    }
+
+    fn type_arguments(&self, tys: &Option<Vec<Type>>) -> String {
+        match tys {
+            Some(tys) => format!("<{}>", self.types(tys)),
+            None => String::new(),
+        }
+    }
+
+    fn types(&self, tys: &[Type]) -> String {
+        tys.iter()
+            .map(|ty| self.type_(ty))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    //**********************************************************************************************
+    // Definitions
+    //**********************************************************************************************
+
+    fn definition(&mut self, def: &Definition) -> Doc {
+        match def {
+            Definition::Module(module) => self.module(module),
+            Definition::Address(address) => {
+                let attributes = self.attributes(&address.attributes);
+                let leading = self.leading(start(address.loc));
+                let header = format!("address {} {{", self.leading_name(&address.addr));
+                let close = self.find(
+                    address
+                        .modules
+                        .last()
+                        .map_or(end(address.loc), |module| end(module.loc)),
+                    '}',
+                );
+                let mut body = vec![];
+                for (idx, module) in address.modules.iter().enumerate() {
+                    if idx > 0 {
+                        body.push(Doc::HardLine);
+                    }
+                    body.push(Doc::HardLine);
+                    body.push(self.module(module));
+                }
+                body.push(self.dangling(close));
+                let body = Doc::Concat(body);
+                if body.is_empty() {
+                    return docs![attributes, leading, header, "}"];
+                }
+                docs![
+                    attributes,
+                    leading,
+                    header,
+                    Doc::nest(body),
+                    Doc::HardLine,
+                    "}"
+                ]
+            },
+            Definition::Script(script) => {
+                let attributes = self.attributes(&script.attributes);
+                let leading = self.leading(start(script.loc));
+                let mut members: Vec<_> = script.uses.iter().map(Member::Use).collect();
+                members.extend(script.constants.iter().map(Member::Constant));
+                members.push(Member::Function(&script.function));
+                members.extend(script.specs.iter().map(Member::Spec));
+                let body = self.members(&members, end(script.loc) - 1);
+                docs![attributes, leading, "script ", body]
+            },
+        }
+    }
+
+    fn module(&mut self, module: &ModuleDefinition) -> Doc {
+        let attributes = self.attributes(&module.attributes);
+        let leading = self.leading(start(module.loc));
+        let mut header = String::from(
+            if module.is_spec_module {
+                "spec "
+            } else {
+                "module "
+            },
+        );
+        if let Some(address) = &module.address {
+            header.push_str(&self.leading_name(address));
+            header.push_str("::");
+        }
+        header.push_str(&format!("{} ", module.name));
+        let members: Vec<Member> = module.members.iter().map(Member::from).collect();
+        let body = self.members(&members, end(module.loc) - 1);
+        docs![attributes, leading, header, body]
+    }
+
+    /// Prints the members of a module or script between braces, with `close` the position of
+    /// the closing brace.
+    fn members(&mut self, members: &[Member], close: usize) -> Doc {
+        let mut body = vec![];
+        let mut open = Doc::nil();
+        if let Some(first) = members.first() {
+            open = self.after_open(first.start());
+        }
+        let mut prev_kind = None;
+        for (idx, member) in members.iter().enumerate() {
+            let kind = member.compact_kind();
+            // A documented member is separated from the previous one even if they are compact.
+            let documented = self.next_before(member.start()).is_some();
+            if idx > 0 && (kind.is_none() || kind != prev_kind || documented) {
+                body.push(Doc::HardLine);
+            }
+            prev_kind = kind;
+            body.push(Doc::HardLine);
+            body.push(self.member(*member));
+        }
+        body.push(self.dangling(close));
+        let body = Doc::Concat(body);
+        if body.is_empty() {
+            return Doc::text("{}");
+        }
+        docs!["{", open, Doc::nest(body), Doc::HardLine, "}"]
+    }
+
+    fn member(&mut self, member: Member) -> Doc {
+        match member {
+            Member::Use(use_decl) => self.use_decl(use_decl),
+            Member::Friend(friend) => {
+                let attributes = self.attributes(&friend.attributes);
+                let leading = self.leading(start(friend.loc));
+                let trailing = self.trailing(end(friend.loc));
+                docs![
+                    attributes,
+                    leading,
+                    format!("friend {};", self.name_access_chain(&friend.friend)),
+                    trailing
+                ]
+            },
+            Member::Constant(constant) => {
+                let attributes = self.attributes(&constant.attributes);
+                let leading = self.leading(start(constant.loc));
+                let header = format!(
+                    "const {}: {} = ",
+                    constant.name,
+                    self.type_(&constant.signature)
+                );
+                let value = self.exp(&constant.value, 0, true);
+                let trailing = self.trailing(end(constant.loc));
+                docs![attributes, leading, header, value, ";", trailing]
+            },
+            Member::Struct(struct_def) => self.struct_def(struct_def),
+            Member::Function(function) => self.function(function),
+            Member::Spec(spec) => {
+                let doc = self.spec_block(spec);
+                let trailing = self.trailing(end(spec.loc));
+                docs![doc, trailing]
+            },
+        }
+    }
+
+    fn attributes(&mut self, attributes: &[Attributes]) -> Doc {
+        let mut docs = vec![];
+        for attribute in attributes {
+            docs.push(self.leading(start(attribute.loc)));
+            let attrs: Vec<_> = attribute
+                .value
+                .iter()
+                .map(|attr| self.attribute(attr))
+                .collect();
+            docs.push(Doc::text(format!("#[{}]", attrs.join(", "))));
+            docs.push(Doc::HardLine);
+        }
+        Doc::Concat(docs)
+    }
+
+    fn attribute(&self, attribute: &Attribute) -> String {
+        match &attribute.value {
+            Attribute_::Name(attr) => name(attr),
+            Attribute_::Assigned(attr, value) => {
+                let value = match &value.value {
+                    AttributeValue_::Value(value) => self.slice(value.loc).to_string(),
+                    AttributeValue_::ModuleAccess(chain) => self.name_access_chain(chain),
+                };
+                format!("{} = {}", attr, value)
+            },
+            Attribute_::Parameterized(attr, attributes) => {
+                let attrs: Vec<_> = attributes
+                    .value
+                    .iter()
+                    .map(|attr| self.attribute(attr))
+                    .collect();
+                format!("{}({})", attr, attrs.join(", "))
+            },
+        }
+    }
+
+    fn use_decl(&mut self, use_decl: &UseDecl) -> Doc {
+        let attributes = self.attributes(&use_decl.attributes);
+        let ident = use_ident(use_decl);
+        let leading = self.leading(start(ident.loc));
+        let (path, last) = match &use_decl.use_ {
+            Use::Module(ident, alias) => {
+                let mut path = self.module_ident(ident);
+                if let Some(alias) = alias {
+                    path.push_str(&format!(" as {}", alias));
+                }
+                let last = alias.as_ref().map_or(ident.loc, |alias| alias.loc());
+                (Doc::text(path), last)
+            },
+            Use::Members(ident, members) => {
+                let path = format!("{}::", self.module_ident(ident));
+                let member = |(member, alias): &(Name, Option<Name>)| match alias {
+                    Some(alias) => format!("{} as {}", member, alias),
+                    None => name(member),
+                };
+                let last = members
+                    .last()
+                    .map(|(member, alias)| alias.as_ref().unwrap_or(member).loc)
+                    .unwrap_or(ident.loc);
+                let doc = match members.as_slice() {
+                    [single] if single.0.value.as_str() != "Self" => {
+                        docs![path, member(single)]
+                    },
+                    _ => {
+                        let list = self.list(
+                            ("{", "}"),
+                            ListStyle::Tight,
+                            members,
+                            |(member, alias)| {
+                                (start(member.loc), end(alias.as_ref().unwrap_or(member).loc))
+                            },
+                            |_, elem| Doc::text(member(elem)),
+                            None,
+                        );
+                        docs![path, list]
+                    },
+                };
+                (doc, last)
+            },
+        };
+        let trailing = self.trailing(self.find(end(last), ';') + 1);
+        docs![attributes, leading, "use ", path, ";", trailing]
+    }
+
+    fn struct_def(&mut self, struct_def: &StructDefinition) -> Doc {
+        let attributes = self.attributes(&struct_def.attributes);
+        let leading = self.leading(start(struct_def.loc));
+        let native = matches!(struct_def.fields, StructFields::Native(_));
+        let mut header = format!(
+            "{}struct {}",
+            if native { "native " } else { "" },
+            struct_def.name
+        );
+        if !struct_def.type_parameters.is_empty() {
+            let params: Vec<_> = struct_def
+                .type_parameters
+                .iter()
+                .map(|param| {
+                    format!(
+                        "{}{}",
+                        if param.is_phantom { "phantom " } else { "" },
+                        type_parameter(&param.name, &param.constraints)
+                    )
+                })
+                .collect();
+            header.push_str(&format!("<{}>", params.join(", ")));
+        }
+        if !struct_def.abilities.is_empty() {
+            header.push_str(&format!(" has {}", abilities(&struct_def.abilities, ", ")));
+        }
+        let fields = match &struct_def.fields {
+            StructFields::Native(_) => Doc::text(";"),
+            StructFields::Defined(fields) => {
+                let list = self.list(
+                    ("{", "}"),
+                    ListStyle::Broken,
+                    fields,
+                    |(field, ty)| (start(field.loc()), end(ty.loc)),
+                    |printer, (field, ty)| Doc::text(format!("{}: {}", field, printer.type_(ty))),
+                    Some(end(struct_def.loc) - 1),
+                );
+                docs![" ", list]
+            },
+        };
+        let trailing = self.trailing(end(struct_def.loc));
+        docs![attributes, leading, header, fields, trailing]
+    }
+
+    fn function(&mut self, function: &Function) -> Doc {
+        let attributes = self.attributes(&function.attributes);
+        let leading = self.leading(start(function.loc));
+        let mut header = String::new();
+        match &function.visibility {
+            Visibility::Public(_) => header.push_str("public "),
+            Visibility::Script(_) => header.push_str("public(script) "),
+            Visibility::Friend(_) => header.push_str("public(friend) "),
+            Visibility::Internal => {},
+        }
+        // `public(script)` implies `entry`.
+        if function.entry.is_some() && function.visibility.loc() != function.entry {
+            header.push_str("entry ");
+        }
+        let native = matches!(function.body.value, FunctionBody_::Native);
+        if native {
+            header.push_str("native ");
+        }
+        if function.inline {
+            header.push_str("inline ");
+        }
+        header.push_str(&format!("fun {}", function.name));
+        let signature = self.signature(&function.signature, false);
+        let acquires = if function.acquires.is_empty() {
+            String::new()
+        } else {
+            let acquires: Vec<_> = function
+                .acquires
+                .iter()
+                .map(|chain| self.name_access_chain(chain))
+                .collect();
+            format!(" acquires {}", acquires.join(", "))
+        };
+        let body = match &function.body.value {
+            FunctionBody_::Native => Doc::text(";"),
+            FunctionBody_::Defined(seq) => {
+                docs![" ", self.block(seq, end(function.body.loc) - 1, true)]
+            },
+        };
+        let trailing = self.trailing(end(function.loc));
+        docs![attributes, leading, header, signature, acquires, body, trailing]
+    }
+
+    /// Prints the type parameters, parameters and return type of a function. The return type
+    /// is left out if it is `()`, unless `return_unit` is set.
+    fn signature(&mut self, signature: &FunctionSignature, return_unit: bool) -> Doc {
+        let type_params = type_parameters(&signature.type_parameters);
+        let params = self.list(
+            ("(", ")"),
+            ListStyle::Tight,
+            &signature.parameters,
+            |(var, ty)| (start(var.loc()), end(ty.loc)),
+            |printer, (var, ty)| Doc::text(format!("{}: {}", var, printer.type_(ty))),
+            None,
+        );
+        let return_type = match signature.return_type.value {
+            Type_::Unit if !return_unit => String::new(),
+            _ => format!(": {}", self.type_(&signature.return_type)),
+        };
+        docs![type_params, params, return_type]
+    }
+
+    //**********************************************************************************************
+    // Names and types
+    //**********************************************************************************************
+
+    fn leading_name(&self, access: &LeadingNameAccess) -> String {
+        match &access.value {
+            LeadingNameAccess_::AnonymousAddress(_) => self.slice(access.loc).to_string(),
+            LeadingNameAccess_::Name(n) => name(n),
+        }
+    }
+
+    fn module_ident(&self, ident: &ModuleIdent) -> String {
+        format!(
+            "{}::{}",
+            self.leading_name(&ident.value.address),
+            ident.value.module
+        )
+    }
+
+    fn name_access_chain(&self, chain: &NameAccessChain) -> String {
+        match &chain.value {
+            NameAccessChain_::One(n) => name(n),
+            NameAccessChain_::Two(access, n) => format!("{}::{}", self.leading_name(access), n),
+            NameAccessChain_::Three(access_n, n) => {
+                let (access, module) = &access_n.value;
+                format!("{}::{}::{}", self.leading_name(access), module, n)
+            },
+        }
+    }
+
+    fn type_(&self, ty: &Type) -> String {
+        match &ty.value {
+            Type_::Apply(chain, tys) => {
+                let chain = self.name_access_chain(chain);
+                if tys.is_empty() {
+                    chain
+                } else {
+                    format!("{}<{}>", chain, self.types(tys))
+                }
+            },
+            Type_::Ref(mutable, ty) => {
+                format!("&{}{}", if *mutable { "mut " } else { "" }, self.type_(ty))
+            },
+            Type_::Fun(args, result) => match result.value {
+                Type_::Unit => format!("|{}|", self.types(args)),
+                _ => format!("|{}| {}", self.types(args), self.type_(result)),
+            },
+            Type_::Unit => "()".to_string(),
+            Type_::Multiple(tys) => format!("({})", self.types(tys)),
+        }
+    }
+
+    //**********************************************************************************************
+    // Sequences
+    //**********************************************************************************************
+
+    /// Prints a block, with `close` the position of its closing brace. The block is laid out on
+    /// one line if it only consists of an expression, unless `always_break` is set, and the
+    /// caller places it in a group.
+    fn block(&mut self, seq: &Sequence, close: usize, always_break: bool) -> Doc {
+        let (uses, items, _, last) = seq;
+        let last = last.as_ref().as_ref();
+        let first = uses
+            .first()
+            .map(|use_decl| start(use_ident(use_decl).loc))
+            .or_else(|| items.first().map(|item| start(item.loc)))
+            .or_else(|| last.map(|exp| start(exp.loc)));
+        let open = match first {
+            Some(first) => self.after_open(first),
+            None => Doc::nil(),
+        };
+        let mut body = vec![];
+        for use_decl in uses {
+            body.push(Doc::HardLine);
+            body.push(self.use_decl(use_decl));
+        }
+        for item in items {
+            body.push(Doc::HardLine);
+            body.push(self.leading(start(item.loc)));
+            body.push(self.sequence_item(item));
+            body.push(Doc::text(";"));
+            body.push(self.trailing(end(item.loc)));
+        }
+        if let Some(exp) = last {
+            body.push(Doc::Line);
+            body.push(self.leading(start(exp.loc)));
+            body.push(self.exp(exp, 0, true));
+            body.push(self.trailing(end(exp.loc)));
+        }
+        body.push(self.dangling(close));
+        let mut body = Doc::Concat(body);
+        if body.is_empty() {
+            return Doc::text("{}");
+        }
+        if always_break || !uses.is_empty() || !items.is_empty() {
+            body = docs![Doc::BreakParent, body];
+        }
+        docs!["{", open, Doc::nest(body), Doc::Line, "}"]
+    }
+
+    fn sequence_item(&mut self, item: &SequenceItem) -> Doc {
+        match &item.value {
+            SequenceItem_::Seq(exp) => self.exp(exp, 0, true),
+            SequenceItem_::Declare(binds, ty) => {
+                let binds = self.bind_list(binds);
+                let ty = ty
+                    .as_ref()
+                    .map_or(String::new(), |ty| format!(": {}", self.type_(ty)));
+                docs!["let ", binds, ty]
+            },
+            SequenceItem_::Bind(binds, ty, exp) => {
+                let binds = self.bind_list(binds);
+                let ty = ty
+                    .as_ref()
+                    .map_or(String::new(), |ty| format!(": {}", self.type_(ty)));
+                let exp = self.exp(exp, 0, true);
+                docs!["let ", binds, ty, " = ", exp]
+            },
+        }
+    }
+
+    fn bind_list(&mut self, binds: &BindList) -> Doc {
+        match binds.value.as_slice() {
+            [bind] => self.bind(bind),
+            binds => self.list(
+                ("(", ")"),
+                ListStyle::Tight,
+                binds,
+                |bind| (start(bind.loc), end(bind.loc)),
+                |printer, bind| printer.bind(bind),
+                None,
+            ),
+        }
+    }
+
+    fn bind(&mut self, bind: &Bind) -> Doc {
+        match &bind.value {
+            Bind_::Var(var) => Doc::text(var.to_string()),
+            Bind_::Unpack(chain, tys, fields) => {
+                let head = format!(
+                    "{}{} ",
+                    self.name_access_chain(chain),
+                    self.type_arguments(tys)
+                );
+                let fields = self.list(
+                    ("{", "}"),
+                    ListStyle::Spaced,
+                    fields,
+                    |(field, bind)| (start(field.loc()), end(bind.loc)),
+                    |printer, (field, bind)| match &bind.value {
+                        // The field is bound to a variable of the same name.
+                        Bind_::Var(var) if var.loc() == field.loc() => Doc::text(field.to_string()),
+                        _ => docs![format!("{}: ", field), printer.bind(bind)],
+                    },
+                    Some(end(bind.loc) - 1),
+                );
+                docs![head, fields]
+            },
+        }
+    }
+
+    //**********************************************************************************************
+    // Expressions
+    //**********************************************************************************************
+
+    /// Prints an expression in a context which binds with precedence `min_prec`, where
+    /// `rightmost` tells whether the expression ends its context, so that it can extend to the
+    /// right as far as possible.
+    fn exp(&mut self, exp: &Exp, min_prec: u32, rightmost: bool) -> Doc {
+        let leading = self.leading(start(exp.loc));
+        let parens = precedence(&exp.value) < min_prec || (!rightmost && is_open(&exp.value));
+        let doc = self.exp_(exp, rightmost || parens);
+        if parens {
+            docs![leading, "(", doc, ")"]
+        } else {
+            docs![leading, doc]
+        }
+    }
+
+    fn exp_(&mut self, exp: &Exp, rightmost: bool) -> Doc {
+        match &exp.value {
+            Exp_::Value(value) => Doc::text(self.slice(value.loc)),
+            Exp_::Move(var) => Doc::text(format!("move {}", var)),
+            Exp_::Copy(var) => Doc::text(format!("copy {}", var)),
+            Exp_::Name(chain, tys) => Doc::text(format!(
+                "{}{}",
+                self.name_access_chain(chain),
+                self.type_arguments(tys)
+            )),
+            Exp_::Call(chain, is_macro, tys, args) => {
+                let head = format!(
+                    "{}{}{}",
+                    self.name_access_chain(chain),
+                    if *is_macro { "!" } else { "" },
+                    self.type_arguments(tys)
+                );
+                let args = self.exp_list(("(", ")"), args);
+                docs![head, args]
+            },
+            Exp_::Pack(chain, tys, fields) => {
+                let head = format!(
+                    "{}{} ",
+                    self.name_access_chain(chain),
+                    self.type_arguments(tys)
+                );
+                let fields = self.list(
+                    ("{", "}"),
+                    ListStyle::Spaced,
+                    fields,
+                    |(field, exp)| (start(field.loc()), end(exp.loc)),
+                    |printer, (field, exp)| {
+                        // The field is initialized with a variable of the same name.
+                        if exp.loc == field.loc() {
+                            Doc::text(field.to_string())
+                        } else {
+                            docs![format!("{}: ", field), printer.exp(exp, 0, true)]
+                        }
+                    },
+                    Some(end(exp.loc) - 1),
+                );
+                docs![head, fields]
+            },
+            Exp_::Vector(_, tys, args) => {
+                let head = format!("vector{}", self.type_arguments(tys));
+                let args = self.exp_list(("[", "]"), args);
+                docs![head, args]
+            },
+            Exp_::IfElse(cond, then, other) => {
+                Doc::group(self.if_else(cond, then, other.as_deref(), rightmost))
+            },
+            Exp_::While(cond, body) => {
+                // A loop invariant `while (cond) body spec { .. }` is parsed as a block around
+                // the condition.
+                let (cond, spec) = match &cond.value {
+                    Exp_::Block((uses, items, None, last)) if uses.is_empty() => {
+                        match (items.as_slice(), last.as_ref()) {
+                            ([item], Some(inner)) if inner.loc == cond.loc => match &item.value {
+                                SequenceItem_::Seq(seq) => match &seq.value {
+                                    Exp_::Spec(spec) => (inner, Some(spec)),
+                                    _ => (cond.as_ref(), None),
+                                },
+                                _ => (cond.as_ref(), None),
+                            },
+                            _ => (cond.as_ref(), None),
+                        }
+                    },
+                    _ => (cond.as_ref(), None),
+                };
+                let cond = self.exp(cond, 0, true);
+                let body = self.branch(body, rightmost && spec.is_none());
+                let spec = match spec {
+                    Some(spec) => docs![" ", self.spec_block(spec)],
+                    None => Doc::nil(),
+                };
+                Doc::group(docs!["while (", cond, ")", body, spec])
+            },
+            Exp_::Loop(body) => {
+                let body = self.branch(body, rightmost);
+                Doc::group(docs!["loop", body])
+            },
+            Exp_::Block(seq) => Doc::group(self.block(seq, end(exp.loc) - 1, false)),
+            Exp_::Lambda(binds, body) => {
+                let binds = if binds.value.is_empty() {
+                    Doc::text("| |")
+                } else {
+                    let binds: Vec<_> = binds.value.iter().map(|bind| self.bind(bind)).collect();
+                    let mut docs = vec![Doc::text("|")];
+                    for (idx, bind) in binds.into_iter().enumerate() {
+                        if idx > 0 {
+                            docs.push(Doc::text(", "));
+                        }
+                        docs.push(bind);
+                    }
+                    docs.push(Doc::text("|"));
+                    Doc::Concat(docs)
+                };
+                let body = self.exp(body, 0, rightmost);
+                docs![binds, " ", body]
+            },
+            Exp_::Quant(kind, binds, triggers, cond, body) => {
+                let kind = match kind.value {
+                    QuantKind_::Forall => "forall ",
+                    QuantKind_::Exists => "exists ",
+                    QuantKind_::Choose => "choose ",
+                    QuantKind_::ChooseMin => "choose min ",
+                };
+                let mut binds_docs = vec![];
+                for (idx, bind) in binds.value.iter().enumerate() {
+                    if idx > 0 {
+                        binds_docs.push(Doc::text(", "));
+                    }
+                    binds_docs.push(self.quant_bind(bind));
+                }
+                let mut triggers_docs = vec![];
+                for trigger in triggers {
+                    let exps: Vec<_> = trigger.iter().map(|exp| self.exp(exp, 0, true)).collect();
+                    let mut docs = vec![Doc::text(" {")];
+                    for (idx, exp) in exps.into_iter().enumerate() {
+                        if idx > 0 {
+                            docs.push(Doc::text(", "));
+                        }
+                        docs.push(exp);
+                    }
+                    docs.push(Doc::text("}"));
+                    triggers_docs.push(Doc::Concat(docs));
+                }
+                let cond = match cond {
+                    Some(cond) => docs![" where ", self.exp(cond, 0, true)],
+                    None => Doc::nil(),
+                };
+                let body = self.exp(body, 0, rightmost);
+                let body = if matches!(kind.trim(), "forall" | "exists") {
+                    docs![":", Doc::nest(docs![Doc::Line, body])]
+                } else {
+                    docs![" where", Doc::nest(docs![Doc::Line, body])]
+                };
+                Doc::group(docs![kind, binds_docs, triggers_docs, cond, body])
+            },
+            Exp_::ExpList(exps) => self.list(
+                ("(", ")"),
+                ListStyle::Tight,
+                exps,
+                |exp| (start(exp.loc), end(exp.loc)),
+                |printer, exp| printer.exp(exp, 0, true),
+                Some(end(exp.loc) - 1),
+            ),
+            Exp_::Unit => Doc::text("()"),
+            Exp_::Assign(lhs, rhs) => {
+                let lhs = self.exp(lhs, UNARY, false);
+                let rhs = self.exp(rhs, 0, rightmost);
+                docs![lhs, " = ", rhs]
+            },
+            Exp_::Return(None) => Doc::text("return"),
+            Exp_::Return(Some(exp)) => docs!["return ", self.exp(exp, 0, rightmost)],
+            Exp_::Abort(exp) => docs!["abort ", self.exp(exp, 0, rightmost)],
+            Exp_::Break => Doc::text("break"),
+            Exp_::Continue => Doc::text("continue"),
+            Exp_::Dereference(exp) => docs!["*", self.exp(exp, UNARY, rightmost)],
+            Exp_::UnaryExp(_, exp) => docs!["!", self.exp(exp, UNARY, rightmost)],
+            Exp_::BinopExp(..) => self.binop(exp),
+            Exp_::Borrow(mutable, exp) => docs![
+                if *mutable { "&mut " } else { "&" },
+                self.exp(exp, UNARY, rightmost)
+            ],
+            Exp_::Dot(exp, field) => docs![self.exp(exp, TERM, false), format!(".{}", field)],
+            Exp_::Index(exp, index) => {
+                docs![
+                    self.exp(exp, TERM, false),
+                    "[",
+                    self.exp(index, 0, true),
+                    "]"
+                ]
+            },
+            Exp_::Cast(exp, ty) => {
+                // `(a >> 1 as u8)` reads as if `1` was cast.
+                let exp = self.exp(exp, UNARY, true);
+                docs!["(", exp, format!(" as {})", self.type_(ty))]
+            },
+            Exp_::Annotate(exp, ty) => {
+                docs![
+                    "(",
+                    self.exp(exp, 0, true),
+                    format!(": {})", self.type_(ty))
+                ]
+            },
+            Exp_::Spec(spec) => self.spec_block(spec),
+            Exp_::UnresolvedError => unreachable!("the parser reports errors"),
+        }
+    }
+
+    fn exp_list(&mut self, delimiters: (&str, &str), exps: &Spanned<Vec<Exp>>) -> Doc {
+        self.list(
+            delimiters,
+            ListStyle::Tight,
+            &exps.value,
+            |exp| (start(exp.loc), end(exp.loc)),
+            |printer, exp| printer.exp(exp, 0, true),
+            Some(end(exps.loc) - 1),
+        )
+    }
+
+    /// Prints an `if`, whose branches break together with it.
+    fn if_else(&mut self, cond: &Exp, then: &Exp, other: Option<&Exp>, rightmost: bool) -> Doc {
+        let cond = self.exp(cond, 0, true);
+        let then_doc = self.branch(then, rightmost && other.is_none());
+        let other = match other {
+            None => Doc::nil(),
+            Some(other) => {
+                let sep = if is_block(then) {
+                    Doc::text(" ")
+                } else {
+                    Doc::Line
+                };
+                match &other.value {
+                    Exp_::IfElse(cond, then, other_else) => {
+                        let leading = self.leading(start(other.loc));
+                        let chained = self.if_else(cond, then, other_else.as_deref(), rightmost);
+                        docs![sep, "else ", leading, chained]
+                    },
+                    _ => docs![sep, "else", self.branch(other, rightmost)],
+                }
+            },
+        };
+        docs!["if (", cond, ")", then_doc, other]
+    }
+
+    /// Prints the body of a control expression, which is placed on the line of the control
+    /// expression if it is a block, and nested on the next line if it does not fit otherwise.
+    fn branch(&mut self, body: &Exp, rightmost: bool) -> Doc {
+        match &body.value {
+            Exp_::Block(seq) => {
+                let leading = self.leading(start(body.loc));
+                docs![" ", leading, self.block(seq, end(body.loc) - 1, true)]
+            },
+            _ => Doc::nest(docs![Doc::Line, self.exp(body, 0, rightmost)]),
+        }
+    }
+
+    /// Prints a chain of binary operators with the same precedence, breaking before the
+    /// operators if it does not fit on one line. Control expressions in the operands, and
+    /// arithmetic in the operands of bitwise operators, are put in parentheses for clarity.
+    fn binop(&mut self, exp: &Exp) -> Doc {
+        let prec = precedence(&exp.value);
+        let mut operands = vec![];
+        let mut ops = vec![];
+        let mut cur = exp;
+        while let Exp_::BinopExp(lhs, op, rhs) = &cur.value {
+            if binop_precedence(op.value) != prec {
+                break;
+            }
+            ops.push(op.value);
+            operands.push(rhs.as_ref());
+            cur = lhs;
+        }
+        operands.push(cur);
+        operands.reverse();
+        ops.reverse();
+
+        let bitwise = (7..=10).contains(&prec);
+        let min_prec = |operand: &Exp, min_prec: u32| match &operand.value {
+            Exp_::BinopExp(_, op, _) if bitwise && binop_precedence(op.value) != prec => TERM,
+            _ => min_prec,
+        };
+        let first = self.exp(operands[0], min_prec(operands[0], prec), false);
+        let trailing = self.trailing(end(operands[0].loc));
+        let mut rest = vec![];
+        for (idx, (op, operand)) in ops.iter().zip(&operands[1..]).enumerate() {
+            let last = idx + 2 == operands.len();
+            let operand_doc = self.exp(operand, min_prec(operand, prec + 1), false);
+            if *op == BinOp_::Range {
+                rest.push(docs!["..", operand_doc]);
+            } else {
+                rest.push(docs![Doc::Line, format!("{} ", op), operand_doc]);
+            }
+            if !last {
+                rest.push(self.trailing(end(operand.loc)));
+            }
+        }
+        Doc::group(docs![first, trailing, Doc::nest(Doc::Concat(rest))])
+    }
+
+    fn quant_bind(&mut self, bind: &BindWithRange) -> Doc {
+        let (bind, range) = &bind.value;
+        let bind = self.bind(bind);
+        match &range.value {
+            // A quantifier over a type is parsed as a call of a builtin.
+            Exp_::Call(chain, false, Some(tys), args)
+                if args.value.is_empty()
+                    && matches!(&chain.value, NameAccessChain_::One(n) if n.value.as_str() == "$spec_domain") =>
+            {
+                docs![bind, format!(": {}", self.types(tys))]
+            },
+            _ => docs![bind, " in ", self.exp(range, 0, true)],
+        }
+    }
+
+    //**********************************************************************************************
+    // Specs
+    //**********************************************************************************************
+
+    fn spec_block(&mut self, spec: &SpecBlock) -> Doc {
+        let SpecBlock_ {
+            attributes,
+            target,
+            uses,
+            members,
+        } = &spec.value;
+        let attributes = self.attributes(attributes);
+        let leading = self.leading(start(spec.loc));
+        if let Some(member) = singleton(spec) {
+            let prefix = match member {
+                SpecBlockMember_::Function { .. } => "spec ",
+                _ => "",
+            };
+            return docs![attributes, leading, prefix, self.spec_member(&members[0])];
+        }
+        let target = match &target.value {
+            SpecBlockTarget_::Code => Doc::nil(),
+            SpecBlockTarget_::Module => Doc::text("module "),
+            SpecBlockTarget_::Member(member, signature) => match signature {
+                Some(signature) => docs![name(member), self.signature(signature, false), " "],
+                None => Doc::text(format!("{} ", member)),
+            },
+            SpecBlockTarget_::Schema(schema, type_params) => Doc::text(format!(
+                "schema {}{} ",
+                schema,
+                type_parameters(type_params)
+            )),
+        };
+        let first = uses
+            .first()
+            .map(|use_decl| start(use_ident(use_decl).loc))
+            .or_else(|| members.first().map(|member| start(member.loc)));
+        let open = match first {
+            Some(first) => self.after_open(first),
+            None => Doc::nil(),
+        };
+        let mut body = vec![];
+        for use_decl in uses {
+            body.push(Doc::HardLine);
+            body.push(self.use_decl(use_decl));
+        }
+        for member in members {
+            body.push(Doc::HardLine);
+            body.push(self.leading(start(member.loc)));
+            body.push(self.spec_member(member));
+            body.push(self.trailing(end(member.loc)));
+        }
+        body.push(self.dangling(end(spec.loc) - 1));
+        let body = Doc::Concat(body);
+        let body = if body.is_empty() {
+            Doc::text("{}")
+        } else {
+            docs!["{", open, Doc::nest(body), Doc::HardLine, "}"]
+        };
+        docs![attributes, leading, "spec ", target, body]
+    }
+
+    fn spec_member(&mut self, member: &SpecBlockMember) -> Doc {
+        match &member.value {
+            SpecBlockMember_::Condition {
+                kind,
+                properties,
+                exp,
+                additional_exps,
+            } => {
+                let keyword = match &kind.value {
+                    SpecConditionKind_::Assert => "assert".to_string(),
+                    SpecConditionKind_::Assume => "assume".to_string(),
+                    SpecConditionKind_::Decreases => "decreases".to_string(),
+                    SpecConditionKind_::AbortsIf => "aborts_if".to_string(),
+                    SpecConditionKind_::AbortsWith => "aborts_with".to_string(),
+                    SpecConditionKind_::SucceedsIf => "succeeds_if".to_string(),
+                    SpecConditionKind_::Modifies => "modifies".to_string(),
+                    SpecConditionKind_::Emits => "emits".to_string(),
+                    SpecConditionKind_::Ensures => "ensures".to_string(),
+                    SpecConditionKind_::Requires => "requires".to_string(),
+                    SpecConditionKind_::Invariant(params) => {
+                        format!("invariant{}", type_parameters(params))
+                    },
+                    SpecConditionKind_::InvariantUpdate(params) => {
+                        format!("invariant{} update", type_parameters(params))
+                    },
+                    SpecConditionKind_::Axiom(params) => {
+                        format!("axiom{}", type_parameters(params))
+                    },
+                };
+                let properties = self.properties(properties);
+                let body = match &kind.value {
+                    // The expression is a placeholder, and the arguments follow.
+                    SpecConditionKind_::AbortsWith | SpecConditionKind_::Modifies => {
+                        let exps: Vec<_> = additional_exps
+                            .iter()
+                            .map(|exp| self.exp(exp, 0, true))
+                            .collect();
+                        Doc::group(Doc::nest(comma_separated(exps)))
+                    },
+                    SpecConditionKind_::AbortsIf => {
+                        let exp = self.exp(exp, 0, true);
+                        match additional_exps.first() {
+                            Some(code) => docs![exp, " with ", self.exp(code, 0, true)],
+                            None => exp,
+                        }
+                    },
+                    SpecConditionKind_::Emits => {
+                        let exp = self.exp(exp, 0, true);
+                        let handle = self.exp(&additional_exps[0], 0, true);
+                        let cond = match additional_exps.get(1) {
+                            Some(cond) => docs![" if ", self.exp(cond, 0, true)],
+                            None => Doc::nil(),
+                        };
+                        docs![exp, " to ", handle, cond]
+                    },
+                    _ => self.exp(exp, 0, true),
+                };
+                docs![keyword, properties, " ", body, ";"]
+            },
+            SpecBlockMember_::Function {
+                uninterpreted,
+                name: fun_name,
+                signature,
+                body,
+            } => {
+                let signature = self.signature(signature, true);
+                match &body.value {
+                    FunctionBody_::Native => docs![
+                        if *uninterpreted { "" } else { "native " },
+                        format!("fun {}", fun_name),
+                        signature,
+                        ";"
+                    ],
+                    FunctionBody_::Defined(seq) => {
+                        let block = self.block(seq, end(body.loc) - 1, true);
+                        docs![format!("fun {}", fun_name), signature, " ", block]
+                    },
+                }
+            },
+            SpecBlockMember_::Variable {
+                is_global,
+                name: var,
+                type_parameters: params,
+                type_,
+                init,
+            } => {
+                let prefix = if *is_global {
+                    "global "
+                } else if SPEC_KEYWORDS.contains(&var.value.as_str()) {
+                    "local "
+                } else {
+                    ""
+                };
+                let decl = format!(
+                    "{}{}{}: {}",
+                    prefix,
+                    var,
+                    type_parameters(params),
+                    self.type_(type_)
+                );
+                let init = match init {
+                    Some(init) => docs![" = ", self.exp(init, 0, true)],
+                    None => Doc::nil(),
+                };
+                docs![decl, init, ";"]
+            },
+            SpecBlockMember_::Let {
+                name: var,
+                post_state,
+                def,
+            } => {
+                let decl = format!("let {}{} = ", if *post_state { "post " } else { "" }, var);
+                docs![decl, self.exp(def, 0, true), ";"]
+            },
+            SpecBlockMember_::Update { lhs, rhs } => {
+                let lhs = self.exp(lhs, UNARY, false);
+                docs!["update ", lhs, " = ", self.exp(rhs, 0, true), ";"]
+            },
+            SpecBlockMember_::Include { properties, exp } => {
+                let properties = self.properties(properties);
+                docs!["include", properties, " ", self.exp(exp, 0, true), ";"]
+            },
+            SpecBlockMember_::Apply {
+                exp,
+                patterns,
+                exclusion_patterns,
+            } => {
+                let exp = self.exp(exp, 0, true);
+                let patterns = self.apply_patterns(patterns);
+                let exclusions = if exclusion_patterns.is_empty() {
+                    String::new()
+                } else {
+                    format!(" except {}", self.apply_patterns(exclusion_patterns))
+                };
+                docs!["apply ", exp, format!(" to {}{};", patterns, exclusions)]
+            },
+            SpecBlockMember_::Pragma { properties } => {
+                let properties: Vec<_> = properties
+                    .iter()
+                    .map(|property| Doc::text(self.property(property)))
+                    .collect();
+                Doc::group(docs![
+                    "pragma ",
+                    Doc::nest(comma_separated(properties)),
+                    ";"
+                ])
+            },
+        }
+    }
+
+    fn properties(&self, properties: &[PragmaProperty]) -> String {
+        if properties.is_empty() {
+            return String::new();
+        }
+        let properties: Vec<_> = properties
+            .iter()
+            .map(|property| self.property(property))
+            .collect();
+        format!(" [{}]", properties.join(", "))
+    }
+
+    fn property(&self, property: &PragmaProperty) -> String {
+        match &property.value.value {
+            None => name(&property.value.name),
+            Some(PragmaValue::Literal(value)) => {
+                format!("{} = {}", property.value.name, self.slice(value.loc))
+            },
+            Some(PragmaValue::Ident(chain)) => format!(
+                "{} = {}",
+                property.value.name,
+                self.name_access_chain(chain)
+            ),
+        }
+    }
+
+    fn apply_patterns(&self, patterns: &[SpecApplyPattern]) -> String {
+        patterns
+            .iter()
+            .map(|pattern| {
+                let visibility = match pattern.value.visibility {
+                    Some(Visibility::Public(_)) => "public ",
+                    Some(Visibility::Internal) => "internal ",
+                    _ => "",
+                };
+                let fragments: String = pattern
+                    .value
+                    .name_pattern
+                    .iter()
+                    .map(|fragment| match &fragment.value {
+                        SpecApplyFragment_::Wildcard => "*".to_string(),
+                        SpecApplyFragment_::NamePart(part) => name(part),
+                    })
+                    .collect();
+                format!(
+                    "{}{}{}",
+                    visibility,
+                    fragments,
+                    type_parameters(&pattern.value.type_parameters)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
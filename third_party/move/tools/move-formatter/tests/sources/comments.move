@@ -0,0 +1,24 @@
+/// Comments are kept where they are, with their text unchanged.
+module 0x42::comments {
+    /* A block comment
+       spanning several lines
+       moves along with its first line. */
+        struct S has drop { // trailing comments are separated by one space
+            a: u64,   // the first field
+            /* inline */ b: bool,
+        }
+
+  /**
+   * A documentation comment.
+   */
+  public fun f(s: S): u64 {
+      // a comment on its own line
+      let S { a, b: _ } = s;
+      a /* the result */
+  }
+    // a comment at the end of the module
+
+
+
+}
+// a comment at the end of the file
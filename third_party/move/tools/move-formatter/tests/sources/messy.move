@@ -0,0 +1,64 @@
+// A module written without any regard for formatting.
+module 0x42::messy{
+use std::vector ;
+use std::option::{Self,Option};
+
+
+   struct Pair<T:copy+drop> has copy,drop{first:T,second:T}
+
+   const E_EMPTY:u64=1;
+
+    public fun sum(v:&vector<u64>):u64{
+      let total=0;let i=0;
+      while(i <vector::length(v)){
+          total=total+*vector::borrow(v,i);
+          i=i+1;
+      };
+      total
+    }
+
+    public fun first<T:copy+drop>(p:&Pair<T>):T{p.first}
+
+    fun swap<T:copy+drop>(p:&mut Pair<T>){
+        let tmp=p.first;
+        p.first=p.second;p.second=tmp;
+    }
+
+    fun max(a:u64,b:u64):u64{
+        if(a>b){a}
+        else{b}
+    }
+
+    inline fun apply(x:u64,f:|u64|u64):u64{f(x)}
+
+    fun uses_lambda():u64{
+        apply(1,|x|x+1)
+    }
+
+    fun nested(v:vector<vector<u8>>):vector<Option<u8>>{
+        let result=vector::empty<Option<u8>>();
+        if(!vector::is_empty(&v)&&(vector::length(&v)<10)){
+            vector::push_back(&mut result,option::none())
+        };
+        assert!(vector::length(&result)<=1,E_EMPTY);
+        result
+    }
+
+    fun long_call(a:u64,
+        b:u64,
+            c:u64):u64 {
+        sum(&vector[a,
+        b,
+        c])
+    }
+
+    #[test]
+    #[expected_failure(abort_code=1,location=Self)]
+    fun test_abort(){abort(E_EMPTY)}
+
+    spec sum{
+        pragma verify=false,  timeout=10;
+        aborts_if false;
+        ensures result>=0;
+    }
+}
@@ -0,0 +1,12 @@
+script{
+use std::signer;
+use aptos_framework::coin;
+use aptos_framework::aptos_coin::AptosCoin;
+
+fun main(sender:&signer,amount:u64){
+let balance=coin::balance<AptosCoin>(signer::address_of(sender));
+if(balance>=amount){
+coin::transfer<AptosCoin>(sender,@0x1,amount);
+}
+}
+}
@@ -0,0 +1,34 @@
+module 0x42::specs {
+    struct Counter has key { value: u64 }
+
+    fun increment(addr: address) acquires Counter {
+        let counter = borrow_global_mut<Counter>(addr);
+        counter.value = counter.value+1;
+    }
+
+    spec module {
+        pragma verify=true,aborts_if_is_strict;
+        invariant forall addr:address where exists<Counter>(addr): global<Counter>(addr).value>=0;
+    }
+
+    spec increment(addr:address) {
+        let post new_value=global<Counter>(addr).value;
+        include CounterExists{addr};
+        aborts_if global<Counter>(addr).value+1>MAX_U64;
+        ensures new_value==old(global<Counter>(addr).value)+1;
+        modifies global<Counter>(addr);
+    }
+
+    spec schema CounterExists {
+        addr: address;
+        aborts_if !exists<Counter>(addr);
+    }
+
+    spec fun value_of(addr:address):u64 {
+        global<Counter>(addr).value
+    }
+
+    spec module {
+        apply CounterExists{addr:addr} to *increment*;
+    }
+}
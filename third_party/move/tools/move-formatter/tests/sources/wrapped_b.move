@@ -0,0 +1,49 @@
+// `wrapped_a.move` and `wrapped_b.move` wrap the same program differently.
+module 0x42::wrapped
+{
+    use std::vector;
+
+    struct Point has copy, drop {
+        x: u64,
+        y: u64
+    }
+
+    public fun distance_squared(
+        a: &Point,
+        b: &Point
+    ): u64 {
+        let dx =
+            if (a.x > b.x)
+                a.x - b.x
+            else
+                b.x - a.x;
+        let dy = if (a.y > b.y) a.y - b.y
+        else b.y - a.y;
+        dx * dx
+            + dy * dy
+    }
+
+    public fun sum_of_all_coordinates_in_the_given_points(points: &vector<Point>,
+                                                          offset: u64): u64 {
+        let total = offset;
+        let i = 0;
+        while (i < vector::length(points))
+        {
+            let p = vector::borrow(points, i);
+            total = total + p.x
+                + p.y;
+            i = i + 1;
+        };
+        total
+    }
+    fun make(x: u64, y: u64): Point {
+        Point {
+            x,
+            y,
+        }
+    }
+    spec make {
+        ensures result.x == x
+            && result.y == y;
+    }
+}
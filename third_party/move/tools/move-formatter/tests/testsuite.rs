@@ -0,0 +1,51 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::testing::{
+    add_update_baseline_fix, format_diff, read_env_update_baseline, EXP_EXT,
+};
+use move_formatter::format_source;
+use std::{fs, path::Path};
+
+// Formats each source under tests/sources and compares the result with the expected output.
+fn run_test_impl(path: &Path) -> anyhow::Result<()> {
+    let file_name = path.to_str().unwrap();
+    let source = fs::read_to_string(path)?;
+    let formatted = format_source(file_name, &source)?;
+
+    let exp_path = path.with_extension(EXP_EXT);
+    if read_env_update_baseline() {
+        fs::write(&exp_path, &formatted)?;
+    }
+    if !exp_path.is_file() {
+        let msg = format!("No expected output found for {:?}", path);
+        anyhow::bail!(add_update_baseline_fix(msg));
+    }
+    let expected = fs::read_to_string(&exp_path)?;
+    if expected != formatted {
+        let msg = format!(
+            "Expected outputs differ for {:?}:\n{}",
+            exp_path,
+            format_diff(expected, formatted)
+        );
+        anyhow::bail!(add_update_baseline_fix(msg));
+    }
+
+    // Formatted sources stay as they are.
+    let reformatted = format_source(file_name, &formatted)?;
+    if reformatted != formatted {
+        anyhow::bail!(
+            "Formatting is not idempotent for {:?}:\n{}",
+            path,
+            format_diff(formatted, reformatted)
+        );
+    }
+    Ok(())
+}
+
+fn run_test(path: &Path) -> datatest_stable::Result<()> {
+    run_test_impl(path)?;
+    Ok(())
+}
+
+datatest_stable::harness!(run_test, "tests/sources", r".*\.move$");
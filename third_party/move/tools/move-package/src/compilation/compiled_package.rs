@@ -4,6 +4,7 @@
 
 use crate::{
     compilation::package_layout::CompiledPackageLayout,
+    package_hooks,
     resolution::resolution_graph::{Renaming, ResolvedGraph, ResolvedPackage, ResolvedTable},
     source_package::{
         layout::{SourcePackageLayout, REFERENCE_TEMPLATE_FILENAME},
@@ -11,7 +12,7 @@ use crate::{
     },
     BuildConfig,
 };
-use anyhow::{ensure, Result};
+use anyhow::{ensure, Context, Result};
 use colored::Colorize;
 use itertools::{Either, Itertools};
 use move_abigen::{Abigen, AbigenOptions};
@@ -620,6 +621,23 @@ impl CompiledPackage {
                 deps_compiled_units.push((package_name, unit))
             }
         }
+        if !resolution_graph.build_options.test_mode {
+            for (dep_name, (info, pin)) in &resolution_graph.custom_dependencies {
+                // Units are attributed to the resolved name of their package
+                let units: Vec<_> = deps_compiled_units
+                    .iter()
+                    .filter(|(package_name, _)| package_name == dep_name)
+                    .map(|(_, unit)| &unit.unit)
+                    .collect();
+                ensure!(
+                    !units.is_empty(),
+                    "No units were compiled from custom dependency '{}'",
+                    dep_name
+                );
+                package_hooks::verify_custom_dependency(*dep_name, info, pin, &units)
+                    .with_context(|| format!("While verifying custom dependency '{}'", dep_name))?;
+            }
+        }
         let bytecode_version = get_bytecode_version_from_env(bytecode_version);
 
         let mut compiled_docs = None;
@@ -14,7 +14,10 @@ use crate::{
         build_plan::BuildPlan, compiled_package::CompiledPackage, model_builder::ModelBuilder,
     },
     package_lock::PackageLock,
-    resolution::resolution_graph::{ResolutionGraph, ResolvedGraph},
+    resolution::{
+        lock_file::LockFile,
+        resolution_graph::{ResolutionGraph, ResolvedGraph},
+    },
     source_package::manifest_parser,
 };
 use anyhow::{bail, Result};
@@ -213,7 +216,12 @@ impl BuildConfig {
         // This should be locked as it inspects the environment for `MOVE_HOME` which could
         // possibly be set by a different process in parallel.
         let manifest = manifest_parser::parse_source_manifest(toml_manifest)?;
-        ResolutionGraph::download_dependency_repos(&manifest, self, &path, writer)?;
+        let locked = LockFile::read(&path)?;
+        let mut lock_file = locked.clone();
+        ResolutionGraph::download_dependency_repos(&manifest, self, &path, &mut lock_file, writer)?;
+        if lock_file != locked {
+            lock_file.write(&path)?;
+        }
         mutx.unlock();
         Ok(())
     }
@@ -1,8 +1,9 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::source_package::parsed_manifest::CustomDepInfo;
+use crate::source_package::parsed_manifest::{CustomDepInfo, CustomDepPin};
 use anyhow::bail;
+use move_compiler::compiled_unit::CompiledUnit;
 use move_symbol_pool::Symbol;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
@@ -21,12 +22,26 @@ pub trait PackageHooks {
 
     /// A resolver for custom dependencies in the manifest. This is called to download the
     /// dependency from the dependency into the `info.local_path` location, similar as with git
-    /// dependencies.
+    /// dependencies. If the lock file pins the dependency, it is downloaded at `pin`. Returns
+    /// the version at which the dependency was downloaded, which is recorded in the lock file.
     fn resolve_custom_dependency(
         &self,
         dep_name: Symbol,
         info: &CustomDepInfo,
-    ) -> anyhow::Result<()>;
+        pin: Option<&CustomDepPin>,
+    ) -> anyhow::Result<CustomDepPin>;
+
+    /// Checks the units compiled from a custom dependency against the ones it was resolved from
+    /// at `pin`. This is called when the dependency is compiled outside of test mode.
+    fn verify_custom_dependency(
+        &self,
+        _dep_name: Symbol,
+        _info: &CustomDepInfo,
+        _pin: &CustomDepPin,
+        _units: &[&CompiledUnit],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 static HOOKS: Lazy<Mutex<Option<Box<dyn PackageHooks + Send + Sync>>>> =
     Lazy::new(|| Mutex::new(None));
@@ -40,14 +55,29 @@ pub fn register_package_hooks(hooks: Box<dyn PackageHooks + Send + Sync>) {
 pub(crate) fn resolve_custom_dependency(
     dep_name: Symbol,
     info: &CustomDepInfo,
-) -> anyhow::Result<()> {
+    pin: Option<&CustomDepPin>,
+) -> anyhow::Result<CustomDepPin> {
     if let Some(hooks) = &*HOOKS.lock().unwrap() {
-        hooks.resolve_custom_dependency(dep_name, info)
+        hooks.resolve_custom_dependency(dep_name, info, pin)
     } else {
         bail!("use of unsupported custom dependency in package manifest")
     }
 }
 
+/// Calls any registered hook to verify the units compiled from a node dependency.
+pub(crate) fn verify_custom_dependency(
+    dep_name: Symbol,
+    info: &CustomDepInfo,
+    pin: &CustomDepPin,
+    units: &[&CompiledUnit],
+) -> anyhow::Result<()> {
+    if let Some(hooks) = &*HOOKS.lock().unwrap() {
+        hooks.verify_custom_dependency(dep_name, info, pin, units)
+    } else {
+        Ok(())
+    }
+}
+
 pub(crate) fn custom_dependency_key() -> Option<String> {
     if let Some(hooks) = &*HOOKS.lock().unwrap() {
         hooks.custom_dependency_key()
@@ -0,0 +1,95 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::source_package::{
+    layout::SourcePackageLayout,
+    parsed_manifest::{CustomDepInfo, CustomDepPin, PackageName},
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const LOCK_FILE_HEADER: &str =
+    "# This file is generated by the Move package system. Do not edit it.\n\n";
+
+/// The lock file of a package, `Move.lock` next to its manifest. It records the versions at which
+/// the custom dependencies of the package, including transitive ones, were resolved, so that later
+/// builds resolve them to the same contents without asking the node for the latest ones.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LockFile {
+    #[serde(
+        default,
+        rename = "custom_dependency",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub custom_dependencies: Vec<LockedCustomDep>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LockedCustomDep {
+    pub name: String,
+    pub node_url: String,
+    pub address: String,
+    pub version: u64,
+    pub digest: String,
+}
+
+impl LockFile {
+    /// Reads the lock file of the package at `root`, or returns an empty one if there is none.
+    pub fn read(root: &Path) -> Result<Self> {
+        let path = root.join(SourcePackageLayout::Lock.path());
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(&path)?;
+        toml::from_str(&contents).with_context(|| format!("Malformed lock file {:?}", path))
+    }
+
+    /// Writes the lock file of the package at `root`.
+    pub fn write(&self, root: &Path) -> Result<()> {
+        let contents = toml::to_string(self)?;
+        fs::write(
+            root.join(SourcePackageLayout::Lock.path()),
+            format!("{}{}", LOCK_FILE_HEADER, contents),
+        )?;
+        Ok(())
+    }
+
+    /// Returns the version at which the custom dependency `name` is locked, if any.
+    pub fn pin(&self, name: PackageName, info: &CustomDepInfo) -> Option<CustomDepPin> {
+        self.custom_dependencies
+            .iter()
+            .find(|dep| dep.is_for(name, info))
+            .map(|dep| CustomDepPin {
+                version: dep.version,
+                digest: dep.digest.clone(),
+            })
+    }
+
+    /// Locks the custom dependency `name` at `pin`, replacing any previous version.
+    pub fn insert(&mut self, name: PackageName, info: &CustomDepInfo, pin: &CustomDepPin) {
+        let locked = LockedCustomDep {
+            name: name.to_string(),
+            node_url: info.node_url.to_string(),
+            address: info.package_address.to_string(),
+            version: pin.version,
+            digest: pin.digest.clone(),
+        };
+        match self
+            .custom_dependencies
+            .iter_mut()
+            .find(|dep| dep.is_for(name, info))
+        {
+            Some(dep) => *dep = locked,
+            None => self.custom_dependencies.push(locked),
+        }
+    }
+}
+
+impl LockedCustomDep {
+    fn is_for(&self, name: PackageName, info: &CustomDepInfo) -> bool {
+        self.name == name.as_str()
+            && self.node_url == info.node_url.as_str()
+            && self.address == info.package_address.as_str()
+    }
+}
@@ -4,13 +4,13 @@
 
 use crate::{
     package_hooks,
-    resolution::digest::compute_digest,
+    resolution::{digest::compute_digest, lock_file::LockFile},
     source_package::{
         layout::SourcePackageLayout,
         manifest_parser::{parse_move_manifest_string, parse_source_manifest},
         parsed_manifest::{
-            Dependencies, Dependency, FileName, NamedAddress, PackageDigest, PackageName,
-            SourceManifest, SubstOrRename,
+            CustomDepInfo, CustomDepPin, Dependencies, Dependency, FileName, NamedAddress,
+            PackageDigest, PackageName, SourceManifest, SubstOrRename,
         },
     },
     BuildConfig,
@@ -76,6 +76,12 @@ pub struct ResolutionGraph<T> {
     pub graph: DiGraphMap<PackageName, ()>,
     /// A mapping of package name to its resolution
     pub package_table: BTreeMap<PackageName, ResolutionPackage<T>>,
+    /// The lock file of the root package, updated with the versions at which custom dependencies
+    /// were resolved
+    pub lock_file: LockFile,
+    /// The custom dependencies in the graph, by resolved package name, and the versions at which
+    /// they were resolved
+    pub custom_dependencies: BTreeMap<PackageName, (CustomDepInfo, CustomDepPin)>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -106,22 +112,33 @@ impl ResolvingGraph {
                 build_options.architecture = info.architecture;
             }
         }
+        let lock_file = LockFile::read(&root_package_path)?;
         let mut resolution_graph = Self {
             root_package_path: root_package_path.clone(),
             build_options,
             root_package: root_package.clone(),
             graph: DiGraphMap::new(),
             package_table: BTreeMap::new(),
+            lock_file: lock_file.clone(),
+            custom_dependencies: BTreeMap::new(),
         };
 
         resolution_graph
-            .build_resolution_graph(root_package.clone(), root_package_path, true, writer)
+            .build_resolution_graph(
+                root_package.clone(),
+                root_package_path.clone(),
+                true,
+                writer,
+            )
             .with_context(|| {
                 format!(
                     "Unable to resolve packages for package '{}'",
                     root_package.package.name
                 )
             })?;
+        if resolution_graph.lock_file != lock_file {
+            resolution_graph.lock_file.write(&root_package_path)?;
+        }
         Ok(resolution_graph)
     }
 
@@ -132,6 +149,8 @@ impl ResolvingGraph {
             root_package,
             graph,
             package_table,
+            lock_file,
+            custom_dependencies,
         } = self;
 
         let mut unresolved_addresses = Vec::new();
@@ -192,6 +211,8 @@ impl ResolvingGraph {
             root_package,
             graph,
             package_table: resolved_package_table,
+            lock_file,
+            custom_dependencies,
         })
     }
 
@@ -396,15 +417,21 @@ impl ResolvingGraph {
         root_path: PathBuf,
         writer: &mut W,
     ) -> Result<(Renaming, ResolvingTable)> {
-        Self::download_and_update_if_remote(
+        let pin = Self::download_and_update_if_remote(
             dep_name_in_pkg,
             &dep,
             self.build_options.skip_fetch_latest_git_deps,
+            &mut self.lock_file,
             writer,
         )?;
         let (dep_package, dep_package_dir) =
             Self::parse_package_manifest(&dep, &dep_name_in_pkg, root_path)
                 .with_context(|| format!("While processing dependency '{}'", dep_name_in_pkg))?;
+        if let (Some(node_info), Some(pin)) = (&dep.node_info, pin) {
+            // Keyed by the name of the downloaded package, which its units are compiled under
+            self.custom_dependencies
+                .insert(dep_package.package.name, (node_info.clone(), pin));
+        }
         self.build_resolution_graph(dep_package.clone(), dep_package_dir, false, writer)
             .with_context(|| {
                 format!("Unable to resolve package dependency '{}'", dep_name_in_pkg)
@@ -528,6 +555,7 @@ impl ResolvingGraph {
         manifest: &SourceManifest,
         build_options: &BuildConfig,
         root_path: &Path,
+        lock_file: &mut LockFile,
         writer: &mut W,
     ) -> Result<()> {
         // include dev dependencies if in dev mode
@@ -544,6 +572,7 @@ impl ResolvingGraph {
                 *dep_name,
                 dep,
                 build_options.skip_fetch_latest_git_deps,
+                lock_file,
                 writer,
             )?;
 
@@ -551,17 +580,26 @@ impl ResolvingGraph {
                 Self::parse_package_manifest(dep, dep_name, root_path.to_path_buf())
                     .with_context(|| format!("While processing dependency '{}'", *dep_name))?;
             // download dependencies of dependencies
-            Self::download_dependency_repos(&dep_manifest, build_options, root_path, writer)?;
+            Self::download_dependency_repos(
+                &dep_manifest,
+                build_options,
+                root_path,
+                lock_file,
+                writer,
+            )?;
         }
         Ok(())
     }
 
+    /// Downloads the dependency if it is remote. Custom dependencies are resolved at the version
+    /// in `lock_file`, if there is one, and the version they are resolved at is recorded there.
     fn download_and_update_if_remote<W: Write>(
         dep_name: PackageName,
         dep: &Dependency,
         skip_fetch_latest_git_deps: bool,
+        lock_file: &mut LockFile,
         writer: &mut W,
-    ) -> Result<()> {
+    ) -> Result<Option<CustomDepPin>> {
         if let Some(git_info) = &dep.git_info {
             let git_url = git_info.git_url.as_str();
             let git_rev = git_info.git_rev.as_str();
@@ -603,7 +641,7 @@ impl ResolvingGraph {
                     if let Ok(parsable_version) = String::from_utf8(rev.stdout) {
                         // If it's exactly the same, then it's a git rev
                         if parsable_version.trim().starts_with(git_rev) {
-                            return Ok(());
+                            return Ok(None);
                         }
                     }
                 }
@@ -618,7 +656,7 @@ impl ResolvingGraph {
                         // Tags don't easily update locally and you can't use reset --hard to cleanup
                         // any extra files
                         if parsable_version.trim().starts_with(git_rev) {
-                            return Ok(());
+                            return Ok(None);
                         }
                     }
                 }
@@ -685,9 +723,15 @@ impl ResolvingGraph {
             }
         }
         if let Some(node_info) = &dep.node_info {
-            package_hooks::resolve_custom_dependency(dep_name, node_info)?
+            let pin = package_hooks::resolve_custom_dependency(
+                dep_name,
+                node_info,
+                lock_file.pin(dep_name, node_info).as_ref(),
+            )?;
+            lock_file.insert(dep_name, node_info, &pin);
+            return Ok(Some(pin));
         }
-        Ok(())
+        Ok(None)
     }
 }
 
@@ -5,6 +5,7 @@
 use crate::Architecture;
 use move_core_types::account_address::AccountAddress;
 use move_symbol_pool::symbol::Symbol;
+use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, path::PathBuf};
 
 pub type NamedAddress = Symbol;
@@ -73,6 +74,17 @@ pub struct CustomDepInfo {
     pub download_to: PathBuf,
 }
 
+/// The version at which a custom dependency was resolved, as recorded in the lock file of the
+/// depending package. Like the package address, the representation depends on the registered
+/// node resolver.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CustomDepPin {
+    /// The version of the node state the package was downloaded at.
+    pub version: u64,
+    /// The digest of the package at that version.
+    pub digest: String,
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct BuildInfo {
     pub language_version: Option<Version>,
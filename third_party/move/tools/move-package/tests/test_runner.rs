@@ -13,7 +13,7 @@ use move_package::{
     resolution::resolution_graph as RG,
     source_package::{
         manifest_parser as MP,
-        parsed_manifest::{CustomDepInfo, PackageDigest},
+        parsed_manifest::{CustomDepInfo, CustomDepPin, PackageDigest},
     },
     BuildConfig, ModelConfig,
 };
@@ -146,13 +146,15 @@ impl PackageHooks for TestHooks {
         &self,
         dep_name: Symbol,
         info: &CustomDepInfo,
-    ) -> anyhow::Result<()> {
+        pin: Option<&CustomDepPin>,
+    ) -> anyhow::Result<CustomDepPin> {
         bail!(
-            "TestHooks resolve dep {} = {} {} {}",
+            "TestHooks resolve dep {} = {} {} {} {:?}",
             dep_name,
             info.node_url,
             info.package_name,
-            info.package_address
+            info.package_address,
+            pin.map(|pin| pin.version)
         )
     }
 }
@@ -0,0 +1,215 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation and shrinking of the arguments of `#[fuzz]` tests.
+//!
+//! A `#[fuzz]` test is run with random arguments for its parameters which are not assigned in its
+//! `#[test]` attribute. When a run fails, its arguments are shrunk one at a time, e.g. integers
+//! towards zero and vectors towards fewer elements, for as long as the test keeps failing. The
+//! result is reported as a minimal counterexample.
+
+use move_core_types::{
+    account_address::AccountAddress,
+    u256::U256,
+    value::{MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The maximal length of generated vectors.
+const MAX_VECTOR_LEN: usize = 16;
+
+/// Generates random values from a seed.
+pub struct ValueGenerator {
+    rng: StdRng,
+}
+
+impl ValueGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Generates a value of type `layout`, or returns `None` if values of the type cannot be
+    /// generated.
+    pub fn generate(&mut self, layout: &MoveTypeLayout) -> Option<MoveValue> {
+        use MoveTypeLayout as L;
+        Some(match layout {
+            L::Bool => MoveValue::Bool(self.rng.gen()),
+            L::U8 | L::U16 | L::U32 | L::U64 | L::U128 | L::U256 => {
+                let max = max_int(layout)?;
+                int_value(layout, self.generate_int(max))?
+            },
+            L::Address => MoveValue::Address(self.generate_address()),
+            L::Signer => MoveValue::Signer(self.generate_address()),
+            L::Vector(elem) => {
+                let len = self.rng.gen_range(0..=MAX_VECTOR_LEN);
+                MoveValue::Vector(
+                    (0..len)
+                        .map(|_| self.generate(elem))
+                        .collect::<Option<_>>()?,
+                )
+            },
+            L::Struct(MoveStructLayout::Runtime(fields)) => MoveValue::Struct(MoveStruct::Runtime(
+                fields
+                    .iter()
+                    .map(|field| self.generate(field))
+                    .collect::<Option<_>>()?,
+            )),
+            L::Struct(_) => return None,
+        })
+    }
+
+    /// Generates an integer up to `max`, favoring the bounds and small values, which are where
+    /// bugs tend to hide.
+    fn generate_int(&mut self, max: U256) -> U256 {
+        match self.rng.gen_range(0..8) {
+            0 => U256::zero(),
+            1 => max,
+            _ => {
+                let bits = 256 - max.leading_zeros();
+                let mask = max >> (self.rng.gen_range(0..bits) as u8);
+                U256::from_le_bytes(&self.rng.gen()) & mask
+            },
+        }
+    }
+
+    fn generate_address(&mut self) -> AccountAddress {
+        let mut bytes: [u8; AccountAddress::LENGTH] = self.rng.gen();
+        // Small addresses, like the ones of the framework, a quarter of the time
+        if self.rng.gen_ratio(1, 4) {
+            let last = bytes[AccountAddress::LENGTH - 1] % 16;
+            bytes = [0; AccountAddress::LENGTH];
+            bytes[AccountAddress::LENGTH - 1] = last;
+        }
+        AccountAddress::new(bytes)
+    }
+}
+
+/// Returns the candidates for a simpler version of `arguments`, simplest first. Each candidate
+/// shrinks one of the arguments for which `shrinkable` holds.
+pub fn shrink_arguments(arguments: &[MoveValue], shrinkable: &[bool]) -> Vec<Vec<MoveValue>> {
+    let mut candidates = vec![];
+    for (idx, argument) in arguments.iter().enumerate() {
+        if !shrinkable[idx] {
+            continue;
+        }
+        for value in shrink(argument) {
+            let mut candidate = arguments.to_vec();
+            candidate[idx] = value;
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Returns the candidates for a simpler version of `value`, simplest first.
+fn shrink(value: &MoveValue) -> Vec<MoveValue> {
+    match value {
+        MoveValue::Bool(true) => vec![MoveValue::Bool(false)],
+        MoveValue::Bool(false) => vec![],
+        MoveValue::Address(addr) if *addr != AccountAddress::ZERO => {
+            vec![MoveValue::Address(AccountAddress::ZERO)]
+        },
+        MoveValue::Signer(addr) if *addr != AccountAddress::ZERO => {
+            vec![MoveValue::Signer(AccountAddress::ZERO)]
+        },
+        MoveValue::Address(_) | MoveValue::Signer(_) => vec![],
+        MoveValue::Vector(elems) => {
+            let mut candidates = vec![];
+            if elems.len() > 1 {
+                candidates.push(MoveValue::Vector(vec![]));
+            }
+            for idx in 0..elems.len() {
+                let mut elems = elems.clone();
+                elems.remove(idx);
+                candidates.push(MoveValue::Vector(elems));
+            }
+            for (idx, elem) in elems.iter().enumerate() {
+                for value in shrink(elem) {
+                    let mut elems = elems.clone();
+                    elems[idx] = value;
+                    candidates.push(MoveValue::Vector(elems));
+                }
+            }
+            candidates
+        },
+        MoveValue::Struct(MoveStruct::Runtime(fields)) => {
+            let mut candidates = vec![];
+            for (idx, field) in fields.iter().enumerate() {
+                for value in shrink(field) {
+                    let mut fields = fields.clone();
+                    fields[idx] = value;
+                    candidates.push(MoveValue::Struct(MoveStruct::Runtime(fields)));
+                }
+            }
+            candidates
+        },
+        MoveValue::Struct(_) => vec![],
+        MoveValue::U8(_)
+        | MoveValue::U16(_)
+        | MoveValue::U32(_)
+        | MoveValue::U64(_)
+        | MoveValue::U128(_)
+        | MoveValue::U256(_) => {
+            let layout = int_layout(value);
+            let n = int(value);
+            // Halve the distance to zero, starting from zero, so that shrinking a failing value
+            // for as long as the test fails ends at the smallest failing one.
+            let mut candidates = vec![];
+            let mut distance = n;
+            while distance != U256::zero() {
+                candidates.extend(int_value(&layout, n - distance));
+                distance = distance >> 1u8;
+            }
+            candidates
+        },
+    }
+}
+
+fn max_int(layout: &MoveTypeLayout) -> Option<U256> {
+    Some(match layout {
+        MoveTypeLayout::U8 => U256::from(u8::MAX),
+        MoveTypeLayout::U16 => U256::from(u16::MAX),
+        MoveTypeLayout::U32 => U256::from(u32::MAX),
+        MoveTypeLayout::U64 => U256::from(u64::MAX),
+        MoveTypeLayout::U128 => U256::from(u128::MAX),
+        MoveTypeLayout::U256 => U256::max_value(),
+        _ => return None,
+    })
+}
+
+fn int_value(layout: &MoveTypeLayout, n: U256) -> Option<MoveValue> {
+    Some(match layout {
+        MoveTypeLayout::U8 => MoveValue::U8(n.unchecked_as_u8()),
+        MoveTypeLayout::U16 => MoveValue::U16(n.unchecked_as_u16()),
+        MoveTypeLayout::U32 => MoveValue::U32(n.unchecked_as_u32()),
+        MoveTypeLayout::U64 => MoveValue::U64(n.unchecked_as_u64()),
+        MoveTypeLayout::U128 => MoveValue::U128(n.unchecked_as_u128()),
+        MoveTypeLayout::U256 => MoveValue::U256(n),
+        _ => return None,
+    })
+}
+
+fn int_layout(value: &MoveValue) -> MoveTypeLayout {
+    match value {
+        MoveValue::U8(_) => MoveTypeLayout::U8,
+        MoveValue::U16(_) => MoveTypeLayout::U16,
+        MoveValue::U32(_) => MoveTypeLayout::U32,
+        MoveValue::U64(_) => MoveTypeLayout::U64,
+        MoveValue::U128(_) => MoveTypeLayout::U128,
+        _ => MoveTypeLayout::U256,
+    }
+}
+
+fn int(value: &MoveValue) -> U256 {
+    match value {
+        MoveValue::U8(n) => U256::from(*n),
+        MoveValue::U16(n) => U256::from(*n),
+        MoveValue::U32(n) => U256::from(*n),
+        MoveValue::U64(n) => U256::from(*n),
+        MoveValue::U128(n) => U256::from(*n),
+        MoveValue::U256(n) => *n,
+        _ => U256::zero(),
+    }
+}
@@ -4,11 +4,15 @@
 
 pub mod cargo_runner;
 pub mod extensions;
+pub mod fuzz;
+pub mod mutation;
 pub mod test_reporter;
 pub mod test_runner;
 
 use crate::test_runner::TestRunner;
 use clap::*;
+use colored::Colorize;
+use move_binary_format::access::ModuleAccess;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
     self,
@@ -18,11 +22,12 @@ use move_compiler::{
     Compiler, Flags, PASS_CFGIR,
 };
 use move_core_types::language_storage::ModuleId;
+use move_ir_types::location::Loc;
 use move_vm_runtime::native_functions::NativeFunctionTable;
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
-    collections::BTreeMap,
-    io::{Result, Write},
+    collections::{BTreeMap, BTreeSet},
+    io::{self, Result, Write},
     marker::Send,
     sync::Mutex,
 };
@@ -115,6 +120,16 @@ pub struct UnitTestingConfig {
     #[clap(short = 'v', long = "verbose")]
     pub report_writeset: bool,
 
+    /// Run the tests against mutants of the modules under test, e.g. with a comparison flipped or
+    /// an assertion removed, and report the mutants for which all tests still pass.
+    #[clap(long = "mutate")]
+    pub mutate: bool,
+
+    /// The seed from which the arguments of #[fuzz] tests are generated. A random one by default,
+    /// which is reported with the counterexample of a failing test.
+    #[clap(long = "seed")]
+    pub seed: Option<u64>,
+
     /// Use the EVM-based execution backend.
     /// Does not work with --stackless.
     #[cfg(feature = "evm-backend")]
@@ -148,6 +163,8 @@ impl UnitTestingConfig {
             list: false,
             named_address_values: vec![],
             report_writeset: false,
+            mutate: false,
+            seed: None,
 
             #[cfg(feature = "evm-backend")]
             evm: false,
@@ -240,6 +257,51 @@ impl UnitTestingConfig {
         }
 
         writeln!(shared_writer.lock().unwrap(), "Running Move unit tests")?;
+        let mutation_plan = self.mutate.then(|| {
+            (
+                test_plan.clone(),
+                native_function_table.clone(),
+                cost_table.clone(),
+            )
+        });
+        let test_runner = self.test_runner(test_plan, native_function_table, cost_table);
+        let test_results = test_runner.run(&shared_writer).unwrap();
+        if self.report_statistics {
+            test_results.report_statistics(&shared_writer)?;
+        }
+
+        if self.report_writeset {
+            test_results.report_goldens(&shared_writer)?;
+        }
+
+        let ok = test_results.summarize(&shared_writer)?;
+
+        if let Some((test_plan, native_function_table, cost_table)) = mutation_plan {
+            if ok {
+                self.run_mutation_tests(
+                    test_plan,
+                    native_function_table,
+                    cost_table,
+                    &shared_writer,
+                )?;
+            } else {
+                writeln!(
+                    shared_writer.lock().unwrap(),
+                    "Skipping mutation testing since not all tests pass"
+                )?;
+            }
+        }
+
+        let writer = shared_writer.into_inner().unwrap();
+        Ok((writer, ok))
+    }
+
+    fn test_runner(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        cost_table: Option<CostTable>,
+    ) -> TestRunner {
         let mut test_runner = TestRunner::new(
             self.gas_limit.unwrap_or(DEFAULT_EXECUTION_BOUND),
             self.num_threads,
@@ -252,6 +314,7 @@ impl UnitTestingConfig {
             cost_table,
             verify_and_create_named_address_mapping(self.named_address_values.clone()).unwrap(),
             self.report_writeset,
+            self.seed.unwrap_or_else(rand::random),
             #[cfg(feature = "evm-backend")]
             self.evm,
         )
@@ -260,19 +323,119 @@ impl UnitTestingConfig {
         if let Some(filter_str) = &self.filter {
             test_runner.filter(filter_str)
         }
+        test_runner
+    }
 
-        let test_results = test_runner.run(&shared_writer).unwrap();
-        if self.report_statistics {
-            test_results.report_statistics(&shared_writer)?;
+    /// Runs the tests of `test_plan` against each mutant of the modules under test, and reports
+    /// the mutants which survive, that is, for which all tests pass.
+    fn run_mutation_tests<W: Write + Send>(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        cost_table: Option<CostTable>,
+        writer: &Mutex<W>,
+    ) -> Result<()> {
+        let mut mutants = vec![];
+        for module_id in self.modules_under_test(&test_plan) {
+            let module = &test_plan.module_info[&module_id].module;
+            let tests = test_plan.module_tests.get(&module_id);
+            // Test functions are not mutated.
+            let is_test = |function| {
+                let handle = module.function_handle_at(module.function_def_at(function).function);
+                let name = module.identifier_at(handle.name).as_str();
+                tests.map_or(false, |plan| plan.tests.contains_key(name))
+            };
+            mutants.extend(
+                mutation::mutants(module, |function| !is_test(function))
+                    .into_iter()
+                    .map(|mutant| (module_id.clone(), mutant)),
+            );
         }
+        writeln!(
+            writer.lock().unwrap(),
+            "Running Move unit tests against {} mutants",
+            mutants.len()
+        )?;
+
+        let num_mutants = mutants.len();
+        let mut num_survivors = 0;
+        for (module_id, mutant) in mutants {
+            let name = mutant.function_name().to_string();
+            let info = &test_plan.module_info[&module_id];
+            let loc = info
+                .source_map
+                .get_code_location(mutant.function, mutant.offset)
+                .ok();
+
+            let mut mutant_plan = test_plan.clone();
+            mutant_plan.module_info.get_mut(&module_id).unwrap().module = mutant.module;
+            let results = self
+                .test_runner(
+                    mutant_plan,
+                    native_function_table.clone(),
+                    cost_table.clone(),
+                )
+                .run(&Mutex::new(io::sink()))
+                .unwrap();
+            if !results.summarize(&Mutex::new(io::sink()))? {
+                continue;
+            }
 
-        if self.report_writeset {
-            test_results.report_goldens(&shared_writer)?;
+            num_survivors += 1;
+            writeln!(
+                writer.lock().unwrap(),
+                "[ {} ] {}::{}: {}",
+                "SURVIVED".bold().bright_red(),
+                format_module_id(&module_id),
+                name,
+                mutant.description
+            )?;
+            if let Some(location) = loc.and_then(|loc| format_loc(&test_plan, loc)) {
+                writeln!(writer.lock().unwrap(), "   ┌─ {}", location)?;
+            }
         }
+        writeln!(
+            writer.lock().unwrap(),
+            "Mutation result: {} of {} mutants killed",
+            num_mutants - num_survivors,
+            num_mutants
+        )
+    }
 
-        let ok = test_results.summarize(&shared_writer)?;
-
-        let writer = shared_writer.into_inner().unwrap();
-        Ok((writer, ok))
+    /// Returns the modules the tests of `test_plan` are for: the modules of the packages with
+    /// tests or, when compiling files rather than packages, the modules of the source files.
+    fn modules_under_test(&self, test_plan: &TestPlan) -> Vec<ModuleId> {
+        let packages: BTreeSet<_> = test_plan
+            .module_tests
+            .keys()
+            .filter_map(|module_id| test_plan.module_info.get(module_id))
+            .map(|info| info.package_name)
+            .collect();
+        test_plan
+            .module_info
+            .iter()
+            .filter(|(_, info)| {
+                packages.contains(&info.package_name)
+                    && (info.package_name.is_some()
+                        || test_plan
+                            .files
+                            .get(&info.source_map.definition_location.file_hash())
+                            .map_or(false, |(file, _)| {
+                                self.source_files
+                                    .iter()
+                                    .any(|source| source == file.as_str())
+                            }))
+            })
+            .map(|(module_id, _)| module_id.clone())
+            .collect()
     }
 }
+
+/// Formats `loc` as `file:line:column`.
+fn format_loc(test_plan: &TestPlan, loc: Loc) -> Option<String> {
+    let (file, source) = test_plan.files.get(&loc.file_hash())?;
+    let before = source.get(..loc.start() as usize)?;
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    Some(format!("{}:{}:{}", file, line, column))
+}
@@ -0,0 +1,250 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mutation testing of Move unit tests.
+//!
+//! A mutant is a copy of a compiled module in which one instruction of a function is changed:
+//! - a comparison is replaced, e.g. `<` with `<=` or `>=`, and `==` with `!=`,
+//! - an arithmetic operator is swapped, e.g. `+` with `-` and `*` with `/`,
+//! - an integer constant is off by one,
+//! - a conditional abort, like the one of an `assert!`, is removed.
+//!
+//! The unit tests are run against each mutant. A mutant survives if all the tests still pass,
+//! which points at behavior of the function the tests do not check.
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset, CompiledModule, FunctionDefinitionIndex, TableIndex},
+};
+use move_bytecode_verifier::verify_module;
+use move_core_types::u256::U256;
+use std::collections::BTreeSet;
+
+/// A module with one mutated function.
+pub struct Mutant {
+    pub function: FunctionDefinitionIndex,
+    /// The offset of the mutated instruction.
+    pub offset: CodeOffset,
+    /// What the mutation changed, e.g. "replaced `<` with `<=`".
+    pub description: String,
+    pub module: CompiledModule,
+}
+
+impl Mutant {
+    /// Returns the name of the mutated function.
+    pub fn function_name(&self) -> &str {
+        let fdef = self.module.function_def_at(self.function);
+        self.module
+            .identifier_at(self.module.function_handle_at(fdef.function).name)
+            .as_str()
+    }
+}
+
+/// A mutation of the code of a function, as the instructions to replace.
+struct Mutation {
+    offset: usize,
+    edits: Vec<(usize, Bytecode)>,
+    description: String,
+}
+
+/// Returns the mutants of the functions of `module` for which `include` holds. Mutants the
+/// bytecode verifier rejects are skipped.
+pub fn mutants(
+    module: &CompiledModule,
+    include: impl Fn(FunctionDefinitionIndex) -> bool,
+) -> Vec<Mutant> {
+    let mut mutants = vec![];
+    for (idx, fdef) in module.function_defs.iter().enumerate() {
+        let function = FunctionDefinitionIndex(idx as TableIndex);
+        let code = match &fdef.code {
+            Some(code) if include(function) => &code.code,
+            _ => continue,
+        };
+        for mutation in mutations(code) {
+            let mut mutated = module.clone();
+            let code = &mut mutated.function_defs[idx].code.as_mut().unwrap().code;
+            for (offset, instr) in mutation.edits {
+                code[offset] = instr;
+            }
+            if verify_module(&mutated).is_ok() {
+                mutants.push(Mutant {
+                    function,
+                    offset: mutation.offset as CodeOffset,
+                    description: mutation.description,
+                    module: mutated,
+                });
+            }
+        }
+    }
+    mutants
+}
+
+fn mutations(code: &[Bytecode]) -> Vec<Mutation> {
+    use Bytecode::*;
+
+    let targets: BTreeSet<_> = code
+        .iter()
+        .filter_map(|instr| match instr {
+            Branch(target) | BrTrue(target) | BrFalse(target) => Some(*target as usize),
+            _ => None,
+        })
+        .collect();
+
+    let mut mutations = vec![];
+    for (offset, instr) in code.iter().enumerate() {
+        let replacements: Vec<Bytecode> = match instr {
+            Lt => vec![Le, Ge],
+            Le => vec![Lt, Gt],
+            Gt => vec![Ge, Le],
+            Ge => vec![Gt, Lt],
+            Eq => vec![Neq],
+            Neq => vec![Eq],
+            Add => vec![Sub],
+            Sub => vec![Add],
+            Mul => vec![Div],
+            Div => vec![Mul],
+            Mod => vec![Div],
+            // Constants directly followed by an abort are abort codes.
+            LdU8(_) | LdU16(_) | LdU32(_) | LdU64(_) | LdU128(_) | LdU256(_)
+                if !matches!(code.get(offset + 1), Some(Abort)) =>
+            {
+                off_by_one(instr)
+            },
+            BrTrue(target) | BrFalse(target) => {
+                if let Some(edits) = remove_abort(code, offset, *target as usize, &targets) {
+                    mutations.push(Mutation {
+                        offset,
+                        edits,
+                        description: "removed assertion".to_string(),
+                    });
+                }
+                continue;
+            },
+            _ => continue,
+        };
+        for replacement in replacements {
+            let description = match (symbol(instr), symbol(&replacement)) {
+                (Some(from), Some(to)) => format!("replaced `{}` with `{}`", from, to),
+                _ => format!(
+                    "replaced `{}` with `{}`",
+                    constant(instr),
+                    constant(&replacement)
+                ),
+            };
+            mutations.push(Mutation {
+                offset,
+                edits: vec![(offset, replacement)],
+                description,
+            });
+        }
+    }
+    mutations
+}
+
+/// Returns the loads of the constant loaded by `instr` plus and minus one, when they fit its type.
+fn off_by_one(instr: &Bytecode) -> Vec<Bytecode> {
+    use Bytecode::*;
+    match instr {
+        LdU8(c) => [c.checked_add(1), c.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(LdU8)
+            .collect(),
+        LdU16(c) => [c.checked_add(1), c.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(LdU16)
+            .collect(),
+        LdU32(c) => [c.checked_add(1), c.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(LdU32)
+            .collect(),
+        LdU64(c) => [c.checked_add(1), c.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(LdU64)
+            .collect(),
+        LdU128(c) => [c.checked_add(1), c.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .map(LdU128)
+            .collect(),
+        LdU256(c) => [c.checked_add(U256::one()), c.checked_sub(U256::one())]
+            .into_iter()
+            .flatten()
+            .map(LdU256)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Returns the edits which make the conditional branch at `offset` to `target` always continue on
+/// the path which does not abort, if the other path aborts right away, as for an `assert!`.
+fn remove_abort(
+    code: &[Bytecode],
+    offset: usize,
+    target: usize,
+    targets: &BTreeSet<usize>,
+) -> Option<Vec<(usize, Bytecode)>> {
+    let fall_through = offset + 1;
+    match (aborts(code, target), aborts(code, fall_through)) {
+        // Pop the condition and fall through.
+        (Some(_), None) => Some(vec![(offset, Bytecode::Pop)]),
+        // Pop the condition and jump over the abort to the target, unless another branch leads
+        // into the code which aborts.
+        (None, Some(abort)) if (fall_through..=abort).all(|at| !targets.contains(&at)) => {
+            let mut edits = vec![(offset, Bytecode::Pop)];
+            edits.extend(
+                (fall_through..=abort).map(|at| (at, Bytecode::Branch(target as CodeOffset))),
+            );
+            Some(edits)
+        },
+        _ => None,
+    }
+}
+
+/// Returns the offset of the `Abort` ending the straight-line code starting at `start`, if any.
+fn aborts(code: &[Bytecode], start: usize) -> Option<usize> {
+    for (offset, instr) in code.iter().enumerate().skip(start) {
+        match instr {
+            Bytecode::Abort => return Some(offset),
+            Bytecode::Branch(_) | Bytecode::BrTrue(_) | Bytecode::BrFalse(_) | Bytecode::Ret => {
+                return None
+            },
+            _ => (),
+        }
+    }
+    None
+}
+
+fn symbol(instr: &Bytecode) -> Option<&'static str> {
+    use Bytecode::*;
+    Some(match instr {
+        Lt => "<",
+        Le => "<=",
+        Gt => ">",
+        Ge => ">=",
+        Eq => "==",
+        Neq => "!=",
+        Add => "+",
+        Sub => "-",
+        Mul => "*",
+        Div => "/",
+        Mod => "%",
+        _ => return None,
+    })
+}
+
+fn constant(instr: &Bytecode) -> String {
+    use Bytecode::*;
+    match instr {
+        LdU8(c) => c.to_string(),
+        LdU16(c) => c.to_string(),
+        LdU32(c) => c.to_string(),
+        LdU64(c) => c.to_string(),
+        LdU128(c) => c.to_string(),
+        LdU256(c) => c.to_string(),
+        _ => format!("{:?}", instr),
+    }
+}
@@ -46,6 +46,8 @@ pub enum FailureReason {
     },
     // Property checking failed
     Property(String),
+    // Arguments of a #[fuzz] test could not be generated
+    FuzzError(String),
 
     // Failed to compile Move code into EVM bytecode.
     #[cfg(feature = "evm-backend")]
@@ -58,6 +60,8 @@ pub struct TestFailure {
     pub vm_error: Option<VMError>,
     pub failure_reason: FailureReason,
     pub storage_state: Option<String>,
+    /// The minimal arguments found to fail a #[fuzz] test.
+    pub counterexample: Option<String>,
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
@@ -137,6 +141,10 @@ impl FailureReason {
         FailureReason::Property(details)
     }
 
+    pub fn fuzz_error(details: String) -> Self {
+        FailureReason::FuzzError(details)
+    }
+
     #[cfg(feature = "evm-backend")]
     pub fn move_to_evm_error(diagnostics: String) -> Self {
         FailureReason::MoveToEVMError(diagnostics)
@@ -155,9 +163,15 @@ impl TestFailure {
             vm_error,
             failure_reason,
             storage_state,
+            counterexample: None,
         }
     }
 
+    pub fn with_counterexample(mut self, counterexample: Option<String>) -> Self {
+        self.counterexample = counterexample;
+        self
+    }
+
     pub fn render_error(&self, test_plan: &TestPlan) -> String {
         let error_string = match &self.failure_reason {
             FailureReason::NoError(message) => message.to_string(),
@@ -224,6 +238,7 @@ impl TestFailure {
                 )
             },
             FailureReason::Property(message) => message.clone(),
+            FailureReason::FuzzError(message) => message.clone(),
 
             #[cfg(feature = "evm-backend")]
             FailureReason::MoveToEVMError(diagnostics) => {
@@ -234,6 +249,14 @@ impl TestFailure {
             },
         };
 
+        let error_string = match &self.counterexample {
+            None => error_string,
+            Some(counterexample) => format!(
+                "{}\n────── Counterexample ──────\n{}",
+                error_string, counterexample
+            ),
+        };
+
         match &self.storage_state {
             None => error_string,
             Some(storage_state) => {
@@ -4,11 +4,12 @@
 
 use crate::{
     extensions, format_module_id,
+    fuzz::{self, ValueGenerator},
     test_reporter::{
         FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
     },
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use codespan_reporting::{
     diagnostic::Severity,
     term::termcolor::{ColorChoice, StandardStream},
@@ -18,13 +19,14 @@ use move_binary_format::{errors::VMResult, file_format::CompiledModule};
 use move_bytecode_utils::Modules;
 use move_compiler::{
     shared::{Flags, NumericalAddress, PackagePaths},
-    unit_test::{ExpectedFailure, ModuleTestPlan, TestCase, TestPlan},
+    unit_test::{ExpectedFailure, FuzzConfig, ModuleTestPlan, TestCase, TestPlan},
 };
 use move_core_types::{
     account_address::AccountAddress,
     effects::{ChangeSet, Op},
     identifier::IdentStr,
-    value::serialize_values,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveTypeLayout, MoveValue},
     vm_status::StatusCode,
 };
 use move_model::{
@@ -46,7 +48,13 @@ use move_vm_test_utils::{
     InMemoryStorage,
 };
 use rayon::prelude::*;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    marker::Send,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 #[cfg(feature = "evm-backend")]
 use {
     evm::{backend::MemoryVicinity, ExitReason},
@@ -54,7 +62,6 @@ use {
     move_to_yul,
     primitive_types::{H160, U256},
     std::convert::TryInto,
-    std::time::Duration,
 };
 
 /// Test state common to all tests
@@ -70,6 +77,7 @@ pub struct SharedTestingConfig {
     check_stackless_vm: bool,
     verbose: bool,
     record_writeset: bool,
+    fuzz_seed: u64,
 
     #[cfg(feature = "evm-backend")]
     evm: bool,
@@ -81,6 +89,9 @@ pub struct TestRunner {
     tests: TestPlan,
 }
 
+/// The maximal number of runs spent on shrinking the arguments of a failing #[fuzz] test.
+const MAX_SHRINK_RUNS: usize = 1000;
+
 /// A gas schedule where every instruction has a cost of "1". This is used to bound execution of a
 /// test to a certain number of ticks.
 fn unit_cost_table() -> CostTable {
@@ -153,6 +164,7 @@ impl TestRunner {
         cost_table: Option<CostTable>,
         named_address_values: BTreeMap<String, NumericalAddress>,
         record_writeset: bool,
+        fuzz_seed: u64,
         #[cfg(feature = "evm-backend")] evm: bool,
     ) -> Result<Self> {
         let source_files = tests
@@ -186,6 +198,7 @@ impl TestRunner {
                 verbose,
                 named_address_values,
                 record_writeset,
+                fuzz_seed,
                 #[cfg(feature = "evm-backend")]
                 evm,
             },
@@ -276,7 +289,7 @@ impl SharedTestingConfig {
         &self,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (
         VMResult<ChangeSet>,
         VMResult<NativeContextExtensions>,
@@ -295,7 +308,7 @@ impl SharedTestingConfig {
             &test_plan.module_id,
             IdentStr::new(function_name).unwrap(),
             vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
+            serialize_values(arguments.iter()),
             &mut gas_meter,
         );
         let mut return_result = serialized_return_values_result.map(|res| {
@@ -325,6 +338,114 @@ impl SharedTestingConfig {
         }
     }
 
+    /// Runs the #[fuzz] test `function_name` with generated arguments until a run fails. Returns
+    /// the arguments of the last run and whether it failed, in which case they are shrunk to a
+    /// minimal counterexample.
+    fn fuzz(
+        &self,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        fuzz: &FuzzConfig,
+    ) -> Result<(Vec<MoveValue>, bool)> {
+        let layouts = self
+            .parameter_layouts(&test_plan.module_id, function_name)
+            .map_err(|err| anyhow!("Unable to generate the arguments of the test: {}", err))?;
+        let mut generator = ValueGenerator::new(self.fuzz_seed);
+        let mut arguments = vec![];
+        for _ in 0..fuzz.runs.max(1) {
+            arguments = fuzz
+                .parameters
+                .iter()
+                .zip(&layouts)
+                .map(|((name, assigned), layout)| {
+                    assigned
+                        .clone()
+                        .or_else(|| generator.generate(layout))
+                        .ok_or_else(|| {
+                            anyhow!("Unable to generate values for the parameter `{}`", name)
+                        })
+                })
+                .collect::<Result<_>>()?;
+            if self.fails(test_plan, function_name, test_info, &arguments) {
+                let shrinkable: Vec<_> = fuzz
+                    .parameters
+                    .iter()
+                    .map(|(_, assigned)| assigned.is_none())
+                    .collect();
+                let mut budget = MAX_SHRINK_RUNS;
+                'shrink: loop {
+                    for candidate in fuzz::shrink_arguments(&arguments, &shrinkable) {
+                        if budget == 0 {
+                            break 'shrink;
+                        }
+                        budget -= 1;
+                        if self.fails(test_plan, function_name, test_info, &candidate) {
+                            arguments = candidate;
+                            continue 'shrink;
+                        }
+                    }
+                    break;
+                }
+                return Ok((arguments, true));
+            }
+        }
+        Ok((arguments, false))
+    }
+
+    /// Returns whether running the test `function_name` with `arguments` fails.
+    fn fails(
+        &self,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        arguments: &[MoveValue],
+    ) -> bool {
+        let (_, _, exec_result, _) = self.execute_via_move_vm(test_plan, function_name, arguments);
+        match (exec_result, test_info.expected_failure.as_ref()) {
+            (Ok(_), expected_failure) => expected_failure.is_some(),
+            (Err(_), None) => true,
+            (Err(_), Some(ExpectedFailure::Expected)) => false,
+            (Err(err), Some(ExpectedFailure::ExpectedWithError(expected_err))) => {
+                expected_err
+                    != &MoveError(err.major_status(), err.sub_status(), err.location().clone())
+            },
+            (Err(err), Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))) => {
+                err.major_status() != StatusCode::ABORTED || err.sub_status() != Some(*code)
+            },
+        }
+    }
+
+    fn parameter_layouts(
+        &self,
+        module_id: &ModuleId,
+        function_name: &str,
+    ) -> VMResult<Vec<MoveTypeLayout>> {
+        let move_vm = MoveVM::new(self.native_function_table.clone()).unwrap();
+        let session = move_vm.new_session(&self.starting_storage_state);
+        let function =
+            session.load_function(module_id, IdentStr::new(function_name).unwrap(), &[])?;
+        function
+            .parameters
+            .iter()
+            .map(|ty| session.get_type_layout(&session.get_type_tag(ty)?))
+            .collect()
+    }
+
+    fn format_counterexample(&self, fuzz: &FuzzConfig, arguments: &[MoveValue]) -> String {
+        let mut lines: Vec<_> = fuzz
+            .parameters
+            .iter()
+            .zip(arguments)
+            .map(|((name, _), value)| format!("{} = {}", name, value))
+            .collect();
+        lines.push(format!(
+            "(arguments generated with seed {})",
+            self.fuzz_seed
+        ));
+        lines.join("\n")
+    }
+
     fn execute_via_stackless_vm(
         &self,
         env: &GlobalEnv,
@@ -416,8 +537,45 @@ impl SharedTestingConfig {
         let mut stats = TestStatistics::new();
 
         for (function_name, test_info) in &test_plan.tests {
+            // A #[fuzz] test is checked as any other test with the arguments of its last run, which
+            // are a minimal counterexample if it failed
+            let fuzzed_test_info;
+            let mut counterexample = None;
+            let test_info = match &test_info.fuzz {
+                None => test_info,
+                Some(fuzz) => match self.fuzz(test_plan, function_name, test_info, fuzz) {
+                    Ok((arguments, failed)) => {
+                        if failed {
+                            counterexample = Some(self.format_counterexample(fuzz, &arguments));
+                        }
+                        fuzzed_test_info = TestCase {
+                            arguments,
+                            ..test_info.clone()
+                        };
+                        &fuzzed_test_info
+                    },
+                    Err(err) => {
+                        output.fail(function_name);
+                        stats.test_failure(
+                            TestFailure::new(
+                                FailureReason::fuzz_error(err.to_string()),
+                                TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0),
+                                None,
+                                None,
+                            ),
+                            test_plan,
+                        );
+                        continue;
+                    },
+                },
+            };
+            let failure = |failure_reason, test_run_info, vm_error, storage_state| {
+                TestFailure::new(failure_reason, test_run_info, vm_error, storage_state)
+                    .with_counterexample(counterexample.clone())
+            };
+
             let (cs_result, ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info);
+                self.execute_via_move_vm(test_plan, function_name, &test_info.arguments);
 
             if self.record_writeset {
                 stats.test_output(
@@ -443,7 +601,7 @@ impl SharedTestingConfig {
                 {
                     output.fail(function_name);
                     stats.test_failure(
-                        TestFailure::new(
+                        failure(
                             FailureReason::mismatch(
                                 move_vm_result,
                                 move_vm_change_set,
@@ -461,7 +619,7 @@ impl SharedTestingConfig {
                 if let Some(prop_failure) = prop_check_result {
                     output.fail(function_name);
                     stats.test_failure(
-                        TestFailure::new(
+                        failure(
                             FailureReason::property(prop_failure),
                             test_run_info,
                             None,
@@ -517,7 +675,7 @@ impl SharedTestingConfig {
                         Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
                             output.fail(function_name);
                             stats.test_failure(
-                                TestFailure::new(
+                                failure(
                                     FailureReason::wrong_error(expected_err.clone(), actual_err),
                                     test_run_info,
                                     Some(err),
@@ -529,7 +687,7 @@ impl SharedTestingConfig {
                         Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
                             output.fail(function_name);
                             stats.test_failure(
-                                TestFailure::new(
+                                failure(
                                     FailureReason::wrong_abort_deprecated(
                                         *expected_code,
                                         actual_err,
@@ -545,7 +703,7 @@ impl SharedTestingConfig {
                             // Ran out of ticks, report a test timeout and log a test failure
                             output.timeout(function_name);
                             stats.test_failure(
-                                TestFailure::new(
+                                failure(
                                     FailureReason::timeout(),
                                     test_run_info,
                                     Some(err),
@@ -557,7 +715,7 @@ impl SharedTestingConfig {
                         None => {
                             output.fail(function_name);
                             stats.test_failure(
-                                TestFailure::new(
+                                failure(
                                     FailureReason::unexpected_error(actual_err),
                                     test_run_info,
                                     Some(err),
@@ -573,7 +731,7 @@ impl SharedTestingConfig {
                     if test_info.expected_failure.is_some() {
                         output.fail(function_name);
                         stats.test_failure(
-                            TestFailure::new(
+                            failure(
                                 FailureReason::no_error(),
                                 test_run_info,
                                 None,
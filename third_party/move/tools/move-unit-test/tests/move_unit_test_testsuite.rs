@@ -16,6 +16,7 @@ use std::{
 // tests flaky.
 const TEST_MODIFIER_STRS: &[&str] = &[
     "storage",
+    "mutate",
     #[cfg(feature = "evm-backend")]
     "evm",
 ];
@@ -24,6 +25,7 @@ pub fn modify(mut base_config: UnitTestingConfig, modifier_str: &str) -> Option<
     // Add future test modifiers here
     match modifier_str {
         "storage" => base_config.report_storage_on_error = true,
+        "mutate" => base_config.mutate = true,
         #[cfg(feature = "evm-backend")]
         "evm" => base_config.evm = true,
         _ => return None,
@@ -90,6 +92,8 @@ fn run_test_impl(path: &Path) -> anyhow::Result<()> {
             .collect(),
         report_writeset: true,
         report_stacktrace_on_abort: true,
+        // Generate the same arguments for #[fuzz] tests on every run
+        seed: Some(0),
 
         ..UnitTestingConfig::default_with_bound(None)
     };
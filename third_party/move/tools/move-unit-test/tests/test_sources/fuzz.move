@@ -0,0 +1,35 @@
+address 0x1 {
+module M {
+    use std::vector;
+
+    struct Point has drop { x: u8, y: u8 }
+
+    #[test]
+    #[fuzz]
+    fun below_hundred(x: u64) {
+        assert!(x < 100, 0)
+    }
+
+    #[test(a = @0x42)]
+    #[fuzz(runs = 10)]
+    fun fixed_signer(a: signer, p: Point) {
+        let Point { x, y } = p;
+        assert!(std::signer::address_of(&a) == @0x42, 0);
+        assert!((x as u16) + (y as u16) <= 510, 1)
+    }
+
+    #[test]
+    #[fuzz]
+    fun reverse_keeps_length(v: vector<u64>) {
+        let len = vector::length(&v);
+        vector::reverse(&mut v);
+        assert!(vector::length(&v) == len, 0)
+    }
+
+    #[test]
+    #[fuzz]
+    fun short_vector(v: vector<u8>) {
+        assert!(vector::length(&v) < 3, 0)
+    }
+}
+}
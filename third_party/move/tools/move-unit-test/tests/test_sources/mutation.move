@@ -0,0 +1,22 @@
+address 0x1 {
+module M {
+    public fun max(a: u64, b: u64): u64 {
+        if (a > b) a else b
+    }
+
+    public fun withdraw(balance: u64, amount: u64): u64 {
+        assert!(amount <= balance, 1);
+        balance - amount
+    }
+
+    #[test]
+    fun test_max() {
+        assert!(max(1, 2) == 2, 0);
+    }
+
+    #[test]
+    fun test_withdraw() {
+        assert!(withdraw(3, 2) == 1, 0);
+    }
+}
+}
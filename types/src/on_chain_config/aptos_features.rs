@@ -22,6 +22,8 @@ pub enum FeatureFlag {
     CRYPTOGRAPHY_ALGEBRA_NATIVES = 12,
     BLS12_381_STRUCTURES = 13,
     ED25519_PUBKEY_VALIDATE_RETURN_FALSE_WRONG_LENGTH = 14,
+    SECP256K1_ECDSA_AUTHENTICATOR = 15,
+    WEBAUTHN_AUTHENTICATOR = 16,
 }
 
 /// Representation of features on chain as a bitset.
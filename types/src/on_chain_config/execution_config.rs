@@ -64,6 +64,7 @@ impl Default for ExecutionConfigV1 {
 pub enum TransactionShufflerType {
     NoShuffling,
     SenderAwareV1(u32),
+    GasAndDependencyAwareV1(u32),
 }
 
 #[cfg(test)]
@@ -102,6 +103,20 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_gas_and_dependency_aware_config_serialization() {
+        let config = OnChainExecutionConfig::V1(ExecutionConfigV1 {
+            transaction_shuffler_type: TransactionShufflerType::GasAndDependencyAwareV1(32),
+        });
+
+        let s = bcs::to_bytes(&config).unwrap();
+        let result = bcs::from_bytes::<OnChainExecutionConfig>(&s).unwrap();
+        assert_eq!(
+            result.transaction_shuffler_type(),
+            TransactionShufflerType::GasAndDependencyAwareV1(32)
+        );
+    }
+
     #[test]
     fn test_config_onchain_payload() {
         let execution_config = OnChainExecutionConfig::V1(ExecutionConfigV1 {
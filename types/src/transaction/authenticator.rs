@@ -4,13 +4,17 @@
 
 use crate::{
     account_address::AccountAddress,
-    transaction::{RawTransaction, RawTransactionWithData},
+    transaction::{
+        webauthn::PartialAuthenticatorAssertionResponse, RawTransaction, RawTransactionWithData,
+    },
 };
 use anyhow::{ensure, Error, Result};
 use aptos_crypto::{
     ed25519::{Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
     multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    secp256k1_ecdsa::{Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature},
+    secp256r1_ecdsa::Secp256r1EcdsaPublicKey,
     traits::Signature,
     CryptoMaterialError, HashValue, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
@@ -58,6 +62,16 @@ pub enum TransactionAuthenticator {
         secondary_signer_addresses: Vec<AccountAddress>,
         secondary_signers: Vec<AccountAuthenticator>,
     },
+    /// Single secp256k1 ECDSA signature
+    Secp256k1Ecdsa {
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    },
+    /// Single WebAuthn assertion of a passkey, with a secp256r1 ECDSA signature
+    WebAuthn {
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    },
 }
 
 impl TransactionAuthenticator {
@@ -93,6 +107,28 @@ impl TransactionAuthenticator {
         }
     }
 
+    /// Create a single-signature secp256k1 ECDSA authenticator
+    pub fn secp256k1_ecdsa(
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> Self {
+        Self::Secp256k1Ecdsa {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a single-signature WebAuthn authenticator
+    pub fn webauthn(
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    ) -> Self {
+        Self::WebAuthn {
+            public_key,
+            signature,
+        }
+    }
+
     /// Return Ok if all AccountAuthenticator's public keys match their signatures, Err otherwise
     pub fn verify(&self, raw_txn: &RawTransaction) -> Result<()> {
         let num_sigs: usize = self.sender().number_of_signatures()
@@ -128,6 +164,14 @@ impl TransactionAuthenticator {
                 }
                 Ok(())
             },
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => signature.verify(raw_txn, public_key),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => signature.verify(raw_txn, public_key),
         }
     }
 
@@ -145,6 +189,14 @@ impl TransactionAuthenticator {
                 signature,
             } => AccountAuthenticator::multi_ed25519(public_key.clone(), signature.clone()),
             Self::MultiAgent { sender, .. } => sender.clone(),
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => AccountAuthenticator::secp256k1_ecdsa(public_key.clone(), signature.clone()),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => AccountAuthenticator::webauthn(public_key.clone(), signature.clone()),
         }
     }
 
@@ -154,7 +206,9 @@ impl TransactionAuthenticator {
             | Self::MultiEd25519 {
                 public_key: _,
                 signature: _,
-            } => vec![],
+            }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. } => vec![],
             Self::MultiAgent {
                 sender: _,
                 secondary_signer_addresses,
@@ -169,7 +223,9 @@ impl TransactionAuthenticator {
             | Self::MultiEd25519 {
                 public_key: _,
                 signature: _,
-            } => vec![],
+            }
+            | Self::Secp256k1Ecdsa { .. }
+            | Self::WebAuthn { .. } => vec![],
             Self::MultiAgent {
                 sender: _,
                 secondary_signer_addresses: _,
@@ -225,6 +281,26 @@ impl fmt::Display for TransactionAuthenticator {
                     sender, sec_addrs, sec_signers,
                 )
             },
+            Self::Secp256k1Ecdsa {
+                public_key: _,
+                signature: _,
+            } => {
+                write!(
+                    f,
+                    "TransactionAuthenticator[scheme: Secp256k1Ecdsa, sender: {}]",
+                    self.sender()
+                )
+            },
+            Self::WebAuthn {
+                public_key: _,
+                signature: _,
+            } => {
+                write!(
+                    f,
+                    "TransactionAuthenticator[scheme: WebAuthn, sender: {}]",
+                    self.sender()
+                )
+            },
         }
     }
 }
@@ -242,6 +318,8 @@ impl fmt::Display for TransactionAuthenticator {
 pub enum Scheme {
     Ed25519 = 0,
     MultiEd25519 = 1,
+    Secp256k1Ecdsa = 2,
+    WebAuthn = 3,
     // ... add more schemes here
     /// Scheme identifier used to derive addresses (not the authentication key) of objects and
     /// resources accounts. This application serves to domain separate hashes. Without such
@@ -257,6 +335,8 @@ impl fmt::Display for Scheme {
         let display = match self {
             Scheme::Ed25519 => "Ed25519",
             Scheme::MultiEd25519 => "MultiEd25519",
+            Scheme::Secp256k1Ecdsa => "Secp256k1Ecdsa",
+            Scheme::WebAuthn => "WebAuthn",
             Scheme::DeriveObjectAddressFromGuid => "DeriveObjectAddressFromGuid",
             Scheme::DeriveObjectAddressFromSeed => "DeriveObjectAddressFromSeed",
             Scheme::DeriveResourceAccountAddress => "DeriveResourceAccountAddress",
@@ -277,6 +357,16 @@ pub enum AccountAuthenticator {
         public_key: MultiEd25519PublicKey,
         signature: MultiEd25519Signature,
     },
+    /// Single secp256k1 ECDSA signature
+    Secp256k1Ecdsa {
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    },
+    /// Single WebAuthn assertion of a passkey, with a secp256r1 ECDSA signature
+    WebAuthn {
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    },
     // ... add more schemes here
 }
 
@@ -286,6 +376,8 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { .. } => Scheme::Ed25519,
             Self::MultiEd25519 { .. } => Scheme::MultiEd25519,
+            Self::Secp256k1Ecdsa { .. } => Scheme::Secp256k1Ecdsa,
+            Self::WebAuthn { .. } => Scheme::WebAuthn,
         }
     }
 
@@ -308,6 +400,28 @@ impl AccountAuthenticator {
         }
     }
 
+    /// Create a single-signature secp256k1 ECDSA authenticator
+    pub fn secp256k1_ecdsa(
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> Self {
+        Self::Secp256k1Ecdsa {
+            public_key,
+            signature,
+        }
+    }
+
+    /// Create a single-signature WebAuthn authenticator
+    pub fn webauthn(
+        public_key: Secp256r1EcdsaPublicKey,
+        signature: PartialAuthenticatorAssertionResponse,
+    ) -> Self {
+        Self::WebAuthn {
+            public_key,
+            signature,
+        }
+    }
+
     /// Return Ok if the authenticator's public key matches its signature, Err otherwise
     pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
         match self {
@@ -319,6 +433,14 @@ impl AccountAuthenticator {
                 public_key,
                 signature,
             } => signature.verify(message, public_key),
+            Self::Secp256k1Ecdsa {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
+            Self::WebAuthn {
+                public_key,
+                signature,
+            } => signature.verify(message, public_key),
         }
     }
 
@@ -327,6 +449,8 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { public_key, .. } => public_key.to_bytes().to_vec(),
             Self::MultiEd25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::Secp256k1Ecdsa { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::WebAuthn { public_key, .. } => public_key.to_bytes().to_vec(),
         }
     }
 
@@ -335,6 +459,11 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { signature, .. } => signature.to_bytes().to_vec(),
             Self::MultiEd25519 { signature, .. } => signature.to_bytes().to_vec(),
+            Self::Secp256k1Ecdsa { signature, .. } => signature.to_bytes().to_vec(),
+            // The whole assertion is needed to verify the signature
+            Self::WebAuthn { signature, .. } => {
+                bcs::to_bytes(signature).expect("WebAuthn assertions should serialize")
+            },
         }
     }
 
@@ -353,6 +482,8 @@ impl AccountAuthenticator {
         match self {
             Self::Ed25519 { .. } => 1,
             Self::MultiEd25519 { signature, .. } => signature.signatures().len(),
+            Self::Secp256k1Ecdsa { .. } => 1,
+            Self::WebAuthn { .. } => 1,
         }
     }
 }
@@ -405,6 +536,16 @@ impl AuthenticationKey {
         Self::from_preimage(&AuthenticationKeyPreimage::multi_ed25519(public_key))
     }
 
+    /// Create an authentication key from a secp256k1 ECDSA public key
+    pub fn secp256k1_ecdsa(public_key: &Secp256k1EcdsaPublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::secp256k1_ecdsa(public_key))
+    }
+
+    /// Create an authentication key from the secp256r1 ECDSA public key of a passkey
+    pub fn webauthn(public_key: &Secp256r1EcdsaPublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::webauthn(public_key))
+    }
+
     /// Return an address derived from the last `AccountAddress::LENGTH` bytes of this
     /// authentication key.
     pub fn derived_address(&self) -> AccountAddress {
@@ -460,6 +601,17 @@ impl AuthenticationKeyPreimage {
         Self::new(public_key.to_bytes(), Scheme::MultiEd25519)
     }
 
+    /// Construct a preimage from a secp256k1 ECDSA public key, in its uncompressed form
+    pub fn secp256k1_ecdsa(public_key: &Secp256k1EcdsaPublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes().to_vec(), Scheme::Secp256k1Ecdsa)
+    }
+
+    /// Construct a preimage from the secp256r1 ECDSA public key of a passkey, in its uncompressed
+    /// form
+    pub fn webauthn(public_key: &Secp256r1EcdsaPublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes().to_vec(), Scheme::WebAuthn)
+    }
+
     /// Construct a vector from this authentication key
     pub fn into_vec(self) -> Vec<u8> {
         self.0
@@ -11,7 +11,10 @@ use crate::{
     proof::{
         accumulator::InMemoryAccumulator, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
-    transaction::authenticator::{AccountAuthenticator, TransactionAuthenticator},
+    transaction::{
+        authenticator::{AccountAuthenticator, TransactionAuthenticator},
+        webauthn::PartialAuthenticatorAssertionResponse,
+    },
     vm_status::{DiscardedVMStatus, KeptVMStatus, StatusCode, StatusType, VMStatus},
     write_set::WriteSet,
 };
@@ -20,6 +23,8 @@ use aptos_crypto::{
     ed25519::*,
     hash::{CryptoHash, EventAccumulatorHasher},
     multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    secp256k1_ecdsa::{Secp256k1EcdsaPrivateKey, Secp256k1EcdsaPublicKey, Secp256k1EcdsaSignature},
+    secp256r1_ecdsa::Secp256r1EcdsaPublicKey,
     traits::{signing_message, SigningKey},
     CryptoMaterialError, HashValue,
 };
@@ -41,6 +46,7 @@ mod module;
 mod multisig;
 mod script;
 mod transaction_argument;
+pub mod webauthn;
 
 use crate::state_store::{state_key::StateKey, state_value::StateValue};
 #[cfg(any(test, feature = "fuzzing"))]
@@ -247,6 +253,19 @@ impl RawTransaction {
         )))
     }
 
+    /// Signs the given `RawTransaction` with a secp256k1 ECDSA key. Note that this consumes the
+    /// `RawTransaction` and turns it into a `SignatureCheckedTransaction`.
+    pub fn sign_secp256k1_ecdsa(
+        self,
+        private_key: &Secp256k1EcdsaPrivateKey,
+        public_key: Secp256k1EcdsaPublicKey,
+    ) -> Result<SignatureCheckedTransaction> {
+        let signature = private_key.sign(&self)?;
+        Ok(SignatureCheckedTransaction(
+            SignedTransaction::new_secp256k1_ecdsa(self, public_key, signature),
+        ))
+    }
+
     /// Signs the given multi-agent `RawTransaction`, which is a transaction with secondary
     /// signers in addition to a sender. The private keys of the sender and the
     /// secondary signers are used to sign the transaction.
@@ -530,6 +549,32 @@ impl SignedTransaction {
         }
     }
 
+    pub fn new_secp256k1_ecdsa(
+        raw_txn: RawTransaction,
+        public_key: Secp256k1EcdsaPublicKey,
+        signature: Secp256k1EcdsaSignature,
+    ) -> SignedTransaction {
+        let authenticator = TransactionAuthenticator::secp256k1_ecdsa(public_key, signature);
+        SignedTransaction {
+            raw_txn,
+            authenticator,
+            size: OnceCell::new(),
+        }
+    }
+
+    pub fn new_webauthn(
+        raw_txn: RawTransaction,
+        public_key: Secp256r1EcdsaPublicKey,
+        assertion: PartialAuthenticatorAssertionResponse,
+    ) -> SignedTransaction {
+        let authenticator = TransactionAuthenticator::webauthn(public_key, assertion);
+        SignedTransaction {
+            raw_txn,
+            authenticator,
+            size: OnceCell::new(),
+        }
+    }
+
     pub fn new_multi_agent(
         raw_txn: RawTransaction,
         sender: AccountAuthenticator,
@@ -609,6 +654,49 @@ impl SignedTransaction {
         Ok(SignatureCheckedTransaction(self))
     }
 
+    /// Checks the signatures of a batch of transactions, returning the results in the same order
+    /// as `check_signature` would. Single-signer Ed25519 transactions are verified together,
+    /// which is significantly cheaper than verifying them one by one. If the batch does not
+    /// verify, they are checked individually so that the invalid ones can be identified.
+    pub fn batch_check_signature(
+        txns: Vec<SignedTransaction>,
+    ) -> Vec<Result<SignatureCheckedTransaction>> {
+        let signing_messages: Vec<_> = txns
+            .iter()
+            .map(|txn| match &txn.authenticator {
+                TransactionAuthenticator::Ed25519 { .. } => signing_message(&txn.raw_txn).ok(),
+                _ => None,
+            })
+            .collect();
+        let batch: Vec<_> = txns
+            .iter()
+            .zip(signing_messages.iter())
+            .filter_map(|(txn, message)| match (&txn.authenticator, message) {
+                (
+                    TransactionAuthenticator::Ed25519 {
+                        public_key,
+                        signature,
+                    },
+                    Some(message),
+                ) => Some((message.as_slice(), public_key, signature)),
+                _ => None,
+            })
+            .collect();
+        let batch_is_valid =
+            batch.len() > 1 && Ed25519Signature::batch_verify_arbitrary_msgs(&batch).is_ok();
+
+        txns.into_iter()
+            .zip(signing_messages)
+            .map(|(txn, message)| {
+                if batch_is_valid && message.is_some() {
+                    Ok(SignatureCheckedTransaction(txn))
+                } else {
+                    txn.check_signature()
+                }
+            })
+            .collect()
+    }
+
     /// Checks that the signature of given transaction inplace. Returns `Ok(())` if
     /// the signature is valid.
     pub fn signature_is_valid(&self) -> bool {
@@ -0,0 +1,126 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! WebAuthn assertions, with which passkeys sign transactions.
+//!
+//! A WebAuthn authenticator does not sign the message it is given, called the challenge: it signs
+//! its authenticator data concatenated with the SHA2-256 hash of the client data JSON, which the
+//! browser builds around the base64url-encoded challenge. The challenge of a transaction is the
+//! SHA3-256 hash of its signing message, see `challenge`.
+
+use anyhow::{ensure, Result};
+use aptos_crypto::{
+    hash::CryptoHash,
+    secp256r1_ecdsa::{Secp256r1EcdsaPublicKey, Secp256r1EcdsaSignature},
+    traits::{signing_message, Signature},
+    HashValue,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The minimum length of authenticator data: the SHA2-256 hash of the relying party ID, a flags
+/// byte and a 4-byte signature counter, optionally followed by extensions.
+const MIN_AUTHENTICATOR_DATA_LENGTH: usize = 37;
+/// The position of the flags byte in authenticator data.
+const FLAGS_INDEX: usize = 32;
+/// The flag set in authenticator data when the user was present.
+const USER_PRESENT_FLAG: u8 = 0x01;
+/// The type of the client data of an assertion, as opposed to a credential creation.
+const ASSERTION_TYPE: &str = "webauthn.get";
+
+/// Returns the challenge a passkey signs to authenticate `message`, e.g., a `RawTransaction`.
+pub fn challenge<T: CryptoHash + Serialize>(message: &T) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&signing_message(message)?))
+}
+
+/// The fields of a WebAuthn `AuthenticatorAssertionResponse` needed to verify it
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct PartialAuthenticatorAssertionResponse {
+    /// The signature of the authenticator, with a low S (see `Secp256r1EcdsaSignature::from_der`)
+    signature: Secp256r1EcdsaSignature,
+    #[serde(with = "serde_bytes")]
+    authenticator_data: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    client_data_json: Vec<u8>,
+}
+
+/// The fields of the client data JSON that are checked, others being ignored
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    ty: String,
+    challenge: String,
+}
+
+impl PartialAuthenticatorAssertionResponse {
+    pub fn new(
+        signature: Secp256r1EcdsaSignature,
+        authenticator_data: Vec<u8>,
+        client_data_json: Vec<u8>,
+    ) -> Self {
+        Self {
+            signature,
+            authenticator_data,
+            client_data_json,
+        }
+    }
+
+    pub fn signature(&self) -> &Secp256r1EcdsaSignature {
+        &self.signature
+    }
+
+    pub fn authenticator_data(&self) -> &[u8] {
+        &self.authenticator_data
+    }
+
+    pub fn client_data_json(&self) -> &[u8] {
+        &self.client_data_json
+    }
+
+    /// Verifies that this assertion is a signature by `public_key` on the challenge of `message`,
+    /// with the user present.
+    ///
+    /// The origin and the relying party ID are not checked: any website may ask a passkey to sign
+    /// a transaction, the same way any website may ask a wallet to.
+    pub fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Secp256r1EcdsaPublicKey,
+    ) -> Result<()> {
+        ensure!(
+            self.authenticator_data.len() >= MIN_AUTHENTICATOR_DATA_LENGTH,
+            "WebAuthn authenticator data is too short: {} bytes",
+            self.authenticator_data.len()
+        );
+        ensure!(
+            self.authenticator_data[FLAGS_INDEX] & USER_PRESENT_FLAG != 0,
+            "WebAuthn assertion was made without the user being present"
+        );
+
+        let client_data: CollectedClientData = serde_json::from_slice(&self.client_data_json)?;
+        ensure!(
+            client_data.ty == ASSERTION_TYPE,
+            "WebAuthn client data has type {}, expected {}",
+            client_data.ty,
+            ASSERTION_TYPE
+        );
+        let expected_challenge =
+            base64::encode_config(challenge(message)?.as_ref(), base64::URL_SAFE_NO_PAD);
+        ensure!(
+            client_data.challenge == expected_challenge,
+            "WebAuthn challenge does not match the message"
+        );
+
+        self.signature
+            .verify_arbitrary_msg(&self.signed_data(), public_key)
+    }
+
+    /// The data signed by the authenticator.
+    fn signed_data(&self) -> Vec<u8> {
+        [
+            &self.authenticator_data[..],
+            &Sha256::digest(&self.client_data_json)[..],
+        ]
+        .concat()
+    }
+}
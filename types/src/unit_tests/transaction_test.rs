@@ -44,6 +44,32 @@ proptest! {
         assert!(signed_txn.check_signature().is_ok());
     }
 
+    #[test]
+    fn test_batch_check_signature(
+        raw_txns in proptest::collection::vec(any::<RawTransaction>(), 2..10),
+        keypair in ed25519::keypair_strategy(),
+        invalid_idx in any::<prop::sample::Index>(),
+    ) {
+        let mut txns: Vec<_> = raw_txns
+            .into_iter()
+            .map(|raw_txn| raw_txn.sign(&keypair.private_key, keypair.public_key.clone()).unwrap().into_inner())
+            .collect();
+        let results = SignedTransaction::batch_check_signature(txns.clone());
+        prop_assert!(results.iter().all(|result| result.is_ok()));
+
+        // Only the transaction with an invalid signature is rejected
+        let invalid_idx = invalid_idx.index(txns.len());
+        txns[invalid_idx] = SignedTransaction::new(
+            txns[invalid_idx].clone().into_raw_transaction(),
+            keypair.public_key.clone(),
+            Ed25519Signature::try_from(&[1u8; 64][..]).unwrap(),
+        );
+        let results = SignedTransaction::batch_check_signature(txns);
+        for (idx, result) in results.iter().enumerate() {
+            prop_assert_eq!(result.is_ok(), idx != invalid_idx);
+        }
+    }
+
     #[test]
     fn transaction_payload_bcs_roundtrip(txn_payload in any::<TransactionPayload>()) {
         assert_canonical_encode_decode(txn_payload);
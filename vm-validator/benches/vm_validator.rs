@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares validating the transactions of a mempool broadcast one by one, and as a batch, whose
+//! signatures are verified together.
+//!
+//! Run with `cargo bench -p aptos-vm-validator`; criterion reports the validation throughput of
+//! both approaches for each batch size.
+
+use aptos_cached_packages::aptos_stdlib;
+use aptos_db::AptosDB;
+use aptos_storage_interface::DbReaderWriter;
+use aptos_types::{
+    account_config, test_helpers::transaction_test_helpers, transaction::SignedTransaction,
+};
+use aptos_vm::AptosVM;
+use aptos_vm_validator::vm_validator::{TransactionValidation, VMValidator};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+const BATCH_SIZES: [usize; 3] = [16, 128, 512];
+
+fn transactions(count: usize) -> Vec<SignedTransaction> {
+    let address = account_config::aptos_test_root_address();
+    (0..count as u64)
+        .map(|sequence_number| {
+            transaction_test_helpers::get_test_signed_txn(
+                address,
+                sequence_number,
+                &aptos_vm_genesis::GENESIS_KEYPAIR.0,
+                aptos_vm_genesis::GENESIS_KEYPAIR.1.clone(),
+                Some(aptos_stdlib::aptos_coin_mint(address, 100)),
+            )
+        })
+        .collect()
+}
+
+fn benchmark(c: &mut Criterion) {
+    let db_path = aptos_temppath::TempPath::new();
+    db_path.create_as_dir().unwrap();
+    let (db, db_rw) = DbReaderWriter::wrap(AptosDB::new_for_test(db_path.path()));
+    aptos_executor_test_helpers::bootstrap_genesis::<AptosVM>(
+        &db_rw,
+        &aptos_vm_genesis::test_genesis_transaction(),
+    )
+    .expect("Db-bootstrapper should not fail.");
+    let vm_validator = VMValidator::new(db);
+
+    let mut group = c.benchmark_group("vm_validator");
+    for batch_size in BATCH_SIZES {
+        group.throughput(Throughput::Elements(batch_size as u64));
+        group.bench_function(format!("validate_transaction/{}", batch_size), |b| {
+            b.iter_batched(
+                || transactions(batch_size),
+                |txns| {
+                    txns.into_iter()
+                        .map(|txn| vm_validator.validate_transaction(txn))
+                        .collect::<Vec<_>>()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_function(format!("validate_transactions/{}", batch_size), |b| {
+            b.iter_batched(
+                || transactions(batch_size),
+                |txns| vm_validator.validate_transactions(txns),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark);
+criterion_main!(benches);
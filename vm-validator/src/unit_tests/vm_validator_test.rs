@@ -113,6 +113,45 @@ fn test_validate_invalid_signature() {
     assert_eq!(ret.status().unwrap(), StatusCode::INVALID_SIGNATURE);
 }
 
+#[test]
+fn test_validate_transactions() {
+    let vm_validator = TestValidator::new();
+
+    let mut rng = ::rand::rngs::StdRng::from_seed([1u8; 32]);
+    let other_private_key = Ed25519PrivateKey::generate(&mut rng);
+
+    let address = account_config::aptos_test_root_address();
+    let mut transactions: Vec<_> = (1..5)
+        .map(|sequence_number| {
+            transaction_test_helpers::get_test_signed_txn(
+                address,
+                sequence_number,
+                &aptos_vm_genesis::GENESIS_KEYPAIR.0,
+                aptos_vm_genesis::GENESIS_KEYPAIR.1.clone(),
+                Some(aptos_stdlib::aptos_coin_mint(address, 100)),
+            )
+        })
+        .collect();
+    // Signed with a different private key, this fails the batch signature verification
+    transactions.push(transaction_test_helpers::get_test_unchecked_txn(
+        address,
+        5,
+        &other_private_key,
+        aptos_vm_genesis::GENESIS_KEYPAIR.1.clone(),
+        aptos_stdlib::aptos_coin_mint(address, 100),
+    ));
+
+    let statuses: Vec<_> = vm_validator
+        .validate_transactions(transactions)
+        .into_iter()
+        .map(|ret| ret.unwrap().status())
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![None, None, None, None, Some(StatusCode::INVALID_SIGNATURE)]
+    );
+}
+
 #[test]
 fn test_validate_known_script_too_large_args() {
     let vm_validator = TestValidator::new();
@@ -29,6 +29,16 @@ pub trait TransactionValidation: Send + Sync + Clone {
     /// Validate a txn from client
     fn validate_transaction(&self, _txn: SignedTransaction) -> Result<VMValidatorResult>;
 
+    /// Validate a batch of txns, returning the results in the same order
+    fn validate_transactions(
+        &self,
+        txns: Vec<SignedTransaction>,
+    ) -> Vec<Result<VMValidatorResult>> {
+        txns.into_iter()
+            .map(|txn| self.validate_transaction(txn))
+            .collect()
+    }
+
     /// Restart the transaction validation instance
     fn restart(&mut self, config: OnChainConfigPayload) -> Result<()>;
 
@@ -77,6 +87,28 @@ impl TransactionValidation for VMValidator {
         Ok(self.vm.validate_transaction(txn, &self.state_view))
     }
 
+    fn validate_transactions(
+        &self,
+        txns: Vec<SignedTransaction>,
+    ) -> Vec<Result<VMValidatorResult>> {
+        fail_point!("vm_validator::validate_transaction", |_| {
+            txns.iter()
+                .map(|_| {
+                    Err(anyhow::anyhow!(
+                        "Injected error in vm_validator::validate_transaction"
+                    ))
+                })
+                .collect()
+        });
+        use aptos_vm::VMValidator;
+
+        self.vm
+            .validate_transactions(txns, &self.state_view)
+            .into_iter()
+            .map(Ok)
+            .collect()
+    }
+
     fn restart(&mut self, _config: OnChainConfigPayload) -> Result<()> {
         self.notify_commit();
 